@@ -16,7 +16,10 @@
 
 //! Helpers for implementing various message-related runtime API methods.
 
-use bp_messages::{InboundMessageDetails, MessageNonce, MessagePayload, OutboundMessageDetails};
+use bp_messages::{
+	FailedMessageDispatchDetails, InboundMessageDetails, MessageNonce, MessagePayload,
+	OutboundMessageDetails,
+};
 use sp_std::vec::Vec;
 
 /// Implementation of the `To*OutboundLaneApi::message_details`.
@@ -62,3 +65,16 @@ where
 		})
 		.collect()
 }
+
+/// Implementation of the `From*InboundLaneDispatchApi::failed_messages`.
+pub fn failed_message_details<Runtime, MessagesPalletInstance>(
+	lane: Runtime::LaneId,
+) -> Vec<FailedMessageDispatchDetails>
+where
+	Runtime: pallet_bridge_messages::Config<MessagesPalletInstance>,
+	MessagesPalletInstance: 'static,
+{
+	pallet_bridge_messages::Pallet::<Runtime, MessagesPalletInstance>::failed_message_dispatches(
+		lane,
+	)
+}
@@ -21,9 +21,13 @@
 
 pub use bp_polkadot_core::*;
 
+use bp_beefy::{BeefyEcdsaToEthereum, ChainWithBeefy, EcdsaValidatorId};
 use bp_header_chain::ChainWithGrandpa;
 use bp_runtime::{decl_bridge_finality_runtime_apis, Chain, ChainId};
-use frame_support::{sp_runtime::StateVersion, weights::Weight};
+use frame_support::{
+	sp_runtime::{traits::Keccak256, StateVersion},
+	weights::Weight,
+};
 
 /// Westend Chain
 pub struct Westend;
@@ -61,6 +65,16 @@ impl ChainWithGrandpa for Westend {
 	const AVERAGE_HEADER_SIZE: u32 = AVERAGE_HEADER_SIZE;
 }
 
+impl ChainWithBeefy for Westend {
+	type CommitmentHasher = Keccak256;
+	type MmrHashing = Keccak256;
+	type MmrHash = Hash;
+	// root of the parachain heads merkle tree
+	type BeefyMmrLeafExtra = Hash;
+	type AuthorityId = EcdsaValidatorId;
+	type AuthorityIdToMerkleLeaf = BeefyEcdsaToEthereum;
+}
+
 // The TransactionExtension used by Westend.
 pub use bp_polkadot_core::CommonTransactionExtension as TransactionExtension;
 
@@ -69,6 +83,8 @@ pub const PARAS_PALLET_NAME: &str = "Paras";
 
 /// Name of the With-Westend GRANDPA pallet instance that is deployed at bridged chains.
 pub const WITH_WESTEND_GRANDPA_PALLET_NAME: &str = "BridgeWestendGrandpa";
+/// Name of the With-Westend BEEFY pallet instance that is deployed at bridged chains.
+pub const WITH_WESTEND_BEEFY_PALLET_NAME: &str = "BridgeWestendBeefy";
 /// Name of the With-Westend parachains pallet instance that is deployed at bridged chains.
 pub const WITH_WESTEND_BRIDGE_PARACHAINS_PALLET_NAME: &str = "BridgeWestendParachains";
 
@@ -16,18 +16,26 @@
 
 //! Everything about incoming messages receival.
 
-use crate::{BridgedChainOf, Config};
+use crate::{BridgedChainOf, Config, ThisChainOf};
 
 use bp_messages::{
 	target_chain::{DispatchMessage, DispatchMessageData, MessageDispatch},
-	ChainWithMessages, DeliveredMessages, InboundLaneData, LaneState, MessageKey, MessageNonce,
-	OutboundLaneData, ReceptionResult, UnrewardedRelayer,
+	ChainWithMessages, DeliveredMessages, FailedMessageDispatchDetails, InboundLaneData, LaneState,
+	MessageKey, MessageNonce, OutboundLaneData, ReceptionResult, UnrewardedRelayer,
 };
 use bp_runtime::AccountIdOf;
 use codec::{Decode, Encode, EncodeLike, MaxEncodedLen};
+use frame_support::{
+	traits::{ConstU32, Get},
+	weights::Weight,
+	BoundedVec, CloneNoBound, EqNoBound, PartialEqNoBound, RuntimeDebugNoBound,
+};
 use scale_info::{Type, TypeInfo};
 use sp_runtime::RuntimeDebug;
-use sp_std::prelude::PartialEq;
+use sp_std::{marker::PhantomData, prelude::PartialEq};
+
+/// Maximal size of the encoded dispatch result, that is kept for failed inbound messages.
+pub const MAX_DISPATCH_ERROR_SIZE: u32 = 128;
 
 /// Inbound lane storage.
 pub trait InboundLaneStorage {
@@ -113,6 +121,57 @@ impl<T: Config<I>, I: 'static> MaxEncodedLen for StoredInboundLaneData<T, I> {
 	}
 }
 
+/// Limit for the `StoredInboundMessagePayload` vector.
+pub struct StoredInboundMessagePayloadLimit<T, I>(PhantomData<(T, I)>);
+
+impl<T: Config<I>, I: 'static> Get<u32> for StoredInboundMessagePayloadLimit<T, I> {
+	fn get() -> u32 {
+		ThisChainOf::<T, I>::maximal_incoming_message_size()
+	}
+}
+
+/// Inbound message payload wrapper that implements `MaxEncodedLen`.
+pub type StoredInboundMessagePayload<T, I> = BoundedVec<u8, StoredInboundMessagePayloadLimit<T, I>>;
+
+/// Inbound message that has been delivered, but has failed to dispatch.
+#[derive(
+	Encode,
+	Decode,
+	CloneNoBound,
+	RuntimeDebugNoBound,
+	PartialEqNoBound,
+	EqNoBound,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+#[scale_info(skip_type_params(T, I))]
+#[codec(mel_bound())]
+pub struct StoredFailedMessageDispatch<T: Config<I>, I: 'static> {
+	/// Message payload, kept to retry the dispatch.
+	pub payload: StoredInboundMessagePayload<T, I>,
+	/// Message dispatch weight, computed during the last dispatch attempt.
+	pub dispatch_weight: Weight,
+	/// Unspent weight, reported by the last dispatch attempt.
+	pub unspent_weight: Weight,
+	/// Encoded (and possibly truncated) dispatch result of the last dispatch attempt.
+	pub dispatch_error: BoundedVec<u8, ConstU32<MAX_DISPATCH_ERROR_SIZE>>,
+	/// Number of times the message dispatch has been attempted.
+	pub dispatch_attempts: u32,
+}
+
+impl<T: Config<I>, I: 'static> StoredFailedMessageDispatch<T, I> {
+	/// Return details of the failed message with given nonce.
+	pub fn details(&self, nonce: MessageNonce) -> FailedMessageDispatchDetails {
+		FailedMessageDispatchDetails {
+			nonce,
+			dispatch_weight: self.dispatch_weight,
+			unspent_weight: self.unspent_weight,
+			dispatch_error: self.dispatch_error.to_vec(),
+			dispatch_attempts: self.dispatch_attempts,
+		}
+	}
+}
+
 /// Inbound messages lane.
 pub struct InboundLane<S> {
 	storage: S,
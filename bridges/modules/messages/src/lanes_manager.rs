@@ -15,9 +15,9 @@
 // along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
 
 use crate::{
-	BridgedChainOf, Config, InboundLane, InboundLaneStorage, InboundLanes, OutboundLane,
-	OutboundLaneStorage, OutboundLanes, OutboundMessages, StoredInboundLaneData,
-	StoredMessagePayload,
+	BridgedChainOf, Config, FailedMessageDispatches, FailedMessageDispatchesCount, InboundLane,
+	InboundLaneStorage, InboundLanes, OutboundLane, OutboundLaneStorage, OutboundLanes,
+	OutboundMessages, StoredInboundLaneData, StoredMessagePayload,
 };
 
 use bp_messages::{
@@ -222,7 +222,14 @@ impl<T: Config<I>, I: 'static> InboundLaneStorage for RuntimeInboundLaneStorage<
 	}
 
 	fn purge(self) {
-		InboundLanes::<T, I>::remove(self.lane_id)
+		InboundLanes::<T, I>::remove(self.lane_id);
+		// the number of failed dispatches is bounded, so a single call removes all of them
+		let _ = FailedMessageDispatches::<T, I>::clear_prefix(
+			self.lane_id,
+			T::MaxFailedMessageDispatchesPerLane::get(),
+			None,
+		);
+		FailedMessageDispatchesCount::<T, I>::remove(self.lane_id);
 	}
 }
 
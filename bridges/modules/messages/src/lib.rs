@@ -27,6 +27,11 @@
 //! The assigned nonce is reported using `MessageAccepted` event. When message is
 //! delivered to the the bridged chain, it is reported using `MessagesDelivered` event.
 //!
+//! Inbound messages that have been delivered, but have failed to dispatch, are kept in the
+//! `FailedMessageDispatches` map. Their dispatch may be retried by the pallet owner (or root)
+//! using the `retry_message_dispatch()` call. The number of kept messages is limited by the
+//! `MaxFailedMessageDispatchesPerLane` value for every lane.
+//!
 //! **IMPORTANT NOTE**: after generating weights (custom `WeighInfo` implementation) for
 //! your runtime (where this module is plugged to), please add test for these weights.
 //! The test should call the `ensure_weights_are_correct` function from this module.
@@ -36,7 +41,10 @@
 #![warn(missing_docs)]
 #![cfg_attr(not(feature = "std"), no_std)]
 
-pub use inbound_lane::{InboundLane, InboundLaneStorage, StoredInboundLaneData};
+pub use inbound_lane::{
+	InboundLane, InboundLaneStorage, StoredFailedMessageDispatch, StoredInboundLaneData,
+	StoredInboundMessagePayload, StoredInboundMessagePayloadLimit, MAX_DISPATCH_ERROR_SIZE,
+};
 pub use lanes_manager::{
 	LanesManager, LanesManagerError, RuntimeInboundLaneStorage, RuntimeOutboundLaneStorage,
 };
@@ -57,12 +65,13 @@ use bp_messages::{
 		SendMessageArtifacts,
 	},
 	target_chain::{
-		DeliveryPayments, DispatchMessage, FromBridgedChainMessagesProof, MessageDispatch,
-		ProvedLaneMessages, ProvedMessages,
+		DeliveryPayments, DispatchMessage, DispatchMessageData, FromBridgedChainMessagesProof,
+		MessageDispatch, ProvedMessages,
 	},
-	ChainWithMessages, DeliveredMessages, InboundLaneData, InboundMessageDetails, MessageKey,
-	MessageNonce, MessagePayload, MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails,
-	UnrewardedRelayersState, VerificationError,
+	ChainWithMessages, DeliveredMessages, FailedMessageDispatchDetails, InboundLaneData,
+	InboundMessageDetails, Message, MessageKey, MessageNonce, MessagePayload,
+	MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails, UnrewardedRelayersState,
+	VerificationError,
 };
 use bp_runtime::{
 	AccountIdOf, BasicOperatingMode, HashOf, OwnedBridgeModule, PreComputedSize, RangeInclusiveExt,
@@ -98,7 +107,7 @@ pub const LOG_TARGET: &str = "runtime::bridge-messages";
 pub mod pallet {
 	use super::*;
 	use bp_messages::{LaneIdType, ReceivedMessages, ReceptionResult};
-	use bp_runtime::RangeInclusiveExt;
+	use bp_runtime::{messages::MessageDispatchResult, RangeInclusiveExt};
 	use frame_support::pallet_prelude::*;
 	use frame_system::pallet_prelude::*;
 
@@ -142,6 +151,11 @@ pub mod pallet {
 			DispatchPayload = Self::InboundPayload,
 			LaneId = Self::LaneId,
 		>;
+		/// Maximal number of inbound messages with failed dispatch, that are kept in the
+		/// `FailedMessageDispatches` map for every lane. When the limit is reached, further
+		/// failed messages of the lane are not kept and may not be retried.
+		#[pallet::constant]
+		type MaxFailedMessageDispatchesPerLane: Get<u32>;
 	}
 
 	/// Shortcut to this chain type for Config.
@@ -209,7 +223,7 @@ pub mod pallet {
 		/// The call may succeed, but some messages may not be delivered e.g. if they are not fit
 		/// into the unrewarded relayers vector.
 		#[pallet::call_index(2)]
-		#[pallet::weight(T::WeightInfo::receive_messages_proof_weight(&**proof, *messages_count, *dispatch_weight))]
+		#[pallet::weight(Pallet::<T, I>::receive_messages_proof_weight(&**proof, *messages_count, *dispatch_weight))]
 		pub fn receive_messages_proof(
 			origin: OriginFor<T>,
 			relayer_id_at_bridged_chain: AccountIdOf<BridgedChainOf<T, I>>,
@@ -237,24 +251,21 @@ pub mod pallet {
 			//
 			// The DeclaredWeight is exactly what's computed here. Unfortunately it is impossible
 			// to get pre-computed value (and it has been already computed by the executive).
-			let declared_weight = T::WeightInfo::receive_messages_proof_weight(
-				&*proof,
-				messages_count,
-				dispatch_weight,
-			);
+			let declared_weight =
+				Self::receive_messages_proof_weight(&*proof, messages_count, dispatch_weight);
 			let mut actual_weight = declared_weight;
 
 			// verify messages proof && convert proof into messages
-			let (lane_id, lane_data) =
-				verify_and_decode_messages_proof::<T, I>(*proof, messages_count).map_err(
-					|err| {
-						log::trace!(target: LOG_TARGET, "Rejecting invalid messages proof: {:?}", err,);
+			let (lane_id, lane_data) = verify_messages_proof::<T, I>(*proof, messages_count)
+				.map_err(|err| {
+					log::trace!(target: LOG_TARGET, "Rejecting invalid messages proof: {:?}", err,);
 
-						Error::<T, I>::InvalidMessagesProof
-					},
-				)?;
+					Error::<T, I>::InvalidMessagesProof
+				})?;
 
 			// dispatch messages and (optionally) update lane(s) state(s)
+			let failed_dispatch_db_weight = Self::failed_message_dispatch_db_weight();
+			let mut is_failed_dispatches_count_read = false;
 			let mut total_messages = 0;
 			let mut valid_messages = 0;
 			let mut dispatch_weight_left = dispatch_weight;
@@ -282,10 +293,13 @@ pub mod pallet {
 
 			let mut messages_received_status =
 				ReceivedMessages::new(lane_id, Vec::with_capacity(lane_data.messages.len()));
-			for mut message in lane_data.messages {
-				debug_assert_eq!(message.key.lane_id, lane_id);
+			for raw_message in lane_data.messages {
+				debug_assert_eq!(raw_message.key.lane_id, lane_id);
 				total_messages += 1;
 
+				// the raw payload is kept around in case we need to retry the dispatch later
+				let mut message = decode_message::<T, I>(raw_message.key, &raw_message.payload);
+
 				// ensure that relayer has declared enough weight for dispatching next message
 				// on this lane. We can't dispatch lane messages out-of-order, so if declared
 				// weight is not enough, let's move to next lane
@@ -314,15 +328,27 @@ pub mod pallet {
 				// losing funds for messages dispatch. But keep in mind that relayer pays base
 				// delivery transaction cost anyway. And base cost covers everything except
 				// dispatch, so we have a balance here.
+				let mut used_failed_dispatch_db_weight = Weight::zero();
 				let unspent_weight = match &receival_result {
 					ReceptionResult::Dispatched(dispatch_result) => {
 						valid_messages += 1;
+						used_failed_dispatch_db_weight = Self::note_dispatch_result(
+							lane_id,
+							message.key.nonce,
+							raw_message.payload,
+							message_dispatch_weight,
+							dispatch_result,
+						);
 						dispatch_result.unspent_weight
 					},
 					ReceptionResult::InvalidNonce |
 					ReceptionResult::TooManyUnrewardedRelayers |
 					ReceptionResult::TooManyUnconfirmedMessages => message_dispatch_weight,
 				};
+				is_failed_dispatches_count_read |= !used_failed_dispatch_db_weight.is_zero();
+				actual_weight = actual_weight.saturating_sub(
+					failed_dispatch_db_weight.saturating_sub(used_failed_dispatch_db_weight),
+				);
 				messages_received_status.push(message.key.nonce, receival_result);
 
 				let unspent_weight = unspent_weight.min(message_dispatch_weight);
@@ -330,6 +356,15 @@ pub mod pallet {
 				actual_weight = actual_weight.saturating_sub(unspent_weight);
 			}
 
+			// we have not touched the failed dispatches counter => refund its proof size
+			if !is_failed_dispatches_count_read {
+				actual_weight = actual_weight.set_proof_size(
+					actual_weight
+						.proof_size()
+						.saturating_sub(Self::failed_message_dispatches_count_proof_size()),
+				);
+			}
+
 			// let's now deal with relayer payments
 			T::DeliveryPayments::pay_reward(
 				relayer_id_at_this_chain,
@@ -443,6 +478,68 @@ pub mod pallet {
 
 			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
 		}
+
+		/// Retry dispatch of the inbound message that has previously failed to dispatch.
+		///
+		/// If the dispatch succeeds, the message is removed from the `FailedMessageDispatches`
+		/// map. Otherwise, the stored dispatch result is updated.
+		///
+		/// May only be called either by root, or by `PalletOwner`.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::DbWeight::get().reads_writes(3, 2).saturating_add(*weight_limit))]
+		pub fn retry_message_dispatch(
+			origin: OriginFor<T>,
+			lane_id: T::LaneId,
+			nonce: MessageNonce,
+			weight_limit: Weight,
+		) -> DispatchResultWithPostInfo {
+			Self::ensure_owner_or_root(origin)?;
+			Self::ensure_not_halted().map_err(Error::<T, I>::BridgeModule)?;
+
+			let mut failed_dispatch = FailedMessageDispatches::<T, I>::get(lane_id, nonce)
+				.ok_or(Error::<T, I>::UnknownFailedMessage)?;
+			let mut message =
+				decode_message::<T, I>(MessageKey { lane_id, nonce }, &failed_dispatch.payload);
+			let dispatch_weight = T::MessageDispatch::dispatch_weight(&mut message);
+			ensure!(
+				!dispatch_weight.any_gt(weight_limit),
+				Error::<T, I>::InsufficientDispatchWeight
+			);
+
+			let dispatch_result = T::MessageDispatch::dispatch(message);
+			let dispatched =
+				!T::MessageDispatch::is_dispatch_failed(&dispatch_result.dispatch_level_result);
+			if dispatched {
+				FailedMessageDispatches::<T, I>::remove(lane_id, nonce);
+				FailedMessageDispatchesCount::<T, I>::mutate(lane_id, |count| {
+					*count = count.saturating_sub(1)
+				});
+			} else {
+				failed_dispatch.dispatch_weight = dispatch_weight;
+				failed_dispatch.unspent_weight = dispatch_result.unspent_weight;
+				failed_dispatch.dispatch_error =
+					BoundedVec::truncate_from(dispatch_result.dispatch_level_result.encode());
+				failed_dispatch.dispatch_attempts =
+					failed_dispatch.dispatch_attempts.saturating_add(1);
+				FailedMessageDispatches::<T, I>::insert(lane_id, nonce, failed_dispatch);
+			}
+
+			log::trace!(
+				target: LOG_TARGET,
+				"Retried dispatch of message {:?}/{}: {:?}",
+				lane_id,
+				nonce,
+				dispatch_result.dispatch_level_result,
+			);
+
+			let unspent_weight = dispatch_result.unspent_weight.min(dispatch_weight);
+			Self::deposit_event(Event::MessageDispatchRetried { lane_id, nonce, dispatch_result });
+
+			let actual_weight = T::DbWeight::get()
+				.reads_writes(3, 2)
+				.saturating_add(dispatch_weight.saturating_sub(unspent_weight));
+			Ok(PostDispatchInfo { actual_weight: Some(actual_weight), pays_fee: Pays::Yes })
+		}
 	}
 
 	#[pallet::event]
@@ -470,6 +567,16 @@ pub mod pallet {
 			/// Delivered messages.
 			messages: DeliveredMessages,
 		},
+		/// Dispatch of the previously failed inbound message has been retried.
+		MessageDispatchRetried {
+			/// Lane of the message.
+			lane_id: T::LaneId,
+			/// Nonce of the message.
+			nonce: MessageNonce,
+			/// Result of the dispatch.
+			dispatch_result:
+				MessageDispatchResult<<T::MessageDispatch as MessageDispatch>::DispatchLevelResult>,
+		},
 	}
 
 	#[pallet::error]
@@ -497,6 +604,8 @@ pub mod pallet {
 		ReceptionConfirmation(ReceptionConfirmationError),
 		/// Error generated by the `OwnedBridgeModule` trait.
 		BridgeModule(bp_runtime::OwnedBridgeModuleError),
+		/// There's no failed inbound message with given lane and nonce.
+		UnknownFailedMessage,
 	}
 
 	/// Optional pallet owner.
@@ -537,6 +646,27 @@ pub mod pallet {
 	pub type OutboundMessages<T: Config<I>, I: 'static = ()> =
 		StorageMap<_, Blake2_128Concat, MessageKey<T::LaneId>, StoredMessagePayload<T, I>>;
 
+	/// Inbound messages that have been delivered, but have failed to dispatch.
+	///
+	/// Entries are removed when the dispatch is successfully retried, or when the lane is
+	/// purged.
+	#[pallet::storage]
+	pub type FailedMessageDispatches<T: Config<I>, I: 'static = ()> = StorageDoubleMap<
+		Hasher1 = Blake2_128Concat,
+		Key1 = T::LaneId,
+		Hasher2 = Twox64Concat,
+		Key2 = MessageNonce,
+		Value = StoredFailedMessageDispatch<T, I>,
+		QueryKind = OptionQuery,
+	>;
+
+	/// Number of entries in the `FailedMessageDispatches` map for every lane.
+	///
+	/// It never exceeds the `Config::MaxFailedMessageDispatchesPerLane` value.
+	#[pallet::storage]
+	pub type FailedMessageDispatchesCount<T: Config<I>, I: 'static = ()> =
+		StorageMap<_, Blake2_128Concat, T::LaneId, u32, ValueQuery>;
+
 	#[pallet::genesis_config]
 	#[derive(DefaultNoBound)]
 	pub struct GenesisConfig<T: Config<I>, I: 'static = ()> {
@@ -609,6 +739,117 @@ pub mod pallet {
 		) -> Option<InboundLaneData<AccountIdOf<BridgedChainOf<T, I>>>> {
 			InboundLanes::<T, I>::get(lane).map(|lane| lane.0)
 		}
+
+		/// Return details of inbound messages at given lane that have failed to dispatch,
+		/// ordered by the nonce.
+		pub fn failed_message_dispatches(lane: T::LaneId) -> Vec<FailedMessageDispatchDetails> {
+			let mut failed_dispatches = FailedMessageDispatches::<T, I>::iter_prefix(lane)
+				.map(|(nonce, failed_dispatch)| failed_dispatch.details(nonce))
+				.collect::<Vec<_>>();
+			failed_dispatches.sort_by_key(|details| details.nonce);
+			failed_dispatches
+		}
+
+		/// Weight of the `receive_messages_proof` call, including the weight of remembering
+		/// messages with failed dispatch.
+		pub fn receive_messages_proof_weight(
+			proof: &impl Size,
+			messages_count: u32,
+			dispatch_weight: Weight,
+		) -> Weight {
+			T::WeightInfo::receive_messages_proof_weight(proof, messages_count, dispatch_weight)
+				.saturating_add(
+					Self::failed_message_dispatch_db_weight().saturating_mul(messages_count as _),
+				)
+				.saturating_add(Weight::from_parts(
+					0,
+					Self::failed_message_dispatches_count_proof_size(),
+				))
+		}
+
+		/// Weight of db operations, performed when the message dispatch has failed.
+		///
+		/// We read and update the `FailedMessageDispatchesCount` value and insert new entry
+		/// into the `FailedMessageDispatches` map.
+		fn failed_message_dispatch_db_weight() -> Weight {
+			T::DbWeight::get().reads_writes(1, 2)
+		}
+
+		/// Size of the `FailedMessageDispatchesCount` entry in the storage proof.
+		fn failed_message_dispatches_count_proof_size() -> u64 {
+			// Blake2_128Concat hash + lane identifier + counter
+			16u64
+				.saturating_add(T::LaneId::max_encoded_len() as u64)
+				.saturating_add(u32::max_encoded_len() as u64)
+		}
+
+		/// Remember the inbound message, if its dispatch has failed.
+		///
+		/// Returns weight of db operations that have been performed.
+		fn note_dispatch_result(
+			lane_id: T::LaneId,
+			nonce: MessageNonce,
+			payload: MessagePayload,
+			dispatch_weight: Weight,
+			dispatch_result: &MessageDispatchResult<
+				<T::MessageDispatch as MessageDispatch>::DispatchLevelResult,
+			>,
+		) -> Weight {
+			if !T::MessageDispatch::is_dispatch_failed(&dispatch_result.dispatch_level_result) {
+				return Weight::zero()
+			}
+
+			let failed_dispatches_count = FailedMessageDispatchesCount::<T, I>::get(lane_id);
+			if failed_dispatches_count >= T::MaxFailedMessageDispatchesPerLane::get() {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to dispatch message {:?}/{}, but there are already {} failed messages \
+					at the lane. The message is not kept",
+					lane_id,
+					nonce,
+					failed_dispatches_count,
+				);
+				return T::DbWeight::get().reads(1)
+			}
+
+			let Ok(payload) = StoredInboundMessagePayload::<T, I>::try_from(payload) else {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to dispatch message {:?}/{}, but its payload is too large to be kept",
+					lane_id,
+					nonce,
+				);
+				return T::DbWeight::get().reads(1)
+			};
+
+			log::trace!(
+				target: LOG_TARGET,
+				"Failed to dispatch message {:?}/{}: {:?}",
+				lane_id,
+				nonce,
+				dispatch_result.dispatch_level_result,
+			);
+
+			FailedMessageDispatches::<T, I>::insert(
+				lane_id,
+				nonce,
+				StoredFailedMessageDispatch {
+					payload,
+					dispatch_weight,
+					unspent_weight: dispatch_result.unspent_weight,
+					dispatch_error: BoundedVec::truncate_from(
+						dispatch_result.dispatch_level_result.encode(),
+					),
+					dispatch_attempts: 1,
+				},
+			);
+			FailedMessageDispatchesCount::<T, I>::insert(
+				lane_id,
+				failed_dispatches_count.saturating_add(1),
+			);
+
+			Self::failed_message_dispatch_db_weight()
+		}
 	}
 
 	#[cfg(any(feature = "try-runtime", test))]
@@ -764,24 +1005,24 @@ fn any_state_outbound_lane<T: Config<I>, I: 'static>(
 		.map_err(Error::LanesManager)
 }
 
-/// Verify messages proof and return proved messages with decoded payload.
-fn verify_and_decode_messages_proof<T: Config<I>, I: 'static>(
+/// Verify messages proof and return proved messages.
+fn verify_messages_proof<T: Config<I>, I: 'static>(
 	proof: FromBridgedChainMessagesProof<HashOf<BridgedChainOf<T, I>>, T::LaneId>,
 	messages_count: u32,
-) -> Result<
-	ProvedMessages<T::LaneId, DispatchMessage<T::InboundPayload, T::LaneId>>,
-	VerificationError,
-> {
+) -> Result<ProvedMessages<T::LaneId, Message<T::LaneId>>, VerificationError> {
 	// `receive_messages_proof` weight formula and `MAX_UNCONFIRMED_MESSAGES_IN_CONFIRMATION_TX`
 	// check guarantees that the `message_count` is sane and Vec<Message> may be allocated.
 	// (tx with too many messages will either be rejected from the pool, or will fail earlier)
-	proofs::verify_messages_proof::<T, I>(proof, messages_count).map(|(lane, lane_data)| {
-		(
-			lane,
-			ProvedLaneMessages {
-				lane_state: lane_data.lane_state,
-				messages: lane_data.messages.into_iter().map(Into::into).collect(),
-			},
-		)
-	})
+	proofs::verify_messages_proof::<T, I>(proof, messages_count)
+}
+
+/// Decode payload of the inbound message.
+fn decode_message<T: Config<I>, I: 'static>(
+	key: MessageKey<T::LaneId>,
+	payload: &[u8],
+) -> DispatchMessage<T::InboundPayload, T::LaneId> {
+	DispatchMessage {
+		key,
+		data: DispatchMessageData { payload: T::InboundPayload::decode(&mut &payload[..]) },
+	}
 }
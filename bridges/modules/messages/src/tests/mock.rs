@@ -204,6 +204,7 @@ impl Config for TestRuntime {
 	type OnMessagesDelivered = TestOnMessagesDelivered;
 
 	type MessageDispatch = TestMessageDispatch;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<2>;
 }
 
 #[cfg(feature = "runtime-benchmarks")]
@@ -352,6 +353,10 @@ impl TestMessageDispatch {
 		}
 	}
 
+	pub fn emulate_failed_dispatch(failed: bool) {
+		frame_support::storage::unhashed::put(b"TestMessageDispatch.DispatchFailed", &failed);
+	}
+
 	pub fn emulate_enqueued_message(lane: TestLaneIdType) {
 		let key = (b"dispatched", lane).encode();
 		let dispatched = frame_support::storage::unhashed::get_or_default::<MessageNonce>(&key[..]);
@@ -388,6 +393,10 @@ impl MessageDispatch for TestMessageDispatch {
 			Err(_) => dispatch_result(0),
 		}
 	}
+
+	fn is_dispatch_failed(_result: &TestDispatchLevelResult) -> bool {
+		frame_support::storage::unhashed::get_or_default(b"TestMessageDispatch.DispatchFailed")
+	}
 }
 
 /// Test callback, called during message delivery confirmation transaction.
@@ -22,17 +22,18 @@ use crate::{
 	outbound_lane::ReceptionConfirmationError,
 	tests::mock::{RuntimeEvent as TestEvent, *},
 	weights_ext::WeightInfoExt,
-	Call, Config, Error, Event, InboundLanes, LanesManagerError, OutboundLanes, OutboundMessages,
-	Pallet, PalletOperatingMode, PalletOwner, StoredInboundLaneData,
+	Call, Config, Error, Event, FailedMessageDispatches, FailedMessageDispatchesCount,
+	InboundLanes, LanesManager, LanesManagerError, OutboundLanes, OutboundMessages, Pallet,
+	PalletOperatingMode, PalletOwner, StoredInboundLaneData,
 };
 
 use bp_messages::{
 	source_chain::{FromBridgedChainMessagesDeliveryProof, MessagesBridge},
 	target_chain::{FromBridgedChainMessagesProof, MessageDispatch},
-	BridgeMessagesCall, ChainWithMessages, DeliveredMessages, InboundLaneData,
-	InboundMessageDetails, LaneIdType, LaneState, MessageKey, MessageNonce, MessagesOperatingMode,
-	OutboundLaneData, OutboundMessageDetails, UnrewardedRelayer, UnrewardedRelayersState,
-	VerificationError,
+	BridgeMessagesCall, ChainWithMessages, DeliveredMessages, FailedMessageDispatchDetails,
+	InboundLaneData, InboundMessageDetails, LaneIdType, LaneState, MessageKey, MessageNonce,
+	MessagesOperatingMode, OutboundLaneData, OutboundMessageDetails, UnrewardedRelayer,
+	UnrewardedRelayersState, VerificationError,
 };
 use bp_runtime::{BasicOperatingMode, PreComputedSize, RangeInclusiveExt, Size};
 use bp_test_utils::generate_owned_bridge_module_tests;
@@ -41,6 +42,7 @@ use frame_support::{
 	assert_err, assert_noop, assert_ok,
 	dispatch::Pays,
 	storage::generator::{StorageMap, StorageValue},
+	traits::Get,
 	weights::Weight,
 };
 use frame_system::{EventRecord, Pallet as System, Phase};
@@ -430,6 +432,270 @@ fn receive_messages_succeeds_when_dispatcher_becomes_inactive_in_the_middle_of_t
 	});
 }
 
+#[test]
+fn receive_messages_proof_keeps_messages_with_failed_dispatch() {
+	run_test(|| {
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		TestMessageDispatch::emulate_failed_dispatch(false);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(2, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+
+		// message is delivered, even if its dispatch has failed
+		assert_eq!(
+			InboundLanes::<TestRuntime>::get(test_lane_id())
+				.unwrap()
+				.0
+				.last_delivered_nonce(),
+			2
+		);
+		assert_eq!(
+			Pallet::<TestRuntime>::failed_message_dispatches(test_lane_id()),
+			vec![FailedMessageDispatchDetails {
+				nonce: 1,
+				dispatch_weight: REGULAR_PAYLOAD.declared_weight,
+				unspent_weight: Weight::zero(),
+				dispatch_error: ().encode(),
+				dispatch_attempts: 1,
+			}],
+		);
+	});
+}
+
+#[test]
+fn receive_messages_proof_keeps_limited_number_of_messages_with_failed_dispatch() {
+	run_test(|| {
+		let max_failed_dispatches =
+			<TestRuntime as Config>::MaxFailedMessageDispatchesPerLane::get() as MessageNonce;
+		let messages_count = max_failed_dispatches + 1;
+
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(
+				(1..=messages_count).map(|nonce| message(nonce, REGULAR_PAYLOAD)).collect(),
+				None,
+			),
+			messages_count as _,
+			REGULAR_PAYLOAD.declared_weight * messages_count,
+		));
+
+		// all messages are delivered, but only the first failed messages are kept
+		assert_eq!(
+			InboundLanes::<TestRuntime>::get(test_lane_id())
+				.unwrap()
+				.0
+				.last_delivered_nonce(),
+			messages_count,
+		);
+		assert_eq!(
+			Pallet::<TestRuntime>::failed_message_dispatches(test_lane_id())
+				.into_iter()
+				.map(|details| details.nonce)
+				.collect::<Vec<_>>(),
+			(1..=max_failed_dispatches).collect::<Vec<_>>(),
+		);
+		assert_eq!(
+			FailedMessageDispatchesCount::<TestRuntime>::get(test_lane_id()),
+			max_failed_dispatches as u32,
+		);
+
+		// when the failed message dispatch is retried, there's room for the next failed message
+		TestMessageDispatch::emulate_failed_dispatch(false);
+		assert_ok!(Pallet::<TestRuntime>::retry_message_dispatch(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(messages_count + 1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		assert_eq!(
+			Pallet::<TestRuntime>::failed_message_dispatches(test_lane_id())
+				.into_iter()
+				.map(|details| details.nonce)
+				.collect::<Vec<_>>(),
+			(2..=max_failed_dispatches).chain(Some(messages_count + 1)).collect::<Vec<_>>(),
+		);
+	});
+}
+
+#[test]
+fn receive_messages_proof_refunds_unused_failed_dispatch_weight() {
+	run_test(|| {
+		fn submit_with_failed_dispatch(nonce: MessageNonce, failed: bool) -> (Weight, Weight) {
+			TestMessageDispatch::emulate_failed_dispatch(failed);
+			let proof = prepare_messages_proof(vec![message(nonce, REGULAR_PAYLOAD)], None);
+			let pre_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof_weight(
+				&*proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			);
+			let post_dispatch_weight = Pallet::<TestRuntime>::receive_messages_proof(
+				RuntimeOrigin::signed(1),
+				TEST_RELAYER_A,
+				proof,
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			)
+			.expect("delivery has failed")
+			.actual_weight
+			.expect("receive_messages_proof always returns Some");
+
+			(pre_dispatch_weight, post_dispatch_weight)
+		}
+
+		// when dispatch fails, we pay for keeping the message
+		let (_, post_when_failed) = submit_with_failed_dispatch(1, true);
+
+		// when dispatch succeeds, the weight of keeping the message is refunded
+		let (pre, post_when_dispatched) = submit_with_failed_dispatch(2, false);
+		let failed_dispatch_weight =
+			pre.saturating_sub(<TestRuntime as Config>::WeightInfo::receive_messages_proof_weight(
+				&*prepare_messages_proof(vec![message(2, REGULAR_PAYLOAD)], None),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			));
+		assert_ne!(failed_dispatch_weight.ref_time(), 0);
+		assert_ne!(failed_dispatch_weight.proof_size(), 0);
+		assert_eq!(post_when_dispatched, post_when_failed - failed_dispatch_weight);
+	});
+}
+
+#[test]
+fn lane_purge_removes_messages_with_failed_dispatch() {
+	run_test(|| {
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		assert_eq!(FailedMessageDispatchesCount::<TestRuntime>::get(test_lane_id()), 1);
+
+		LanesManager::<TestRuntime, ()>::new()
+			.any_state_inbound_lane(test_lane_id())
+			.unwrap()
+			.purge();
+		assert!(Pallet::<TestRuntime>::failed_message_dispatches(test_lane_id()).is_empty());
+		assert_eq!(FailedMessageDispatchesCount::<TestRuntime>::get(test_lane_id()), 0);
+	});
+}
+
+#[test]
+fn retry_message_dispatch_works() {
+	run_test(|| {
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+
+		// dispatch fails again => attempts are counted
+		assert_ok!(Pallet::<TestRuntime>::retry_message_dispatch(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		assert_eq!(
+			FailedMessageDispatches::<TestRuntime>::get(test_lane_id(), 1)
+				.unwrap()
+				.dispatch_attempts,
+			2
+		);
+
+		// dispatch succeeds => message is removed
+		TestMessageDispatch::emulate_failed_dispatch(false);
+		get_ready_for_events();
+		assert_ok!(Pallet::<TestRuntime>::retry_message_dispatch(
+			RuntimeOrigin::root(),
+			test_lane_id(),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+		assert!(FailedMessageDispatches::<TestRuntime>::get(test_lane_id(), 1).is_none());
+		assert_eq!(FailedMessageDispatchesCount::<TestRuntime>::get(test_lane_id()), 0);
+		assert_eq!(
+			System::<TestRuntime>::events(),
+			vec![EventRecord {
+				phase: Phase::Initialization,
+				event: TestEvent::Messages(Event::MessageDispatchRetried {
+					lane_id: test_lane_id(),
+					nonce: 1,
+					dispatch_result: REGULAR_PAYLOAD.dispatch_result,
+				}),
+				topics: vec![],
+			}],
+		);
+	});
+}
+
+#[test]
+fn retry_message_dispatch_rejects_invalid_calls() {
+	run_test(|| {
+		TestMessageDispatch::emulate_failed_dispatch(true);
+		assert_ok!(Pallet::<TestRuntime>::receive_messages_proof(
+			RuntimeOrigin::signed(1),
+			TEST_RELAYER_A,
+			prepare_messages_proof(vec![message(1, REGULAR_PAYLOAD)], None),
+			1,
+			REGULAR_PAYLOAD.declared_weight,
+		));
+
+		assert_noop!(
+			Pallet::<TestRuntime>::retry_message_dispatch(
+				RuntimeOrigin::signed(1),
+				test_lane_id(),
+				1,
+				REGULAR_PAYLOAD.declared_weight,
+			),
+			DispatchError::BadOrigin,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::retry_message_dispatch(
+				RuntimeOrigin::root(),
+				test_lane_id(),
+				2,
+				REGULAR_PAYLOAD.declared_weight,
+			),
+			Error::<TestRuntime, ()>::UnknownFailedMessage,
+		);
+		assert_noop!(
+			Pallet::<TestRuntime>::retry_message_dispatch(
+				RuntimeOrigin::root(),
+				test_lane_id(),
+				1,
+				Weight::zero(),
+			),
+			Error::<TestRuntime, ()>::InsufficientDispatchWeight,
+		);
+	});
+}
+
 #[test]
 fn receive_messages_proof_does_not_accept_message_if_dispatch_weight_is_not_enough() {
 	run_test(|| {
@@ -275,6 +275,7 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	type OnMessagesDelivered = ();
 
 	type MessageDispatch = DummyMessageDispatch;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<16>;
 	type ThisChain = ThisUnderlyingChain;
 	type BridgedChain = BridgedUnderlyingParachain;
 	type BridgedHeaderChain = BridgeGrandpa;
@@ -125,6 +125,10 @@ where
 		};
 		MessageDispatchResult { unspent_weight: Weight::zero(), dispatch_level_result }
 	}
+
+	fn is_dispatch_failed(result: &Self::DispatchLevelResult) -> bool {
+		!matches!(result, XcmBlobMessageDispatchResult::Dispatched)
+	}
 }
 
 #[cfg(test)]
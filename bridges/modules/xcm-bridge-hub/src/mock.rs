@@ -102,6 +102,7 @@ impl pallet_bridge_messages::Config for TestRuntime {
 	type OnMessagesDelivered = ();
 
 	type MessageDispatch = TestMessageDispatch;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<16>;
 }
 
 pub struct TestMessagesWeights;
@@ -268,6 +269,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type TransferLimiter = ();
 }
 
 thread_local! {
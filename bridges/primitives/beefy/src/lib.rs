@@ -22,7 +22,9 @@
 pub use binary_merkle_tree::merkle_root;
 pub use pallet_beefy_mmr::BeefyEcdsaToEthereum;
 pub use pallet_mmr::{
-	primitives::{DataOrHash as MmrDataOrHash, LeafProof as MmrProof},
+	primitives::{
+		DataOrHash as MmrDataOrHash, EncodableOpaqueLeaf, Error as MmrError, LeafProof as MmrProof,
+	},
 	verify_leaves_proof as verify_mmr_leaves_proof,
 };
 pub use sp_consensus_beefy::{
@@ -31,18 +33,21 @@ pub use sp_consensus_beefy::{
 	},
 	known_payloads::MMR_ROOT_ID as MMR_ROOT_PAYLOAD_ID,
 	mmr::{BeefyAuthoritySet, MmrLeafVersion},
-	BeefyAuthorityId, Commitment, Payload as BeefyPayload, SignedCommitment, ValidatorSet,
-	ValidatorSetId, BEEFY_ENGINE_ID,
+	BeefyAuthorityId, Commitment, ConsensusLog as BeefyConsensusLog, Payload as BeefyPayload,
+	SignedCommitment, ValidatorSet, ValidatorSetId, VersionedFinalityProof, BEEFY_ENGINE_ID,
 };
 
+pub mod storage_keys;
+
 use bp_runtime::{BasicOperatingMode, BlockNumberOf, Chain, HashOf};
-use codec::{Decode, Encode};
+use codec::{Codec, Decode, Encode};
 use frame_support::Parameter;
 use scale_info::TypeInfo;
 use serde::{Deserialize, Serialize};
 use sp_runtime::{
+	generic::OpaqueDigestItemId,
 	traits::{Convert, MaybeSerializeDeserialize},
-	RuntimeAppPublic, RuntimeDebug,
+	Digest, RuntimeAppPublic, RuntimeDebug,
 };
 use sp_std::prelude::*;
 
@@ -105,6 +110,9 @@ pub type BeefyValidatorSignatureOf<C> =
 /// Signed BEEFY commitment used by given Substrate chain.
 pub type BeefySignedCommitmentOf<C> =
 	SignedCommitment<BlockNumberOf<C>, BeefyValidatorSignatureOf<C>>;
+/// Versioned BEEFY finality proof (justification) used by given Substrate chain.
+pub type BeefyVersionedFinalityProofOf<C> =
+	VersionedFinalityProof<BlockNumberOf<C>, BeefyValidatorSignatureOf<C>>;
 /// Hash algorithm, used to compute the digest of the BEEFY commitment before signing it.
 pub type BeefyCommitmentHasher<C> = <C as ChainWithBeefy>::CommitmentHasher;
 /// Hash algorithm used in Beefy MMR construction by given Substrate chain.
@@ -149,3 +157,30 @@ pub struct ImportedCommitment<BlockNumber, BlockHash, MmrHash> {
 	/// MMR root at the imported block.
 	pub mmr_root: MmrHash,
 }
+
+/// Some high level info about the imported commitments.
+#[derive(Encode, Decode, RuntimeDebug, PartialEq, TypeInfo)]
+pub struct ImportedCommitmentsInfoData<BlockNumber> {
+	/// Best known block number, provided in a BEEFY commitment. However this is not
+	/// the best proven block. The best proven block is this block's parent.
+	pub best_block_number: BlockNumber,
+	/// The head of the `ImportedBlockNumbers` ring buffer.
+	pub next_block_number_index: u32,
+}
+
+/// Find the BEEFY validator set, enacted by the block with given digest.
+///
+/// BEEFY validator set is changed at the first block of every session. The first commitment
+/// that is signed by the new set is always finalizing this block.
+pub fn find_authorities_change<AuthorityId: Codec>(
+	digest: &Digest,
+) -> Option<ValidatorSet<AuthorityId>> {
+	digest.convert_first(|log| {
+		match log.try_to::<BeefyConsensusLog<AuthorityId>>(OpaqueDigestItemId::Consensus(
+			&BEEFY_ENGINE_ID,
+		)) {
+			Some(BeefyConsensusLog::AuthoritiesChange(validator_set)) => Some(validator_set),
+			_ => None,
+		}
+	})
+}
@@ -0,0 +1,57 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Storage keys of bridge BEEFY pallet.
+
+/// Name of the `PalletOperatingMode` storage value.
+pub const PALLET_OPERATING_MODE_VALUE_NAME: &str = "PalletOperatingMode";
+/// Name of the `ImportedCommitmentsInfo` storage value.
+pub const IMPORTED_COMMITMENTS_INFO_VALUE_NAME: &str = "ImportedCommitmentsInfo";
+/// Name of the `ImportedCommitments` storage map.
+pub const IMPORTED_COMMITMENTS_MAP_NAME: &str = "ImportedCommitments";
+/// Name of the `CurrentAuthoritySetInfo` storage value.
+pub const CURRENT_AUTHORITY_SET_INFO_VALUE_NAME: &str = "CurrentAuthoritySetInfo";
+
+use codec::Encode;
+use frame_support::Blake2_128Concat;
+use sp_core::storage::StorageKey;
+
+/// Storage key of the `PalletOperatingMode` variable in the runtime storage.
+pub fn pallet_operating_mode_key(pallet_prefix: &str) -> StorageKey {
+	bp_runtime::storage_value_key(pallet_prefix, PALLET_OPERATING_MODE_VALUE_NAME)
+}
+
+/// Storage key of the `ImportedCommitmentsInfo` variable in the runtime storage.
+pub fn imported_commitments_info_key(pallet_prefix: &str) -> StorageKey {
+	bp_runtime::storage_value_key(pallet_prefix, IMPORTED_COMMITMENTS_INFO_VALUE_NAME)
+}
+
+/// Storage key of the `CurrentAuthoritySetInfo` variable in the runtime storage.
+pub fn current_authority_set_info_key(pallet_prefix: &str) -> StorageKey {
+	bp_runtime::storage_value_key(pallet_prefix, CURRENT_AUTHORITY_SET_INFO_VALUE_NAME)
+}
+
+/// Storage key of the commitment, imported for the given block, in the runtime storage.
+pub fn imported_commitment_key<BlockNumber: Encode>(
+	pallet_prefix: &str,
+	block_number: &BlockNumber,
+) -> StorageKey {
+	bp_runtime::storage_map_final_key::<Blake2_128Concat>(
+		pallet_prefix,
+		IMPORTED_COMMITMENTS_MAP_NAME,
+		&block_number.encode(),
+	)
+}
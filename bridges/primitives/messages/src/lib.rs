@@ -324,6 +324,23 @@ pub struct InboundMessageDetails {
 	pub dispatch_weight: Weight,
 }
 
+/// Details of the inbound message that has been delivered, but has failed to dispatch. Returned
+/// by runtime APIs.
+#[derive(Clone, Encode, Decode, RuntimeDebug, PartialEq, Eq, TypeInfo)]
+pub struct FailedMessageDispatchDetails {
+	/// Nonce of the message.
+	pub nonce: MessageNonce,
+	/// Message dispatch weight, computed during the last dispatch attempt.
+	pub dispatch_weight: Weight,
+	/// Unspent weight, reported by the last dispatch attempt.
+	pub unspent_weight: Weight,
+	/// Encoded (and possibly truncated) `target_chain::MessageDispatch::DispatchLevelResult`
+	/// of the last dispatch attempt.
+	pub dispatch_error: Vec<u8>,
+	/// Number of times the message dispatch has been attempted.
+	pub dispatch_attempts: u32,
+}
+
 /// Unrewarded relayer entry stored in the inbound lane data.
 ///
 /// This struct represents a continuous range of messages that have been delivered by the same
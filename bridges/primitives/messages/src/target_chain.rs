@@ -94,7 +94,7 @@ pub trait MessageDispatch {
 	type DispatchPayload: Decode;
 
 	/// Fine-grained result of single message dispatch (for better diagnostic purposes)
-	type DispatchLevelResult: Clone + sp_std::fmt::Debug + Eq;
+	type DispatchLevelResult: Clone + sp_std::fmt::Debug + Eq + Encode;
 
 	/// Lane identifier type.
 	type LaneId: Encode;
@@ -124,6 +124,14 @@ pub trait MessageDispatch {
 	fn dispatch(
 		message: DispatchMessage<Self::DispatchPayload, Self::LaneId>,
 	) -> MessageDispatchResult<Self::DispatchLevelResult>;
+
+	/// Returns `true` if given dispatch result means that the message has not been dispatched.
+	///
+	/// The messages pallet keeps such messages in its storage, so that the failure may be
+	/// inspected and the dispatch may be retried later.
+	fn is_dispatch_failed(_result: &Self::DispatchLevelResult) -> bool {
+		false
+	}
 }
 
 /// Manages payments that are happening at the target chain during message delivery transaction.
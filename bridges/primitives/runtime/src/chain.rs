@@ -394,6 +394,11 @@ macro_rules! decl_bridge_messages_runtime_apis {
 				pub const [<FROM_ $chain:upper _MESSAGE_DETAILS_METHOD>]: &str =
 					stringify!([<From $chain:camel InboundLaneApi_message_details>]);
 
+				/// Name of the `From<ThisChain>InboundLaneDispatchApi::failed_messages` runtime
+				/// method.
+				pub const [<FROM_ $chain:upper _FAILED_MESSAGES_METHOD>]: &str =
+					stringify!([<From $chain:camel InboundLaneDispatchApi_failed_messages>]);
+
 				sp_api::decl_runtime_apis! {
 					/// Outbound message lane API for messages that are sent to this chain.
 					///
@@ -426,6 +431,18 @@ macro_rules! decl_bridge_messages_runtime_apis {
 							messages: $crate::private::Vec<(bp_messages::MessagePayload, bp_messages::OutboundMessageDetails)>,
 						) -> $crate::private::Vec<bp_messages::InboundMessageDetails>;
 					}
+
+					/// Inbound message lane dispatch API for messages sent by this chain.
+					///
+					/// This API is implemented by runtimes that are receiving messages from this chain, not by this
+					/// chain's runtime itself.
+					pub trait [<From $chain:camel InboundLaneDispatchApi>] {
+						/// Return details of messages at given lane, that have been delivered, but have failed
+						/// to dispatch. The vector is ordered by the nonce.
+						fn failed_messages(
+							lane: $lane_id_type,
+						) -> $crate::private::Vec<bp_messages::FailedMessageDispatchDetails>;
+					}
 				}
 			}
 
@@ -17,6 +17,7 @@
 use crate::calls::UtilityCall;
 
 use crate::SimpleRuntimeVersion;
+use bp_beefy::ChainWithBeefy as ChainWithBeefyBase;
 use bp_header_chain::ChainWithGrandpa as ChainWithGrandpaBase;
 use bp_messages::ChainWithMessages as ChainWithMessagesBase;
 use bp_runtime::{
@@ -107,6 +108,17 @@ pub trait ChainWithGrandpa: Chain + ChainWithGrandpaBase {
 	type KeyOwnerProof: Decode + TypeInfo + Send;
 }
 
+/// Substrate-based chain with BEEFY && MMR pallets deployed, from minimal relay-client point of
+/// view.
+pub trait ChainWithBeefy: Chain + ChainWithBeefyBase {
+	/// Name of the bridge BEEFY pallet (used in `construct_runtime` macro call) that is deployed
+	/// at some other chain to bridge with this `ChainWithBeefy`.
+	///
+	/// We assume that all chains that are bridging with this `ChainWithBeefy` are using
+	/// the same name.
+	const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str;
+}
+
 /// Substrate-based parachain from minimal relay-client point of view.
 pub trait Parachain: Chain + ParachainBase {}
 
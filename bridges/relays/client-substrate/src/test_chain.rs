@@ -21,7 +21,8 @@
 
 #![cfg(any(feature = "test-helpers", test))]
 
-use crate::{Chain, ChainWithBalances, ChainWithMessages};
+use crate::{Chain, ChainWithBalances, ChainWithBeefy, ChainWithMessages};
+use bp_beefy::ChainWithBeefy as ChainWithBeefyBase;
 use bp_messages::{ChainWithMessages as ChainWithMessagesBase, MessageNonce};
 use bp_runtime::ChainId;
 use frame_support::{sp_runtime::StateVersion, weights::Weight};
@@ -84,6 +85,19 @@ impl ChainWithMessages for TestChain {
 	const FROM_CHAIN_MESSAGE_DETAILS_METHOD: &'static str = "TestFromMessagesDetailsMethod";
 }
 
+impl ChainWithBeefyBase for TestChain {
+	type CommitmentHasher = sp_runtime::traits::Keccak256;
+	type MmrHashing = sp_runtime::traits::Keccak256;
+	type MmrHash = sp_core::H256;
+	type BeefyMmrLeafExtra = ();
+	type AuthorityId = bp_beefy::EcdsaValidatorId;
+	type AuthorityIdToMerkleLeaf = bp_beefy::BeefyEcdsaToEthereum;
+}
+
+impl ChainWithBeefy for TestChain {
+	const WITH_CHAIN_BEEFY_PALLET_NAME: &'static str = "BridgeTestBeefy";
+}
+
 /// Primitives-level parachain that may be used in tests.
 #[derive(Clone, Debug, PartialEq, Eq)]
 pub struct TestParachainBase;
@@ -0,0 +1,430 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Mocked BEEFY pipeline and in-memory Substrate client, used in BEEFY relay tests.
+
+use crate::beefy::{
+	BeefyCommitmentProof, SubmitBeefyCommitmentCallBuilder, SubstrateBeefySyncPipeline,
+};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	BeefyAuthoritySetOf, BeefyConsensusLog, BeefyPayload, BeefySignedCommitmentOf, Commitment,
+	EcdsaValidatorId, SignedCommitment, ValidatorSet, ValidatorSetId, VersionedFinalityProof,
+	BEEFY_ENGINE_ID, MMR_ROOT_PAYLOAD_ID,
+};
+use bp_runtime::{ChainId, UnderlyingChainProvider};
+use codec::Encode;
+use frame_support::weights::Weight;
+use relay_substrate_client::{
+	test_chain::TestChain, AccountKeyPairOf, BlockNumberOf, BlockWithJustification, Chain,
+	ChainWithTransactions, Client, Error, HashOf, HeaderIdOf, HeaderOf, NonceOf, Result, SignParam,
+	SignedBlockOf, SimpleRuntimeVersion, StreamDescription, Subscription, TransactionTracker,
+	UnsignedTransaction,
+};
+use sp_core::{
+	storage::{StorageData, StorageKey},
+	Bytes, Pair,
+};
+use sp_runtime::{
+	traits::Header as HeaderT, transaction_validity::TransactionValidity, DigestItem,
+	Justifications, StateVersion,
+};
+use sp_trie::StorageProof;
+use sp_version::RuntimeVersion;
+use std::{
+	collections::HashMap,
+	sync::{Arc, Mutex},
+	time::Duration,
+};
+
+/// Signed block of all chains, used in tests.
+pub type TestSignedBlock = sp_runtime::generic::SignedBlock<
+	sp_runtime::generic::Block<TestHeader, sp_runtime::OpaqueExtrinsic>,
+>;
+/// Header of all chains, used in tests.
+pub type TestHeader = sp_runtime::generic::Header<u32, sp_runtime::traits::BlakeTwo256>;
+
+/// Chain that receives BEEFY commitments in tests.
+pub struct TestTargetUnderlyingChain;
+
+impl bp_runtime::Chain for TestTargetUnderlyingChain {
+	const ID: ChainId = *b"tbtc";
+
+	type BlockNumber = u32;
+	type Hash = sp_core::H256;
+	type Hasher = sp_runtime::traits::BlakeTwo256;
+	type Header = TestHeader;
+
+	type AccountId = sp_runtime::AccountId32;
+	type Balance = u32;
+	type Nonce = u32;
+	type Signature = sp_runtime::MultiSignature;
+
+	const STATE_VERSION: StateVersion = StateVersion::V1;
+
+	fn max_extrinsic_size() -> u32 {
+		u32::MAX
+	}
+
+	fn max_extrinsic_weight() -> Weight {
+		Weight::MAX
+	}
+}
+
+/// Relay-level chain that receives BEEFY commitments in tests.
+#[derive(Clone, Debug)]
+pub struct TestTargetChain;
+
+impl UnderlyingChainProvider for TestTargetChain {
+	type Chain = TestTargetUnderlyingChain;
+}
+
+impl Chain for TestTargetChain {
+	const NAME: &'static str = "TestTarget";
+	const BEST_FINALIZED_HEADER_ID_METHOD: &'static str = "TestTargetMethod";
+	const FREE_HEADERS_INTERVAL_METHOD: &'static str = "TestTargetMethod";
+	const AVERAGE_BLOCK_INTERVAL: Duration = Duration::from_millis(0);
+
+	type SignedBlock = TestSignedBlock;
+	type Call = ();
+}
+
+impl ChainWithTransactions for TestTargetChain {
+	type AccountKeyPair = sp_core::sr25519::Pair;
+	type SignedTransaction = ();
+
+	fn sign_transaction(
+		_: SignParam<Self>,
+		_: UnsignedTransaction<Self>,
+	) -> std::result::Result<Self::SignedTransaction, Error>
+	where
+		Self: Sized,
+	{
+		unimplemented!()
+	}
+}
+
+/// BEEFY pipeline that is used in tests.
+#[derive(Clone, Debug)]
+pub struct TestPipeline;
+
+impl SubstrateBeefySyncPipeline for TestPipeline {
+	type SourceChain = TestChain;
+	type TargetChain = TestTargetChain;
+
+	type SubmitBeefyCommitmentCallBuilder = TestSubmitBeefyCommitmentCallBuilder;
+}
+
+/// Call builder that is used in tests.
+pub struct TestSubmitBeefyCommitmentCallBuilder;
+
+impl SubmitBeefyCommitmentCallBuilder<TestPipeline> for TestSubmitBeefyCommitmentCallBuilder {
+	fn build_submit_commitment_call(_proof: BeefyCommitmentProof<TestChain>) {}
+}
+
+/// State of the in-memory chain.
+pub struct TestClientData<C: Chain> {
+	/// Chain blocks, ordered by their numbers.
+	pub blocks: Vec<SignedBlockOf<C>>,
+	/// Encoded BEEFY justifications, yielded by the justifications subscription.
+	pub beefy_justifications_stream: Vec<Bytes>,
+	/// Runtime storage at the best block.
+	pub storage: HashMap<StorageKey, Vec<u8>>,
+	/// Encoded results of runtime API calls at given blocks.
+	pub state_calls: HashMap<(String, HashOf<C>), Vec<u8>>,
+}
+
+impl<C: Chain> Default for TestClientData<C> {
+	fn default() -> Self {
+		TestClientData {
+			blocks: Vec::new(),
+			beefy_justifications_stream: Vec::new(),
+			storage: HashMap::new(),
+			state_calls: HashMap::new(),
+		}
+	}
+}
+
+/// In-memory client of the chain `C`.
+pub struct TestClient<C: Chain> {
+	/// Chain state.
+	pub data: Arc<Mutex<TestClientData<C>>>,
+}
+
+impl<C: Chain> Clone for TestClient<C> {
+	fn clone(&self) -> Self {
+		TestClient { data: self.data.clone() }
+	}
+}
+
+impl<C: Chain> std::fmt::Debug for TestClient<C> {
+	fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+		f.write_str("TestClient")
+	}
+}
+
+impl<C: Chain> TestClient<C> {
+	/// Create client with given state.
+	pub fn new(data: TestClientData<C>) -> Self {
+		TestClient { data: Arc::new(Mutex::new(data)) }
+	}
+}
+
+#[async_trait]
+impl<C: Chain> Client<C> for TestClient<C> {
+	async fn ensure_synced(&self) -> Result<()> {
+		Ok(())
+	}
+
+	async fn reconnect(&self) -> Result<()> {
+		Ok(())
+	}
+
+	fn genesis_hash(&self) -> HashOf<C> {
+		self.data.lock().unwrap().blocks[0].header().hash()
+	}
+
+	async fn header_hash_by_number(&self, number: BlockNumberOf<C>) -> Result<HashOf<C>> {
+		self.data
+			.lock()
+			.unwrap()
+			.blocks
+			.iter()
+			.map(|block| block.header())
+			.find(|header| *header.number() == number)
+			.map(|header| header.hash())
+			.ok_or_else(|| Error::Custom(format!("Missing header {number:?}")))
+	}
+
+	async fn header_by_hash(&self, hash: HashOf<C>) -> Result<HeaderOf<C>> {
+		Ok(self.block_by_hash(hash).await?.header())
+	}
+
+	async fn block_by_hash(&self, hash: HashOf<C>) -> Result<SignedBlockOf<C>> {
+		self.data
+			.lock()
+			.unwrap()
+			.blocks
+			.iter()
+			.find(|block| block.header().hash() == hash)
+			.cloned()
+			.ok_or_else(|| Error::Custom(format!("Missing block {hash:?}")))
+	}
+
+	async fn best_finalized_header_hash(&self) -> Result<HashOf<C>> {
+		Ok(self.best_header().await?.hash())
+	}
+
+	async fn best_header(&self) -> Result<HeaderOf<C>> {
+		self.data
+			.lock()
+			.unwrap()
+			.blocks
+			.last()
+			.map(|block| block.header())
+			.ok_or_else(|| Error::Custom("No headers".into()))
+	}
+
+	async fn subscribe_best_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_finalized_headers(&self) -> Result<Subscription<HeaderOf<C>>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_grandpa_finality_justifications(&self) -> Result<Subscription<Bytes>>
+	where
+		C: relay_substrate_client::ChainWithGrandpa,
+	{
+		unimplemented!()
+	}
+
+	async fn generate_grandpa_key_ownership_proof(
+		&self,
+		_at: HashOf<C>,
+		_set_id: sp_consensus_grandpa::SetId,
+		_authority_id: sp_consensus_grandpa::AuthorityId,
+	) -> Result<Option<sp_consensus_grandpa::OpaqueKeyOwnershipProof>> {
+		unimplemented!()
+	}
+
+	async fn subscribe_beefy_finality_justifications(&self) -> Result<Subscription<Bytes>> {
+		let justifications =
+			std::mem::take(&mut self.data.lock().unwrap().beefy_justifications_stream);
+		Ok(Subscription::new_broadcasted(
+			StreamDescription::new("BEEFY justifications".into(), C::NAME.into()),
+			futures::stream::iter(justifications),
+		))
+	}
+
+	async fn token_decimals(&self) -> Result<Option<u64>> {
+		unimplemented!()
+	}
+
+	async fn runtime_version(&self) -> Result<RuntimeVersion> {
+		unimplemented!()
+	}
+
+	async fn simple_runtime_version(&self) -> Result<SimpleRuntimeVersion> {
+		unimplemented!()
+	}
+
+	fn can_start_version_guard(&self) -> bool {
+		false
+	}
+
+	async fn raw_storage_value(
+		&self,
+		_at: HashOf<C>,
+		storage_key: StorageKey,
+	) -> Result<Option<StorageData>> {
+		Ok(self.data.lock().unwrap().storage.get(&storage_key).cloned().map(StorageData))
+	}
+
+	async fn pending_extrinsics(&self) -> Result<Vec<Bytes>> {
+		unimplemented!()
+	}
+
+	async fn submit_unsigned_extrinsic(&self, _transaction: Bytes) -> Result<HashOf<C>> {
+		unimplemented!()
+	}
+
+	async fn submit_signed_extrinsic(
+		&self,
+		_signer: &AccountKeyPairOf<C>,
+		_prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<HashOf<C>>
+	where
+		C: ChainWithTransactions,
+		relay_substrate_client::AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		unimplemented!()
+	}
+
+	async fn submit_and_watch_signed_extrinsic(
+		&self,
+		_signer: &AccountKeyPairOf<C>,
+		_prepare_extrinsic: impl FnOnce(HeaderIdOf<C>, NonceOf<C>) -> Result<UnsignedTransaction<C>>
+			+ Send
+			+ 'static,
+	) -> Result<TransactionTracker<C, Self>>
+	where
+		C: ChainWithTransactions,
+		relay_substrate_client::AccountIdOf<C>: From<<AccountKeyPairOf<C> as Pair>::Public>,
+	{
+		unimplemented!()
+	}
+
+	async fn validate_transaction<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_transaction: SignedTransaction,
+	) -> Result<TransactionValidity> {
+		unimplemented!()
+	}
+
+	async fn estimate_extrinsic_weight<SignedTransaction: Encode + Send + 'static>(
+		&self,
+		_at: HashOf<C>,
+		_transaction: SignedTransaction,
+	) -> Result<Weight> {
+		unimplemented!()
+	}
+
+	async fn raw_state_call<Args: Encode + Send>(
+		&self,
+		at: HashOf<C>,
+		method: String,
+		_arguments: Args,
+	) -> Result<Bytes> {
+		self.data
+			.lock()
+			.unwrap()
+			.state_calls
+			.get(&(method.clone(), at))
+			.cloned()
+			.map(Bytes)
+			.ok_or_else(|| Error::Custom(format!("Unexpected state call {method} at {at:?}")))
+	}
+
+	async fn prove_storage(
+		&self,
+		_at: HashOf<C>,
+		_keys: Vec<StorageKey>,
+	) -> Result<(StorageProof, HashOf<C>)> {
+		unimplemented!()
+	}
+}
+
+/// Returns BEEFY validator set with given id.
+pub fn validator_set(id: ValidatorSetId) -> BeefyAuthoritySetOf<TestChain> {
+	ValidatorSet::new(
+		vec![EcdsaValidatorId::from(sp_core::ecdsa::Pair::from_seed(&[1; 32]).public())],
+		id,
+	)
+	.expect("validator set is not empty")
+}
+
+/// Returns block with given number, that optionally enacts BEEFY validator set and has
+/// persisted BEEFY justification.
+pub fn block(
+	number: u32,
+	enacted_set_id: Option<ValidatorSetId>,
+	justification: Option<Vec<u8>>,
+) -> TestSignedBlock {
+	let mut header = TestHeader::new(
+		number,
+		Default::default(),
+		Default::default(),
+		Default::default(),
+		Default::default(),
+	);
+	if let Some(enacted_set_id) = enacted_set_id {
+		header.digest_mut().push(DigestItem::Consensus(
+			BEEFY_ENGINE_ID,
+			BeefyConsensusLog::AuthoritiesChange(validator_set(enacted_set_id)).encode(),
+		));
+	}
+	TestSignedBlock {
+		block: sp_runtime::generic::Block { header, extrinsics: vec![] },
+		justifications: justification
+			.map(|justification| Justifications::from((BEEFY_ENGINE_ID, justification))),
+	}
+}
+
+/// Returns BEEFY commitment for given block, signed by validator set with given id.
+pub fn commitment(
+	block_number: u32,
+	validator_set_id: ValidatorSetId,
+) -> BeefySignedCommitmentOf<TestChain> {
+	SignedCommitment {
+		commitment: Commitment {
+			payload: BeefyPayload::from_single_entry(MMR_ROOT_PAYLOAD_ID, vec![]),
+			block_number,
+			validator_set_id,
+		},
+		signatures: vec![None],
+	}
+}
+
+/// Returns encoded BEEFY justification for given commitment.
+pub fn justification(commitment: BeefySignedCommitmentOf<TestChain>) -> Vec<u8> {
+	VersionedFinalityProof::<u32, bp_beefy::EcdsaValidatorSignature>::V1(commitment).encode()
+}
@@ -0,0 +1,205 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Types and functions intended to ease adding of new Substrate -> Substrate
+//! BEEFY finality synchronization pipelines.
+//!
+//! The BEEFY bridge pallet (`pallet-bridge-beefy`) is importing signed BEEFY commitments
+//! along with the MMR proof of the leaf, inserted by the commitment block. So, unlike GRANDPA
+//! pallet, it is proving the parent of the commitment block. Moreover, the pallet is learning
+//! the next BEEFY authority set from every imported MMR leaf, so it is able to import at most one
+//! commitment per authority set. That's why BEEFY pipelines aren't using the generic
+//! `finality_relay` loop, which is built around source headers. Instead, the loop follows BEEFY
+//! justifications of the source chain and submits commitments, signed by the authority set that
+//! is known to the target chain. If there are no such commitments in the justifications stream,
+//! it submits the justification that is persisted in the block, enacting this authority set.
+
+use crate::{
+	beefy::{
+		source::SubstrateBeefySource, sync_loop::BeefySyncParams, target::SubstrateBeefyTarget,
+	},
+	TransactionParams,
+};
+
+use bp_beefy::{
+	BeefyAuthorityIdOf, BeefyAuthoritySetOf, BeefyMmrLeafExtraOf, BeefyMmrLeafOf,
+	BeefySignedCommitmentOf, MmrHashOf, MmrHashingOf, MmrProofOf,
+};
+use finality_relay::HeadersToRelay;
+use pallet_bridge_beefy::{Call as BridgeBeefyCall, Config as BridgeBeefyConfig};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain, ChainWithBeefy,
+	ChainWithTransactions, Client, HashOf, HeaderIdOf,
+};
+use relay_utils::metrics::MetricsParams;
+use sp_core::Pair;
+use std::{fmt::Debug, marker::PhantomData};
+
+#[cfg(test)]
+pub(crate) mod mock;
+pub mod source;
+pub mod sync_loop;
+pub mod target;
+
+/// Substrate -> Substrate BEEFY finality synchronization pipeline.
+pub trait SubstrateBeefySyncPipeline: 'static + Clone + Debug + Send + Sync {
+	/// BEEFY commitments of this chain are submitted to the `TargetChain`.
+	type SourceChain: ChainWithBeefy;
+	/// BEEFY commitments of the `SourceChain` are submitted to this chain.
+	type TargetChain: ChainWithTransactions;
+
+	/// How submit commitment call is built?
+	type SubmitBeefyCommitmentCallBuilder: SubmitBeefyCommitmentCallBuilder<Self>;
+}
+
+/// Signed BEEFY commitment with all data that is required to import it at the target chain.
+#[derive(Clone, Debug)]
+pub struct BeefyCommitmentProof<C: ChainWithBeefy> {
+	/// Signed BEEFY commitment.
+	pub commitment: BeefySignedCommitmentOf<C>,
+	/// Validator set that has signed the commitment.
+	pub validator_set: BeefyAuthoritySetOf<C>,
+	/// MMR leaf, inserted by the commitment block.
+	pub mmr_leaf: BeefyMmrLeafOf<C>,
+	/// Proof of the `mmr_leaf` inclusion into the MMR with root, signed by the commitment.
+	pub mmr_proof: MmrProofOf<C>,
+}
+
+impl<C: ChainWithBeefy> BeefyCommitmentProof<C> {
+	/// Returns id of the source header, that is proved by this commitment.
+	///
+	/// This is the parent of the commitment block.
+	pub fn proved_header_id(&self) -> HeaderIdOf<C> {
+		let (number, hash) = self.mmr_leaf.parent_number_and_hash;
+		bp_runtime::HeaderId(number, hash)
+	}
+}
+
+/// Different ways of building `submit_commitment` calls.
+pub trait SubmitBeefyCommitmentCallBuilder<P: SubstrateBeefySyncPipeline> {
+	/// Given signed BEEFY commitment and associated data, build call of `submit_commitment`
+	/// function of bridge BEEFY module at the target chain.
+	fn build_submit_commitment_call(
+		proof: BeefyCommitmentProof<P::SourceChain>,
+	) -> CallOf<P::TargetChain>;
+}
+
+/// Building `submit_commitment` call when you have direct access to the target
+/// chain runtime.
+pub struct DirectSubmitBeefyCommitmentCallBuilder<P, R, I> {
+	_phantom: PhantomData<(P, R, I)>,
+}
+
+impl<P, R, I> SubmitBeefyCommitmentCallBuilder<P>
+	for DirectSubmitBeefyCommitmentCallBuilder<P, R, I>
+where
+	P: SubstrateBeefySyncPipeline,
+	R: BridgeBeefyConfig<I>,
+	I: 'static,
+	R::BridgedChain: bp_beefy::ChainWithBeefy<
+		BlockNumber = BlockNumberOf<P::SourceChain>,
+		Hash = HashOf<P::SourceChain>,
+		MmrHash = MmrHashOf<P::SourceChain>,
+		BeefyMmrLeafExtra = BeefyMmrLeafExtraOf<P::SourceChain>,
+		AuthorityId = BeefyAuthorityIdOf<P::SourceChain>,
+	>,
+	MmrHashingOf<R::BridgedChain>: 'static + Send + Sync,
+	CallOf<P::TargetChain>: From<BridgeBeefyCall<R, I>>,
+{
+	fn build_submit_commitment_call(
+		proof: BeefyCommitmentProof<P::SourceChain>,
+	) -> CallOf<P::TargetChain> {
+		BridgeBeefyCall::<R, I>::submit_commitment {
+			commitment: proof.commitment,
+			validator_set: proof.validator_set,
+			mmr_leaf: Box::new(proof.mmr_leaf),
+			mmr_proof: proof.mmr_proof,
+		}
+		.into()
+	}
+}
+
+/// Macro that generates `SubmitBeefyCommitmentCallBuilder` implementation for the case when
+/// you only have an access to the mocked version of target chain runtime. In this case you
+/// should provide "name" of the call variant for the bridge BEEFY calls and the "name" of
+/// the variant for the `submit_commitment` call within that first option.
+#[rustfmt::skip]
+#[macro_export]
+macro_rules! generate_submit_beefy_commitment_call_builder {
+	($pipeline:ident, $mocked_builder:ident, $bridge_beefy:path, $submit_commitment:path) => {
+		pub struct $mocked_builder;
+
+		impl $crate::beefy::SubmitBeefyCommitmentCallBuilder<$pipeline>
+			for $mocked_builder
+		{
+			fn build_submit_commitment_call(
+				proof: $crate::beefy::BeefyCommitmentProof<
+					<$pipeline as $crate::beefy::SubstrateBeefySyncPipeline>::SourceChain
+				>,
+			) -> relay_substrate_client::CallOf<
+				<$pipeline as $crate::beefy::SubstrateBeefySyncPipeline>::TargetChain
+			> {
+				bp_runtime::paste::item! {
+					$bridge_beefy($submit_commitment {
+						commitment: proof.commitment,
+						validator_set: proof.validator_set,
+						mmr_leaf: Box::new(proof.mmr_leaf),
+						mmr_proof: proof.mmr_proof,
+					})
+				}
+			}
+		}
+	};
+}
+
+/// Run Substrate-to-Substrate BEEFY finality sync loop.
+pub async fn run<P: SubstrateBeefySyncPipeline>(
+	source_client: impl Client<P::SourceChain>,
+	target_client: impl Client<P::TargetChain>,
+	headers_to_relay: HeadersToRelay,
+	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+	metrics_params: MetricsParams,
+) -> anyhow::Result<()>
+where
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+{
+	log::info!(
+		target: "bridge",
+		"Starting {} -> {} BEEFY finality relay: relaying {:?} commitments\n\t\
+			Tx mortality: {:?}",
+		P::SourceChain::NAME,
+		P::TargetChain::NAME,
+		headers_to_relay,
+		transaction_params.mortality,
+	);
+
+	sync_loop::run(
+		SubstrateBeefySource::<P, _>::new(source_client),
+		SubstrateBeefyTarget::<P, _>::new(target_client, transaction_params),
+		BeefySyncParams {
+			tick: std::cmp::max(
+				P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+				P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+			),
+			headers_to_relay,
+		},
+		None,
+		metrics_params,
+		futures::future::pending(),
+	)
+	.await
+	.map_err(|e| anyhow::format_err!("{}", e))
+}
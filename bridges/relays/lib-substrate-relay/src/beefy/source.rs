@@ -0,0 +1,394 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Default generic implementation of BEEFY finality source for basic Substrate client.
+
+use crate::beefy::{BeefyCommitmentProof, SubstrateBeefySyncPipeline};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	BeefyAuthoritySetInfoOf, BeefyAuthoritySetOf, BeefyMmrLeafOf, BeefySignedCommitmentOf,
+	BeefyVersionedFinalityProofOf, EncodableOpaqueLeaf, InitializationData, MmrError, MmrHashOf,
+	MmrProofOf, ValidatorSetId, VersionedFinalityProof, BEEFY_ENGINE_ID,
+};
+use bp_runtime::BasicOperatingMode;
+use codec::Decode;
+use futures::stream::{unfold, Stream, StreamExt};
+use num_traits::{One, Saturating};
+use relay_substrate_client::{
+	BlockNumberOf, BlockWithJustification, Chain, ChainWithBeefy, Client, Error, HashOf,
+};
+use relay_utils::relay_loop::Client as RelayClient;
+use sp_runtime::traits::Header as HeaderT;
+use std::{marker::PhantomData, pin::Pin};
+
+/// Name of the runtime API method, that returns the BEEFY validator set at given block.
+pub(crate) const BEEFY_VALIDATOR_SET_METHOD: &str = "BeefyApi_validator_set";
+/// Name of the runtime API method, that returns information on the current BEEFY authority set.
+const BEEFY_MMR_AUTHORITY_SET_METHOD: &str = "BeefyMmrApi_authority_set_proof";
+/// Name of the runtime API method, that generates MMR leaf proofs.
+const MMR_GENERATE_PROOF_METHOD: &str = "MmrApi_generate_proof";
+
+/// Signed BEEFY commitments stream.
+pub type SignedCommitmentsStream<C> =
+	Pin<Box<dyn Stream<Item = BeefySignedCommitmentOf<C>> + Send>>;
+
+/// Substrate node as BEEFY finality source.
+pub struct SubstrateBeefySource<P: SubstrateBeefySyncPipeline, SourceClnt> {
+	client: SourceClnt,
+	_phantom: PhantomData<P>,
+}
+
+impl<P: SubstrateBeefySyncPipeline, SourceClnt: Client<P::SourceChain>>
+	SubstrateBeefySource<P, SourceClnt>
+{
+	/// Create new BEEFY source.
+	pub fn new(client: SourceClnt) -> Self {
+		SubstrateBeefySource { client, _phantom: Default::default() }
+	}
+
+	/// Returns reference to the underlying RPC client.
+	pub fn client(&self) -> &SourceClnt {
+		&self.client
+	}
+
+	/// Returns number of the best finalized block at the source chain.
+	pub async fn best_finalized_block_number(
+		&self,
+	) -> Result<BlockNumberOf<P::SourceChain>, Error> {
+		self.client.best_finalized_header_number().await
+	}
+
+	/// Returns hash of the source block with given number.
+	pub async fn block_hash(
+		&self,
+		number: BlockNumberOf<P::SourceChain>,
+	) -> Result<HashOf<P::SourceChain>, Error> {
+		self.client.header_hash_by_number(number).await
+	}
+
+	/// Subscribe to signed BEEFY commitments, produced by the source chain.
+	///
+	/// The subscription is yielding commitments for a subset of finalized blocks. Every
+	/// commitment is only announced once, so it is up to the caller to keep recent
+	/// commitments.
+	pub async fn signed_commitments(
+		&self,
+	) -> Result<SignedCommitmentsStream<P::SourceChain>, Error> {
+		Ok(unfold(
+			self.client.subscribe_beefy_finality_justifications().await?,
+			move |mut subscription| async move {
+				loop {
+					let next_justification = subscription.next().await?;
+					match decode_signed_commitment::<P::SourceChain>(&next_justification) {
+						Ok(commitment) => return Some((commitment, subscription)),
+						Err(err) => {
+							log::error!(
+								target: "bridge",
+								"Failed to decode BEEFY justification from the {} justifications stream: {:?}",
+								P::SourceChain::NAME,
+								err,
+							);
+						},
+					}
+				}
+			},
+		)
+		.boxed())
+	}
+
+	/// Returns signed commitment, persisted in the source block with given number.
+	///
+	/// BEEFY justifications are only guaranteed to be persisted for the blocks that enact
+	/// new BEEFY authority sets.
+	pub async fn persisted_commitment(
+		&self,
+		number: BlockNumberOf<P::SourceChain>,
+	) -> Result<Option<BeefySignedCommitmentOf<P::SourceChain>>, Error> {
+		let hash = self.client.header_hash_by_number(number).await?;
+		let signed_block = self.client.block_by_hash(hash).await?;
+		signed_block
+			.justification(BEEFY_ENGINE_ID)
+			.map(|raw_justification| {
+				decode_signed_commitment::<P::SourceChain>(raw_justification).map_err(|e| {
+					Error::Custom(format!(
+						"Failed to decode BEEFY justification of {} block {number}: {e:?}",
+						P::SourceChain::NAME,
+					))
+				})
+			})
+			.transpose()
+	}
+
+	/// Returns id of the BEEFY validator set, that is active at the source block with given
+	/// number.
+	pub async fn validator_set_id(
+		&self,
+		number: BlockNumberOf<P::SourceChain>,
+	) -> Result<ValidatorSetId, Error> {
+		let hash = self.client.header_hash_by_number(number).await?;
+		Ok(self.validator_set(hash).await?.id())
+	}
+
+	/// Find the first block in the inclusive range, that enacts the BEEFY validator set with
+	/// given id.
+	pub async fn find_validator_set_enactment(
+		&self,
+		set_id: ValidatorSetId,
+		range: (BlockNumberOf<P::SourceChain>, BlockNumberOf<P::SourceChain>),
+	) -> Result<Option<BlockNumberOf<P::SourceChain>>, Error> {
+		let mut current = range.0;
+		while current <= range.1 {
+			let header = self.client.header_by_number(current).await?;
+			let enacted_set = bp_beefy::find_authorities_change::<
+				bp_beefy::BeefyAuthorityIdOf<P::SourceChain>,
+			>(header.digest());
+			if enacted_set.map(|set| set.id() == set_id).unwrap_or(false) {
+				return Ok(Some(current))
+			}
+
+			current = current.saturating_add(One::one());
+		}
+
+		Ok(None)
+	}
+
+	/// Collect everything that is required to import given signed commitment at the target
+	/// chain.
+	///
+	/// The MMR leaf, that is proved here, is the leaf of the block that has been signed. So
+	/// the block, that is actually proved by the returned proof, is the parent of the
+	/// commitment block.
+	pub async fn prove_commitment(
+		&self,
+		commitment: BeefySignedCommitmentOf<P::SourceChain>,
+	) -> Result<BeefyCommitmentProof<P::SourceChain>, Error> {
+		let block_number = commitment.commitment.block_number;
+		let block_hash = self.client.header_hash_by_number(block_number).await?;
+
+		let validator_set = self.validator_set(block_hash).await?;
+		if validator_set.id() != commitment.commitment.validator_set_id {
+			return Err(Error::Custom(format!(
+				"BEEFY commitment for {} block {block_number} is signed by the validator set {}, \
+				but the set {} is active at this block",
+				P::SourceChain::NAME,
+				commitment.commitment.validator_set_id,
+				validator_set.id(),
+			)))
+		}
+
+		let (mut leaves, mmr_proof) = self
+			.client
+			.state_call::<_, Result<(Vec<EncodableOpaqueLeaf>, MmrProofOf<P::SourceChain>), MmrError>>(
+				block_hash,
+				MMR_GENERATE_PROOF_METHOD.into(),
+				(vec![block_number], Some(block_number)),
+			)
+			.await?
+			.map_err(|e| {
+				Error::Custom(format!(
+					"Failed to generate MMR proof for {} block {block_number}: {e:?}",
+					P::SourceChain::NAME,
+				))
+			})?;
+		let raw_leaf = match (leaves.pop(), leaves.is_empty()) {
+			(Some(raw_leaf), true) => raw_leaf.into_opaque_leaf(),
+			_ =>
+				return Err(Error::Custom(format!(
+					"Expected single MMR leaf in the proof for {} block {block_number}",
+					P::SourceChain::NAME,
+				))),
+		};
+		let mmr_leaf =
+			BeefyMmrLeafOf::<P::SourceChain>::decode(&mut &raw_leaf.0[..]).map_err(|e| {
+				Error::Custom(format!(
+					"Failed to decode MMR leaf of {} block {block_number}: {e:?}",
+					P::SourceChain::NAME,
+				))
+			})?;
+
+		Ok(BeefyCommitmentProof { commitment, validator_set, mmr_leaf, mmr_proof })
+	}
+
+	/// Prepare initialization data for the BEEFY bridge pallet.
+	///
+	/// The pallet is initialized with the next signed commitment, produced by the source chain.
+	pub async fn prepare_initialization_data(
+		&self,
+	) -> Result<InitializationData<BlockNumberOf<P::SourceChain>, MmrHashOf<P::SourceChain>>, Error>
+	{
+		let mut commitments = self.signed_commitments().await?;
+		let commitment = commitments.next().await.ok_or_else(|| {
+			Error::Custom(format!(
+				"{} BEEFY justifications stream has ended unexpectedly",
+				P::SourceChain::NAME,
+			))
+		})?;
+
+		let best_block_number = commitment.commitment.block_number;
+		let block_hash = self.client.header_hash_by_number(best_block_number).await?;
+		let authority_set: BeefyAuthoritySetInfoOf<P::SourceChain> = self
+			.client
+			.state_call(block_hash, BEEFY_MMR_AUTHORITY_SET_METHOD.into(), ())
+			.await?;
+		if authority_set.id != commitment.commitment.validator_set_id {
+			return Err(Error::Custom(format!(
+				"BEEFY commitment for {} block {best_block_number} is signed by the validator set {}, \
+				but the set {} is active at this block",
+				P::SourceChain::NAME,
+				commitment.commitment.validator_set_id,
+				authority_set.id,
+			)))
+		}
+
+		Ok(InitializationData {
+			operating_mode: BasicOperatingMode::Normal,
+			best_block_number,
+			authority_set,
+		})
+	}
+
+	async fn validator_set(
+		&self,
+		at: HashOf<P::SourceChain>,
+	) -> Result<BeefyAuthoritySetOf<P::SourceChain>, Error> {
+		self.client
+			.state_call::<_, Option<BeefyAuthoritySetOf<P::SourceChain>>>(
+				at,
+				BEEFY_VALIDATOR_SET_METHOD.into(),
+				(),
+			)
+			.await?
+			.ok_or_else(|| {
+				Error::Custom(format!(
+					"BEEFY validator set is not available at {} block {at:?}",
+					P::SourceChain::NAME,
+				))
+			})
+	}
+}
+
+impl<P: SubstrateBeefySyncPipeline, SourceClnt: Clone> Clone
+	for SubstrateBeefySource<P, SourceClnt>
+{
+	fn clone(&self) -> Self {
+		SubstrateBeefySource { client: self.client.clone(), _phantom: Default::default() }
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefySyncPipeline, SourceClnt: Client<P::SourceChain>> RelayClient
+	for SubstrateBeefySource<P, SourceClnt>
+{
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		self.client.reconnect().await
+	}
+}
+
+/// Decode signed BEEFY commitment from the encoded versioned finality proof.
+fn decode_signed_commitment<C: ChainWithBeefy>(
+	mut raw_justification: &[u8],
+) -> Result<BeefySignedCommitmentOf<C>, codec::Error> {
+	match BeefyVersionedFinalityProofOf::<C>::decode(&mut raw_justification)? {
+		VersionedFinalityProof::V1(signed_commitment) => Ok(signed_commitment),
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::beefy::mock::*;
+	use codec::Encode;
+	use relay_substrate_client::test_chain::TestChain;
+	use sp_core::Bytes;
+
+	fn source(
+		data: TestClientData<TestChain>,
+	) -> SubstrateBeefySource<TestPipeline, TestClient<TestChain>> {
+		SubstrateBeefySource::new(TestClient::new(data))
+	}
+
+	#[async_std::test]
+	async fn find_validator_set_enactment_returns_first_enacting_block() {
+		let source = source(TestClientData {
+			blocks: (0..=10)
+				.map(|number| match number {
+					5 => block(number, Some(1), None),
+					8 => block(number, Some(2), None),
+					_ => block(number, None, None),
+				})
+				.collect(),
+			..Default::default()
+		});
+
+		assert_eq!(source.find_validator_set_enactment(1, (1, 10)).await.unwrap(), Some(5));
+		assert_eq!(source.find_validator_set_enactment(2, (1, 10)).await.unwrap(), Some(8));
+		assert_eq!(source.find_validator_set_enactment(1, (6, 10)).await.unwrap(), None);
+		assert_eq!(source.find_validator_set_enactment(3, (1, 10)).await.unwrap(), None);
+	}
+
+	#[async_std::test]
+	async fn persisted_commitment_is_decoded_from_block_justification() {
+		let source = source(TestClientData {
+			blocks: vec![
+				block(0, None, None),
+				block(1, Some(1), Some(justification(commitment(1, 1)))),
+				block(2, None, Some(vec![42])),
+			],
+			..Default::default()
+		});
+
+		assert_eq!(source.persisted_commitment(0).await.unwrap(), None);
+		assert_eq!(source.persisted_commitment(1).await.unwrap(), Some(commitment(1, 1)));
+		assert!(source.persisted_commitment(2).await.is_err());
+	}
+
+	#[async_std::test]
+	async fn signed_commitments_skips_undecodable_justifications() {
+		let source = source(TestClientData {
+			beefy_justifications_stream: vec![
+				Bytes(justification(commitment(1, 0))),
+				Bytes(vec![42]),
+				Bytes(justification(commitment(2, 0))),
+			],
+			..Default::default()
+		});
+
+		let commitments = source.signed_commitments().await.unwrap().collect::<Vec<_>>().await;
+		assert_eq!(commitments, vec![commitment(1, 0), commitment(2, 0)]);
+	}
+
+	#[async_std::test]
+	async fn validator_set_id_is_read_from_runtime() {
+		let at = block(3, None, None);
+		let at_hash = at.block.header.hash();
+		let source = source(TestClientData {
+			blocks: vec![block(0, None, None), at],
+			state_calls: vec![(
+				(BEEFY_VALIDATOR_SET_METHOD.into(), at_hash),
+				Some(validator_set(7)).encode(),
+			)]
+			.into_iter()
+			.collect(),
+			..Default::default()
+		});
+
+		assert_eq!(source.validator_set_id(3).await.unwrap(), 7);
+		assert!(source.validator_set_id(0).await.is_err());
+	}
+}
@@ -0,0 +1,588 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! BEEFY commitments synchronization loop.
+
+use crate::{
+	beefy::{
+		source::{SignedCommitmentsStream, SubstrateBeefySource},
+		target::{BeefyTargetState, SubstrateBeefyTarget},
+		SubstrateBeefySyncPipeline,
+	},
+	finality::source::RequiredHeaderNumberRef,
+};
+
+use bp_beefy::{BeefySignedCommitmentOf, ValidatorSetId};
+use finality_relay::{HeadersToRelay, SyncLoopMetrics};
+use futures::{future::Fuse, select, Future, FutureExt, StreamExt};
+use num_traits::{One, Saturating};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, Chain, Client, Error as SubstrateError,
+};
+use relay_utils::{
+	metrics::MetricsParams, FailedClient, MaybeConnectionError, TrackedTransactionStatus,
+	TransactionTracker,
+};
+use sp_core::Pair;
+use std::{collections::BTreeMap, time::Duration};
+
+/// Maximal number of recent commitments from the justifications stream that we keep in memory.
+const RECENT_COMMITMENTS_LIMIT: usize = 1024;
+/// Maximal number of source headers that are scanned for BEEFY validator set changes during
+/// single loop iteration.
+const MAX_HEADERS_TO_SCAN_PER_ITERATION: u32 = 1024;
+
+/// BEEFY sync loop parameters.
+#[derive(Debug, Clone)]
+pub struct BeefySyncParams {
+	/// Interval at which we check updates on both clients. Normally should be larger than
+	/// `min(source_block_time, target_block_time)`.
+	pub tick: Duration,
+	/// Which commitments do we want to relay. BEEFY commitments may not be imported for free,
+	/// so `HeadersToRelay::Free` is the same as `HeadersToRelay::Mandatory` here.
+	pub headers_to_relay: HeadersToRelay,
+}
+
+/// Error that may happen inside BEEFY synchronization loop.
+#[derive(Debug)]
+enum Error<Number> {
+	/// Source client request has failed with given error.
+	Source(SubstrateError),
+	/// Target client request has failed with given error.
+	Target(SubstrateError),
+	/// Commitment, that enacts the authority set, known to the target, is missing from the
+	/// source node.
+	MissingMandatoryCommitment(Number),
+}
+
+impl<Number> Error<Number> {
+	fn fail_if_connection_error(&self) -> Result<(), FailedClient> {
+		match *self {
+			Error::Source(ref error) if error.is_connection_error() => Err(FailedClient::Source),
+			Error::Target(ref error) if error.is_connection_error() => Err(FailedClient::Target),
+			_ => Ok(()),
+		}
+	}
+}
+
+/// Prometheus metrics prefix for the BEEFY sync loop of given pipeline.
+pub fn metrics_prefix<P: SubstrateBeefySyncPipeline>() -> String {
+	format!("{}_to_{}_BeefySync", P::SourceChain::NAME, P::TargetChain::NAME)
+}
+
+/// BEEFY commitments synchronization loop.
+pub(crate) struct BeefySyncLoop<P: SubstrateBeefySyncPipeline, SourceClnt, TargetClnt> {
+	source: SubstrateBeefySource<P, SourceClnt>,
+	target: SubstrateBeefyTarget<P, TargetClnt>,
+	sync_params: BeefySyncParams,
+	required_header_number: Option<RequiredHeaderNumberRef<P::SourceChain>>,
+	metrics_sync: Option<SyncLoopMetrics>,
+
+	commitments_stream: Option<SignedCommitmentsStream<P::SourceChain>>,
+	recent_commitments:
+		BTreeMap<BlockNumberOf<P::SourceChain>, BeefySignedCommitmentOf<P::SourceChain>>,
+	scanned_headers: Option<(ValidatorSetId, BlockNumberOf<P::SourceChain>)>,
+	best_submitted_number: Option<BlockNumberOf<P::SourceChain>>,
+}
+
+impl<P, SourceClnt, TargetClnt> BeefySyncLoop<P, SourceClnt, TargetClnt>
+where
+	P: SubstrateBeefySyncPipeline,
+	SourceClnt: Client<P::SourceChain>,
+	TargetClnt: Client<P::TargetChain>,
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+{
+	fn new(
+		source: SubstrateBeefySource<P, SourceClnt>,
+		target: SubstrateBeefyTarget<P, TargetClnt>,
+		sync_params: BeefySyncParams,
+		required_header_number: Option<RequiredHeaderNumberRef<P::SourceChain>>,
+		metrics_sync: Option<SyncLoopMetrics>,
+	) -> Self {
+		BeefySyncLoop {
+			source,
+			target,
+			sync_params,
+			required_header_number,
+			metrics_sync,
+			commitments_stream: None,
+			recent_commitments: BTreeMap::new(),
+			scanned_headers: None,
+			best_submitted_number: None,
+		}
+	}
+
+	/// Run BEEFY sync loop until connection to one of nodes is lost.
+	async fn run_until_connection_lost(
+		mut self,
+		exit_signal: impl Future<Output = ()>,
+	) -> Result<(), FailedClient> {
+		let commitment_submission_tx_tracker = Fuse::terminated();
+		let exit_signal = exit_signal.fuse();
+		futures::pin_mut!(exit_signal, commitment_submission_tx_tracker);
+
+		loop {
+			self.ensure_commitments_stream().await?;
+
+			// run loop iteration
+			let next_tick = match self.run_iteration().await {
+				Ok(Some(tx_tracker)) => {
+					commitment_submission_tx_tracker.set(tx_tracker.wait().fuse());
+					self.sync_params.tick
+				},
+				Ok(None) => self.sync_params.tick,
+				Err(error) => {
+					log::error!(
+						target: "bridge",
+						"BEEFY sync loop iteration has failed with error: {:?}",
+						error,
+					);
+					error.fail_if_connection_error()?;
+					relay_utils::relay_loop::RECONNECT_DELAY
+				},
+			};
+
+			// wait till exit signal, or new source block
+			select! {
+				tx_status = commitment_submission_tx_tracker => {
+					if let TrackedTransactionStatus::Lost = tx_status {
+						log::error!(
+							target: "bridge",
+							"BEEFY commitment submission tx to {} has been lost.",
+							P::TargetChain::NAME,
+						);
+					}
+					// the commitment is either imported, or we need to resubmit it
+					self.best_submitted_number = None;
+				},
+				_ = async_std::task::sleep(next_tick).fuse() => {},
+				_ = exit_signal => return Ok(()),
+			}
+		}
+	}
+
+	async fn run_iteration(
+		&mut self,
+	) -> Result<
+		Option<relay_substrate_client::TransactionTracker<P::TargetChain, TargetClnt>>,
+		Error<BlockNumberOf<P::SourceChain>>,
+	> {
+		self.target.ensure_pallet_active().await.map_err(Error::Target)?;
+		let state = self.target.state().await.map_err(Error::Target)?;
+		let best_number_at_source =
+			self.source.best_finalized_block_number().await.map_err(Error::Source)?;
+		self.update_metrics(&state, best_number_at_source).await?;
+		self.read_recent_commitments(state.best_block_number);
+
+		// if we have already submitted commitment, then we just need to wait for it
+		if self.best_submitted_number > Some(state.best_block_number) {
+			return Ok(None)
+		}
+
+		let Some(commitment) = self.select_commitment(&state, best_number_at_source).await? else {
+			return Ok(None)
+		};
+		let commitment_number = commitment.commitment.block_number;
+		let proof = self.source.prove_commitment(commitment).await.map_err(Error::Source)?;
+
+		log::info!(
+			target: "bridge",
+			"Submitting {} BEEFY commitment for block {:?}, signed by validator set {} to {}",
+			P::SourceChain::NAME,
+			commitment_number,
+			proof.validator_set.id(),
+			P::TargetChain::NAME,
+		);
+
+		let tx_tracker = self.target.submit_commitment(proof).await.map_err(Error::Target)?;
+		self.best_submitted_number = Some(commitment_number);
+		Ok(Some(tx_tracker))
+	}
+
+	/// Select commitment that may be imported by the target chain.
+	async fn select_commitment(
+		&mut self,
+		state: &BeefyTargetState<P::SourceChain>,
+		best_number_at_source: BlockNumberOf<P::SourceChain>,
+	) -> Result<Option<BeefySignedCommitmentOf<P::SourceChain>>, Error<BlockNumberOf<P::SourceChain>>>
+	{
+		let set_id = state.authority_set.id;
+		let relay_non_mandatory = self.sync_params.headers_to_relay == HeadersToRelay::All ||
+			match self.required_header_number {
+				Some(ref required_header_number) =>
+					*required_header_number.lock().await >= state.best_block_number,
+				None => false,
+			};
+
+		// if the set, known to the target, is already active at the best target block, any
+		// commitment signed by this set may be imported. Otherwise, we must import the
+		// commitment that enacts this set before we're able to import anything else
+		let is_set_enacted = self
+			.source
+			.validator_set_id(state.best_block_number)
+			.await
+			.map_err(Error::Source)? ==
+			set_id;
+		if relay_non_mandatory || is_set_enacted {
+			let recent_commitment = self
+				.recent_commitments
+				.values()
+				.rev()
+				.find(|commitment| commitment.commitment.validator_set_id == set_id);
+			if let Some(recent_commitment) = recent_commitment {
+				return Ok(Some(recent_commitment.clone()))
+			}
+		}
+		if is_set_enacted {
+			return Ok(None)
+		}
+
+		// look for the block that enacts the set
+		let Some(scan_range) = headers_scan_range(
+			self.scanned_headers,
+			set_id,
+			state.best_block_number,
+			best_number_at_source,
+		) else {
+			return Ok(None)
+		};
+		let enactment_block = self
+			.source
+			.find_validator_set_enactment(set_id, scan_range)
+			.await
+			.map_err(Error::Source)?;
+		let Some(enactment_block) = enactment_block else {
+			self.scanned_headers = Some((set_id, scan_range.1));
+			return Ok(None)
+		};
+
+		match self.source.persisted_commitment(enactment_block).await.map_err(Error::Source)? {
+			Some(commitment) if commitment.commitment.validator_set_id == set_id =>
+				Ok(Some(commitment)),
+			_ => Err(Error::MissingMandatoryCommitment(enactment_block)),
+		}
+	}
+
+	/// Read new commitments from the justifications stream.
+	fn read_recent_commitments(&mut self, best_number_at_target: BlockNumberOf<P::SourceChain>) {
+		if let Some(ref mut commitments_stream) = self.commitments_stream {
+			loop {
+				match commitments_stream.next().now_or_never() {
+					Some(Some(commitment)) => {
+						self.recent_commitments
+							.insert(commitment.commitment.block_number, commitment);
+					},
+					Some(None) => {
+						self.commitments_stream = None;
+						break
+					},
+					None => break,
+				}
+			}
+		}
+
+		self.recent_commitments = self
+			.recent_commitments
+			.split_off(&best_number_at_target.saturating_add(One::one()));
+		while self.recent_commitments.len() > RECENT_COMMITMENTS_LIMIT {
+			self.recent_commitments.pop_first();
+		}
+	}
+
+	/// Update sync metrics.
+	async fn update_metrics(
+		&self,
+		state: &BeefyTargetState<P::SourceChain>,
+		best_number_at_source: BlockNumberOf<P::SourceChain>,
+	) -> Result<(), Error<BlockNumberOf<P::SourceChain>>> {
+		let Some(ref metrics_sync) = self.metrics_sync else { return Ok(()) };
+
+		// the pallet only knows the hash of the parent of the best commitment block
+		let is_using_same_fork = match state.best_proven_header_id {
+			Some(ref best_proven_header_id) =>
+				self.source.block_hash(best_proven_header_id.0).await.map_err(Error::Source)? ==
+					best_proven_header_id.1,
+			None => true,
+		};
+
+		metrics_sync.update_best_block_at_source(best_number_at_source);
+		metrics_sync.update_best_block_at_target(state.best_block_number);
+		metrics_sync.update_using_same_fork(is_using_same_fork);
+		Ok(())
+	}
+
+	async fn ensure_commitments_stream(&mut self) -> Result<(), FailedClient> {
+		if self.commitments_stream.is_some() {
+			return Ok(())
+		}
+
+		match self.source.signed_commitments().await {
+			Ok(commitments_stream) => {
+				self.commitments_stream = Some(commitments_stream);
+				Ok(())
+			},
+			Err(e) => {
+				log::error!(
+					target: "bridge",
+					"Failed to subscribe to {} BEEFY justifications: {:?}",
+					P::SourceChain::NAME,
+					e,
+				);
+				if e.is_connection_error() {
+					return Err(FailedClient::Source)
+				}
+				Ok(())
+			},
+		}
+	}
+}
+
+/// Returns inclusive range of source headers that must be scanned for the block, enacting
+/// the validator set with given id.
+fn headers_scan_range<Number: Copy + Ord + Saturating + One + From<u32>>(
+	scanned_headers: Option<(ValidatorSetId, Number)>,
+	set_id: ValidatorSetId,
+	best_number_at_target: Number,
+	best_number_at_source: Number,
+) -> Option<(Number, Number)> {
+	let scanned_up_to = match scanned_headers {
+		Some((scanned_set_id, scanned_up_to)) if scanned_set_id == set_id =>
+			std::cmp::max(scanned_up_to, best_number_at_target),
+		_ => best_number_at_target,
+	};
+	let scan_from = scanned_up_to.saturating_add(One::one());
+	if scan_from > best_number_at_source {
+		return None
+	}
+
+	let scan_to = std::cmp::min(
+		best_number_at_source,
+		scanned_up_to.saturating_add(MAX_HEADERS_TO_SCAN_PER_ITERATION.into()),
+	);
+	Some((scan_from, scan_to))
+}
+
+/// Run BEEFY commitments synchronization loop.
+pub(crate) async fn run<P, SourceClnt, TargetClnt>(
+	source: SubstrateBeefySource<P, SourceClnt>,
+	target: SubstrateBeefyTarget<P, TargetClnt>,
+	sync_params: BeefySyncParams,
+	required_header_number: Option<RequiredHeaderNumberRef<P::SourceChain>>,
+	metrics_params: MetricsParams,
+	exit_signal: impl Future<Output = ()> + 'static + Send,
+) -> Result<(), relay_utils::Error>
+where
+	P: SubstrateBeefySyncPipeline,
+	SourceClnt: Client<P::SourceChain>,
+	TargetClnt: Client<P::TargetChain>,
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+{
+	let exit_signal = exit_signal.shared();
+	relay_utils::relay_loop(source, target)
+		.with_metrics(metrics_params)
+		.loop_metric(SyncLoopMetrics::new(
+			Some(&metrics_prefix::<P>()),
+			"source",
+			"source_at_target",
+		)?)?
+		.expose()
+		.await?
+		.run(metrics_prefix::<P>(), move |source, target, metrics| {
+			BeefySyncLoop::new(
+				source,
+				target,
+				sync_params.clone(),
+				required_header_number.clone(),
+				metrics,
+			)
+			.run_until_connection_lost(exit_signal.clone())
+		})
+		.await
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::{
+		beefy::{mock::*, source::BEEFY_VALIDATOR_SET_METHOD, target::BeefyTargetState},
+		TransactionParams,
+	};
+	use bp_beefy::BeefyAuthoritySet;
+	use codec::Encode;
+	use relay_substrate_client::test_chain::TestChain;
+	use sp_core::{Bytes, H256};
+	use sp_runtime::traits::Header as _;
+
+	type TestSyncLoop =
+		BeefySyncLoop<TestPipeline, TestClient<TestChain>, TestClient<TestTargetChain>>;
+
+	/// Source chain with 20 blocks, where set `1` is enacted at block `5` and set `2` is enacted
+	/// at block `14`.
+	fn sync_loop(
+		enactment_justification: Option<Vec<u8>>,
+		justifications_stream: Vec<Vec<u8>>,
+	) -> TestSyncLoop {
+		let blocks = (0..=20)
+			.map(|number| match number {
+				5 => block(number, Some(1), Some(justification(commitment(5, 1)))),
+				14 => block(number, Some(2), enactment_justification.clone()),
+				_ => block(number, None, None),
+			})
+			.collect::<Vec<_>>();
+		let state_calls = blocks
+			.iter()
+			.map(|block| {
+				let number = *block.block.header.number();
+				let set_id = if number < 5 {
+					0
+				} else if number < 14 {
+					1
+				} else {
+					2
+				};
+				(
+					(BEEFY_VALIDATOR_SET_METHOD.into(), block.block.header.hash()),
+					Some(validator_set(set_id)).encode(),
+				)
+			})
+			.collect();
+		let source = SubstrateBeefySource::new(TestClient::new(TestClientData {
+			blocks,
+			beefy_justifications_stream: justifications_stream.into_iter().map(Bytes).collect(),
+			state_calls,
+			..Default::default()
+		}));
+		let target = SubstrateBeefyTarget::new(
+			TestClient::new(TestClientData {
+				blocks: vec![block(0, None, None)],
+				..Default::default()
+			}),
+			TransactionParams {
+				signer: sp_core::sr25519::Pair::from_seed(&[0; 32]),
+				mortality: None,
+			},
+		);
+		BeefySyncLoop::new(
+			source,
+			target,
+			BeefySyncParams {
+				tick: Duration::from_secs(1),
+				headers_to_relay: HeadersToRelay::Mandatory,
+			},
+			None,
+			None,
+		)
+	}
+
+	fn target_state(best_block_number: u32, set_id: ValidatorSetId) -> BeefyTargetState<TestChain> {
+		BeefyTargetState {
+			best_block_number,
+			best_proven_header_id: None,
+			authority_set: BeefyAuthoritySet {
+				id: set_id,
+				len: 1,
+				keyset_commitment: H256::default(),
+			},
+		}
+	}
+
+	async fn select_commitment(
+		sync_loop: &mut TestSyncLoop,
+		state: BeefyTargetState<TestChain>,
+	) -> Result<Option<BeefySignedCommitmentOf<TestChain>>, Error<u32>> {
+		sync_loop.ensure_commitments_stream().await.unwrap();
+		sync_loop.read_recent_commitments(state.best_block_number);
+		sync_loop.select_commitment(&state, 20).await
+	}
+
+	#[async_std::test]
+	async fn selects_best_recent_commitment_signed_by_enacted_set() {
+		let mut sync_loop = sync_loop(
+			None,
+			vec![
+				justification(commitment(8, 1)),
+				justification(commitment(11, 1)),
+				justification(commitment(12, 1)),
+				justification(commitment(16, 2)),
+			],
+		);
+
+		assert_eq!(
+			select_commitment(&mut sync_loop, target_state(10, 1)).await.unwrap(),
+			Some(commitment(12, 1)),
+		);
+		// commitments that are older than the best block at target are pruned
+		assert_eq!(sync_loop.recent_commitments.keys().collect::<Vec<_>>(), vec![&11, &12, &16]);
+	}
+
+	#[async_std::test]
+	async fn selects_nothing_when_there_are_no_commitments_signed_by_enacted_set() {
+		let mut sync_loop = sync_loop(None, vec![justification(commitment(16, 2))]);
+
+		assert_eq!(select_commitment(&mut sync_loop, target_state(10, 1)).await.unwrap(), None);
+	}
+
+	#[async_std::test]
+	async fn selects_persisted_commitment_that_enacts_known_set() {
+		let mut sync_loop = sync_loop(
+			Some(justification(commitment(14, 2))),
+			vec![justification(commitment(16, 2))],
+		);
+
+		// the set `2` is not yet enacted at block `10`, so we need to submit the mandatory
+		// commitment first
+		assert_eq!(
+			select_commitment(&mut sync_loop, target_state(10, 2)).await.unwrap(),
+			Some(commitment(14, 2)),
+		);
+	}
+
+	#[async_std::test]
+	async fn fails_when_commitment_that_enacts_known_set_is_missing() {
+		let mut sync_loop = sync_loop(None, vec![justification(commitment(16, 2))]);
+
+		assert!(matches!(
+			select_commitment(&mut sync_loop, target_state(10, 2)).await,
+			Err(Error::MissingMandatoryCommitment(14)),
+		));
+	}
+
+	#[test]
+	fn headers_scan_range_starts_after_best_block_at_target() {
+		assert_eq!(headers_scan_range::<u32>(None, 5, 100, 200), Some((101, 200)));
+		assert_eq!(headers_scan_range::<u32>(Some((4, 150)), 5, 100, 200), Some((101, 200)));
+	}
+
+	#[test]
+	fn headers_scan_range_skips_scanned_headers() {
+		assert_eq!(headers_scan_range::<u32>(Some((5, 150)), 5, 100, 200), Some((151, 200)));
+		assert_eq!(headers_scan_range::<u32>(Some((5, 50)), 5, 100, 200), Some((101, 200)));
+	}
+
+	#[test]
+	fn headers_scan_range_is_empty_when_everything_is_scanned() {
+		assert_eq!(headers_scan_range::<u32>(Some((5, 200)), 5, 100, 200), None);
+		assert_eq!(headers_scan_range::<u32>(None, 5, 200, 200), None);
+	}
+
+	#[test]
+	fn headers_scan_range_is_limited() {
+		assert_eq!(
+			headers_scan_range::<u32>(None, 5, 100, 100_000),
+			Some((101, 100 + MAX_HEADERS_TO_SCAN_PER_ITERATION)),
+		);
+	}
+}
@@ -0,0 +1,274 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Default generic implementation of BEEFY finality target for basic Substrate client.
+
+use crate::{
+	beefy::{BeefyCommitmentProof, SubmitBeefyCommitmentCallBuilder, SubstrateBeefySyncPipeline},
+	TransactionParams,
+};
+
+use async_trait::async_trait;
+use bp_beefy::{
+	storage_keys, BeefyAuthoritySetInfoOf, ImportedCommitment, ImportedCommitmentsInfoData,
+	MmrHashOf,
+};
+use bp_runtime::{BasicOperatingMode, HeaderId, OperatingMode};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, ChainWithBeefy, Client, Error, HashOf,
+	HeaderIdOf, TransactionEra, TransactionTracker, UnsignedTransaction,
+};
+use relay_utils::relay_loop::Client as RelayClient;
+use sp_core::Pair;
+use std::marker::PhantomData;
+
+/// State of the BEEFY bridge pallet at the target chain.
+#[derive(Clone, Debug)]
+pub struct BeefyTargetState<C: ChainWithBeefy> {
+	/// Number of the source block, finalized by the best imported commitment.
+	pub best_block_number: BlockNumberOf<C>,
+	/// Id of the best source header, which hash is known to the pallet.
+	///
+	/// This is the parent of `best_block_number`. It is `None` if no commitments have been
+	/// imported since the pallet has been initialized.
+	pub best_proven_header_id: Option<HeaderIdOf<C>>,
+	/// Authority set that is expected to sign the next imported commitment.
+	pub authority_set: BeefyAuthoritySetInfoOf<C>,
+}
+
+/// Substrate client as BEEFY finality target.
+pub struct SubstrateBeefyTarget<P: SubstrateBeefySyncPipeline, TargetClnt> {
+	client: TargetClnt,
+	transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+	_phantom: PhantomData<P>,
+}
+
+impl<P: SubstrateBeefySyncPipeline, TargetClnt: Client<P::TargetChain>>
+	SubstrateBeefyTarget<P, TargetClnt>
+{
+	/// Create new BEEFY target.
+	pub fn new(
+		client: TargetClnt,
+		transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+	) -> Self {
+		SubstrateBeefyTarget { client, transaction_params, _phantom: Default::default() }
+	}
+
+	/// Returns reference to the underlying RPC client.
+	pub fn client(&self) -> &TargetClnt {
+		&self.client
+	}
+
+	/// Ensure that the bridge pallet at target chain is active.
+	pub async fn ensure_pallet_active(&self) -> Result<(), Error> {
+		let is_halted = self
+			.client
+			.storage_value::<BasicOperatingMode>(
+				self.client.best_header_hash().await?,
+				storage_keys::pallet_operating_mode_key(
+					P::SourceChain::WITH_CHAIN_BEEFY_PALLET_NAME,
+				),
+			)
+			.await?
+			.map(|operating_mode| operating_mode.is_halted())
+			.unwrap_or(false);
+		if is_halted {
+			return Err(Error::BridgePalletIsHalted)
+		}
+
+		Ok(())
+	}
+
+	/// Read state of the bridge pallet at the best target block.
+	pub async fn state(&self) -> Result<BeefyTargetState<P::SourceChain>, Error> {
+		let pallet_name = P::SourceChain::WITH_CHAIN_BEEFY_PALLET_NAME;
+		let at = self.client.best_header_hash().await?;
+
+		let best_block_number = self
+			.client
+			.storage_value::<ImportedCommitmentsInfoData<BlockNumberOf<P::SourceChain>>>(
+				at,
+				storage_keys::imported_commitments_info_key(pallet_name),
+			)
+			.await?
+			.ok_or(Error::BridgePalletIsNotInitialized)?
+			.best_block_number;
+		let best_proven_header_id = self
+			.client
+			.storage_value::<ImportedCommitment<
+				BlockNumberOf<P::SourceChain>,
+				HashOf<P::SourceChain>,
+				MmrHashOf<P::SourceChain>,
+			>>(at, storage_keys::imported_commitment_key(pallet_name, &best_block_number))
+			.await?
+			.map(|commitment| {
+				let (number, hash) = commitment.parent_number_and_hash;
+				HeaderId(number, hash)
+			});
+		let authority_set = self
+			.client
+			.storage_value::<BeefyAuthoritySetInfoOf<P::SourceChain>>(
+				at,
+				storage_keys::current_authority_set_info_key(pallet_name),
+			)
+			.await?
+			.unwrap_or_default();
+
+		Ok(BeefyTargetState { best_block_number, best_proven_header_id, authority_set })
+	}
+
+	/// Submit BEEFY commitment to the target chain.
+	pub async fn submit_commitment(
+		&self,
+		proof: BeefyCommitmentProof<P::SourceChain>,
+	) -> Result<TransactionTracker<P::TargetChain, TargetClnt>, Error>
+	where
+		AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as Pair>::Public>,
+	{
+		let mortality = self.transaction_params.mortality;
+		let call = P::SubmitBeefyCommitmentCallBuilder::build_submit_commitment_call(proof);
+		self.client
+			.submit_and_watch_signed_extrinsic(
+				&self.transaction_params.signer,
+				move |best_block_id, transaction_nonce| {
+					Ok(UnsignedTransaction::new(call.into(), transaction_nonce)
+						.era(TransactionEra::new(best_block_id, mortality)))
+				},
+			)
+			.await
+	}
+}
+
+impl<P: SubstrateBeefySyncPipeline, TargetClnt: Clone> Clone
+	for SubstrateBeefyTarget<P, TargetClnt>
+{
+	fn clone(&self) -> Self {
+		SubstrateBeefyTarget {
+			client: self.client.clone(),
+			transaction_params: self.transaction_params.clone(),
+			_phantom: Default::default(),
+		}
+	}
+}
+
+#[async_trait]
+impl<P: SubstrateBeefySyncPipeline, TargetClnt: Client<P::TargetChain>> RelayClient
+	for SubstrateBeefyTarget<P, TargetClnt>
+{
+	type Error = Error;
+
+	async fn reconnect(&mut self) -> Result<(), Error> {
+		self.client.reconnect().await
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use crate::beefy::mock::*;
+	use bp_beefy::BeefyAuthoritySet;
+	use codec::Encode;
+	use relay_substrate_client::test_chain::TestChain;
+	use sp_core::{storage::StorageKey, H256};
+
+	const PALLET_NAME: &str = <TestChain as ChainWithBeefy>::WITH_CHAIN_BEEFY_PALLET_NAME;
+
+	fn target(
+		storage: Vec<(StorageKey, Vec<u8>)>,
+	) -> SubstrateBeefyTarget<TestPipeline, TestClient<TestTargetChain>> {
+		SubstrateBeefyTarget::new(
+			TestClient::new(TestClientData {
+				blocks: vec![block(0, None, None)],
+				storage: storage.into_iter().collect(),
+				..Default::default()
+			}),
+			TransactionParams {
+				signer: sp_core::sr25519::Pair::from_seed(&[0; 32]),
+				mortality: None,
+			},
+		)
+	}
+
+	fn authority_set() -> BeefyAuthoritySet<H256> {
+		BeefyAuthoritySet { id: 5, len: 1, keyset_commitment: H256::repeat_byte(5) }
+	}
+
+	#[async_std::test]
+	async fn ensure_pallet_active_fails_when_pallet_is_halted() {
+		assert!(target(vec![]).ensure_pallet_active().await.is_ok());
+		assert!(target(vec![(
+			storage_keys::pallet_operating_mode_key(PALLET_NAME),
+			BasicOperatingMode::Normal.encode(),
+		)])
+		.ensure_pallet_active()
+		.await
+		.is_ok());
+		assert!(matches!(
+			target(vec![(
+				storage_keys::pallet_operating_mode_key(PALLET_NAME),
+				BasicOperatingMode::Halted.encode(),
+			)])
+			.ensure_pallet_active()
+			.await,
+			Err(Error::BridgePalletIsHalted),
+		));
+	}
+
+	#[async_std::test]
+	async fn state_fails_when_pallet_is_not_initialized() {
+		assert!(matches!(target(vec![]).state().await, Err(Error::BridgePalletIsNotInitialized)));
+	}
+
+	#[async_std::test]
+	async fn state_is_read_from_pallet_storage() {
+		let info_key = storage_keys::imported_commitments_info_key(PALLET_NAME);
+		let info =
+			ImportedCommitmentsInfoData { best_block_number: 10u32, next_block_number_index: 1 };
+		let authority_set_key = storage_keys::current_authority_set_info_key(PALLET_NAME);
+
+		// right after initialization, there's no imported commitment
+		let state = target(vec![
+			(info_key.clone(), info.encode()),
+			(authority_set_key.clone(), authority_set().encode()),
+		])
+		.state()
+		.await
+		.unwrap();
+		assert_eq!(state.best_block_number, 10);
+		assert_eq!(state.best_proven_header_id, None);
+		assert_eq!(state.authority_set, authority_set());
+
+		// after commitment is imported, the pallet knows its parent
+		let state = target(vec![
+			(info_key, info.encode()),
+			(authority_set_key, authority_set().encode()),
+			(
+				storage_keys::imported_commitment_key(PALLET_NAME, &10u32),
+				ImportedCommitment {
+					parent_number_and_hash: (9u32, H256::repeat_byte(9)),
+					mmr_root: H256::repeat_byte(10),
+				}
+				.encode(),
+			),
+		])
+		.state()
+		.await
+		.unwrap();
+		assert_eq!(state.best_block_number, 10);
+		assert_eq!(state.best_proven_header_id, Some(HeaderId(9, H256::repeat_byte(9))));
+		assert_eq!(state.authority_set, authority_set());
+	}
+}
@@ -825,7 +825,7 @@ mod tests {
 		use super::super::*;
 		use bp_messages::{target_chain::ForbidInboundMessages, HashedLaneId};
 		use bp_runtime::ChainId;
-		use frame_support::derive_impl;
+		use frame_support::{derive_impl, traits::ConstU32};
 		use sp_core::H256;
 		use sp_runtime::{
 			generic, testing::Header as SubstrateHeader, traits::BlakeTwo256, StateVersion,
@@ -863,6 +863,7 @@ mod tests {
 			type DeliveryConfirmationPayments = ();
 			type OnMessagesDelivered = ();
 			type MessageDispatch = ForbidInboundMessages<Vec<u8>, Self::LaneId>;
+			type MaxFailedMessageDispatchesPerLane = ConstU32<16>;
 		}
 
 		pub struct ThisUnderlyingChain;
@@ -0,0 +1,231 @@
+// Copyright 2019-2021 Parity Technologies (UK) Ltd.
+// This file is part of Parity Bridges Common.
+
+// Parity Bridges Common is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Parity Bridges Common is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Parity Bridges Common.  If not, see <http://www.gnu.org/licenses/>.
+
+//! On-demand Substrate -> Substrate BEEFY finality relay.
+
+use crate::{
+	beefy::{
+		source::SubstrateBeefySource,
+		sync_loop::{self, BeefySyncParams},
+		target::SubstrateBeefyTarget,
+		SubmitBeefyCommitmentCallBuilder, SubstrateBeefySyncPipeline,
+	},
+	finality::source::RequiredHeaderNumberRef,
+	on_demand::OnDemandRelay,
+	TransactionParams,
+};
+
+use async_std::sync::{Arc, Mutex};
+use async_trait::async_trait;
+use finality_relay::HeadersToRelay;
+use futures::StreamExt;
+use num_traits::{One, Saturating, Zero};
+use relay_substrate_client::{
+	AccountIdOf, AccountKeyPairOf, BlockNumberOf, CallOf, Chain, Client, Error as SubstrateError,
+	HeaderIdOf,
+};
+use relay_utils::metrics::MetricsParams;
+
+/// On-demand Substrate <-> Substrate BEEFY finality relay.
+///
+/// This relay may be requested to sync more headers, whenever some other relay (e.g. messages
+/// relay) needs it to continue its regular work. When enough headers are relayed, on-demand
+/// relay only submits commitments that enact new BEEFY authority sets.
+#[derive(Clone)]
+pub struct OnDemandBeefyRelay<P: SubstrateBeefySyncPipeline, SourceClnt, TargetClnt> {
+	/// Relay task name.
+	relay_task_name: String,
+	/// Shared reference to maximal required finalized header number.
+	required_header_number: RequiredHeaderNumberRef<P::SourceChain>,
+	/// Source of BEEFY commitments.
+	source: SubstrateBeefySource<P, SourceClnt>,
+	/// Target of BEEFY commitments.
+	target: SubstrateBeefyTarget<P, TargetClnt>,
+}
+
+impl<
+		P: SubstrateBeefySyncPipeline,
+		SourceClnt: Client<P::SourceChain>,
+		TargetClnt: Client<P::TargetChain>,
+	> OnDemandBeefyRelay<P, SourceClnt, TargetClnt>
+where
+	AccountIdOf<P::TargetChain>: From<<AccountKeyPairOf<P::TargetChain> as sp_core::Pair>::Public>,
+{
+	/// Create new on-demand BEEFY relay.
+	///
+	/// If `metrics_params` is `Some(_)`, the metrics of the BEEFY relay are registered.
+	/// Otherwise, all required metrics must be exposed outside of this method.
+	pub fn new(
+		source_client: SourceClnt,
+		target_client: TargetClnt,
+		target_transaction_params: TransactionParams<AccountKeyPairOf<P::TargetChain>>,
+		headers_to_relay: HeadersToRelay,
+		metrics_params: Option<MetricsParams>,
+	) -> Self {
+		let required_header_number = Arc::new(Mutex::new(Zero::zero()));
+		let this = OnDemandBeefyRelay {
+			relay_task_name: on_demand_beefy_relay_name::<P::SourceChain, P::TargetChain>(),
+			required_header_number: required_header_number.clone(),
+			source: SubstrateBeefySource::new(source_client),
+			target: SubstrateBeefyTarget::new(target_client, target_transaction_params),
+		};
+		let (source, target, relay_task_name) =
+			(this.source.clone(), this.target.clone(), this.relay_task_name.clone());
+		async_std::task::spawn(async move {
+			let result = sync_loop::run(
+				source,
+				target,
+				BeefySyncParams {
+					tick: std::cmp::max(
+						P::SourceChain::AVERAGE_BLOCK_INTERVAL,
+						P::TargetChain::AVERAGE_BLOCK_INTERVAL,
+					),
+					headers_to_relay,
+				},
+				Some(required_header_number),
+				metrics_params.unwrap_or_else(MetricsParams::disabled),
+				futures::future::pending(),
+			)
+			.await;
+
+			if let Err(e) = result {
+				log::error!(
+					target: "bridge",
+					"[{}] BEEFY relay task has exited with error: {:?}",
+					relay_task_name,
+					e,
+				);
+			}
+		});
+
+		this
+	}
+}
+
+#[async_trait]
+impl<
+		P: SubstrateBeefySyncPipeline,
+		SourceClnt: Client<P::SourceChain>,
+		TargetClnt: Client<P::TargetChain>,
+	> OnDemandRelay<P::SourceChain, P::TargetChain> for OnDemandBeefyRelay<P, SourceClnt, TargetClnt>
+{
+	async fn reconnect(&self) -> Result<(), SubstrateError> {
+		// using clone is fine here (to avoid mut requirement), because clone on Client clones
+		// internal references
+		self.source.client().clone().reconnect().await?;
+		self.target.client().clone().reconnect().await
+	}
+
+	async fn require_more_headers(&self, required_header: BlockNumberOf<P::SourceChain>) {
+		let mut required_header_number = self.required_header_number.lock().await;
+		if required_header > *required_header_number {
+			log::trace!(
+				target: "bridge",
+				"[{}] More {} headers required. Going to sync up to the {}",
+				self.relay_task_name,
+				P::SourceChain::NAME,
+				required_header,
+			);
+
+			*required_header_number = required_header;
+		}
+	}
+
+	async fn prove_header(
+		&self,
+		required_header: BlockNumberOf<P::SourceChain>,
+	) -> Result<(HeaderIdOf<P::SourceChain>, Vec<CallOf<P::TargetChain>>), SubstrateError> {
+		let state = self.target.state().await?;
+		let set_id = state.authority_set.id;
+		let best_number_at_source = self.source.best_finalized_block_number().await?;
+
+		// the commitment proves its parent, so we need commitment for the descendant of the
+		// `required_header`
+		let is_set_enacted = self.source.validator_set_id(state.best_block_number).await? == set_id;
+		let commitment = if is_set_enacted {
+			// wait for the next commitment, signed by the set, known to the target
+			let mut commitments = self.source.signed_commitments().await?;
+			loop {
+				let commitment = commitments.next().await.ok_or_else(|| {
+					SubstrateError::Custom(format!(
+						"{} BEEFY justifications stream has ended unexpectedly",
+						P::SourceChain::NAME,
+					))
+				})?;
+				if commitment.commitment.validator_set_id != set_id {
+					return Err(SubstrateError::Custom(format!(
+						"{} BEEFY validator set has changed from {} to {} and the enacting \
+						commitment needs to be imported first",
+						P::SourceChain::NAME,
+						set_id,
+						commitment.commitment.validator_set_id,
+					)))
+				}
+				if commitment.commitment.block_number > required_header {
+					break commitment
+				}
+			}
+		} else {
+			// we must import commitment that enacts the set, known to the target
+			let scan_range =
+				(state.best_block_number.saturating_add(One::one()), best_number_at_source);
+			let enactment_block =
+				self.source.find_validator_set_enactment(set_id, scan_range).await?.ok_or_else(
+					|| {
+						SubstrateError::Custom(format!(
+							"{} BEEFY validator set {} is not enacted in the range {:?}",
+							P::SourceChain::NAME,
+							set_id,
+							scan_range,
+						))
+					},
+				)?;
+			self.source
+				.persisted_commitment(enactment_block)
+				.await?
+				.filter(|commitment| commitment.commitment.validator_set_id == set_id)
+				.ok_or_else(|| {
+					SubstrateError::Custom(format!(
+						"Missing {} BEEFY justification for block {:?}, enacting validator set {}",
+						P::SourceChain::NAME,
+						enactment_block,
+						set_id,
+					))
+				})?
+		};
+
+		let proof = self.source.prove_commitment(commitment).await?;
+		let proved_header_id = proof.proved_header_id();
+
+		log::debug!(
+			target: "bridge",
+			"[{}] Requested to prove {} head {:?}. Selected to prove {} head {:?}",
+			self.relay_task_name,
+			P::SourceChain::NAME,
+			required_header,
+			P::SourceChain::NAME,
+			proved_header_id,
+		);
+
+		let call = P::SubmitBeefyCommitmentCallBuilder::build_submit_commitment_call(proof);
+		Ok((proved_header_id, vec![call]))
+	}
+}
+
+/// On-demand BEEFY relay task name.
+fn on_demand_beefy_relay_name<SourceChain: Chain, TargetChain: Chain>() -> String {
+	format!("{}-to-{}-on-demand-beefy", SourceChain::NAME, TargetChain::NAME)
+}
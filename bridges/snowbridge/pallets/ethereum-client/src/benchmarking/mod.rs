@@ -5,6 +5,7 @@ mod util;
 
 use crate::Pallet as EthereumBeaconClient;
 use frame_benchmarking::v2::*;
+use frame_support::storage::{with_transaction, TransactionOutcome};
 use frame_system::RawOrigin;
 use hex_literal::hex;
 use snowbridge_beacon_primitives::{
@@ -65,6 +66,39 @@ mod benchmarks {
 		Ok(())
 	}
 
+	/// Each update of the chain is verified with the sync committee established by the previous
+	/// one. The fixtures only hold a single sync committee update, so every update is verified
+	/// and applied on top of the same state, which is restored after each of them.
+	#[benchmark]
+	fn submit_sync_committee_updates(
+		n: Linear<1, { config::MAX_SYNC_COMMITTEE_UPDATES }>,
+	) -> Result<(), BenchmarkError> {
+		let checkpoint_update = make_checkpoint();
+		let sync_committee_update = make_sync_committee_update();
+		EthereumBeaconClient::<T>::process_checkpoint_update(&checkpoint_update)?;
+
+		#[block]
+		{
+			assert!(!EthereumBeaconClient::<T>::operating_mode().is_halted());
+			for _ in 0..n {
+				with_transaction(|| {
+					let result = EthereumBeaconClient::<T>::verify_update_with_gap(
+						&sync_committee_update,
+						false,
+					)
+					.and_then(|_| {
+						EthereumBeaconClient::<T>::apply_update(&sync_committee_update)
+							.map(|_| ())
+							.map_err(|e| e.error)
+					});
+					TransactionOutcome::Rollback(result)
+				})?;
+			}
+		}
+
+		Ok(())
+	}
+
 	#[benchmark(extra)]
 	fn bls_fast_aggregate_verify_pre_aggregated() -> Result<(), BenchmarkError> {
 		EthereumBeaconClient::<T>::process_checkpoint_update(&make_checkpoint())?;
@@ -38,3 +38,6 @@ pub const SYNC_COMMITTEE_BITS_SIZE: usize = SYNC_COMMITTEE_SIZE / 8;
 pub const SLOTS_PER_HISTORICAL_ROOT: usize = 8192;
 /// The index of the block_roots field in the beacon state tree.
 pub const BLOCK_ROOT_AT_INDEX_DEPTH: usize = 13;
+/// Maximum number of chained sync committee updates accepted in a single submission. Allows
+/// the light client to catch up on ~9 days of missed sync committee periods.
+pub const MAX_SYNC_COMMITTEE_UPDATES: u32 = 8;
@@ -15,6 +15,8 @@
 //! ## Consensus Updates
 //!
 //! * [`Call::submit`]: Submit a finalized beacon header with an optional sync committee update
+//! * [`Call::submit_sync_committee_updates`]: Submit a chain of sync committee updates spanning
+//!   several sync committee periods, used to catch up after missed periods
 #![cfg_attr(not(feature = "std"), no_std)]
 
 pub mod config;
@@ -148,6 +150,8 @@ pub mod pallet {
 		ExecutionHeaderTooFarBehind,
 		ExecutionHeaderSkippedBlock,
 		Halted,
+		/// No sync committee updates were provided.
+		EmptySyncCommitteeUpdates,
 	}
 
 	/// Latest imported checkpoint root
@@ -228,6 +232,36 @@ pub mod pallet {
 			Self::process_update(&update)
 		}
 
+		/// Submits a chain of sync committee updates, applied in order. Each update must contain
+		/// the next sync committee and is verified with the sync committee established by the
+		/// previous one. Unlike [`Call::submit`], the gap between consecutive finalized headers
+		/// is not limited to `SLOTS_PER_HISTORICAL_ROOT`, allowing the light client to recover
+		/// from missed sync committee periods without a new checkpoint. As with
+		/// [`Call::force_checkpoint`], execution headers in a skipped gap cannot be proven with
+		/// ancestry proofs.
+		#[pallet::call_index(4)]
+		#[pallet::weight(T::WeightInfo::submit_sync_committee_updates(updates.len() as u32))]
+		#[transactional]
+		pub fn submit_sync_committee_updates(
+			origin: OriginFor<T>,
+			updates: BoundedVec<Update, ConstU32<{ config::MAX_SYNC_COMMITTEE_UPDATES }>>,
+		) -> DispatchResultWithPostInfo {
+			ensure_signed(origin)?;
+			ensure!(!Self::operating_mode().is_halted(), Error::<T>::Halted);
+			ensure!(!updates.is_empty(), Error::<T>::EmptySyncCommitteeUpdates);
+
+			for update in updates.iter() {
+				ensure!(
+					update.next_sync_committee_update.is_some(),
+					Error::<T>::SyncCommitteeUpdateRequired
+				);
+				Self::verify_update_with_gap(update, false)?;
+				Self::apply_update(update)?;
+			}
+
+			Ok(().into())
+		}
+
 		/// Halt or resume all pallet operations. May only be called by root.
 		#[pallet::call_index(3)]
 		#[pallet::weight((T::DbWeight::get().reads_writes(1, 1), DispatchClass::Operational))]
@@ -309,11 +343,20 @@ pub mod pallet {
 			Self::apply_update(update)
 		}
 
+		fn verify_update(update: &Update) -> DispatchResult {
+			Self::verify_update_with_gap(update, true)
+		}
+
 		/// References and strictly follows <https://github.com/ethereum/consensus-specs/blob/dev/specs/altair/light-client/sync-protocol.md#validate_light_client_update>
 		/// Verifies that provided next sync committee is valid through a series of checks
 		/// (including checking that a sync committee period isn't skipped and that the header is
-		/// signed by the current sync committee.
-		fn verify_update(update: &Update) -> DispatchResult {
+		/// signed by the current sync committee. The finalized header gap check is skipped when
+		/// `enforce_finalized_header_gap` is false, which is only the case when catching up on
+		/// missed sync committee periods.
+		pub(crate) fn verify_update_with_gap(
+			update: &Update,
+			enforce_finalized_header_gap: bool,
+		) -> DispatchResult {
 			// Verify sync committee has sufficient participants.
 			let participation =
 				decompress_sync_committee_bits(update.sync_aggregate.sync_committee_bits);
@@ -356,10 +399,11 @@ pub mod pallet {
 			// header is not larger than the sync committee period, otherwise we cannot do
 			// ancestry proofs for execution headers in the gap.
 			ensure!(
-				latest_finalized_state
-					.slot
-					.saturating_add(config::SLOTS_PER_HISTORICAL_ROOT as u64) >=
-					update.finalized_header.slot,
+				!enforce_finalized_header_gap ||
+					latest_finalized_state
+						.slot
+						.saturating_add(config::SLOTS_PER_HISTORICAL_ROOT as u64) >=
+						update.finalized_header.slot,
 				Error::<T>::InvalidFinalizedHeaderGap
 			);
 
@@ -470,7 +514,7 @@ pub mod pallet {
 		/// is present in the update, verify the sync committee by converting it to a
 		/// SyncCommitteePrepared type. Stores the provided finalized header. Updates are free
 		/// if the certain conditions specified in `check_refundable` are met.
-		fn apply_update(update: &Update) -> DispatchResultWithPostInfo {
+		pub(crate) fn apply_update(update: &Update) -> DispatchResultWithPostInfo {
 			let latest_finalized_state =
 				FinalizedBeaconState::<T>::get(LatestFinalizedBlockRoot::<T>::get())
 					.ok_or(Error::<T>::NotBootstrapped)?;
@@ -822,6 +822,80 @@ fn verify_message_receipt_does_not_contain_log() {
 	});
 }
 
+#[test]
+fn submit_sync_committee_updates_across_periods() {
+	let checkpoint = Box::new(load_checkpoint_update_fixture());
+	let update = load_sync_committee_update_fixture();
+	let next_update = load_next_sync_committee_update_fixture();
+	let next_update_period = compute_period(next_update.finalized_header.slot);
+
+	new_tester().execute_with(|| {
+		assert_ok!(EthereumBeaconClient::process_checkpoint_update(&checkpoint));
+		assert_ok!(EthereumBeaconClient::submit_sync_committee_updates(
+			RuntimeOrigin::signed(1),
+			vec![update, next_update].try_into().unwrap()
+		));
+
+		assert!(<NextSyncCommittee<Test>>::exists());
+		assert_eq!(<LatestSyncCommitteeUpdatePeriod<Test>>::get(), next_update_period);
+		let last_finalized_state =
+			FinalizedBeaconState::<Test>::get(LatestFinalizedBlockRoot::<Test>::get()).unwrap();
+		assert_eq!(compute_period(last_finalized_state.slot), next_update_period);
+	});
+}
+
+/// Check that the finalized header gap is not enforced when catching up on sync committee
+/// periods.
+#[test]
+fn submit_sync_committee_updates_with_large_gap() {
+	let checkpoint = Box::new(load_checkpoint_update_fixture());
+	let update = load_sync_committee_update_fixture();
+	let mut next_update = load_next_sync_committee_update_fixture();
+
+	let slot_with_large_gap = checkpoint.header.slot + SLOTS_PER_HISTORICAL_ROOT as u64 + 1;
+	next_update.finalized_header.slot = slot_with_large_gap;
+	next_update.attested_header.slot = slot_with_large_gap + 33;
+	next_update.signature_slot = slot_with_large_gap + 43;
+
+	new_tester().execute_with(|| {
+		assert_ok!(EthereumBeaconClient::process_checkpoint_update(&checkpoint));
+		assert_noop!(
+			EthereumBeaconClient::submit_sync_committee_updates(
+				RuntimeOrigin::signed(1),
+				vec![update, next_update].try_into().unwrap()
+			),
+			// Passes the InvalidFinalizedHeaderGap check and fails on the merkle proof, because
+			// the next_update slots were changed.
+			Error::<Test>::InvalidHeaderMerkleProof
+		);
+	});
+}
+
+#[test]
+fn submit_sync_committee_updates_without_sync_committee() {
+	let checkpoint = Box::new(load_checkpoint_update_fixture());
+	let update = load_sync_committee_update_fixture();
+	let finalized_update = load_finalized_header_update_fixture();
+
+	new_tester().execute_with(|| {
+		assert_ok!(EthereumBeaconClient::process_checkpoint_update(&checkpoint));
+		assert_noop!(
+			EthereumBeaconClient::submit_sync_committee_updates(
+				RuntimeOrigin::signed(1),
+				vec![update, finalized_update].try_into().unwrap()
+			),
+			Error::<Test>::SyncCommitteeUpdateRequired
+		);
+		assert_noop!(
+			EthereumBeaconClient::submit_sync_committee_updates(
+				RuntimeOrigin::signed(1),
+				Default::default()
+			),
+			Error::<Test>::EmptySyncCommitteeUpdates
+		);
+	});
+}
+
 #[test]
 fn set_operating_mode() {
 	let checkpoint = Box::new(load_checkpoint_update_fixture());
@@ -839,6 +913,13 @@ fn set_operating_mode() {
 			EthereumBeaconClient::submit(RuntimeOrigin::signed(1), update),
 			Error::<Test>::Halted
 		);
+		assert_noop!(
+			EthereumBeaconClient::submit_sync_committee_updates(
+				RuntimeOrigin::signed(1),
+				vec![load_sync_committee_update_fixture()].try_into().unwrap()
+			),
+			Error::<Test>::Halted
+		);
 	});
 }
 
@@ -36,6 +36,7 @@ pub trait WeightInfo {
 	fn force_checkpoint() -> Weight;
 	fn submit() -> Weight;
 	fn submit_with_sync_committee() -> Weight;
+	fn submit_sync_committee_updates(n: u32) -> Weight;
 }
 
 // For backwards compatibility and tests
@@ -55,7 +56,17 @@ impl WeightInfo for () {
 	fn submit_with_sync_committee() -> Weight {
 		Weight::from_parts(122_461_312_000_u64, 0)
 			.saturating_add(Weight::from_parts(0, 93857))
-			.saturating_add(RocksDbWeight::get().reads(6))
-			.saturating_add(RocksDbWeight::get().writes(1))
+			.saturating_add(RocksDbWeight::get().reads(7))
+			.saturating_add(RocksDbWeight::get().writes(2))
+	}
+	fn submit_sync_committee_updates(n: u32) -> Weight {
+		// Each update is weighed as a `submit_with_sync_committee`, with the `OperatingMode` read
+		// once for all of them.
+		Weight::from_parts(122_461_312_000_u64, 0)
+			.saturating_mul(n.into())
+			.saturating_add(Weight::from_parts(0, 93857))
+			.saturating_add(RocksDbWeight::get().reads(1))
+			.saturating_add(RocksDbWeight::get().reads((6_u64).saturating_mul(n.into())))
+			.saturating_add(RocksDbWeight::get().writes((2_u64).saturating_mul(n.into())))
 	}
 }
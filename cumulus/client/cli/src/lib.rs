@@ -316,6 +316,14 @@ pub struct RunCmd {
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	#[arg(long)]
 	pub experimental_max_pov_percentage: Option<u32>,
+
+	/// Keep the available data (PoV and persisted validation data) of the blocks collated or
+	/// recovered by this node for the given number of finalized blocks, and serve it to other
+	/// parachain nodes that can not recover these blocks from the relay chain.
+	///
+	/// Disabled by default. E.g. `100800` keeps the data for about a week with 6 second blocks.
+	#[arg(long, value_name = "BLOCKS")]
+	pub serve_available_data: Option<u32>,
 }
 
 impl RunCmd {
@@ -118,18 +118,23 @@ where
 
 	/// Explicitly creates the inherent data for parachain block authoring and overrides
 	/// the timestamp inherent data with the one provided, if any.
+	///
+	/// The `relay_storage_keys` are proven in the relay chain state proof in addition to the keys
+	/// the parachain system pallet reads.
 	pub async fn create_inherent_data(
 		&self,
 		relay_parent: PHash,
 		validation_data: &PersistedValidationData,
 		parent_hash: Block::Hash,
 		timestamp: impl Into<Option<Timestamp>>,
+		relay_storage_keys: Vec<Vec<u8>>,
 	) -> Result<(ParachainInherentData, InherentData), Box<dyn Error + Send + Sync + 'static>> {
 		let paras_inherent_data = ParachainInherentDataProvider::create_at(
 			relay_parent,
 			&self.relay_client,
 			validation_data,
 			self.para_id,
+			relay_storage_keys,
 		)
 		.await;
 
@@ -29,7 +29,9 @@ use cumulus_client_collator::{
 };
 use cumulus_client_consensus_common::ParachainBlockImportMarker;
 use cumulus_client_consensus_proposer::ProposerInterface;
-use cumulus_primitives_core::{relay_chain::BlockId as RBlockId, CollectCollationInfo};
+use cumulus_primitives_core::{
+	relay_chain::BlockId as RBlockId, CollectCollationInfo, RelayStorageKeysApi,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_node_primitives::CollationResult;
@@ -101,7 +103,8 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	Client::Api: AuraApi<Block, P::Public> + CollectCollationInfo<Block>,
+	Client::Api:
+		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + RelayStorageKeysApi<Block>,
 	RClient: RelayChainInterface + Send + Clone + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + Send + 'static,
 	CIDP::InherentDataProviders: Send,
@@ -234,6 +237,7 @@ where
 						&validation_data,
 						parent_hash,
 						claim.timestamp(),
+						super::relay_storage_keys(&*params.para_client, parent_hash),
 					)
 					.await
 			);
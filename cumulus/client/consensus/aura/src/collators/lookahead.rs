@@ -36,8 +36,11 @@ use codec::{Codec, Encode};
 use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
 use cumulus_client_consensus_common::{self as consensus_common, ParachainBlockImportMarker};
 use cumulus_client_consensus_proposer::ProposerInterface;
+use cumulus_client_pov_recovery::AvailableDataStore;
 use cumulus_primitives_aura::AuraUnincludedSegmentApi;
-use cumulus_primitives_core::{ClaimQueueOffset, CollectCollationInfo, PersistedValidationData};
+use cumulus_primitives_core::{
+	ClaimQueueOffset, CollectCollationInfo, PersistedValidationData, RelayStorageKeysApi,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_node_primitives::SubmitCollationParams;
@@ -114,8 +117,10 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	Client::Api:
-		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + AuraUnincludedSegmentApi<Block>,
+	Client::Api: AuraApi<Block, P::Public>
+		+ CollectCollationInfo<Block>
+		+ AuraUnincludedSegmentApi<Block>
+		+ RelayStorageKeysApi<Block>,
 	Backend: sc_client_api::Backend<Block> + 'static,
 	RClient: RelayChainInterface + Clone + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
@@ -128,16 +133,24 @@ where
 	P::Public: AppPublic + Member + Codec,
 	P::Signature: TryFrom<Vec<u8>> + Member + Codec,
 {
-	run_with_export::<_, P, _, _, _, _, _, _, _, _>(ParamsWithExport { params, export_pov: None })
+	run_with_export::<_, P, _, _, _, _, _, _, _, _>(ParamsWithExport {
+		params,
+		export_pov: None,
+		available_data_store: None,
+	})
 }
 
 /// Parameters for [`run_with_export`].
-pub struct ParamsWithExport<BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS> {
+pub struct ParamsWithExport<Block: BlockT, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS> {
 	/// The parameters.
 	pub params: Params<BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS>,
 
 	/// When set, the collator will export every produced `POV` to this folder.
 	pub export_pov: Option<PathBuf>,
+
+	/// When set, the collator keeps the available data of every produced block in this store, to
+	/// serve it to other parachain nodes.
+	pub available_data_store: Option<AvailableDataStore<Block>>,
 }
 
 /// Run async-backing-friendly Aura.
@@ -145,7 +158,8 @@ pub struct ParamsWithExport<BI, CIDP, Client, Backend, RClient, CHP, Proposer, C
 /// This is exactly the same as [`run`], but it supports the optional export of each produced `POV`
 /// to the file system.
 pub fn run_with_export<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS>(
-	ParamsWithExport { mut params, export_pov }: ParamsWithExport<
+	ParamsWithExport { mut params, export_pov, available_data_store }: ParamsWithExport<
+		Block,
 		BI,
 		CIDP,
 		Client,
@@ -166,8 +180,10 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	Client::Api:
-		AuraApi<Block, P::Public> + CollectCollationInfo<Block> + AuraUnincludedSegmentApi<Block>,
+	Client::Api: AuraApi<Block, P::Public>
+		+ CollectCollationInfo<Block>
+		+ AuraUnincludedSegmentApi<Block>
+		+ RelayStorageKeysApi<Block>,
 	Backend: sc_client_api::Backend<Block> + 'static,
 	RClient: RelayChainInterface + Clone + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
@@ -353,6 +369,7 @@ where
 						&validation_data,
 						parent_hash,
 						slot_claim.timestamp(),
+						super::relay_storage_keys(para_client, parent_hash),
 					)
 					.await
 				{
@@ -404,6 +421,16 @@ where
 						// and provides sybil-resistance, as it should.
 						collator.collator_service().announce_block(new_block_hash, None);
 
+						if let Some(ref available_data_store) = available_data_store {
+							available_data_store.keep(
+								*block_data.header().number(),
+								new_block_hash,
+								relay_parent,
+								collation.proof_of_validity.clone(),
+								validation_data.clone(),
+							);
+						}
+
 						if let Some(ref export_pov) = export_pov {
 							export_pov_to_path::<Block>(
 								export_pov.clone(),
@@ -25,7 +25,9 @@ use crate::collator::SlotClaim;
 use codec::Codec;
 use cumulus_client_consensus_common::{self as consensus_common, ParentSearchParams};
 use cumulus_primitives_aura::{AuraUnincludedSegmentApi, Slot};
-use cumulus_primitives_core::{relay_chain::Hash as ParaHash, BlockT, ClaimQueueOffset};
+use cumulus_primitives_core::{
+	relay_chain::Hash as ParaHash, BlockT, ClaimQueueOffset, RelayStorageKeysApi,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 use polkadot_node_subsystem::messages::RuntimeApiRequest;
 use polkadot_node_subsystem_util::runtime::ClaimQueueSnapshot;
@@ -219,6 +221,42 @@ where
 		.then(|| SlotClaim::unchecked::<P>(author_pub, para_slot, timestamp))
 }
 
+/// Fetch the relay chain storage keys the runtime at `parent_hash` requests to be proven in the
+/// parachain inherent.
+///
+/// Returns no keys if the runtime doesn't implement the [`RelayStorageKeysApi`] or calling it
+/// fails, in which case the block is built with the default relay chain state proof.
+fn relay_storage_keys<Block: BlockT, Client>(
+	client: &Client,
+	parent_hash: Block::Hash,
+) -> Vec<Vec<u8>>
+where
+	Client: ProvideRuntimeApi<Block>,
+	Client::Api: RelayStorageKeysApi<Block>,
+{
+	let runtime_api = client.runtime_api();
+	let keys =
+		runtime_api
+			.has_api::<dyn RelayStorageKeysApi<Block>>(parent_hash)
+			.and_then(|has_api| {
+				if has_api {
+					runtime_api.relay_storage_keys(parent_hash)
+				} else {
+					Ok(Vec::new())
+				}
+			});
+
+	keys.unwrap_or_else(|error| {
+		tracing::warn!(
+			target: crate::LOG_TARGET,
+			?parent_hash,
+			%error,
+			"Failed to fetch the relay chain storage keys requested by the runtime",
+		);
+		Vec::new()
+	})
+}
+
 /// Use [`cumulus_client_consensus_common::find_potential_parents`] to find parachain blocks that
 /// we can build on. Once a list of potential parents is retrieved, return the last one of the
 /// longest chain.
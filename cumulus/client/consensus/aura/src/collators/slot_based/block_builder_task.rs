@@ -21,7 +21,7 @@ use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterfa
 use cumulus_client_consensus_common::{self as consensus_common, ParachainBlockImportMarker};
 use cumulus_client_consensus_proposer::ProposerInterface;
 use cumulus_primitives_aura::AuraUnincludedSegmentApi;
-use cumulus_primitives_core::{GetCoreSelectorApi, PersistedValidationData};
+use cumulus_primitives_core::{GetCoreSelectorApi, PersistedValidationData, RelayStorageKeysApi};
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_primitives::{Block as RelayBlock, Id as ParaId};
@@ -120,8 +120,10 @@ where
 		+ Send
 		+ Sync
 		+ 'static,
-	Client::Api:
-		AuraApi<Block, P::Public> + GetCoreSelectorApi<Block> + AuraUnincludedSegmentApi<Block>,
+	Client::Api: AuraApi<Block, P::Public>
+		+ GetCoreSelectorApi<Block>
+		+ AuraUnincludedSegmentApi<Block>
+		+ RelayStorageKeysApi<Block>,
 	Backend: sc_client_api::Backend<Block> + 'static,
 	RelayClient: RelayChainInterface + Clone + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
@@ -317,6 +319,7 @@ where
 					&validation_data,
 					parent_hash,
 					slot_claim.timestamp(),
+					crate::collators::relay_storage_keys(&*para_client, parent_hash),
 				)
 				.await
 			{
@@ -379,7 +382,7 @@ where
 				parachain_candidate: candidate,
 				validation_code_hash,
 				core_index: *core_index,
-				max_pov_size: validation_data.max_pov_size,
+				validation_data,
 			}) {
 				tracing::error!(target: crate::LOG_TARGET, ?err, "Unable to send block to collation task.");
 				return
@@ -19,6 +19,7 @@ use codec::Encode;
 use std::path::PathBuf;
 
 use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
+use cumulus_client_pov_recovery::AvailableDataStore;
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 use polkadot_node_primitives::{MaybeCompressedPoV, SubmitCollationParams};
@@ -55,6 +56,8 @@ pub struct Params<Block: BlockT, RClient, CS> {
 	pub block_import_handle: super::SlotBasedBlockImportHandle<Block>,
 	/// When set, the collator will export every produced `POV` to this folder.
 	pub export_pov: Option<PathBuf>,
+	/// When set, the collator keeps the available data of every produced block in this store.
+	pub available_data_store: Option<AvailableDataStore<Block>>,
 }
 
 /// Asynchronously executes the collation task for a parachain.
@@ -73,6 +76,7 @@ pub async fn run_collation_task<Block, RClient, CS>(
 		mut collator_receiver,
 		mut block_import_handle,
 		export_pov,
+		available_data_store,
 	}: Params<Block, RClient, CS>,
 ) where
 	Block: BlockT,
@@ -99,7 +103,7 @@ pub async fn run_collation_task<Block, RClient, CS>(
 					return;
 				};
 
-				handle_collation_message(message, &collator_service, &mut overseer_handle,relay_client.clone(),export_pov.clone()).await;
+				handle_collation_message(message, available_data_store.as_ref(), &collator_service, &mut overseer_handle,relay_client.clone(),export_pov.clone()).await;
 			},
 			block_import_msg = block_import_handle.next().fuse() => {
 				// TODO: Implement me.
@@ -115,6 +119,7 @@ pub async fn run_collation_task<Block, RClient, CS>(
 /// the collation-generation subsystem of the relay chain.
 async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface + Clone + 'static>(
 	message: CollatorMessage<Block>,
+	available_data_store: Option<&AvailableDataStore<Block>>,
 	collator_service: &impl CollatorServiceInterface<Block>,
 	overseer_handle: &mut OverseerHandle,
 	relay_client: RClient,
@@ -126,7 +131,7 @@ async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface +
 		validation_code_hash,
 		relay_parent,
 		core_index,
-		max_pov_size,
+		validation_data,
 	} = message;
 
 	let hash = parachain_candidate.block.header().hash();
@@ -148,6 +153,16 @@ async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface +
 		block_data.storage_proof().encoded_size() as f64 / 1024f64,
 	);
 
+	if let Some(available_data_store) = available_data_store {
+		available_data_store.keep(
+			number,
+			hash,
+			relay_parent,
+			collation.proof_of_validity.clone(),
+			validation_data.clone(),
+		);
+	}
+
 	if let MaybeCompressedPoV::Compressed(ref pov) = collation.proof_of_validity {
 		if let Some(pov_path) = export_pov {
 			if let Ok(Some(relay_parent_header)) =
@@ -161,7 +176,7 @@ async fn handle_collation_message<Block: BlockT, RClient: RelayChainInterface +
 					parent_header.clone(),
 					relay_parent_header.state_root,
 					relay_parent_header.number,
-					max_pov_size,
+					validation_data.max_pov_size,
 				);
 			} else {
 				tracing::error!(target: LOG_TARGET, "Failed to get relay parent header from hash: {relay_parent:?}");
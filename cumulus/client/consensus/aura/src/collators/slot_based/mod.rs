@@ -64,7 +64,8 @@
 //! functions:
 //!
 //! 1. Block compression
-//! 2. Submission to the collation-generation subsystem
+//! 2. Keeping the available data of the block, if configured, to serve it to other parachain nodes
+//! 3. Submission to the collation-generation subsystem
 
 use self::{block_builder_task::run_block_builder, collation_task::run_collation_task};
 use codec::Codec;
@@ -72,8 +73,12 @@ use consensus_common::ParachainCandidate;
 use cumulus_client_collator::service::ServiceInterface as CollatorServiceInterface;
 use cumulus_client_consensus_common::{self as consensus_common, ParachainBlockImportMarker};
 use cumulus_client_consensus_proposer::ProposerInterface;
+use cumulus_client_pov_recovery::AvailableDataStore;
 use cumulus_primitives_aura::AuraUnincludedSegmentApi;
-use cumulus_primitives_core::{ClaimQueueOffset, CoreSelector, GetCoreSelectorApi};
+use cumulus_primitives_core::{
+	ClaimQueueOffset, CoreSelector, GetCoreSelectorApi, PersistedValidationData,
+	RelayStorageKeysApi,
+};
 use cumulus_relay_chain_interface::RelayChainInterface;
 use futures::FutureExt;
 use polkadot_primitives::{
@@ -146,6 +151,9 @@ pub struct Params<Block, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS,
 	/// The maximum percentage of the maximum PoV size that the collator can use.
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	pub max_pov_percentage: Option<u32>,
+	/// When set, the collator keeps the available data of every produced block in this store, to
+	/// serve it to other parachain nodes.
+	pub available_data_store: Option<AvailableDataStore<Block>>,
 }
 
 /// Run aura-based block building and collation task.
@@ -162,8 +170,10 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		+ Send
 		+ Sync
 		+ 'static,
-	Client::Api:
-		AuraApi<Block, P::Public> + GetCoreSelectorApi<Block> + AuraUnincludedSegmentApi<Block>,
+	Client::Api: AuraApi<Block, P::Public>
+		+ GetCoreSelectorApi<Block>
+		+ AuraUnincludedSegmentApi<Block>
+		+ RelayStorageKeysApi<Block>,
 	Backend: sc_client_api::Backend<Block> + 'static,
 	RClient: RelayChainInterface + Clone + 'static,
 	CIDP: CreateInherentDataProviders<Block, ()> + 'static,
@@ -197,6 +207,7 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		export_pov,
 		relay_chain_slot_duration,
 		max_pov_percentage,
+		available_data_store,
 	} = params;
 
 	let (tx, rx) = tracing_unbounded("mpsc_builder_to_collator", 100);
@@ -209,6 +220,7 @@ pub fn run<Block, P, BI, CIDP, Client, Backend, RClient, CHP, Proposer, CS, Spaw
 		collator_receiver: rx,
 		block_import_handle,
 		export_pov,
+		available_data_store,
 	};
 
 	let collation_task_fut = run_collation_task::<Block, _, _>(collator_task_params);
@@ -260,8 +272,9 @@ struct CollatorMessage<Block: BlockT> {
 	pub validation_code_hash: ValidationCodeHash,
 	/// Core index that this block should be submitted on
 	pub core_index: CoreIndex,
-	/// Maximum pov size. Currently needed only for exporting PoV.
-	pub max_pov_size: u32,
+	/// The persisted validation data of the block, kept alongside its PoV and used for
+	/// exporting it.
+	pub validation_data: PersistedValidationData,
 }
 
 /// Fetch the `CoreSelector` and `ClaimQueueOffset` for `parent_hash`.
@@ -24,18 +24,37 @@ use cumulus_primitives_core::{
 use cumulus_relay_chain_interface::RelayChainInterface;
 
 mod mock;
+#[cfg(test)]
+mod tests;
 
 pub use cumulus_primitives_parachain_inherent::{ParachainInherentData, INHERENT_IDENTIFIER};
 pub use mock::{MockValidationDataInherentDataProvider, MockXcmConfig};
 
 const LOG_TARGET: &str = "parachain-inherent";
 
+/// The maximum number of additional relay chain storage keys that are put into the relay chain
+/// state proof.
+///
+/// Keys beyond this number are ignored.
+pub const MAX_ADDITIONAL_RELAY_KEYS: usize = 128;
+
+/// The maximum length of an additional relay chain storage key that is put into the relay chain
+/// state proof.
+///
+/// Longer keys are ignored.
+pub const MAX_ADDITIONAL_RELAY_KEY_LEN: usize = 256;
+
 /// Collect the relevant relay chain state in form of a proof for putting it into the validation
 /// data inherent.
+///
+/// Besides the keys read by the parachain system pallet, the proof contains the
+/// `additional_relay_keys` requested by the runtime, bounded by [`MAX_ADDITIONAL_RELAY_KEYS`] and
+/// [`MAX_ADDITIONAL_RELAY_KEY_LEN`].
 async fn collect_relay_storage_proof(
 	relay_chain_interface: &impl RelayChainInterface,
 	para_id: ParaId,
 	relay_parent: PHash,
+	additional_relay_keys: Vec<Vec<u8>>,
 ) -> Option<sp_state_machine::StorageProof> {
 	use relay_chain::well_known_keys as relay_well_known_keys;
 
@@ -121,6 +140,7 @@ async fn collect_relay_storage_proof(
 	relevant_keys.extend(egress_channels.into_iter().map(|recipient| {
 		relay_well_known_keys::hrmp_channels(HrmpChannelId { sender: para_id, recipient })
 	}));
+	relevant_keys.extend(bound_additional_relay_keys(additional_relay_keys));
 
 	relay_chain_interface
 		.prove_read(relay_parent, &relevant_keys)
@@ -136,20 +156,62 @@ async fn collect_relay_storage_proof(
 		.ok()
 }
 
+/// Drop the additional relay chain storage keys that are too long and the keys beyond
+/// [`MAX_ADDITIONAL_RELAY_KEYS`].
+fn bound_additional_relay_keys(additional_relay_keys: Vec<Vec<u8>>) -> Vec<Vec<u8>> {
+	let n_keys = additional_relay_keys.len();
+	let mut keys = additional_relay_keys
+		.into_iter()
+		.filter(|key| key.len() <= MAX_ADDITIONAL_RELAY_KEY_LEN)
+		.collect::<Vec<_>>();
+
+	if keys.len() < n_keys {
+		tracing::warn!(
+			target: LOG_TARGET,
+			dropped = n_keys - keys.len(),
+			max_len = MAX_ADDITIONAL_RELAY_KEY_LEN,
+			"Ignoring additional relay chain storage keys that are too long.",
+		);
+	}
+
+	if keys.len() > MAX_ADDITIONAL_RELAY_KEYS {
+		tracing::warn!(
+			target: LOG_TARGET,
+			dropped = keys.len() - MAX_ADDITIONAL_RELAY_KEYS,
+			max_keys = MAX_ADDITIONAL_RELAY_KEYS,
+			"Ignoring additional relay chain storage keys beyond the maximum number of keys.",
+		);
+		keys.truncate(MAX_ADDITIONAL_RELAY_KEYS);
+	}
+
+	keys
+}
+
 pub struct ParachainInherentDataProvider;
 
 impl ParachainInherentDataProvider {
 	/// Create the [`ParachainInherentData`] at the given `relay_parent`.
 	///
+	/// The relay chain state proof contains the `additional_relay_keys`, which are usually the
+	/// keys returned by the `RelayStorageKeysApi` of the runtime. At most
+	/// [`MAX_ADDITIONAL_RELAY_KEYS`] keys of at most [`MAX_ADDITIONAL_RELAY_KEY_LEN`] bytes are put
+	/// into the proof, the others are ignored.
+	///
 	/// Returns `None` if the creation failed.
 	pub async fn create_at(
 		relay_parent: PHash,
 		relay_chain_interface: &impl RelayChainInterface,
 		validation_data: &PersistedValidationData,
 		para_id: ParaId,
+		additional_relay_keys: Vec<Vec<u8>>,
 	) -> Option<ParachainInherentData> {
-		let relay_chain_state =
-			collect_relay_storage_proof(relay_chain_interface, para_id, relay_parent).await?;
+		let relay_chain_state = collect_relay_storage_proof(
+			relay_chain_interface,
+			para_id,
+			relay_parent,
+			additional_relay_keys,
+		)
+		.await?;
 
 		let downward_messages = relay_chain_interface
 			.retrieve_dmq_contents(para_id, relay_parent)
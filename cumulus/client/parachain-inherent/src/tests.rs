@@ -0,0 +1,272 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+use crate::*;
+
+use async_trait::async_trait;
+use cumulus_primitives_core::relay_chain::BlockId;
+use cumulus_relay_chain_interface::{
+	BlockNumber, CommittedCandidateReceipt, CoreIndex, CoreState, InboundDownwardMessage,
+	InboundHrmpMessage, OccupiedCoreAssumption, OverseerHandle, PHeader, RelayChainError,
+	RelayChainResult, SessionIndex, StorageValue, ValidationCodeHash, ValidatorId,
+};
+use futures::{executor::block_on, Stream};
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::{InMemoryBackend, StorageProof};
+use sp_version::RuntimeVersion;
+use std::{
+	collections::{BTreeMap, VecDeque},
+	pin::Pin,
+	sync::Mutex,
+};
+
+/// A relay chain that proves reads of its in-memory state and records the proven keys.
+struct Relaychain {
+	backend: InMemoryBackend<BlakeTwo256>,
+	proven_keys: Mutex<Vec<Vec<u8>>>,
+}
+
+impl Relaychain {
+	fn new(state: BTreeMap<Vec<u8>, Vec<u8>>) -> Self {
+		Self {
+			backend: InMemoryBackend::from((state, Default::default())),
+			proven_keys: Default::default(),
+		}
+	}
+
+	fn state_root(&self) -> PHash {
+		*self.backend.root()
+	}
+
+	fn proven_keys(&self) -> Vec<Vec<u8>> {
+		self.proven_keys.lock().unwrap().clone()
+	}
+}
+
+#[async_trait]
+impl RelayChainInterface for Relaychain {
+	async fn validators(&self, _: PHash) -> RelayChainResult<Vec<ValidatorId>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn best_block_hash(&self) -> RelayChainResult<PHash> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn finalized_block_hash(&self) -> RelayChainResult<PHash> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn call_runtime_api(
+		&self,
+		_: &'static str,
+		_: PHash,
+		_: &[u8],
+	) -> RelayChainResult<Vec<u8>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn retrieve_dmq_contents(
+		&self,
+		_: ParaId,
+		_: PHash,
+	) -> RelayChainResult<Vec<InboundDownwardMessage>> {
+		Ok(Vec::new())
+	}
+
+	async fn retrieve_all_inbound_hrmp_channel_contents(
+		&self,
+		_: ParaId,
+		_: PHash,
+	) -> RelayChainResult<BTreeMap<ParaId, Vec<InboundHrmpMessage>>> {
+		Ok(BTreeMap::new())
+	}
+
+	async fn persisted_validation_data(
+		&self,
+		_: PHash,
+		_: ParaId,
+		_: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<PersistedValidationData>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn validation_code_hash(
+		&self,
+		_: PHash,
+		_: ParaId,
+		_: OccupiedCoreAssumption,
+	) -> RelayChainResult<Option<ValidationCodeHash>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn candidate_pending_availability(
+		&self,
+		_: PHash,
+		_: ParaId,
+	) -> RelayChainResult<Option<CommittedCandidateReceipt>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn candidates_pending_availability(
+		&self,
+		_: PHash,
+		_: ParaId,
+	) -> RelayChainResult<Vec<CommittedCandidateReceipt>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn session_index_for_child(&self, _: PHash) -> RelayChainResult<SessionIndex> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn import_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn finality_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn is_major_syncing(&self) -> RelayChainResult<bool> {
+		unimplemented!("Not needed for test")
+	}
+
+	fn overseer_handle(&self) -> RelayChainResult<OverseerHandle> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn get_storage_by_key(
+		&self,
+		_: PHash,
+		key: &[u8],
+	) -> RelayChainResult<Option<StorageValue>> {
+		use sp_state_machine::Backend as _;
+
+		self.backend
+			.storage(key)
+			.map_err(|e| RelayChainError::GenericError(e.to_string()))
+	}
+
+	async fn prove_read(
+		&self,
+		_: PHash,
+		relevant_keys: &Vec<Vec<u8>>,
+	) -> RelayChainResult<StorageProof> {
+		self.proven_keys.lock().unwrap().extend(relevant_keys.iter().cloned());
+
+		sp_state_machine::prove_read(self.backend.clone(), relevant_keys)
+			.map_err(|e| RelayChainError::GenericError(e.to_string()))
+	}
+
+	async fn wait_for_block(&self, _: PHash) -> RelayChainResult<()> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn new_best_notification_stream(
+		&self,
+	) -> RelayChainResult<Pin<Box<dyn Stream<Item = PHeader> + Send>>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn header(&self, _: BlockId) -> RelayChainResult<Option<PHeader>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn availability_cores(
+		&self,
+		_: PHash,
+	) -> RelayChainResult<Vec<CoreState<PHash, BlockNumber>>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn claim_queue(
+		&self,
+		_: PHash,
+	) -> RelayChainResult<BTreeMap<CoreIndex, VecDeque<ParaId>>> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn scheduling_lookahead(&self, _: PHash) -> RelayChainResult<u32> {
+		unimplemented!("Not needed for test")
+	}
+
+	async fn version(&self, _: PHash) -> RelayChainResult<RuntimeVersion> {
+		unimplemented!("Not needed for test")
+	}
+}
+
+fn create_inherent_data(
+	relay_chain: &Relaychain,
+	additional_relay_keys: Vec<Vec<u8>>,
+) -> ParachainInherentData {
+	block_on(ParachainInherentDataProvider::create_at(
+		PHash::default(),
+		relay_chain,
+		&Default::default(),
+		ParaId::from(100),
+		additional_relay_keys,
+	))
+	.expect("creates the inherent data")
+}
+
+#[test]
+fn additional_relay_keys_are_in_relay_chain_state_proof() {
+	let state = BTreeMap::from([
+		(b"key1".to_vec(), b"value1".to_vec()),
+		(b"key2".to_vec(), b"value2".to_vec()),
+		(b"key3".to_vec(), b"value3".to_vec()),
+	]);
+	let relay_chain = Relaychain::new(state);
+
+	let inherent_data =
+		create_inherent_data(&relay_chain, vec![b"key1".to_vec(), b"key3".to_vec()]);
+
+	let values = sp_state_machine::read_proof_check::<BlakeTwo256, _>(
+		relay_chain.state_root(),
+		inherent_data.relay_chain_state,
+		[b"key1", b"key3"],
+	)
+	.expect("the requested keys are proven");
+	assert_eq!(values[&b"key1".to_vec()], Some(b"value1".to_vec()));
+	assert_eq!(values[&b"key3".to_vec()], Some(b"value3".to_vec()));
+
+	let proven_keys = relay_chain.proven_keys();
+	assert!(proven_keys.contains(&b"key1".to_vec()));
+	assert!(proven_keys.contains(&b"key3".to_vec()));
+	assert!(!proven_keys.contains(&b"key2".to_vec()));
+}
+
+#[test]
+fn additional_relay_keys_are_bounded() {
+	let relay_chain = Relaychain::new(Default::default());
+
+	let too_long_key = vec![1; MAX_ADDITIONAL_RELAY_KEY_LEN + 1];
+	let mut additional_relay_keys = vec![too_long_key.clone()];
+	additional_relay_keys
+		.extend((0..MAX_ADDITIONAL_RELAY_KEYS as u32 + 1).map(|i| i.to_le_bytes().to_vec()));
+
+	create_inherent_data(&relay_chain, additional_relay_keys);
+
+	let proven_keys = relay_chain.proven_keys();
+	assert!(!proven_keys.contains(&too_long_key));
+	assert!(proven_keys.contains(&(MAX_ADDITIONAL_RELAY_KEYS as u32 - 1).to_le_bytes().to_vec()));
+	assert!(!proven_keys.contains(&(MAX_ADDITIONAL_RELAY_KEYS as u32).to_le_bytes().to_vec()));
+}
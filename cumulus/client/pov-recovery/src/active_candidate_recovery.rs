@@ -17,12 +17,12 @@
 
 use sp_runtime::traits::Block as BlockT;
 
-use polkadot_node_primitives::PoV;
+use polkadot_node_primitives::AvailableData;
 use polkadot_node_subsystem::messages::AvailabilityRecoveryMessage;
 
 use futures::{channel::oneshot, stream::FuturesUnordered, Future, FutureExt, StreamExt};
 
-use std::{pin::Pin, sync::Arc};
+use std::pin::Pin;
 
 use crate::RecoveryHandle;
 
@@ -31,8 +31,9 @@ use crate::RecoveryHandle;
 /// This handles the candidate recovery and tracks the activate recoveries.
 pub(crate) struct ActiveCandidateRecovery<Block: BlockT> {
 	/// The recoveries that are currently being executed.
-	recoveries:
-		FuturesUnordered<Pin<Box<dyn Future<Output = (Block::Hash, Option<Arc<PoV>>)> + Send>>>,
+	recoveries: FuturesUnordered<
+		Pin<Box<dyn Future<Output = (Block::Hash, Option<AvailableData>)> + Send>>,
+	>,
 	recovery_handle: Box<dyn RecoveryHandle>,
 }
 
@@ -65,7 +66,7 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 		self.recoveries.push(
 			async move {
 				match rx.await {
-					Ok(Ok(res)) => (block_hash, Some(res.pov)),
+					Ok(Ok(res)) => (block_hash, Some(res)),
 					Ok(Err(error)) => {
 						tracing::debug!(
 							target: crate::LOG_TARGET,
@@ -88,10 +89,19 @@ impl<Block: BlockT> ActiveCandidateRecovery<Block> {
 		);
 	}
 
+	/// Track the recovery of the block with the given `block_hash` from other parachain nodes.
+	pub fn recover_candidate_from_peers(
+		&mut self,
+		block_hash: Block::Hash,
+		recovery: impl Future<Output = Option<AvailableData>> + Send + 'static,
+	) {
+		self.recoveries.push(recovery.map(move |res| (block_hash, res)).boxed());
+	}
+
 	/// Waits for the next recovery.
 	///
-	/// If the returned [`PoV`] is `None`, it means that the recovery failed.
-	pub async fn wait_for_recovery(&mut self) -> (Block::Hash, Option<Arc<PoV>>) {
+	/// If the returned [`AvailableData`] is `None`, it means that the recovery failed.
+	pub async fn wait_for_recovery(&mut self) -> (Block::Hash, Option<AvailableData>) {
 		loop {
 			if let Some(res) = self.recoveries.next().await {
 				return res
@@ -44,6 +44,12 @@
 //! 4b. Since we are trying to recover pending candidates, availability is not guaranteed. If the
 //! block     PoV is not yet available, we retry.
 //!
+//! 4c. If configured with [`PoVRecovery::with_peer_recovery`], we first ask other parachain nodes
+//! for the PoV before retrying. See [`peer_recovery`] for details.
+//!
+//! Explicitly requested blocks that are not pending candidates anymore, e.g. old blocks the node
+//! is missing, are recovered from other parachain nodes only, after the same delay.
+//!
 //! If we need to recover multiple PoV blocks (which should hopefully not happen in real life), we
 //! make sure that the blocks are imported in the correct order.
 
@@ -53,7 +59,7 @@ use sp_api::RuntimeApiInfo;
 use sp_consensus::{BlockOrigin, BlockStatus, SyncOracle};
 use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
 
-use polkadot_node_primitives::{PoV, POV_BOMB_LIMIT};
+use polkadot_node_primitives::{AvailableData, MaybeCompressedPoV, PoV, POV_BOMB_LIMIT};
 use polkadot_node_subsystem::messages::{AvailabilityRecoveryMessage, RuntimeApiRequest};
 use polkadot_overseer::Handle as OverseerHandle;
 use polkadot_primitives::{
@@ -87,6 +93,11 @@ mod tests;
 mod active_candidate_recovery;
 use active_candidate_recovery::ActiveCandidateRecovery;
 
+pub mod peer_recovery;
+pub use peer_recovery::{
+	AvailableDataRequestHandler, AvailableDataStore, NetworkPeerRecovery, PeerRecoveryHandle,
+};
+
 const LOG_TARGET: &str = "cumulus-pov-recovery";
 
 /// Test-friendly wrapper trait for the overseer handle.
@@ -112,7 +123,7 @@ impl RecoveryHandle for OverseerHandle {
 }
 
 /// Type of recovery to trigger.
-#[derive(Debug, PartialEq)]
+#[derive(Clone, Copy, Debug, PartialEq)]
 pub enum RecoveryKind {
 	/// Single block recovery.
 	Simple,
@@ -239,12 +250,21 @@ pub struct PoVRecovery<Block: BlockT, PC, RC> {
 	/// Blocks that we are retrying currently
 	candidates_in_retry: HashSet<Block::Hash>,
 	parachain_sync_service: Arc<dyn SyncOracle + Sync + Send>,
+	/// Used to recover blocks from other parachain nodes if the relay chain recovery fails.
+	peer_recovery: Option<Arc<dyn PeerRecoveryHandle<Block>>>,
+	/// Blocks that we already tried to recover from other parachain nodes.
+	candidates_in_peer_recovery: HashSet<Block::Hash>,
+	/// Blocks that are not pending candidates, queued to be or being recovered from other
+	/// parachain nodes.
+	blocks_in_peer_recovery: HashMap<Block::Hash, RecoveryKind>,
+	/// Keeps the available data of recovered blocks, to serve it to other parachain nodes.
+	available_data_store: Option<AvailableDataStore<Block>>,
 }
 
 impl<Block: BlockT, PC, RCInterface> PoVRecovery<Block, PC, RCInterface>
 where
 	PC: BlockBackend<Block> + BlockchainEvents<Block> + UsageProvider<Block>,
-	RCInterface: RelayChainInterface + Clone,
+	RCInterface: RelayChainInterface + Clone + 'static,
 {
 	/// Create a new instance.
 	pub fn new(
@@ -269,9 +289,28 @@ where
 			candidates_in_retry: HashSet::new(),
 			recovery_chan_rx,
 			parachain_sync_service,
+			peer_recovery: None,
+			candidates_in_peer_recovery: HashSet::new(),
+			blocks_in_peer_recovery: HashMap::new(),
+			available_data_store: None,
 		}
 	}
 
+	/// Recover blocks from other parachain nodes, if the recovery from the relay chain fails.
+	pub fn with_peer_recovery(mut self, peer_recovery: Arc<dyn PeerRecoveryHandle<Block>>) -> Self {
+		self.peer_recovery = Some(peer_recovery);
+		self
+	}
+
+	/// Keep the available data of recovered blocks in the given `available_data_store`.
+	pub fn with_available_data_store(
+		mut self,
+		available_data_store: AvailableDataStore<Block>,
+	) -> Self {
+		self.available_data_store = Some(available_data_store);
+		self
+	}
+
 	/// Handle a new pending candidate.
 	fn handle_pending_candidate(
 		&mut self,
@@ -332,6 +371,11 @@ where
 
 	/// Recover the candidate for the given `block_hash`.
 	async fn recover_candidate(&mut self, block_hash: Block::Hash) {
+		if self.blocks_in_peer_recovery.contains_key(&block_hash) {
+			self.recover_block_from_peers(block_hash);
+			return
+		}
+
 		match self.candidates.get(&block_hash) {
 			Some(candidate) if candidate.waiting_recovery => {
 				tracing::debug!(target: LOG_TARGET, ?block_hash, "Issuing recovery request");
@@ -341,6 +385,100 @@ where
 		}
 	}
 
+	/// Recover the candidate for the given `block_hash` from other parachain nodes.
+	///
+	/// Returns `false` if there is no peer recovery or it was already tried for the candidate.
+	fn recover_candidate_from_peers(&mut self, block_hash: Block::Hash) -> bool {
+		let (Some(peer_recovery), Some(candidate)) =
+			(self.peer_recovery.clone(), self.candidates.get(&block_hash))
+		else {
+			return false
+		};
+		if !self.candidates_in_peer_recovery.insert(block_hash) {
+			return false
+		}
+
+		tracing::debug!(target: LOG_TARGET, ?block_hash, "Recovery failed, asking peers.");
+		let recovery = peer_recovery::recover_from_peers(
+			peer_recovery,
+			block_hash,
+			Some(candidate.block_number),
+			Some(candidate.receipt.clone()),
+		);
+		self.active_candidate_recovery
+			.recover_candidate_from_peers(block_hash, recovery);
+		true
+	}
+
+	/// Queue the recovery of the block with the given `block_hash`, which is not a pending
+	/// candidate, from other parachain nodes.
+	///
+	/// Returns `false` if there is no peer recovery.
+	fn queue_block_recovery_from_peers(
+		&mut self,
+		block_hash: Block::Hash,
+		kind: RecoveryKind,
+	) -> bool {
+		if self.peer_recovery.is_none() {
+			return false
+		}
+
+		if !self.blocks_in_peer_recovery.contains_key(&block_hash) {
+			self.blocks_in_peer_recovery.insert(block_hash, kind);
+			self.candidate_recovery_queue.push_recovery(block_hash);
+		}
+		true
+	}
+
+	/// Recover the block with the given `block_hash`, which is not a pending candidate, from other
+	/// parachain nodes, unless it was imported in the meantime.
+	fn recover_block_from_peers(&mut self, block_hash: Block::Hash) {
+		let Some(peer_recovery) = self.peer_recovery.clone() else { return };
+		if !matches!(self.parachain_client.block_status(block_hash), Ok(BlockStatus::Unknown)) {
+			self.blocks_in_peer_recovery.remove(&block_hash);
+			return
+		}
+
+		tracing::debug!(target: LOG_TARGET, ?block_hash, "Block is not a pending candidate, asking peers.");
+		let recovery = peer_recovery::recover_from_peers(peer_recovery, block_hash, None, None);
+		self.active_candidate_recovery
+			.recover_candidate_from_peers(block_hash, recovery);
+	}
+
+	/// Handle a block that is not a pending candidate, recovered from other parachain nodes.
+	///
+	/// For a [`RecoveryKind::Full`] recovery, the ancestors that are not known either are recovered
+	/// first.
+	fn handle_block_recovered_from_peers(
+		&mut self,
+		block_hash: Block::Hash,
+		kind: RecoveryKind,
+		available_data: Option<AvailableData>,
+	) {
+		let Some(block) = available_data.and_then(|data| decode_block::<Block>(&data.pov)) else {
+			tracing::warn!(target: LOG_TARGET, ?block_hash, "Unable to recover block from peers.");
+			self.reset_candidate(block_hash);
+			return
+		};
+
+		let parent = *block.header().parent_hash();
+		if kind == RecoveryKind::Full &&
+			matches!(self.parachain_client.block_status(parent), Ok(BlockStatus::Unknown))
+		{
+			tracing::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				parent_hash = ?parent,
+				"Recovering unknown parent of block recovered from peers.",
+			);
+			self.waiting_for_parent.entry(parent).or_default().push(block);
+			self.recover(RecoveryRequest { hash: parent, kind: RecoveryKind::Full });
+			return
+		}
+
+		self.import_block(block);
+	}
+
 	/// Clear `waiting_for_parent` and `waiting_recovery` for the candidate with `hash`.
 	/// Also clears children blocks waiting for this parent.
 	fn reset_candidate(&mut self, hash: Block::Hash) {
@@ -355,14 +493,26 @@ where
 	}
 
 	/// Handle a recovered candidate.
-	async fn handle_candidate_recovered(&mut self, block_hash: Block::Hash, pov: Option<&PoV>) {
-		let pov = match pov {
-			Some(pov) => {
+	async fn handle_candidate_recovered(
+		&mut self,
+		block_hash: Block::Hash,
+		available_data: Option<AvailableData>,
+	) {
+		if let Some(kind) = self.blocks_in_peer_recovery.remove(&block_hash) {
+			self.handle_block_recovered_from_peers(block_hash, kind, available_data);
+			return
+		}
+
+		let available_data = match available_data {
+			Some(available_data) => {
 				self.candidates_in_retry.remove(&block_hash);
-				pov
+				self.candidates_in_peer_recovery.remove(&block_hash);
+				available_data
 			},
 			None =>
-				if self.candidates_in_retry.insert(block_hash) {
+				if self.recover_candidate_from_peers(block_hash) {
+					return
+				} else if self.candidates_in_retry.insert(block_hash) {
 					tracing::debug!(target: LOG_TARGET, ?block_hash, "Recovery failed, retrying.");
 					self.candidate_recovery_queue.push_recovery(block_hash);
 					return
@@ -373,37 +523,30 @@ where
 						"Unable to recover block after retry.",
 					);
 					self.candidates_in_retry.remove(&block_hash);
+					self.candidates_in_peer_recovery.remove(&block_hash);
 					self.reset_candidate(block_hash);
 					return
 				},
 		};
 
-		let raw_block_data =
-			match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
-				Ok(r) => r,
-				Err(error) => {
-					tracing::debug!(target: LOG_TARGET, ?error, "Failed to decompress PoV");
-
-					self.reset_candidate(block_hash);
-					return
-				},
-			};
-
-		let block_data = match ParachainBlockData::<Block>::decode(&mut &raw_block_data[..]) {
-			Ok(d) => d,
-			Err(error) => {
-				tracing::warn!(
-					target: LOG_TARGET,
-					?error,
-					"Failed to decode parachain block data from recovered PoV",
-				);
-
-				self.reset_candidate(block_hash);
-				return
-			},
+		let Some(block) = decode_block::<Block>(&available_data.pov) else {
+			self.reset_candidate(block_hash);
+			return
 		};
 
-		let block = block_data.into_block();
+		// Keep the data to serve it to other parachain nodes.
+		if let (Some(store), Some(candidate)) =
+			(&self.available_data_store, self.candidates.get(&block_hash))
+		{
+			let AvailableData { pov, validation_data } = available_data;
+			store.keep(
+				*block.header().number(),
+				block_hash,
+				candidate.receipt.descriptor.relay_parent(),
+				MaybeCompressedPoV::Compressed(Arc::unwrap_or_clone(pov)),
+				validation_data,
+			);
+		}
 
 		let parent = *block.header().parent_hash();
 
@@ -501,6 +644,10 @@ where
 			let candidate = match self.candidates.get_mut(&hash) {
 				Some(candidate) => candidate,
 				None => {
+					if to_recover.is_empty() && self.queue_block_recovery_from_peers(hash, kind) {
+						return
+					}
+
 					tracing::debug!(
 						target: LOG_TARGET,
 						block_hash = ?hash,
@@ -611,16 +758,40 @@ where
 				next_to_recover = self.candidate_recovery_queue.next_recovery().fuse() => {
 						self.recover_candidate(next_to_recover).await;
 				},
-				(block_hash, pov) =
+				(block_hash, available_data) =
 					self.active_candidate_recovery.wait_for_recovery().fuse() =>
 				{
-					self.handle_candidate_recovered(block_hash, pov.as_deref()).await;
+					self.handle_candidate_recovered(block_hash, available_data).await;
 				},
 			}
 		}
 	}
 }
 
+/// Decode the block of the given recovered `pov`.
+pub(crate) fn decode_block<Block: BlockT>(pov: &PoV) -> Option<Block> {
+	let raw_block_data =
+		match sp_maybe_compressed_blob::decompress(&pov.block_data.0, POV_BOMB_LIMIT) {
+			Ok(r) => r,
+			Err(error) => {
+				tracing::debug!(target: LOG_TARGET, ?error, "Failed to decompress PoV");
+				return None
+			},
+		};
+
+	match ParachainBlockData::<Block>::decode(&mut &raw_block_data[..]) {
+		Ok(block_data) => Some(block_data.into_block()),
+		Err(error) => {
+			tracing::warn!(
+				target: LOG_TARGET,
+				?error,
+				"Failed to decode parachain block data from recovered PoV",
+			);
+			None
+		},
+	}
+}
+
 /// Returns a stream over pending candidates for the parachain corresponding to `para_id`.
 async fn pending_candidates(
 	relay_chain_client: impl RelayChainInterface + Clone,
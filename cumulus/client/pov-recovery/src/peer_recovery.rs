@@ -0,0 +1,580 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: GPL-3.0-or-later WITH Classpath-exception-2.0
+
+// Cumulus is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Cumulus is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE. See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Cumulus. If not, see <https://www.gnu.org/licenses/>.
+
+//! Recovery of PoVs from other parachain nodes.
+//!
+//! Nodes started with an [`AvailableDataStore`] keep the [`AvailableData`] of the blocks they
+//! collated or recovered for a configurable number of finalized blocks and serve it to the other
+//! nodes of the parachain network with the [`AvailableDataRequestHandler`]. The
+//! [`PoVRecovery`](crate::PoVRecovery) asks these nodes through a [`PeerRecoveryHandle`] if the
+//! recovery of a pending candidate from the relay chain fails, e.g. because the validators already
+//! pruned the data, and for blocks that are not pending candidates anymore, e.g. old blocks the
+//! node is missing.
+//!
+//! Unlike the availability protocol of the relay chain, which distributes one erasure coded chunk
+//! of the data per validator, the whole [`AvailableData`] is served: a single honest peer is
+//! enough to recover a block. Peers are not trusted: the data of a pending candidate is erasure
+//! coded into the chunks the relay chain validators would have stored and only accepted if the root
+//! of these chunks matches the erasure root of the candidate receipt. The data of other blocks is
+//! only accepted if it contains the requested block.
+
+use codec::{Decode, Encode};
+use futures::{channel::oneshot, select, Future, StreamExt};
+use rand::seq::SliceRandom;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use sc_network::{
+	request_responses::{IncomingRequest, OutgoingResponse},
+	service::traits::NetworkService,
+	IfDisconnected, NetworkBackend, NetworkRequest, PeerId, ProtocolName, MAX_RESPONSE_SIZE,
+};
+use sc_network_sync::SyncingService;
+use sp_runtime::traits::{Block as BlockT, NumberFor, One, Saturating};
+
+use polkadot_erasure_coding::{branches, obtain_chunks_v1};
+use polkadot_node_primitives::{AvailableData, MaybeCompressedPoV};
+use polkadot_primitives::{
+	vstaging::CandidateReceiptV2 as CandidateReceipt, Hash as RelayHash, PersistedValidationData,
+};
+
+use cumulus_relay_chain_interface::RelayChainInterface;
+
+use std::{marker::PhantomData, sync::Arc, time::Duration};
+
+use crate::LOG_TARGET;
+
+/// Number of block numbers whose available data is pruned at most on finalization of a block.
+const MAX_PRUNED_BLOCK_NUMBERS: u32 = 1024;
+
+/// Number of peers asked for the available data of a block before giving up.
+const MAX_PEERS_TO_ASK: usize = 5;
+
+/// Number of incoming requests that are queued before new ones are dropped.
+const MAX_QUEUED_REQUESTS: usize = 16;
+
+/// Number of available data writes that are queued before new ones are dropped.
+const MAX_QUEUED_WRITES: usize = 16;
+
+const MAX_REQUEST_SIZE: u64 = 1024;
+const REQUEST_TIMEOUT: Duration = Duration::from_secs(20);
+
+const AVAILABLE_DATA_PREFIX: &[u8] = b"cumulus_pov_recovery_available_data";
+const AVAILABLE_DATA_INDEX_PREFIX: &[u8] = b"cumulus_pov_recovery_available_data_index";
+const AVAILABLE_DATA_LOWEST_KEY: &[u8] = b"cumulus_pov_recovery_available_data_lowest";
+
+/// Request for the [`AvailableData`] of a parachain block.
+///
+/// It is answered with a SCALE-encoded `Option<ServedAvailableData>`.
+#[derive(Debug, Clone, Encode, Decode)]
+pub struct AvailableDataRequest<Hash> {
+	/// Hash of the parachain block.
+	pub block_hash: Hash,
+}
+
+/// The [`AvailableData`] of a parachain block, as kept and served to other parachain nodes.
+#[derive(Debug, Clone, PartialEq, Encode, Decode)]
+pub struct ServedAvailableData {
+	/// The available data of the block.
+	pub available_data: AvailableData,
+	/// The number of chunks the data was erasure coded into, i.e. the number of validators in the
+	/// session of the relay parent of the candidate.
+	///
+	/// Sent along as the relay chain may have pruned the session of old candidates. It does not
+	/// need to be trusted, as the erasure root commits to the number of chunks.
+	pub n_validators: u32,
+}
+
+/// The name of the available data request protocol of the parachain with the given
+/// `genesis_hash`.
+pub fn protocol_name<Hash: AsRef<[u8]>>(genesis_hash: Hash) -> ProtocolName {
+	format!("/{}/pov-recovery/1", array_bytes::bytes2hex("", genesis_hash.as_ref())).into()
+}
+
+fn available_data_key<Hash: Encode>(block_hash: &Hash) -> Vec<u8> {
+	(AVAILABLE_DATA_PREFIX, block_hash).encode()
+}
+
+// Key of the hashes of the blocks with kept available data at `block_number`.
+fn index_key<Number: Encode>(block_number: Number) -> Vec<u8> {
+	(AVAILABLE_DATA_INDEX_PREFIX, block_number).encode()
+}
+
+fn read_decoded<T: Decode>(
+	store: &impl AuxStore,
+	key: &[u8],
+	what: &str,
+) -> sp_blockchain::Result<Option<T>> {
+	match store.get_aux(key)? {
+		Some(raw) => T::decode(&mut &raw[..])
+			.map(Some)
+			.map_err(|e| sp_blockchain::Error::Backend(format!("Failed to decode {what}: {e}"))),
+		None => Ok(None),
+	}
+}
+
+fn read_index<Block: BlockT>(
+	store: &impl AuxStore,
+	block_number: NumberFor<Block>,
+) -> sp_blockchain::Result<Vec<Block::Hash>> {
+	Ok(read_decoded(store, &index_key(block_number), "available data index")?.unwrap_or_default())
+}
+
+// The lowest block number that may have kept available data.
+fn read_lowest<Block: BlockT>(
+	store: &impl AuxStore,
+) -> sp_blockchain::Result<Option<NumberFor<Block>>> {
+	read_decoded(store, AVAILABLE_DATA_LOWEST_KEY, "lowest available data block number")
+}
+
+/// Write the `served` available data of the block with the given `block_hash` and
+/// `block_number`.
+///
+/// Only called by the [`AvailableDataStore`] task, which is the only writer of the index.
+pub(crate) fn write_available_data<Block: BlockT>(
+	store: &impl AuxStore,
+	block_number: NumberFor<Block>,
+	block_hash: Block::Hash,
+	served: &ServedAvailableData,
+) -> sp_blockchain::Result<()> {
+	let mut index = read_index::<Block>(store, block_number)?;
+	if index.contains(&block_hash) {
+		return Ok(())
+	}
+	index.push(block_hash);
+
+	let mut insert = vec![
+		(available_data_key(&block_hash), served.encode()),
+		(index_key(block_number), index.encode()),
+	];
+	if read_lowest::<Block>(store)?.map_or(true, |lowest| block_number < lowest) {
+		insert.push((AVAILABLE_DATA_LOWEST_KEY.to_vec(), block_number.encode()));
+	}
+
+	store.insert_aux(
+		&insert.iter().map(|(key, value)| (&key[..], &value[..])).collect::<Vec<_>>(),
+		&[],
+	)
+}
+
+/// Read the available data of the block with the given `block_hash`, if it is kept.
+pub(crate) fn read_available_data<Block: BlockT>(
+	store: &impl AuxStore,
+	block_hash: Block::Hash,
+) -> sp_blockchain::Result<Option<ServedAvailableData>> {
+	read_decoded(store, &available_data_key(&block_hash), "available data")
+}
+
+/// Delete the available data that is not kept anymore after `finalized_number` got finalized.
+///
+/// The data is kept for `keep_blocks` finalized blocks. The data of at most
+/// [`MAX_PRUNED_BLOCK_NUMBERS`] block numbers is deleted at once, the rest is deleted on the next
+/// finalized blocks.
+pub(crate) fn prune_available_data<Block: BlockT>(
+	store: &impl AuxStore,
+	finalized_number: NumberFor<Block>,
+	keep_blocks: u32,
+) -> sp_blockchain::Result<()> {
+	let Some(lowest) = read_lowest::<Block>(store)? else { return Ok(()) };
+	let keep_from = finalized_number
+		.saturating_sub(keep_blocks.into())
+		.min(lowest.saturating_add(MAX_PRUNED_BLOCK_NUMBERS.into()));
+	if lowest >= keep_from {
+		return Ok(())
+	}
+
+	let mut pruned_keys = Vec::new();
+	let mut block_number = lowest;
+	while block_number < keep_from {
+		let index = read_index::<Block>(store, block_number)?;
+		if !index.is_empty() {
+			pruned_keys.extend(index.iter().map(available_data_key));
+			pruned_keys.push(index_key(block_number));
+		}
+		block_number += One::one();
+	}
+
+	store.insert_aux(
+		&[(AVAILABLE_DATA_LOWEST_KEY, &keep_from.encode()[..])],
+		&pruned_keys.iter().map(|key| &key[..]).collect::<Vec<_>>(),
+	)
+}
+
+/// The available data of a block to keep.
+struct KeepRequest<Block: BlockT> {
+	block_number: NumberFor<Block>,
+	block_hash: Block::Hash,
+	relay_parent: RelayHash,
+	pov: MaybeCompressedPoV,
+	validation_data: PersistedValidationData,
+}
+
+/// Keeps the [`AvailableData`] of parachain blocks, to serve it to other parachain nodes.
+///
+/// The data is compressed, written to the aux store of the parachain client and pruned by the
+/// task returned from [`AvailableDataStore::new`], off the paths of collation and recovery. If the
+/// task can not keep up, the data of new blocks is dropped.
+pub struct AvailableDataStore<Block: BlockT> {
+	sender: async_channel::Sender<KeepRequest<Block>>,
+}
+
+impl<Block: BlockT> Clone for AvailableDataStore<Block> {
+	fn clone(&self) -> Self {
+		Self { sender: self.sender.clone() }
+	}
+}
+
+impl<Block: BlockT> AvailableDataStore<Block> {
+	/// Create a new [`AvailableDataStore`] keeping the data for `keep_blocks` finalized blocks,
+	/// together with the task writing it.
+	///
+	/// The task should be spawned as a blocking task, as it writes to the database.
+	pub fn new<Client, RCInterface>(
+		client: Arc<Client>,
+		relay_chain_interface: RCInterface,
+		keep_blocks: u32,
+	) -> (Self, impl Future<Output = ()> + Send + 'static)
+	where
+		Client: AuxStore + BlockchainEvents<Block> + Send + Sync + 'static,
+		RCInterface: RelayChainInterface + 'static,
+	{
+		let (sender, receiver) = async_channel::bounded(MAX_QUEUED_WRITES);
+
+		(Self { sender }, run_store(client, relay_chain_interface, keep_blocks, receiver))
+	}
+
+	/// Keep the available data of the block `block_number` with the given `block_hash`, built on
+	/// top of `relay_parent`.
+	pub fn keep(
+		&self,
+		block_number: NumberFor<Block>,
+		block_hash: Block::Hash,
+		relay_parent: RelayHash,
+		pov: MaybeCompressedPoV,
+		validation_data: PersistedValidationData,
+	) {
+		let request = KeepRequest { block_number, block_hash, relay_parent, pov, validation_data };
+		if let Err(error) = self.sender.try_send(request) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?block_hash,
+				?error,
+				"Dropping the available data of the block",
+			);
+		}
+	}
+}
+
+async fn run_store<Block, Client, RCInterface>(
+	client: Arc<Client>,
+	relay_chain_interface: RCInterface,
+	keep_blocks: u32,
+	receiver: async_channel::Receiver<KeepRequest<Block>>,
+) where
+	Block: BlockT,
+	Client: AuxStore + BlockchainEvents<Block>,
+	RCInterface: RelayChainInterface,
+{
+	let mut finalized_blocks = client.finality_notification_stream().fuse();
+	let mut receiver = receiver.fuse();
+
+	loop {
+		select! {
+			request = receiver.next() => {
+				let Some(request) = request else { return };
+				keep_available_data(&*client, &relay_chain_interface, request).await;
+			},
+			finalized = finalized_blocks.next() => {
+				let Some(finalized) = finalized else { return };
+				if let Err(error) = prune_available_data::<Block>(
+					&*client,
+					*finalized.header.number(),
+					keep_blocks,
+				) {
+					tracing::warn!(target: LOG_TARGET, ?error, "Failed to prune available data");
+				}
+			},
+		}
+	}
+}
+
+async fn keep_available_data<Block: BlockT>(
+	store: &impl AuxStore,
+	relay_chain_interface: &impl RelayChainInterface,
+	KeepRequest { block_number, block_hash, relay_parent, pov, validation_data }: KeepRequest<
+		Block,
+	>,
+) {
+	// Candidates are erasure coded into one chunk per validator of the session of their relay
+	// parent, which is recent while the block is collated or recovered.
+	let n_validators = match relay_chain_interface.validators(relay_parent).await {
+		Ok(validators) => validators.len() as u32,
+		Err(error) => {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?error,
+				?block_hash,
+				"Failed to fetch the validators of the relay parent",
+			);
+			return
+		},
+	};
+
+	let served = ServedAvailableData {
+		available_data: AvailableData { pov: Arc::new(pov.into_compressed()), validation_data },
+		n_validators,
+	};
+	if let Err(error) = write_available_data::<Block>(store, block_number, block_hash, &served) {
+		tracing::warn!(target: LOG_TARGET, ?error, ?block_hash, "Failed to write available data");
+	}
+}
+
+/// Handler for incoming [`AvailableDataRequest`]s from other parachain nodes.
+pub struct AvailableDataRequestHandler<Block, Client> {
+	client: Arc<Client>,
+	request_receiver: async_channel::Receiver<IncomingRequest>,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> AvailableDataRequestHandler<Block, Client>
+where
+	Block: BlockT,
+	Client: AuxStore + Send + Sync + 'static,
+{
+	/// Create a new [`AvailableDataRequestHandler`] for the parachain with the given
+	/// `genesis_hash`, together with the config of its protocol.
+	pub fn new<N: NetworkBackend<Block, Block::Hash>>(
+		genesis_hash: Block::Hash,
+		client: Arc<Client>,
+	) -> (Self, N::RequestResponseProtocolConfig) {
+		let (tx, request_receiver) = async_channel::bounded(MAX_QUEUED_REQUESTS);
+
+		let protocol_config = N::request_response_config(
+			protocol_name(genesis_hash),
+			Vec::new(),
+			MAX_REQUEST_SIZE,
+			MAX_RESPONSE_SIZE,
+			REQUEST_TIMEOUT,
+			Some(tx),
+		);
+
+		(Self { client, request_receiver, _phantom: PhantomData }, protocol_config)
+	}
+
+	/// Run the [`AvailableDataRequestHandler`].
+	pub async fn run(mut self) {
+		while let Some(IncomingRequest { peer, payload, pending_response }) =
+			self.request_receiver.next().await
+		{
+			self.handle_request(peer, payload, pending_response);
+		}
+	}
+
+	fn handle_request(
+		&self,
+		peer: PeerId,
+		payload: Vec<u8>,
+		pending_response: oneshot::Sender<OutgoingResponse>,
+	) {
+		let result = match AvailableDataRequest::<Block::Hash>::decode(&mut &payload[..]) {
+			Ok(AvailableDataRequest { block_hash }) =>
+				match read_available_data::<Block>(&*self.client, block_hash) {
+					Ok(served) => {
+						tracing::trace!(
+							target: LOG_TARGET,
+							%peer,
+							?block_hash,
+							found = served.is_some(),
+							"Handled available data request",
+						);
+						Ok(served.encode())
+					},
+					Err(error) => {
+						tracing::warn!(
+							target: LOG_TARGET,
+							?error,
+							?block_hash,
+							"Failed to read available data",
+						);
+						Err(())
+					},
+				},
+			Err(error) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					%peer,
+					?error,
+					"Failed to decode available data request",
+				);
+				Err(())
+			},
+		};
+
+		let _ = pending_response.send(OutgoingResponse {
+			result,
+			reputation_changes: Vec::new(),
+			sent_feedback: None,
+		});
+	}
+}
+
+/// Requests the [`AvailableData`] of parachain blocks from other parachain nodes.
+///
+/// Test-friendly abstraction over the parachain network.
+#[async_trait::async_trait]
+pub trait PeerRecoveryHandle<Block: BlockT>: Send + Sync {
+	/// The peers that may be able to serve the available data of the block `block_number`, or of
+	/// any block if the number is not known.
+	async fn peers(&self, block_number: Option<NumberFor<Block>>) -> Vec<PeerId>;
+
+	/// Request the available data of the block with the given `block_hash` from `peer`.
+	///
+	/// The returned data is not validated yet.
+	async fn request_available_data(
+		&self,
+		peer: PeerId,
+		block_hash: Block::Hash,
+	) -> Option<ServedAvailableData>;
+}
+
+/// [`PeerRecoveryHandle`] sending [`AvailableDataRequest`]s over the parachain network.
+pub struct NetworkPeerRecovery<Block: BlockT> {
+	network: Arc<dyn NetworkService>,
+	sync_service: Arc<SyncingService<Block>>,
+	protocol_name: ProtocolName,
+}
+
+impl<Block: BlockT> NetworkPeerRecovery<Block> {
+	/// Create a new instance for the parachain with the given `genesis_hash`.
+	pub fn new(
+		network: Arc<dyn NetworkService>,
+		sync_service: Arc<SyncingService<Block>>,
+		genesis_hash: Block::Hash,
+	) -> Self {
+		Self { network, sync_service, protocol_name: protocol_name(genesis_hash) }
+	}
+}
+
+#[async_trait::async_trait]
+impl<Block: BlockT> PeerRecoveryHandle<Block> for NetworkPeerRecovery<Block> {
+	async fn peers(&self, block_number: Option<NumberFor<Block>>) -> Vec<PeerId> {
+		self.sync_service
+			.peers_info()
+			.await
+			.unwrap_or_default()
+			.into_iter()
+			.filter(|(_, info)| block_number.map_or(true, |number| info.best_number >= number))
+			.map(|(peer, _)| peer)
+			.collect()
+	}
+
+	async fn request_available_data(
+		&self,
+		peer: PeerId,
+		block_hash: Block::Hash,
+	) -> Option<ServedAvailableData> {
+		let response = self
+			.network
+			.request(
+				peer,
+				self.protocol_name.clone(),
+				AvailableDataRequest { block_hash }.encode(),
+				None,
+				IfDisconnected::ImmediateError,
+			)
+			.await;
+
+		match response
+			.map(|(response, _)| Option::<ServedAvailableData>::decode(&mut &response[..]))
+		{
+			Ok(Ok(served)) => served,
+			Ok(Err(error)) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					%peer,
+					?error,
+					"Failed to decode available data response",
+				);
+				None
+			},
+			Err(error) => {
+				tracing::debug!(
+					target: LOG_TARGET,
+					%peer,
+					?error,
+					"Available data request failed",
+				);
+				None
+			},
+		}
+	}
+}
+
+/// Checks that the `served` data is the data committed to by `receipt`.
+fn matches_erasure_root(served: &ServedAvailableData, receipt: &CandidateReceipt) -> bool {
+	let ServedAvailableData { available_data, n_validators } = served;
+	if available_data.pov.hash() != receipt.descriptor.pov_hash() {
+		return false
+	}
+
+	match obtain_chunks_v1(*n_validators as usize, available_data) {
+		Ok(chunks) => branches(&chunks).root() == receipt.descriptor.erasure_root(),
+		Err(error) => {
+			tracing::debug!(target: LOG_TARGET, ?error, "Failed to obtain chunks");
+			false
+		},
+	}
+}
+
+/// Recover the available data of the block with the given `block_hash` from other parachain
+/// nodes.
+///
+/// Peers are asked one after another, until one of them sends data matching the erasure root of
+/// the `receipt` of the candidate or, if the block is not a pending candidate, containing the
+/// block.
+pub(crate) async fn recover_from_peers<Block: BlockT>(
+	peer_recovery: Arc<dyn PeerRecoveryHandle<Block>>,
+	block_hash: Block::Hash,
+	block_number: Option<NumberFor<Block>>,
+	receipt: Option<CandidateReceipt>,
+) -> Option<AvailableData> {
+	let mut peers = peer_recovery.peers(block_number).await;
+	peers.shuffle(&mut rand::thread_rng());
+
+	for peer in peers.into_iter().take(MAX_PEERS_TO_ASK) {
+		let Some(served) = peer_recovery.request_available_data(peer, block_hash).await else {
+			continue
+		};
+
+		let is_valid = match receipt {
+			Some(ref receipt) => matches_erasure_root(&served, receipt),
+			None => crate::decode_block::<Block>(&served.available_data.pov)
+				.map_or(false, |block| block.hash() == block_hash),
+		};
+		if is_valid {
+			tracing::debug!(target: LOG_TARGET, %peer, ?block_hash, "Recovered block from peer");
+			return Some(served.available_data)
+		}
+
+		tracing::debug!(
+			target: LOG_TARGET,
+			%peer,
+			?block_hash,
+			"Peer sent available data not matching the requested block",
+		);
+	}
+
+	None
+}
@@ -30,7 +30,8 @@ use cumulus_test_client::{
 	Sr25519Keyring,
 };
 use futures::{channel::mpsc, SinkExt};
-use polkadot_node_primitives::AvailableData;
+use peer_recovery::ServedAvailableData;
+use polkadot_node_primitives::{AvailableData, PoV};
 use polkadot_node_subsystem::{messages::AvailabilityRecoveryMessage, RecoveryError, TimeoutExt};
 use rstest::rstest;
 use sc_client_api::{
@@ -38,6 +39,7 @@ use sc_client_api::{
 	FinalizeSummary, ImportNotifications, StorageEventStream, StorageKey,
 };
 use sc_consensus::import_queue::RuntimeOrigin;
+use sc_network::PeerId;
 use sc_utils::mpsc::{TracingUnboundedReceiver, TracingUnboundedSender};
 use sp_blockchain::Info;
 use sp_runtime::{generic::SignedBlock, Justifications};
@@ -52,6 +54,7 @@ use tokio::task;
 
 const GENESIS_HASH: PHash = PHash::zero();
 const TEST_SESSION_INDEX: SessionIndex = 0;
+const TEST_N_VALIDATORS: usize = 10;
 
 struct AvailabilityRecoverySubsystemHandle {
 	tx: mpsc::Sender<AvailabilityRecoveryMessage>,
@@ -81,6 +84,7 @@ struct ParachainClientInner<Block: BlockT> {
 	finality_notifications_rx: Option<TracingUnboundedReceiver<FinalityNotification<Block>>>,
 	usage_infos: Vec<ClientInfo<Block>>,
 	block_statuses: Arc<Mutex<HashMap<Block::Hash, BlockStatus>>>,
+	aux: HashMap<Vec<u8>, Vec<u8>>,
 }
 
 impl<Block: BlockT> ParachainClientInner<Block> {
@@ -102,6 +106,7 @@ impl<Block: BlockT> ParachainClientInner<Block> {
 				finality_notifications_rx: Some(finality_notifications_rx),
 				usage_infos,
 				block_statuses,
+				aux: HashMap::new(),
 			},
 			import_notifications_tx,
 			finality_notifications_tx,
@@ -226,6 +231,33 @@ impl<Block: BlockT> UsageProvider<Block> for ParachainClient<Block> {
 	}
 }
 
+impl<Block: BlockT> AuxStore for ParachainClient<Block> {
+	fn insert_aux<
+		'a,
+		'b: 'a,
+		'c: 'a,
+		I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+		D: IntoIterator<Item = &'a &'b [u8]>,
+	>(
+		&self,
+		insert: I,
+		delete: D,
+	) -> sp_blockchain::Result<()> {
+		let aux = &mut self.inner.lock().expect("Poisoned lock").aux;
+		for (key, value) in insert {
+			aux.insert(key.to_vec(), value.to_vec());
+		}
+		for key in delete {
+			aux.remove(*key);
+		}
+		Ok(())
+	}
+
+	fn get_aux(&self, key: &[u8]) -> sp_blockchain::Result<Option<Vec<u8>>> {
+		Ok(self.inner.lock().expect("Poisoned lock").aux.get(key).cloned())
+	}
+}
+
 struct ParachainImportQueue<Block: BlockT> {
 	import_requests_tx: TracingUnboundedSender<Vec<IncomingBlock<Block>>>,
 }
@@ -276,6 +308,31 @@ impl SyncOracle for DummySyncOracle {
 	}
 }
 
+struct DummyPeerRecovery {
+	available_data: HashMap<PeerId, AvailableData>,
+}
+
+#[async_trait::async_trait]
+impl PeerRecoveryHandle<Block> for DummyPeerRecovery {
+	async fn peers(&self, _: Option<NumberFor<Block>>) -> Vec<PeerId> {
+		self.available_data.keys().cloned().collect()
+	}
+
+	async fn request_available_data(
+		&self,
+		peer: PeerId,
+		_: <Block as BlockT>::Hash,
+	) -> Option<ServedAvailableData> {
+		self.available_data
+			.get(&peer)
+			.cloned()
+			.map(|available_data| ServedAvailableData {
+				available_data,
+				n_validators: TEST_N_VALIDATORS as u32,
+			})
+	}
+}
+
 #[derive(Clone)]
 struct RelaychainInner {
 	runtime_version: u32,
@@ -335,7 +392,7 @@ impl RelayChainInterface for Relaychain {
 	}
 
 	async fn validators(&self, _: PHash) -> RelayChainResult<Vec<ValidatorId>> {
-		unimplemented!("Not needed for test")
+		Ok(vec![Sr25519Keyring::Alice.public().into(); TEST_N_VALIDATORS])
 	}
 
 	async fn best_block_hash(&self) -> RelayChainResult<PHash> {
@@ -1433,3 +1490,304 @@ async fn chained_recovery_child_succeeds_before_parent() {
 	// No more import requests received
 	assert_matches!(import_requests_rx.next().timeout(Duration::from_millis(100)).await, None);
 }
+
+#[tokio::test]
+async fn single_pending_candidate_recovery_from_peers() {
+	sp_tracing::init_for_tests();
+
+	let (recovery_subsystem_tx, mut recovery_subsystem_rx) =
+		AvailabilityRecoverySubsystemHandle::new();
+	let recovery_delay_range =
+		RecoveryDelayRange { min: Duration::from_millis(0), max: Duration::from_millis(10) };
+	let (_explicit_recovery_chan_tx, explicit_recovery_chan_rx) = mpsc::channel(10);
+	let mut candidates = make_candidate_chain(1..2);
+	let header = Header::decode(&mut &candidates[0].commitments.head_data.0[..]).unwrap();
+	let available_data = AvailableData {
+		pov: Arc::new(PoV {
+			block_data: ParachainBlockData::<Block>::new(
+				header.clone(),
+				vec![],
+				CompactProof { encoded_nodes: vec![] },
+			)
+			.encode()
+			.into(),
+		}),
+		validation_data: dummy_pvd(),
+	};
+
+	// Commit to the available data in the candidate, like the relay chain does.
+	let chunks =
+		polkadot_erasure_coding::obtain_chunks_v1(TEST_N_VALIDATORS, &available_data).unwrap();
+	let collator = Sr25519Keyring::Ferdie;
+	candidates[0].descriptor = CandidateDescriptor {
+		para_id: ParaId::from(1000),
+		relay_parent: PHash::zero(),
+		collator: collator.public().into(),
+		persisted_validation_data_hash: available_data.validation_data.hash(),
+		pov_hash: available_data.pov.hash(),
+		erasure_root: polkadot_erasure_coding::branches(&chunks).root(),
+		signature: collator.sign(&[0u8; 132]).into(),
+		para_head: PHash::zero(),
+		validation_code_hash: PHash::zero().into(),
+	}
+	.into();
+	let candidate_hash = candidates[0].hash();
+
+	let relay_chain_client = Relaychain::new(vec![(
+		PHeader {
+			parent_hash: PHash::from_low_u64_be(0),
+			number: 1,
+			state_root: PHash::random(),
+			extrinsics_root: PHash::random(),
+			digest: Default::default(),
+		},
+		candidates,
+	)]);
+	let mut known_blocks = HashMap::new();
+	known_blocks.insert(GENESIS_HASH, BlockStatus::InChainWithState);
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Arc::new(Mutex::new(known_blocks)));
+	let (parachain_import_queue, mut import_requests_rx) = ParachainImportQueue::new();
+	let (store_client, _import_notifications_tx, _store_finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Default::default());
+	let store_client = Arc::new(store_client);
+	let (available_data_store, available_data_store_task) =
+		AvailableDataStore::new(store_client.clone(), relay_chain_client.clone(), 10);
+	task::spawn(available_data_store_task);
+
+	// One peer sends data not committed to by the candidate, the other one the right data.
+	let mut wrong_available_data = available_data.clone();
+	wrong_available_data.validation_data.relay_parent_number += 1;
+	let peer_recovery = DummyPeerRecovery {
+		available_data: [
+			(PeerId::random(), wrong_available_data),
+			(PeerId::random(), available_data.clone()),
+		]
+		.into_iter()
+		.collect(),
+	};
+
+	let pov_recovery = PoVRecovery::<Block, _, _>::new(
+		Box::new(recovery_subsystem_tx),
+		recovery_delay_range,
+		Arc::new(parachain_client),
+		Box::new(parachain_import_queue),
+		relay_chain_client,
+		ParaId::new(1000),
+		explicit_recovery_chan_rx,
+		Arc::new(DummySyncOracle::default()),
+	)
+	.with_peer_recovery(Arc::new(peer_recovery))
+	.with_available_data_store(available_data_store);
+
+	task::spawn(pov_recovery.run());
+
+	// Recovery from the relay chain fails.
+	assert_matches!(
+		recovery_subsystem_rx.next().await,
+		Some(AvailabilityRecoveryMessage::RecoverAvailableData(
+			receipt,
+			session_index,
+			None,
+			None,
+			response_tx
+		)) => {
+			assert_eq!(receipt.hash(), candidate_hash);
+			assert_eq!(session_index, TEST_SESSION_INDEX);
+			response_tx.send(
+				Err(RecoveryError::Unavailable)
+			).unwrap()
+		}
+	);
+
+	// The candidate is recovered from the peers instead.
+	assert_matches!(import_requests_rx.next().await, Some(incoming_blocks) => {
+		assert_eq!(incoming_blocks.len(), 1);
+		assert_eq!(incoming_blocks[0].header, Some(header.clone()));
+	});
+
+	// No more recovery messages received.
+	assert_matches!(recovery_subsystem_rx.next().timeout(Duration::from_millis(100)).await, None);
+
+	// The data is kept to serve it to other nodes, together with the number of chunks.
+	let served = async {
+		loop {
+			if let Some(served) =
+				peer_recovery::read_available_data::<Block>(&*store_client, header.hash()).unwrap()
+			{
+				return served
+			}
+			tokio::time::sleep(Duration::from_millis(10)).await;
+		}
+	};
+	assert_eq!(
+		served.timeout(Duration::from_secs(1)).await,
+		Some(ServedAvailableData { available_data, n_validators: TEST_N_VALIDATORS as u32 }),
+	);
+}
+
+#[tokio::test]
+async fn unknown_block_recovery_from_peers() {
+	sp_tracing::init_for_tests();
+
+	let (recovery_subsystem_tx, mut recovery_subsystem_rx) =
+		AvailabilityRecoverySubsystemHandle::new();
+	let recovery_delay_range =
+		RecoveryDelayRange { min: Duration::from_millis(0), max: Duration::from_millis(10) };
+	let (mut explicit_recovery_chan_tx, explicit_recovery_chan_rx) = mpsc::channel(10);
+
+	// Two old blocks that are not pending candidates anymore.
+	let candidates = make_candidate_chain(1..3);
+	let headers = candidates
+		.iter()
+		.map(|candidate| Header::decode(&mut &candidate.commitments.head_data.0[..]).unwrap())
+		.collect::<Vec<_>>();
+	let available_data = |header: &Header| AvailableData {
+		pov: Arc::new(PoV {
+			block_data: ParachainBlockData::<Block>::new(
+				header.clone(),
+				vec![],
+				CompactProof { encoded_nodes: vec![] },
+			)
+			.encode()
+			.into(),
+		}),
+		validation_data: dummy_pvd(),
+	};
+
+	let relay_chain_client = Relaychain::new(vec![]);
+	let mut known_blocks = HashMap::new();
+	known_blocks.insert(GENESIS_HASH, BlockStatus::InChainWithState);
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::new(vec![dummy_usage_info(0)], Arc::new(Mutex::new(known_blocks)));
+	let (parachain_import_queue, mut import_requests_rx) = ParachainImportQueue::new();
+
+	// The first peer sends another block, the second one the requested block and the third one
+	// its parent.
+	let (wrong_peer, child_peer, parent_peer) =
+		(PeerId::random(), PeerId::random(), PeerId::random());
+	struct BlockPeerRecovery {
+		peers: Vec<PeerId>,
+		blocks: HashMap<(PeerId, <Block as BlockT>::Hash), AvailableData>,
+	}
+	#[async_trait::async_trait]
+	impl PeerRecoveryHandle<Block> for BlockPeerRecovery {
+		async fn peers(&self, block_number: Option<NumberFor<Block>>) -> Vec<PeerId> {
+			assert_eq!(block_number, None);
+			self.peers.clone()
+		}
+
+		async fn request_available_data(
+			&self,
+			peer: PeerId,
+			block_hash: <Block as BlockT>::Hash,
+		) -> Option<ServedAvailableData> {
+			self.blocks.get(&(peer, block_hash)).cloned().map(|available_data| {
+				ServedAvailableData { available_data, n_validators: TEST_N_VALIDATORS as u32 }
+			})
+		}
+	}
+	let peer_recovery = BlockPeerRecovery {
+		peers: vec![wrong_peer, child_peer, parent_peer],
+		blocks: [
+			((wrong_peer, headers[1].hash()), available_data(&headers[0])),
+			((child_peer, headers[1].hash()), available_data(&headers[1])),
+			((parent_peer, headers[0].hash()), available_data(&headers[0])),
+		]
+		.into_iter()
+		.collect(),
+	};
+
+	let pov_recovery = PoVRecovery::<Block, _, _>::new(
+		Box::new(recovery_subsystem_tx),
+		recovery_delay_range,
+		Arc::new(parachain_client),
+		Box::new(parachain_import_queue),
+		relay_chain_client,
+		ParaId::new(1000),
+		explicit_recovery_chan_rx,
+		Arc::new(DummySyncOracle::default()),
+	)
+	.with_peer_recovery(Arc::new(peer_recovery));
+
+	task::spawn(pov_recovery.run());
+
+	explicit_recovery_chan_tx
+		.send(RecoveryRequest { hash: headers[1].hash(), kind: RecoveryKind::Full })
+		.await
+		.unwrap();
+
+	// The parent is recovered as well and both are imported in order.
+	assert_matches!(import_requests_rx.next().await, Some(incoming_blocks) => {
+		assert_eq!(
+			incoming_blocks.into_iter().map(|block| block.header.unwrap()).collect::<Vec<_>>(),
+			headers,
+		);
+	});
+
+	// The relay chain is not asked for blocks that are not pending candidates.
+	assert_matches!(recovery_subsystem_rx.next().timeout(Duration::from_millis(100)).await, None);
+
+	// No more import requests received.
+	assert_matches!(import_requests_rx.next().timeout(Duration::from_millis(100)).await, None);
+}
+
+#[test]
+fn available_data_is_pruned_by_block_number() {
+	let (parachain_client, _import_notifications_tx, _finality_notifications_tx) =
+		ParachainClient::<Block>::new(vec![dummy_usage_info(0)], Default::default());
+	let served = ServedAvailableData {
+		available_data: AvailableData {
+			pov: Arc::new(PoV { block_data: vec![1, 2, 3].into() }),
+			validation_data: dummy_pvd(),
+		},
+		n_validators: TEST_N_VALIDATORS as u32,
+	};
+	let write = |block_number: u32, block_hash: PHash| {
+		peer_recovery::write_available_data::<Block>(
+			&parachain_client,
+			block_number,
+			block_hash,
+			&served,
+		)
+		.unwrap()
+	};
+	let is_kept = |block_hash: PHash| {
+		peer_recovery::read_available_data::<Block>(&parachain_client, block_hash)
+			.unwrap()
+			.is_some()
+	};
+	let keep_blocks = 100;
+	let prune = |finalized_number: u32| {
+		peer_recovery::prune_available_data::<Block>(
+			&parachain_client,
+			finalized_number,
+			keep_blocks,
+		)
+		.unwrap()
+	};
+
+	// Two forks at block 1 and a block 2.
+	let (fork_a, fork_b, child) =
+		(PHash::repeat_byte(1), PHash::repeat_byte(2), PHash::repeat_byte(3));
+	write(1, fork_a);
+	write(1, fork_b);
+	write(2, child);
+
+	prune(keep_blocks + 1);
+	assert!(is_kept(fork_a) && is_kept(fork_b) && is_kept(child));
+
+	prune(keep_blocks + 2);
+	assert!(!is_kept(fork_a) && !is_kept(fork_b));
+	assert!(is_kept(child));
+
+	// Far ahead only a bounded number of block numbers is pruned at once, but everything below is.
+	prune(keep_blocks + 100_000);
+	assert!(!is_kept(child));
+
+	// Data of older blocks written later is pruned as well.
+	let old = PHash::repeat_byte(4);
+	write(5, old);
+	prune(keep_blocks + 100_000);
+	assert!(!is_kept(old));
+}
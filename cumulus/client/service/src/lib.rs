@@ -22,7 +22,10 @@
 use cumulus_client_cli::CollatorOptions;
 use cumulus_client_consensus_common::ParachainConsensus;
 use cumulus_client_network::{AssumeSybilResistance, RequireSecondedInBlockAnnounce};
-use cumulus_client_pov_recovery::{PoVRecovery, RecoveryDelayRange, RecoveryHandle};
+use cumulus_client_pov_recovery::{
+	AvailableDataRequestHandler, AvailableDataStore, NetworkPeerRecovery, PoVRecovery,
+	RecoveryDelayRange, RecoveryHandle,
+};
 use cumulus_primitives_core::{CollectCollationInfo, ParaId};
 use cumulus_relay_chain_inprocess_interface::build_inprocess_relay_chain;
 use cumulus_relay_chain_interface::{RelayChainInterface, RelayChainResult};
@@ -32,7 +35,8 @@ use cumulus_relay_chain_minimal_node::{
 use futures::{channel::mpsc, StreamExt};
 use polkadot_primitives::{CollatorPair, OccupiedCoreAssumption};
 use sc_client_api::{
-	Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, ProofProvider, UsageProvider,
+	AuxStore, Backend as BackendT, BlockBackend, BlockchainEvents, Finalizer, ProofProvider,
+	UsageProvider,
 };
 use sc_consensus::{
 	import_queue::{ImportQueue, ImportQueueService},
@@ -107,6 +111,11 @@ pub struct StartRelayChainTasksParams<'a, Block: BlockT, Client, RCInterface> {
 	pub relay_chain_slot_duration: Duration,
 	pub recovery_handle: Box<dyn RecoveryHandle>,
 	pub sync_service: Arc<SyncingService<Block>>,
+	/// The parachain network, used to recover blocks from other parachain nodes if the relay
+	/// chain can not provide them. `None` disables this fallback.
+	pub network: Option<Arc<dyn NetworkService>>,
+	/// Keeps the available data of recovered blocks, to serve it to other parachain nodes.
+	pub available_data_store: Option<AvailableDataStore<Block>>,
 }
 
 /// Parameters given to [`start_full_node`].
@@ -178,6 +187,8 @@ where
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		network: None,
+		available_data_store: None,
 	})?;
 
 	#[allow(deprecated)]
@@ -215,6 +226,8 @@ pub fn start_relay_chain_tasks<Block, Client, Backend, RCInterface>(
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service,
+		network,
+		available_data_store,
 	}: StartRelayChainTasksParams<Block, Client, RCInterface>,
 ) -> sc_service::error::Result<()>
 where
@@ -268,7 +281,7 @@ where
 		DARecoveryProfile::Other(profile) => profile,
 	};
 
-	let pov_recovery = PoVRecovery::new(
+	let mut pov_recovery = PoVRecovery::new(
 		recovery_handle,
 		da_recovery_profile,
 		client.clone(),
@@ -276,8 +289,18 @@ where
 		relay_chain_interface.clone(),
 		para_id,
 		recovery_chan_rx,
-		sync_service,
+		sync_service.clone(),
 	);
+	if let Some(network) = network {
+		pov_recovery = pov_recovery.with_peer_recovery(Arc::new(NetworkPeerRecovery::new(
+			network,
+			sync_service,
+			client.usage_info().chain.genesis_hash,
+		)));
+	}
+	if let Some(available_data_store) = available_data_store {
+		pov_recovery = pov_recovery.with_available_data_store(available_data_store);
+	}
 
 	task_manager
 		.spawn_essential_handle()
@@ -328,6 +351,8 @@ where
 		recovery_handle,
 		sync_service,
 		da_recovery_profile: DARecoveryProfile::FullNode,
+		network: None,
+		available_data_store: None,
 	})
 }
 
@@ -421,13 +446,15 @@ pub struct BuildNetworkParams<
 	pub spawn_handle: SpawnTaskHandle,
 	pub import_queue: IQ,
 	pub sybil_resistance_level: CollatorSybilResistance,
+	/// Serve the available data kept by an [`AvailableDataStore`] to other parachain nodes.
+	pub serve_available_data: bool,
 }
 
 /// Build the network service, the network status sinks and an RPC sender.
 pub async fn build_network<'a, Block, Client, RCInterface, IQ, Network>(
 	BuildNetworkParams {
 		parachain_config,
-		net_config,
+		mut net_config,
 		client,
 		transaction_pool,
 		para_id,
@@ -435,6 +462,7 @@ pub async fn build_network<'a, Block, Client, RCInterface, IQ, Network>(
 		relay_chain_interface,
 		import_queue,
 		sybil_resistance_level,
+		serve_available_data,
 	}: BuildNetworkParams<'a, Block, Client, Network, RCInterface, IQ>,
 ) -> sc_service::error::Result<(
 	Arc<dyn NetworkService>,
@@ -455,6 +483,7 @@ where
 		+ HeaderMetadata<Block, Error = sp_blockchain::Error>
 		+ BlockIdTo<Block, Error = sp_blockchain::Error>
 		+ ProofProvider<Block>
+		+ AuxStore
 		+ 'static,
 	Client::Api: CollectCollationInfo<Block>
 		+ sp_transaction_pool::runtime_api::TaggedTransactionQueue<Block>,
@@ -493,6 +522,13 @@ where
 			Box::new(block_announce_validator) as Box<_>
 		},
 	};
+	if serve_available_data {
+		let (pov_request_handler, pov_request_protocol_config) =
+			AvailableDataRequestHandler::new::<Network>(client.info().genesis_hash, client.clone());
+		net_config.add_request_response_protocol(pov_request_protocol_config);
+		spawn_handle.spawn("cumulus-pov-request-handler", None, pov_request_handler.run());
+	}
+
 	let metrics = Network::register_notification_metrics(
 		parachain_config.prometheus_config.as_ref().map(|config| &config.registry),
 	);
@@ -1736,6 +1736,20 @@ pub trait RelaychainStateProvider {
 	fn set_current_relay_chain_state(_state: RelayChainState) {}
 }
 
+/// This exposes the relay chain state proof of the current block to other runtime modules.
+///
+/// Besides the keys read by this pallet, the proof contains the keys which the runtime requested
+/// through the [`RelayStorageKeysApi`](cumulus_primitives_core::RelayStorageKeysApi) at the parent
+/// block. Values read from the proof are checked against the relay parent storage root and can be
+/// trusted.
+pub trait RelaychainStateProofProvider {
+	/// May be called by any runtime module to obtain the relay chain state proof of the current
+	/// block.
+	///
+	/// Returns `None` before the validation data is set by the inherent, e.g. in `on_initialize`.
+	fn current_relay_state_proof() -> Option<RelayChainStateProof>;
+}
+
 /// Implements [`BlockNumberProvider`] that returns relay chain block number fetched from validation
 /// data.
 ///
@@ -1746,8 +1760,8 @@ pub trait RelaychainStateProvider {
 #[deprecated = "Use `RelaychainDataProvider` instead"]
 pub type RelaychainBlockNumberProvider<T> = RelaychainDataProvider<T>;
 
-/// Implements [`BlockNumberProvider`] and [`RelaychainStateProvider`] that returns relevant relay
-/// data fetched from validation data.
+/// Implements [`BlockNumberProvider`], [`RelaychainStateProvider`] and
+/// [`RelaychainStateProofProvider`] that returns relevant relay data fetched from validation data.
 ///
 /// NOTE: When validation data is not available (e.g. within `on_initialize`):
 ///
@@ -1755,6 +1769,7 @@ pub type RelaychainBlockNumberProvider<T> = RelaychainDataProvider<T>;
 ///   of [`RelayChainState`].
 /// - [`current_block_number`](Self::current_block_number): Will return
 ///   [`Pallet::last_relay_block_number()`].
+/// - [`current_relay_state_proof`](Self::current_relay_state_proof): Will return `None`.
 pub struct RelaychainDataProvider<T>(core::marker::PhantomData<T>);
 
 impl<T: Config> BlockNumberProvider for RelaychainDataProvider<T> {
@@ -1806,3 +1821,16 @@ impl<T: Config> RelaychainStateProvider for RelaychainDataProvider<T> {
 		ValidationData::<T>::put(validation_data)
 	}
 }
+
+impl<T: Config> RelaychainStateProofProvider for RelaychainDataProvider<T> {
+	fn current_relay_state_proof() -> Option<RelayChainStateProof> {
+		let validation_data = ValidationData::<T>::get()?;
+		let proof = RelayStateProof::<T>::get()?;
+		RelayChainStateProof::new(
+			T::SelfParaId::get(),
+			validation_data.relay_parent_storage_root,
+			proof,
+		)
+		.ok()
+	}
+}
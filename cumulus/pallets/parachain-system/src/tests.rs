@@ -528,6 +528,21 @@ fn manipulates_storage() {
 		);
 }
 
+#[test]
+fn requested_relay_storage_can_be_read_from_proof() {
+	const KEY: &[u8] = b"requested_relay_key";
+
+	BlockTests::new()
+		.with_relay_sproof_builder(|_, block_number, builder| {
+			builder.additional_key_values.push((KEY.to_vec(), block_number.encode()));
+		})
+		.add(123, || {
+			let proof = RelaychainDataProvider::<Test>::current_relay_state_proof()
+				.expect("validation data is set by the inherent");
+			assert_eq!(proof.read_entry::<RelayChainBlockNumber>(KEY, None).unwrap(), 123);
+		});
+}
+
 #[test]
 fn aborted_upgrade() {
 	BlockTests::new()
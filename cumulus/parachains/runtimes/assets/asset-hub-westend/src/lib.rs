@@ -118,6 +118,7 @@ use xcm::latest::prelude::{
 use xcm_runtime_apis::{
 	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
 	fees::Error as XcmPaymentApiError,
+	lifecycle::{LifecycleEvent, LifecycleStage},
 };
 
 impl_opaque_keys! {
@@ -1180,6 +1181,7 @@ construct_runtime!(
 		Revive: pallet_revive = 60,
 
 		AssetRewards: pallet_asset_rewards = 61,
+		XcmNfts: pallet_xcm_nfts = 62,
 
 		StateTrieMigration: pallet_state_trie_migration = 70,
 
@@ -1480,6 +1482,7 @@ mod benches {
 		[cumulus_pallet_parachain_system, ParachainSystem]
 		[cumulus_pallet_xcmp_queue, XcmpQueue]
 		[pallet_xcm_bridge_hub_router, ToRococo]
+		[pallet_xcm_nfts, XcmNfts]
 		[pallet_asset_conversion_ops, AssetConversionMigration]
 		// XCM
 		[pallet_xcm, PalletXcmExtrinsicsBenchmark::<Runtime>]
@@ -1740,6 +1743,27 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_runtime_apis::lifecycle::XcmLifecycleApi<Block> for Runtime {
+		fn xcm_lifecycle_events() -> Vec<LifecycleEvent> {
+			System::read_events_no_consensus()
+				.enumerate()
+				.filter_map(|(index, record)| {
+					let (message_id, stage) = match record.event {
+						RuntimeEvent::PolkadotXcm(event) => PolkadotXcm::lifecycle_stage(event)?,
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed { id, weight_used, success, .. }) =>
+							(id.into(), LifecycleStage::Processed { weight_used, success }),
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::ProcessingFailed { id, error, .. }) =>
+							(id.into(), LifecycleStage::ProcessingFailed { error }),
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::OverweightEnqueued { id, .. }) =>
+							(id, LifecycleStage::Overweight),
+						_ => return None,
+					};
+					Some(LifecycleEvent { message_id, event_index: index as u32, stage })
+				})
+				.collect()
+		}
+	}
+
 	impl xcm_runtime_apis::conversions::LocationToAccountApi<Block, AccountId> for Runtime {
 		fn convert_location(location: VersionedLocation) -> Result<
 			AccountId,
@@ -2190,6 +2214,9 @@ impl_runtime_apis! {
 				}
 
 				fn worst_case_asset_exchange() -> Result<(XcmAssets, XcmAssets), BenchmarkError> {
+					// A swap through a single pool. The other routes `PoolAssetsExchanger`
+					// quotes and the longer paths it swaps along are weighed by the
+					// `pallet-asset-conversion` benchmarks, see `weights::xcm`.
 					let native_asset_location = WestendLocation::get();
 					let native_asset_id = AssetId(native_asset_location.clone());
 					let (account, _) = pallet_xcm_benchmarks::account_and_location::<Runtime>(1);
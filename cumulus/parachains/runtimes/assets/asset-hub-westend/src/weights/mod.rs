@@ -42,6 +42,7 @@ pub mod pallet_uniques;
 pub mod pallet_utility;
 pub mod pallet_xcm;
 pub mod pallet_xcm_bridge_hub_router;
+pub mod pallet_xcm_nfts;
 pub mod paritydb_weights;
 pub mod rocksdb_weights;
 pub mod snowbridge_pallet_system_backend;
@@ -16,9 +16,13 @@
 mod pallet_xcm_benchmarks_fungible;
 mod pallet_xcm_benchmarks_generic;
 
-use crate::{xcm_config::MaxAssetsIntoHolding, Runtime};
+use crate::{
+	xcm_config::{MaxAssetsIntoHolding, PoolAssetsExchanger},
+	Runtime,
+};
 use alloc::vec::Vec;
-use frame_support::weights::Weight;
+use frame_support::{traits::Get, weights::Weight};
+use pallet_asset_conversion::WeightInfo as _;
 use pallet_xcm_benchmarks_fungible::WeightInfo as XcmFungibleWeight;
 use pallet_xcm_benchmarks_generic::WeightInfo as XcmGeneric;
 use sp_runtime::BoundedVec;
@@ -54,6 +58,31 @@ impl WeighAssets for AssetFilter {
 	}
 }
 
+/// The weight of quoting a swap through a pool, i.e. of reading its reserves.
+fn quote_pool_weight() -> Weight {
+	// The reserves are `ForeignAssets::Account` or `System::Account` entries, the former being
+	// the largest with a proof size of 3207 bytes.
+	<Runtime as frame_system::Config>::DbWeight::get()
+		.reads(2)
+		.saturating_add(Weight::from_parts(0, 2 * 3207))
+}
+
+/// The weight `PoolAssetsExchanger` adds to the `ExchangeAsset` benchmark, which quotes and swaps
+/// through a single pool, to quote all the routes and swap along the longest one.
+fn exchange_routes_weight() -> Weight {
+	type SwapWeight = crate::weights::pallet_asset_conversion::WeightInfo<Runtime>;
+	// The swaps are benchmarked for every path length, a path of two assets going through a
+	// single pool.
+	let swap_weight = |path_len| {
+		SwapWeight::swap_exact_tokens_for_tokens(path_len)
+			.max(SwapWeight::swap_tokens_for_exact_tokens(path_len))
+	};
+	let max_path_len = <Runtime as pallet_asset_conversion::Config>::MaxSwapPathLength::get();
+	let extra_swaps_weight = swap_weight(max_path_len).saturating_sub(swap_weight(2));
+	let extra_quoted_pools = PoolAssetsExchanger::max_quoted_pools().saturating_sub(1);
+	extra_swaps_weight.saturating_add(quote_pool_weight().saturating_mul(extra_quoted_pools))
+}
+
 impl WeighAssets for Assets {
 	fn weigh_assets(&self, weight: Weight) -> Weight {
 		weight.saturating_mul(self.inner().iter().count() as u64)
@@ -125,7 +154,8 @@ impl<Call> XcmWeightInfo<Call> for AssetHubWestendXcmWeight<Call> {
 		assets.weigh_assets(XcmFungibleWeight::<Runtime>::deposit_reserve_asset())
 	}
 	fn exchange_asset(give: &AssetFilter, receive: &Assets, _maximal: &bool) -> Weight {
-		let base_weight = XcmGeneric::<Runtime>::exchange_asset();
+		let base_weight =
+			XcmGeneric::<Runtime>::exchange_asset().saturating_add(exchange_routes_weight());
 		let give_weight = give.weigh_assets(base_weight);
 		let receive_weight = receive.weigh_assets(base_weight);
 		give_weight.max(receive_weight)
@@ -104,14 +104,18 @@ impl<T: frame_system::Config> WeightInfo<T> {
 	// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
 	// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferLimits` (r:2 w:0)
+	// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferWindows` (r:2 w:1)
+	// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	pub fn transfer_reserve_asset() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `638`
 		//  Estimated: `8799`
 		// Minimum execution time: 130_383_000 picoseconds.
 		Weight::from_parts(134_409_000, 8799)
-			.saturating_add(T::DbWeight::get().reads(12))
-			.saturating_add(T::DbWeight::get().writes(7))
+			.saturating_add(T::DbWeight::get().reads(16))
+			.saturating_add(T::DbWeight::get().writes(8))
 	}
 	pub fn reserve_asset_deposited() -> Weight {
 		// Proof Size summary in bytes:
@@ -132,14 +136,18 @@ impl<T: frame_system::Config> WeightInfo<T> {
 	// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
 	// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferLimits` (r:2 w:0)
+	// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferWindows` (r:2 w:1)
+	// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	pub fn initiate_reserve_withdraw() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `212`
 		//  Estimated: `6196`
 		// Minimum execution time: 109_295_000 picoseconds.
 		Weight::from_parts(120_413_000, 6196)
-			.saturating_add(T::DbWeight::get().reads(7))
-			.saturating_add(T::DbWeight::get().writes(3))
+			.saturating_add(T::DbWeight::get().reads(11))
+			.saturating_add(T::DbWeight::get().writes(4))
 	}
 	pub fn receive_teleported_asset() -> Weight {
 		// Proof Size summary in bytes:
@@ -179,14 +187,18 @@ impl<T: frame_system::Config> WeightInfo<T> {
 	// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
 	// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferLimits` (r:2 w:0)
+	// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferWindows` (r:2 w:1)
+	// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	pub fn deposit_reserve_asset() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `425`
 		//  Estimated: `3890`
 		// Minimum execution time: 72_043_000 picoseconds.
 		Weight::from_parts(74_586_000, 3890)
-			.saturating_add(T::DbWeight::get().reads(8))
-			.saturating_add(T::DbWeight::get().writes(4))
+			.saturating_add(T::DbWeight::get().reads(12))
+			.saturating_add(T::DbWeight::get().writes(5))
 	}
 	// Storage: `ParachainInfo::ParachainId` (r:1 w:0)
 	// Proof: `ParachainInfo::ParachainId` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
@@ -200,14 +212,18 @@ impl<T: frame_system::Config> WeightInfo<T> {
 	// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
 	// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferLimits` (r:2 w:0)
+	// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferWindows` (r:2 w:1)
+	// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	pub fn initiate_teleport() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `425`
 		//  Estimated: `3890`
 		// Minimum execution time: 53_342_000 picoseconds.
 		Weight::from_parts(56_209_000, 3890)
-			.saturating_add(T::DbWeight::get().reads(6))
-			.saturating_add(T::DbWeight::get().writes(1))
+			.saturating_add(T::DbWeight::get().reads(10))
+			.saturating_add(T::DbWeight::get().writes(2))
 	}
 	// Storage: `Assets::Asset` (r:1 w:1)
 	// Proof: `Assets::Asset` (`max_values`: None, `max_size`: Some(210), added: 2685, mode: `MaxEncodedLen`)
@@ -225,13 +241,17 @@ impl<T: frame_system::Config> WeightInfo<T> {
 	// Proof: `ParachainSystem::HostConfiguration` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
 	// Storage: `ParachainSystem::PendingUpwardMessages` (r:1 w:1)
 	// Proof: `ParachainSystem::PendingUpwardMessages` (`max_values`: Some(1), `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferLimits` (r:2 w:0)
+	// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	// Storage: `PolkadotXcm::OutboundTransferWindows` (r:2 w:1)
+	// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	pub fn initiate_transfer() -> Weight {
 		// Proof Size summary in bytes:
 		//  Measured:  `425`
 		//  Estimated: `6196`
 		// Minimum execution time: 89_970_000 picoseconds.
 		Weight::from_parts(92_829_000, 6196)
-			.saturating_add(T::DbWeight::get().reads(9))
-			.saturating_add(T::DbWeight::get().writes(5))
+			.saturating_add(T::DbWeight::get().reads(13))
+			.saturating_add(T::DbWeight::get().writes(6))
 	}
 }
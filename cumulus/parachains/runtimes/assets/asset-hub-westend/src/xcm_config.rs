@@ -16,9 +16,9 @@
 use super::{
 	AccountId, AllPalletsWithSystem, Assets, Authorship, Balance, Balances, BaseDeliveryFee,
 	CollatorSelection, DepositPerByte, DepositPerItem, FeeAssetId, ForeignAssets,
-	ForeignAssetsInstance, ParachainInfo, ParachainSystem, PolkadotXcm, PoolAssets, Runtime,
+	ForeignAssetsInstance, Nfts, ParachainInfo, ParachainSystem, PolkadotXcm, PoolAssets, Runtime,
 	RuntimeCall, RuntimeEvent, RuntimeHoldReason, RuntimeOrigin, ToRococoXcmRouter,
-	TransactionByteFee, TrustBackedAssetsInstance, Uniques, WeightToFee, XcmpQueue,
+	TransactionByteFee, TrustBackedAssetsInstance, Uniques, WeightToFee, XcmNfts, XcmpQueue,
 };
 use assets_common::{
 	matching::{FromSiblingParachain, IsForeignConcreteAsset, ParentLocation},
@@ -29,8 +29,9 @@ use frame_support::{
 	traits::{
 		fungible::HoldConsideration,
 		tokens::imbalance::{ResolveAssetTo, ResolveTo},
-		ConstU32, Contains, Equals, Everything, LinearStoragePrice, PalletInfoAccess,
+		ConstU32, Contains, Equals, Everything, LinearStoragePrice, Nothing, PalletInfoAccess,
 	},
+	PalletId,
 };
 use frame_system::EnsureRoot;
 use pallet_xcm::{AuthorizedAliasers, XcmPassthrough};
@@ -44,19 +45,21 @@ use parachains_common::{
 use polkadot_parachain_primitives::primitives::Sibling;
 use polkadot_runtime_common::xcm_sender::ExponentialPrice;
 use snowbridge_outbound_queue_primitives::v2::exporter::PausableExporter;
-use sp_runtime::traits::{AccountIdConversion, ConvertInto, TryConvertInto};
+use sp_runtime::traits::{AccountIdConversion, ConvertInto, MaybeConvert, TryConvertInto};
+use testnet_parachains_constants::westend::currency::CENTS;
 use westend_runtime_constants::system_parachain::COLLECTIVES_ID;
 use xcm::latest::{prelude::*, ROCOCO_GENESIS_HASH, WESTEND_GENESIS_HASH};
 use xcm_builder::{
 	AccountId32Aliases, AliasChildLocation, AllowExplicitUnpaidExecutionFrom,
 	AllowHrmpNotificationsFromRelayChain, AllowKnownQueryResponses, AllowSubscriptionsFrom,
-	AllowTopLevelPaidExecutionFrom, DenyRecursively, DenyReserveTransferToRelayChain, DenyThenTry,
-	DescribeAllTerminal, DescribeFamily, EnsureXcmOrigin, ExternalConsensusLocationsConverterFor,
-	FrameTransactionalProcessor, FungibleAdapter, FungiblesAdapter, HashedDescription, IsConcrete,
-	LocalMint, MatchedConvertedConcreteId, NetworkExportTableItem, NoChecking, NonFungiblesAdapter,
-	ParentAsSuperuser, ParentIsPreset, RelayChainAsNative, SendXcmFeeToAccount,
-	SiblingParachainAsNative, SiblingParachainConvertsVia, SignedAccountId32AsNative,
-	SignedToAccountId32, SingleAssetExchangeAdapter, SovereignPaidRemoteExporter,
+	AllowTopLevelPaidExecutionFrom, AsPrefixedGeneralIndex, DenyRecursively,
+	DenyReserveTransferToRelayChain, DenyThenTry, DescribeAllTerminal, DescribeFamily,
+	EnsureXcmOrigin, ExternalConsensusLocationsConverterFor, FrameTransactionalProcessor,
+	FungibleAdapter, FungiblesAdapter, HashedDescription, IsConcrete, LocalMint,
+	MatchedConvertedConcreteId, MultiHopAssetExchangeAdapter, NetworkExportTableItem, NoChecking,
+	NonFungiblesAdapter, ParentAsSuperuser, ParentIsPreset, RelayChainAsNative,
+	SendXcmFeeToAccount, SiblingParachainAsNative, SiblingParachainConvertsVia,
+	SignedAccountId32AsNative, SignedToAccountId32, SovereignPaidRemoteExporter,
 	SovereignSignedViaLocation, StartsWith, StartsWithExplicitGlobalConsensus, TakeWeightCredit,
 	TrailingSetTopicAsId, UsingComponents, WeightInfoBounds, WithComputedOrigin,
 	WithLatestLocationConverter, WithUniqueTopic, XcmFeeManagerFromComponents,
@@ -81,6 +84,8 @@ parameter_types! {
 		PalletInstance(<PoolAssets as PalletInfoAccess>::index() as u8).into();
 	pub UniquesPalletLocation: Location =
 		PalletInstance(<Uniques as PalletInfoAccess>::index() as u8).into();
+	pub NftsPalletLocation: Location =
+		PalletInstance(<Nfts as PalletInfoAccess>::index() as u8).into();
 	pub CheckingAccount: AccountId = PolkadotXcm::check_account();
 	pub StakingPot: AccountId = CollatorSelection::account_id();
 	pub TreasuryAccount: AccountId = TREASURY_PALLET_ID.into_account_truncating();
@@ -218,6 +223,7 @@ pub type AssetTransactors = (
 	ForeignFungiblesTransactor,
 	PoolFungiblesTransactor,
 	UniquesTransactor,
+	XcmNfts,
 );
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
@@ -357,11 +363,18 @@ pub type TrustedTeleporters = (
 /// - Allow origins explicitly authorized by the alias target location.
 pub type TrustedAliasers = (AliasChildLocation, AuthorizedAliasers<Runtime>);
 
+parameter_types! {
+	/// Assets that swaps may be routed through when there's no pool between the two assets.
+	pub PoolIntermediateAssets: alloc::vec::Vec<xcm::v5::Location> =
+		alloc::vec![WestendLocation::get()];
+}
+
 /// Asset converter for pool assets.
-/// Used to convert one asset to another, when there is a pool available between the two.
-/// This type thus allows paying fees with any asset as long as there is a pool between said
-/// asset and the asset required for fee payment.
-pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
+/// Used to convert one asset to another, when there is a pool available between the two or
+/// between each of them and the relay token.
+/// This type thus allows paying fees with any asset as long as there is a route of pools between
+/// said asset and the asset required for fee payment.
+pub type PoolAssetsExchanger = MultiHopAssetExchangeAdapter<
 	crate::AssetConversion,
 	crate::NativeAndNonPoolAssets,
 	(
@@ -376,6 +389,7 @@ pub type PoolAssetsExchanger = SingleAssetExchangeAdapter<
 			TryConvertInto,
 		>,
 	),
+	PoolIntermediateAssets,
 	AccountId,
 >;
 
@@ -476,6 +490,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = PolkadotXcm;
+	type TransferLimiter = PolkadotXcm;
 }
 
 /// Converts a local signed origin into an XCM location. Forms the basis for local origins
@@ -561,6 +576,61 @@ impl cumulus_pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 }
 
+parameter_types! {
+	pub const XcmNftsPalletId: PalletId = PalletId(*b"py/xcnft");
+	pub XcmNftsRemoteExecutionFee: Asset = (WestendLocation::get(), CENTS).into();
+}
+
+/// Siblings are expected to run `XcmNfts` at the same pallet index as this chain.
+pub struct XcmNftsIndexOfSiblings;
+impl MaybeConvert<Location, u8> for XcmNftsIndexOfSiblings {
+	fn maybe_convert(location: Location) -> Option<u8> {
+		matches!(location.unpack(), (1, [Parachain(_)]))
+			.then(|| <XcmNfts as PalletInfoAccess>::index() as u8)
+	}
+}
+
+/// This chain is the reserve of the collections of `Nfts`, and doesn't mirror foreign ones.
+impl pallet_xcm_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type LocalCollections = AsPrefixedGeneralIndex<
+		NftsPalletLocation,
+		<Runtime as pallet_nfts::Config>::CollectionId,
+		TryConvertInto,
+	>;
+	type ForeignCollections = Nothing;
+	type ItemInstance = TryConvertInto;
+	type LocationToAccountId = LocationToAccountId;
+	type SendOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type ReserveOrigin = pallet_xcm::EnsureXcm<Nothing>;
+	type RemotePalletIndex = XcmNftsIndexOfSiblings;
+	type RemoteExecutionFee = XcmNftsRemoteExecutionFee;
+	type XcmSender = XcmRouter;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type UniversalLocation = UniversalLocation;
+	type PalletId = XcmNftsPalletId;
+	type MaxAttributes = ConstU32<64>;
+	type WeightInfo = crate::weights::pallet_xcm_nfts::WeightInfo<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = XcmNftsBenchmarkHelper;
+}
+
+/// Benchmark helper for `XcmNfts`.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct XcmNftsBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_xcm_nfts::BenchmarkHelper for XcmNftsBenchmarkHelper {
+	fn reachable_dest() -> Option<Location> {
+		let para_id = 2000;
+		ParachainSystem::open_outbound_hrmp_channel_for_benchmarks_or_tests(para_id.into());
+		Some(Location::new(1, [Parachain(para_id)]))
+	}
+
+	fn foreign_collection() -> Option<Location> {
+		None
+	}
+}
+
 /// Simple conversion of `u32` into an `AssetId` for use in benchmarking.
 pub struct XcmBenchmarkHelper;
 #[cfg(feature = "runtime-benchmarks")]
@@ -32,7 +32,7 @@ use bp_messages::{
 
 use frame_support::{
 	parameter_types,
-	traits::{Equals, PalletInfoAccess},
+	traits::{ConstU32, Equals, PalletInfoAccess},
 };
 use frame_system::{EnsureNever, EnsureRoot};
 use pallet_bridge_messages::LaneIdOf;
@@ -125,6 +125,7 @@ impl pallet_bridge_messages::Config<WithRococoBulletinMessagesInstance> for Runt
 	type DeliveryConfirmationPayments = ();
 
 	type MessageDispatch = XcmOverRococoBulletin;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<128>;
 	type OnMessagesDelivered = XcmOverRococoBulletin;
 }
 
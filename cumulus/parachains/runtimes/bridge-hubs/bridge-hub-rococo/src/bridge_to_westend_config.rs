@@ -33,7 +33,10 @@ use bp_messages::{
 use bridge_hub_common::xcm_version::XcmVersionOfDestAndRemoteBridge;
 use pallet_xcm_bridge_hub::{BridgeId, XcmAsPlainPayload};
 
-use frame_support::{parameter_types, traits::PalletInfoAccess};
+use frame_support::{
+	parameter_types,
+	traits::{ConstU32, PalletInfoAccess},
+};
 use frame_system::{EnsureNever, EnsureRoot};
 use pallet_bridge_messages::LaneIdOf;
 use pallet_bridge_relayers::extension::{
@@ -125,6 +128,7 @@ impl pallet_bridge_messages::Config<WithBridgeHubWestendMessagesInstance> for Ru
 	>;
 
 	type MessageDispatch = XcmOverBridgeHubWestend;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<128>;
 	type OnMessagesDelivered = XcmOverBridgeHubWestend;
 }
 
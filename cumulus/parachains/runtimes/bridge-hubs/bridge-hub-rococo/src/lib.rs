@@ -962,6 +962,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl bp_bridge_hub_westend::FromBridgeHubWestendInboundLaneDispatchApi<Block> for Runtime {
+		fn failed_messages(
+			lane: LaneIdOf<Runtime, bridge_to_westend_config::WithBridgeHubWestendMessagesInstance>,
+		) -> Vec<bp_messages::FailedMessageDispatchDetails> {
+			bridge_runtime_common::messages_api::failed_message_details::<
+				Runtime,
+				bridge_to_westend_config::WithBridgeHubWestendMessagesInstance,
+			>(lane)
+		}
+	}
+
 	// This is exposed by BridgeHubRococo
 	impl bp_bridge_hub_westend::ToBridgeHubWestendOutboundLaneApi<Block> for Runtime {
 		fn message_details(
@@ -1005,6 +1016,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl bp_polkadot_bulletin::FromPolkadotBulletinInboundLaneDispatchApi<Block> for Runtime {
+		fn failed_messages(
+			lane: LaneIdOf<Runtime, bridge_to_bulletin_config::WithRococoBulletinMessagesInstance>,
+		) -> Vec<bp_messages::FailedMessageDispatchDetails> {
+			bridge_runtime_common::messages_api::failed_message_details::<
+				Runtime,
+				bridge_to_bulletin_config::WithRococoBulletinMessagesInstance,
+			>(lane)
+		}
+	}
+
 	impl bp_polkadot_bulletin::ToPolkadotBulletinOutboundLaneApi<Block> for Runtime {
 		fn message_details(
 			lane: LaneIdOf<Runtime, bridge_to_bulletin_config::WithRococoBulletinMessagesInstance>,
@@ -155,6 +155,7 @@ impl pallet_bridge_messages::Config<WithBridgeHubRococoMessagesInstance> for Run
 	>;
 
 	type MessageDispatch = XcmOverBridgeHubRococo;
+	type MaxFailedMessageDispatchesPerLane = ConstU32<128>;
 	type OnMessagesDelivered = XcmOverBridgeHubRococo;
 }
 
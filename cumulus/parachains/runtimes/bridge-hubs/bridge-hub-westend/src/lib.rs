@@ -936,6 +936,17 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl bp_bridge_hub_rococo::FromBridgeHubRococoInboundLaneDispatchApi<Block> for Runtime {
+		fn failed_messages(
+			lane: LaneIdOf<Runtime, bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance>,
+		) -> Vec<bp_messages::FailedMessageDispatchDetails> {
+			bridge_runtime_common::messages_api::failed_message_details::<
+				Runtime,
+				bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance,
+			>(lane)
+		}
+	}
+
 	impl bp_bridge_hub_rococo::ToBridgeHubRococoOutboundLaneApi<Block> for Runtime {
 		fn message_details(
 			lane: LaneIdOf<Runtime, bridge_to_rococo_config::WithBridgeHubRococoMessagesInstance>,
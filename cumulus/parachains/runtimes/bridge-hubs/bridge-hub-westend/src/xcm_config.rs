@@ -258,6 +258,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = PolkadotXcm;
+	type TransferLimiter = ();
 }
 
 pub type PriceForParentDelivery =
@@ -72,6 +72,42 @@ impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: `PolkadotXcm::RemoteFeeTables` (r:0 w:1)
+	/// Proof: `PolkadotXcm::RemoteFeeTables` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_remote_fee_table() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_312_000 picoseconds.
+		Weight::from_parts(10_741_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `PolkadotXcm::OutboundTransferLimits` (r:0 w:1)
+	/// Proof: `PolkadotXcm::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `PolkadotXcm::OutboundTransferWindows` (r:0 w:1)
+	/// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_outbound_transfer_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 12_458_000 picoseconds.
+		Weight::from_parts(12_916_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `PolkadotXcm::OutboundTransferWindows` (r:1 w:1)
+	/// Proof: `PolkadotXcm::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn resume_outbound_transfers() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `163`
+		//  Estimated: `3628`
+		// Minimum execution time: 15_104_000 picoseconds.
+		Weight::from_parts(15_687_000, 0)
+			.saturating_add(Weight::from_parts(0, 3628))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 	/// Storage: `PolkadotXcm::SupportedVersion` (r:1 w:0)
 	/// Proof: `PolkadotXcm::SupportedVersion` (`max_values`: None, `max_size`: None, mode: `Measured`)
 	/// Storage: `ParachainSystem::HostConfiguration` (r:1 w:0)
@@ -30,6 +30,7 @@
 
 pub mod block_weights;
 pub mod extrinsic_weights;
+pub mod pallet_xcm_barrier;
 pub mod paritydb_weights;
 pub mod rocksdb_weights;
 
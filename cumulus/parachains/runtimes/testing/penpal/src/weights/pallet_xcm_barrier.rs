@@ -0,0 +1,51 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Weights for `pallet_xcm_barrier`
+//!
+//! Not benchmarked yet: `set_policy` is weighed as one read and one write of `Policies`, with the
+//! proof size of its maximum encoded length for this runtime's `MaxPolicies` and an estimated ref
+//! time of 25µs. To be overwritten by running:
+//!
+//! frame-omni-bencher v1 benchmark pallet
+//! --extrinsic=*
+//! --runtime=target/production/wbuild/penpal-runtime/penpal_runtime.wasm
+//! --pallet=pallet_xcm_barrier
+//! --header=./cumulus/file_header.txt
+//! --output=./cumulus/parachains/runtimes/testing/penpal/src/weights
+//! --wasm-execution=compiled
+//! --steps=50
+//! --repeat=20
+//! --heap-pages=4096
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use frame_support::{traits::Get, weights::Weight};
+use core::marker::PhantomData;
+
+/// Weight functions for `pallet_xcm_barrier`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: pallet_xcm_barrier::Config> pallet_xcm_barrier::WeightInfo for WeightInfo<T> {
+	/// Storage: `XcmBarrier::Policies` (r:1 w:1)
+	/// Proof: `XcmBarrier::Policies` (`max_values`: Some(1), `max_size`: `MaxPolicies` policies, mode: `MaxEncodedLen`)
+	fn set_policy() -> Weight {
+		Weight::from_parts(25_000_000, pallet_xcm_barrier::weights::policies_proof_size::<T::MaxPolicies>())
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
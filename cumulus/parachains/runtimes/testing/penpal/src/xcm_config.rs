@@ -36,9 +36,9 @@
 //! soon.
 use super::{
 	AccountId, AllPalletsWithSystem, AssetId as AssetIdPalletAssets, Assets, Authorship, Balance,
-	Balances, CollatorSelection, ForeignAssets, ForeignAssetsInstance, NonZeroIssuance,
+	Balances, CollatorSelection, ForeignAssets, ForeignAssetsInstance, Nfts, NonZeroIssuance,
 	ParachainInfo, ParachainSystem, PolkadotXcm, Runtime, RuntimeCall, RuntimeEvent,
-	RuntimeHoldReason, RuntimeOrigin, WeightToFee, XcmpQueue,
+	RuntimeHoldReason, RuntimeOrigin, WeightToFee, XcmBarrier, XcmNfts, XcmpQueue,
 };
 use crate::{BaseDeliveryFee, FeeAssetId, TransactionByteFee};
 use assets_common::TrustBackedAssetsAsLocation;
@@ -47,10 +47,11 @@ use frame_support::{
 	parameter_types,
 	traits::{
 		fungible::HoldConsideration, tokens::imbalance::ResolveAssetTo, ConstU32, Contains,
-		ContainsPair, Equals, Everything, EverythingBut, Get, LinearStoragePrice, Nothing,
-		PalletInfoAccess,
+		ContainsPair, EnsureOrigin, Equals, Everything, EverythingBut, Get, LinearStoragePrice,
+		Nothing, PalletInfoAccess,
 	},
 	weights::Weight,
+	PalletId,
 };
 use frame_system::EnsureRoot;
 use pallet_xcm::{AuthorizedAliasers, XcmPassthrough};
@@ -60,7 +61,9 @@ use parachains_common::{
 };
 use polkadot_parachain_primitives::primitives::Sibling;
 use polkadot_runtime_common::{impls::ToAuthor, xcm_sender::ExponentialPrice};
-use sp_runtime::traits::{AccountIdConversion, ConvertInto, Identity, TryConvertInto};
+use sp_runtime::traits::{
+	AccountIdConversion, ConvertInto, Identity, MaybeConvert, TryConvertInto,
+};
 use testnet_parachains_constants::westend::currency::deposit;
 use xcm::latest::{prelude::*, WESTEND_GENESIS_HASH};
 use xcm_builder::{
@@ -76,7 +79,10 @@ use xcm_builder::{
 	TakeWeightCredit, TrailingSetTopicAsId, UsingComponents, WithComputedOrigin, WithUniqueTopic,
 	XcmFeeManagerFromComponents,
 };
-use xcm_executor::{traits::JustTry, XcmExecutor};
+use xcm_executor::{
+	traits::{JustTry, WeightBounds},
+	XcmExecutor,
+};
 
 parameter_types! {
 	pub const RelayLocation: Location = Location::parent();
@@ -97,6 +103,8 @@ parameter_types! {
 	pub TrustBackedAssetsPalletIndex: u8 = <Assets as PalletInfoAccess>::index() as u8;
 	pub TrustBackedAssetsPalletLocation: Location =
 		PalletInstance(TrustBackedAssetsPalletIndex::get()).into();
+	pub NftsPalletLocation: Location =
+		PalletInstance(<Nfts as PalletInfoAccess>::index() as u8).into();
 }
 
 /// Type for specifying how a `Location` can be converted into an `AccountId`. This is used
@@ -196,7 +204,8 @@ pub type ForeignFungiblesTransactor = FungiblesAdapter<
 >;
 
 /// Means for transacting assets on this chain.
-pub type AssetTransactors = (FungibleTransactor, ForeignFungiblesTransactor, FungiblesTransactor);
+pub type AssetTransactors =
+	(FungibleTransactor, ForeignFungiblesTransactor, FungiblesTransactor, XcmNfts);
 
 /// This is the type we use to convert an (incoming) XCM origin into a local `Origin` instance,
 /// ready for dispatching a transaction with Xcm's `Transact`. There is an `OriginKind` which can
@@ -231,6 +240,46 @@ parameter_types! {
 	pub XcmAssetFeesReceiver: Option<AccountId> = Authorship::author();
 }
 
+/// Weighs messages with [`FixedWeightBounds`], adding the burning of derivative NFTs by `XcmNfts`
+/// for the non-fungible assets withdrawn.
+pub struct XcmWeigher;
+impl WeightBounds<RuntimeCall> for XcmWeigher {
+	fn weight(message: &mut Xcm<RuntimeCall>) -> Result<Weight, ()> {
+		let weight =
+			FixedWeightBounds::<UnitWeightCost, RuntimeCall, MaxInstructions>::weight(message)?;
+		weight.checked_add(&derivative_burns(&message.0)).ok_or(())
+	}
+
+	fn instr_weight(instruction: &mut Instruction<RuntimeCall>) -> Result<Weight, ()> {
+		let weight =
+			FixedWeightBounds::<UnitWeightCost, RuntimeCall, MaxInstructions>::instr_weight(
+				instruction,
+			)?;
+		weight
+			.checked_add(&derivative_burns(core::slice::from_ref(instruction)))
+			.ok_or(())
+	}
+}
+
+/// The weight of burning every non-fungible asset withdrawn by `instructions` as a derivative.
+fn derivative_burns(instructions: &[Instruction<RuntimeCall>]) -> Weight {
+	use pallet_xcm_nfts::WeightInfo as _;
+	instructions.iter().fold(Weight::zero(), |weight, instruction| {
+		let burns = match instruction {
+			WithdrawAsset(assets) => {
+				let count =
+					assets.inner().iter().filter(|a| matches!(a.fun, NonFungible(_))).count();
+				<Runtime as pallet_xcm_nfts::Config>::WeightInfo::burn_derivative()
+					.saturating_mul(count as u64)
+			},
+			SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } =>
+				derivative_burns(&xcm.0),
+			_ => Weight::zero(),
+		};
+		weight.saturating_add(burns)
+	})
+}
+
 pub struct ParentOrParentsExecutivePlurality;
 impl Contains<Location> for ParentOrParentsExecutivePlurality {
 	fn contains(location: &Location) -> bool {
@@ -252,6 +301,8 @@ pub type Barrier = TrailingSetTopicAsId<(
 			AllowSubscriptionsFrom<Everything>,
 			// HRMP notifications from the relay chain are OK.
 			AllowHrmpNotificationsFromRelayChain,
+			// Messages permitted by the policies set by governance are OK.
+			XcmBarrier,
 		),
 		UniversalLocation,
 		ConstU32<8>,
@@ -318,6 +369,16 @@ parameter_types! {
 	pub storage CustomizableAssetFromSystemAssetHub: Location = SystemAssetHubLocation::get();
 }
 
+/// Accepts the items of the `pallet-nfts` collections of sibling parachains, coming from them.
+pub struct NftsFromSiblings;
+impl ContainsPair<Asset, Location> for NftsFromSiblings {
+	fn contains(asset: &Asset, origin: &Location) -> bool {
+		matches!(origin.unpack(), (1, [Parachain(_)])) &&
+			matches!(asset, Asset { id: AssetId(collection), fun: NonFungible(_) }
+			if SiblingNftCollections::contains(collection) && collection.starts_with(origin))
+	}
+}
+
 /// Accepts asset with ID `AssetLocation` and is coming from `Origin` chain.
 pub struct AssetFromChain<AssetLocation, Origin>(PhantomData<(AssetLocation, Origin)>);
 impl<AssetLocation: Get<Location>, Origin: Get<Location>> ContainsPair<Asset, Location>
@@ -335,6 +396,7 @@ pub type TrustedReserves = (
 	ConcreteAssetFromSystem<RelayLocation>,
 	AssetsFrom<SystemAssetHubLocation>,
 	AssetPrefixFrom<CustomizableAssetFromSystemAssetHub, SystemAssetHubLocation>,
+	NftsFromSiblings,
 );
 pub type TrustedTeleporters =
 	(AssetFromChain<LocalTeleportableToAssetHub, SystemAssetHubLocation>,);
@@ -386,7 +448,7 @@ impl xcm_executor::Config for XcmConfig {
 	type IsTeleporter = TrustedTeleporters;
 	type UniversalLocation = UniversalLocation;
 	type Barrier = Barrier;
-	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+	type Weigher = XcmWeigher;
 	type Trader = (
 		UsingComponents<WeightToFee, RelayLocation, AccountId, Balances, ToAuthor<Runtime>>,
 		cumulus_primitives_utility::SwapFirstAssetTrader<
@@ -428,6 +490,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = PolkadotXcm;
+	type TransferLimiter = ();
 }
 
 /// Multiplier used for dedicated `TakeFirstAssetTrader` with `ForeignAssets` instance.
@@ -470,7 +533,7 @@ impl pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 	type XcmTeleportFilter = Everything;
 	type XcmReserveTransferFilter = Everything;
-	type Weigher = FixedWeightBounds<UnitWeightCost, RuntimeCall, MaxInstructions>;
+	type Weigher = XcmWeigher;
 	type UniversalLocation = UniversalLocation;
 	type RuntimeOrigin = RuntimeOrigin;
 	type RuntimeCall = RuntimeCall;
@@ -501,6 +564,92 @@ impl cumulus_pallet_xcm::Config for Runtime {
 	type XcmExecutor = XcmExecutor<XcmConfig>;
 }
 
+impl pallet_xcm_barrier::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type AdminOrigin = EnsureRoot<AccountId>;
+	type MaxPolicies = ConstU32<32>;
+	type WeightInfo = crate::weights::pallet_xcm_barrier::WeightInfo<Runtime>;
+}
+
+parameter_types! {
+	pub const XcmNftsPalletId: PalletId = PalletId(*b"py/xcnft");
+	// 0.01 of the relay token buys the execution of item data on siblings.
+	pub XcmNftsRemoteExecutionFee: Asset = (RelayLocation::get(), 10_000_000_000u128).into();
+}
+
+/// The collections of the `pallet-nfts` instances of sibling parachains.
+pub struct SiblingNftCollections;
+impl Contains<Location> for SiblingNftCollections {
+	fn contains(location: &Location) -> bool {
+		matches!(location.unpack(), (1, [Parachain(_), PalletInstance(_), GeneralIndex(_)]))
+	}
+}
+
+/// Siblings are expected to run `XcmNfts` at the same pallet index as this chain.
+pub struct XcmNftsIndexOfSiblings;
+impl MaybeConvert<Location, u8> for XcmNftsIndexOfSiblings {
+	fn maybe_convert(location: Location) -> Option<u8> {
+		matches!(location.unpack(), (1, [Parachain(_)]))
+			.then(|| <XcmNfts as PalletInfoAccess>::index() as u8)
+	}
+}
+
+/// The `Xcm` origin of sibling parachains, as the reserves of the items mirrored by `XcmNfts`.
+pub struct SiblingReserveOrigin;
+impl EnsureOrigin<RuntimeOrigin> for SiblingReserveOrigin {
+	type Success = Location;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<Location, RuntimeOrigin> {
+		let location = pallet_xcm::EnsureXcm::<Everything>::try_origin(o.clone())?;
+		match location.unpack() {
+			(1, [Parachain(_)]) => Ok(location),
+			_ => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(pallet_xcm::Origin::Xcm(SystemAssetHubLocation::get()).into())
+	}
+}
+
+impl pallet_xcm_nfts::Config for Runtime {
+	type RuntimeEvent = RuntimeEvent;
+	type LocalCollections = AsPrefixedGeneralIndex<NftsPalletLocation, u32, TryConvertInto>;
+	type ForeignCollections = SiblingNftCollections;
+	type ItemInstance = TryConvertInto;
+	type LocationToAccountId = LocationToAccountId;
+	type SendOrigin = EnsureXcmOrigin<RuntimeOrigin, LocalOriginToLocation>;
+	type ReserveOrigin = SiblingReserveOrigin;
+	type RemotePalletIndex = XcmNftsIndexOfSiblings;
+	type RemoteExecutionFee = XcmNftsRemoteExecutionFee;
+	type XcmSender = XcmRouter;
+	type XcmExecutor = XcmExecutor<XcmConfig>;
+	type UniversalLocation = UniversalLocation;
+	type PalletId = XcmNftsPalletId;
+	// Bounds the attributes cleared when burning derivatives, as weighed by `XcmWeigher`.
+	type MaxAttributes = ConstU32<16>;
+	type WeightInfo = pallet_xcm_nfts::weights::SubstrateWeight<Runtime>;
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = XcmNftsBenchmarkHelper;
+}
+
+/// Benchmark helper for `XcmNfts`.
+#[cfg(feature = "runtime-benchmarks")]
+pub struct XcmNftsBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl pallet_xcm_nfts::BenchmarkHelper for XcmNftsBenchmarkHelper {
+	fn reachable_dest() -> Option<Location> {
+		ParachainSystem::open_outbound_hrmp_channel_for_benchmarks_or_tests(ASSET_HUB_ID.into());
+		Some(SystemAssetHubLocation::get())
+	}
+
+	fn foreign_collection() -> Option<Location> {
+		// A collection of the `pallet-nfts` instance of Asset Hub.
+		Some(Location::new(1, [Parachain(ASSET_HUB_ID), PalletInstance(52), GeneralIndex(0)]))
+	}
+}
+
 /// Simple conversion of `u32` into an `AssetId` for use in benchmarking.
 pub struct XcmBenchmarkHelper;
 #[cfg(feature = "runtime-benchmarks")]
@@ -166,6 +166,15 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub dev_block_time: Option<u64>,
 
+	/// Watch the given runtime Wasm file and apply it as a runtime upgrade whenever it changes.
+	///
+	/// This is a dev option, only supported together with `--dev` or `--dev-block-time`. The new
+	/// runtime is written to the state by the next block, keeping the existing state, and its
+	/// `on_runtime_upgrade` hooks are executed in the block after it, even if the `spec_version`
+	/// was not bumped.
+	#[arg(long, value_name = "PATH")]
+	pub dev_watch_runtime: Option<PathBuf>,
+
 	/// DEPRECATED: This feature has been stabilized, pLease use `--authoring slot-based` instead.
 	///
 	/// Use slot-based collator which can handle elastic scaling.
@@ -194,6 +203,13 @@ pub struct Cli<Config: CliConfig> {
 	#[arg(long)]
 	pub export_pov_to_path: Option<PathBuf>,
 
+	/// Index the lifecycle of the XCM messages of imported blocks, as returned by the
+	/// `xcm_messageLifecycle` RPC.
+	///
+	/// Only blocks of runtimes implementing `XcmLifecycleApi` are indexed.
+	#[arg(long)]
+	pub enable_xcm_lifecycle_index: bool,
+
 	/// Relay chain arguments
 	#[arg(raw = true)]
 	pub relay_chain_args: Vec<String>,
@@ -232,6 +248,8 @@ impl<Config: CliConfig> Cli<Config> {
 				.unwrap_or(self.authoring),
 			export_pov: self.export_pov_to_path.clone(),
 			max_pov_percentage: self.run.experimental_max_pov_percentage,
+			enable_xcm_lifecycle_index: self.enable_xcm_lifecycle_index,
+			serve_available_data: self.run.serve_available_data,
 		}
 	}
 }
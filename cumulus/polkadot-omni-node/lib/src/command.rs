@@ -249,16 +249,30 @@ pub fn run<CliConfig: crate::cli::CliConfig>(cmd_config: RunConfig) -> Result<()
 					// TODO: take block time from AURA config if set.
 					let dev_block_time = cli.dev_block_time.unwrap_or(DEFAULT_DEV_BLOCK_TIME_MS);
 					return node_spec
-						.start_manual_seal_node(config, para_id, dev_block_time)
+						.start_manual_seal_node(
+							config,
+							para_id,
+							dev_block_time,
+							cli.dev_watch_runtime.clone(),
+						)
 						.map_err(Into::into);
 				}
 
 				if let Some(dev_block_time) = cli.dev_block_time {
 					return node_spec
-						.start_manual_seal_node(config, para_id, dev_block_time)
+						.start_manual_seal_node(
+							config,
+							para_id,
+							dev_block_time,
+							cli.dev_watch_runtime.clone(),
+						)
 						.map_err(Into::into);
 				}
 
+				if cli.dev_watch_runtime.is_some() {
+					return Err("`--dev-watch-runtime` requires `--dev` or `--dev-block-time`".into())
+				}
+
 				// If Statemint (Statemine, Westmint, Rockmine) DB exists and we're using the
 				// asset-hub chain spec, then rename the base path to the new chain ID. In the case
 				// that both file paths exist, the node will exit, as the user must decide (by
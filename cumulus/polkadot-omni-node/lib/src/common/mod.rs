@@ -27,7 +27,7 @@ pub mod spec;
 pub mod types;
 
 use crate::cli::AuthoringPolicy;
-use cumulus_primitives_core::{CollectCollationInfo, GetCoreSelectorApi};
+use cumulus_primitives_core::{CollectCollationInfo, GetCoreSelectorApi, RelayStorageKeysApi};
 use sc_client_db::DbHash;
 use sc_offchain::OffchainWorkerApi;
 use serde::de::DeserializeOwned;
@@ -40,6 +40,7 @@ use sp_runtime::{
 use sp_session::SessionKeys;
 use sp_transaction_pool::runtime_api::TaggedTransactionQueue;
 use std::{fmt::Debug, path::PathBuf, str::FromStr};
+use xcm_runtime_apis::lifecycle::XcmLifecycleApi;
 
 pub trait NodeBlock:
 	BlockT<Extrinsic = OpaqueExtrinsic, Header = Self::BoundedHeader, Hash = DbHash> + DeserializeOwned
@@ -70,6 +71,8 @@ pub trait NodeRuntimeApi<Block: BlockT>:
 	+ OffchainWorkerApi<Block>
 	+ CollectCollationInfo<Block>
 	+ GetCoreSelectorApi<Block>
+	+ RelayStorageKeysApi<Block>
+	+ XcmLifecycleApi<Block>
 	+ Sized
 {
 }
@@ -82,7 +85,9 @@ impl<T, Block: BlockT> NodeRuntimeApi<Block> for T where
 		+ TaggedTransactionQueue<Block>
 		+ OffchainWorkerApi<Block>
 		+ GetCoreSelectorApi<Block>
+		+ RelayStorageKeysApi<Block>
 		+ CollectCollationInfo<Block>
+		+ XcmLifecycleApi<Block>
 {
 }
 
@@ -116,4 +121,12 @@ pub struct NodeExtraArgs {
 	/// The maximum percentage of the maximum PoV size that the collator can use.
 	/// It will be removed once <https://github.com/paritytech/polkadot-sdk/issues/6020> is fixed.
 	pub max_pov_percentage: Option<u32>,
+
+	/// Index the lifecycle of the XCM messages of imported blocks, as returned by the
+	/// `xcm_messageLifecycle` RPC.
+	pub enable_xcm_lifecycle_index: bool,
+
+	/// If set, the available data of the blocks collated or recovered by the node is kept for
+	/// this number of finalized blocks and served to other parachain nodes.
+	pub serve_available_data: Option<u32>,
 }
@@ -28,6 +28,7 @@ use sp_runtime::traits::Block as BlockT;
 use std::{marker::PhantomData, sync::Arc};
 use substrate_frame_rpc_system::{System, SystemApiServer};
 use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
+use xcm_lifecycle_rpc::{XcmLifecycle, XcmLifecycleApiServer};
 
 /// A type representing all RPC extensions.
 pub type RpcExtension = jsonrpsee::RpcModule<()>;
@@ -67,6 +68,7 @@ where
 			module.merge(System::new(client.clone(), pool).into_rpc())?;
 			module.merge(TransactionPayment::new(client.clone()).into_rpc())?;
 			module.merge(StateMigration::new(client.clone(), backend).into_rpc())?;
+			module.merge(XcmLifecycle::<_, Block>::new(client.clone()).into_rpc())?;
 			module.merge(Dev::new(client).into_rpc())?;
 
 			Ok(module)
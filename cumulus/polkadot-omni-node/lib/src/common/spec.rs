@@ -24,6 +24,7 @@ use crate::common::{
 	ConstructNodeRuntimeApi, NodeBlock, NodeExtraArgs,
 };
 use cumulus_client_cli::CollatorOptions;
+use cumulus_client_pov_recovery::AvailableDataStore;
 use cumulus_client_service::{
 	build_network, build_relay_chain_interface, prepare_node_config, start_relay_chain_tasks,
 	BuildNetworkParams, CollatorSybilResistance, DARecoveryProfile, StartRelayChainTasksParams,
@@ -47,6 +48,7 @@ use sc_transaction_pool::TransactionPoolHandle;
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_keystore::KeystorePtr;
 use std::{future::Future, pin::Pin, sync::Arc, time::Duration};
+use xcm_lifecycle_rpc::LifecycleIndexer;
 
 pub(crate) trait BuildImportQueue<
 	Block: BlockT,
@@ -83,6 +85,7 @@ where
 		announce_block: Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>,
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
+		available_data_store: Option<AvailableDataStore<Block>>,
 		block_import_extra_return_value: BIAuxiliaryData,
 	) -> Result<(), sc_service::Error>;
 }
@@ -285,6 +288,19 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 			.map_err(|e| sc_service::Error::Application(Box::new(e) as Box<_>))?;
 
 			let validator = parachain_config.role.is_authority();
+			let available_data_store = node_extra_args.serve_available_data.map(|keep_blocks| {
+				let (available_data_store, available_data_store_task) = AvailableDataStore::new(
+					client.clone(),
+					relay_chain_interface.clone(),
+					keep_blocks,
+				);
+				task_manager.spawn_handle().spawn_blocking(
+					"cumulus-available-data-store",
+					None,
+					available_data_store_task,
+				);
+				available_data_store
+			});
 			let prometheus_registry = parachain_config.prometheus_registry().cloned();
 			let transaction_pool = params.transaction_pool.clone();
 			let import_queue_service = params.import_queue.service();
@@ -304,6 +320,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 					relay_chain_interface: relay_chain_interface.clone(),
 					import_queue: params.import_queue,
 					sybil_resistance_level: Self::SYBIL_RESISTANCE,
+					serve_available_data: available_data_store.is_some(),
 				})
 				.await?;
 
@@ -357,6 +374,14 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				telemetry: telemetry.as_mut(),
 			})?;
 
+			if node_extra_args.enable_xcm_lifecycle_index {
+				task_manager.spawn_handle().spawn(
+					"xcm-lifecycle-indexer",
+					None,
+					LifecycleIndexer::new(client.clone()).run(),
+				);
+			}
+
 			if let Some(hwbench) = hwbench {
 				sc_sysinfo::print_hwbench(&hwbench);
 				if validator {
@@ -399,6 +424,8 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 				relay_chain_slot_duration,
 				recovery_handle: Box::new(overseer_handle.clone()),
 				sync_service,
+				network: Some(network),
+				available_data_store: available_data_store.clone(),
 			})?;
 
 			if validator {
@@ -418,6 +445,7 @@ pub(crate) trait NodeSpec: BaseNodeSpec {
 					announce_block,
 					backend.clone(),
 					node_extra_args,
+					available_data_store,
 					block_import_auxiliary_data,
 				)?;
 			}
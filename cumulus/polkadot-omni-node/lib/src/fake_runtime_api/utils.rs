@@ -169,6 +169,18 @@ macro_rules! impl_node_runtime_apis {
 				}
 			}
 
+			impl cumulus_primitives_core::RelayStorageKeysApi<$block> for $runtime {
+				fn relay_storage_keys() -> Vec<Vec<u8>> {
+					unimplemented!()
+				}
+			}
+
+			impl xcm_runtime_apis::lifecycle::XcmLifecycleApi<$block> for $runtime {
+				fn xcm_lifecycle_events() -> Vec<xcm_runtime_apis::lifecycle::LifecycleEvent> {
+					unimplemented!()
+				}
+			}
+
 			#[cfg(feature = "try-runtime")]
 			impl frame_try_runtime::TryRuntime<$block> for $runtime {
 				fn on_runtime_upgrade(
@@ -47,6 +47,7 @@ use cumulus_client_consensus_aura::{
 };
 use cumulus_client_consensus_proposer::{Proposer, ProposerInterface};
 use cumulus_client_consensus_relay_chain::Verifier as RelayChainVerifier;
+use cumulus_client_pov_recovery::AvailableDataStore;
 #[allow(deprecated)]
 use cumulus_client_service::CollatorSybilResistance;
 use cumulus_primitives_core::{relay_chain::ValidationCode, ParaId};
@@ -323,6 +324,7 @@ where
 		announce_block: Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>,
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
+		available_data_store: Option<AvailableDataStore<Block>>,
 		block_import_handle: SlotBasedBlockImportHandle<Block>,
 	) -> Result<(), Error> {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::with_proof_recording(
@@ -364,6 +366,7 @@ where
 			spawner: task_manager.spawn_handle(),
 			export_pov: node_extra_args.export_pov,
 			max_pov_percentage: node_extra_args.max_pov_percentage,
+			available_data_store,
 		};
 
 		// We have a separate function only to be able to use `docify::export` on this piece of
@@ -448,6 +451,7 @@ where
 		announce_block: Arc<dyn Fn(Hash, Option<Vec<u8>>) + Send + Sync>,
 		backend: Arc<ParachainBackend<Block>>,
 		node_extra_args: NodeExtraArgs,
+		available_data_store: Option<AvailableDataStore<Block>>,
 		_: (),
 	) -> Result<(), Error> {
 		let proposer_factory = sc_basic_authorship::ProposerFactory::with_proof_recording(
@@ -467,6 +471,7 @@ where
 
 		let params = aura::ParamsWithExport {
 			export_pov: node_extra_args.export_pov,
+			available_data_store,
 			params: AuraParams {
 				create_inherent_data_providers: move |_, ()| async move { Ok(()) },
 				block_import,
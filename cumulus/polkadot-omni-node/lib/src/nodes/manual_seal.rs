@@ -14,10 +14,13 @@
 // See the License for the specific language governing permissions and
 // limitations under the License.
 
-use crate::common::{
-	rpc::BuildRpcExtensions as BuildRpcExtensionsT,
-	spec::{BaseNodeSpec, BuildImportQueue, ClientBlockImport, NodeSpec as NodeSpecT},
-	types::{Hash, ParachainBlockImport, ParachainClient},
+use crate::{
+	common::{
+		rpc::BuildRpcExtensions as BuildRpcExtensionsT,
+		spec::{BaseNodeSpec, BuildImportQueue, ClientBlockImport, NodeSpec as NodeSpecT},
+		types::{Hash, ParachainBlockImport, ParachainClient},
+	},
+	nodes::runtime_reload::{PendingRuntimeSlot, ReloadingProposerFactory, RuntimeWatcher},
 };
 use codec::Encode;
 use cumulus_client_parachain_inherent::{MockValidationDataInherentDataProvider, MockXcmConfig};
@@ -34,7 +37,7 @@ use sc_telemetry::TelemetryHandle;
 use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_api::{ApiExt, ProvideRuntimeApi};
 use sp_runtime::traits::Header;
-use std::{marker::PhantomData, sync::Arc};
+use std::{marker::PhantomData, path::PathBuf, sync::Arc};
 
 pub struct ManualSealNode<NodeSpec>(PhantomData<NodeSpec>);
 
@@ -80,6 +83,7 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 		mut config: Configuration,
 		para_id: ParaId,
 		block_time: u64,
+		watch_runtime: Option<PathBuf>,
 	) -> sc_service::error::Result<TaskManager>
 	where
 		Net: NetworkBackend<NodeSpec::Block, Hash>,
@@ -142,12 +146,26 @@ impl<NodeSpec: NodeSpecT> ManualSealNode<NodeSpec> {
 			);
 		}
 
-		let proposer = sc_basic_authorship::ProposerFactory::new(
-			task_manager.spawn_handle(),
+		let pending_runtime = match watch_runtime {
+			Some(path) => {
+				let watcher = RuntimeWatcher::new(path);
+				let pending_runtime = watcher.pending();
+				task_manager.spawn_handle().spawn("runtime-watcher", None, watcher.run());
+				pending_runtime
+			},
+			None => PendingRuntimeSlot::default(),
+		};
+		let proposer = ReloadingProposerFactory::new(
+			sc_basic_authorship::ProposerFactory::new(
+				task_manager.spawn_handle(),
+				client.clone(),
+				transaction_pool.clone(),
+				None,
+				None,
+			),
 			client.clone(),
-			transaction_pool.clone(),
-			None,
-			None,
+			backend.clone(),
+			pending_runtime,
 		);
 
 		let (manual_seal_sink, manual_seal_stream) = futures::channel::mpsc::channel(1024);
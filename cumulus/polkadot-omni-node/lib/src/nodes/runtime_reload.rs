@@ -0,0 +1,459 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Cumulus.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime hot-reloading for the manual seal dev node.
+//!
+//! A [`RuntimeWatcher`] polls a runtime Wasm file and, whenever it changes, hands the new code to
+//! the [`ReloadingProposerFactory`]. The next block proposed by it then writes the code to
+//! `:code`, so that the block after it is executed by the new runtime, with its
+//! `on_runtime_upgrade` hooks and on top of the existing state.
+//!
+//! Writing `:code` from the node is not something the runtime could verify, which is fine for a
+//! dev node without any peers, but must never be done anywhere else.
+
+use crate::common::runtime::DEFAULT_FRAME_SYSTEM_PALLET_NAME;
+use codec::Decode;
+use cumulus_client_service::ParachainHostFunctions;
+use futures::{future::BoxFuture, FutureExt, TryFutureExt};
+use sc_client_api::{backend::StateBackend, Backend};
+use sc_executor::{RuntimeVersion, WasmExecutor};
+use sc_executor_common::runtime_blob::RuntimeBlob;
+use sc_runtime_utilities::fetch_latest_metadata_from_code_blob;
+use sp_api::CallApiAt;
+use sp_consensus::{Environment, Proposal, Proposer};
+use sp_core::{storage::well_known_keys, twox_128};
+use sp_inherents::InherentData;
+use sp_runtime::{
+	traits::{Block as BlockT, Header as HeaderT},
+	Cow, Digest,
+};
+use sp_storage::ChildInfo;
+use std::{
+	marker::PhantomData,
+	path::PathBuf,
+	sync::{Arc, Mutex},
+	time::{Duration, SystemTime},
+};
+use subxt_metadata::Metadata;
+
+const LOG_TARGET: &str = "runtime-reload";
+
+/// How often the runtime file is checked for changes.
+const POLL_INTERVAL: Duration = Duration::from_secs(1);
+
+/// Name of the storage item of `frame_system` holding the version of the last runtime upgrade.
+const LAST_RUNTIME_UPGRADE: &str = "LastRuntimeUpgrade";
+
+/// A runtime that was picked up by the [`RuntimeWatcher`] but not applied yet.
+pub struct PendingRuntime {
+	code: Vec<u8>,
+	version: RuntimeVersion,
+	last_runtime_upgrade_key: Vec<u8>,
+}
+
+impl PendingRuntime {
+	/// Read the version of the runtime `code` and where it stores its last runtime upgrade.
+	fn new(code: Vec<u8>) -> Result<Self, String> {
+		let blob = RuntimeBlob::uncompress_if_needed(&code).map_err(|e| e.to_string())?;
+		let version = sc_executor::read_embedded_version(&blob)
+			.map_err(|e| e.to_string())?
+			.ok_or("The runtime does not embed its version")?;
+		let last_runtime_upgrade_key = last_runtime_upgrade_key(&code).unwrap_or_else(|err| {
+			log::warn!(
+				target: LOG_TARGET,
+				"Failed to find `{LAST_RUNTIME_UPGRADE}` in the runtime metadata, assuming \
+				`frame-system` is named `{DEFAULT_FRAME_SYSTEM_PALLET_NAME}`: {err}",
+			);
+			storage_key(DEFAULT_FRAME_SYSTEM_PALLET_NAME, LAST_RUNTIME_UPGRADE)
+		});
+
+		Ok(Self { code, version, last_runtime_upgrade_key })
+	}
+}
+
+/// Slot shared between the [`RuntimeWatcher`] and the [`ReloadingProposerFactory`].
+///
+/// Only the latest runtime is kept if it changes multiple times before the next block.
+pub type PendingRuntimeSlot = Arc<Mutex<Option<PendingRuntime>>>;
+
+/// Watches a runtime Wasm file for changes.
+pub struct RuntimeWatcher {
+	path: PathBuf,
+	pending: PendingRuntimeSlot,
+	last_modified: Option<SystemTime>,
+}
+
+impl RuntimeWatcher {
+	/// Create a new watcher for the runtime at `path`.
+	///
+	/// Only changes to the file after the creation of the watcher are picked up.
+	pub fn new(path: PathBuf) -> Self {
+		let last_modified = modified(&path);
+		Self { path, pending: Default::default(), last_modified }
+	}
+
+	/// The slot the new runtime is put into whenever the watched file changes.
+	pub fn pending(&self) -> PendingRuntimeSlot {
+		self.pending.clone()
+	}
+
+	/// Run the watcher.
+	pub async fn run(mut self) {
+		log::info!(
+			target: LOG_TARGET,
+			"👀 Watching {} for runtime changes.",
+			self.path.display(),
+		);
+
+		loop {
+			futures_timer::Delay::new(POLL_INTERVAL).await;
+
+			let last_modified = modified(&self.path);
+			if last_modified.is_none() || last_modified == self.last_modified {
+				continue
+			}
+
+			// The file may still be in the middle of being written. Then reading it fails, and
+			// we try again once it is modified for the last time.
+			match read_runtime(&self.path) {
+				Ok(runtime) => {
+					log::info!(
+						target: LOG_TARGET,
+						"🔁 Runtime {}-{} changed, applying it with the next block.",
+						runtime.version.spec_name,
+						runtime.version.spec_version,
+					);
+					*self.pending.lock().expect("Poisoned lock") = Some(runtime);
+				},
+				Err(err) => log::warn!(
+					target: LOG_TARGET,
+					"Failed to read runtime from {}: {err}",
+					self.path.display(),
+				),
+			}
+			self.last_modified = last_modified;
+		}
+	}
+}
+
+fn modified(path: &PathBuf) -> Option<SystemTime> {
+	std::fs::metadata(path).and_then(|metadata| metadata.modified()).ok()
+}
+
+fn read_runtime(path: &PathBuf) -> Result<PendingRuntime, String> {
+	let code = std::fs::read(path).map_err(|e| e.to_string())?;
+	PendingRuntime::new(code)
+}
+
+/// Storage key of `frame_system::LastRuntimeUpgrade` in the runtime `code`, under the storage
+/// prefix of the pallet holding it in the metadata, i.e. the name `frame-system` has in the
+/// runtime.
+///
+/// Removing it makes `frame_executive` run `on_runtime_upgrade`, even if the `spec_version` was
+/// not bumped.
+fn last_runtime_upgrade_key(code: &[u8]) -> Result<Vec<u8>, String> {
+	let opaque_metadata = fetch_latest_metadata_from_code_blob(
+		&WasmExecutor::<ParachainHostFunctions>::builder()
+			.with_allow_missing_host_functions(true)
+			.build(),
+		Cow::Borrowed(code),
+	)
+	.map_err(|err| err.to_string())?;
+	let metadata =
+		Metadata::decode(&mut (*opaque_metadata).as_slice()).map_err(|err| err.to_string())?;
+
+	let prefix = metadata
+		.pallets()
+		.filter_map(|pallet| pallet.storage())
+		.find(|storage| storage.entry_by_name(LAST_RUNTIME_UPGRADE).is_some())
+		.map(|storage| storage.prefix())
+		.ok_or("No pallet has a storage item with this name")?;
+	Ok(storage_key(prefix, LAST_RUNTIME_UPGRADE))
+}
+
+fn storage_key(prefix: &str, name: &str) -> Vec<u8> {
+	[twox_128(prefix.as_bytes()), twox_128(name.as_bytes())].concat()
+}
+
+/// Wraps an [`Environment`] to apply the runtimes picked up by a [`RuntimeWatcher`].
+pub struct ReloadingProposerFactory<Block, E, Client, BE> {
+	inner: E,
+	client: Arc<Client>,
+	backend: Arc<BE>,
+	pending: PendingRuntimeSlot,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, E, Client, BE> ReloadingProposerFactory<Block, E, Client, BE> {
+	/// Create a new instance, applying the runtimes put into `pending`.
+	pub fn new(
+		inner: E,
+		client: Arc<Client>,
+		backend: Arc<BE>,
+		pending: PendingRuntimeSlot,
+	) -> Self {
+		Self { inner, client, backend, pending, _phantom: PhantomData }
+	}
+}
+
+impl<Block, E, Client, BE> Environment<Block> for ReloadingProposerFactory<Block, E, Client, BE>
+where
+	Block: BlockT,
+	E: Environment<Block>,
+	Client: CallApiAt<Block> + Send + Sync + 'static,
+	BE: Backend<Block> + 'static,
+{
+	type Proposer = ReloadingProposer<Block, E::Proposer, Client, BE>;
+	type CreateProposer = BoxFuture<'static, Result<Self::Proposer, Self::Error>>;
+	type Error = E::Error;
+
+	fn init(&mut self, parent_header: &Block::Header) -> Self::CreateProposer {
+		let client = self.client.clone();
+		let backend = self.backend.clone();
+		let pending = self.pending.clone();
+		let parent_hash = parent_header.hash();
+
+		self.inner
+			.init(parent_header)
+			.map_ok(move |inner| ReloadingProposer { inner, client, backend, pending, parent_hash })
+			.boxed()
+	}
+}
+
+/// Proposer of the [`ReloadingProposerFactory`].
+pub struct ReloadingProposer<Block: BlockT, P, Client, BE> {
+	inner: P,
+	client: Arc<Client>,
+	backend: Arc<BE>,
+	pending: PendingRuntimeSlot,
+	parent_hash: Block::Hash,
+}
+
+impl<Block, P, Client, BE> Proposer<Block> for ReloadingProposer<Block, P, Client, BE>
+where
+	Block: BlockT,
+	P: Proposer<Block>,
+	Client: CallApiAt<Block> + Send + Sync + 'static,
+	BE: Backend<Block> + 'static,
+{
+	type Error = P::Error;
+	type Proposal = BoxFuture<'static, Result<Proposal<Block, Self::Proof>, Self::Error>>;
+	type ProofRecording = P::ProofRecording;
+	type Proof = P::Proof;
+
+	fn propose(
+		self,
+		inherent_data: InherentData,
+		inherent_digests: Digest,
+		max_duration: Duration,
+		block_size_limit: Option<usize>,
+	) -> Self::Proposal {
+		let proposal =
+			self.inner
+				.propose(inherent_data, inherent_digests, max_duration, block_size_limit);
+		let Some(runtime) = self.pending.lock().expect("Poisoned lock").take() else {
+			return proposal.boxed()
+		};
+
+		let (client, backend, parent_hash, pending) =
+			(self.client, self.backend, self.parent_hash, self.pending);
+		async move {
+			let proposal = match proposal.await {
+				Ok(proposal) => proposal,
+				Err(err) => {
+					// Apply the runtime with the next block that is proposed successfully, unless
+					// another runtime was picked up in the meantime.
+					pending.lock().expect("Poisoned lock").get_or_insert(runtime);
+					return Err(err)
+				},
+			};
+			apply_runtime(&*client, &*backend, parent_hash, proposal, runtime).map_err(Into::into)
+		}
+		.boxed()
+	}
+}
+
+/// Write the code of `runtime` to the state of the block in `proposal`.
+fn apply_runtime<Block, Proof, Client, BE>(
+	client: &Client,
+	backend: &BE,
+	parent_hash: Block::Hash,
+	mut proposal: Proposal<Block, Proof>,
+	runtime: PendingRuntime,
+) -> Result<Proposal<Block, Proof>, sp_consensus::Error>
+where
+	Block: BlockT,
+	Client: CallApiAt<Block>,
+	BE: Backend<Block>,
+{
+	let current_version = client
+		.runtime_version_at(parent_hash)
+		.map_err(|e| sp_consensus::Error::StateUnavailable(e.to_string()))?;
+	if current_version.spec_name != runtime.version.spec_name {
+		log::error!(
+			target: LOG_TARGET,
+			"Not applying runtime {}, as the chain runs {}.",
+			runtime.version.spec_name,
+			current_version.spec_name,
+		);
+		return Ok(proposal)
+	}
+
+	let state = backend
+		.state_at(parent_hash)
+		.map_err(|e| sp_consensus::Error::StateUnavailable(e.to_string()))?;
+
+	let PendingRuntime { code, version, last_runtime_upgrade_key } = runtime;
+	let changes = &mut proposal.storage_changes;
+	changes
+		.main_storage_changes
+		.retain(|(key, _)| key != well_known_keys::CODE && *key != last_runtime_upgrade_key);
+	changes.main_storage_changes.push((well_known_keys::CODE.to_vec(), Some(code)));
+	changes.main_storage_changes.push((last_runtime_upgrade_key, None));
+
+	let child_infos = changes
+		.child_storage_changes
+		.iter()
+		.map(|(storage_key, _)| ChildInfo::new_default(storage_key))
+		.collect::<Vec<_>>();
+	let (state_root, transaction) = state.full_storage_root(
+		changes.main_storage_changes.iter().map(|(k, v)| (&k[..], v.as_deref())),
+		child_infos
+			.iter()
+			.zip(&changes.child_storage_changes)
+			.map(|(info, (_, changes))| {
+				(info, changes.iter().map(|(k, v)| (&k[..], v.as_deref())))
+			}),
+		current_version.state_version(),
+	);
+	changes.transaction = transaction;
+	changes.transaction_storage_root = state_root;
+
+	let (mut header, extrinsics) = proposal.block.deconstruct();
+	header.set_state_root(state_root);
+	log::info!(
+		target: LOG_TARGET,
+		"✨ Runtime {}-{} written in block #{}, it is used from the next block on.",
+		version.spec_name,
+		version.spec_version,
+		header.number(),
+	);
+	proposal.block = Block::new(header, extrinsics);
+
+	Ok(proposal)
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use futures::{
+		channel::{mpsc, oneshot},
+		SinkExt,
+	};
+	use sc_basic_authorship::ProposerFactory;
+	use sc_client_api::StorageProvider;
+	use sc_consensus_manual_seal::{run_manual_seal, EngineCommand, ManualSealParams};
+	use sc_transaction_pool::{BasicPool, FullChainApi, Options, RevalidationType};
+	use sp_storage::StorageKey;
+	use substrate_test_runtime_client::{
+		runtime::{wasm_binary_logging_disabled_unwrap, Hash},
+		LongestChain, TestClientBuilder, TestClientBuilderExt,
+	};
+
+	async fn seal_block(sink: &mut mpsc::Sender<EngineCommand<Hash>>) -> Hash {
+		let (sender, receiver) = oneshot::channel();
+		sink.send(EngineCommand::SealNewBlock {
+			create_empty: true,
+			finalize: false,
+			parent_hash: None,
+			sender: Some(sender),
+		})
+		.await
+		.unwrap();
+
+		receiver.await.unwrap().unwrap().hash
+	}
+
+	#[tokio::test]
+	async fn runtime_is_upgraded_on_top_of_existing_state() {
+		let (client, backend) = TestClientBuilder::new()
+			.add_extra_storage(b"kept".to_vec(), b"value".to_vec())
+			.build_with_backend();
+		let client = Arc::new(client);
+		let select_chain = LongestChain::new(backend.clone());
+		let spawner = sp_core::testing::TaskExecutor::new();
+		let genesis_hash = client.chain_info().genesis_hash;
+		let pool_api = Arc::new(FullChainApi::new(client.clone(), None, &spawner.clone()));
+		let pool = Arc::new(BasicPool::with_revalidation_type(
+			Options::default(),
+			true.into(),
+			pool_api,
+			None,
+			RevalidationType::Full,
+			spawner.clone(),
+			0,
+			genesis_hash,
+			genesis_hash,
+		));
+		let pending = PendingRuntimeSlot::default();
+		let env = ReloadingProposerFactory::new(
+			ProposerFactory::new(spawner.clone(), client.clone(), pool.clone(), None, None),
+			client.clone(),
+			backend.clone(),
+			pending.clone(),
+		);
+		let (mut sink, commands_stream) = mpsc::channel(1024);
+		tokio::spawn(run_manual_seal(ManualSealParams {
+			block_import: client.clone(),
+			env,
+			client: client.clone(),
+			pool,
+			commands_stream,
+			select_chain,
+			consensus_data_provider: None,
+			create_inherent_data_providers: |_, _| async { Ok(()) },
+		}));
+
+		let storage = |hash: Hash, key: &[u8]| {
+			client.storage(hash, &StorageKey(key.to_vec())).unwrap().map(|data| data.0)
+		};
+
+		let code = wasm_binary_logging_disabled_unwrap().to_vec();
+		// The key is found in the metadata of the runtime.
+		assert_eq!(
+			last_runtime_upgrade_key(&code).unwrap(),
+			storage_key("System", LAST_RUNTIME_UPGRADE),
+		);
+		let runtime = PendingRuntime::new(code.clone()).unwrap();
+		let last_runtime_upgrade_key = runtime.last_runtime_upgrade_key.clone();
+		assert_ne!(storage(genesis_hash, well_known_keys::CODE), Some(code.clone()));
+		assert!(storage(genesis_hash, &last_runtime_upgrade_key).is_some());
+
+		*pending.lock().unwrap() = Some(runtime);
+
+		// The next block writes the new runtime to the state.
+		let hash = seal_block(&mut sink).await;
+		assert!(pending.lock().unwrap().is_none());
+		assert_eq!(storage(hash, well_known_keys::CODE), Some(code.clone()));
+		assert_eq!(storage(hash, &last_runtime_upgrade_key), None);
+
+		// The block after it is executed by the new runtime, which runs `on_runtime_upgrade`.
+		let hash = seal_block(&mut sink).await;
+		assert_eq!(storage(hash, well_known_keys::CODE), Some(code));
+		assert!(storage(hash, &last_runtime_upgrade_key).is_some());
+		assert_eq!(storage(hash, b"kept"), Some(b"value".to_vec()));
+	}
+}
@@ -431,4 +431,14 @@ sp_api::decl_runtime_apis! {
 		/// Retrieve core selector and claim queue offset for the next block.
 		fn core_selector() -> (CoreSelector, ClaimQueueOffset);
 	}
+
+	/// Runtime api to request relay chain storage to be proven in the parachain inherent.
+	pub trait RelayStorageKeysApi {
+		/// The relay chain storage keys which should be included in the relay chain state proof of
+		/// the next block, in addition to the keys read by the parachain system pallet.
+		///
+		/// The collator bounds the number and the length of the keys. Keys beyond these bounds
+		/// are not proven.
+		fn relay_storage_keys() -> Vec<Vec<u8>>;
+	}
 }
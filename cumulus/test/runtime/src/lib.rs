@@ -571,6 +571,12 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl cumulus_primitives_core::RelayStorageKeysApi<Block> for Runtime {
+		fn relay_storage_keys() -> Vec<Vec<u8>> {
+			Vec::new()
+		}
+	}
+
 	impl sp_genesis_builder::GenesisBuilder<Block> for Runtime {
 		fn build_state(config: Vec<u8>) -> sp_genesis_builder::Result {
 			build_state::<RuntimeGenesisConfig>(config)
@@ -388,6 +388,7 @@ where
 			                                                             * resistant or null that
 			                                                             * is not producing any
 			                                                             * blocks at all. */
+			serve_available_data: false,
 		})
 		.await?;
 
@@ -447,6 +448,8 @@ where
 		relay_chain_slot_duration,
 		recovery_handle,
 		sync_service: sync_service.clone(),
+		network: Some(network.clone()),
+		available_data_store: None,
 	})?;
 
 	if let Some(collator_key) = collator_key {
@@ -509,6 +512,7 @@ where
 					spawner: task_manager.spawn_handle(),
 					export_pov: None,
 					max_pov_percentage: None,
+					available_data_store: None,
 				};
 
 				slot_based::run::<Block, AuthorityPair, _, _, _, _, _, _, _, _, _>(params);
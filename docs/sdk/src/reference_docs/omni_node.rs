@@ -102,6 +102,8 @@
 //! * For local development, the following options are available:
 //!     * `polkadot-omni-node` (notably, with [`--dev-block-time`]). References:
 //!         * [`crate::guides::your_first_node`]
+//!         * [`--dev-watch-runtime`] applies a rebuilt runtime to the running dev chain, keeping
+//!           its state.
 //!     * External tools such as `chopsticks`, `zombienet`.
 //!         * See the `README.md` file of the `polkadot-sdk-parachain-template`.
 //! * For production `polkadot-omni-node` can be used out of the box.
@@ -194,6 +196,7 @@
 //! [`templates`]: crate::polkadot_sdk::templates
 //! [`parachain-template`]: https://github.com/paritytech/polkadot-sdk-parachain-template
 //! [`--dev-block-time`]: polkadot_omni_node_lib::cli::Cli::dev_block_time
+//! [`--dev-watch-runtime`]: polkadot_omni_node_lib::cli::Cli::dev_watch_runtime
 //! [`polkadot-omni-node`]: https://crates.io/crates/polkadot-omni-node
 //! [`chain-spec-builder`]: https://crates.io/crates/staging-chain-spec-builder
 //! [`cumulus-pallet-parachain-system`]: https://docs.rs/cumulus-pallet-parachain-system/latest/cumulus_pallet_parachain_system/
@@ -142,6 +142,12 @@ pub struct RunCmd {
 	///  **Dangerous!** Do not touch unless explicitly advised to.
 	#[arg(long)]
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// The number of threads the erasure coding of a single large PoV may be split across.
+	///
+	/// The threads are shared by the availability store and availability recovery. By default,
+	/// each PoV is coded on a single thread.
+	#[arg(long, value_name = "THREADS")]
+	pub erasure_coding_threads: Option<usize>,
 	/// TESTING ONLY: disable the version check between nodes and workers.
 	#[arg(long, hide = true)]
 	pub disable_worker_version_check: bool,
@@ -158,6 +164,23 @@ pub struct RunCmd {
 	/// networks.
 	#[arg(long)]
 	pub keep_finalized_for: Option<u32>,
+
+	/// Archive the available data (PoVs) of the given para in the availability store, instead of
+	/// pruning it after `--keep-finalized-for`. Can be passed multiple times.
+	///
+	/// Only supported on validators, which recover the data from the network if they don't have it.
+	#[arg(long = "availability-archive-para", value_name = "PARA_ID")]
+	pub availability_archive_paras: Vec<u32>,
+
+	/// How long archived available data should be kept in the availability store (in hours).
+	/// If not specified, it is kept indefinitely.
+	#[arg(long, requires = "availability_archive_paras")]
+	pub availability_archive_keep_for: Option<u32>,
+
+	/// Index the lifecycle of the XCM messages of imported blocks, as returned by the
+	/// `xcm_messageLifecycle` RPC.
+	#[arg(long)]
+	pub enable_xcm_lifecycle_index: bool,
 }
 
 #[allow(missing_docs)]
@@ -34,6 +34,7 @@ use sp_keyring::Sr25519Keyring;
 pub use crate::error::Error;
 #[cfg(feature = "pyroscope")]
 use std::net::ToSocketAddrs;
+use std::time::Duration;
 
 type Result<T> = std::result::Result<T, Error>;
 
@@ -203,6 +204,13 @@ where
 
 	let secure_validator_mode = cli.run.base.validator && !cli.run.insecure_validator;
 
+	// Only validators run the availability store and recovery subsystems archiving the data.
+	if !cli.run.availability_archive_paras.is_empty() && !runner.config().role.is_authority() {
+		return Err(Error::Other(
+			"`--availability-archive-para` is only supported on validators".into(),
+		))
+	}
+
 	runner.run_node_until_exit(move |config| async move {
 		let hwbench = (!cli.run.no_hardware_benchmarks)
 			.then(|| {
@@ -234,8 +242,22 @@ where
 				execute_workers_max_num: cli.run.execute_workers_max_num,
 				prepare_workers_hard_max_num: cli.run.prepare_workers_hard_max_num,
 				prepare_workers_soft_max_num: cli.run.prepare_workers_soft_max_num,
+				erasure_coding_threads: cli.run.erasure_coding_threads,
 				enable_approval_voting_parallel: cli.run.enable_approval_voting_parallel,
 				keep_finalized_for: cli.run.keep_finalized_for,
+				availability_archive_config: polkadot_service::AvailabilityArchiveConfig {
+					paras: cli
+						.run
+						.availability_archive_paras
+						.iter()
+						.map(|id| (*id).into())
+						.collect(),
+					keep_for: cli
+						.run
+						.availability_archive_keep_for
+						.map(|hours| Duration::from_secs(hours as u64 * 3600)),
+				},
+				enable_xcm_lifecycle_index: cli.run.enable_xcm_lifecycle_index,
 			},
 		)
 		.map(|full| full.task_manager)?;
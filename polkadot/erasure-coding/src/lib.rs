@@ -32,13 +32,22 @@ use sp_trie::{
 	trie_types::{TrieDBBuilder, TrieDBMutBuilderV0 as TrieDBMutBuilder},
 	LayoutV0, MemoryDB, Trie, TrieMut, EMPTY_PREFIX,
 };
+use std::{cmp, ops::Range, sync::Arc};
 use thiserror::Error;
 
 use novelpoly::{CodeParams, WrappedShard};
 
+mod thread_pool;
+
+pub use thread_pool::ThreadPool;
+
 // we are limited to the field order of GF(2^16), which is 65536
 const MAX_VALIDATORS: usize = novelpoly::f2e16::FIELD_SIZE;
 
+/// Payloads smaller than this are always coded on the calling thread, as splitting them up is not
+/// worth the cost of handing them to other threads.
+const MIN_PARALLEL_PAYLOAD_SIZE: usize = 1024 * 1024;
+
 /// Errors in erasure coding.
 #[derive(Debug, Clone, PartialEq, Error)]
 pub enum Error {
@@ -191,12 +200,16 @@ pub fn obtain_chunks<T: Encode>(n_validators: usize, data: &T) -> Result<Vec<Vec
 		return Err(Error::BadPayload)
 	}
 
+	Ok(encode_payload(&params, &encoded))
+}
+
+fn encode_payload(params: &CodeParams, payload: &[u8]) -> Vec<Vec<u8>> {
 	let shards = params
 		.make_encoder()
-		.encode::<WrappedShard>(&encoded[..])
+		.encode::<WrappedShard>(payload)
 		.expect("Payload non-empty, shard sizes are uniform, and validator numbers checked; qed");
 
-	Ok(shards.into_iter().map(|w: WrappedShard| w.into_inner()).collect())
+	shards.into_iter().map(|w: WrappedShard| w.into_inner()).collect()
 }
 
 /// Reconstruct the v1 available data from a set of chunks.
@@ -239,6 +252,216 @@ where
 	Decode::decode(&mut &payload_bytes[..]).map_err(|_| Error::BadPayload)
 }
 
+/// Obtain erasure-coded chunks for v1 `AvailableData`, one for each validator, using the threads
+/// of the `pool`.
+///
+/// The chunks are identical to the ones returned by [`obtain_chunks_v1`].
+pub fn obtain_chunks_v1_parallel(
+	n_validators: usize,
+	data: &AvailableData,
+	pool: &ThreadPool,
+) -> Result<Vec<Vec<u8>>, Error> {
+	obtain_chunks_parallel(n_validators, data, pool)
+}
+
+/// Obtain erasure-coded chunks, one for each validator, using the threads of the `pool`.
+///
+/// Every `2 * k` bytes of the encoded payload are coded independently into one symbol of each
+/// chunk, so the payload is split into runs of whole pieces that are encoded concurrently and
+/// concatenated afterwards. The chunks are identical to the ones returned by [`obtain_chunks`].
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn obtain_chunks_parallel<T: Encode>(
+	n_validators: usize,
+	data: &T,
+	pool: &ThreadPool,
+) -> Result<Vec<Vec<u8>>, Error> {
+	let params = code_params(n_validators)?;
+	let encoded = data.encode();
+
+	if encoded.is_empty() {
+		return Err(Error::BadPayload)
+	}
+
+	let segments = parallel_segments(encoded.len(), 2 * params.k(), pool.threads());
+	if segments.len() <= 1 {
+		return Ok(encode_payload(&params, &encoded))
+	}
+
+	let encoded = Arc::new(encoded);
+	let encoded_segments =
+		pool.run(segments, move |segment| encode_payload(&params, &encoded[segment]));
+
+	let mut chunks = vec![Vec::new(); n_validators];
+	for shards in encoded_segments {
+		for (chunk, shard) in chunks.iter_mut().zip(shards) {
+			chunk.extend(shard);
+		}
+	}
+
+	Ok(chunks)
+}
+
+/// Reconstruct the v1 available data from a set of chunks, using the threads of the `pool`.
+///
+/// See [`reconstruct_v1`].
+pub fn reconstruct_v1_parallel<'a, I: 'a>(
+	n_validators: usize,
+	chunks: I,
+	pool: &ThreadPool,
+) -> Result<AvailableData, Error>
+where
+	I: IntoIterator<Item = (&'a [u8], usize)>,
+{
+	reconstruct_parallel(n_validators, chunks, pool)
+}
+
+/// Reconstruct decodable data from a set of chunks, using the threads of the `pool`.
+///
+/// The chunks are split into runs of symbols which are reconstructed concurrently. The result is
+/// identical to the one returned by [`reconstruct`].
+///
+/// Works only up to 65536 validators, and `n_validators` must be non-zero.
+pub fn reconstruct_parallel<'a, I: 'a, T: Decode>(
+	n_validators: usize,
+	chunks: I,
+	pool: &ThreadPool,
+) -> Result<T, Error>
+where
+	I: IntoIterator<Item = (&'a [u8], usize)>,
+{
+	let params = code_params(n_validators)?;
+	let mut received_shards: Vec<Option<&[u8]>> = vec![None; n_validators];
+	for (chunk_data, chunk_idx) in chunks.into_iter().take(n_validators) {
+		if chunk_data.len() % 2 != 0 {
+			return Err(Error::UnevenLength)
+		}
+
+		received_shards[chunk_idx] = Some(chunk_data);
+	}
+
+	let segments = uniform_shard_len(received_shards.iter().flatten().copied())
+		.map(|shard_len| parallel_segments(shard_len * params.k(), 2 * params.k(), pool.threads()))
+		.unwrap_or_default();
+	if segments.len() <= 1 {
+		return reconstruct(
+			n_validators,
+			received_shards
+				.into_iter()
+				.enumerate()
+				.filter_map(|(idx, shard)| shard.map(|shard| (shard, idx))),
+		)
+	}
+
+	let segment_shards = segments
+		.iter()
+		.map(|segment| {
+			let symbols = shard_range(segment, params.k());
+			received_shards
+				.iter()
+				.map(|shard| shard.map(|shard| WrappedShard::new(shard[symbols.clone()].to_vec())))
+				.collect::<Vec<_>>()
+		})
+		.collect();
+
+	let payload_bytes = pool
+		.run(segment_shards, move |shards| params.make_encoder().reconstruct(shards))
+		.into_iter()
+		.collect::<Result<Vec<_>, _>>()?
+		.concat();
+
+	Decode::decode(&mut &payload_bytes[..]).map_err(|_| Error::BadPayload)
+}
+
+/// Reconstruct the v1 available data from the set of systematic chunks, using the threads of the
+/// `pool`.
+///
+/// See [`reconstruct_from_systematic_v1`].
+pub fn reconstruct_from_systematic_v1_parallel(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+	pool: &ThreadPool,
+) -> Result<AvailableData, Error> {
+	reconstruct_from_systematic_parallel(n_validators, chunks, pool)
+}
+
+/// Reconstruct the available data from the set of systematic chunks, using the threads of the
+/// `pool`.
+///
+/// The result is identical to the one returned by [`reconstruct_from_systematic`].
+pub fn reconstruct_from_systematic_parallel<T: Decode>(
+	n_validators: usize,
+	chunks: Vec<Vec<u8>>,
+	pool: &ThreadPool,
+) -> Result<T, Error> {
+	let code_params = code_params(n_validators)?;
+	let k = code_params.k();
+
+	let segments = match chunks.get(..k) {
+		Some(systematic_chunks) if systematic_chunks.iter().all(|chunk| chunk.len() % 2 == 0) =>
+			uniform_shard_len(systematic_chunks.iter().map(|chunk| chunk.as_slice()))
+				.map(|shard_len| parallel_segments(shard_len * k, 2 * k, pool.threads()))
+				.unwrap_or_default(),
+		_ => Vec::new(),
+	};
+	if segments.len() <= 1 {
+		return reconstruct_from_systematic(n_validators, chunks)
+	}
+
+	let segment_shards = segments
+		.iter()
+		.map(|segment| {
+			let symbols = shard_range(segment, k);
+			chunks
+				.iter()
+				.take(k)
+				.map(|chunk| WrappedShard::new(chunk[symbols.clone()].to_vec()))
+				.collect::<Vec<_>>()
+		})
+		.collect();
+
+	let bytes = pool
+		.run(segment_shards, move |shards| {
+			code_params.make_encoder().reconstruct_from_systematic(shards)
+		})
+		.into_iter()
+		.collect::<Result<Vec<_>, _>>()?
+		.concat();
+
+	Decode::decode(&mut &bytes[..]).map_err(|err| Error::Decode(err))
+}
+
+/// Split `len` bytes of payload into at most `threads` contiguous segments, each made of whole
+/// pieces of `piece_len` bytes except for possibly the last one.
+///
+/// Returns a single segment if the payload is too small to be worth coding in parallel.
+fn parallel_segments(len: usize, piece_len: usize, threads: usize) -> Vec<Range<usize>> {
+	if threads <= 1 || len < MIN_PARALLEL_PAYLOAD_SIZE {
+		return vec![0..len]
+	}
+
+	let pieces_per_segment = len.div_ceil(piece_len).div_ceil(threads);
+	let segment_len = pieces_per_segment * piece_len;
+
+	(0..len)
+		.step_by(segment_len)
+		.map(|start| start..cmp::min(start + segment_len, len))
+		.collect()
+}
+
+/// The byte range of each shard holding the symbols of the given payload segment.
+///
+/// Each piece of `2 * k` payload bytes maps to a single 2-byte symbol in every shard.
+fn shard_range(segment: &Range<usize>, k: usize) -> Range<usize> {
+	(segment.start / k)..(segment.end.div_ceil(2 * k) * 2)
+}
+
+/// The length shared by all given shards, if they are non-empty and of uniform length.
+fn uniform_shard_len<'a>(mut shards: impl Iterator<Item = &'a [u8]>) -> Option<usize> {
+	let len = shards.next()?.len();
+	(len != 0 && shards.all(|shard| shard.len() == len)).then_some(len)
+}
+
 /// An iterator that yields merkle branches and chunk data for all chunks to
 /// be sent to other validators.
 pub struct Branches<'a, I> {
@@ -287,6 +510,45 @@ impl<'a, I: AsRef<[u8]>> Iterator for Branches<'a, I> {
 /// Construct a trie from chunks of an erasure-coded value. This returns the root hash and an
 /// iterator of merkle proofs, one for each validator.
 pub fn branches<'a, I: 'a>(chunks: &'a [I]) -> Branches<'a, I>
+where
+	I: AsRef<[u8]>,
+{
+	let chunk_hashes = chunks.iter().map(|chunk| BlakeTwo256::hash(chunk.as_ref())).collect();
+
+	branches_from_hashes(chunks, chunk_hashes)
+}
+
+/// Construct a trie from chunks of an erasure-coded value, like [`branches`], hashing the chunks
+/// in batches on the threads of the `pool`.
+///
+/// The resulting root and proofs are identical to the ones returned by [`branches`].
+pub fn branches_parallel<'a, I: 'a>(chunks: &'a [I], pool: &ThreadPool) -> Branches<'a, I>
+where
+	I: AsRef<[u8]>,
+{
+	let total_len: usize = chunks.iter().map(|chunk| chunk.as_ref().len()).sum();
+	if pool.threads() <= 1 || total_len < MIN_PARALLEL_PAYLOAD_SIZE {
+		return branches(chunks)
+	}
+
+	// The threads of the pool can't borrow the chunks, so each batch is copied to them.
+	let batch_size = chunks.len().div_ceil(pool.threads());
+	let batches = chunks
+		.chunks(batch_size)
+		.map(|batch| batch.iter().map(|chunk| chunk.as_ref().to_vec()).collect::<Vec<_>>())
+		.collect();
+	let chunk_hashes = pool
+		.run(batches, |batch| {
+			batch.iter().map(|chunk| BlakeTwo256::hash(chunk)).collect::<Vec<_>>()
+		})
+		.into_iter()
+		.flatten()
+		.collect();
+
+	branches_from_hashes(chunks, chunk_hashes)
+}
+
+fn branches_from_hashes<'a, I: 'a>(chunks: &'a [I], chunk_hashes: Vec<H256>) -> Branches<'a, I>
 where
 	I: AsRef<[u8]>,
 {
@@ -296,9 +558,8 @@ where
 	// construct trie mapping each chunk's index to its hash.
 	{
 		let mut trie = TrieDBMutBuilder::new(&mut trie_storage, &mut root).build();
-		for (i, chunk) in chunks.as_ref().iter().enumerate() {
+		for (i, chunk_hash) in chunk_hashes.iter().enumerate() {
 			(i as u32).using_encoded(|encoded_index| {
-				let chunk_hash = BlakeTwo256::hash(chunk.as_ref());
 				trie.insert(encoded_index, chunk_hash.as_ref())
 					.expect("a fresh trie stored in memory cannot have errors loading nodes; qed");
 			})
@@ -411,6 +672,71 @@ mod tests {
 		QuickCheck::new().quickcheck(property as fn(ArbitraryAvailableData, u16))
 	}
 
+	#[test]
+	fn parallel_coding_matches_serial() {
+		// Large enough to be split up, and not a multiple of any piece length.
+		let pov =
+			PoV { block_data: BlockData((0..3 * 1024 * 1024 + 7).map(|i| i as u8).collect()) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let pool = ThreadPool::new(4).unwrap();
+
+		for n_validators in [10, 300, 1000] {
+			let chunks = obtain_chunks_v1(n_validators, &available_data).unwrap();
+			let parallel_chunks =
+				obtain_chunks_v1_parallel(n_validators, &available_data, &pool).unwrap();
+			assert_eq!(parallel_chunks, chunks);
+
+			let serial_branches = branches(&chunks);
+			let parallel_branches = branches_parallel(&chunks, &pool);
+			assert_eq!(parallel_branches.root(), serial_branches.root());
+			assert!(parallel_branches.eq(serial_branches));
+
+			let threshold = recovery_threshold(n_validators).unwrap();
+			let reconstructed = reconstruct_v1_parallel(
+				n_validators,
+				chunks.iter().enumerate().skip(1).take(threshold).map(|(i, c)| (&c[..], i)),
+				&pool,
+			)
+			.unwrap();
+			assert_eq!(reconstructed, available_data);
+
+			let systematic_threshold = systematic_recovery_threshold(n_validators).unwrap();
+			let reconstructed = reconstruct_from_systematic_v1_parallel(
+				n_validators,
+				chunks.iter().take(systematic_threshold).cloned().collect(),
+				&pool,
+			)
+			.unwrap();
+			assert_eq!(reconstructed, available_data);
+		}
+	}
+
+	#[test]
+	fn parallel_reconstruction_rejects_bad_chunks() {
+		let pov = PoV { block_data: BlockData(vec![1; 2 * 1024 * 1024]) };
+		let available_data = AvailableData { pov: pov.into(), validation_data: Default::default() };
+		let mut chunks = obtain_chunks_v1(10, &available_data).unwrap();
+		let pool = ThreadPool::new(4).unwrap();
+
+		assert_eq!(
+			reconstruct_v1_parallel(10, chunks.iter().take(3).map(|c| &c[..]).zip(0..), &pool),
+			Err(Error::NotEnoughChunks),
+		);
+
+		// Chunks of different lengths are handed to the serial path, to fail the same way.
+		chunks[1].truncate(chunks[1].len() - 2);
+		let serial = reconstruct_v1(10, chunks.iter().take(4).map(|c| &c[..]).zip(0..));
+		assert!(serial.is_err());
+		assert_eq!(
+			reconstruct_v1_parallel(10, chunks.iter().take(4).map(|c| &c[..]).zip(0..), &pool),
+			serial,
+		);
+
+		let serial = reconstruct_from_systematic_v1(10, chunks.clone());
+		assert!(serial.is_err());
+		assert_eq!(reconstruct_from_systematic_v1_parallel(10, chunks, &pool), serial);
+	}
+
 	#[test]
 	fn reconstruct_does_not_panic_on_low_validator_count() {
 		let reconstructed = reconstruct_v1(1, [].iter().cloned());
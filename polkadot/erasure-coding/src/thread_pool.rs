@@ -0,0 +1,123 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A pool of threads that the coding of large payloads is split across.
+
+use std::{
+	io,
+	panic::{self, AssertUnwindSafe},
+	sync::{mpsc, Arc, Mutex},
+	thread,
+};
+
+type Job = Box<dyn FnOnce() + Send>;
+
+/// A pool of threads that the coding of large payloads is split across.
+///
+/// The threads are spawned once, when the pool is created, and shared by all clones of the pool.
+/// They exit once the last clone is dropped. A pool of a single thread spawns no threads at all and
+/// everything is coded on the calling thread.
+#[derive(Clone, Debug)]
+pub struct ThreadPool {
+	jobs: Option<mpsc::Sender<Job>>,
+	threads: usize,
+}
+
+impl ThreadPool {
+	/// Spawn a pool of `threads` threads.
+	pub fn new(threads: usize) -> io::Result<Self> {
+		if threads <= 1 {
+			return Ok(Self::default())
+		}
+
+		let (jobs, receiver) = mpsc::channel::<Job>();
+		let receiver = Arc::new(Mutex::new(receiver));
+		for index in 0..threads {
+			let receiver = receiver.clone();
+			thread::Builder::new()
+				.name(format!("erasure-coding-{}", index))
+				.spawn(move || loop {
+					let job = match receiver.lock() {
+						Ok(receiver) => receiver.recv(),
+						Err(_) => return,
+					};
+
+					match job {
+						Ok(job) => job(),
+						Err(_) => return,
+					}
+				})?;
+		}
+
+		Ok(Self { jobs: Some(jobs), threads })
+	}
+
+	/// The number of threads of the pool.
+	pub fn threads(&self) -> usize {
+		self.threads
+	}
+
+	/// Run `f` on each of the `inputs` on the threads of the pool, returning the outputs in order.
+	///
+	/// Panics of `f` are propagated to the caller.
+	pub(crate) fn run<In, Out, F>(&self, inputs: Vec<In>, f: F) -> Vec<Out>
+	where
+		In: Send + 'static,
+		Out: Send + 'static,
+		F: Fn(In) -> Out + Send + Sync + 'static,
+	{
+		let Some(jobs) = &self.jobs else { return inputs.into_iter().map(f).collect() };
+
+		let n_inputs = inputs.len();
+		let f = Arc::new(f);
+		let (results_tx, results_rx) = mpsc::channel();
+		for (index, input) in inputs.into_iter().enumerate() {
+			let f = f.clone();
+			let results_tx = results_tx.clone();
+			let job: Job = Box::new(move || {
+				let result = panic::catch_unwind(AssertUnwindSafe(|| f(input)));
+				let _ = results_tx.send((index, result));
+			});
+
+			// The threads only exit once the pool is dropped, but run the job here if they don't
+			// accept it for any reason.
+			if let Err(mpsc::SendError(job)) = jobs.send(job) {
+				job()
+			}
+		}
+		drop(results_tx);
+
+		let mut outputs: Vec<Option<Out>> = (0..n_inputs).map(|_| None).collect();
+		for (index, result) in results_rx {
+			match result {
+				Ok(output) => outputs[index] = Some(output),
+				Err(panic) => panic::resume_unwind(panic),
+			}
+		}
+
+		outputs
+			.into_iter()
+			.map(|output| output.expect("every job sends its output before it is dropped; qed"))
+			.collect()
+	}
+}
+
+impl Default for ThreadPool {
+	/// A pool of a single thread, coding everything on the calling thread.
+	fn default() -> Self {
+		Self { jobs: None, threads: 1 }
+	}
+}
@@ -41,14 +41,18 @@ use sp_consensus::SyncOracle;
 use bitvec::{order::Lsb0 as BitOrderLsb0, vec::BitVec};
 use polkadot_node_primitives::{AvailableData, ErasureChunk};
 use polkadot_node_subsystem::{
-	errors::{ChainApiError, RuntimeApiError},
-	messages::{AvailabilityStoreMessage, ChainApiMessage, StoreAvailableDataError},
+	errors::{ChainApiError, RecoveryError, RuntimeApiError},
+	messages::{
+		AvailabilityRecoveryMessage, AvailabilityStoreMessage, ChainApiMessage,
+		StoreAvailableDataError,
+	},
 	overseer, ActiveLeavesUpdate, FromOrchestra, OverseerSignal, SpawnedSubsystem, SubsystemError,
 };
 use polkadot_node_subsystem_util as util;
 use polkadot_primitives::{
 	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt},
-	BlockNumber, CandidateHash, ChunkIndex, CoreIndex, Hash, Header, NodeFeatures, ValidatorIndex,
+	BlockNumber, CandidateHash, ChunkIndex, CoreIndex, GroupIndex, Hash, Header, Id as ParaId,
+	NodeFeatures, SessionIndex, ValidatorIndex,
 };
 use util::availability_chunks::availability_chunk_indices;
 
@@ -67,6 +71,7 @@ const CHUNK_PREFIX: &[u8; 5] = b"chunk";
 const META_PREFIX: &[u8; 4] = b"meta";
 const UNFINALIZED_PREFIX: &[u8; 11] = b"unfinalized";
 const PRUNE_BY_TIME_PREFIX: &[u8; 13] = b"prune_by_time";
+const ARCHIVED_PREFIX: &[u8; 8] = b"archived";
 
 // We have some keys we want to map to empty values because existence of the key is enough. We use
 // this because rocksdb doesn't support empty values.
@@ -157,6 +162,14 @@ struct CandidateMeta {
 	chunks_stored: BitVec<u8, BitOrderLsb0>,
 }
 
+// Marks a candidate of an archived para.
+#[derive(Debug, Encode, Decode)]
+struct Archived {
+	para_id: ParaId,
+	// Whether the candidate is finalized and kept without a pruning record.
+	kept_indefinitely: bool,
+}
+
 fn query_inner<D: Decode>(
 	db: &Arc<dyn Database>,
 	column: u32,
@@ -257,6 +270,31 @@ fn delete_meta(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
 	tx.delete(config.col_meta, &key[..])
 }
 
+fn load_archived(
+	db: &Arc<dyn Database>,
+	config: &Config,
+	hash: &CandidateHash,
+) -> Result<Option<Archived>, Error> {
+	let key = (ARCHIVED_PREFIX, hash).encode();
+
+	query_inner(db, config.col_meta, &key)
+}
+
+fn write_archived(
+	tx: &mut DBTransaction,
+	config: &Config,
+	hash: &CandidateHash,
+	archived: &Archived,
+) {
+	let key = (ARCHIVED_PREFIX, hash).encode();
+	tx.put_vec(config.col_meta, &key, archived.encode());
+}
+
+fn delete_archived(tx: &mut DBTransaction, config: &Config, hash: &CandidateHash) {
+	let key = (ARCHIVED_PREFIX, hash).encode();
+	tx.delete(config.col_meta, &key[..]);
+}
+
 fn delete_unfinalized_height(tx: &mut DBTransaction, config: &Config, block_number: BlockNumber) {
 	let prefix = (UNFINALIZED_PREFIX, BEBlockNumber(block_number)).encode();
 	tx.delete_prefix(config.col_meta, &prefix);
@@ -331,6 +369,14 @@ fn decode_unfinalized_key(s: &[u8]) -> Result<(BlockNumber, Hash, CandidateHash)
 		.map(|(b, h, ch)| (b.0, h, ch))
 }
 
+fn decode_archived_key(s: &[u8]) -> Result<CandidateHash, CodecError> {
+	if !s.starts_with(ARCHIVED_PREFIX) {
+		return Err("missing magic string".into())
+	}
+
+	CandidateHash::decode(&mut &s[ARCHIVED_PREFIX.len()..])
+}
+
 fn decode_pruning_key(s: &[u8]) -> Result<(Duration, CandidateHash), CodecError> {
 	if !s.starts_with(PRUNE_BY_TIME_PREFIX) {
 		return Err("missing magic string".into())
@@ -431,6 +477,39 @@ pub struct Config {
 	pub keep_finalized_for: u32,
 }
 
+/// Configuration of the archive mode of the availability store.
+///
+/// The full available data of candidates of the archived paras is recovered from the network once
+/// they are included, if it isn't stored already, and its chunks are stored alongside it. After
+/// finality it is kept for `keep_for`, or indefinitely, instead of being pruned after
+/// [`Config::keep_finalized_for`]. Meanwhile, the available data and the chunks are served to the
+/// network like the ones of any other candidate.
+///
+/// Data which is kept indefinitely is pruned like the data of any other candidate once its para is
+/// removed from `paras` or `keep_for` is set, starting from the next start of the subsystem.
+#[derive(Debug, Clone, Default)]
+pub struct ArchiveConfig {
+	/// The paras to archive the available data of.
+	pub paras: HashSet<ParaId>,
+	/// How long finalized data of archived paras should be kept. `None` keeps it indefinitely.
+	///
+	/// Data is never kept for a shorter time than [`Config::keep_finalized_for`].
+	pub keep_for: Option<Duration>,
+}
+
+/// An included candidate of an archived para, whose available data is recovered from the network.
+struct ArchivedCandidate {
+	receipt: CandidateReceipt,
+	session_index: SessionIndex,
+	group_index: GroupIndex,
+	core_index: CoreIndex,
+	n_validators: usize,
+	node_features: NodeFeatures,
+}
+
+/// Result of recovering the available data of an archived candidate.
+type ArchiveRecovery = (ArchivedCandidate, Result<AvailableData, RecoveryError>);
+
 trait Clock: Send + Sync {
 	// Returns time since unix epoch.
 	fn now(&self) -> Result<Duration, Error>;
@@ -454,6 +533,8 @@ pub struct AvailabilityStoreSubsystem {
 	metrics: Metrics,
 	clock: Box<dyn Clock>,
 	sync_oracle: Box<dyn SyncOracle + Send + Sync>,
+	erasure_coding_pool: polkadot_erasure_coding::ThreadPool,
+	archive: ArchiveConfig,
 }
 
 impl AvailabilityStoreSubsystem {
@@ -498,8 +579,23 @@ impl AvailabilityStoreSubsystem {
 			known_blocks: KnownUnfinalizedBlocks::default(),
 			sync_oracle,
 			finalized_number: None,
+			erasure_coding_pool: Default::default(),
+			archive: ArchiveConfig::default(),
 		}
 	}
+
+	/// Split the erasure coding of stored available data across the threads of `pool`. By
+	/// default, the coding runs on the subsystem's own thread.
+	pub fn with_erasure_coding_pool(mut self, pool: polkadot_erasure_coding::ThreadPool) -> Self {
+		self.erasure_coding_pool = pool;
+		self
+	}
+
+	/// Archive the available data of the paras in `archive`. By default, nothing is archived.
+	pub fn with_archive(mut self, archive: ArchiveConfig) -> Self {
+		self.archive = archive;
+		self
+	}
 }
 
 /// We keep the hashes and numbers of all unfinalized
@@ -550,12 +646,19 @@ async fn run<Context>(mut subsystem: AvailabilityStoreSubsystem, mut ctx: Contex
 	// Pruning interval is in the order of minutes so we shouldn't have more than one task running
 	// at one moment in time, so 10 should be more than enough.
 	let (mut pruning_result_tx, mut pruning_result_rx) = channel(10);
+	let (mut archive_recovery_tx, mut archive_recovery_rx) = channel(64);
+
+	if let Err(e) = prune_unarchived(&subsystem) {
+		e.trace();
+	}
+
 	loop {
 		let res = run_iteration(
 			&mut ctx,
 			&mut subsystem,
 			&mut next_pruning,
 			(&mut pruning_result_tx, &mut pruning_result_rx),
+			(&mut archive_recovery_tx, &mut archive_recovery_rx),
 		)
 		.await;
 		match res {
@@ -583,6 +686,10 @@ async fn run_iteration<Context>(
 		&mut MpscSender<Result<(), Error>>,
 		&mut MpscReceiver<Result<(), Error>>,
 	),
+	(archive_recovery_tx, archive_recovery_rx): (
+		&mut MpscSender<ArchiveRecovery>,
+		&mut MpscReceiver<ArchiveRecovery>,
+	),
 ) -> Result<bool, Error> {
 	select! {
 		incoming = ctx.recv().fuse() => {
@@ -593,7 +700,12 @@ async fn run_iteration<Context>(
 				) => {
 					for activated in activated.into_iter() {
 						let _timer = subsystem.metrics.time_block_activated();
-						process_block_activated(ctx, subsystem, activated.hash).await?;
+						process_block_activated(
+							ctx,
+							subsystem,
+							activated.hash,
+							archive_recovery_tx,
+						).await?;
 					}
 				}
 				FromOrchestra::Signal(OverseerSignal::BlockFinalized(hash, number)) => {
@@ -609,7 +721,12 @@ async fn run_iteration<Context>(
 							// If we're major syncing, processing finalized
 							// blocks might take quite a very long time
 							// and make the subsystem unresponsive.
-							process_block_activated(ctx, subsystem, hash).await?;
+							process_block_activated(
+								ctx,
+								subsystem,
+								hash,
+								archive_recovery_tx,
+							).await?;
 						}
 					}
 					subsystem.finalized_number = Some(number);
@@ -640,6 +757,20 @@ async fn run_iteration<Context>(
 				result?;
 			}
 		},
+		recovery = archive_recovery_rx.next() => {
+			if let Some((candidate, result)) = recovery {
+				match result {
+					Ok(available_data) =>
+						store_archived_data(subsystem, candidate, available_data)?,
+					Err(err) => gum::warn!(
+						target: LOG_TARGET,
+						candidate_hash = ?candidate.receipt.hash(),
+						?err,
+						"Failed to recover available data of archived candidate",
+					),
+				}
+			}
+		},
 	}
 
 	Ok(false)
@@ -681,6 +812,7 @@ async fn process_block_activated<Context>(
 	ctx: &mut Context,
 	subsystem: &mut AvailabilityStoreSubsystem,
 	activated: Hash,
+	archive_recovery_tx: &MpscSender<ArchiveRecovery>,
 ) -> Result<(), Error> {
 	let now = subsystem.clock.now()?;
 
@@ -710,12 +842,13 @@ async fn process_block_activated<Context>(
 		// it's important to commit the db transactions for a head before the next one is processed
 		// alternatively, we could utilize the OverlayBackend from approval-voting
 		let mut tx = DBTransaction::new();
-		process_new_head(
+		let to_recover = process_new_head(
 			ctx,
 			&subsystem.db,
 			&mut tx,
 			&subsystem.config,
 			&subsystem.pruning_config,
+			&subsystem.archive,
 			now,
 			hash,
 			header,
@@ -723,11 +856,46 @@ async fn process_block_activated<Context>(
 		.await?;
 		subsystem.known_blocks.insert(hash, block_number);
 		subsystem.db.write(tx)?;
+
+		for candidate in to_recover {
+			recover_archived_data(ctx, candidate, archive_recovery_tx.clone()).await?;
+		}
 	}
 
 	Ok(())
 }
 
+// Request the recovery of the available data of an archived candidate. The result is handled by
+// the main loop, so that the subsystem doesn't block on the recovery.
+#[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
+async fn recover_archived_data<Context>(
+	ctx: &mut Context,
+	candidate: ArchivedCandidate,
+	mut archive_recovery_tx: MpscSender<ArchiveRecovery>,
+) -> Result<(), Error> {
+	let (tx, rx) = oneshot::channel();
+	ctx.send_message(AvailabilityRecoveryMessage::RecoverAvailableData(
+		candidate.receipt.clone(),
+		candidate.session_index,
+		Some(candidate.group_index),
+		Some(candidate.core_index),
+		tx,
+	))
+	.await;
+
+	ctx.spawn(
+		"av-store-archive-recovery",
+		Box::pin(async move {
+			let result = rx.await.unwrap_or(Err(RecoveryError::ChannelClosed));
+			if let Err(err) = archive_recovery_tx.send((candidate, result)).await {
+				// This usually means that the node is closing down, log it just in case
+				gum::debug!(target: LOG_TARGET, ?err, "Failed to send archive recovery result");
+			}
+		}),
+	)?;
+	Ok(())
+}
+
 #[overseer::contextbounds(AvailabilityStore, prefix = self::overseer)]
 async fn process_new_head<Context>(
 	ctx: &mut Context,
@@ -735,10 +903,11 @@ async fn process_new_head<Context>(
 	db_transaction: &mut DBTransaction,
 	config: &Config,
 	pruning_config: &PruningConfig,
+	archive: &ArchiveConfig,
 	now: Duration,
 	hash: Hash,
 	header: Header,
-) -> Result<(), Error> {
+) -> Result<Vec<ArchivedCandidate>, Error> {
 	let candidate_events = util::request_candidate_events(hash, ctx.sender()).await.await??;
 
 	// We need to request the number of validators based on the parent state,
@@ -746,6 +915,10 @@ async fn process_new_head<Context>(
 	let n_validators =
 		util::request_validators(header.parent_hash, ctx.sender()).await.await??.len();
 
+	// Only requested if there are archived candidates.
+	let mut session = None;
+	let mut to_recover = Vec::new();
+
 	for event in candidate_events {
 		match event {
 			CandidateEvent::CandidateBacked(receipt, _head, _core_index, _group_index) => {
@@ -759,7 +932,39 @@ async fn process_new_head<Context>(
 					receipt,
 				)?;
 			},
-			CandidateEvent::CandidateIncluded(receipt, _head, _core_index, _group_index) => {
+			CandidateEvent::CandidateIncluded(receipt, _head, core_index, group_index) => {
+				if archive.paras.contains(&receipt.descriptor.para_id()) {
+					let candidate_hash = receipt.hash();
+					let archived = Archived {
+						para_id: receipt.descriptor.para_id(),
+						kept_indefinitely: false,
+					};
+					write_archived(db_transaction, config, &candidate_hash, &archived);
+
+					// Only recover once, on the first inclusion of the candidate.
+					if let Some(CandidateMeta {
+						state: State::Unavailable(_),
+						data_available: false,
+						..
+					}) = load_meta(db, config, &candidate_hash)?
+					{
+						if session.is_none() {
+							session =
+								Some(request_session(header.parent_hash, ctx.sender()).await?);
+						}
+						let (session_index, node_features) =
+							session.clone().expect("set above; qed");
+						to_recover.push(ArchivedCandidate {
+							receipt: receipt.clone(),
+							session_index,
+							group_index,
+							core_index,
+							n_validators,
+							node_features,
+						});
+					}
+				}
+
 				note_block_included(
 					db,
 					db_transaction,
@@ -773,7 +978,18 @@ async fn process_new_head<Context>(
 		}
 	}
 
-	Ok(())
+	Ok(to_recover)
+}
+
+// Request the index and the node features of the session of the children of `parent`.
+async fn request_session(
+	parent: Hash,
+	sender: &mut impl overseer::AvailabilityStoreSenderTrait,
+) -> Result<(SessionIndex, NodeFeatures), Error> {
+	let session_index = util::request_session_index_for_child(parent, sender).await.await??;
+	let node_features = util::request_node_features(parent, session_index, sender).await.await??;
+
+	Ok((session_index, node_features))
 }
 
 fn note_block_backed(
@@ -1048,14 +1264,28 @@ fn update_blocks_at_finalized_height(
 
 			meta.state = State::Finalized(now.into());
 
-			// Write the meta and a pruning record.
+			// Write the meta and a pruning record, unless the candidate is archived indefinitely.
 			write_meta(db_transaction, &subsystem.config, &candidate_hash, &meta);
-			write_pruning_key(
-				db_transaction,
-				&subsystem.config,
-				now + subsystem.pruning_config.keep_finalized_for,
-				&candidate_hash,
-			);
+			let archived = load_archived(&subsystem.db, &subsystem.config, &candidate_hash)?
+				.filter(|archived| subsystem.archive.paras.contains(&archived.para_id));
+			match (archived, subsystem.archive.keep_for) {
+				(Some(mut archived), None) => {
+					archived.kept_indefinitely = true;
+					write_archived(db_transaction, &subsystem.config, &candidate_hash, &archived);
+				},
+				(Some(_), Some(keep_for)) => write_pruning_key(
+					db_transaction,
+					&subsystem.config,
+					now + keep_for.max(subsystem.pruning_config.keep_finalized_for),
+					&candidate_hash,
+				),
+				(None, _) => write_pruning_key(
+					db_transaction,
+					&subsystem.config,
+					now + subsystem.pruning_config.keep_finalized_for,
+					&candidate_hash,
+				),
+			}
 		} else {
 			meta.state = match meta.state {
 				State::Finalized(_) => continue,   // sanity.
@@ -1311,8 +1541,13 @@ fn store_available_data(
 
 	// Important note: This check below is critical for consensus and the `backing` subsystem relies
 	// on it to ensure candidate validity.
-	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data)?;
-	let branches = polkadot_erasure_coding::branches(chunks.as_ref());
+	let chunks = polkadot_erasure_coding::obtain_chunks_v1_parallel(
+		n_validators,
+		&available_data,
+		&subsystem.erasure_coding_pool,
+	)?;
+	let branches =
+		polkadot_erasure_coding::branches_parallel(chunks.as_ref(), &subsystem.erasure_coding_pool);
 
 	if branches.root() != expected_erasure_root {
 		return Err(Error::InvalidErasureRoot)
@@ -1353,6 +1588,71 @@ fn store_available_data(
 	Ok(())
 }
 
+// Store the recovered available data of an archived candidate, along with all its chunks. This
+// way it is served to both the full data and the chunk requests of the availability recovery.
+fn store_archived_data(
+	subsystem: &AvailabilityStoreSubsystem,
+	candidate: ArchivedCandidate,
+	available_data: AvailableData,
+) -> Result<(), Error> {
+	let candidate_hash = candidate.receipt.hash();
+	match load_meta(&subsystem.db, &subsystem.config, &candidate_hash)? {
+		Some(meta) if !meta.data_available => {},
+		// Already stored in the meantime or pruned.
+		_ => return Ok(()),
+	}
+
+	store_available_data(
+		subsystem,
+		candidate_hash,
+		candidate.n_validators,
+		available_data,
+		candidate.receipt.descriptor.erasure_root(),
+		candidate.core_index,
+		candidate.node_features,
+	)?;
+
+	gum::debug!(target: LOG_TARGET, ?candidate_hash, "Stored recovered data of archived candidate");
+
+	Ok(())
+}
+
+// Start pruning the finalized candidates kept indefinitely, which are not to be kept indefinitely
+// anymore, because their para is not archived anymore or the archived data is kept for a limited
+// time now.
+fn prune_unarchived(subsystem: &AvailabilityStoreSubsystem) -> Result<(), Error> {
+	let now = subsystem.clock.now()?;
+	let mut tx = DBTransaction::new();
+	for r in subsystem.db.iter_with_prefix(subsystem.config.col_meta, ARCHIVED_PREFIX) {
+		let (k, v) = r?;
+		let (candidate_hash, mut archived) =
+			match (decode_archived_key(&k[..]), Archived::decode(&mut &v[..])) {
+				(Ok(candidate_hash), Ok(archived)) => (candidate_hash, archived),
+				_ => continue, // sanity
+			};
+
+		if !archived.kept_indefinitely {
+			continue
+		}
+
+		let keep_for = if subsystem.archive.paras.contains(&archived.para_id) {
+			match subsystem.archive.keep_for {
+				Some(keep_for) => keep_for.max(subsystem.pruning_config.keep_finalized_for),
+				None => continue,
+			}
+		} else {
+			subsystem.pruning_config.keep_finalized_for
+		};
+
+		write_pruning_key(&mut tx, &subsystem.config, now + keep_for, &candidate_hash);
+		archived.kept_indefinitely = false;
+		write_archived(&mut tx, &subsystem.config, &candidate_hash, &archived);
+	}
+	subsystem.db.write(tx)?;
+
+	Ok(())
+}
+
 fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(), Error> {
 	let (range_start, range_end) = pruning_range(now);
 
@@ -1371,6 +1671,7 @@ fn prune_all(db: &Arc<dyn Database>, config: &Config, now: Duration) -> Result<(
 		};
 
 		delete_meta(&mut tx, config, &candidate_hash);
+		delete_archived(&mut tx, config, &candidate_hash);
 
 		// Clean up all attached data of the candidate.
 		if let Some(meta) = load_meta(db, config, &candidate_hash)? {
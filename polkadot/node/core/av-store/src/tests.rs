@@ -31,8 +31,10 @@ use polkadot_node_subsystem::{
 use polkadot_node_subsystem_test_helpers as test_helpers;
 use polkadot_node_subsystem_util::{database::Database, TimeoutExt};
 use polkadot_primitives::{
-	node_features, vstaging::CandidateReceiptV2 as CandidateReceipt, CandidateHash, CoreIndex,
-	GroupIndex, HeadData, Header, PersistedValidationData, ValidatorId,
+	node_features,
+	vstaging::{CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	CandidateHash, CoreIndex, GroupIndex, HeadData, Header, Id as ParaId, PersistedValidationData,
+	ValidatorId,
 };
 use polkadot_primitives_test_helpers::TestCandidateBuilder;
 use sp_keyring::Sr25519Keyring;
@@ -122,6 +124,15 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 	state: TestState,
 	store: Arc<dyn Database>,
 	test: impl FnOnce(VirtualOverseer) -> T,
+) {
+	test_harness_with_archive(state, store, ArchiveConfig::default(), test)
+}
+
+fn test_harness_with_archive<T: Future<Output = VirtualOverseer>>(
+	state: TestState,
+	store: Arc<dyn Database>,
+	archive: ArchiveConfig,
+	test: impl FnOnce(VirtualOverseer) -> T,
 ) {
 	sp_tracing::init_for_tests();
 
@@ -136,7 +147,8 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		Box::new(state.clock),
 		Box::new(NoSyncOracle),
 		Metrics::default(),
-	);
+	)
+	.with_archive(archive);
 
 	let subsystem = run(subsystem, context);
 
@@ -893,6 +905,352 @@ fn stored_data_kept_until_finalized() {
 	});
 }
 
+const ARCHIVED_PARA: ParaId = ParaId::new(1);
+
+fn archive_config(keep_for: Option<Duration>) -> ArchiveConfig {
+	ArchiveConfig { paras: [ARCHIVED_PARA].into_iter().collect(), keep_for }
+}
+
+// A candidate of `para_id` with a valid erasure root for `n_validators` and its available data.
+fn candidate_with_data(
+	test_state: &TestState,
+	para_id: ParaId,
+	n_validators: usize,
+) -> (CandidateReceipt, AvailableData) {
+	let pov = PoV { block_data: BlockData(vec![4, 5, 6]) };
+	let mut candidate =
+		TestCandidateBuilder { para_id, pov_hash: pov.hash(), ..Default::default() }.build();
+	let available_data = AvailableData {
+		pov: Arc::new(pov),
+		validation_data: test_state.persisted_validation_data.clone(),
+	};
+
+	let chunks = polkadot_erasure_coding::obtain_chunks_v1(n_validators, &available_data).unwrap();
+	let branches = polkadot_erasure_coding::branches(chunks.as_ref());
+	candidate.descriptor.set_erasure_root(branches.root());
+
+	(candidate, available_data)
+}
+
+// Back and include `candidate` of an archived para and recover its available data, returning the
+// leaf it is included in.
+async fn include_archived_candidate(
+	virtual_overseer: &mut VirtualOverseer,
+	candidate: &CandidateReceipt,
+	available_data: &AvailableData,
+	validators: Vec<ValidatorId>,
+) -> Hash {
+	let candidate_hash = candidate.hash();
+	let backed_leaf = import_leaf(
+		virtual_overseer,
+		Hash::repeat_byte(2),
+		1,
+		vec![CandidateEvent::CandidateBacked(
+			candidate.clone(),
+			HeadData::default(),
+			CoreIndex::default(),
+			GroupIndex::default(),
+		)],
+		validators.clone(),
+	)
+	.await;
+
+	// We don't have the data, so it gets recovered once the candidate is included.
+	assert!(query_available_data(virtual_overseer, candidate_hash).await.is_none());
+
+	let included_leaf = import_leaf(
+		virtual_overseer,
+		backed_leaf,
+		2,
+		vec![candidate_included(candidate.clone())],
+		validators,
+	)
+	.await;
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::SessionIndexForChild(tx),
+		)) => {
+			assert_eq!(relay_parent, backed_leaf);
+			tx.send(Ok(1)).unwrap();
+		}
+	);
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::RuntimeApi(RuntimeApiMessage::Request(
+			relay_parent,
+			RuntimeApiRequest::NodeFeatures(1, tx),
+		)) => {
+			assert_eq!(relay_parent, backed_leaf);
+			tx.send(Ok(NodeFeatures::EMPTY)).unwrap();
+		}
+	);
+
+	assert_matches!(
+		overseer_recv(virtual_overseer).await,
+		AllMessages::AvailabilityRecovery(AvailabilityRecoveryMessage::RecoverAvailableData(
+			receipt,
+			session_index,
+			Some(GroupIndex(0)),
+			Some(CoreIndex(0)),
+			tx,
+		)) => {
+			assert_eq!(&receipt, candidate);
+			assert_eq!(session_index, 1);
+			tx.send(Ok(available_data.clone())).unwrap();
+		}
+	);
+
+	// The recovered data is stored asynchronously.
+	let mut stored = None;
+	for _ in 0..10 {
+		stored = query_available_data(virtual_overseer, candidate_hash).await;
+		if stored.is_some() {
+			break
+		}
+		Delay::new(Duration::from_millis(50)).await;
+	}
+	assert_eq!(stored.as_ref(), Some(available_data));
+
+	included_leaf
+}
+
+#[test]
+fn archived_data_is_recovered_and_kept() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness_with_archive(
+		test_state.clone(),
+		store.clone(),
+		archive_config(None),
+		|mut virtual_overseer| async move {
+			let n_validators = 10;
+			let validators: Vec<ValidatorId> =
+				(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect();
+			let (candidate, available_data) =
+				candidate_with_data(&test_state, ARCHIVED_PARA, n_validators);
+			let candidate_hash = candidate.hash();
+
+			let included_leaf = include_archived_candidate(
+				&mut virtual_overseer,
+				&candidate,
+				&available_data,
+				validators,
+			)
+			.await;
+
+			// The chunks are stored alongside the recovered data, to serve chunk requests.
+			assert!(
+				has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators as u32, true)
+					.await
+			);
+
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::BlockFinalized(included_leaf, 2),
+			)
+			.await;
+
+			// Wait until finalized data would definitely be pruned.
+			test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 10);
+			test_state.wait_for_pruning().await;
+
+			// Archived data is kept indefinitely.
+			assert_eq!(
+				query_available_data(&mut virtual_overseer, candidate_hash).await,
+				Some(available_data),
+			);
+			assert!(
+				has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators as u32, true)
+					.await
+			);
+			virtual_overseer
+		},
+	);
+}
+
+#[test]
+fn archived_data_is_kept_for_configured_time() {
+	let store = test_store();
+	let test_state = TestState::default();
+	let keep_for = test_state.pruning_config.keep_finalized_for * 4;
+
+	test_harness_with_archive(
+		test_state.clone(),
+		store.clone(),
+		archive_config(Some(keep_for)),
+		|mut virtual_overseer| async move {
+			let n_validators = 10;
+			let validators: Vec<ValidatorId> =
+				(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect();
+			let (candidate, available_data) =
+				candidate_with_data(&test_state, ARCHIVED_PARA, n_validators);
+			let candidate_hash = candidate.hash();
+
+			let included_leaf = include_archived_candidate(
+				&mut virtual_overseer,
+				&candidate,
+				&available_data,
+				validators,
+			)
+			.await;
+
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::BlockFinalized(included_leaf, 2),
+			)
+			.await;
+
+			// Finalized data of other paras would be pruned by now.
+			test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 2);
+			test_state.wait_for_pruning().await;
+
+			assert_eq!(
+				query_available_data(&mut virtual_overseer, candidate_hash).await,
+				Some(available_data),
+			);
+
+			// Wait until archived data should definitely be gone.
+			test_state.clock.inc(keep_for);
+			test_state.wait_for_pruning().await;
+
+			assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+			assert!(
+				has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators as u32, false)
+					.await
+			);
+			virtual_overseer
+		},
+	);
+}
+
+#[test]
+fn data_of_not_archived_para_is_not_recovered_and_pruned() {
+	let store = test_store();
+	let test_state = TestState::default();
+
+	test_harness_with_archive(
+		test_state.clone(),
+		store.clone(),
+		archive_config(None),
+		|mut virtual_overseer| async move {
+			let n_validators = 10;
+			let validators: Vec<ValidatorId> =
+				(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect();
+			let (candidate, available_data) =
+				candidate_with_data(&test_state, ParaId::from(2), n_validators);
+			let candidate_hash = candidate.hash();
+
+			let (tx, rx) = oneshot::channel();
+			let block_msg = AvailabilityStoreMessage::StoreAvailableData {
+				candidate_hash,
+				n_validators: n_validators as u32,
+				available_data: available_data.clone(),
+				tx,
+				node_features: NodeFeatures::EMPTY,
+				core_index: CoreIndex(0),
+				expected_erasure_root: candidate.descriptor.erasure_root(),
+			};
+			virtual_overseer.send(FromOrchestra::Communication { msg: block_msg }).await;
+			rx.await.unwrap().unwrap();
+
+			let included_leaf = import_leaf(
+				&mut virtual_overseer,
+				Hash::repeat_byte(2),
+				1,
+				vec![candidate_included(candidate)],
+				validators,
+			)
+			.await;
+
+			// Nothing is recovered for the para.
+			assert!(overseer_recv_with_timeout(&mut virtual_overseer, TIMEOUT).await.is_none());
+
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::BlockFinalized(included_leaf, 1),
+			)
+			.await;
+
+			// Wait until finalized data should definitely be gone.
+			test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 2);
+			test_state.wait_for_pruning().await;
+
+			assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+			assert!(
+				has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators as u32, false)
+					.await
+			);
+			virtual_overseer
+		},
+	);
+}
+
+#[test]
+fn data_kept_indefinitely_is_pruned_once_para_is_not_archived() {
+	let store = test_store();
+	let test_state = TestState::default();
+	let n_validators = 10;
+	let (candidate, available_data) = candidate_with_data(&test_state, ARCHIVED_PARA, n_validators);
+	let candidate_hash = candidate.hash();
+
+	test_harness_with_archive(test_state.clone(), store.clone(), archive_config(None), {
+		let test_state = test_state.clone();
+		let available_data = available_data.clone();
+		|mut virtual_overseer| async move {
+			let validators: Vec<ValidatorId> =
+				(0..n_validators).map(|_| Sr25519Keyring::Alice.public().into()).collect();
+
+			let included_leaf = include_archived_candidate(
+				&mut virtual_overseer,
+				&candidate,
+				&available_data,
+				validators,
+			)
+			.await;
+
+			overseer_signal(
+				&mut virtual_overseer,
+				OverseerSignal::BlockFinalized(included_leaf, 2),
+			)
+			.await;
+
+			test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 10);
+			test_state.wait_for_pruning().await;
+
+			assert_eq!(
+				query_available_data(&mut virtual_overseer, candidate_hash).await,
+				Some(available_data),
+			);
+			virtual_overseer
+		}
+	});
+
+	// Restart without archiving the para.
+	test_harness(test_state.clone(), store.clone(), |mut virtual_overseer| async move {
+		// The data is kept for the usual time after the restart.
+		test_state.wait_for_pruning().await;
+		assert_eq!(
+			query_available_data(&mut virtual_overseer, candidate_hash).await,
+			Some(available_data),
+		);
+
+		// Wait until it definitely should be gone.
+		test_state.clock.inc(test_state.pruning_config.keep_finalized_for * 2);
+		test_state.wait_for_pruning().await;
+
+		assert!(query_available_data(&mut virtual_overseer, candidate_hash).await.is_none());
+		assert!(
+			has_all_chunks(&mut virtual_overseer, candidate_hash, n_validators as u32, false).await
+		);
+		virtual_overseer
+	});
+}
+
 #[test]
 fn we_dont_miss_anything_if_import_notifications_are_missed() {
 	let store = test_store();
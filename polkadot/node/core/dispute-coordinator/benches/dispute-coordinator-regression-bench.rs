@@ -0,0 +1,70 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! dispute-coordinator regression tests
+//!
+//! Burst of disputes spread across several sessions, including a spamming validator.
+//!
+//! Subsystems involved:
+//! - dispute-coordinator
+
+use polkadot_subsystem_bench::{
+	configuration::TestConfiguration,
+	disputes::{benchmark_dispute_coordinator, prepare_test, DisputeCoordinatorOptions, TestState},
+	usage::BenchmarkUsage,
+	utils::save_to_file,
+};
+use std::io::Write;
+
+const BENCH_COUNT: usize = 10;
+
+fn main() -> Result<(), String> {
+	let mut config = TestConfiguration::default();
+	config.n_cores = 20;
+	config.n_validators = 500;
+	config.num_blocks = 6;
+	config.generate_pov_sizes();
+	let options = DisputeCoordinatorOptions {
+		n_sessions: 3,
+		n_disputes_per_block: 5,
+		n_spam_disputes_per_block: 10,
+	};
+	let state = TestState::new(&config, &options);
+
+	println!("Benchmarking...");
+	let usages: Vec<BenchmarkUsage> = (0..BENCH_COUNT)
+		.map(|n| {
+			print!("\r[{}{}]", "#".repeat(n), "_".repeat(BENCH_COUNT - n));
+			std::io::stdout().flush().unwrap();
+			let mut env = prepare_test(&state, false);
+			env.runtime().block_on(benchmark_dispute_coordinator(&mut env, &state))
+		})
+		.collect();
+	println!("\rDone!{}", " ".repeat(BENCH_COUNT));
+
+	let average_usage = BenchmarkUsage::average(&usages);
+	save_to_file(
+		"charts/dispute-coordinator-regression-bench.json",
+		average_usage.to_chart_json().map_err(|e| e.to_string())?,
+	)
+	.map_err(|e| e.to_string())?;
+	println!("{}", average_usage);
+
+	// Disputes are not distributed, so there is no network usage to check, and there is no
+	// reference CPU usage measured on the benchmark runners yet. Until then regressions are
+	// tracked by the published chart.
+	Ok(())
+}
@@ -17,7 +17,7 @@
 //! Dispute coordinator subsystem in initialized state (after first active leaf is received).
 
 use std::{
-	collections::{BTreeMap, VecDeque},
+	collections::{BTreeMap, HashSet, VecDeque},
 	sync::Arc,
 };
 
@@ -29,8 +29,8 @@ use futures::{
 use sc_keystore::LocalKeystore;
 
 use polkadot_node_primitives::{
-	disputes::ValidCandidateVotes, CandidateVotes, DisputeStatus, SignedDisputeStatement,
-	Timestamp, DISPUTE_WINDOW,
+	disputes::{DisputeInfo, OwnParticipation, ValidCandidateVotes},
+	CandidateVotes, DisputeStatus, SignedDisputeStatement, Timestamp, DISPUTE_WINDOW,
 };
 use polkadot_node_subsystem::{
 	messages::{
@@ -80,6 +80,11 @@ use super::{
 /// updates (and especially on startup) so the dispute coordinator won't be considered stalling.
 const CHAIN_IMPORT_MAX_BATCH_SIZE: usize = 8;
 
+/// The maximum number of disputes returned by a single `InspectDisputes` request.
+///
+/// The disputes of the most recent sessions are returned first.
+pub(crate) const MAX_INSPECTED_DISPUTES: usize = 64;
+
 // Initial data for `dispute-coordinator`. It is provided only at first start.
 pub struct InitialData {
 	pub participations: Vec<(ParticipationPriority, ParticipationRequest)>,
@@ -105,6 +110,10 @@ pub(crate) struct Initialized {
 	gaps_in_cache: bool,
 	spam_slots: SpamSlots,
 	participation: Participation,
+	/// Our own participations in disputes of the sessions in the dispute window, for inspection.
+	participations: BTreeMap<(SessionIndex, CandidateHash), OwnParticipation>,
+	/// The validator indices we control in the sessions we imported votes for, for inspection.
+	controlled_indices: BTreeMap<SessionIndex, HashSet<ValidatorIndex>>,
 	scraper: ChainScraper,
 	participation_receiver: WorkerMessageReceiver,
 	/// Backlog of still to be imported votes from chain.
@@ -154,6 +163,8 @@ impl Initialized {
 			spam_slots,
 			scraper,
 			participation,
+			participations: BTreeMap::new(),
+			controlled_indices: BTreeMap::new(),
 			participation_receiver,
 			chain_import_backlog: VecDeque::new(),
 			metrics,
@@ -204,7 +215,9 @@ impl Initialized {
 			initial_data.take()
 		{
 			for (priority, request) in participations {
+				let key = (request.session(), *request.candidate_hash());
 				self.participation.queue_participation(ctx, priority, request).await?;
+				self.note_participation_queued(key, clock.now());
 			}
 
 			let mut overlay_db = OverlayedBackend::new(backend);
@@ -243,6 +256,11 @@ impl Initialized {
 							candidate_receipt,
 							outcome,
 						} = self.participation.get_participation_result(ctx, msg).await?;
+						let now = clock.now();
+						self.participations
+							.entry((session, candidate_hash))
+							.or_insert(OwnParticipation { queued_at: now, concluded: None })
+							.concluded = Some((outcome, now));
 						if let Some(valid) = outcome.validity() {
 							gum::trace!(
 								target: LOG_TARGET,
@@ -258,7 +276,7 @@ impl Initialized {
 								candidate_receipt,
 								session,
 								valid,
-								clock.now(),
+								now,
 							)
 							.await?;
 						} else {
@@ -366,6 +384,9 @@ impl Initialized {
 					db::v1::note_earliest_session(overlay_db, prune_up_to)?;
 					self.spam_slots.prune_old(prune_up_to);
 					self.offchain_disabled_validators.prune_old(prune_up_to);
+					self.participations =
+						self.participations.split_off(&(prune_up_to, CandidateHash::default()));
+					self.controlled_indices = self.controlled_indices.split_off(&prune_up_to);
 				},
 				Ok(_) => { /* no new session => nothing to cache */ },
 				Err(err) => {
@@ -876,6 +897,11 @@ impl Initialized {
 						.collect(),
 				);
 			},
+			DisputeCoordinatorMessage::InspectDisputes { active_only, tx } => {
+				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::InspectDisputes");
+				let disputes = self.inspect_disputes(overlay_db, active_only, now)?;
+				let _ = tx.send(disputes);
+			},
 			DisputeCoordinatorMessage::QueryCandidateVotes(query, tx) => {
 				gum::trace!(target: LOG_TARGET, "DisputeCoordinatorMessage::QueryCandidateVotes");
 				let mut query_output = Vec::new();
@@ -995,6 +1021,9 @@ impl Initialized {
 			},
 			Some(env) => env,
 		};
+		self.controlled_indices
+			.entry(session)
+			.or_insert_with(|| env.controlled_indices().clone());
 
 		let n_validators = env.validators().len();
 
@@ -1209,6 +1238,9 @@ impl Initialized {
 					),
 				)
 				.await;
+			if r.is_ok() {
+				self.note_participation_queued((session, candidate_hash), now);
+			}
 			log_error(r)?;
 		} else {
 			gum::trace!(
@@ -1565,6 +1597,78 @@ impl Initialized {
 		Ok(())
 	}
 
+	/// Collect up to [`MAX_INSPECTED_DISPUTES`] of the most recent (or only the active) disputes
+	/// with their votes and our participation.
+	///
+	/// Only state which is already cached is used besides the votes, so that inspection doesn't
+	/// issue any runtime requests.
+	fn inspect_disputes(
+		&self,
+		overlay_db: &mut OverlayedBackend<'_, impl Backend>,
+		active_only: bool,
+		now: Timestamp,
+	) -> Result<Vec<DisputeInfo>> {
+		let recent_disputes = overlay_db.load_recent_disputes()?.unwrap_or_default();
+		let disputes = if active_only {
+			get_active_with_status(recent_disputes.into_iter().rev(), now)
+				.take(MAX_INSPECTED_DISPUTES)
+				.collect::<Vec<_>>()
+		} else {
+			recent_disputes.into_iter().rev().take(MAX_INSPECTED_DISPUTES).collect()
+		};
+
+		let mut inspected = Vec::with_capacity(disputes.len());
+		for ((session, candidate_hash), status) in disputes {
+			let votes: Option<CandidateVotes> =
+				overlay_db.load_candidate_votes(session, &candidate_hash)?.map(Into::into);
+			let own_votes = votes.as_ref().and_then(|votes| self.own_votes(session, votes));
+			inspected.push(DisputeInfo {
+				session,
+				candidate_hash,
+				status,
+				votes,
+				own_votes,
+				participation: self.participations.get(&(session, candidate_hash)).copied(),
+			});
+		}
+
+		Ok(inspected)
+	}
+
+	/// Our own votes on a candidate, `true` meaning valid.
+	///
+	/// `None` if we are not a validator in the session, or no votes of the session were imported
+	/// since startup.
+	fn own_votes(
+		&self,
+		session: SessionIndex,
+		votes: &CandidateVotes,
+	) -> Option<Vec<(ValidatorIndex, bool)>> {
+		let controlled_indices = self.controlled_indices.get(&session)?;
+		if controlled_indices.is_empty() {
+			return None
+		}
+
+		let valid = votes.valid.raw().keys().map(|index| (*index, true));
+		let invalid = votes.invalid.keys().map(|index| (*index, false));
+		Some(
+			valid
+				.chain(invalid)
+				.filter(|(index, _)| controlled_indices.contains(index))
+				.collect(),
+		)
+	}
+
+	/// Note that a participation got queued, unless we already know about it.
+	///
+	/// Participations may be queued again, e.g. on startup, which must neither reset the time it
+	/// got queued at first, nor the outcome if it concluded already.
+	fn note_participation_queued(&mut self, key: (SessionIndex, CandidateHash), now: Timestamp) {
+		self.participations
+			.entry(key)
+			.or_insert(OwnParticipation { queued_at: now, concluded: None });
+	}
+
 	fn session_is_ancient(&self, session_idx: SessionIndex) -> bool {
 		return session_idx < self.highest_session_seen.saturating_sub(DISPUTE_WINDOW.get() - 1)
 	}
@@ -32,6 +32,7 @@ use futures::{
 use polkadot_node_subsystem_util::database::Database;
 
 use polkadot_node_primitives::{
+	disputes::{OwnParticipation, ParticipationOutcome},
 	DisputeMessage, DisputeStatus, SignedDisputeStatement, SignedFullStatement, Statement,
 	DISPUTE_WINDOW,
 };
@@ -1090,6 +1091,106 @@ fn dispute_gets_confirmed_via_participation() {
 	});
 }
 
+#[test]
+fn inspect_disputes_reports_votes_and_participation() {
+	test_harness(|mut test_state, mut virtual_overseer| {
+		Box::pin(async move {
+			let session = 1;
+
+			test_state.handle_resume_sync(&mut virtual_overseer, session).await;
+
+			let candidate_receipt = make_valid_candidate_receipt();
+			let candidate_hash = candidate_receipt.hash();
+
+			test_state
+				.activate_leaf_at_session(
+					&mut virtual_overseer,
+					session,
+					1,
+					vec![make_candidate_backed_event(candidate_receipt.clone())],
+				)
+				.await;
+
+			let (valid_vote, invalid_vote) = generate_opposing_votes_pair(
+				&test_state,
+				ValidatorIndex(3),
+				ValidatorIndex(1),
+				candidate_hash,
+				session,
+				VoteType::Explicit,
+			)
+			.await;
+
+			virtual_overseer
+				.send(FromOrchestra::Communication {
+					msg: DisputeCoordinatorMessage::ImportStatements {
+						candidate_receipt: candidate_receipt.clone(),
+						session,
+						statements: vec![
+							(valid_vote, ValidatorIndex(3)),
+							(invalid_vote, ValidatorIndex(1)),
+						],
+						pending_confirmation: None,
+					},
+				})
+				.await;
+			handle_disabled_validators_queries(&mut virtual_overseer, Vec::new()).await;
+			handle_approval_vote_request(&mut virtual_overseer, &candidate_hash, HashMap::new())
+				.await;
+
+			participation_with_distribution(
+				&mut virtual_overseer,
+				&candidate_hash,
+				candidate_receipt.commitments_hash,
+			)
+			.await;
+
+			for active_only in [true, false] {
+				let (tx, rx) = oneshot::channel();
+				virtual_overseer
+					.send(FromOrchestra::Communication {
+						msg: DisputeCoordinatorMessage::InspectDisputes { active_only, tx },
+					})
+					.await;
+
+				let disputes = rx.await.unwrap();
+				assert_eq!(disputes.len(), 1);
+				let dispute = &disputes[0];
+				assert_eq!(dispute.session, session);
+				assert_eq!(dispute.candidate_hash, candidate_hash);
+				assert_eq!(dispute.status, DisputeStatus::Active);
+
+				let votes = dispute.votes.as_ref().unwrap();
+				assert_eq!(votes.candidate_receipt, candidate_receipt);
+				assert_eq!(
+					votes.valid.raw().keys().copied().collect::<Vec<_>>(),
+					vec![ValidatorIndex(0), ValidatorIndex(3)],
+				);
+				assert_eq!(
+					votes.invalid.keys().copied().collect::<Vec<_>>(),
+					vec![ValidatorIndex(1)]
+				);
+
+				assert_eq!(dispute.own_votes, Some(vec![(ValidatorIndex(0), true)]));
+				assert_matches!(
+					dispute.participation,
+					Some(OwnParticipation {
+						queued_at,
+						concluded: Some((ParticipationOutcome::Valid, concluded_at)),
+					}) if queued_at <= concluded_at
+				);
+			}
+
+			virtual_overseer.send(FromOrchestra::Signal(OverseerSignal::Conclude)).await;
+
+			// No more messages expected:
+			assert!(virtual_overseer.try_recv().await.is_none());
+
+			test_state
+		})
+	});
+}
+
 #[test]
 fn dispute_gets_confirmed_at_byzantine_threshold() {
 	test_harness(|mut test_state, mut virtual_overseer| {
@@ -24,12 +24,15 @@ use polkadot_primitives::{
 use sc_executor_common::{
 	error::WasmError,
 	runtime_blob::RuntimeBlob,
-	wasm_runtime::{HeapAllocStrategy, WasmModule as _},
+	wasm_runtime::{HeapAllocStrategy, WasmModule},
 };
 use sc_executor_wasmtime::{Config, DeterministicStackLimit, Semantics, WasmtimeRuntime};
 use sp_core::storage::{ChildInfo, TrackedStorageKey};
 use sp_externalities::MultiRemovalResults;
-use std::any::{Any, TypeId};
+use std::{
+	any::{Any, TypeId},
+	sync::OnceLock,
+};
 
 // Memory configuration
 //
@@ -46,6 +49,17 @@ use std::any::{Any, TypeId};
 const DEFAULT_HEAP_PAGES_ESTIMATE: u32 = 32;
 const EXTRA_HEAP_PAGES: u32 = 2048;
 
+/// Size of a single memory page, in bytes.
+const PAGE_SIZE: u64 = 64 * 1024;
+
+/// PolkaVM program blobs start with this magic. They're accepted only when the
+/// [`ExecutorParam::PolkaVm`] parameter is set. The PolkaVM "artifact" is the program blob
+/// itself, since PolkaVM compiles the program when the module is instantiated.
+const POLKAVM_MAGIC: &[u8] = b"PVM\0";
+
+/// Size of a single PolkaVM memory page, in bytes.
+const POLKAVM_PAGE_SIZE: u32 = 0x4000;
+
 // VALUES OF THE DEFAULT CONFIGURATION SHOULD NEVER BE CHANGED
 // They are used as base values for the execution environment parametrization.
 // To overwrite them, add new ones to `EXECUTOR_PARAMS` in the `session_info` pallet and perform
@@ -138,7 +152,12 @@ pub unsafe fn execute_artifact(
 pub unsafe fn create_runtime_from_artifact_bytes(
 	compiled_artifact_blob: &[u8],
 	executor_params: &ExecutorParams,
-) -> Result<WasmtimeRuntime, WasmError> {
+) -> Result<Box<dyn WasmModule>, WasmError> {
+	if compiled_artifact_blob.starts_with(POLKAVM_MAGIC) {
+		let program = parse_polkavm_program(compiled_artifact_blob, executor_params)?;
+		return create_polkavm_runtime(&program, executor_params)
+	}
+
 	let mut config = DEFAULT_CONFIG.clone();
 	config.semantics = params_to_wasmtime_semantics(executor_params).0;
 
@@ -146,6 +165,78 @@ pub unsafe fn create_runtime_from_artifact_bytes(
 		compiled_artifact_blob,
 		config,
 	)
+	.map(|runtime: WasmtimeRuntime| Box::new(runtime) as Box<dyn WasmModule>)
+}
+
+/// Parses the PolkaVM program blob, making sure that PolkaVM PVFs are allowed by the executor
+/// parameters.
+fn parse_polkavm_program(
+	code: &[u8],
+	executor_params: &ExecutorParams,
+) -> Result<polkavm::ProgramBlob, WasmError> {
+	if !executor_params.iter().any(|p| matches!(p, ExecutorParam::PolkaVm)) {
+		return Err(WasmError::Other(
+			"found a PolkaVM program blob, but PolkaVM PVFs are not enabled by the executor parameters"
+				.into(),
+		))
+	}
+
+	Ok(polkavm::ProgramBlob::parse(polkavm::ArcBytes::from(code))?)
+}
+
+/// Returns the PolkaVM engine, that is used to prepare and execute all PolkaVM PVFs.
+///
+/// Unlike `polkavm::Config::from_env`, the configuration never depends on the environment of the
+/// node, so every validator executes PolkaVM PVFs the same way. The interpreter is available on
+/// all platforms and, unlike the compiler, doesn't spawn sandbox processes, which would conflict
+/// with the seccomp and landlock restrictions of the PVF workers.
+fn polkavm_engine() -> Result<&'static polkavm::Engine, WasmError> {
+	static ENGINE: OnceLock<Result<polkavm::Engine, String>> = OnceLock::new();
+
+	ENGINE
+		.get_or_init(|| {
+			let mut config = polkavm::Config::default();
+			config.set_backend(Some(polkavm::BackendKind::Interpreter));
+			config.set_cache_enabled(false);
+			polkavm::Engine::new(&config).map_err(|err| err.to_string())
+		})
+		.as_ref()
+		.map_err(|err| WasmError::Other(err.clone()))
+}
+
+/// Creates the PolkaVM runtime for the given program, enforcing the same memory limit that the
+/// wasmtime runtime would have. The memory, required by the program data and stack, is checked
+/// upfront and the rest of the limit is left for the heap.
+fn create_polkavm_runtime(
+	program: &polkavm::ProgramBlob,
+	executor_params: &ExecutorParams,
+) -> Result<Box<dyn WasmModule>, WasmError> {
+	let maximum_pages = match params_to_wasmtime_semantics(executor_params).0.heap_alloc_strategy {
+		HeapAllocStrategy::Dynamic { maximum_pages } =>
+			maximum_pages.unwrap_or(DEFAULT_HEAP_PAGES_ESTIMATE + EXTRA_HEAP_PAGES),
+		HeapAllocStrategy::Static { extra_pages } => DEFAULT_HEAP_PAGES_ESTIMATE + extra_pages,
+	};
+	let memory_limit = u64::from(maximum_pages) * PAGE_SIZE;
+	let memory_required = u64::from(program.ro_data_size()) +
+		u64::from(program.rw_data_size()) +
+		u64::from(program.stack_size());
+	if memory_required > memory_limit {
+		return Err(WasmError::Other(format!(
+			"PolkaVM program requires {memory_required} bytes of memory, but only {memory_limit} bytes are allowed"
+		)))
+	}
+
+	let heap_limit = u32::try_from(memory_limit - memory_required).unwrap_or(u32::MAX);
+
+	let mut module_config = polkavm::ModuleConfig::new();
+	module_config.set_page_size(POLKAVM_PAGE_SIZE);
+	module_config.set_gas_metering(None);
+	sc_executor_polkavm::create_runtime_with_config::<HostFunctions>(
+		polkavm_engine()?,
+		&module_config,
+		program,
+		heap_limit,
+	)
 }
 
 /// Takes the default config and overwrites any settings with existing executor parameters.
@@ -167,6 +258,7 @@ pub fn params_to_wasmtime_semantics(par: &ExecutorParams) -> (Semantics, Determi
 			ExecutorParam::StackLogicalMax(slm) => stack_limit.logical_max = *slm,
 			ExecutorParam::StackNativeMax(snm) => stack_limit.native_stack_max = *snm,
 			ExecutorParam::WasmExtBulkMemory => sem.wasm_bulk_memory = true,
+			ExecutorParam::PolkaVm |
 			ExecutorParam::PrecheckingMaxMemory(_) |
 			ExecutorParam::PvfPrepTimeout(_, _) |
 			ExecutorParam::PvfExecTimeout(_, _) => (), /* Not used here */
@@ -176,22 +268,48 @@ pub fn params_to_wasmtime_semantics(par: &ExecutorParams) -> (Semantics, Determi
 	(sem, stack_limit)
 }
 
-/// Runs the prevalidation on the given code. Returns a [`RuntimeBlob`] if it succeeds.
-pub fn prevalidate(code: &[u8]) -> Result<RuntimeBlob, sc_executor_common::error::WasmError> {
+/// A prevalidated PVF code.
+pub enum PvfBlob {
+	/// WebAssembly PVF, executed by wasmtime.
+	Wasm(RuntimeBlob),
+	/// Raw PolkaVM program blob, that is known to be parseable.
+	PolkaVm(Vec<u8>),
+}
+
+/// Runs the prevalidation on the given code. Returns a [`PvfBlob`] if it succeeds.
+pub fn prevalidate(code: &[u8]) -> Result<PvfBlob, sc_executor_common::error::WasmError> {
+	// PolkaVM blobs are parsed here, regardless of the executor parameters. Whether they're
+	// allowed is decided during preparation.
+	if code.starts_with(POLKAVM_MAGIC) {
+		polkavm::ProgramBlob::parse(polkavm::ArcBytes::from(code))?;
+		return Ok(PvfBlob::PolkaVm(code.to_vec()))
+	}
+
 	// Construct the runtime blob and do some basic checks for consistency.
 	let blob = RuntimeBlob::new(code)?;
 	// In the future this function should take care of any further prevalidation logic.
-	Ok(blob)
+	Ok(PvfBlob::Wasm(blob))
 }
 
 /// Runs preparation on the given runtime blob. If successful, it returns a serialized compiled
 /// artifact which can then be used to pass into `Executor::execute` after writing it to the disk.
 pub fn prepare(
-	blob: RuntimeBlob,
+	blob: PvfBlob,
 	executor_params: &ExecutorParams,
 ) -> Result<Vec<u8>, sc_executor_common::error::WasmError> {
-	let (semantics, _) = params_to_wasmtime_semantics(executor_params);
-	sc_executor_wasmtime::prepare_runtime_artifact(blob, &semantics)
+	match blob {
+		PvfBlob::Wasm(blob) => {
+			let (semantics, _) = params_to_wasmtime_semantics(executor_params);
+			sc_executor_wasmtime::prepare_runtime_artifact(blob, &semantics)
+		},
+		PvfBlob::PolkaVm(code) => {
+			// PolkaVM doesn't support ahead-of-time compilation, so the artifact is the program
+			// itself. We still create the runtime once to surface compilation and linking errors.
+			let program = parse_polkavm_program(&code, executor_params)?;
+			create_polkavm_runtime(&program, executor_params)?;
+			Ok(code)
+		},
+	}
 }
 
 /// Available host functions. We leave out:
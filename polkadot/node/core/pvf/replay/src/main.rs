@@ -0,0 +1,306 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A tool for re-validating a candidate offline, e.g. when investigating a dispute.
+//!
+//! The available data of the candidate is taken either from the av-store of a node or from a file
+//! containing the SCALE-encoded `AvailableData`. The validation code and the executor parameters
+//! of the session the candidate was disputed in are read from the state of a relay chain node at
+//! the relay parent of the candidate. The candidate is replayed through the real prepare and
+//! execute workers, the same way the candidate-validation subsystem would.
+//!
+//! Preparation and execution are run as separate steps, so that their timings and errors can be
+//! told apart.
+
+use clap::Parser;
+use color_eyre::eyre::{self, eyre};
+use polkadot_node_core_pvf::{
+	start, Config, Metrics, PrepareJobKind, Priority, PvfPrepData, ValidationHost,
+};
+use polkadot_node_subsystem::messages::PvfExecKind;
+use polkadot_primitives::{
+	executor_params::{
+		DEFAULT_APPROVAL_EXECUTION_TIMEOUT, DEFAULT_LENIENT_PREPARATION_TIMEOUT,
+		DEFAULT_PRECHECK_PREPARATION_TIMEOUT,
+	},
+	CandidateHash, ExecutorParams, Hash, PvfExecKind as RuntimePvfExecKind, PvfPrepKind,
+	ValidationCodeHash, MAX_CODE_SIZE,
+};
+use std::{
+	path::PathBuf,
+	sync::Arc,
+	time::{Duration, Instant},
+};
+
+mod source;
+
+use source::{AvailableDataSource, DatabaseBackend, RelayChainState};
+
+const LOG_TARGET: &str = "parachain::pvf-replay";
+
+/// The default limit for decompressing the validation code.
+const DEFAULT_VALIDATION_CODE_BOMB_LIMIT: u32 = MAX_CODE_SIZE * 4;
+
+#[derive(Debug, Parser)]
+#[command(about, rename_all = "kebab-case")]
+struct Cli {
+	/// Path to a file containing the SCALE-encoded `AvailableData` of the candidate.
+	#[arg(
+		long,
+		conflicts_with_all = ["database", "candidate_hash"],
+		required_unless_present = "database"
+	)]
+	available_data: Option<PathBuf>,
+
+	/// Base path of the node whose av-store the available data is read from.
+	///
+	/// This is the directory containing the `parachains` database, i.e. the node's chain data
+	/// directory. The database is opened read-only, so the node may keep running.
+	#[arg(long, requires = "candidate_hash")]
+	database: Option<PathBuf>,
+
+	/// The backend of the database given with `--database`.
+	#[arg(long, value_enum, default_value = "rocks-db")]
+	database_backend: DatabaseBackend,
+
+	/// The hash of the candidate to look up in the av-store.
+	#[arg(long, value_parser = parse_candidate_hash)]
+	candidate_hash: Option<CandidateHash>,
+
+	/// The WebSocket RPC endpoint of a relay chain node to read the validation code and the
+	/// executor parameters from.
+	///
+	/// The node must keep the state of the relay parent, e.g. be an archive node for old
+	/// candidates.
+	#[arg(long, default_value = "ws://127.0.0.1:9944")]
+	rpc_url: String,
+
+	/// The relay parent of the candidate.
+	#[arg(long, value_parser = parse_hash)]
+	relay_parent: Hash,
+
+	/// The hash of the validation code of the candidate.
+	#[arg(long, value_parser = parse_hash)]
+	validation_code_hash: Hash,
+
+	/// Path to the prepare worker binary.
+	#[arg(long)]
+	prepare_worker: PathBuf,
+
+	/// Path to the execute worker binary.
+	#[arg(long)]
+	execute_worker: PathBuf,
+
+	/// Directory to keep the prepared artifacts in. A temporary directory is used if not given.
+	#[arg(long)]
+	cache_dir: Option<PathBuf>,
+
+	/// Prepare the PVF as a pre-check instead of as a compilation for execution.
+	///
+	/// This uses the stricter pre-checking timeout.
+	#[arg(long)]
+	precheck: bool,
+
+	/// Override the preparation timeout, in milliseconds.
+	#[arg(long)]
+	prep_timeout_ms: Option<u64>,
+
+	/// Override the execution timeout, in milliseconds.
+	///
+	/// By default the approval timeout of the session is used, like for disputes.
+	#[arg(long)]
+	exec_timeout_ms: Option<u64>,
+
+	/// The limit for decompressing the validation code.
+	#[arg(long, default_value_t = DEFAULT_VALIDATION_CODE_BOMB_LIMIT)]
+	validation_code_bomb_limit: u32,
+
+	/// Run the workers in secure validator mode.
+	#[arg(long)]
+	secure_validator_mode: bool,
+}
+
+fn parse_hash(s: &str) -> Result<Hash, String> {
+	s.trim_start_matches("0x")
+		.parse::<Hash>()
+		.map_err(|err| format!("invalid hash: {:?}", err))
+}
+
+fn parse_candidate_hash(s: &str) -> Result<CandidateHash, String> {
+	parse_hash(s).map(CandidateHash)
+}
+
+impl Cli {
+	fn available_data_source(&self) -> eyre::Result<AvailableDataSource> {
+		match (&self.available_data, &self.database, self.candidate_hash) {
+			(Some(path), _, _) => Ok(AvailableDataSource::File(path.clone())),
+			(None, Some(base_path), Some(candidate_hash)) => Ok(AvailableDataSource::Database {
+				base_path: base_path.clone(),
+				backend: self.database_backend,
+				candidate_hash,
+			}),
+			_ => Err(eyre!(
+				"either `--available-data` or `--database` with `--candidate-hash` is required"
+			)),
+		}
+	}
+
+	fn prep_kind(&self) -> PrepareJobKind {
+		if self.precheck {
+			PrepareJobKind::Prechecking
+		} else {
+			PrepareJobKind::Compilation
+		}
+	}
+
+	fn prep_timeout(&self, executor_params: &ExecutorParams) -> Duration {
+		if let Some(ms) = self.prep_timeout_ms {
+			return Duration::from_millis(ms)
+		}
+
+		let (kind, default) = if self.precheck {
+			(PvfPrepKind::Precheck, DEFAULT_PRECHECK_PREPARATION_TIMEOUT)
+		} else {
+			(PvfPrepKind::Prepare, DEFAULT_LENIENT_PREPARATION_TIMEOUT)
+		};
+		executor_params.pvf_prep_timeout(kind).unwrap_or(default)
+	}
+
+	fn exec_timeout(&self, executor_params: &ExecutorParams) -> Duration {
+		if let Some(ms) = self.exec_timeout_ms {
+			return Duration::from_millis(ms)
+		}
+
+		executor_params
+			.pvf_exec_timeout(RuntimePvfExecKind::Approval)
+			.unwrap_or(DEFAULT_APPROVAL_EXECUTION_TIMEOUT)
+	}
+}
+
+#[tokio::main]
+async fn main() -> eyre::Result<()> {
+	color_eyre::install()?;
+	sp_tracing::try_init_simple();
+
+	let cli = Cli::parse();
+
+	let available_data = cli.available_data_source()?.load()?;
+
+	let relay_chain = RelayChainState::connect(&cli.rpc_url, cli.relay_parent).await?;
+	let session = relay_chain.session_index().await?;
+	let executor_params = relay_chain.executor_params(session).await?;
+	let code = relay_chain
+		.validation_code(ValidationCodeHash::from(cli.validation_code_hash))
+		.await?
+		.0;
+
+	let prep_timeout = cli.prep_timeout(&executor_params);
+	let exec_timeout = cli.exec_timeout(&executor_params);
+
+	println!("Session:           {}", session);
+	println!("Validation code:   {} bytes", code.len());
+	println!("PoV:               {} bytes", available_data.pov.block_data.0.len());
+	println!("Executor params:   {:?}", executor_params);
+	println!("Relay parent:      #{}", available_data.validation_data.relay_parent_number);
+	println!("Prep timeout:      {:?}", prep_timeout);
+	println!("Exec timeout:      {:?}", exec_timeout);
+
+	// Keep the temporary directory alive until the workers are done with it.
+	let temp_dir = tempfile::tempdir()?;
+	let cache_path = cli.cache_dir.clone().unwrap_or_else(|| temp_dir.path().to_owned());
+
+	// Worker version checks are skipped; the workers are expected to match the node whose
+	// behavior is being investigated, not this tool.
+	let config = Config::new(
+		cache_path,
+		None,
+		cli.secure_validator_mode,
+		cli.prepare_worker.clone(),
+		cli.execute_worker.clone(),
+		1,
+		1,
+		1,
+	);
+	let (mut host, task) = start(config, Metrics::default()).await?;
+	let _ = tokio::task::spawn(task);
+
+	let pvf = PvfPrepData::from_code(
+		code,
+		executor_params,
+		prep_timeout,
+		cli.prep_kind(),
+		cli.validation_code_bomb_limit,
+	);
+
+	gum::debug!(target: LOG_TARGET, code_hash = ?pvf.code_hash(), "Preparing the PVF");
+
+	let started = Instant::now();
+	let prepare_result = prepare(&mut host, pvf.clone()).await?;
+	let prepare_elapsed = started.elapsed();
+
+	println!();
+	println!("Preparation:       {:?}", prepare_elapsed);
+	if let Err(err) = prepare_result {
+		println!("Preparation error: {:?}", err);
+		std::process::exit(1)
+	}
+
+	gum::debug!(target: LOG_TARGET, "Executing the candidate");
+
+	let started = Instant::now();
+	let (result_tx, result_rx) = futures::channel::oneshot::channel();
+	host.execute_pvf(
+		pvf,
+		exec_timeout,
+		Arc::new(available_data.validation_data),
+		Arc::new(available_data.pov),
+		Priority::Critical,
+		PvfExecKind::Dispute,
+		result_tx,
+	)
+	.await
+	.map_err(|err| eyre!(err))?;
+	let execute_result = result_rx.await?;
+	let execute_elapsed = started.elapsed();
+
+	println!("Execution:         {:?}", execute_elapsed);
+	match execute_result {
+		Ok(validation_result) => {
+			println!("Result:            valid");
+			println!("{:#?}", validation_result);
+			Ok(())
+		},
+		Err(err) => {
+			println!("Result:            invalid");
+			println!("Execution error:   {}", err);
+			std::process::exit(1)
+		},
+	}
+}
+
+/// Prepare the PVF, waiting for the preparation to conclude.
+///
+/// A pre-check request is used to learn the outcome of the preparation. The prepared artifact is
+/// kept by the host, so the subsequent execution does not prepare again.
+async fn prepare(
+	host: &mut ValidationHost,
+	pvf: PvfPrepData,
+) -> eyre::Result<Result<(), polkadot_node_core_pvf::PrepareError>> {
+	let (result_tx, result_rx) = futures::channel::oneshot::channel();
+	host.precheck_pvf(pvf, result_tx).await.map_err(|err| eyre!(err))?;
+
+	Ok(result_rx.await?)
+}
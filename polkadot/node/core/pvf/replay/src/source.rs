@@ -0,0 +1,156 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Loading of the inputs needed to replay a candidate validation.
+
+use codec::{Decode, Encode};
+use color_eyre::eyre::{self, eyre, WrapErr};
+use polkadot_node_primitives::AvailableData;
+use polkadot_primitives::{
+	CandidateHash, ExecutorParams, Hash, SessionIndex, ValidationCode, ValidationCodeHash,
+};
+use sp_core::Bytes;
+use std::path::{Path, PathBuf};
+use substrate_rpc_client::{ws_client, StateApi, WsClient};
+
+/// The av-store prefix under which the full available data of a candidate is kept.
+///
+/// Must be kept in sync with `polkadot-node-core-av-store`.
+const AVAILABLE_PREFIX: &[u8; 9] = b"available";
+
+/// The database backend used by the node the data is read from.
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+pub enum DatabaseBackend {
+	/// RocksDB.
+	RocksDb,
+	/// ParityDB.
+	ParityDb,
+}
+
+/// Where to take the available data of the candidate from.
+pub enum AvailableDataSource {
+	/// A file containing the SCALE-encoded `AvailableData`.
+	File(PathBuf),
+	/// The av-store of a node, looked up by candidate hash.
+	Database { base_path: PathBuf, backend: DatabaseBackend, candidate_hash: CandidateHash },
+}
+
+impl AvailableDataSource {
+	/// Load and decode the available data.
+	pub fn load(&self) -> eyre::Result<AvailableData> {
+		let encoded = match self {
+			Self::File(path) => read_file(path)?,
+			Self::Database { base_path, backend, candidate_hash } => {
+				let key = (AVAILABLE_PREFIX, candidate_hash).encode();
+				let value = read_av_store(base_path, *backend, &key)?;
+
+				value.ok_or_else(|| {
+					eyre!("no available data for candidate {:?} in the av-store", candidate_hash)
+				})?
+			},
+		};
+
+		AvailableData::decode(&mut &encoded[..]).wrap_err("failed to decode the available data")
+	}
+}
+
+/// The state of the relay chain the candidate is validated against, as seen by a node.
+pub struct RelayChainState {
+	client: WsClient,
+	relay_parent: Hash,
+}
+
+impl RelayChainState {
+	/// Connect to the RPC endpoint of a relay chain node, reading the state at the relay parent.
+	pub async fn connect(url: &str, relay_parent: Hash) -> eyre::Result<Self> {
+		let client = ws_client(url)
+			.await
+			.map_err(|err| eyre!("failed to connect to {}: {}", url, err))?;
+
+		Ok(Self { client, relay_parent })
+	}
+
+	/// The index of the session the candidate is validated in, i.e. of the child of the relay
+	/// parent.
+	pub async fn session_index(&self) -> eyre::Result<SessionIndex> {
+		self.runtime_call("ParachainHost_session_index_for_child", ()).await
+	}
+
+	/// The executor parameters of the given session.
+	pub async fn executor_params(&self, session: SessionIndex) -> eyre::Result<ExecutorParams> {
+		let executor_params: Option<ExecutorParams> =
+			self.runtime_call("ParachainHost_session_executor_params", session).await?;
+
+		executor_params.ok_or_else(|| eyre!("no executor parameters for session {}", session))
+	}
+
+	/// The validation code with the given hash.
+	pub async fn validation_code(
+		&self,
+		code_hash: ValidationCodeHash,
+	) -> eyre::Result<ValidationCode> {
+		let code: Option<ValidationCode> =
+			self.runtime_call("ParachainHost_validation_code_by_hash", code_hash).await?;
+
+		code.ok_or_else(|| eyre!("no validation code with hash {:?}", code_hash))
+	}
+
+	async fn runtime_call<R: Decode>(&self, method: &str, args: impl Encode) -> eyre::Result<R> {
+		let encoded = StateApi::<Hash>::call(
+			&self.client,
+			method.to_owned(),
+			Bytes(args.encode()),
+			Some(self.relay_parent),
+		)
+		.await
+		.wrap_err_with(|| format!("failed to call `{}` at {:?}", method, self.relay_parent))?;
+
+		R::decode(&mut &encoded[..])
+			.wrap_err_with(|| format!("failed to decode the result of `{}`", method))
+	}
+}
+
+/// Read a whole file, attaching the path to the error.
+pub fn read_file(path: &Path) -> eyre::Result<Vec<u8>> {
+	std::fs::read(path).wrap_err_with(|| format!("failed to read {}", path.display()))
+}
+
+/// Read a value from the av-store column of the parachains database.
+///
+/// The database is opened read-only, so the node may keep running.
+fn read_av_store(
+	base_path: &Path,
+	backend: DatabaseBackend,
+	key: &[u8],
+) -> eyre::Result<Option<Vec<u8>>> {
+	use kvdb::KeyValueDB;
+
+	// RocksDB keeps the logs of secondary instances here, they are not needed afterwards.
+	let secondary_path = tempfile::tempdir()?;
+	let db = match backend {
+		DatabaseBackend::RocksDb => polkadot_service::open_read_only_rocksdb(
+			base_path.to_owned(),
+			secondary_path.path().to_owned(),
+		),
+		DatabaseBackend::ParityDb =>
+			polkadot_service::open_read_only_paritydb(base_path.to_owned()),
+	}
+	.wrap_err_with(|| {
+		format!("failed to open the parachains database at {}", base_path.display())
+	})?;
+
+	Ok(db.get(polkadot_service::REAL_COLUMNS.col_availability_data, key)?)
+}
@@ -39,6 +39,7 @@ use std::{io::Write, sync::Arc, time::Duration};
 use tokio::sync::Mutex;
 
 mod adder;
+mod polkavm;
 #[cfg(target_os = "linux")]
 mod process;
 mod worker_common;
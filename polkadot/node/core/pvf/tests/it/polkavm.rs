@@ -0,0 +1,221 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! PVF host integration tests checking preparation and execution of PolkaVM PVFs.
+
+use super::TestHost;
+use assert_matches::assert_matches;
+use codec::{Decode, Encode};
+use polkadot_node_core_pvf::{InvalidCandidate, PrepareError, ValidationError};
+use polkadot_node_primitives::PoV;
+use polkadot_parachain_primitives::primitives::{
+	BlockData as GenericBlockData, HeadData as GenericHeadData, ValidationResult,
+};
+use polkadot_primitives::{ExecutorParam, ExecutorParams, PersistedValidationData};
+use polkavm_common::{
+	abi::MemoryMapBuilder,
+	program::{asm, Reg},
+	writer::ProgramBlobBuilder,
+};
+use sp_core::H256;
+use test_parachain_adder::{hash_state, BlockData, HeadData};
+
+/// Page size that is used by the PVF executor for PolkaVM programs.
+const POLKAVM_PAGE_SIZE: u32 = 0x4000;
+
+/// Builds the PolkaVM PVF, which `validate_block` export returns the given encoded validation
+/// result. If `heap_size` is given, the PVF grows its heap by that number of bytes first.
+fn polkavm_pvf(result: &ValidationResult, heap_size: Option<u32>) -> Vec<u8> {
+	let result = result.encode();
+	let result_size = result.len() as u32;
+	let ro_data_address = MemoryMapBuilder::new(POLKAVM_PAGE_SIZE)
+		.ro_data_size(result_size)
+		.build()
+		.unwrap()
+		.ro_data_address();
+
+	let mut code = Vec::new();
+	if let Some(heap_size) = heap_size {
+		code.push(asm::load_imm(Reg::A0, heap_size));
+		code.push(asm::sbrk(Reg::A0, Reg::A0));
+	}
+	code.push(asm::load_imm(Reg::A0, ro_data_address));
+	code.push(asm::load_imm(Reg::A1, result_size));
+	code.push(asm::ret());
+
+	let mut builder = ProgramBlobBuilder::new();
+	builder.set_ro_data_size(result_size);
+	builder.set_ro_data(result);
+	builder.set_stack_size(POLKAVM_PAGE_SIZE);
+	builder.add_export_by_basic_block(0, b"validate_block");
+	builder.set_code(&code, &[]);
+	builder.into_vec()
+}
+
+fn validation_result() -> ValidationResult {
+	ValidationResult {
+		head_data: GenericHeadData(vec![42; 32]),
+		new_validation_code: None,
+		upward_messages: Default::default(),
+		horizontal_messages: Default::default(),
+		processed_downward_messages: 0,
+		hrmp_watermark: 1,
+	}
+}
+
+fn pvd() -> PersistedValidationData {
+	PersistedValidationData {
+		parent_head: Default::default(),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	}
+}
+
+fn pov() -> PoV {
+	PoV { block_data: GenericBlockData(Vec::new()) }
+}
+
+fn polkavm_executor_params() -> ExecutorParams {
+	ExecutorParams::from(&[ExecutorParam::PolkaVm][..])
+}
+
+#[tokio::test]
+async fn polkavm_pvf_is_rejected_if_not_enabled() {
+	let host = TestHost::new().await;
+
+	let result = host
+		.precheck_pvf(&polkavm_pvf(&validation_result(), None), Default::default())
+		.await;
+
+	assert_matches!(result, Err(PrepareError::Preparation(err)) if err.contains("not enabled"));
+}
+
+#[tokio::test]
+async fn polkavm_pvf_is_prepared_if_enabled() {
+	let host = TestHost::new().await;
+
+	host.precheck_pvf(&polkavm_pvf(&validation_result(), None), polkavm_executor_params())
+		.await
+		.unwrap();
+}
+
+#[tokio::test]
+async fn polkavm_pvf_is_executed_if_enabled() {
+	let host = TestHost::new().await;
+
+	let result = host
+		.validate_candidate(
+			&polkavm_pvf(&validation_result(), None),
+			pvd(),
+			pov(),
+			polkavm_executor_params(),
+			H256::default(),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(result, validation_result());
+}
+
+#[tokio::test]
+async fn wasm_pvf_is_executed_if_polkavm_is_enabled() {
+	let parent_head = HeadData { number: 0, parent_hash: [0; 32], post_state: hash_state(0) };
+	let block_data = BlockData { state: 0, add: 512 };
+	let pvd = PersistedValidationData {
+		parent_head: GenericHeadData(parent_head.encode()),
+		relay_parent_number: 1u32,
+		relay_parent_storage_root: H256::default(),
+		max_pov_size: 4096 * 1024,
+	};
+	let pov = PoV { block_data: GenericBlockData(block_data.encode()) };
+
+	let host = TestHost::new().await;
+
+	let result = host
+		.validate_candidate(
+			test_parachain_adder::wasm_binary_unwrap(),
+			pvd,
+			pov,
+			polkavm_executor_params(),
+			H256::default(),
+		)
+		.await
+		.unwrap();
+
+	let new_head = HeadData::decode(&mut &result.head_data.0[..]).unwrap();
+	assert_eq!(new_head.number, 1);
+	assert_eq!(new_head.post_state, hash_state(512));
+}
+
+#[tokio::test]
+async fn polkavm_pvf_heap_is_limited() {
+	// the memory limit is (64 + 32) pages of 64KiB => the PVF may not allocate 8MiB
+	let executor_params =
+		ExecutorParams::from(&[ExecutorParam::PolkaVm, ExecutorParam::MaxMemoryPages(64)][..]);
+	let host = TestHost::new().await;
+
+	let result = host
+		.validate_candidate(
+			&polkavm_pvf(&validation_result(), Some(1024 * 1024)),
+			pvd(),
+			pov(),
+			executor_params.clone(),
+			H256::default(),
+		)
+		.await;
+	assert_eq!(result.unwrap(), validation_result());
+
+	let result = host
+		.validate_candidate(
+			&polkavm_pvf(&validation_result(), Some(8 * 1024 * 1024)),
+			pvd(),
+			pov(),
+			executor_params,
+			H256::default(),
+		)
+		.await;
+	assert_matches!(
+		result,
+		Err(ValidationError::Invalid(InvalidCandidate::WorkerReportedInvalid(err)))
+			if err.contains("heap limit")
+	);
+}
+
+// The PolkaVM executor must work in the workers with all the security features enabled.
+#[cfg(all(feature = "ci-only-tests", target_os = "linux"))]
+#[tokio::test]
+async fn polkavm_pvf_is_executed_with_all_security_features() {
+	let host = TestHost::new().await;
+
+	let security_status = host.security_status().await;
+	assert!(security_status.can_enable_seccomp);
+	assert!(security_status.can_unshare_user_namespace_and_change_root);
+	assert!(security_status.can_do_secure_clone);
+
+	let result = host
+		.validate_candidate(
+			&polkavm_pvf(&validation_result(), None),
+			pvd(),
+			pov(),
+			polkavm_executor_params(),
+			H256::default(),
+		)
+		.await
+		.unwrap();
+
+	assert_eq!(result, validation_result());
+}
@@ -42,8 +42,8 @@ use task::{
 };
 
 use polkadot_erasure_coding::{
-	branches, obtain_chunks_v1, recovery_threshold, systematic_recovery_threshold,
-	Error as ErasureEncodingError,
+	branches_parallel, obtain_chunks_v1_parallel, recovery_threshold,
+	systematic_recovery_threshold, Error as ErasureEncodingError, ThreadPool,
 };
 use task::{RecoveryParams, RecoveryStrategy, RecoveryTask};
 
@@ -138,6 +138,8 @@ pub struct AvailabilityRecoverySubsystem {
 	req_v1_protocol_name: ProtocolName,
 	/// Full protocol name for ChunkFetchingV2.
 	req_v2_protocol_name: ProtocolName,
+	/// The pool of threads a single erasure coding operation is split across.
+	erasure_coding_pool: ThreadPool,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -182,10 +184,11 @@ fn reconstructed_data_matches_root(
 	expected_root: &Hash,
 	data: &AvailableData,
 	metrics: &Metrics,
+	erasure_coding_pool: &ThreadPool,
 ) -> bool {
 	let _timer = metrics.time_reencode_chunks();
 
-	let chunks = match obtain_chunks_v1(n_validators, data) {
+	let chunks = match obtain_chunks_v1_parallel(n_validators, data, erasure_coding_pool) {
 		Ok(chunks) => chunks,
 		Err(e) => {
 			gum::debug!(
@@ -197,7 +200,7 @@ fn reconstructed_data_matches_root(
 		},
 	};
 
-	let branches = branches(&chunks);
+	let branches = branches_parallel(&chunks, erasure_coding_pool);
 
 	branches.root() == *expected_root
 }
@@ -383,6 +386,7 @@ async fn handle_recover<Context>(
 	maybe_core_index: Option<CoreIndex>,
 	req_v1_protocol_name: ProtocolName,
 	req_v2_protocol_name: ProtocolName,
+	erasure_coding_pool: ThreadPool,
 ) -> Result<()> {
 	let candidate_hash = receipt.hash();
 
@@ -549,6 +553,7 @@ async fn handle_recover<Context>(
 					req_v2_protocol_name,
 					chunk_mapping_enabled,
 					erasure_task_tx,
+					erasure_coding_pool,
 				},
 			)
 			.await
@@ -612,6 +617,7 @@ impl AvailabilityRecoverySubsystem {
 				.get_name(request_v1::ChunkFetchingRequest::PROTOCOL),
 			req_v2_protocol_name: req_protocol_names
 				.get_name(request_v2::ChunkFetchingRequest::PROTOCOL),
+			erasure_coding_pool: Default::default(),
 		}
 	}
 
@@ -642,6 +648,7 @@ impl AvailabilityRecoverySubsystem {
 				.get_name(request_v1::ChunkFetchingRequest::PROTOCOL),
 			req_v2_protocol_name: req_protocol_names
 				.get_name(request_v2::ChunkFetchingRequest::PROTOCOL),
+			erasure_coding_pool: Default::default(),
 		}
 	}
 
@@ -664,9 +671,19 @@ impl AvailabilityRecoverySubsystem {
 				.get_name(request_v1::ChunkFetchingRequest::PROTOCOL),
 			req_v2_protocol_name: req_protocol_names
 				.get_name(request_v2::ChunkFetchingRequest::PROTOCOL),
+			erasure_coding_pool: Default::default(),
 		}
 	}
 
+	/// Split each erasure coding operation across the threads of `pool`.
+	///
+	/// This speeds up the reconstruction and re-encoding of large PoVs. By default, each
+	/// operation runs on a single thread.
+	pub fn with_erasure_coding_pool(mut self, pool: ThreadPool) -> Self {
+		self.erasure_coding_pool = pool;
+		self
+	}
+
 	/// Starts the inner subsystem loop.
 	pub async fn run<Context>(self, mut ctx: Context) -> std::result::Result<(), FatalError> {
 		let mut state = State::default();
@@ -678,6 +695,7 @@ impl AvailabilityRecoverySubsystem {
 			post_recovery_check,
 			req_v1_protocol_name,
 			req_v2_protocol_name,
+			erasure_coding_pool,
 		} = self;
 
 		let (erasure_task_tx, erasure_task_rx) = futures::channel::mpsc::channel(16);
@@ -705,6 +723,7 @@ impl AvailabilityRecoverySubsystem {
 			// Pool is guaranteed to have at least 1 worker thread.
 			NonZeroUsize::new(2).expect("There are 2 threads; qed"),
 			metrics.clone(),
+			&erasure_coding_pool,
 			&mut ctx,
 		)
 		.into_iter()
@@ -765,6 +784,7 @@ impl AvailabilityRecoverySubsystem {
 										maybe_core_index,
 										req_v1_protocol_name.clone(),
 										req_v2_protocol_name.clone(),
+										erasure_coding_pool.clone(),
 									).await
 							}
 						},
@@ -848,6 +868,7 @@ impl ThreadPoolBuilder {
 	pub fn build<Context>(
 		size: NonZeroUsize,
 		metrics: Metrics,
+		erasure_coding_pool: &ThreadPool,
 		ctx: &mut Context,
 	) -> Vec<futures::channel::mpsc::Sender<ErasureTask>> {
 		// At least 1 task, at most `MAX_THREADS.
@@ -858,9 +879,10 @@ impl ThreadPoolBuilder {
 			let (tx, rx) = futures::channel::mpsc::channel(8);
 			senders.push(tx);
 
-			if let Err(e) = ctx
-				.spawn_blocking("erasure-task", Box::pin(erasure_task_thread(metrics.clone(), rx)))
-			{
+			if let Err(e) = ctx.spawn_blocking(
+				"erasure-task",
+				Box::pin(erasure_task_thread(metrics.clone(), erasure_coding_pool.clone(), rx)),
+			) {
 				gum::warn!(
 					target: LOG_TARGET,
 					err = ?e,
@@ -876,12 +898,13 @@ impl ThreadPoolBuilder {
 // Handles CPU intensive operation on a dedicated blocking thread.
 async fn erasure_task_thread(
 	metrics: Metrics,
+	erasure_coding_pool: ThreadPool,
 	mut ingress: futures::channel::mpsc::Receiver<ErasureTask>,
 ) {
 	loop {
 		match ingress.next().await {
 			Some(ErasureTask::Reconstruct(n_validators, chunks, sender)) => {
-				let _ = sender.send(polkadot_erasure_coding::reconstruct_v1(
+				let _ = sender.send(polkadot_erasure_coding::reconstruct_v1_parallel(
 					n_validators,
 					chunks.iter().map(|(c_index, chunk)| {
 						(
@@ -890,6 +913,7 @@ async fn erasure_task_thread(
 								.expect("usize is at least u32 bytes on all modern targets."),
 						)
 					}),
+					&erasure_coding_pool,
 				));
 			},
 			Some(ErasureTask::Reencode(n_validators, root, available_data, sender)) => {
@@ -900,6 +924,7 @@ async fn erasure_task_thread(
 					&root,
 					&available_data,
 					&metrics,
+					&erasure_coding_pool,
 				) {
 					Some(available_data)
 				} else {
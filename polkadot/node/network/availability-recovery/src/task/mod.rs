@@ -31,6 +31,7 @@ pub use self::strategy::{REGULAR_CHUNKS_REQ_RETRY_LIMIT, SYSTEMATIC_CHUNKS_REQ_R
 use crate::{metrics::Metrics, ErasureTask, PostRecoveryCheck, LOG_TARGET};
 
 use codec::Encode;
+use polkadot_erasure_coding::ThreadPool;
 use polkadot_node_primitives::AvailableData;
 use polkadot_node_subsystem::{messages::AvailabilityStoreMessage, overseer, RecoveryError};
 use polkadot_primitives::{AuthorityDiscoveryId, CandidateHash, Hash};
@@ -83,6 +84,9 @@ pub struct RecoveryParams {
 
 	/// Channel to the erasure task handler.
 	pub erasure_task_tx: mpsc::Sender<ErasureTask>,
+
+	/// The pool of threads a single erasure coding operation is split across.
+	pub erasure_coding_pool: ThreadPool,
 }
 
 /// A stateful reconstruction of availability data in reference to
@@ -683,6 +683,7 @@ mod tests {
 				req_v2_protocol_name: "/req_chunk/2".into(),
 				chunk_mapping_enabled: true,
 				erasure_task_tx,
+				erasure_coding_pool: Default::default(),
 			}
 		}
 	}
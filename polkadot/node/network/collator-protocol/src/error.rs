@@ -153,6 +153,9 @@ pub enum FetchError {
 
 	#[error("Peer's protocol doesn't match the advertisement")]
 	ProtocolMismatch,
+
+	#[error("No free slot in the waiting queue for the collator")]
+	WaitingQueueFull,
 }
 
 /// Utility for eating top level errors and log them.
@@ -21,14 +21,17 @@
 #![deny(unused_crate_dependencies)]
 #![recursion_limit = "256"]
 
-use std::time::{Duration, Instant};
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
 
 use futures::{
 	stream::{FusedStream, StreamExt},
 	FutureExt, TryFutureExt,
 };
 
-use polkadot_node_subsystem_util::reputation::ReputationAggregator;
+use polkadot_node_subsystem_util::{database::Database, reputation::ReputationAggregator};
 use sp_keystore::KeystorePtr;
 
 use polkadot_node_network_protocol::{
@@ -44,6 +47,8 @@ mod error;
 mod collator_side;
 mod validator_side;
 
+pub use validator_side::CollatorScoresConfig;
+
 const LOG_TARGET: &'static str = "parachain::collator-protocol";
 
 /// A collator eviction policy - how fast to evict collators which are inactive.
@@ -74,6 +79,10 @@ pub enum ProtocolSide {
 		eviction_policy: CollatorEvictionPolicy,
 		/// Prometheus metrics for validators.
 		metrics: validator_side::Metrics,
+		/// The database the collator scores are persisted in.
+		db: Arc<dyn Database>,
+		/// Configuration of the collator scores database.
+		scores_config: CollatorScoresConfig,
 	},
 	/// Collators operate on a parachain.
 	Collator {
@@ -110,8 +119,8 @@ impl CollatorProtocolSubsystem {
 impl<Context> CollatorProtocolSubsystem {
 	fn start(self, ctx: Context) -> SpawnedSubsystem {
 		let future = match self.protocol_side {
-			ProtocolSide::Validator { keystore, eviction_policy, metrics } =>
-				validator_side::run(ctx, keystore, eviction_policy, metrics)
+			ProtocolSide::Validator { keystore, eviction_policy, metrics, db, scores_config } =>
+				validator_side::run(ctx, keystore, eviction_policy, metrics, db, scores_config)
 					.map_err(|e| SubsystemError::with_origin("collator-protocol", e))
 					.boxed(),
 			ProtocolSide::Collator { peer_id, collator_pair, request_receiver_v2, metrics } =>
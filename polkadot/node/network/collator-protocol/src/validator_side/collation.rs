@@ -22,14 +22,16 @@
 //!       based on the entries there. A parachain can't have more fetched collations than the
 //!       entries in the claim queue at a specific relay parent. When calculating this limit the
 //!       validator counts all advertisements within its view not just at the relay parent.
-//!    3. If the advertisement was accepted, it's queued for fetch (per relay parent).
+//!    3. If the advertisement was accepted, it's queued for fetch (per relay parent). Some slots of
+//!       the queue are reserved for collators with a high score, see [`CollatorScores`].
 //!    4. Once it's requested, the collation is said to be pending fetch
 //!       (`CollationStatus::Fetching`).
 //!    5. Pending fetch collation becomes pending validation
 //!       (`CollationStatus::WaitingOnValidation`) once received, we send it to backing for
 //!       validation.
 //!    6. If it turns to be invalid or async backing allows seconding another candidate, carry on
-//!       with the next advertisement, otherwise we're done with this relay parent.
+//!       with the next advertisement, otherwise we're done with this relay parent. The next
+//!       advertisement is the one of the highest scoring collator.
 //!
 //!    ┌───────────────────────────────────┐
 //!    └─▶Waiting ─▶ Fetching ─▶ WaitingOnValidation
@@ -39,6 +41,7 @@ use std::{
 	future::Future,
 	pin::Pin,
 	task::Poll,
+	time::{Duration, Instant},
 };
 
 use futures::{future::BoxFuture, FutureExt};
@@ -55,7 +58,19 @@ use polkadot_primitives::{
 };
 use tokio_util::sync::CancellationToken;
 
-use crate::{error::SecondingError, LOG_TARGET};
+use super::collator_scores::CollatorScores;
+use crate::{
+	error::{FetchError, SecondingError},
+	LOG_TARGET,
+};
+
+/// The maximum number of collations waiting to be fetched per para and relay parent.
+const MAX_WAITING_COLLATIONS_PER_PARA: usize = 16;
+
+/// The number of slots in the waiting queue of a para which can only be used by trusted
+/// collators. This keeps spamming collators from crowding out the collators which have been
+/// providing valid collations.
+const RESERVED_WAITING_SLOTS: usize = 4;
 
 /// Candidate supplied with a para head it's built on top of.
 #[derive(Debug, Copy, Clone, Hash, Eq, PartialEq)]
@@ -270,9 +285,28 @@ impl Collations {
 	}
 
 	/// Adds a new collation to the waiting queue for the relay parent. This function doesn't
-	/// perform any limits check. The caller should assure that the collation limit is respected.
-	pub(super) fn add_to_waiting_queue(&mut self, collation: (PendingCollation, CollatorId)) {
-		self.waiting_queue.entry(collation.0.para_id).or_default().push_back(collation);
+	/// check the seconding limit, the caller should assure that it is respected.
+	///
+	/// The last `RESERVED_WAITING_SLOTS` slots of the queue of a para are only available to
+	/// `trusted` collators.
+	pub(super) fn add_to_waiting_queue(
+		&mut self,
+		collation: (PendingCollation, CollatorId),
+		trusted: bool,
+	) -> Result<(), FetchError> {
+		let queue = self.waiting_queue.entry(collation.0.para_id).or_default();
+		let limit = if trusted {
+			MAX_WAITING_COLLATIONS_PER_PARA
+		} else {
+			MAX_WAITING_COLLATIONS_PER_PARA - RESERVED_WAITING_SLOTS
+		};
+
+		if queue.len() >= limit {
+			return Err(FetchError::WaitingQueueFull)
+		}
+
+		queue.push_back(collation);
+		Ok(())
 	}
 
 	/// Picks a collation to fetch from the waiting queue.
@@ -283,13 +317,15 @@ impl Collations {
 	///
 	/// To achieve this each seconded collation is mapped to an entry from the claim queue. The next
 	/// fetch is the first unfulfilled entry from the claim queue for which there is an
-	/// advertisement.
+	/// advertisement. Out of the advertisements for the para, the one of the collator with the
+	/// highest score is picked, in the order they were received if the scores are equal.
 	///
 	/// `unfulfilled_claim_queue_entries` represents all claim queue entries which are still not
 	/// fulfilled.
 	pub(super) fn pick_a_collation_to_fetch(
 		&mut self,
 		unfulfilled_claim_queue_entries: Vec<ParaId>,
+		collator_scores: &CollatorScores,
 	) -> Option<(PendingCollation, CollatorId)> {
 		gum::trace!(
 			target: LOG_TARGET,
@@ -300,11 +336,17 @@ impl Collations {
 
 		for assignment in unfulfilled_claim_queue_entries {
 			// if there is an unfulfilled assignment - return it
-			if let Some(collation) = self
-				.waiting_queue
-				.get_mut(&assignment)
-				.and_then(|collations| collations.pop_front())
-			{
+			let Some(collations) = self.waiting_queue.get_mut(&assignment) else { continue };
+
+			let best = collations
+				.iter()
+				.enumerate()
+				.max_by_key(|(index, (_, collator_id))| {
+					(collator_scores.score(assignment, collator_id), std::cmp::Reverse(*index))
+				})
+				.map(|(index, _)| index);
+
+			if let Some(collation) = best.and_then(|index| collations.remove(index)) {
 				return Some(collation)
 			}
 		}
@@ -344,12 +386,16 @@ pub(super) struct CollationFetchRequest {
 	pub cancellation_token: CancellationToken,
 	/// A metric histogram for the lifetime of the request
 	pub _lifetime_timer: Option<HistogramTimer>,
+	/// When the request was sent.
+	pub requested_at: Instant,
 }
 
 impl Future for CollationFetchRequest {
+	/// The requested collation, the response and how long it took to get it.
 	type Output = (
 		CollationEvent,
 		std::result::Result<request_v1::CollationFetchingResponse, CollationFetchError>,
+		Duration,
 	);
 
 	fn poll(mut self: Pin<&mut Self>, cx: &mut std::task::Context<'_>) -> Poll<Self::Output> {
@@ -367,6 +413,7 @@ impl Future for CollationFetchRequest {
 					pending_collation: self.pending_collation,
 				},
 				Err(CollationFetchError::Cancelled),
+				self.requested_at.elapsed(),
 			))
 		}
 
@@ -378,6 +425,7 @@ impl Future for CollationFetchRequest {
 					pending_collation: self.pending_collation,
 				},
 				res.map_err(CollationFetchError::Request),
+				self.requested_at.elapsed(),
 			)
 		});
 
@@ -0,0 +1,428 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Persistent per-para scores of collators.
+//!
+//! Unlike the network reputation of a peer, which is forgotten on restart and tied to a `PeerId`,
+//! the score is tracked per `(ParaId, CollatorId)` and kept in the database. It is raised for
+//! collations which got seconded and for collations which were delivered in time, and lowered for
+//! invalid collations and fetches which timed out.
+//!
+//! The score is used to order the collations waiting to be fetched and to decide which collators
+//! may use the slots of the waiting queue which are reserved for trusted collators.
+//!
+//! Scores are updated in memory and written to the database in batches, every
+//! [`FLUSH_INTERVAL`], on session changes and on shutdown.
+
+use std::{
+	collections::{HashMap, HashSet},
+	sync::Arc,
+	time::{Duration, SystemTime, UNIX_EPOCH},
+};
+
+use codec::{Decode, Encode};
+use polkadot_node_subsystem_util::database::{DBTransaction, Database};
+use polkadot_primitives::{CollatorId, Id as ParaId, SessionIndex};
+
+use crate::LOG_TARGET;
+
+/// Prefix of the keys of the collator scores in the database.
+const COLLATOR_SCORE_PREFIX: &[u8; 14] = b"collator_score";
+
+/// Score change for a collation which got seconded.
+const VALID_COLLATION_BONUS: i32 = 100;
+/// Score change for a collation which was fetched within `MAX_UNSHARED_DOWNLOAD_TIME`.
+const TIMELY_COLLATION_BONUS: i32 = 10;
+/// Score change for a collation which turned out to be invalid.
+const INVALID_COLLATION_PENALTY: i32 = -1_000;
+/// Score change for a collation fetch which timed out.
+const TIMED_OUT_PENALTY: i32 = -50;
+
+/// Upper bound of a score, so that a collator can't build up an unlimited amount of trust.
+const MAX_SCORE: i32 = 10_000;
+/// Lower bound of a score, so that a collator can recover eventually.
+const MIN_SCORE: i32 = -10_000;
+
+/// The score from which on a collator may use the reserved slots of the waiting queue.
+const TRUSTED_SCORE_THRESHOLD: i32 = 1_000;
+
+/// The maximum number of collators we keep a score for per para. The least recently updated
+/// score is dropped if there are more.
+const MAX_COLLATORS_PER_PARA: usize = 100;
+
+/// Scores which were not updated for this long are dropped on startup.
+const SCORE_RETENTION_SECS: u64 = 30 * 24 * 60 * 60;
+
+/// How often the updated scores are written to the database.
+pub(super) const FLUSH_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Configuration of the collator scores database.
+#[derive(Debug, Clone, Copy)]
+pub struct CollatorScoresConfig {
+	/// The column of the database the collator scores are stored in.
+	pub col_data: u32,
+}
+
+/// Something a collator did which affects its score.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub(super) enum ScoreEvent {
+	/// A collation of the collator got seconded.
+	Valid,
+	/// A collation of the collator turned out to be invalid.
+	Invalid,
+	/// A collation of the collator was fetched in time.
+	Timely,
+	/// Fetching a collation from the collator timed out.
+	TimedOut,
+}
+
+impl ScoreEvent {
+	fn delta(self) -> i32 {
+		match self {
+			Self::Valid => VALID_COLLATION_BONUS,
+			Self::Invalid => INVALID_COLLATION_PENALTY,
+			Self::Timely => TIMELY_COLLATION_BONUS,
+			Self::TimedOut => TIMED_OUT_PENALTY,
+		}
+	}
+}
+
+/// The score of a collator as stored in the database.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Encode, Decode)]
+struct Score {
+	/// The current score.
+	value: i32,
+	/// Seconds since the UNIX epoch of the last update.
+	last_updated: u64,
+}
+
+/// Per-para collator scores, backed by the database.
+pub(super) struct CollatorScores {
+	db: Arc<dyn Database>,
+	config: CollatorScoresConfig,
+	scores: HashMap<ParaId, HashMap<CollatorId, Score>>,
+	/// The scores which were updated or dropped since the last flush.
+	dirty: HashSet<(ParaId, CollatorId)>,
+	/// The session of the latest leaf, the scores are flushed when it changes.
+	session: Option<SessionIndex>,
+}
+
+impl CollatorScores {
+	/// Load all the scores from the database, dropping those which expired.
+	pub(super) fn load(db: Arc<dyn Database>, config: CollatorScoresConfig) -> Self {
+		let now = unix_now();
+		let mut scores = HashMap::<ParaId, HashMap<CollatorId, Score>>::new();
+		let mut expired = Vec::new();
+
+		for item in db.iter_with_prefix(config.col_data, COLLATOR_SCORE_PREFIX) {
+			let (key, value) = match item {
+				Ok(item) => item,
+				Err(err) => {
+					gum::warn!(target: LOG_TARGET, ?err, "Failed to read collator scores");
+					break
+				},
+			};
+
+			let decoded = decode_key(&key).and_then(|(para_id, collator_id)| {
+				Some((para_id, collator_id, decode_value(&value)?))
+			});
+			match decoded {
+				Some((_, _, score))
+					if now.saturating_sub(score.last_updated) > SCORE_RETENTION_SECS =>
+					expired.push(key.to_vec()),
+				Some((para_id, collator_id, score)) => {
+					scores.entry(para_id).or_default().insert(collator_id, score);
+				},
+				None => {
+					gum::debug!(target: LOG_TARGET, "Dropping undecodable collator score");
+					expired.push(key.to_vec());
+				},
+			}
+		}
+
+		let mut scores = Self { db, config, scores, dirty: HashSet::new(), session: None };
+
+		let mut tx = DBTransaction::new();
+		for key in expired {
+			tx.delete(scores.config.col_data, &key);
+		}
+		scores.write(tx);
+
+		let para_ids = scores.scores.keys().copied().collect::<Vec<_>>();
+		for para_id in para_ids {
+			scores.prune_para(para_id);
+		}
+		scores.flush();
+
+		gum::debug!(
+			target: LOG_TARGET,
+			n_paras = scores.scores.len(),
+			"Loaded collator scores",
+		);
+
+		scores
+	}
+
+	/// The score of a collator for a para. Unknown collators have a score of zero.
+	pub(super) fn score(&self, para_id: ParaId, collator_id: &CollatorId) -> i32 {
+		self.scores
+			.get(&para_id)
+			.and_then(|scores| scores.get(collator_id))
+			.map_or(0, |score| score.value)
+	}
+
+	/// Whether the collator has a high enough score to use the reserved slots of the waiting
+	/// queue.
+	pub(super) fn is_trusted(&self, para_id: ParaId, collator_id: &CollatorId) -> bool {
+		self.score(para_id, collator_id) >= TRUSTED_SCORE_THRESHOLD
+	}
+
+	/// Update the score of a collator. It is persisted on the next flush.
+	pub(super) fn note(&mut self, para_id: ParaId, collator_id: CollatorId, event: ScoreEvent) {
+		let para_scores = self.scores.entry(para_id).or_default();
+		let score = para_scores
+			.entry(collator_id.clone())
+			.or_insert(Score { value: 0, last_updated: 0 });
+		score.value = score.value.saturating_add(event.delta()).clamp(MIN_SCORE, MAX_SCORE);
+		score.last_updated = unix_now();
+
+		gum::trace!(
+			target: LOG_TARGET,
+			?para_id,
+			?collator_id,
+			?event,
+			score = score.value,
+			"Updated collator score",
+		);
+
+		self.dirty.insert((para_id, collator_id));
+		self.prune_para(para_id);
+	}
+
+	/// Note the session of a new leaf, flushing the scores if the session changed.
+	pub(super) fn note_session(&mut self, session: SessionIndex) {
+		if self.session.replace(session).map_or(false, |previous| previous != session) {
+			self.flush();
+		}
+	}
+
+	/// Write the scores which were updated or dropped since the last flush to the database.
+	pub(super) fn flush(&mut self) {
+		let mut tx = DBTransaction::new();
+		for (para_id, collator_id) in self.dirty.drain() {
+			let key = encode_key(para_id, &collator_id);
+			match self.scores.get(&para_id).and_then(|scores| scores.get(&collator_id)) {
+				Some(score) => tx.put_vec(self.config.col_data, &key, score.encode()),
+				None => tx.delete(self.config.col_data, &key),
+			}
+		}
+
+		self.write(tx);
+	}
+
+	/// Drop the least recently updated scores of a para if there are too many.
+	fn prune_para(&mut self, para_id: ParaId) {
+		let Some(para_scores) = self.scores.get_mut(&para_id) else { return };
+
+		while para_scores.len() > MAX_COLLATORS_PER_PARA {
+			let Some(oldest) = para_scores
+				.iter()
+				.min_by_key(|(_, score)| score.last_updated)
+				.map(|(collator_id, _)| collator_id.clone())
+			else {
+				break
+			};
+
+			para_scores.remove(&oldest);
+			self.dirty.insert((para_id, oldest));
+		}
+	}
+
+	fn write(&self, tx: DBTransaction) {
+		if tx.ops.is_empty() {
+			return
+		}
+
+		// Losing a score update is not critical, so we carry on with the in-memory state.
+		if let Err(err) = self.db.write(tx) {
+			gum::warn!(target: LOG_TARGET, ?err, "Failed to write collator scores");
+		}
+	}
+}
+
+fn unix_now() -> u64 {
+	SystemTime::now().duration_since(UNIX_EPOCH).map_or(0, |d| d.as_secs())
+}
+
+fn encode_key(para_id: ParaId, collator_id: &CollatorId) -> Vec<u8> {
+	(COLLATOR_SCORE_PREFIX, para_id, collator_id).encode()
+}
+
+fn decode_key(key: &[u8]) -> Option<(ParaId, CollatorId)> {
+	let (_, para_id, collator_id) = <([u8; 14], ParaId, CollatorId)>::decode(&mut &key[..]).ok()?;
+	Some((para_id, collator_id))
+}
+
+fn decode_value(value: &[u8]) -> Option<Score> {
+	Score::decode(&mut &value[..]).ok()
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter;
+	use sp_keyring::Sr25519Keyring;
+
+	const TEST_CONFIG: CollatorScoresConfig = CollatorScoresConfig { col_data: 0 };
+
+	fn make_db() -> Arc<dyn Database> {
+		let db = kvdb_memorydb::create(1);
+		Arc::new(DbAdapter::new(db, &[0]))
+	}
+
+	fn collator(keyring: Sr25519Keyring) -> CollatorId {
+		keyring.public().into()
+	}
+
+	#[test]
+	fn scores_survive_reload() {
+		let db = make_db();
+		let para_id = ParaId::from(1);
+
+		let mut scores = CollatorScores::load(db.clone(), TEST_CONFIG);
+		scores.note(para_id, collator(Sr25519Keyring::Alice), ScoreEvent::Valid);
+		scores.note(para_id, collator(Sr25519Keyring::Alice), ScoreEvent::Timely);
+		scores.note(para_id, collator(Sr25519Keyring::Bob), ScoreEvent::Invalid);
+		scores.flush();
+
+		let scores = CollatorScores::load(db, TEST_CONFIG);
+		assert_eq!(
+			scores.score(para_id, &collator(Sr25519Keyring::Alice)),
+			VALID_COLLATION_BONUS + TIMELY_COLLATION_BONUS,
+		);
+		assert_eq!(
+			scores.score(para_id, &collator(Sr25519Keyring::Bob)),
+			INVALID_COLLATION_PENALTY
+		);
+		assert_eq!(scores.score(ParaId::from(2), &collator(Sr25519Keyring::Alice)), 0);
+	}
+
+	#[test]
+	fn scores_are_bounded() {
+		let db = make_db();
+		let para_id = ParaId::from(1);
+		let alice = collator(Sr25519Keyring::Alice);
+		let bob = collator(Sr25519Keyring::Bob);
+
+		let mut scores = CollatorScores::load(db, TEST_CONFIG);
+		for _ in 0..(MAX_SCORE / VALID_COLLATION_BONUS + 10) {
+			scores.note(para_id, alice.clone(), ScoreEvent::Valid);
+		}
+		for _ in 0..(MIN_SCORE / INVALID_COLLATION_PENALTY + 10) {
+			scores.note(para_id, bob.clone(), ScoreEvent::Invalid);
+		}
+
+		assert_eq!(scores.score(para_id, &alice), MAX_SCORE);
+		assert!(scores.is_trusted(para_id, &alice));
+		assert_eq!(scores.score(para_id, &bob), MIN_SCORE);
+		assert!(!scores.is_trusted(para_id, &bob));
+	}
+
+	#[test]
+	fn scores_are_written_on_flush() {
+		let db = make_db();
+		let para_id = ParaId::from(1);
+		let alice = collator(Sr25519Keyring::Alice);
+		let key = encode_key(para_id, &alice);
+
+		let mut scores = CollatorScores::load(db.clone(), TEST_CONFIG);
+		scores.note(para_id, alice.clone(), ScoreEvent::Valid);
+		assert!(db.get(TEST_CONFIG.col_data, &key).unwrap().is_none());
+
+		scores.flush();
+		let stored = db.get(TEST_CONFIG.col_data, &key).unwrap().and_then(|v| decode_value(&v));
+		assert_eq!(stored.map(|score| score.value), Some(VALID_COLLATION_BONUS));
+
+		// Noting the first session or the same session again doesn't flush.
+		scores.note(para_id, alice.clone(), ScoreEvent::Timely);
+		scores.note_session(1);
+		scores.note_session(1);
+		let stored = db.get(TEST_CONFIG.col_data, &key).unwrap().and_then(|v| decode_value(&v));
+		assert_eq!(stored.map(|score| score.value), Some(VALID_COLLATION_BONUS));
+
+		scores.note_session(2);
+		let stored = db.get(TEST_CONFIG.col_data, &key).unwrap().and_then(|v| decode_value(&v));
+		assert_eq!(
+			stored.map(|score| score.value),
+			Some(VALID_COLLATION_BONUS + TIMELY_COLLATION_BONUS),
+		);
+	}
+
+	#[test]
+	fn pruned_scores_are_deleted_on_flush() {
+		let db = make_db();
+		let para_id = ParaId::from(1);
+		let collators = (0..=MAX_COLLATORS_PER_PARA)
+			.map(|i| CollatorId::from(sp_core::sr25519::Public::from_raw([i as u8; 32])))
+			.collect::<Vec<_>>();
+
+		let mut scores = CollatorScores::load(db.clone(), TEST_CONFIG);
+		for collator_id in &collators[..MAX_COLLATORS_PER_PARA] {
+			scores.note(para_id, collator_id.clone(), ScoreEvent::Valid);
+		}
+		scores.flush();
+
+		// Make the first collator the least recently updated one.
+		scores
+			.scores
+			.get_mut(&para_id)
+			.unwrap()
+			.get_mut(&collators[0])
+			.unwrap()
+			.last_updated = 0;
+		scores.note(para_id, collators[MAX_COLLATORS_PER_PARA].clone(), ScoreEvent::Valid);
+		assert_eq!(scores.score(para_id, &collators[0]), 0);
+		assert!(db
+			.get(TEST_CONFIG.col_data, &encode_key(para_id, &collators[0]))
+			.unwrap()
+			.is_some());
+
+		scores.flush();
+		assert!(db
+			.get(TEST_CONFIG.col_data, &encode_key(para_id, &collators[0]))
+			.unwrap()
+			.is_none());
+	}
+
+	#[test]
+	fn expired_scores_are_dropped_on_load() {
+		let db = make_db();
+		let para_id = ParaId::from(1);
+		let alice = collator(Sr25519Keyring::Alice);
+
+		let mut tx = DBTransaction::new();
+		tx.put_vec(
+			TEST_CONFIG.col_data,
+			&encode_key(para_id, &alice),
+			Score { value: 500, last_updated: unix_now() - SCORE_RETENTION_SECS - 1 }.encode(),
+		);
+		db.write(tx).unwrap();
+
+		let scores = CollatorScores::load(db.clone(), TEST_CONFIG);
+		assert_eq!(scores.score(para_id, &alice), 0);
+		assert!(db.get(TEST_CONFIG.col_data, &encode_key(para_id, &alice)).unwrap().is_none());
+	}
+}
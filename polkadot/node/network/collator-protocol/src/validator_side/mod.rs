@@ -21,6 +21,7 @@ use futures_timer::Delay;
 use std::{
 	collections::{hash_map::Entry, HashMap, HashSet, VecDeque},
 	future::Future,
+	sync::Arc,
 	time::{Duration, Instant},
 };
 use tokio_util::sync::CancellationToken;
@@ -48,6 +49,7 @@ use polkadot_node_subsystem::{
 };
 use polkadot_node_subsystem_util::{
 	backing_implicit_view::View as ImplicitView,
+	database::Database,
 	reputation::{ReputationAggregator, REPUTATION_CHANGE_INTERVAL},
 	request_claim_queue, request_node_features, request_session_index_for_child,
 };
@@ -68,8 +70,11 @@ use super::{modify_reputation, tick_stream, LOG_TARGET};
 
 mod claim_queue_state;
 mod collation;
+mod collator_scores;
 mod metrics;
 
+use collator_scores::{CollatorScores, ScoreEvent, FLUSH_INTERVAL as SCORES_FLUSH_INTERVAL};
+
 use collation::{
 	fetched_collation_sanity_check, CollationEvent, CollationFetchError, CollationFetchRequest,
 	CollationStatus, Collations, FetchedCollation, PendingCollation, PendingCollationFetch,
@@ -79,6 +84,7 @@ use collation::{
 #[cfg(test)]
 mod tests;
 
+pub use collator_scores::CollatorScoresConfig;
 pub use metrics::Metrics;
 
 const COST_UNEXPECTED_MESSAGE: Rep = Rep::CostMinor("An unexpected message");
@@ -352,7 +358,6 @@ struct PerRelayParent {
 }
 
 /// All state relevant for the validator side of the protocol lives here.
-#[derive(Default)]
 struct State {
 	/// Leaves that do support asynchronous backing along with
 	/// implicit ancestry. Leaves from the implicit view are present in
@@ -408,9 +413,34 @@ struct State {
 
 	/// Aggregated reputation change
 	reputation: ReputationAggregator,
+
+	/// Persistent scores of the collators, used to prioritize fetches.
+	collator_scores: CollatorScores,
 }
 
 impl State {
+	fn new(
+		metrics: Metrics,
+		reputation: ReputationAggregator,
+		collator_scores: CollatorScores,
+	) -> Self {
+		Self {
+			implicit_view: Default::default(),
+			active_leaves: Default::default(),
+			per_relay_parent: Default::default(),
+			peer_data: Default::default(),
+			current_assignments: Default::default(),
+			collation_requests: Default::default(),
+			collation_requests_cancel_handles: Default::default(),
+			metrics,
+			collation_fetch_timeouts: Default::default(),
+			fetched_candidates: Default::default(),
+			blocked_from_seconding: Default::default(),
+			reputation,
+			collator_scores,
+		}
+	}
+
 	// Returns the number of seconded and pending collations for a specific `ParaId`. Pending
 	// collations are:
 	// 1. Collations being fetched from a collator.
@@ -723,6 +753,7 @@ async fn request_collation(
 		from_collator: response_recv,
 		cancellation_token: cancellation_token.clone(),
 		_lifetime_timer: state.metrics.time_collation_request_duration(),
+		requested_at: Instant::now(),
 	};
 
 	state.collation_requests.push(collation_request);
@@ -1233,6 +1264,7 @@ where
 			parent_head_data_hash,
 		});
 
+	let trusted = state.collator_scores.is_trusted(para_id, &collator_id);
 	let collations = &mut per_relay_parent.collations;
 	let pending_collation =
 		PendingCollation::new(relay_parent, para_id, &peer_id, prospective_candidate);
@@ -1246,7 +1278,7 @@ where
 				?relay_parent,
 				"Added collation to the pending list"
 			);
-			collations.add_to_waiting_queue((pending_collation, collator_id));
+			collations.add_to_waiting_queue((pending_collation, collator_id), trusted)?;
 		},
 		CollationStatus::Waiting => {
 			// We were waiting for a collation to be advertised to us (we were idle) so we can fetch
@@ -1278,6 +1310,7 @@ where
 			.await
 			.await
 			.map_err(Error::CancelledSessionIndex)??;
+		state.collator_scores.note_session(session_index);
 
 		let v2_receipts = request_node_features(*leaf, session_index, sender)
 			.await
@@ -1512,6 +1545,7 @@ async fn process_msg<Context>(
 				let PendingCollation {
 					relay_parent, peer_id, prospective_candidate, para_id, ..
 				} = pending_collation;
+				state.collator_scores.note(para_id, collator_id.clone(), ScoreEvent::Valid);
 				note_good_collation(
 					&mut state.reputation,
 					ctx.sender(),
@@ -1571,6 +1605,7 @@ async fn process_msg<Context>(
 
 			let fetched_collation = FetchedCollation::from(&candidate_receipt);
 			let candidate_hash = fetched_collation.candidate_hash;
+			let para_id = fetched_collation.para_id;
 			let id = match state.fetched_candidates.entry(fetched_collation) {
 				Entry::Occupied(entry)
 					if entry.get().pending_collation.commitments_hash ==
@@ -1588,6 +1623,7 @@ async fn process_msg<Context>(
 				Entry::Vacant(_) => return,
 			};
 
+			state.collator_scores.note(para_id, id.clone(), ScoreEvent::Invalid);
 			report_collator(&mut state.reputation, ctx.sender(), &state.peer_data, id.clone())
 				.await;
 
@@ -1603,6 +1639,8 @@ pub(crate) async fn run<Context>(
 	keystore: KeystorePtr,
 	eviction_policy: crate::CollatorEvictionPolicy,
 	metrics: Metrics,
+	db: Arc<dyn Database>,
+	scores_config: CollatorScoresConfig,
 ) -> std::result::Result<(), crate::error::FatalError> {
 	run_inner(
 		ctx,
@@ -1611,6 +1649,7 @@ pub(crate) async fn run<Context>(
 		metrics,
 		ReputationAggregator::default(),
 		REPUTATION_CHANGE_INTERVAL,
+		CollatorScores::load(db, scores_config),
 	)
 	.await
 }
@@ -1623,11 +1662,15 @@ async fn run_inner<Context>(
 	metrics: Metrics,
 	reputation: ReputationAggregator,
 	reputation_interval: Duration,
+	collator_scores: CollatorScores,
 ) -> std::result::Result<(), crate::error::FatalError> {
 	let new_reputation_delay = || futures_timer::Delay::new(reputation_interval).fuse();
 	let mut reputation_delay = new_reputation_delay();
 
-	let mut state = State { metrics, reputation, ..Default::default() };
+	let new_scores_flush_delay = || futures_timer::Delay::new(SCORES_FLUSH_INTERVAL).fuse();
+	let mut scores_flush_delay = new_scores_flush_delay();
+
+	let mut state = State::new(metrics, reputation, collator_scores);
 
 	let next_inactivity_stream = tick_stream(ACTIVITY_POLL);
 	futures::pin_mut!(next_inactivity_stream);
@@ -1641,6 +1684,10 @@ async fn run_inner<Context>(
 				state.reputation.send(ctx.sender()).await;
 				reputation_delay = new_reputation_delay();
 			},
+			_ = scores_flush_delay => {
+				state.collator_scores.flush();
+				scores_flush_delay = new_scores_flush_delay();
+			},
 			res = ctx.recv().fuse() => {
 				match res {
 					Ok(FromOrchestra::Communication { msg }) => {
@@ -1652,7 +1699,10 @@ async fn run_inner<Context>(
 							&mut state,
 						).await;
 					}
-					Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => break,
+					Ok(FromOrchestra::Signal(OverseerSignal::Conclude)) | Err(_) => {
+						state.collator_scores.flush();
+						break
+					},
 					Ok(FromOrchestra::Signal(_)) => continue,
 				}
 			},
@@ -1700,6 +1750,11 @@ async fn run_inner<Context>(
 
 						if err.is_malicious() {
 							// Report malicious peer.
+							state.collator_scores.note(
+								pending_collation.para_id,
+								collator_id.clone(),
+								ScoreEvent::Invalid,
+							);
 							modify_reputation(&mut state.reputation, ctx.sender(), pending_collation.peer_id, COST_REPORT_BAD).await;
 						}
 						let maybe_candidate_hash =
@@ -1983,8 +2038,11 @@ async fn handle_collation_fetch_response(
 	network_error_freq: &mut gum::Freq,
 	canceled_freq: &mut gum::Freq,
 ) -> std::result::Result<PendingCollationFetch, Option<(PeerId, Rep)>> {
-	let (CollationEvent { collator_id, collator_protocol_version, pending_collation }, response) =
-		response;
+	let (
+		CollationEvent { collator_id, collator_protocol_version, pending_collation },
+		response,
+		fetch_duration,
+	) = response;
 	// Remove the cancellation handle, as the future already completed.
 	state.collation_requests_cancel_handles.remove(&pending_collation);
 
@@ -2029,6 +2087,12 @@ async fn handle_collation_fetch_response(
 			);
 			// For now we don't want to change reputation on timeout, to mitigate issues like
 			// this: https://github.com/paritytech/polkadot/issues/4617
+			// The score is only used for prioritizing fetches, so we can lower it.
+			state.collator_scores.note(
+				pending_collation.para_id,
+				collator_id.clone(),
+				ScoreEvent::TimedOut,
+			);
 			Err(None)
 		},
 		Err(RequestError::NetworkError(err)) => {
@@ -2125,6 +2189,18 @@ async fn handle_collation_fetch_response(
 		},
 	};
 	state.metrics.on_request(metrics_result);
+
+	if let Ok(fetch) = &result {
+		if fetch_duration <= MAX_UNSHARED_DOWNLOAD_TIME {
+			let CollationEvent { collator_id, pending_collation, .. } = &fetch.collation_event;
+			state.collator_scores.note(
+				pending_collation.para_id,
+				collator_id.clone(),
+				ScoreEvent::Timely,
+			);
+		}
+	}
+
 	result
 }
 
@@ -2225,7 +2301,9 @@ fn get_next_collation_to_fetch(
 		}
 	}
 	rp_state.collations.status.back_to_waiting();
-	rp_state.collations.pick_a_collation_to_fetch(unfulfilled_entries)
+	rp_state
+		.collations
+		.pick_a_collation_to_fetch(unfulfilled_entries, &state.collator_scores)
 }
 
 // Sanity check the candidate descriptor version.
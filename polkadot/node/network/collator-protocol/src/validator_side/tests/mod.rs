@@ -203,9 +203,24 @@ struct TestHarness {
 	keystore: KeystorePtr,
 }
 
+const TEST_SCORES_CONFIG: CollatorScoresConfig = CollatorScoresConfig { col_data: 0 };
+
+fn make_db() -> Arc<dyn Database> {
+	let db = kvdb_memorydb::create(1);
+	Arc::new(polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[0]))
+}
+
 fn test_harness<T: Future<Output = VirtualOverseer>>(
 	reputation: ReputationAggregator,
 	test: impl FnOnce(TestHarness) -> T,
+) {
+	test_harness_with_db(reputation, make_db(), test)
+}
+
+fn test_harness_with_db<T: Future<Output = VirtualOverseer>>(
+	reputation: ReputationAggregator,
+	db: Arc<dyn Database>,
+	test: impl FnOnce(TestHarness) -> T,
 ) {
 	sp_tracing::init_for_tests();
 
@@ -232,6 +247,7 @@ fn test_harness<T: Future<Output = VirtualOverseer>>(
 		Metrics::default(),
 		reputation,
 		REPUTATION_CHANGE_TEST_INTERVAL,
+		CollatorScores::load(db, TEST_SCORES_CONFIG),
 	);
 
 	let test_fut = test(TestHarness { virtual_overseer, keystore });
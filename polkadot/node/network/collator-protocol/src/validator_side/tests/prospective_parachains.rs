@@ -1878,6 +1878,139 @@ fn collation_fetching_prefer_entries_earlier_in_claim_queue() {
 	});
 }
 
+#[test]
+fn collation_fetching_prefers_high_scoring_collators() {
+	let mut test_state = TestState::with_one_scheduled_para();
+	let para_id = test_state.chain_ids[0];
+
+	let pair_a = CollatorPair::generate().0;
+	let collator_a = PeerId::random();
+	let pair_b = CollatorPair::generate().0;
+	let collator_b = PeerId::random();
+	let pair_c = CollatorPair::generate().0;
+	let collator_c = PeerId::random();
+
+	// `pair_c` has provided valid collations before the restart.
+	let db = make_db();
+	{
+		let mut scores = CollatorScores::load(db.clone(), TEST_SCORES_CONFIG);
+		for _ in 0..10 {
+			scores.note(para_id, pair_c.public(), ScoreEvent::Valid);
+		}
+		assert!(scores.is_trusted(para_id, &pair_c.public()));
+		scores.flush();
+	}
+
+	test_harness_with_db(ReputationAggregator::new(|_| true), db, |test_harness| async move {
+		let TestHarness { mut virtual_overseer, keystore } = test_harness;
+
+		let head = Hash::from_low_u64_be(128);
+		let head_num: u32 = 2;
+
+		update_view(&mut virtual_overseer, &mut test_state, vec![(head, head_num)]).await;
+
+		for (peer, pair) in [(collator_a, &pair_a), (collator_b, &pair_b), (collator_c, &pair_c)] {
+			connect_and_declare_collator(
+				&mut virtual_overseer,
+				peer,
+				pair.clone(),
+				para_id,
+				CollationVersion::V2,
+			)
+			.await;
+		}
+
+		let (candidate_a, commitments_a) =
+			create_dummy_candidate_and_commitments(para_id, HeadData(vec![0u8]), head);
+		let (candidate_b, _) =
+			create_dummy_candidate_and_commitments(para_id, HeadData(vec![1u8]), head);
+		let (candidate_c, commitments_c) =
+			create_dummy_candidate_and_commitments(para_id, HeadData(vec![2u8]), head);
+
+		// The first advertisement is fetched right away.
+		assert_advertise_collation(
+			&mut virtual_overseer,
+			collator_a,
+			head,
+			para_id,
+			(candidate_a.hash(), HeadData(vec![0u8]).hash()),
+		)
+		.await;
+		let response_channel_a = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			head,
+			para_id,
+			Some(candidate_a.hash()),
+		)
+		.await;
+
+		// The others wait for the pending fetch, `collator_b` advertises before `collator_c`.
+		assert_advertise_collation(
+			&mut virtual_overseer,
+			collator_b,
+			head,
+			para_id,
+			(candidate_b.hash(), HeadData(vec![1u8]).hash()),
+		)
+		.await;
+		assert_advertise_collation(
+			&mut virtual_overseer,
+			collator_c,
+			head,
+			para_id,
+			(candidate_c.hash(), HeadData(vec![2u8]).hash()),
+		)
+		.await;
+		test_helpers::Yield::new().await;
+		assert_matches!(virtual_overseer.recv().now_or_never(), None);
+
+		send_collation_and_assert_processing(
+			&mut virtual_overseer,
+			keystore.clone(),
+			head,
+			para_id,
+			collator_a,
+			response_channel_a,
+			candidate_a,
+			commitments_a,
+			PoV { block_data: BlockData(vec![1]) },
+		)
+		.await;
+
+		// The trusted collator is fetched next, even though it advertised last.
+		let response_channel_c = assert_fetch_collation_request(
+			&mut virtual_overseer,
+			head,
+			para_id,
+			Some(candidate_c.hash()),
+		)
+		.await;
+
+		send_collation_and_assert_processing(
+			&mut virtual_overseer,
+			keystore.clone(),
+			head,
+			para_id,
+			collator_c,
+			response_channel_c,
+			candidate_c,
+			commitments_c,
+			PoV { block_data: BlockData(vec![2]) },
+		)
+		.await;
+
+		assert_fetch_collation_request(
+			&mut virtual_overseer,
+			head,
+			para_id,
+			Some(candidate_b.hash()),
+		)
+		.await;
+
+		virtual_overseer
+	});
+}
+
 #[test]
 fn collation_fetching_considers_advertisements_from_the_whole_view() {
 	let mut test_state = TestState::with_shared_core();
@@ -545,6 +545,7 @@ pub struct Overseer<SupportsParachains> {
 	#[subsystem(blocking, AvailabilityStoreMessage, sends: [
 		ChainApiMessage,
 		RuntimeApiMessage,
+		AvailabilityRecoveryMessage,
 	])]
 	availability_store: AvailabilityStore,
 
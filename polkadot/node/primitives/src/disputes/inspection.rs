@@ -0,0 +1,72 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use polkadot_primitives::{CandidateHash, SessionIndex, ValidatorIndex};
+
+use super::{CandidateVotes, DisputeStatus, Timestamp};
+
+/// Outcome of the validation process of a dispute participation.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub enum ParticipationOutcome {
+	/// Candidate was found to be valid.
+	Valid,
+	/// Candidate was found to be invalid.
+	Invalid,
+	/// Candidate was found to be unavailable.
+	Unavailable,
+	/// Something went wrong (bug), details can be found in the logs.
+	Error,
+}
+
+impl ParticipationOutcome {
+	/// If validation was successful, get whether the candidate was valid or invalid.
+	pub fn validity(self) -> Option<bool> {
+		match self {
+			Self::Valid => Some(true),
+			Self::Invalid => Some(false),
+			Self::Unavailable | Self::Error => None,
+		}
+	}
+}
+
+/// Our own participation in a dispute, as tracked by the dispute coordinator.
+///
+/// This is only kept in memory, participations from before the last restart are not known.
+#[derive(Copy, Clone, Debug, PartialEq, Eq)]
+pub struct OwnParticipation {
+	/// When the participation got queued.
+	pub queued_at: Timestamp,
+	/// The outcome of the participation and when it became known, `None` while pending.
+	pub concluded: Option<(ParticipationOutcome, Timestamp)>,
+}
+
+/// A dispute as seen by the dispute coordinator, for inspection by node operators.
+#[derive(Debug, Clone)]
+pub struct DisputeInfo {
+	/// The session the candidate appears in.
+	pub session: SessionIndex,
+	/// The disputed candidate.
+	pub candidate_hash: CandidateHash,
+	/// The status of the dispute.
+	pub status: DisputeStatus,
+	/// All votes on the candidate, `None` if they are not in the database (anymore).
+	pub votes: Option<CandidateVotes>,
+	/// Our own votes, `true` meaning valid. `None` if we are not a validator in the session or
+	/// the session is not known.
+	pub own_votes: Option<Vec<(ValidatorIndex, bool)>>,
+	/// Our own participation in the dispute, if any.
+	pub participation: Option<OwnParticipation>,
+}
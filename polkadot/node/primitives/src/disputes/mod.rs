@@ -35,6 +35,9 @@ mod message;
 pub use message::{DisputeMessage, Error as DisputeMessageCheckError, UncheckedDisputeMessage};
 mod status;
 pub use status::{dispute_is_inactive, DisputeStatus, Timestamp, ACTIVE_DURATION_SECS};
+/// Types for inspecting disputes.
+mod inspection;
+pub use inspection::{DisputeInfo, OwnParticipation, ParticipationOutcome};
 
 /// A checked dispute statement from an associated validator.
 #[derive(Debug, Clone)]
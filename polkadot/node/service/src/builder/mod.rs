@@ -24,7 +24,7 @@ pub(crate) use partial::{new_partial, new_partial_basics};
 
 use crate::{
 	grandpa_support, open_database,
-	overseer::{ExtendedOverseerGenArgs, OverseerGen, OverseerGenArgs},
+	overseer::{CollatorScoresConfig, ExtendedOverseerGenArgs, OverseerGen, OverseerGenArgs},
 	parachains_db,
 	relay_chain_selection::SelectRelayChain,
 	workers, Chain, Error, FullBackend, FullClient, IdentifyVariant, IsParachainNode,
@@ -35,7 +35,9 @@ use gum::info;
 use mmr_gadget::MmrGadget;
 use polkadot_availability_recovery::FETCH_CHUNKS_THRESHOLD;
 use polkadot_node_core_approval_voting::Config as ApprovalVotingConfig;
-use polkadot_node_core_av_store::Config as AvailabilityConfig;
+use polkadot_node_core_av_store::{
+	ArchiveConfig as AvailabilityArchiveConfig, Config as AvailabilityConfig,
+};
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
 use polkadot_node_core_chain_selection::{
 	self as chain_selection_subsystem, Config as ChainSelectionConfig,
@@ -58,6 +60,7 @@ use sc_transaction_pool_api::OffchainTransactionPoolFactory;
 use sp_consensus_beefy::ecdsa_crypto;
 use sp_runtime::traits::Block as BlockT;
 use std::{collections::HashMap, sync::Arc, time::Duration};
+use xcm_lifecycle_rpc::LifecycleIndexer;
 
 /// Polkadot node service initialization parameters.
 pub struct NewFullParams<OverseerGenerator: OverseerGen> {
@@ -83,8 +86,12 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub prepare_workers_soft_max_num: Option<usize>,
 	/// An optional absolute number of pvf workers that can be spawned in the pvf prepare pool.
 	pub prepare_workers_hard_max_num: Option<usize>,
+	/// An optional number of threads a single erasure coding operation may be split across.
+	pub erasure_coding_threads: Option<usize>,
 	/// How long finalized data should be kept in the availability store (in hours)
 	pub keep_finalized_for: Option<u32>,
+	/// Which paras to archive the available data of in the availability store, and for how long.
+	pub availability_archive_config: AvailabilityArchiveConfig,
 	pub overseer_gen: OverseerGenerator,
 	pub overseer_message_channel_capacity_override: Option<usize>,
 	#[allow(dead_code)]
@@ -92,6 +99,8 @@ pub struct NewFullParams<OverseerGenerator: OverseerGen> {
 	pub hwbench: Option<sc_sysinfo::HwBench>,
 	/// Enable approval voting processing in parallel.
 	pub enable_approval_voting_parallel: bool,
+	/// Index the lifecycle of XCM messages, as returned by the `xcm_messageLifecycle` RPC.
+	pub enable_xcm_lifecycle_index: bool,
 }
 
 /// Completely built polkadot node service.
@@ -150,8 +159,15 @@ where
 			SelectRelayChain::new_longest_chain(basics.backend.clone())
 		};
 
-		let partial_components =
-			new_partial::<SelectRelayChain<_>>(&mut config, basics, select_chain)?;
+		// Disputes and the availability store can only be inspected on validators, which run the
+		// corresponding subsystems.
+		let rpc_overseer_handle = config.role.is_authority().then(|| overseer_handle.clone());
+		let partial_components = new_partial::<SelectRelayChain<_>>(
+			&mut config,
+			basics,
+			select_chain,
+			rpc_overseer_handle,
+		)?;
 
 		let net_config = sc_network::config::FullNetworkConfiguration::<_, _, Network>::new(
 			&config.network,
@@ -201,8 +217,11 @@ where
 					execute_workers_max_num,
 					prepare_workers_soft_max_num,
 					prepare_workers_hard_max_num,
+					erasure_coding_threads,
 					keep_finalized_for,
+					availability_archive_config,
 					enable_approval_voting_parallel,
+					enable_xcm_lifecycle_index,
 				},
 			overseer_connector,
 			partial_components:
@@ -415,6 +434,9 @@ where
 				stagnant_check_interval: Default::default(),
 				stagnant_check_mode: chain_selection_subsystem::StagnantCheckMode::PruneOnly,
 			};
+			let collator_scores_config = CollatorScoresConfig {
+				col_data: parachains_db::REAL_COLUMNS.col_collator_scores_data,
+			};
 
 			// Kusama + testnets get a higher threshold, we are conservative on Polkadot for now.
 			let fetch_chunks_threshold =
@@ -430,11 +452,17 @@ where
 				},
 			};
 
+			// By default, erasure coding runs on the threads of the subsystems.
+			let erasure_coding_pool =
+				polkadot_erasure_coding::ThreadPool::new(erasure_coding_threads.unwrap_or(1))?;
+
 			Some(ExtendedOverseerGenArgs {
 				keystore: keystore_container.local_keystore(),
 				parachains_db,
 				candidate_validation_config,
 				availability_config,
+				availability_archive_config,
+				erasure_coding_pool,
 				pov_req_receiver,
 				chunk_req_v1_receiver,
 				chunk_req_v2_receiver,
@@ -443,6 +471,7 @@ where
 				dispute_req_receiver,
 				dispute_coordinator_config,
 				chain_selection_config,
+				collator_scores_config,
 				fetch_chunks_threshold,
 				enable_approval_voting_parallel,
 			})
@@ -777,6 +806,14 @@ where
 			);
 		}
 
+		if enable_xcm_lifecycle_index {
+			task_manager.spawn_handle().spawn(
+				"xcm-lifecycle-indexer",
+				None,
+				LifecycleIndexer::new(client.clone()).run(),
+			);
+		}
+
 		let config = sc_consensus_grandpa::Config {
 			// FIXME substrate#1578 make this available through chainspec
 			// Grandpa performance can be improved a bit by tuning this parameter, see:
@@ -448,6 +448,12 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_runtime_apis::lifecycle::XcmLifecycleApi<Block> for Runtime {
+		fn xcm_lifecycle_events() -> Vec<xcm_runtime_apis::lifecycle::LifecycleEvent> {
+			unimplemented!()
+		}
+	}
+
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, (), (), ()> for Runtime {
 		fn dry_run_call(_: (), _: (), _: XcmVersion) -> Result<xcm_runtime_apis::dry_run::CallDryRunEffects<()>, xcm_runtime_apis::dry_run::Error> {
 			unimplemented!()
@@ -59,6 +59,8 @@ use polkadot_overseer::SpawnGlue;
 
 #[cfg(feature = "full-node")]
 pub use {
+	parachains_db::{open_read_only_paritydb, open_read_only_rocksdb, ColumnsConfig, REAL_COLUMNS},
+	polkadot_node_core_av_store::ArchiveConfig as AvailabilityArchiveConfig,
 	polkadot_overseer::{Handle, Overseer, OverseerConnector, OverseerHandle},
 	polkadot_primitives::runtime_api::ParachainHost,
 	relay_chain_selection::SelectRelayChain,
@@ -417,7 +419,7 @@ macro_rules! chain_ops {
 		let chain_selection = LongestChain::new(basics.backend.clone());
 
 		let sc_service::PartialComponents { client, backend, import_queue, task_manager, .. } =
-			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection)?;
+			new_partial::<LongestChain<_, Block>>(&mut config, basics, chain_selection, None)?;
 		Ok((client, backend, import_queue, task_manager))
 	}};
 }
@@ -21,7 +21,9 @@ use sp_core::traits::SpawnNamed;
 
 use polkadot_availability_distribution::IncomingRequestReceivers;
 use polkadot_node_core_approval_voting::{Config as ApprovalVotingConfig, RealAssignmentCriteria};
-use polkadot_node_core_av_store::Config as AvailabilityConfig;
+use polkadot_node_core_av_store::{
+	ArchiveConfig as AvailabilityArchiveConfig, Config as AvailabilityConfig,
+};
 use polkadot_node_core_candidate_validation::Config as CandidateValidationConfig;
 use polkadot_node_core_chain_selection::Config as ChainSelectionConfig;
 use polkadot_node_core_dispute_coordinator::Config as DisputeCoordinatorConfig;
@@ -49,8 +51,11 @@ pub use polkadot_approval_distribution::ApprovalDistribution as ApprovalDistribu
 pub use polkadot_availability_bitfield_distribution::BitfieldDistribution as BitfieldDistributionSubsystem;
 pub use polkadot_availability_distribution::AvailabilityDistributionSubsystem;
 pub use polkadot_availability_recovery::AvailabilityRecoverySubsystem;
-pub use polkadot_collator_protocol::{CollatorProtocolSubsystem, ProtocolSide};
+pub use polkadot_collator_protocol::{
+	CollatorProtocolSubsystem, CollatorScoresConfig, ProtocolSide,
+};
 pub use polkadot_dispute_distribution::DisputeDistributionSubsystem;
+pub use polkadot_erasure_coding::ThreadPool as ErasureCodingThreadPool;
 pub use polkadot_gossip_support::GossipSupport as GossipSupportSubsystem;
 pub use polkadot_network_bridge::{
 	Metrics as NetworkBridgeMetrics, NetworkBridgeRx as NetworkBridgeRxSubsystem,
@@ -119,6 +124,10 @@ pub struct ExtendedOverseerGenArgs {
 	pub candidate_validation_config: Option<CandidateValidationConfig>,
 	/// Configuration for the availability store subsystem.
 	pub availability_config: AvailabilityConfig,
+	/// Configuration of the archive mode of the availability store subsystem.
+	pub availability_archive_config: AvailabilityArchiveConfig,
+	/// The pool of threads the erasure coding of large PoVs is split across.
+	pub erasure_coding_pool: ErasureCodingThreadPool,
 	/// POV request receiver.
 	pub pov_req_receiver: IncomingRequestReceiver<request_v1::PoVFetchingRequest>,
 	/// Erasure chunk request v1 receiver.
@@ -135,6 +144,8 @@ pub struct ExtendedOverseerGenArgs {
 	pub dispute_coordinator_config: DisputeCoordinatorConfig,
 	/// Configuration for the chain selection subsystem.
 	pub chain_selection_config: ChainSelectionConfig,
+	/// Configuration for the collator scores of the collator protocol.
+	pub collator_scores_config: CollatorScoresConfig,
 	/// Optional availability recovery fetch chunks threshold. If PoV size size is lower
 	/// than the value put in here we always try to recovery availability from backers.
 	/// The presence of this parameter here is needed to have different values per chain.
@@ -171,6 +182,8 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		parachains_db,
 		candidate_validation_config,
 		availability_config,
+		availability_archive_config,
+		erasure_coding_pool,
 		pov_req_receiver,
 		chunk_req_v1_receiver,
 		chunk_req_v2_receiver,
@@ -179,6 +192,7 @@ pub fn validator_overseer_builder<Spawner, RuntimeClient>(
 		dispute_req_receiver,
 		dispute_coordinator_config,
 		chain_selection_config,
+		collator_scores_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
 	}: ExtendedOverseerGenArgs,
@@ -263,18 +277,25 @@ where
 			req_protocol_names.clone(),
 			Metrics::register(registry)?,
 		))
-		.availability_recovery(AvailabilityRecoverySubsystem::for_validator(
-			fetch_chunks_threshold,
-			available_data_req_receiver,
-			&req_protocol_names,
-			Metrics::register(registry)?,
-		))
-		.availability_store(AvailabilityStoreSubsystem::new(
-			parachains_db.clone(),
-			availability_config,
-			Box::new(sync_service.clone()),
-			Metrics::register(registry)?,
-		))
+		.availability_recovery(
+			AvailabilityRecoverySubsystem::for_validator(
+				fetch_chunks_threshold,
+				available_data_req_receiver,
+				&req_protocol_names,
+				Metrics::register(registry)?,
+			)
+			.with_erasure_coding_pool(erasure_coding_pool.clone()),
+		)
+		.availability_store(
+			AvailabilityStoreSubsystem::new(
+				parachains_db.clone(),
+				availability_config,
+				Box::new(sync_service.clone()),
+				Metrics::register(registry)?,
+			)
+			.with_archive(availability_archive_config)
+			.with_erasure_coding_pool(erasure_coding_pool),
+		)
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			keystore.clone(),
@@ -303,6 +324,8 @@ where
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
 					metrics: Metrics::register(registry)?,
+					db: parachains_db.clone(),
+					scores_config: collator_scores_config,
 				},
 			};
 			CollatorProtocolSubsystem::new(side)
@@ -393,6 +416,8 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		parachains_db,
 		candidate_validation_config,
 		availability_config,
+		availability_archive_config,
+		erasure_coding_pool,
 		pov_req_receiver,
 		chunk_req_v1_receiver,
 		chunk_req_v2_receiver,
@@ -401,6 +426,7 @@ pub fn validator_with_parallel_overseer_builder<Spawner, RuntimeClient>(
 		dispute_req_receiver,
 		dispute_coordinator_config,
 		chain_selection_config,
+		collator_scores_config,
 		fetch_chunks_threshold,
 		enable_approval_voting_parallel,
 	}: ExtendedOverseerGenArgs,
@@ -484,18 +510,25 @@ where
 			req_protocol_names.clone(),
 			Metrics::register(registry)?,
 		))
-		.availability_recovery(AvailabilityRecoverySubsystem::for_validator(
-			fetch_chunks_threshold,
-			available_data_req_receiver,
-			&req_protocol_names,
-			Metrics::register(registry)?,
-		))
-		.availability_store(AvailabilityStoreSubsystem::new(
-			parachains_db.clone(),
-			availability_config,
-			Box::new(sync_service.clone()),
-			Metrics::register(registry)?,
-		))
+		.availability_recovery(
+			AvailabilityRecoverySubsystem::for_validator(
+				fetch_chunks_threshold,
+				available_data_req_receiver,
+				&req_protocol_names,
+				Metrics::register(registry)?,
+			)
+			.with_erasure_coding_pool(erasure_coding_pool.clone()),
+		)
+		.availability_store(
+			AvailabilityStoreSubsystem::new(
+				parachains_db.clone(),
+				availability_config,
+				Box::new(sync_service.clone()),
+				Metrics::register(registry)?,
+			)
+			.with_archive(availability_archive_config)
+			.with_erasure_coding_pool(erasure_coding_pool),
+		)
 		.bitfield_distribution(BitfieldDistributionSubsystem::new(Metrics::register(registry)?))
 		.bitfield_signing(BitfieldSigningSubsystem::new(
 			keystore.clone(),
@@ -524,6 +557,8 @@ where
 					keystore: keystore.clone(),
 					eviction_policy: Default::default(),
 					metrics: Metrics::register(registry)?,
+					db: parachains_db.clone(),
+					scores_config: collator_scores_config,
 				},
 			};
 			CollatorProtocolSubsystem::new(side)
@@ -217,6 +217,10 @@ pub fn prepare_test(
 		MockRuntimeApiCoreState::Occupied,
 	);
 
+	let erasure_coding_pool =
+		polkadot_erasure_coding::ThreadPool::new(state.config.erasure_coding_threads)
+			.expect("Failed to spawn the erasure coding threads");
+
 	let (overseer, overseer_handle) = match &mode {
 		TestDataAvailability::Read(options) => {
 			let subsystem = match options.strategy {
@@ -239,7 +243,8 @@ pub fn prepare_test(
 					Metrics::try_register(&dependencies.registry).unwrap(),
 					RecoveryStrategyKind::SystematicChunks,
 				),
-			};
+			}
+			.with_erasure_coding_pool(erasure_coding_pool);
 
 			// Use a mocked av-store.
 			let av_store = MockAvailabilityStore::new(
@@ -280,7 +285,7 @@ pub fn prepare_test(
 				(network_bridge_tx, network_bridge_rx),
 				availability_distribution,
 				chain_api,
-				new_av_store(&dependencies),
+				new_av_store(&dependencies).with_erasure_coding_pool(erasure_coding_pool),
 				bitfield_distribution,
 				&dependencies,
 			)
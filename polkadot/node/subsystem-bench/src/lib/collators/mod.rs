@@ -0,0 +1,300 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmark of the validator side of the collator-protocol subsystem.
+//!
+//! Emulates many collators declaring themselves and advertising collations to the backing group
+//! of the node under test. The collators are reachable through the emulated network, using the
+//! peers of validators outside of that backing group, so that fetched collations are accounted
+//! for in the network usage.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::{
+		candidate_backing::MockCandidateBacking,
+		chain_api::{ChainApiState, MockChainApi},
+		network_bridge::{MockNetworkBridgeRx, MockNetworkBridgeTx},
+		prospective_parachains::MockProspectiveParachains,
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::{new_network, NetworkEmulatorHandle, NetworkInterface, NetworkInterfaceReceiver},
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use itertools::Itertools;
+use polkadot_collator_protocol::{CollatorProtocolSubsystem, CollatorScoresConfig, ProtocolSide};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_network_protocol::{
+	our_view, peer_set::CollationVersion, v1 as protocol_v1, v2 as protocol_v2, CollationProtocols,
+	ObservedRole,
+};
+use polkadot_node_subsystem::messages::{AllMessages, CollatorProtocolMessage, NetworkBridgeEvent};
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{ValidatorId, ValidatorIndex};
+use sc_keystore::LocalKeystore;
+use sc_network_types::PeerId;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_core::Pair;
+use sp_keystore::{Keystore, KeystorePtr};
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	sync::{atomic::Ordering, Arc},
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::collators";
+
+// The advertised collations are fetched within a few round trips, a block that takes longer than
+// this means the node under test does not fetch at all.
+const MAX_FETCH_TIME: Duration = Duration::from_secs(60);
+
+/// Parameters specific to the collator-protocol benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct CollatorProtocolOptions {
+	#[clap(long, default_value_t = 10)]
+	/// The number of collators advertising a collation at every block.
+	pub n_collators: usize,
+}
+
+fn make_keystore() -> KeystorePtr {
+	let keystore: KeystorePtr = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn test_store() -> Arc<dyn Database> {
+	let db = kvdb_memorydb::create(1);
+	let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[0]);
+	Arc::new(db)
+}
+
+fn build_overseer(
+	state: &TestState,
+	network: NetworkEmulatorHandle,
+	network_interface: NetworkInterface,
+	network_receiver: NetworkInterfaceReceiver,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		Default::default(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	);
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let mock_prospective_parachains =
+		MockProspectiveParachains::new().with_persisted_validation_data(state.pvd.clone());
+	let mock_candidate_backing = MockCandidateBacking::new(
+		state.config.clone(),
+		state
+			.test_authorities
+			.validator_pairs
+			.get(NODE_UNDER_TEST as usize)
+			.unwrap()
+			.clone(),
+		state.pvd.clone(),
+		state.own_backing_group.clone(),
+	);
+	let subsystem = CollatorProtocolSubsystem::new(ProtocolSide::Validator {
+		keystore: make_keystore(),
+		eviction_policy: Default::default(),
+		metrics: Metrics::try_register(&dependencies.registry).unwrap(),
+		db: test_store(),
+		scores_config: CollatorScoresConfig { col_data: 0 },
+	});
+	let network_bridge_tx = MockNetworkBridgeTx::new(
+		network,
+		network_interface.subsystem_sender(),
+		state.test_authorities.clone(),
+	);
+	let network_bridge_rx = MockNetworkBridgeRx::new(network_receiver, None, false);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_prospective_parachains(|_| mock_prospective_parachains)
+		.replace_candidate_backing(|_| mock_candidate_backing)
+		.replace_collator_protocol(|_| subsystem)
+		.replace_network_bridge_tx(|_| network_bridge_tx)
+		.replace_network_bridge_rx(|_| network_bridge_rx);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	let (network, network_interface, network_receiver) = new_network(
+		&state.config,
+		&dependencies,
+		&state.test_authorities,
+		vec![Arc::new(state.clone())],
+	);
+	let (overseer, overseer_handle) =
+		build_overseer(state, network.clone(), network_interface, network_receiver, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+fn collator_message(peer_id: PeerId, message: protocol_v2::CollatorProtocolMessage) -> AllMessages {
+	AllMessages::CollatorProtocol(CollatorProtocolMessage::NetworkBridgeUpdate(
+		NetworkBridgeEvent::PeerMessage(peer_id, CollationProtocols::V2(message)),
+	))
+}
+
+pub async fn benchmark_collator_protocol(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	state.reset_trackers();
+
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	// Collators are reached through connected peers outside of the own backing group.
+	let collator_peers = state
+		.test_authorities
+		.validator_authority_id
+		.iter()
+		.enumerate()
+		.filter(|(i, id)| {
+			!state.own_backing_group.contains(&ValidatorIndex(*i as u32)) &&
+				env.network().is_peer_connected(id)
+		})
+		.map(|(i, _)| state.test_authorities.peer_ids[i])
+		.take(state.collator_pairs.len())
+		.collect_vec();
+	assert_eq!(
+		collator_peers.len(),
+		state.collator_pairs.len(),
+		"Not enough connected peers to emulate {} collators",
+		state.collator_pairs.len()
+	);
+
+	let test_start = Instant::now();
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?}", block_num, config.num_blocks, block_info.hash);
+		env.metrics().set_current_block(block_num);
+		env.import_block(block_info.clone()).await;
+		env.send_message(AllMessages::CollatorProtocol(
+			CollatorProtocolMessage::NetworkBridgeUpdate(NetworkBridgeEvent::OurViewChange(
+				our_view![block_info.hash],
+			)),
+		))
+		.await;
+
+		// Collators declare themselves once we know which para we are assigned to.
+		if block_num == 1 {
+			for (peer_id, pair) in collator_peers.iter().zip(state.collator_pairs.iter()) {
+				env.send_message(AllMessages::CollatorProtocol(
+					CollatorProtocolMessage::NetworkBridgeUpdate(
+						NetworkBridgeEvent::PeerConnected(
+							*peer_id,
+							ObservedRole::Full,
+							CollationVersion::V2.into(),
+							None,
+						),
+					),
+				))
+				.await;
+				env.send_message(collator_message(
+					*peer_id,
+					protocol_v2::CollatorProtocolMessage::Declare(
+						pair.public(),
+						state.para_id,
+						pair.sign(&protocol_v1::declare_signature_payload(peer_id)),
+					),
+				))
+				.await;
+			}
+		}
+
+		let advertisements = state
+			.advertisements
+			.get(&block_info.hash)
+			.expect("Advertisements are generated");
+		for (peer_id, receipt) in collator_peers.iter().zip(advertisements.iter()) {
+			env.send_message(collator_message(
+				*peer_id,
+				protocol_v2::CollatorProtocolMessage::AdvertiseCollation {
+					relay_parent: block_info.hash,
+					candidate_hash: receipt.hash(),
+					parent_head_data_hash: state.pvd.parent_head.hash(),
+				},
+			))
+			.await;
+		}
+
+		let collation_fetched = state
+			.collations_tracker
+			.get(&block_info.hash)
+			.expect("Trackers are generated")
+			.clone();
+		let fetch_start = Instant::now();
+		while !collation_fetched.load(Ordering::SeqCst) {
+			if fetch_start.elapsed() > MAX_FETCH_TIME {
+				panic!(
+					"No collation fetched in block {} after {}s",
+					block_num,
+					MAX_FETCH_TIME.as_secs()
+				);
+			}
+			gum::debug!(target: LOG_TARGET, "Waiting for the collation to be fetched");
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+
+	env.stop().await;
+	env.collect_resource_usage(&["collator-protocol"], false)
+}
@@ -0,0 +1,212 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	collators::CollatorProtocolOptions,
+	configuration::{TestAuthorities, TestConfiguration},
+	mock::runtime_api::session_info_for_peers,
+	network::{HandleNetworkMessage, NetworkMessage},
+	NODE_UNDER_TEST,
+};
+use codec::Encode;
+use polkadot_node_network_protocol::request_response::{v2::CollationFetchingResponse, Requests};
+use polkadot_node_primitives::{BlockData, PoV};
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateHash, CollatorPair, CoreIndex, Hash, Header, Id, PersistedValidationData,
+	ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::{dummy_candidate_receipt_v2, dummy_head_data, dummy_pvd};
+use sc_network::ProtocolName;
+use sp_core::{Pair, H256};
+use std::{
+	collections::HashMap,
+	sync::{
+		atomic::{AtomicBool, Ordering},
+		Arc,
+	},
+};
+
+const SESSION_INDEX: u32 = 0;
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Benchmark specific options
+	pub options: CollatorProtocolOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// One candidate per core for each relay chain block, used to build the claim queue
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// PersistedValidationData, we use one for all candidates
+	pub pvd: PersistedValidationData,
+	// Indices in the backing group where the node under test is
+	pub own_backing_group: Vec<ValidatorIndex>,
+	// The para the backing group of the node under test is assigned to
+	pub para_id: Id,
+	// Keys of the emulated collators
+	pub collator_pairs: Vec<CollatorPair>,
+	// The candidates advertised by each collator at each relay chain block
+	pub advertisements: HashMap<H256, Vec<CandidateReceipt>>,
+	// The PoV of all candidates at each relay chain block
+	pub povs: HashMap<H256, PoV>,
+	// Tracks if a collation was fetched at a relay chain block
+	pub collations_tracker: HashMap<H256, Arc<AtomicBool>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &CollatorProtocolOptions) -> Self {
+		let test_authorities = config.generate_authorities();
+		let session_info = session_info_for_peers(config, &test_authorities);
+		let own_backing_group = session_info
+			.validator_groups
+			.iter()
+			.find(|g| g.contains(&ValidatorIndex(NODE_UNDER_TEST)))
+			.unwrap()
+			.clone();
+		// The group of the node under test is assigned to the first core.
+		let para_id = Id::new(1);
+		let pvd = dummy_pvd(dummy_head_data(), 0);
+		let collator_pairs = (0..options.n_collators)
+			.map(|index| {
+				CollatorPair::from_string_with_seed(&format!("//Collator{}", index), None)
+					.unwrap()
+					.0
+			})
+			.collect();
+		let block_infos: Vec<BlockInfo> =
+			(1..=config.num_blocks).map(generate_block_info).collect();
+		let block_headers = block_infos.iter().map(generate_block_header).collect();
+
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos,
+			block_headers,
+			candidate_receipts: Default::default(),
+			pvd,
+			own_backing_group,
+			para_id,
+			collator_pairs,
+			advertisements: Default::default(),
+			povs: Default::default(),
+			collations_tracker: Default::default(),
+		};
+
+		let pov_size = config.pov_sizes().first().copied().unwrap_or_default();
+		for block_info in state.block_infos.iter() {
+			let receipts = (0..config.n_cores)
+				.map(|core_idx| {
+					let mut receipt = dummy_candidate_receipt_v2(block_info.hash);
+					receipt.descriptor.set_para_id(Id::new(core_idx as u32 + 1));
+					receipt.descriptor.set_core_index(CoreIndex(core_idx as u32));
+					receipt
+				})
+				.collect();
+
+			let pov = PoV { block_data: BlockData(vec![block_info.number as u8; pov_size]) };
+			let advertisements = (0..options.n_collators)
+				.map(|index| {
+					let mut receipt = dummy_candidate_receipt_v2(block_info.hash);
+					receipt.descriptor.set_para_id(para_id);
+					receipt.descriptor.set_core_index(CoreIndex(0));
+					receipt.descriptor.set_session_index(SESSION_INDEX);
+					receipt.descriptor.set_persisted_validation_data_hash(state.pvd.hash());
+					receipt.descriptor.set_pov_hash(pov.hash());
+					// Every collator advertises its own candidate.
+					receipt.descriptor.set_para_head(Hash::from_low_u64_be(index as u64));
+					receipt
+				})
+				.collect();
+
+			state.candidate_receipts.insert(block_info.hash, receipts);
+			state.advertisements.insert(block_info.hash, advertisements);
+			state.povs.insert(block_info.hash, pov);
+			state
+				.collations_tracker
+				.insert(block_info.hash, Arc::new(AtomicBool::new(false)));
+		}
+
+		state
+	}
+
+	fn find_collation(&self, candidate_hash: CandidateHash) -> Option<(&CandidateReceipt, &PoV)> {
+		self.advertisements.iter().find_map(|(relay_parent, receipts)| {
+			receipts
+				.iter()
+				.find(|receipt| receipt.hash() == candidate_hash)
+				.map(|receipt| (receipt, &self.povs[relay_parent]))
+		})
+	}
+
+	pub fn reset_trackers(&self) {
+		self.collations_tracker
+			.values()
+			.for_each(|v| v.as_ref().store(false, Ordering::SeqCst));
+	}
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
+
+#[async_trait::async_trait]
+impl HandleNetworkMessage for TestState {
+	async fn handle(
+		&self,
+		message: NetworkMessage,
+		_node_sender: &mut futures::channel::mpsc::UnboundedSender<NetworkMessage>,
+	) -> Option<NetworkMessage> {
+		match message {
+			NetworkMessage::RequestFromNode(_authority_id, Requests::CollationFetchingV2(req)) => {
+				let payload = req.payload;
+				let (receipt, pov) = self
+					.find_collation(payload.candidate_hash)
+					.expect("Only advertised collations are requested");
+				let response = CollationFetchingResponse::Collation(receipt.clone(), pov.clone());
+				let _ = req.pending_response.send(Ok((response.encode(), ProtocolName::from(""))));
+
+				self.collations_tracker
+					.get(&payload.relay_parent)
+					.expect("Relay parent is known")
+					.store(true, Ordering::SeqCst);
+				None
+			},
+			_ => Some(message),
+		}
+	}
+}
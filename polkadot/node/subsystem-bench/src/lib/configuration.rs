@@ -99,6 +99,9 @@ fn default_max_candidate_depth() -> u32 {
 fn default_allowed_ancestry_len() -> u32 {
 	2
 }
+fn default_erasure_coding_threads() -> usize {
+	1
+}
 
 /// The test input parameters
 #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -156,6 +159,9 @@ pub struct TestConfiguration {
 	/// Async Backing allowed_ancestry_len
 	#[serde(default = "default_allowed_ancestry_len")]
 	pub allowed_ancestry_len: u32,
+	/// Number of threads a single erasure coding operation may be split across
+	#[serde(default = "default_erasure_coding_threads")]
+	pub erasure_coding_threads: usize,
 }
 
 impl Default for TestConfiguration {
@@ -180,6 +186,7 @@ impl Default for TestConfiguration {
 			minimum_backing_votes: default_minimum_backing_votes(),
 			max_candidate_depth: default_max_candidate_depth(),
 			allowed_ancestry_len: default_allowed_ancestry_len(),
+			erasure_coding_threads: default_erasure_coding_threads(),
 		}
 	}
 }
@@ -0,0 +1,249 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmark of the dispute-coordinator subsystem.
+//!
+//! Emulates a burst of disputes spread across several sessions. Disputes on included candidates
+//! make the node under test participate, disputes on unknown candidates are raised by a single
+//! spamming validator and are only accepted until its spam slots are exhausted.
+
+use crate::{
+	dummy_builder,
+	environment::{TestEnvironment, TestEnvironmentDependencies},
+	mock::{
+		approval_voting_parallel::MockApprovalVotingParallel,
+		availability_recovery::MockAvailabilityRecovery,
+		candidate_validation::MockCandidateValidation,
+		chain_api::{ChainApiState, MockChainApi},
+		runtime_api::{MockRuntimeApi, MockRuntimeApiCoreState},
+		AlwaysSupportsParachains,
+	},
+	network::new_network,
+	usage::BenchmarkUsage,
+	NODE_UNDER_TEST,
+};
+use colored::Colorize;
+use futures::{channel::oneshot, future::join_all};
+use polkadot_node_core_dispute_coordinator::{Config, DisputeCoordinatorSubsystem};
+use polkadot_node_metrics::metrics::Metrics;
+use polkadot_node_subsystem::messages::{
+	AllMessages, DisputeCoordinatorMessage, ImportStatementsResult,
+};
+use polkadot_node_subsystem_util::database::Database;
+use polkadot_overseer::{
+	Handle as OverseerHandle, Overseer, OverseerConnector, OverseerMetrics, SpawnGlue,
+};
+use polkadot_primitives::{ValidatorId, ValidatorIndex};
+use sc_keystore::LocalKeystore;
+use sc_service::SpawnTaskHandle;
+use serde::{Deserialize, Serialize};
+use sp_keystore::Keystore;
+use sp_runtime::RuntimeAppPublic;
+use std::{
+	sync::Arc,
+	time::{Duration, Instant},
+};
+pub use test_state::TestState;
+
+mod test_state;
+
+const LOG_TARGET: &str = "subsystem-bench::disputes";
+
+const DISPUTE_DATA_COL: u32 = 0;
+const NUM_COLUMNS: u32 = 1;
+
+// Participation validates the candidates of a block one after another, a block that takes
+// longer than this means the node under test does not participate at all.
+const MAX_PARTICIPATION_TIME: Duration = Duration::from_secs(60);
+
+/// Parameters specific to the dispute-coordinator benchmark
+#[derive(Debug, Clone, Serialize, Deserialize, clap::Parser)]
+#[clap(rename_all = "kebab-case")]
+#[allow(missing_docs)]
+pub struct DisputeCoordinatorOptions {
+	#[clap(long, default_value_t = 1)]
+	/// The number of sessions the blocks are spread across.
+	pub n_sessions: usize,
+	#[clap(long, default_value_t = 1)]
+	/// The number of disputes raised on included candidates per block.
+	pub n_disputes_per_block: usize,
+	#[clap(long, default_value_t = 0)]
+	/// The number of spam disputes raised on unknown candidates per block.
+	pub n_spam_disputes_per_block: usize,
+}
+
+fn make_keystore() -> Arc<LocalKeystore> {
+	let keystore = Arc::new(LocalKeystore::in_memory());
+	Keystore::sr25519_generate_new(&*keystore, ValidatorId::ID, Some("//Node0"))
+		.expect("Insert key into keystore");
+	keystore
+}
+
+fn test_store() -> Arc<dyn Database> {
+	let db = kvdb_memorydb::create(NUM_COLUMNS);
+	let db = polkadot_node_subsystem_util::database::kvdb_impl::DbAdapter::new(db, &[]);
+	Arc::new(db)
+}
+
+fn build_overseer(
+	state: &TestState,
+	dependencies: &TestEnvironmentDependencies,
+) -> (Overseer<SpawnGlue<SpawnTaskHandle>, AlwaysSupportsParachains>, OverseerHandle) {
+	let overseer_connector = OverseerConnector::with_event_capacity(64000);
+	let overseer_metrics = OverseerMetrics::try_register(&dependencies.registry).unwrap();
+	let spawn_task_handle = dependencies.task_manager.spawn_handle();
+	let mock_runtime_api = MockRuntimeApi::new(
+		state.config.clone(),
+		state.test_authorities.clone(),
+		state.candidate_receipts.clone(),
+		state.candidate_events.clone(),
+		Default::default(),
+		0,
+		MockRuntimeApiCoreState::Scheduled,
+	)
+	.with_session_indices(state.block_sessions.clone());
+	let chain_api_state = ChainApiState { block_headers: state.block_headers.clone() };
+	let mock_chain_api = MockChainApi::new(chain_api_state);
+	let subsystem = DisputeCoordinatorSubsystem::new(
+		test_store(),
+		Config { col_dispute_data: DISPUTE_DATA_COL },
+		make_keystore(),
+		Metrics::try_register(&dependencies.registry).unwrap(),
+		true,
+	);
+
+	let dummy = dummy_builder!(spawn_task_handle, overseer_metrics)
+		.replace_runtime_api(|_| mock_runtime_api)
+		.replace_chain_api(|_| mock_chain_api)
+		.replace_availability_recovery(|_| MockAvailabilityRecovery::new())
+		.replace_candidate_validation(|_| MockCandidateValidation::new())
+		.replace_approval_voting_parallel(|_| MockApprovalVotingParallel::new())
+		.replace_dispute_coordinator(|_| subsystem);
+	let (overseer, raw_handle) = dummy.build_with_connector(overseer_connector).unwrap();
+	let overseer_handle = OverseerHandle::new(raw_handle);
+
+	(overseer, overseer_handle)
+}
+
+pub fn prepare_test(state: &TestState, with_prometheus_endpoint: bool) -> TestEnvironment {
+	let dependencies = TestEnvironmentDependencies::default();
+	// Disputes are not distributed in this benchmark, peers are emulated only to satisfy the
+	// test environment.
+	let (network, _network_interface, _network_receiver) =
+		new_network(&state.config, &dependencies, &state.test_authorities, vec![]);
+	let (overseer, overseer_handle) = build_overseer(state, &dependencies);
+
+	TestEnvironment::new(
+		dependencies,
+		state.config.clone(),
+		network,
+		overseer,
+		overseer_handle,
+		state.test_authorities.clone(),
+		with_prometheus_endpoint,
+	)
+}
+
+pub async fn benchmark_dispute_coordinator(
+	env: &mut TestEnvironment,
+	state: &TestState,
+) -> BenchmarkUsage {
+	let config = env.config().clone();
+	env.metrics().set_n_validators(config.n_validators);
+	env.metrics().set_n_cores(config.n_cores);
+
+	let test_start = Instant::now();
+	let mut rejected_imports = 0;
+	for block_info in state.block_infos.iter() {
+		let block_num = block_info.number as usize;
+		let session = state.block_sessions[&block_info.hash];
+		gum::info!(target: LOG_TARGET, "Current block {}/{} {:?} session {}", block_num, config.num_blocks, block_info.hash, session);
+		env.metrics().set_current_block(block_num);
+		env.import_block(block_info.clone()).await;
+
+		let disputes = state.disputes.get(&block_info.hash).expect("Disputes are generated");
+		let mut confirmations = Vec::with_capacity(disputes.len());
+		for dispute in disputes {
+			let (tx, rx) = oneshot::channel();
+			env.send_message(AllMessages::DisputeCoordinator(
+				DisputeCoordinatorMessage::ImportStatements {
+					candidate_receipt: dispute.candidate_receipt.clone(),
+					session: dispute.session,
+					statements: dispute.statements.clone(),
+					pending_confirmation: Some(tx),
+				},
+			))
+			.await;
+			confirmations.push(rx);
+		}
+
+		for result in join_all(confirmations).await {
+			if result.expect("Dispute coordinator never drops confirmations") ==
+				ImportStatementsResult::InvalidImport
+			{
+				rejected_imports += 1;
+			}
+		}
+
+		// Wait for the node under test to participate in all disputes on included candidates.
+		let participations = disputes
+			.iter()
+			.filter(|dispute| dispute.is_included)
+			.map(|dispute| (dispute.session, dispute.candidate_receipt.hash()))
+			.collect::<Vec<_>>();
+		let participation_start = Instant::now();
+		loop {
+			let (tx, rx) = oneshot::channel();
+			env.send_message(AllMessages::DisputeCoordinator(
+				DisputeCoordinatorMessage::QueryCandidateVotes(participations.clone(), tx),
+			))
+			.await;
+			let votes = rx.await.expect("Dispute coordinator never drops queries");
+			let participated = votes
+				.iter()
+				.filter(|(_, _, votes)| {
+					votes.voted_indices().contains(&ValidatorIndex(NODE_UNDER_TEST))
+				})
+				.count();
+			gum::debug!(target: LOG_TARGET, "{}/{} participations", participated, participations.len());
+
+			if participated == participations.len() {
+				break;
+			}
+			if participation_start.elapsed() > MAX_PARTICIPATION_TIME {
+				panic!(
+					"{}/{} participations in block {} after {}s",
+					participated,
+					participations.len(),
+					block_num,
+					MAX_PARTICIPATION_TIME.as_secs()
+				);
+			}
+			tokio::time::sleep(Duration::from_millis(50)).await;
+		}
+	}
+
+	let duration: u128 = test_start.elapsed().as_millis();
+	gum::info!(target: LOG_TARGET, "All blocks processed in {}", format!("{:?}ms", duration).cyan());
+	gum::info!(target: LOG_TARGET,
+		"Avg block time: {}",
+		format!("{} ms", test_start.elapsed().as_millis() / env.config().num_blocks as u128).red()
+	);
+	gum::info!(target: LOG_TARGET, "Rejected dispute imports: {}", format!("{}", rejected_imports).cyan());
+
+	env.stop().await;
+	env.collect_resource_usage(&["dispute-coordinator"], false)
+}
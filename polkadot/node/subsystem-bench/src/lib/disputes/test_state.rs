@@ -0,0 +1,231 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{
+	configuration::{TestAuthorities, TestConfiguration},
+	disputes::DisputeCoordinatorOptions,
+	NODE_UNDER_TEST,
+};
+use polkadot_node_primitives::SignedDisputeStatement;
+use polkadot_node_subsystem_test_helpers::mock::new_block_import_info;
+use polkadot_overseer::BlockInfo;
+use polkadot_primitives::{
+	vstaging::{CandidateEvent, CandidateReceiptV2 as CandidateReceipt, MutateDescriptorV2},
+	BlockNumber, CandidateHash, CoreIndex, GroupIndex, Hash, Header, Id, SessionIndex,
+	ValidatorIndex,
+};
+use polkadot_primitives_test_helpers::{dummy_candidate_receipt_v2, dummy_head_data};
+use sp_core::H256;
+use std::collections::HashMap;
+
+/// A dispute to be imported by the node under test, together with the votes raising it.
+#[derive(Clone)]
+pub struct TestDispute {
+	pub candidate_receipt: CandidateReceipt,
+	pub session: SessionIndex,
+	pub statements: Vec<(SignedDisputeStatement, ValidatorIndex)>,
+	// Whether the candidate was included, making the node under test participate.
+	pub is_included: bool,
+}
+
+#[derive(Clone)]
+pub struct TestState {
+	// Full test config
+	pub config: TestConfiguration,
+	// Benchmark specific options
+	pub options: DisputeCoordinatorOptions,
+	// Authority keys for the network emulation.
+	pub test_authorities: TestAuthorities,
+	// Relay chain block infos
+	pub block_infos: Vec<BlockInfo>,
+	// Relay chain block headers
+	pub block_headers: HashMap<H256, Header>,
+	// The session of each relay chain block
+	pub block_sessions: HashMap<H256, SessionIndex>,
+	// Candidates backed in each relay chain block
+	pub candidate_receipts: HashMap<H256, Vec<CandidateReceipt>>,
+	// Candidates included in each relay chain block
+	pub candidate_events: HashMap<H256, Vec<CandidateEvent>>,
+	// Disputes to import at each relay chain block
+	pub disputes: HashMap<H256, Vec<TestDispute>>,
+}
+
+impl TestState {
+	pub fn new(config: &TestConfiguration, options: &DisputeCoordinatorOptions) -> Self {
+		assert!(config.n_validators >= 4, "Disputes need at least 4 validators");
+		assert!(
+			options.n_disputes_per_block <= config.n_cores,
+			"Only included candidates can be disputed, at most one per core"
+		);
+
+		let test_authorities = config.generate_authorities();
+		let block_infos: Vec<BlockInfo> =
+			(1..=config.num_blocks).map(generate_block_info).collect();
+		let block_headers = block_infos.iter().map(generate_block_header).collect();
+		// Spread the blocks evenly across the sessions.
+		let n_sessions = options.n_sessions.max(1);
+		let block_sessions = block_infos
+			.iter()
+			.enumerate()
+			.map(|(index, info)| {
+				(info.hash, (index * n_sessions / config.num_blocks) as SessionIndex)
+			})
+			.collect::<HashMap<_, _>>();
+
+		let mut state = Self {
+			config: config.clone(),
+			options: options.clone(),
+			test_authorities,
+			block_infos,
+			block_headers,
+			block_sessions,
+			candidate_receipts: Default::default(),
+			candidate_events: Default::default(),
+			disputes: Default::default(),
+		};
+
+		for block_info in state.block_infos.iter() {
+			let session = state.block_sessions[&block_info.hash];
+			let receipts = (0..config.n_cores)
+				.map(|core_idx| {
+					generate_candidate_receipt(block_info.hash, core_idx as u32, session, 0)
+				})
+				.collect::<Vec<_>>();
+			let events = receipts
+				.iter()
+				.enumerate()
+				.map(|(core_idx, receipt)| {
+					CandidateEvent::CandidateIncluded(
+						receipt.clone(),
+						dummy_head_data(),
+						CoreIndex(core_idx as u32),
+						GroupIndex(core_idx as u32),
+					)
+				})
+				.collect();
+
+			// Disputes on included candidates are raised by honest validators, the node under
+			// test is expected to participate in them.
+			let included_disputes = receipts
+				.iter()
+				.take(options.n_disputes_per_block)
+				.enumerate()
+				.map(|(index, receipt)| {
+					let (invalid_voter, valid_voter) = honest_voters(config.n_validators, index);
+					state.generate_dispute(
+						receipt.clone(),
+						session,
+						invalid_voter,
+						Some(valid_voter),
+						true,
+					)
+				});
+			// Spam disputes are raised by a single validator on candidates that were never
+			// included, they are only accepted until the spam slots of that validator run out.
+			let spammer = ValidatorIndex(config.n_validators as u32 - 1);
+			let spam_disputes = (0..options.n_spam_disputes_per_block).map(|index| {
+				let receipt =
+					generate_candidate_receipt(block_info.hash, 0, session, index as u32 + 1);
+				state.generate_dispute(receipt, session, spammer, None, false)
+			});
+			let disputes = included_disputes.chain(spam_disputes).collect();
+
+			state.candidate_receipts.insert(block_info.hash, receipts);
+			state.candidate_events.insert(block_info.hash, events);
+			state.disputes.insert(block_info.hash, disputes);
+		}
+
+		state
+	}
+
+	fn generate_dispute(
+		&self,
+		candidate_receipt: CandidateReceipt,
+		session: SessionIndex,
+		invalid_voter: ValidatorIndex,
+		valid_voter: Option<ValidatorIndex>,
+		is_included: bool,
+	) -> TestDispute {
+		let candidate_hash = candidate_receipt.hash();
+		let statements = std::iter::once((false, invalid_voter))
+			.chain(valid_voter.map(|voter| (true, voter)))
+			.map(|(valid, voter)| {
+				(self.sign_dispute_statement(valid, candidate_hash, session, voter), voter)
+			})
+			.collect();
+
+		TestDispute { candidate_receipt, session, statements, is_included }
+	}
+
+	fn sign_dispute_statement(
+		&self,
+		valid: bool,
+		candidate_hash: CandidateHash,
+		session: SessionIndex,
+		validator_index: ValidatorIndex,
+	) -> SignedDisputeStatement {
+		SignedDisputeStatement::sign_explicit(
+			&self.test_authorities.keyring.keystore(),
+			valid,
+			candidate_hash,
+			session,
+			self.test_authorities.validator_public[validator_index.0 as usize].clone(),
+		)
+		.expect("Keys are in the keystore")
+		.expect("Signing never fails")
+	}
+}
+
+/// Picks two distinct validators which are neither the node under test nor the spammer.
+fn honest_voters(n_validators: usize, index: usize) -> (ValidatorIndex, ValidatorIndex) {
+	let n_honest = n_validators - 2;
+	let invalid_voter = NODE_UNDER_TEST as usize + 1 + index % n_honest;
+	let valid_voter = NODE_UNDER_TEST as usize + 1 + (index + 1) % n_honest;
+
+	(ValidatorIndex(invalid_voter as u32), ValidatorIndex(valid_voter as u32))
+}
+
+fn generate_candidate_receipt(
+	relay_parent: Hash,
+	core_idx: u32,
+	session: SessionIndex,
+	salt: u32,
+) -> CandidateReceipt {
+	let mut receipt = dummy_candidate_receipt_v2(relay_parent);
+	receipt.descriptor.set_para_id(Id::new(core_idx + 1));
+	receipt.descriptor.set_core_index(CoreIndex(core_idx));
+	receipt.descriptor.set_session_index(session);
+	// Make sure candidates which are never included get distinct hashes.
+	receipt.descriptor.set_para_head(Hash::from_low_u64_be(salt as u64));
+	receipt
+}
+
+fn generate_block_info(block_num: usize) -> BlockInfo {
+	new_block_import_info(Hash::repeat_byte(block_num as u8), block_num as BlockNumber)
+}
+
+fn generate_block_header(info: &BlockInfo) -> (H256, Header) {
+	(
+		info.hash,
+		Header {
+			digest: Default::default(),
+			number: info.number,
+			parent_hash: info.parent_hash,
+			extrinsics_root: Default::default(),
+			state_root: Default::default(),
+		},
+	)
+}
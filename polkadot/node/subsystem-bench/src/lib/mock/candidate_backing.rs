@@ -160,6 +160,18 @@ impl MockCandidateBacking {
 								ctx.send_message(message).await;
 							}
 						},
+						CandidateBackingMessage::CanSecond(_request, tx) => {
+							tx.send(true).unwrap();
+						},
+						CandidateBackingMessage::Second(relay_parent, candidate_receipt, ..) => {
+							// Validation and seconding are out of scope for the benchmarks.
+							gum::debug!(
+								target: LOG_TARGET,
+								?relay_parent,
+								candidate_hash = ?candidate_receipt.hash(),
+								"Candidate to second"
+							);
+						},
 						_ => {
 							unimplemented!("Unexpected candidate-backing message")
 						},
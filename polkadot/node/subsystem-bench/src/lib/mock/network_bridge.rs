@@ -139,6 +139,10 @@ impl MockNetworkBridgeTx {
 							let _ = self.to_network_interface.unbounded_send(peer_message);
 						}
 					},
+					NetworkBridgeTxMessage::DisconnectPeer(..) |
+					NetworkBridgeTxMessage::SendCollationMessage(..) => {
+						// Collators are only emulated for the requests they answer.
+					},
 					NetworkBridgeTxMessage::ReportPeer(_) => {
 						// ignore rep changes
 					},
@@ -52,6 +52,8 @@ pub struct RuntimeApiState {
 	babe_epoch: Option<BabeEpoch>,
 	// The session child index,
 	session_index: SessionIndex,
+	// The session child index per block, overrides `session_index` if present
+	session_indices: HashMap<H256, SessionIndex>,
 	// The claim queue
 	claim_queue: BTreeMap<CoreIndex, VecDeque<ParaId>>,
 }
@@ -110,6 +112,7 @@ impl MockRuntimeApi {
 				included_candidates,
 				babe_epoch,
 				session_index,
+				session_indices: Default::default(),
 				node_features,
 				claim_queue,
 			},
@@ -118,6 +121,12 @@ impl MockRuntimeApi {
 		}
 	}
 
+	/// Use a different session index for the children of the given blocks.
+	pub fn with_session_indices(mut self, session_indices: HashMap<H256, SessionIndex>) -> Self {
+		self.state.session_indices = session_indices;
+		self
+	}
+
 	fn session_info(&self) -> SessionInfo {
 		session_info_for_peers(&self.config, &self.state.authorities)
 	}
@@ -215,11 +224,16 @@ impl MockRuntimeApi {
 								sender.send(Ok(self.state.authorities.validator_public.clone()));
 						},
 						RuntimeApiMessage::Request(
-							_block_hash,
+							block_hash,
 							RuntimeApiRequest::SessionIndexForChild(sender),
 						) => {
-							// Session is always the same.
-							let _ = sender.send(Ok(self.state.session_index));
+							let session_index = self
+								.state
+								.session_indices
+								.get(&block_hash)
+								.copied()
+								.unwrap_or(self.state.session_index);
+							let _ = sender.send(Ok(session_index));
 						},
 						RuntimeApiMessage::Request(
 							block_hash,
@@ -330,6 +344,19 @@ impl MockRuntimeApi {
 						RuntimeApiMessage::Request(_parent, RuntimeApiRequest::ClaimQueue(tx)) => {
 							tx.send(Ok(self.state.claim_queue.clone())).unwrap();
 						},
+						RuntimeApiMessage::Request(
+							_parent,
+							RuntimeApiRequest::FetchOnChainVotes(tx),
+						) => {
+							// No disputes are ever concluded on chain.
+							tx.send(Ok(None)).unwrap();
+						},
+						RuntimeApiMessage::Request(
+							_parent,
+							RuntimeApiRequest::UnappliedSlashes(tx),
+						) => {
+							tx.send(Ok(vec![])).unwrap();
+						},
 						// Long term TODO: implement more as needed.
 						message => {
 							unimplemented!("Unexpected runtime-api message: {:?}", message)
@@ -36,6 +36,7 @@ use polkadot_node_primitives::{
 		v1::{BlockApprovalMeta, DelayTranche},
 		v2::{CandidateBitfield, IndirectAssignmentCertV2, IndirectSignedApprovalVoteV2},
 	},
+	disputes::DisputeInfo,
 	AvailableData, BabeEpoch, BlockWeight, CandidateVotes, CollationGenerationConfig,
 	CollationSecondedSignal, DisputeMessage, DisputeStatus, ErasureChunk, PoV,
 	SignedDisputeStatement, SignedFullStatement, SignedFullStatementWithPVD, SubmitCollationParams,
@@ -322,6 +323,18 @@ pub enum DisputeCoordinatorMessage {
 	/// Fetch a list of all active disputes that the coordinator is aware of.
 	/// These disputes are either not yet concluded or recently concluded.
 	ActiveDisputes(oneshot::Sender<Vec<(SessionIndex, CandidateHash, DisputeStatus)>>),
+	/// Inspect the disputes the coordinator is aware of, with their votes and our own
+	/// participation. These are the recent disputes, or only the active ones if `active_only` is
+	/// set.
+	///
+	/// Meant for node operators, this is more expensive than `RecentDisputes`. Only a bounded
+	/// number of the disputes of the most recent sessions is returned.
+	InspectDisputes {
+		/// Only return disputes which are not yet concluded or recently concluded.
+		active_only: bool,
+		/// The disputes, in descending order of session and candidate hash.
+		tx: oneshot::Sender<Vec<DisputeInfo>>,
+	},
 	/// Get candidate votes for a candidate.
 	QueryCandidateVotes(
 		Vec<(SessionIndex, CandidateHash)>,
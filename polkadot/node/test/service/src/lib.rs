@@ -104,9 +104,12 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					hwbench: None,
 					execute_workers_max_num: None,
 					prepare_workers_hard_max_num: None,
+					erasure_coding_threads: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
 					keep_finalized_for: None,
+					availability_archive_config: Default::default(),
+					enable_xcm_lifecycle_index: false,
 				},
 			),
 		sc_network::config::NetworkBackendType::Litep2p =>
@@ -127,9 +130,12 @@ pub fn new_full<OverseerGenerator: OverseerGen>(
 					hwbench: None,
 					execute_workers_max_num: None,
 					prepare_workers_hard_max_num: None,
+					erasure_coding_threads: None,
 					prepare_workers_soft_max_num: None,
 					enable_approval_voting_parallel: false,
 					keep_finalized_for: None,
+					availability_archive_config: Default::default(),
+					enable_xcm_lifecycle_index: false,
 				},
 			),
 	}
@@ -120,6 +120,10 @@ pub enum ExecutorParam {
 	/// Enables WASM bulk memory proposal
 	#[codec(index = 7)]
 	WasmExtBulkMemory,
+	/// Allows PVFs to be PolkaVM program blobs, which are then prepared and executed by the
+	/// PolkaVM executor instead of wasmtime. Experimental, only meant for test networks.
+	#[codec(index = 8)]
+	PolkaVm,
 }
 
 /// Possible inconsistencies of executor params.
@@ -242,6 +246,7 @@ impl ExecutorParams {
 				PvfPrepTimeout(..) => Some(param),
 				PvfExecTimeout(..) => None,
 				WasmExtBulkMemory => Some(param),
+				PolkaVm => Some(param),
 			})
 			.for_each(|p| enc.extend(p.encode()));
 
@@ -325,6 +330,7 @@ impl ExecutorParams {
 					PvfExecKind::Approval => "PvfExecKind::Approval",
 				},
 				WasmExtBulkMemory => "WasmExtBulkMemory",
+				PolkaVm => "PolkaVm",
 			};
 
 			match *param {
@@ -356,7 +362,7 @@ impl ExecutorParams {
 					check!(param_ident, val);
 				},
 
-				WasmExtBulkMemory => {
+				WasmExtBulkMemory | PolkaVm => {
 					check!(param_ident, 1);
 				},
 			}
@@ -429,6 +435,7 @@ fn ensure_prep_hash_changes() {
 			PvfExecTimeout(PvfExecKind::Backing, 0),
 			PvfExecTimeout(PvfExecKind::Approval, 0),
 			WasmExtBulkMemory,
+			PolkaVm,
 		][..],
 	);
 
@@ -452,6 +459,7 @@ fn ensure_prep_hash_changes() {
 			PvfExecTimeout(_, _) => continue,
 			WasmExtBulkMemory =>
 				(ExecutorParams::default(), ExecutorParams::from(&[WasmExtBulkMemory][..])),
+			PolkaVm => (ExecutorParams::default(), ExecutorParams::from(&[PolkaVm][..])),
 		};
 
 		assert_ne!(ep1.prep_hash(), ep2.prep_hash());
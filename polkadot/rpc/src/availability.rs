@@ -0,0 +1,86 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for querying the available data kept by the availability store of a validator.
+
+use codec::Encode;
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::ErrorObject,
+	Extensions,
+};
+use polkadot_node_subsystem_types::messages::AvailabilityStoreMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{CandidateHash, Hash};
+use sc_rpc_api::check_if_safe;
+use sp_core::Bytes;
+
+const AVAILABILITY_STORE_UNAVAILABLE: i32 = 8500;
+
+/// Availability RPC methods.
+#[rpc(server)]
+pub trait AvailabilityApi {
+	/// Returns the SCALE-encoded `AvailableData`, i.e. the PoV and the persisted validation data,
+	/// of the candidate with the given hash, if it is in the availability store.
+	///
+	/// Besides the data of recent candidates, this includes the data of the paras archived with
+	/// `--availability-archive-para`. Only available on validators.
+	#[method(name = "availability_availableData", with_extensions)]
+	async fn available_data(&self, candidate_hash: Hash) -> RpcResult<Option<Bytes>>;
+}
+
+/// Availability RPC methods, querying the availability store through the overseer.
+pub struct Availability {
+	overseer_handle: Handle,
+}
+
+impl Availability {
+	/// Create new `Availability` sending its queries to the overseer behind `overseer_handle`.
+	pub fn new(overseer_handle: Handle) -> Self {
+		Self { overseer_handle }
+	}
+}
+
+#[async_trait]
+impl AvailabilityApiServer for Availability {
+	async fn available_data(
+		&self,
+		ext: &Extensions,
+		candidate_hash: Hash,
+	) -> RpcResult<Option<Bytes>> {
+		check_if_safe(ext)?;
+
+		let (tx, rx) = oneshot::channel();
+		self.overseer_handle
+			.clone()
+			.send_msg(
+				AvailabilityStoreMessage::QueryAvailableData(CandidateHash(candidate_hash), tx),
+				"AvailabilityRpc",
+			)
+			.await;
+		let available_data = rx.await.map_err(|_| {
+			ErrorObject::owned(
+				AVAILABILITY_STORE_UNAVAILABLE,
+				"The availability store is not available",
+				None::<()>,
+			)
+		})?;
+
+		Ok(available_data.map(|data| data.encode().into()))
+	}
+}
@@ -0,0 +1,262 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! RPC for inspecting the disputes known to the dispute coordinator of a validator.
+
+use futures::channel::oneshot;
+use jsonrpsee::{
+	core::{async_trait, RpcResult},
+	proc_macros::rpc,
+	types::error::ErrorObject,
+	Extensions,
+};
+use polkadot_node_primitives::disputes::{
+	DisputeInfo, DisputeStatus, OwnParticipation, ParticipationOutcome, Timestamp,
+};
+use polkadot_node_subsystem_types::messages::DisputeCoordinatorMessage;
+use polkadot_overseer::Handle;
+use polkadot_primitives::{Hash, SessionIndex, ValidDisputeStatementKind};
+use sc_rpc_api::check_if_safe;
+use serde::{Deserialize, Serialize};
+
+const DISPUTE_COORDINATOR_UNAVAILABLE: i32 = 8400;
+
+/// The status of a dispute.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Status {
+	/// The dispute is active and unconcluded.
+	Active,
+	/// The dispute is unconcluded, but confirmed not to be spam.
+	Confirmed,
+	/// The dispute concluded in favor of the candidate.
+	ConcludedFor,
+	/// The dispute concluded against the candidate.
+	ConcludedAgainst,
+}
+
+/// The kind of statement a vote stems from.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VoteKind {
+	/// An explicit statement issued as part of the dispute.
+	Explicit,
+	/// A statement from the backing phase.
+	Backing,
+	/// An approval vote.
+	ApprovalChecking,
+}
+
+/// A vote of a validator on a disputed candidate.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Vote {
+	/// Index of the validator in the session.
+	pub validator_index: u32,
+	/// Whether the validator considers the candidate valid.
+	pub valid: bool,
+	/// The kind of statement the vote stems from.
+	pub kind: VoteKind,
+	/// Whether the vote was cast with one of our keys.
+	pub own: bool,
+}
+
+/// The result of our own participation in a dispute.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum ParticipationResult {
+	/// The candidate was found to be valid.
+	Valid,
+	/// The candidate was found to be invalid.
+	Invalid,
+	/// The candidate could not be recovered.
+	Unavailable,
+	/// Participation failed, details can be found in the logs of the node.
+	Error,
+}
+
+/// Our own participation in a dispute.
+///
+/// Only participations since the last restart of the node are known.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Participation {
+	/// Seconds since the UNIX epoch at which the participation got queued.
+	pub queued_at: Timestamp,
+	/// Seconds since the UNIX epoch at which the participation concluded, if it did.
+	pub concluded_at: Option<Timestamp>,
+	/// The result of the participation, if it concluded.
+	pub result: Option<ParticipationResult>,
+}
+
+/// A dispute known to the dispute coordinator.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Dispute {
+	/// The session the candidate appears in.
+	pub session: SessionIndex,
+	/// Hash of the disputed candidate.
+	pub candidate_hash: Hash,
+	/// The para of the candidate, if its votes are still known.
+	pub para_id: Option<u32>,
+	/// The status of the dispute.
+	pub status: Status,
+	/// Seconds since the UNIX epoch at which the dispute concluded, if it did.
+	pub concluded_at: Option<Timestamp>,
+	/// Number of validators voting for the candidate.
+	pub valid_votes: u32,
+	/// Number of validators voting against the candidate.
+	pub invalid_votes: u32,
+	/// The votes of all validators, ordered by validator index.
+	pub votes: Vec<Vote>,
+	/// Whether we are a validator in the session of the candidate.
+	pub is_validator: bool,
+	/// Our own participation in the dispute, if any.
+	pub participation: Option<Participation>,
+}
+
+impl From<DisputeInfo> for Dispute {
+	fn from(info: DisputeInfo) -> Self {
+		let (status, concluded_at) = match info.status {
+			DisputeStatus::Active => (Status::Active, None),
+			DisputeStatus::Confirmed => (Status::Confirmed, None),
+			DisputeStatus::ConcludedFor(at) => (Status::ConcludedFor, Some(at)),
+			DisputeStatus::ConcludedAgainst(at) => (Status::ConcludedAgainst, Some(at)),
+		};
+		let own_votes = info.own_votes.as_deref().unwrap_or_default();
+		let is_own = |index, valid| own_votes.contains(&(index, valid));
+
+		let (para_id, mut votes) = match &info.votes {
+			Some(candidate_votes) => {
+				let valid = candidate_votes.valid.raw().iter().map(|(index, (kind, _))| Vote {
+					validator_index: index.0,
+					valid: true,
+					kind: match kind {
+						ValidDisputeStatementKind::Explicit => VoteKind::Explicit,
+						ValidDisputeStatementKind::BackingSeconded(_) |
+						ValidDisputeStatementKind::BackingValid(_) => VoteKind::Backing,
+						ValidDisputeStatementKind::ApprovalChecking |
+						ValidDisputeStatementKind::ApprovalCheckingMultipleCandidates(_) =>
+							VoteKind::ApprovalChecking,
+					},
+					own: is_own(*index, true),
+				});
+				let invalid = candidate_votes.invalid.keys().map(|index| Vote {
+					validator_index: index.0,
+					valid: false,
+					kind: VoteKind::Explicit,
+					own: is_own(*index, false),
+				});
+				(
+					Some(candidate_votes.candidate_receipt.descriptor.para_id().into()),
+					valid.chain(invalid).collect::<Vec<_>>(),
+				)
+			},
+			None => (None, Vec::new()),
+		};
+		votes.sort_by_key(|vote| vote.validator_index);
+
+		Self {
+			session: info.session,
+			candidate_hash: info.candidate_hash.0,
+			para_id,
+			status,
+			concluded_at,
+			valid_votes: votes.iter().filter(|vote| vote.valid).count() as u32,
+			invalid_votes: votes.iter().filter(|vote| !vote.valid).count() as u32,
+			votes,
+			is_validator: info.own_votes.is_some(),
+			participation: info.participation.map(Into::into),
+		}
+	}
+}
+
+impl From<OwnParticipation> for Participation {
+	fn from(participation: OwnParticipation) -> Self {
+		let (result, concluded_at) = match participation.concluded {
+			Some((outcome, at)) => (Some(outcome.into()), Some(at)),
+			None => (None, None),
+		};
+		Self { queued_at: participation.queued_at, concluded_at, result }
+	}
+}
+
+impl From<ParticipationOutcome> for ParticipationResult {
+	fn from(outcome: ParticipationOutcome) -> Self {
+		match outcome {
+			ParticipationOutcome::Valid => Self::Valid,
+			ParticipationOutcome::Invalid => Self::Invalid,
+			ParticipationOutcome::Unavailable => Self::Unavailable,
+			ParticipationOutcome::Error => Self::Error,
+		}
+	}
+}
+
+/// Dispute inspection RPC methods.
+#[rpc(server)]
+pub trait DisputesApi {
+	/// Returns the recent disputes known to the dispute coordinator, or only the active ones if
+	/// `active_only` is set, with the votes of all validators and our own participation.
+	///
+	/// Only a bounded number of the disputes of the most recent sessions is returned, most recent
+	/// first. Only available on validators.
+	#[method(name = "disputes_inspect", with_extensions)]
+	async fn inspect(&self, active_only: Option<bool>) -> RpcResult<Vec<Dispute>>;
+}
+
+/// Dispute inspection RPC methods, querying the dispute coordinator through the overseer.
+pub struct Disputes {
+	overseer_handle: Handle,
+}
+
+impl Disputes {
+	/// Create new `Disputes` sending its queries to the overseer behind `overseer_handle`.
+	pub fn new(overseer_handle: Handle) -> Self {
+		Self { overseer_handle }
+	}
+}
+
+#[async_trait]
+impl DisputesApiServer for Disputes {
+	async fn inspect(
+		&self,
+		ext: &Extensions,
+		active_only: Option<bool>,
+	) -> RpcResult<Vec<Dispute>> {
+		check_if_safe(ext)?;
+
+		let (tx, rx) = oneshot::channel();
+		self.overseer_handle
+			.clone()
+			.send_msg(
+				DisputeCoordinatorMessage::InspectDisputes {
+					active_only: active_only.unwrap_or(false),
+					tx,
+				},
+				"DisputesRpc",
+			)
+			.await;
+		let disputes = rx.await.map_err(|_| {
+			ErrorObject::owned(
+				DISPUTE_COORDINATOR_UNAVAILABLE,
+				"The dispute coordinator is not available",
+				None::<()>,
+			)
+		})?;
+
+		Ok(disputes.into_iter().map(Into::into).collect())
+	}
+}
@@ -38,6 +38,9 @@ use sp_consensus_babe::BabeApi;
 use sp_consensus_beefy::AuthorityIdBound;
 use sp_keystore::KeystorePtr;
 
+pub mod availability;
+pub mod disputes;
+
 /// A type representing all RPC extensions.
 pub type RpcExtension = RpcModule<()>;
 
@@ -91,17 +94,24 @@ pub struct FullDeps<C, P, SC, B, AuthorityId: AuthorityIdBound> {
 	pub beefy: BeefyDeps<AuthorityId>,
 	/// Backend used by the node.
 	pub backend: Arc<B>,
+	/// Handle to the overseer, to expose the dispute inspection and availability RPCs on
+	/// validators.
+	pub overseer_handle: Option<polkadot_overseer::Handle>,
 }
 
 /// Instantiate all RPC extensions.
 pub fn create_full<C, P, SC, B, AuthorityId>(
-	FullDeps { client, pool, select_chain, chain_spec, babe, grandpa, beefy, backend }: FullDeps<
-		C,
-		P,
-		SC,
-		B,
-		AuthorityId,
-	>,
+	FullDeps {
+		client,
+		pool,
+		select_chain,
+		chain_spec,
+		babe,
+		grandpa,
+		beefy,
+		backend,
+		overseer_handle,
+	}: FullDeps<C, P, SC, B, AuthorityId>,
 ) -> Result<RpcExtension, Box<dyn std::error::Error + Send + Sync>>
 where
 	C: ProvideRuntimeApi<Block>
@@ -123,6 +133,8 @@ where
 	AuthorityId: AuthorityIdBound,
 	<AuthorityId as RuntimeAppPublic>::Signature: Send + Sync,
 {
+	use availability::{Availability, AvailabilityApiServer};
+	use disputes::{Disputes, DisputesApiServer};
 	use mmr_rpc::{Mmr, MmrApiServer};
 	use pallet_transaction_payment_rpc::{TransactionPayment, TransactionPaymentApiServer};
 	use sc_consensus_babe_rpc::{Babe, BabeApiServer};
@@ -131,6 +143,7 @@ where
 	use sc_sync_state_rpc::{SyncState, SyncStateApiServer};
 	use substrate_frame_rpc_system::{System, SystemApiServer};
 	use substrate_state_trie_migration_rpc::{StateMigration, StateMigrationApiServer};
+	use xcm_lifecycle_rpc::{XcmLifecycle, XcmLifecycleApiServer};
 
 	let mut io = RpcModule::new(());
 	let BabeDeps { babe_worker_handle, keystore } = babe;
@@ -145,6 +158,7 @@ where
 	io.merge(StateMigration::new(client.clone(), backend.clone()).into_rpc())?;
 	io.merge(System::new(client.clone(), pool.clone()).into_rpc())?;
 	io.merge(TransactionPayment::new(client.clone()).into_rpc())?;
+	io.merge(XcmLifecycle::<_, Block>::new(client.clone()).into_rpc())?;
 	io.merge(
 		Mmr::new(
 			client.clone(),
@@ -180,5 +194,10 @@ where
 		.into_rpc(),
 	)?;
 
+	if let Some(overseer_handle) = overseer_handle {
+		io.merge(Disputes::new(overseer_handle.clone()).into_rpc())?;
+		io.merge(Availability::new(overseer_handle).into_rpc())?;
+	}
+
 	Ok(io)
 }
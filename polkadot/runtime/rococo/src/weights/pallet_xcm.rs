@@ -394,4 +394,40 @@ impl<T: frame_system::Config> pallet_xcm::WeightInfo for WeightInfo<T> {
 			.saturating_add(T::DbWeight::get().reads(1))
 			.saturating_add(T::DbWeight::get().writes(1))
 	}
+	/// Storage: `XcmPallet::RemoteFeeTables` (r:0 w:1)
+	/// Proof: `XcmPallet::RemoteFeeTables` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_remote_fee_table() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 10_312_000 picoseconds.
+		Weight::from_parts(10_741_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// Storage: `XcmPallet::OutboundTransferLimits` (r:0 w:1)
+	/// Proof: `XcmPallet::OutboundTransferLimits` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	/// Storage: `XcmPallet::OutboundTransferWindows` (r:0 w:1)
+	/// Proof: `XcmPallet::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn set_outbound_transfer_limit() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 12_458_000 picoseconds.
+		Weight::from_parts(12_916_000, 0)
+			.saturating_add(Weight::from_parts(0, 0))
+			.saturating_add(T::DbWeight::get().writes(2))
+	}
+	/// Storage: `XcmPallet::OutboundTransferWindows` (r:1 w:1)
+	/// Proof: `XcmPallet::OutboundTransferWindows` (`max_values`: None, `max_size`: None, mode: `Measured`)
+	fn resume_outbound_transfers() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `163`
+		//  Estimated: `3628`
+		// Minimum execution time: 15_104_000 picoseconds.
+		Weight::from_parts(15_687_000, 0)
+			.saturating_add(Weight::from_parts(0, 3628))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
 }
@@ -118,7 +118,8 @@ use xcm_builder::PayOverXcm;
 
 use xcm_runtime_apis::{
 	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
-	fees::Error as XcmPaymentApiError,
+	fees::{Error as XcmPaymentApiError, FeeItem, TransferFeesError},
+	lifecycle::{LifecycleEvent, LifecycleStage},
 };
 
 pub use frame_system::Call as SystemCall;
@@ -2551,6 +2552,36 @@ sp_api::impl_runtime_apis! {
 		}
 	}
 
+	impl xcm_runtime_apis::fees::XcmTransferFeesApi<Block, RuntimeCall> for Runtime {
+		fn query_transfer_fees(origin: VersionedLocation, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<Vec<FeeItem>, TransferFeesError> {
+			let RuntimeCall::XcmPallet(call) = call else { return Err(TransferFeesError::UnsupportedCall) };
+			XcmPallet::query_transfer_fees(origin, call, result_xcms_version, |weight| {
+				Ok((xcm_config::TokenLocation::get(), WeightToFee::weight_to_fee(&weight)).into())
+			})
+		}
+	}
+
+	impl xcm_runtime_apis::lifecycle::XcmLifecycleApi<Block> for Runtime {
+		fn xcm_lifecycle_events() -> Vec<LifecycleEvent> {
+			System::read_events_no_consensus()
+				.enumerate()
+				.filter_map(|(index, record)| {
+					let (message_id, stage) = match record.event {
+						RuntimeEvent::XcmPallet(event) => XcmPallet::lifecycle_stage(event)?,
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::Processed { id, weight_used, success, .. }) =>
+							(id.into(), LifecycleStage::Processed { weight_used, success }),
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::ProcessingFailed { id, error, .. }) =>
+							(id.into(), LifecycleStage::ProcessingFailed { error }),
+						RuntimeEvent::MessageQueue(pallet_message_queue::Event::OverweightEnqueued { id, .. }) =>
+							(id, LifecycleStage::Overweight),
+						_ => return None,
+					};
+					Some(LifecycleEvent { message_id, event_index: index as u32, stage })
+				})
+				.collect()
+		}
+	}
+
 	impl xcm_runtime_apis::dry_run::DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for Runtime {
 		fn dry_run_call(origin: OriginCaller, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<CallDryRunEffects<RuntimeEvent>, XcmDryRunApiError> {
 			XcmPallet::dry_run_call::<Runtime, xcm_config::XcmRouter, OriginCaller, RuntimeCall>(origin, call, result_xcms_version)
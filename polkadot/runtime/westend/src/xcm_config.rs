@@ -232,6 +232,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type TransferLimiter = XcmPallet;
 }
 
 parameter_types! {
@@ -0,0 +1,72 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for the storage-backed XCM barrier.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use codec::Decode;
+use frame_benchmarking::v2::*;
+use frame_support::{
+	traits::{EnsureOrigin, Get},
+	BoundedVec,
+};
+use xcm::VersionedLocation;
+
+fn all_instructions() -> PermittedInstructions {
+	// `SetHints` is the last variant.
+	let count = InstructionKind::SetHints as u8 + 1;
+	let kinds = (0..count)
+		.map(|index| InstructionKind::decode(&mut &[index][..]).expect("index is in range"))
+		.collect::<Vec<_>>();
+	kinds.try_into().expect("every instruction fits")
+}
+
+fn pattern(index: u32) -> OriginPattern {
+	OriginPattern::Prefix(VersionedLocation::from(Location::new(
+		1,
+		[Parachain(index), GeneralIndex(index as u128)],
+	)))
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	// Worst case: the policy is appended after all others.
+	#[benchmark]
+	fn set_policy() -> Result<(), BenchmarkError> {
+		let admin =
+			T::AdminOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let max = T::MaxPolicies::get();
+		let policies = (1..max)
+			.map(|index| ExecutionPolicy {
+				origin: pattern(index),
+				instructions: all_instructions(),
+			})
+			.collect::<Vec<_>>();
+		Policies::<T>::put(BoundedVec::truncate_from(policies));
+
+		#[extrinsic_call]
+		_(admin as T::RuntimeOrigin, Box::new(pattern(max)), Some(all_instructions()));
+
+		assert_eq!(Policies::<T>::get().len() as u32, max);
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
@@ -0,0 +1,226 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! A barrier whose allowlist lives in storage.
+//!
+//! The barriers of `xcm-builder` are type lists fixed at compile time, so letting a new origin use
+//! e.g. `Transact` or `UnpaidExecution` requires a runtime upgrade. This pallet keeps a list of
+//! [`ExecutionPolicy`]s, each naming a set of origins and the instructions they may execute, which
+//! [`Config::AdminOrigin`] manages through [`Pallet::set_policy`].
+//!
+//! The pallet itself implements [`ShouldExecute`] and is meant to be placed in the `Barrier` tuple
+//! of the executor configuration. A message passes it when:
+//! - the most specific policy matching its origin permits every instruction of the message,
+//!   including those of nested programs (`SetAppendix`, `SetErrorHandler`, `ExecuteWithOrigin`),
+//!   and
+//! - the message pays for its execution as required by [`AllowTopLevelPaidExecutionFrom`], or, if
+//!   it contains `UnpaidExecution`, as required by [`AllowExplicitUnpaidExecutionFrom`].
+//!
+//! The current policies can be inspected through [`runtime_api::XcmBarrierApi`].
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod benchmarking;
+pub mod policy;
+pub mod runtime_api;
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use alloc::{boxed::Box, vec::Vec};
+use frame_support::traits::{Everything, ProcessMessageError};
+use xcm::latest::prelude::*;
+use xcm_builder::{AllowExplicitUnpaidExecutionFrom, AllowTopLevelPaidExecutionFrom};
+use xcm_executor::traits::{Properties, ShouldExecute};
+
+pub use pallet::*;
+pub use policy::{ExecutionPolicy, InstructionKind, OriginPattern, PermittedInstructions};
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "xcm::barriers::storage";
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// The origin allowed to change the policies.
+		type AdminOrigin: EnsureOrigin<Self::RuntimeOrigin>;
+
+		/// The maximum number of policies.
+		#[pallet::constant]
+		type MaxPolicies: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+	}
+
+	/// The execution policies, at most one per origin pattern.
+	#[pallet::storage]
+	pub type Policies<T: Config> =
+		StorageValue<_, BoundedVec<ExecutionPolicy, T::MaxPolicies>, ValueQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// The instructions permitted to `origin` were set.
+		PolicySet { origin: OriginPattern, instructions: PermittedInstructions },
+		/// The policy of `origin` was removed.
+		PolicyRemoved { origin: OriginPattern },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// The location of the origin pattern could not be converted to the latest version.
+		BadVersion,
+		/// There are already `MaxPolicies` policies.
+		TooManyPolicies,
+		/// There is no policy for the origin pattern.
+		PolicyNotFound,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Set the instructions `origin_pattern` may execute, replacing any previous policy for
+		/// the same pattern, or remove its policy if `instructions` is `None`.
+		///
+		/// Origins not matched by any policy are rejected by the barrier.
+		#[pallet::call_index(0)]
+		#[pallet::weight(T::WeightInfo::set_policy())]
+		pub fn set_policy(
+			origin: OriginFor<T>,
+			origin_pattern: Box<OriginPattern>,
+			instructions: Option<PermittedInstructions>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let origin_pattern =
+				(*origin_pattern).into_latest().map_err(|()| Error::<T>::BadVersion)?;
+
+			let instructions = instructions.map(|mut instructions| {
+				instructions.sort();
+				instructions.dedup();
+				instructions
+			});
+
+			Policies::<T>::try_mutate(|policies| {
+				let existing = policies.iter().position(|policy| policy.origin == origin_pattern);
+				match (existing, instructions) {
+					(Some(index), Some(instructions)) => {
+						policies[index].instructions = instructions.clone();
+						Self::deposit_event(Event::PolicySet {
+							origin: origin_pattern,
+							instructions,
+						});
+					},
+					(None, Some(instructions)) => {
+						policies
+							.try_push(ExecutionPolicy {
+								origin: origin_pattern.clone(),
+								instructions: instructions.clone(),
+							})
+							.map_err(|_| Error::<T>::TooManyPolicies)?;
+						Self::deposit_event(Event::PolicySet {
+							origin: origin_pattern,
+							instructions,
+						});
+					},
+					(Some(index), None) => {
+						policies.remove(index);
+						Self::deposit_event(Event::PolicyRemoved { origin: origin_pattern });
+					},
+					(None, None) => return Err(Error::<T>::PolicyNotFound.into()),
+				}
+				Ok(())
+			})
+		}
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The current policies.
+	pub fn policies() -> Vec<ExecutionPolicy> {
+		Policies::<T>::get().into_inner()
+	}
+
+	/// The policy applying to `origin`, if any.
+	pub fn policy_for(origin: &Location) -> Option<ExecutionPolicy> {
+		Policies::<T>::get()
+			.into_iter()
+			.filter(|policy| policy.origin.matches(origin))
+			.max_by_key(|policy| policy.origin.specificity())
+	}
+}
+
+impl<T: Config> ShouldExecute for Pallet<T> {
+	fn should_execute<RuntimeCall>(
+		origin: &Location,
+		instructions: &mut [Instruction<RuntimeCall>],
+		max_weight: Weight,
+		properties: &mut Properties,
+	) -> Result<(), ProcessMessageError> {
+		tracing::trace!(
+			target: LOG_TARGET,
+			?origin, ?instructions, ?max_weight, ?properties,
+			"StorageBarrier",
+		);
+
+		let Some(policy) = Self::policy_for(origin) else {
+			tracing::trace!(target: LOG_TARGET, ?origin, "No policy for origin");
+			return Err(ProcessMessageError::Unsupported)
+		};
+		if !policy.permits(instructions) {
+			tracing::debug!(
+				target: LOG_TARGET,
+				?origin, ?policy,
+				"Message contains instructions not permitted to origin",
+			);
+			return Err(ProcessMessageError::Unsupported)
+		}
+
+		if instructions
+			.iter()
+			.any(|instruction| matches!(instruction, UnpaidExecution { .. }))
+		{
+			AllowExplicitUnpaidExecutionFrom::<Everything>::should_execute(
+				origin,
+				instructions,
+				max_weight,
+				properties,
+			)
+		} else {
+			AllowTopLevelPaidExecutionFrom::<Everything>::should_execute(
+				origin,
+				instructions,
+				max_weight,
+				properties,
+			)
+		}
+	}
+}
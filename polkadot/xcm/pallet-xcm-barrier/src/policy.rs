@@ -0,0 +1,252 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The execution policies kept in storage by the barrier.
+
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use frame_support::{traits::ConstU32, BoundedVec};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use xcm::{latest::prelude::*, VersionedLocation};
+
+/// The maximum number of instruction kinds a single policy may permit.
+///
+/// Large enough to hold every XCM instruction.
+pub const MAX_INSTRUCTION_KINDS: u32 = 64;
+
+/// The instruction kinds permitted by a policy.
+pub type PermittedInstructions = BoundedVec<InstructionKind, ConstU32<MAX_INSTRUCTION_KINDS>>;
+
+/// The origins a policy applies to.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Eq,
+	PartialEq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum OriginPattern {
+	/// Only the given location.
+	Exact(VersionedLocation),
+	/// The given location and every location interior to it.
+	Prefix(VersionedLocation),
+}
+
+impl OriginPattern {
+	/// The location the pattern is anchored at, in the latest version.
+	pub fn location(&self) -> Result<Location, ()> {
+		match self {
+			Self::Exact(location) | Self::Prefix(location) => location.clone().try_into(),
+		}
+	}
+
+	/// Returns `true` if `origin` is covered by this pattern.
+	pub fn matches(&self, origin: &Location) -> bool {
+		let Ok(location) = self.location() else { return false };
+		match self {
+			Self::Exact(_) => &location == origin,
+			Self::Prefix(_) => &location == origin || origin.starts_with(&location),
+		}
+	}
+
+	/// How specific the pattern is; when several policies match an origin, the most specific one
+	/// applies.
+	pub(crate) fn specificity(&self) -> u32 {
+		match (self, self.location()) {
+			(Self::Exact(_), _) => u32::MAX,
+			// All prefixes matching an origin share its parents, so the longest interior wins.
+			(Self::Prefix(_), Ok(location)) => location.interior().len() as u32,
+			(Self::Prefix(_), Err(())) => 0,
+		}
+	}
+
+	/// The same pattern with its location converted to the latest version.
+	pub(crate) fn into_latest(self) -> Result<Self, ()> {
+		let location = VersionedLocation::from(self.location()?);
+		Ok(match self {
+			Self::Exact(_) => Self::Exact(location),
+			Self::Prefix(_) => Self::Prefix(location),
+		})
+	}
+}
+
+/// An XCM instruction, without its operands.
+#[derive(
+	Copy,
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Eq,
+	PartialEq,
+	Ord,
+	PartialOrd,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub enum InstructionKind {
+	WithdrawAsset,
+	ReserveAssetDeposited,
+	ReceiveTeleportedAsset,
+	QueryResponse,
+	TransferAsset,
+	TransferReserveAsset,
+	Transact,
+	HrmpNewChannelOpenRequest,
+	HrmpChannelAccepted,
+	HrmpChannelClosing,
+	ClearOrigin,
+	DescendOrigin,
+	ReportError,
+	DepositAsset,
+	DepositReserveAsset,
+	ExchangeAsset,
+	InitiateReserveWithdraw,
+	InitiateTeleport,
+	ReportHolding,
+	BuyExecution,
+	RefundSurplus,
+	SetErrorHandler,
+	SetAppendix,
+	ClearError,
+	ClaimAsset,
+	Trap,
+	SubscribeVersion,
+	UnsubscribeVersion,
+	BurnAsset,
+	ExpectAsset,
+	ExpectOrigin,
+	ExpectError,
+	ExpectTransactStatus,
+	QueryPallet,
+	ExpectPallet,
+	ReportTransactStatus,
+	ClearTransactStatus,
+	UniversalOrigin,
+	ExportMessage,
+	LockAsset,
+	UnlockAsset,
+	NoteUnlockable,
+	RequestUnlock,
+	SetFeesMode,
+	SetTopic,
+	ClearTopic,
+	AliasOrigin,
+	UnpaidExecution,
+	PayFees,
+	InitiateTransfer,
+	ExecuteWithOrigin,
+	SetHints,
+}
+
+impl InstructionKind {
+	/// The kind of `instruction`.
+	pub fn of<Call>(instruction: &Instruction<Call>) -> Self {
+		match instruction {
+			WithdrawAsset(..) => Self::WithdrawAsset,
+			ReserveAssetDeposited(..) => Self::ReserveAssetDeposited,
+			ReceiveTeleportedAsset(..) => Self::ReceiveTeleportedAsset,
+			QueryResponse { .. } => Self::QueryResponse,
+			TransferAsset { .. } => Self::TransferAsset,
+			TransferReserveAsset { .. } => Self::TransferReserveAsset,
+			Transact { .. } => Self::Transact,
+			HrmpNewChannelOpenRequest { .. } => Self::HrmpNewChannelOpenRequest,
+			HrmpChannelAccepted { .. } => Self::HrmpChannelAccepted,
+			HrmpChannelClosing { .. } => Self::HrmpChannelClosing,
+			ClearOrigin => Self::ClearOrigin,
+			DescendOrigin(..) => Self::DescendOrigin,
+			ReportError(..) => Self::ReportError,
+			DepositAsset { .. } => Self::DepositAsset,
+			DepositReserveAsset { .. } => Self::DepositReserveAsset,
+			ExchangeAsset { .. } => Self::ExchangeAsset,
+			InitiateReserveWithdraw { .. } => Self::InitiateReserveWithdraw,
+			InitiateTeleport { .. } => Self::InitiateTeleport,
+			ReportHolding { .. } => Self::ReportHolding,
+			BuyExecution { .. } => Self::BuyExecution,
+			RefundSurplus => Self::RefundSurplus,
+			SetErrorHandler(..) => Self::SetErrorHandler,
+			SetAppendix(..) => Self::SetAppendix,
+			ClearError => Self::ClearError,
+			ClaimAsset { .. } => Self::ClaimAsset,
+			Trap(..) => Self::Trap,
+			SubscribeVersion { .. } => Self::SubscribeVersion,
+			UnsubscribeVersion => Self::UnsubscribeVersion,
+			BurnAsset(..) => Self::BurnAsset,
+			ExpectAsset(..) => Self::ExpectAsset,
+			ExpectOrigin(..) => Self::ExpectOrigin,
+			ExpectError(..) => Self::ExpectError,
+			ExpectTransactStatus(..) => Self::ExpectTransactStatus,
+			QueryPallet { .. } => Self::QueryPallet,
+			ExpectPallet { .. } => Self::ExpectPallet,
+			ReportTransactStatus(..) => Self::ReportTransactStatus,
+			ClearTransactStatus => Self::ClearTransactStatus,
+			UniversalOrigin(..) => Self::UniversalOrigin,
+			ExportMessage { .. } => Self::ExportMessage,
+			LockAsset { .. } => Self::LockAsset,
+			UnlockAsset { .. } => Self::UnlockAsset,
+			NoteUnlockable { .. } => Self::NoteUnlockable,
+			RequestUnlock { .. } => Self::RequestUnlock,
+			SetFeesMode { .. } => Self::SetFeesMode,
+			SetTopic(..) => Self::SetTopic,
+			ClearTopic => Self::ClearTopic,
+			AliasOrigin(..) => Self::AliasOrigin,
+			UnpaidExecution { .. } => Self::UnpaidExecution,
+			PayFees { .. } => Self::PayFees,
+			InitiateTransfer { .. } => Self::InitiateTransfer,
+			ExecuteWithOrigin { .. } => Self::ExecuteWithOrigin,
+			SetHints { .. } => Self::SetHints,
+		}
+	}
+}
+
+/// The instructions a set of origins is allowed to execute.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Eq,
+	PartialEq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct ExecutionPolicy {
+	/// The origins the policy applies to.
+	pub origin: OriginPattern,
+	/// The instructions the origins may execute, including in nested programs.
+	pub instructions: PermittedInstructions,
+}
+
+impl ExecutionPolicy {
+	/// Returns `true` if every instruction of `instructions`, and of the programs nested within
+	/// them, is permitted.
+	pub fn permits<Call>(&self, instructions: &[Instruction<Call>]) -> bool {
+		instructions.iter().all(|instruction| {
+			self.instructions.contains(&InstructionKind::of(instruction)) &&
+				match instruction {
+					SetErrorHandler(xcm) | SetAppendix(xcm) | ExecuteWithOrigin { xcm, .. } =>
+						self.permits(&xcm.0),
+					_ => true,
+				}
+		})
+	}
+}
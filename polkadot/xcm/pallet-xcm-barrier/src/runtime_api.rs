@@ -0,0 +1,28 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Runtime API definition for the storage-backed XCM barrier.
+
+use crate::ExecutionPolicy;
+use alloc::vec::Vec;
+
+sp_api::decl_runtime_apis! {
+	/// API to inspect the execution policies enforced by the barrier.
+	pub trait XcmBarrierApi {
+		/// The current policies.
+		fn policies() -> Vec<ExecutionPolicy>;
+	}
+}
@@ -0,0 +1,225 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok, BoundedVec};
+use sp_runtime::DispatchError::BadOrigin;
+use xcm::VersionedLocation;
+use InstructionKind as K;
+
+type Call = ();
+
+fn sibling(id: u32) -> Location {
+	Location::new(1, [Parachain(id)])
+}
+
+fn kinds(kinds: &[InstructionKind]) -> PermittedInstructions {
+	BoundedVec::truncate_from(kinds.to_vec())
+}
+
+fn set_policy(origin: OriginPattern, instructions: &[InstructionKind]) {
+	assert_ok!(XcmBarrier::set_policy(
+		RuntimeOrigin::root(),
+		Box::new(origin),
+		Some(kinds(instructions)),
+	));
+}
+
+fn check(origin: Location, mut message: Vec<Instruction<Call>>) -> Result<(), ProcessMessageError> {
+	XcmBarrier::should_execute(
+		&origin,
+		&mut message,
+		Weight::from_parts(1_000, 1_000),
+		&mut Properties { weight_credit: Weight::zero(), message_id: None },
+	)
+}
+
+fn paid(instructions: Vec<Instruction<Call>>) -> Vec<Instruction<Call>> {
+	let fees: Asset = (Parent, 100u128).into();
+	[
+		vec![WithdrawAsset(fees.clone().into()), BuyExecution { fees, weight_limit: Unlimited }],
+		instructions,
+	]
+	.concat()
+}
+
+fn unpaid(instructions: Vec<Instruction<Call>>) -> Vec<Instruction<Call>> {
+	[vec![UnpaidExecution { weight_limit: Unlimited, check_origin: None }], instructions].concat()
+}
+
+fn transact() -> Instruction<Call> {
+	Transact {
+		origin_kind: OriginKind::Xcm,
+		fallback_max_weight: None,
+		call: Vec::<u8>::new().into(),
+	}
+}
+
+#[test]
+fn set_policy_inserts_replaces_and_removes() {
+	new_test_ext().execute_with(|| {
+		let origin = OriginPattern::Exact(sibling(1000).into());
+
+		assert_noop!(
+			XcmBarrier::set_policy(
+				RuntimeOrigin::signed(1),
+				Box::new(origin.clone()),
+				Some(kinds(&[K::Transact])),
+			),
+			BadOrigin,
+		);
+		assert_noop!(
+			XcmBarrier::set_policy(RuntimeOrigin::root(), Box::new(origin.clone()), None),
+			Error::<Test>::PolicyNotFound,
+		);
+
+		// Instructions are stored sorted and without duplicates.
+		set_policy(origin.clone(), &[K::Transact, K::WithdrawAsset, K::Transact]);
+		System::assert_last_event(
+			Event::PolicySet {
+				origin: origin.clone(),
+				instructions: kinds(&[K::WithdrawAsset, K::Transact]),
+			}
+			.into(),
+		);
+		assert_eq!(
+			XcmBarrier::policies(),
+			vec![ExecutionPolicy {
+				origin: origin.clone(),
+				instructions: kinds(&[K::WithdrawAsset, K::Transact])
+			}],
+		);
+
+		set_policy(origin.clone(), &[K::ClearOrigin]);
+		assert_eq!(
+			XcmBarrier::policies(),
+			vec![ExecutionPolicy {
+				origin: origin.clone(),
+				instructions: kinds(&[K::ClearOrigin])
+			}],
+		);
+
+		assert_ok!(XcmBarrier::set_policy(RuntimeOrigin::root(), Box::new(origin.clone()), None));
+		System::assert_last_event(Event::PolicyRemoved { origin }.into());
+		assert!(XcmBarrier::policies().is_empty());
+	});
+}
+
+#[test]
+fn set_policy_respects_max_policies() {
+	new_test_ext().execute_with(|| {
+		for id in 0..4 {
+			set_policy(OriginPattern::Exact(sibling(id).into()), &[K::ClearOrigin]);
+		}
+		assert_noop!(
+			XcmBarrier::set_policy(
+				RuntimeOrigin::root(),
+				Box::new(OriginPattern::Exact(sibling(4).into())),
+				Some(kinds(&[K::ClearOrigin])),
+			),
+			Error::<Test>::TooManyPolicies,
+		);
+		// Replacing an existing policy is still possible.
+		set_policy(OriginPattern::Exact(sibling(0).into()), &[K::Transact]);
+	});
+}
+
+#[test]
+fn origin_patterns_are_stored_in_latest_version() {
+	new_test_ext().execute_with(|| {
+		let v4 = xcm::v4::Location::new(1, [xcm::v4::Junction::Parachain(1000)]);
+		set_policy(OriginPattern::Prefix(VersionedLocation::V4(v4)), &[K::Transact]);
+		assert_eq!(XcmBarrier::policies()[0].origin, OriginPattern::Prefix(sibling(1000).into()));
+	});
+}
+
+#[test]
+fn barrier_requires_a_policy_permitting_all_instructions() {
+	new_test_ext().execute_with(|| {
+		let message = paid(vec![transact()]);
+		assert_eq!(check(sibling(1000), message.clone()), Err(ProcessMessageError::Unsupported));
+
+		set_policy(
+			OriginPattern::Exact(sibling(1000).into()),
+			&[K::WithdrawAsset, K::BuyExecution],
+		);
+		assert_eq!(check(sibling(1000), message.clone()), Err(ProcessMessageError::Unsupported));
+
+		set_policy(
+			OriginPattern::Exact(sibling(1000).into()),
+			&[K::WithdrawAsset, K::BuyExecution, K::Transact],
+		);
+		assert_ok!(check(sibling(1000), message.clone()));
+		// Exact patterns don't cover interior locations.
+		let account =
+			Location::new(1, [Parachain(1000), AccountId32 { network: None, id: [0; 32] }]);
+		assert_eq!(check(account, message), Err(ProcessMessageError::Unsupported));
+	});
+}
+
+#[test]
+fn barrier_checks_nested_instructions() {
+	new_test_ext().execute_with(|| {
+		set_policy(
+			OriginPattern::Exact(sibling(1000).into()),
+			&[K::WithdrawAsset, K::BuyExecution, K::SetAppendix, K::DepositAsset],
+		);
+		let deposit = DepositAsset { assets: AllCounted(1).into(), beneficiary: sibling(1000) };
+
+		assert_ok!(check(sibling(1000), paid(vec![SetAppendix(Xcm(vec![deposit]))])));
+		assert_eq!(
+			check(sibling(1000), paid(vec![SetAppendix(Xcm(vec![transact()]))])),
+			Err(ProcessMessageError::Unsupported),
+		);
+	});
+}
+
+#[test]
+fn barrier_requires_payment_unless_unpaid_execution_is_permitted() {
+	new_test_ext().execute_with(|| {
+		set_policy(
+			OriginPattern::Prefix(Parent.into()),
+			&[K::WithdrawAsset, K::BuyExecution, K::Transact],
+		);
+		assert_ok!(check(sibling(1000), paid(vec![transact()])));
+		assert_eq!(check(sibling(1000), vec![transact()]), Err(ProcessMessageError::BadFormat),);
+		assert_eq!(
+			check(sibling(1000), unpaid(vec![transact()])),
+			Err(ProcessMessageError::Unsupported),
+		);
+
+		set_policy(OriginPattern::Prefix(Parent.into()), &[K::UnpaidExecution, K::Transact]);
+		assert_ok!(check(sibling(1000), unpaid(vec![transact()])));
+	});
+}
+
+#[test]
+fn most_specific_policy_applies() {
+	new_test_ext().execute_with(|| {
+		set_policy(OriginPattern::Prefix(Parent.into()), &[K::UnpaidExecution, K::Transact]);
+		set_policy(OriginPattern::Prefix(sibling(2000).into()), &[K::UnpaidExecution]);
+		set_policy(OriginPattern::Exact(sibling(3000).into()), &[K::ClearOrigin]);
+
+		assert_ok!(check(sibling(1000), unpaid(vec![transact()])));
+		assert_eq!(
+			check(sibling(2000), unpaid(vec![transact()])),
+			Err(ProcessMessageError::Unsupported),
+		);
+		let interior = Location::new(1, [Parachain(2000), PalletInstance(50)]);
+		assert_ok!(check(interior, unpaid(vec![])));
+		assert_eq!(check(sibling(3000), unpaid(vec![])), Err(ProcessMessageError::Unsupported),);
+	});
+}
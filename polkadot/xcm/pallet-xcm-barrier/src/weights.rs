@@ -0,0 +1,71 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Weights for `pallet_xcm_barrier`.
+//!
+//! Not benchmarked yet: `set_policy` is weighed as one read and one write of `Policies`, with the
+//! proof size of its maximum encoded length and an estimated ref time of 25µs. Replace them with
+//! the output of the `set_policy` benchmark:
+//!
+//! frame-omni-bencher v1 benchmark pallet --runtime=<runtime.wasm> --pallet=pallet_xcm_barrier
+//! --extrinsic=* --steps=50 --repeat=20 --output=<weights dir> --template=substrate/.maintain/frame-weight-template.hbs
+
+#![cfg_attr(rustfmt, rustfmt_skip)]
+#![allow(unused_parens)]
+#![allow(unused_imports)]
+#![allow(missing_docs)]
+
+use crate::ExecutionPolicy;
+use codec::MaxEncodedLen;
+use frame_support::{traits::Get, weights::{Weight, constants::RocksDbWeight}, BoundedVec};
+use core::marker::PhantomData;
+
+/// The key and overhead of the storage proof of a `StorageValue`.
+const STORAGE_VALUE_PROOF_OVERHEAD: u64 = 1_489;
+
+/// The proof size of reading `Policies` bounded to `MaxPolicies` entries.
+pub fn policies_proof_size<MaxPolicies: Get<u32>>() -> u64 {
+	(BoundedVec::<ExecutionPolicy, MaxPolicies>::max_encoded_len() as u64)
+		.saturating_add(STORAGE_VALUE_PROOF_OVERHEAD)
+}
+
+/// Weight functions needed for `pallet_xcm_barrier`.
+pub trait WeightInfo {
+	fn set_policy() -> Weight;
+}
+
+/// Weights for `pallet_xcm_barrier` using the Substrate node and recommended hardware.
+pub struct SubstrateWeight<T>(PhantomData<T>);
+impl<T: crate::Config> WeightInfo for SubstrateWeight<T> {
+	/// Storage: `XcmBarrier::Policies` (r:1 w:1)
+	/// Proof: `XcmBarrier::Policies` (`max_values`: Some(1), `max_size`: `MaxPolicies` policies, mode: `MaxEncodedLen`)
+	fn set_policy() -> Weight {
+		Weight::from_parts(25_000_000, policies_proof_size::<T::MaxPolicies>())
+			.saturating_add(T::DbWeight::get().reads(1_u64))
+			.saturating_add(T::DbWeight::get().writes(1_u64))
+	}
+}
+
+// For backwards compatibility and tests.
+impl WeightInfo for () {
+	/// Storage: `XcmBarrier::Policies` (r:1 w:1)
+	/// Proof: `XcmBarrier::Policies` (`max_values`: Some(1), `max_size`: 32 policies, mode: `MaxEncodedLen`)
+	fn set_policy() -> Weight {
+		Weight::from_parts(25_000_000, policies_proof_size::<frame_support::traits::ConstU32<32>>())
+			.saturating_add(RocksDbWeight::get().reads(1_u64))
+			.saturating_add(RocksDbWeight::get().writes(1_u64))
+	}
+}
@@ -0,0 +1,145 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! The asset transactor of local and derivative collections.
+
+use crate::*;
+use frame_support::{ensure, traits::tokens::nonfungibles_v2::Transfer};
+use xcm_executor::{traits::TransactAsset, AssetsInHolding};
+
+/// The kind of collection an asset belongs to.
+enum Matched<T: Config> {
+	/// A collection whose reserve is this chain.
+	Local(T::CollectionId, T::ItemId),
+	/// A foreign collection, identified by its location.
+	Foreign(Location, T::ItemId),
+}
+
+impl<T: Config> Pallet<T> {
+	fn match_asset(what: &Asset) -> Result<Matched<T>, XcmError> {
+		let (AssetId(location), NonFungible(instance)) = (&what.id, &what.fun) else {
+			return Err(XcmError::AssetNotFound)
+		};
+		if let Some(collection) = T::LocalCollections::convert(location) {
+			let item = T::ItemInstance::convert(instance).ok_or(XcmError::AssetNotFound)?;
+			Ok(Matched::Local(collection, item))
+		} else if T::ForeignCollections::contains(location) {
+			let item = T::ItemInstance::convert(instance).ok_or(XcmError::AssetNotFound)?;
+			Ok(Matched::Foreign(location.clone(), item))
+		} else {
+			Err(XcmError::AssetNotFound)
+		}
+	}
+
+	fn account_of(location: &Location) -> Result<T::AccountId, XcmError> {
+		T::LocationToAccountId::convert_location(location)
+			.ok_or(xcm_executor::traits::Error::AccountIdConversionFailed.into())
+	}
+
+	fn transfer(
+		collection: &T::CollectionId,
+		item: &T::ItemId,
+		to: &T::AccountId,
+	) -> Result<(), XcmError> {
+		<pallet_nfts::Pallet<T> as Transfer<_>>::transfer(collection, item, to).map_err(|error| {
+			tracing::debug!(target: LOG_TARGET, ?error, ?collection, ?item, "Failed to transfer item");
+			XcmError::FailedToTransactAsset(error.into())
+		})
+	}
+}
+
+impl<T: Config> TransactAsset for Pallet<T> {
+	fn deposit_asset(what: &Asset, who: &Location, context: Option<&XcmContext>) -> XcmResult {
+		tracing::trace!(target: LOG_TARGET, ?what, ?who, ?context, "deposit_asset");
+		let who = Self::account_of(who)?;
+		match Self::match_asset(what)? {
+			// Local items are only deposited after having been withdrawn to the pallet account.
+			Matched::Local(collection, item) => {
+				let owner = pallet_nfts::Pallet::<T>::owner(collection, item);
+				if owner != Some(Self::account_id()) {
+					tracing::debug!(target: LOG_TARGET, ?collection, ?item, ?owner, "Item is not in transit");
+					return Err(XcmError::NotDepositable)
+				}
+				Self::transfer(&collection, &item, &who)
+			},
+			Matched::Foreign(collection, item) => Self::mint_derivative(&collection, item, &who)
+				.map_err(|error| {
+					tracing::debug!(target: LOG_TARGET, ?error, ?collection, ?item, "Failed to mint derivative");
+					XcmError::FailedToTransactAsset(error.into())
+				}),
+		}
+	}
+
+	fn withdraw_asset(
+		what: &Asset,
+		who: &Location,
+		maybe_context: Option<&XcmContext>,
+	) -> Result<AssetsInHolding, XcmError> {
+		tracing::trace!(target: LOG_TARGET, ?what, ?who, ?maybe_context, "withdraw_asset");
+		let who = Self::account_of(who)?;
+		match Self::match_asset(what)? {
+			// Local items are kept, along with their data, until they are deposited again.
+			Matched::Local(collection, item) => {
+				ensure!(
+					pallet_nfts::Pallet::<T>::owner(collection, item) == Some(who),
+					XcmError::NotWithdrawable
+				);
+				Self::transfer(&collection, &item, &Self::account_id())?;
+			},
+			Matched::Foreign(collection, item) => {
+				let derivative =
+					DerivativeCollections::<T>::get(collection).ok_or(XcmError::AssetNotFound)?;
+				// Weighed by `WeightInfo::burn_derivative`, which the weigher of `WithdrawAsset`
+				// must account for.
+				Self::clear_item_attributes(derivative, item)
+					.and_then(|()| {
+						<pallet_nfts::Pallet<T> as Mutate<_, _>>::burn(
+							&derivative,
+							&item,
+							Some(&who),
+						)
+					})
+					.map_err(|error| {
+						tracing::debug!(target: LOG_TARGET, ?error, ?derivative, ?item, "Failed to burn derivative");
+						XcmError::FailedToTransactAsset(error.into())
+					})?;
+			},
+		}
+		Ok(what.clone().into())
+	}
+
+	fn internal_transfer_asset(
+		what: &Asset,
+		from: &Location,
+		to: &Location,
+		context: &XcmContext,
+	) -> Result<AssetsInHolding, XcmError> {
+		tracing::trace!(target: LOG_TARGET, ?what, ?from, ?to, ?context, "internal_transfer_asset");
+		let from = Self::account_of(from)?;
+		let to = Self::account_of(to)?;
+		let (collection, item) = match Self::match_asset(what)? {
+			Matched::Local(collection, item) => (collection, item),
+			Matched::Foreign(collection, item) =>
+				(DerivativeCollections::<T>::get(collection).ok_or(XcmError::AssetNotFound)?, item),
+		};
+		ensure!(
+			pallet_nfts::Pallet::<T>::owner(collection, item) == Some(from),
+			XcmError::NotWithdrawable
+		);
+		Self::transfer(&collection, &item, &to)?;
+		Ok(what.clone().into())
+	}
+}
@@ -0,0 +1,178 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Benchmarks for the XCM NFTs pallet.
+
+#![cfg(feature = "runtime-benchmarks")]
+
+use super::*;
+use frame_benchmarking::v2::*;
+use frame_support::traits::EnsureOrigin;
+use xcm_executor::traits::TransactAsset;
+
+fn attributes<T: Config>(count: u32) -> AttributesOf<T> {
+	let value = vec![0u8; <T as pallet_nfts::Config>::ValueLimit::get() as usize];
+	let attributes = (0..count)
+		.map(|i| (BoundedVec::truncate_from(i.encode()), BoundedVec::truncate_from(value.clone())))
+		.collect::<Vec<_>>();
+	BoundedVec::truncate_from(attributes)
+}
+
+fn metadata<T: Config>() -> BoundedVec<u8, <T as pallet_nfts::Config>::StringLimit> {
+	BoundedVec::truncate_from(vec![0u8; <T as pallet_nfts::Config>::StringLimit::get() as usize])
+}
+
+/// Set `count` attributes of a local item, in the `CollectionOwner` namespace.
+fn set_attributes<T: Config>(
+	collection: T::CollectionId,
+	item: T::ItemId,
+	count: u32,
+) -> Result<(), BenchmarkError> {
+	let origin = <T as pallet_nfts::Config>::ForceOrigin::try_successful_origin()
+		.map_err(|_| BenchmarkError::Weightless)?;
+	for (key, value) in attributes::<T>(count) {
+		pallet_nfts::Pallet::<T>::force_set_attribute(
+			origin.clone(),
+			None,
+			collection,
+			Some(item),
+			AttributeNamespace::CollectionOwner,
+			key,
+			value,
+		)?;
+	}
+	Ok(())
+}
+
+/// Set `count` mirrored attributes of a derivative item.
+fn set_derivative_attributes<T: Config>(
+	collection: T::CollectionId,
+	item: T::ItemId,
+	count: u32,
+) -> Result<(), BenchmarkError> {
+	for (key, value) in attributes::<T>(count) {
+		<pallet_nfts::Pallet<T> as Mutate<_, _>>::set_attribute(&collection, &item, &key, &value)?;
+	}
+	Ok(())
+}
+
+/// A foreign collection and its reserve, the successful origin of [`Config::ReserveOrigin`].
+fn foreign_collection<T: Config>(
+) -> Result<(<T as frame_system::Config>::RuntimeOrigin, Location, Location), BenchmarkError> {
+	let collection = T::BenchmarkHelper::foreign_collection().ok_or(BenchmarkError::Skip)?;
+	let origin =
+		T::ReserveOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+	let reserve =
+		T::ReserveOrigin::try_origin(origin.clone()).map_err(|_| BenchmarkError::Weightless)?;
+	Ok((origin, reserve, collection))
+}
+
+#[benchmarks]
+mod benchmarks {
+	use super::*;
+
+	#[benchmark]
+	fn send_item_data(a: Linear<0, { T::MaxAttributes::get() }>) -> Result<(), BenchmarkError> {
+		let dest = T::BenchmarkHelper::reachable_dest().ok_or(BenchmarkError::Skip)?;
+		let origin =
+			T::SendOrigin::try_successful_origin().map_err(|_| BenchmarkError::Weightless)?;
+		let sovereign =
+			T::LocationToAccountId::convert_location(&dest).ok_or(BenchmarkError::Weightless)?;
+		let item =
+			T::ItemInstance::convert(&AssetInstance::Index(0)).ok_or(BenchmarkError::Weightless)?;
+		let collection = pallet_nfts::Pallet::<T>::force_create_collection(
+			Pallet::<T>::account_id(),
+			Pallet::<T>::derivative_config(),
+		)?;
+		<pallet_nfts::Pallet<T> as Mutate<_, _>>::mint_into(
+			&collection,
+			&item,
+			&sovereign,
+			&ItemConfig::default(),
+			true,
+		)?;
+		<pallet_nfts::Pallet<T> as Mutate<_, _>>::set_item_metadata(
+			None,
+			&collection,
+			&item,
+			&metadata::<T>(),
+		)?;
+		set_attributes::<T>(collection, item, a)?;
+
+		#[extrinsic_call]
+		_(origin as T::RuntimeOrigin, collection, item, Box::new(VersionedLocation::from(dest)));
+
+		Ok(())
+	}
+
+	#[benchmark]
+	fn mirror_item_data(a: Linear<0, { T::MaxAttributes::get() }>) -> Result<(), BenchmarkError> {
+		let (origin, _, collection) = foreign_collection::<T>()?;
+		let instance = AssetInstance::Index(0);
+		let item = T::ItemInstance::convert(&instance).ok_or(BenchmarkError::Weightless)?;
+		Pallet::<T>::mint_derivative(&collection, item, &Pallet::<T>::account_id())?;
+		let derivative =
+			DerivativeCollections::<T>::get(&collection).ok_or(BenchmarkError::Weightless)?;
+		// Worst case: as many attributes are replaced.
+		set_derivative_attributes::<T>(derivative, item, a)?;
+		let data = ItemData { metadata: metadata::<T>(), attributes: attributes::<T>(a) };
+
+		#[extrinsic_call]
+		_(
+			origin as T::RuntimeOrigin,
+			Box::new(VersionedLocation::from(collection)),
+			instance,
+			data,
+		);
+
+		assert_eq!(
+			pallet_nfts::Attribute::<T>::iter_key_prefix((
+				derivative,
+				Some(item),
+				AttributeNamespace::Pallet,
+			))
+			.count() as u32,
+			a,
+		);
+		Ok(())
+	}
+
+	// Withdrawing a derivative with `MaxAttributes` mirrored attributes.
+	#[benchmark]
+	fn burn_derivative() -> Result<(), BenchmarkError> {
+		let (_, reserve, collection) = foreign_collection::<T>()?;
+		let holder =
+			T::LocationToAccountId::convert_location(&reserve).ok_or(BenchmarkError::Weightless)?;
+		let instance = AssetInstance::Index(0);
+		let item = T::ItemInstance::convert(&instance).ok_or(BenchmarkError::Weightless)?;
+		Pallet::<T>::mint_derivative(&collection, item, &holder)?;
+		let derivative =
+			DerivativeCollections::<T>::get(&collection).ok_or(BenchmarkError::Weightless)?;
+		set_derivative_attributes::<T>(derivative, item, T::MaxAttributes::get())?;
+		let asset: Asset = (collection, instance).into();
+
+		#[block]
+		{
+			Pallet::<T>::withdraw_asset(&asset, &reserve, None)
+				.map_err(|_| BenchmarkError::Weightless)?;
+		}
+
+		assert!(pallet_nfts::Item::<T>::get(derivative, item).is_none());
+		Ok(())
+	}
+
+	impl_benchmark_test_suite!(Pallet, crate::mock::new_test_ext(), crate::mock::Test);
+}
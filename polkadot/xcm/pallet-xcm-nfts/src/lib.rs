@@ -0,0 +1,504 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Reserve transfers of `pallet-nfts` items over XCM, along with their metadata and attributes.
+//!
+//! XCM only identifies a non-fungible asset by its collection location and instance, so the data
+//! `pallet-nfts` keeps about an item doesn't travel with it. This pallet covers both ends of a
+//! reserve transfer:
+//!
+//! - On the reserve chain (e.g. Asset Hub), it is the asset transactor of the local collections.
+//!   Items leaving the chain are moved to the sovereign account of their destination rather than
+//!   burned, so their metadata and attributes stay in place for when they come back. Anyone may
+//!   then call [`Pallet::send_item_data`] to mirror the metadata and attributes of an item to the
+//!   chain holding it.
+//! - On the receiving chain, it is the asset transactor of foreign collections. A derivative
+//!   collection owned by the pallet is created on demand for each foreign collection, items are
+//!   minted on deposit and burned on withdrawal, and [`Pallet::mirror_item_data`], dispatched by
+//!   the reserve through `Transact`, writes the item data to the derivative.
+//!
+//! The execution of the item data on the receiving chain is bought with
+//! [`Config::RemoteExecutionFee`], withdrawn from the sovereign account of the reserve there. The
+//! caller of [`Pallet::send_item_data`] is charged that fee along with the delivery fees.
+//!
+//! Attributes are mirrored from the `CollectionOwner` namespace of the original item to the
+//! `Pallet` namespace of the derivative, through the `nonfungibles_v2` traits. Keys clashing with
+//! the attributes `pallet-nfts` keeps in that namespace are not mirrored.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+mod adapter;
+mod benchmarking;
+pub mod weights;
+
+#[cfg(test)]
+mod mock;
+#[cfg(test)]
+mod tests;
+
+use alloc::{boxed::Box, vec, vec::Vec};
+use codec::{Decode, DecodeAll, DecodeWithMemTracking, Encode};
+use frame_support::{
+	traits::{tokens::nonfungibles_v2::Mutate, Contains, Get},
+	BoundedVec, CloneNoBound, EqNoBound, PalletId, PartialEqNoBound, RuntimeDebugNoBound,
+};
+use pallet_nfts::{
+	AttributeNamespace, CollectionConfig, CollectionConfigFor, CollectionSetting,
+	CollectionSettings, ItemConfig, PalletAttributes,
+};
+use scale_info::TypeInfo;
+use sp_runtime::traits::{AccountIdConversion, MaybeConvert, MaybeEquivalence};
+use xcm::{latest::prelude::*, VersionedLocation};
+use xcm_executor::traits::ConvertLocation;
+
+pub use pallet::*;
+pub use weights::WeightInfo;
+
+const LOG_TARGET: &str = "xcm::pallet_xcm_nfts";
+
+/// The index of [`Pallet::mirror_item_data`] in the calls of the pallet.
+pub const MIRROR_ITEM_DATA_CALL_INDEX: u8 = 1;
+
+/// The attributes of an item: keys and values as bounded by `pallet-nfts`.
+pub type AttributesOf<T> = BoundedVec<
+	(
+		BoundedVec<u8, <T as pallet_nfts::Config>::KeyLimit>,
+		BoundedVec<u8, <T as pallet_nfts::Config>::ValueLimit>,
+	),
+	<T as Config>::MaxAttributes,
+>;
+
+/// The data of an item mirrored to the chain holding it.
+#[derive(
+	CloneNoBound,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	EqNoBound,
+	PartialEqNoBound,
+	RuntimeDebugNoBound,
+	TypeInfo,
+)]
+#[scale_info(skip_type_params(T))]
+pub struct ItemData<T: Config> {
+	/// The metadata of the item, empty if it has none.
+	pub metadata: BoundedVec<u8, <T as pallet_nfts::Config>::StringLimit>,
+	/// The attributes of the item in the `CollectionOwner` namespace.
+	///
+	/// Mirrored to the `Pallet` namespace of the derivative.
+	pub attributes: AttributesOf<T>,
+}
+
+#[frame_support::pallet]
+pub mod pallet {
+	use super::*;
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config + pallet_nfts::Config {
+		/// The overarching event type.
+		type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+		/// Converts the locations of the local collections, relative to this chain, to their IDs.
+		type LocalCollections: MaybeEquivalence<Location, Self::CollectionId>;
+
+		/// The foreign collections for which derivative collections are created.
+		type ForeignCollections: Contains<Location>;
+
+		/// Converts asset instances to item IDs.
+		type ItemInstance: MaybeEquivalence<AssetInstance, Self::ItemId>;
+
+		/// Converts locations to accounts, for beneficiaries and sovereign accounts.
+		type LocationToAccountId: ConvertLocation<Self::AccountId>;
+
+		/// The origin allowed to send item data, converted to the location charged for delivery.
+		type SendOrigin: EnsureOrigin<
+			<Self as frame_system::Config>::RuntimeOrigin,
+			Success = Location,
+		>;
+
+		/// The origin of the item data sent by reserve chains, converted to the reserve location.
+		type ReserveOrigin: EnsureOrigin<
+			<Self as frame_system::Config>::RuntimeOrigin,
+			Success = Location,
+		>;
+
+		/// The index of this pallet in the runtime of a destination, or `None` if the destination
+		/// doesn't mirror item data.
+		type RemotePalletIndex: MaybeConvert<Location, u8>;
+
+		/// The fee, as seen by this chain, buying the execution of the item data on its
+		/// destination.
+		///
+		/// Withdrawn from the sovereign account of this chain on the destination, and charged to
+		/// the sender of the item data here.
+		type RemoteExecutionFee: Get<Asset>;
+
+		/// How to send the item data.
+		type XcmSender: SendXcm;
+
+		/// Charges the delivery and execution fees of the item data.
+		type XcmExecutor: ExecuteXcm<<Self as frame_system::Config>::RuntimeCall>;
+
+		/// This chain's universal location.
+		type UniversalLocation: Get<InteriorLocation>;
+
+		/// The ID of the pallet, owning the derivative collections and holding the local items
+		/// being transferred.
+		#[pallet::constant]
+		type PalletId: Get<PalletId>;
+
+		/// The maximum number of attributes mirrored per item.
+		///
+		/// Bounds the attributes cleared when a derivative is withdrawn, see
+		/// [`WeightInfo::burn_derivative`].
+		#[pallet::constant]
+		type MaxAttributes: Get<u32>;
+
+		/// Weight information for extrinsics in this pallet.
+		type WeightInfo: WeightInfo;
+
+		/// Helper for benchmarks.
+		#[cfg(feature = "runtime-benchmarks")]
+		type BenchmarkHelper: BenchmarkHelper;
+	}
+
+	/// The derivative collections of foreign collections.
+	///
+	/// Keyed by the latest [`Location`], so that the keys don't depend on the XCM version the
+	/// collection was first seen in. The keys must be migrated if the latest version changes.
+	#[pallet::storage]
+	pub type DerivativeCollections<T: Config> =
+		StorageMap<_, Blake2_128Concat, Location, T::CollectionId, OptionQuery>;
+
+	#[pallet::event]
+	#[pallet::generate_deposit(pub(super) fn deposit_event)]
+	pub enum Event<T: Config> {
+		/// A derivative collection was created for a foreign collection.
+		DerivativeCollectionCreated { original: Location, collection: T::CollectionId },
+		/// The data of an item was sent to the chain holding it.
+		ItemDataSent {
+			collection: T::CollectionId,
+			item: T::ItemId,
+			destination: Location,
+			message_id: XcmHash,
+		},
+		/// The data of a derivative item was mirrored from its reserve.
+		ItemDataMirrored { collection: T::CollectionId, item: T::ItemId },
+	}
+
+	#[pallet::error]
+	pub enum Error<T> {
+		/// A location could not be converted to the latest version.
+		BadVersion,
+		/// The collection is not a local collection.
+		UnknownCollection,
+		/// The item or instance could not be converted.
+		UnknownItem,
+		/// The item is not held by the destination.
+		NotHeldByDestination,
+		/// The destination doesn't mirror item data.
+		UnsupportedDestination,
+		/// The item has more than `MaxAttributes` attributes.
+		TooManyAttributes,
+		/// The item data could not be sent.
+		SendFailure,
+		/// The delivery fees could not be charged.
+		FeesNotMet,
+		/// The origin is not the reserve of the collection.
+		NotReserve,
+		/// There is no derivative of the collection.
+		UnknownDerivative,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Send the metadata and attributes of `item` to `dest`, which must be holding it.
+		///
+		/// The delivery fees and [`Config::RemoteExecutionFee`] are charged to the origin.
+		#[pallet::call_index(0)]
+		#[pallet::weight(<T as Config>::WeightInfo::send_item_data(T::MaxAttributes::get()))]
+		pub fn send_item_data(
+			origin: OriginFor<T>,
+			collection: T::CollectionId,
+			item: T::ItemId,
+			dest: Box<VersionedLocation>,
+		) -> DispatchResult {
+			let origin = T::SendOrigin::ensure_origin(origin)?;
+			let dest: Location = (*dest).try_into().map_err(|()| Error::<T>::BadVersion)?;
+
+			let sovereign = T::LocationToAccountId::convert_location(&dest)
+				.ok_or(Error::<T>::UnsupportedDestination)?;
+			ensure!(
+				pallet_nfts::Pallet::<T>::owner(collection, item) == Some(sovereign),
+				Error::<T>::NotHeldByDestination
+			);
+			let pallet_index = T::RemotePalletIndex::maybe_convert(dest.clone())
+				.ok_or(Error::<T>::UnsupportedDestination)?;
+			let universal_location = T::UniversalLocation::get();
+			let collection_location = T::LocalCollections::convert_back(&collection)
+				.ok_or(Error::<T>::UnknownCollection)?
+				.reanchored(&dest, &universal_location)
+				.map_err(|_| Error::<T>::UnsupportedDestination)?;
+			let instance = T::ItemInstance::convert_back(&item).ok_or(Error::<T>::UnknownItem)?;
+
+			let remote_fees = T::RemoteExecutionFee::get()
+				.reanchored(&dest, &universal_location)
+				.map_err(|_| Error::<T>::UnsupportedDestination)?;
+			let sovereign_location = Location::here()
+				.reanchored(&dest, &universal_location)
+				.map_err(|_| Error::<T>::UnsupportedDestination)?;
+
+			let data = Self::item_data(collection, item)?;
+			let call = (
+				pallet_index,
+				MIRROR_ITEM_DATA_CALL_INDEX,
+				VersionedLocation::from(collection_location),
+				instance,
+				data,
+			)
+				.encode();
+			let message = Xcm(vec![
+				WithdrawAsset(remote_fees.clone().into()),
+				BuyExecution { fees: remote_fees, weight_limit: Unlimited },
+				Transact {
+					origin_kind: OriginKind::Xcm,
+					fallback_max_weight: None,
+					call: call.into(),
+				},
+				RefundSurplus,
+				DepositAsset { assets: Wild(AllCounted(1)), beneficiary: sovereign_location },
+			]);
+
+			let (ticket, price) =
+				validate_send::<T::XcmSender>(dest.clone(), message).map_err(|error| {
+					tracing::debug!(target: LOG_TARGET, ?error, ?dest, "Failed to validate item data");
+					Error::<T>::SendFailure
+				})?;
+			let mut fees = price;
+			fees.push(T::RemoteExecutionFee::get());
+			T::XcmExecutor::charge_fees(origin, fees).map_err(|error| {
+				tracing::debug!(target: LOG_TARGET, ?error, "Failed to charge fees");
+				Error::<T>::FeesNotMet
+			})?;
+			let message_id = T::XcmSender::deliver(ticket).map_err(|error| {
+				tracing::debug!(target: LOG_TARGET, ?error, ?dest, "Failed to deliver item data");
+				Error::<T>::SendFailure
+			})?;
+
+			Self::deposit_event(Event::ItemDataSent {
+				collection,
+				item,
+				destination: dest,
+				message_id,
+			});
+			Ok(())
+		}
+
+		/// Replace the metadata and attributes of the derivative of `item` of `collection` with
+		/// `data`.
+		///
+		/// Dispatched by the reserve of the collection, see [`Pallet::send_item_data`].
+		#[pallet::call_index(1)]
+		#[pallet::weight(<T as Config>::WeightInfo::mirror_item_data(data.attributes.len() as u32))]
+		pub fn mirror_item_data(
+			origin: OriginFor<T>,
+			collection: Box<VersionedLocation>,
+			item: AssetInstance,
+			data: ItemData<T>,
+		) -> DispatchResult {
+			let reserve = T::ReserveOrigin::ensure_origin(origin)?;
+			let collection: Location =
+				(*collection).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			ensure!(collection.starts_with(&reserve), Error::<T>::NotReserve);
+
+			let derivative =
+				DerivativeCollections::<T>::get(collection).ok_or(Error::<T>::UnknownDerivative)?;
+			let item = T::ItemInstance::convert(&item).ok_or(Error::<T>::UnknownItem)?;
+			ensure!(
+				pallet_nfts::Item::<T>::contains_key(derivative, item),
+				Error::<T>::UnknownItem
+			);
+
+			Self::clear_item_attributes(derivative, item)?;
+			if data.metadata.is_empty() {
+				if pallet_nfts::ItemMetadataOf::<T>::contains_key(derivative, item) {
+					<pallet_nfts::Pallet<T> as Mutate<_, _>>::clear_item_metadata(
+						None,
+						&derivative,
+						&item,
+					)?;
+				}
+			} else {
+				<pallet_nfts::Pallet<T> as Mutate<_, _>>::set_item_metadata(
+					None,
+					&derivative,
+					&item,
+					&data.metadata,
+				)?;
+			}
+			for (key, value) in data.attributes {
+				if Self::is_system_attribute(&key) {
+					continue
+				}
+				<pallet_nfts::Pallet<T> as Mutate<_, _>>::set_attribute(
+					&derivative,
+					&item,
+					&key,
+					&value,
+				)?;
+			}
+
+			Self::deposit_event(Event::ItemDataMirrored { collection: derivative, item });
+			Ok(())
+		}
+	}
+}
+
+/// Helper for benchmarks.
+#[cfg(feature = "runtime-benchmarks")]
+pub trait BenchmarkHelper {
+	/// A destination item data can be sent to, or `None` to skip the benchmark of
+	/// [`Pallet::send_item_data`].
+	///
+	/// Delivery to it must succeed, with fees payable by the successful origin of
+	/// [`Config::SendOrigin`].
+	fn reachable_dest() -> Option<Location>;
+
+	/// A foreign collection whose reserve is the successful origin of [`Config::ReserveOrigin`],
+	/// or `None` to skip the benchmarks of derivatives.
+	///
+	/// The reserve location must be convertible to an account.
+	fn foreign_collection() -> Option<Location>;
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper for () {
+	fn reachable_dest() -> Option<Location> {
+		None
+	}
+
+	fn foreign_collection() -> Option<Location> {
+		None
+	}
+}
+
+impl<T: Config> Pallet<T> {
+	/// The account owning the derivative collections and holding the local items in transit.
+	pub fn account_id() -> T::AccountId {
+		T::PalletId::get().into_account_truncating()
+	}
+
+	/// The data of a local item to mirror.
+	pub fn item_data(
+		collection: T::CollectionId,
+		item: T::ItemId,
+	) -> Result<ItemData<T>, Error<T>> {
+		let metadata = pallet_nfts::ItemMetadataOf::<T>::get(collection, item)
+			.map(|metadata| metadata.data)
+			.unwrap_or_default();
+		let attributes = pallet_nfts::Attribute::<T>::iter_prefix((
+			collection,
+			Some(item),
+			AttributeNamespace::CollectionOwner,
+		))
+		.map(|(key, (value, _))| (key, value))
+		.take(T::MaxAttributes::get() as usize + 1)
+		.collect::<Vec<_>>()
+		.try_into()
+		.map_err(|_| Error::<T>::TooManyAttributes)?;
+		Ok(ItemData { metadata, attributes })
+	}
+
+	/// The derivative of the foreign `collection`, created if it doesn't exist yet.
+	pub(crate) fn derivative_collection(
+		collection: &Location,
+	) -> Result<T::CollectionId, sp_runtime::DispatchError> {
+		if let Some(derivative) = DerivativeCollections::<T>::get(collection) {
+			return Ok(derivative)
+		}
+
+		let derivative = pallet_nfts::Pallet::<T>::force_create_collection(
+			Self::account_id(),
+			Self::derivative_config(),
+		)?;
+		DerivativeCollections::<T>::insert(collection, derivative);
+		Self::deposit_event(Event::DerivativeCollectionCreated {
+			original: collection.clone(),
+			collection: derivative,
+		});
+		Ok(derivative)
+	}
+
+	/// The configuration of derivative collections.
+	///
+	/// Derivatives are only minted and burned through XCM, no deposits are needed.
+	pub(crate) fn derivative_config() -> CollectionConfigFor<T> {
+		CollectionConfig {
+			settings: CollectionSettings::from_disabled(CollectionSetting::DepositRequired.into()),
+			max_supply: None,
+			mint_settings: Default::default(),
+		}
+	}
+
+	/// Whether `key` is one of the attributes `pallet-nfts` keeps in the `Pallet` namespace.
+	fn is_system_attribute(key: &[u8]) -> bool {
+		PalletAttributes::<T::CollectionId>::decode_all(&mut &key[..]).is_ok()
+	}
+
+	/// Remove the mirrored attributes of a derivative item.
+	///
+	/// At most `MaxAttributes` attributes are mirrored, and so cleared.
+	pub(crate) fn clear_item_attributes(
+		collection: T::CollectionId,
+		item: T::ItemId,
+	) -> sp_runtime::DispatchResult {
+		let keys = pallet_nfts::Attribute::<T>::iter_key_prefix((
+			collection,
+			Some(item),
+			AttributeNamespace::Pallet,
+		))
+		.filter(|key| !Self::is_system_attribute(key))
+		.take(T::MaxAttributes::get() as usize)
+		.collect::<Vec<_>>();
+		for key in keys {
+			<pallet_nfts::Pallet<T> as Mutate<_, _>>::clear_attribute(&collection, &item, &key)?;
+		}
+		Ok(())
+	}
+
+	/// Mint `item` of the derivative of `collection` to `who`.
+	pub(crate) fn mint_derivative(
+		collection: &Location,
+		item: T::ItemId,
+		who: &T::AccountId,
+	) -> sp_runtime::DispatchResult {
+		let derivative = Self::derivative_collection(collection)?;
+		<pallet_nfts::Pallet<T> as Mutate<_, _>>::mint_into(
+			&derivative,
+			&item,
+			who,
+			&ItemConfig::default(),
+			true,
+		)
+	}
+}
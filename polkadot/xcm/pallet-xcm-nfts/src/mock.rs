@@ -0,0 +1,344 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Test environment for the XCM NFTs pallet.
+//!
+//! The test runtime plays both ends of a transfer: its own collections are local, and the
+//! collections of sibling parachains are foreign.
+
+use crate as pallet_xcm_nfts;
+use crate::*;
+use core::cell::RefCell;
+use frame_support::{
+	construct_runtime, derive_impl, parameter_types,
+	traits::{AsEnsureOriginWithArg, ConstU32, ConstU64, EnsureOrigin, PalletInfoAccess},
+};
+use frame_system::{EnsureSigned, RawOrigin};
+use pallet_nfts::PalletFeatures;
+use sp_runtime::{
+	traits::{IdentifyAccount, IdentityLookup, Verify},
+	BuildStorage, MultiSignature,
+};
+
+type Block = frame_system::mocking::MockBlock<Test>;
+type Signature = MultiSignature;
+type AccountPublic = <Signature as Verify>::Signer;
+pub(crate) type AccountId = <AccountPublic as IdentifyAccount>::AccountId;
+
+pub(crate) const ALICE: AccountId = sp_runtime::AccountId32::new([1u8; 32]);
+pub(crate) const BOB: AccountId = sp_runtime::AccountId32::new([2u8; 32]);
+
+/// The sibling parachain mirroring item data.
+pub(crate) const SIBLING: u32 = 2000;
+/// The parachain ID of the test runtime.
+pub(crate) const PARA_ID: u32 = 1000;
+
+construct_runtime!(
+	pub enum Test
+	{
+		System: frame_system,
+		Balances: pallet_balances,
+		Nfts: pallet_nfts,
+		XcmNfts: pallet_xcm_nfts,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type AccountId = AccountId;
+	type Lookup = IdentityLookup<Self::AccountId>;
+	type Block = Block;
+	type AccountData = pallet_balances::AccountData<u64>;
+}
+
+#[derive_impl(pallet_balances::config_preludes::TestDefaultConfig)]
+impl pallet_balances::Config for Test {
+	type AccountStore = System;
+}
+
+parameter_types! {
+	pub storage Features: PalletFeatures = PalletFeatures::all_enabled();
+}
+
+impl pallet_nfts::Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type CollectionId = u32;
+	type ItemId = u32;
+	type Currency = Balances;
+	type CreateOrigin = AsEnsureOriginWithArg<EnsureSigned<Self::AccountId>>;
+	type ForceOrigin = frame_system::EnsureRoot<Self::AccountId>;
+	type Locker = ();
+	type CollectionDeposit = ConstU64<2>;
+	type ItemDeposit = ConstU64<1>;
+	type MetadataDepositBase = ConstU64<1>;
+	type AttributeDepositBase = ConstU64<1>;
+	type DepositPerByte = ConstU64<1>;
+	type StringLimit = ConstU32<50>;
+	type KeyLimit = ConstU32<50>;
+	type ValueLimit = ConstU32<50>;
+	type ApprovalsLimit = ConstU32<10>;
+	type ItemAttributesApprovalsLimit = ConstU32<2>;
+	type MaxTips = ConstU32<10>;
+	type MaxDeadlineDuration = ConstU64<10000>;
+	type MaxAttributesPerCall = ConstU32<2>;
+	type Features = Features;
+	type OffchainSignature = Signature;
+	type OffchainPublic = AccountPublic;
+	type WeightInfo = ();
+	type BlockNumberProvider = frame_system::Pallet<Test>;
+	pallet_nfts::runtime_benchmarks_enabled! {
+		type Helper = ();
+	}
+}
+
+/// The location of a collection of the `pallet-nfts` instance of the test runtime, relative to it.
+pub(crate) fn local_collection(collection: u32) -> Location {
+	Location::new(0, [PalletInstance(Nfts::index() as u8), GeneralIndex(collection.into())])
+}
+
+/// Converts `(0, [PalletInstance(nfts), GeneralIndex(collection)])` to collection IDs.
+pub struct LocalCollections;
+impl MaybeEquivalence<Location, u32> for LocalCollections {
+	fn convert(location: &Location) -> Option<u32> {
+		match location.unpack() {
+			(0, [PalletInstance(pallet), GeneralIndex(collection)])
+				if *pallet as usize == Nfts::index() =>
+				(*collection).try_into().ok(),
+			_ => None,
+		}
+	}
+
+	fn convert_back(collection: &u32) -> Option<Location> {
+		Some(local_collection(*collection))
+	}
+}
+
+/// Collections of `pallet-nfts` instances of sibling parachains.
+pub struct SiblingCollections;
+impl Contains<Location> for SiblingCollections {
+	fn contains(location: &Location) -> bool {
+		matches!(location.unpack(), (1, [Parachain(_), PalletInstance(_), GeneralIndex(_)]))
+	}
+}
+
+/// Converts `Index` instances to item IDs.
+pub struct IndexInstance;
+impl MaybeEquivalence<AssetInstance, u32> for IndexInstance {
+	fn convert(instance: &AssetInstance) -> Option<u32> {
+		match instance {
+			AssetInstance::Index(index) => (*index).try_into().ok(),
+			_ => None,
+		}
+	}
+
+	fn convert_back(item: &u32) -> Option<AssetInstance> {
+		Some(AssetInstance::Index((*item).into()))
+	}
+}
+
+/// The sovereign account of a sibling parachain.
+pub(crate) fn sibling_account(para: u32) -> AccountId {
+	let mut id = [0u8; 32];
+	id[..4].copy_from_slice(b"sibl");
+	id[4..8].copy_from_slice(&para.to_le_bytes());
+	id.into()
+}
+
+fn sibling_of(who: &AccountId) -> Option<u32> {
+	let id: &[u8; 32] = who.as_ref();
+	(id[..4] == *b"sibl" && id[8..] == [0u8; 24])
+		.then(|| u32::from_le_bytes(id[4..8].try_into().expect("4 bytes; qed")))
+}
+
+/// Converts local accounts and sibling parachains to accounts.
+pub struct LocationToAccountId;
+impl ConvertLocation<AccountId> for LocationToAccountId {
+	fn convert_location(location: &Location) -> Option<AccountId> {
+		match location.unpack() {
+			(0, [AccountId32 { id, .. }]) => Some((*id).into()),
+			(1, [Parachain(para)]) => Some(sibling_account(*para)),
+			_ => None,
+		}
+	}
+}
+
+/// Converts signed origins to the location of their account.
+pub struct SignedToLocation;
+impl EnsureOrigin<RuntimeOrigin> for SignedToLocation {
+	type Success = Location;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<Location, RuntimeOrigin> {
+		match o.clone().into() {
+			Ok(RawOrigin::Signed(who)) => Ok(AccountId32 { network: None, id: who.into() }.into()),
+			_ => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(RuntimeOrigin::signed(ALICE))
+	}
+}
+
+/// Converts origins signed by the sovereign account of a sibling parachain to its location, in
+/// place of the XCM origin converters of a real runtime.
+pub struct SiblingOrigin;
+impl EnsureOrigin<RuntimeOrigin> for SiblingOrigin {
+	type Success = Location;
+
+	fn try_origin(o: RuntimeOrigin) -> Result<Location, RuntimeOrigin> {
+		match o.clone().into() {
+			Ok(RawOrigin::Signed(who)) => match sibling_of(&who) {
+				Some(para) => Ok(Location::new(1, [Parachain(para)])),
+				None => Err(o),
+			},
+			_ => Err(o),
+		}
+	}
+
+	#[cfg(feature = "runtime-benchmarks")]
+	fn try_successful_origin() -> Result<RuntimeOrigin, ()> {
+		Ok(RuntimeOrigin::signed(sibling_account(SIBLING)))
+	}
+}
+
+/// The test runtime itself and [`SIBLING`] mirror item data.
+pub struct RemotePalletIndex;
+impl MaybeConvert<Location, u8> for RemotePalletIndex {
+	fn maybe_convert(location: Location) -> Option<u8> {
+		match location.unpack() {
+			(1, [Parachain(SIBLING)]) | (1, [Parachain(PARA_ID)]) => Some(XcmNfts::index() as u8),
+			_ => None,
+		}
+	}
+}
+
+thread_local! {
+	pub static SENT_XCM: RefCell<Vec<(Location, Xcm<()>)>> = RefCell::new(Vec::new());
+}
+
+/// The messages sent so far.
+pub(crate) fn sent_xcm() -> Vec<(Location, Xcm<()>)> {
+	SENT_XCM.with(|q| q.borrow().clone())
+}
+
+/// Records the messages sent, for free.
+pub struct TestSendXcm;
+impl SendXcm for TestSendXcm {
+	type Ticket = (Location, Xcm<()>);
+
+	fn validate(
+		dest: &mut Option<Location>,
+		msg: &mut Option<Xcm<()>>,
+	) -> SendResult<(Location, Xcm<()>)> {
+		let pair = (dest.take().unwrap(), msg.take().unwrap());
+		Ok((pair, Assets::new()))
+	}
+
+	fn deliver(pair: (Location, Xcm<()>)) -> Result<XcmHash, SendError> {
+		let hash = pair.1.using_encoded(sp_io::hashing::blake2_256);
+		SENT_XCM.with(|q| q.borrow_mut().push(pair));
+		Ok(hash)
+	}
+}
+
+thread_local! {
+	pub static CHARGED_FEES: RefCell<Vec<(Location, Assets)>> = RefCell::new(Vec::new());
+}
+
+/// The fees charged so far.
+pub(crate) fn charged_fees() -> Vec<(Location, Assets)> {
+	CHARGED_FEES.with(|q| q.borrow().clone())
+}
+
+/// Never executes messages, only records the fees charged.
+pub struct TestExecutor;
+pub struct TestPrepared;
+impl PreparedMessage for TestPrepared {
+	fn weight_of(&self) -> Weight {
+		Weight::zero()
+	}
+}
+impl ExecuteXcm<RuntimeCall> for TestExecutor {
+	type Prepared = TestPrepared;
+
+	fn prepare(message: Xcm<RuntimeCall>) -> Result<TestPrepared, Xcm<RuntimeCall>> {
+		Err(message)
+	}
+
+	fn execute(_: impl Into<Location>, _: TestPrepared, _: &mut XcmHash, _: Weight) -> Outcome {
+		unreachable!("messages are never prepared")
+	}
+
+	fn charge_fees(location: impl Into<Location>, fees: Assets) -> XcmResult {
+		CHARGED_FEES.with(|q| q.borrow_mut().push((location.into(), fees)));
+		Ok(())
+	}
+}
+
+parameter_types! {
+	pub RemoteExecutionFee: Asset = (Parent, 1_000u128).into();
+	pub UniversalLocation: InteriorLocation =
+		[GlobalConsensus(NetworkId::Polkadot), Parachain(PARA_ID)].into();
+	pub const XcmNftsPalletId: PalletId = PalletId(*b"py/xcnft");
+}
+
+#[cfg(feature = "runtime-benchmarks")]
+pub struct XcmNftsBenchmarkHelper;
+#[cfg(feature = "runtime-benchmarks")]
+impl BenchmarkHelper for XcmNftsBenchmarkHelper {
+	fn reachable_dest() -> Option<Location> {
+		Some(Location::new(1, [Parachain(SIBLING)]))
+	}
+
+	fn foreign_collection() -> Option<Location> {
+		Some(Location::new(1, [Parachain(SIBLING), PalletInstance(50), GeneralIndex(0)]))
+	}
+}
+
+impl Config for Test {
+	type RuntimeEvent = RuntimeEvent;
+	type LocalCollections = LocalCollections;
+	type ForeignCollections = SiblingCollections;
+	type ItemInstance = IndexInstance;
+	type LocationToAccountId = LocationToAccountId;
+	type SendOrigin = SignedToLocation;
+	type ReserveOrigin = SiblingOrigin;
+	type RemotePalletIndex = RemotePalletIndex;
+	type RemoteExecutionFee = RemoteExecutionFee;
+	type XcmSender = TestSendXcm;
+	type XcmExecutor = TestExecutor;
+	type UniversalLocation = UniversalLocation;
+	type PalletId = XcmNftsPalletId;
+	type MaxAttributes = ConstU32<4>;
+	type WeightInfo = ();
+	#[cfg(feature = "runtime-benchmarks")]
+	type BenchmarkHelper = XcmNftsBenchmarkHelper;
+}
+
+pub(crate) fn new_test_ext() -> sp_io::TestExternalities {
+	let mut t = frame_system::GenesisConfig::<Test>::default().build_storage().unwrap();
+	pallet_balances::GenesisConfig::<Test> {
+		balances: vec![(ALICE, 1_000), (BOB, 1_000)],
+		..Default::default()
+	}
+	.assimilate_storage(&mut t)
+	.unwrap();
+
+	let mut ext = sp_io::TestExternalities::new(t);
+	ext.execute_with(|| System::set_block_number(1));
+	ext
+}
@@ -0,0 +1,411 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the XCM NFTs pallet.
+
+use crate::{mock::*, *};
+use frame_support::{assert_noop, assert_ok};
+use sp_runtime::{traits::Dispatchable, DispatchError};
+use xcm_executor::traits::TransactAsset;
+
+const COLLECTION: u32 = 0;
+const ITEM: u32 = 0;
+
+fn sibling() -> Location {
+	Location::new(1, [Parachain(SIBLING)])
+}
+
+fn account(who: &AccountId) -> Location {
+	AccountId32 { network: None, id: who.clone().into() }.into()
+}
+
+fn local_asset(item: u32) -> Asset {
+	(local_collection(COLLECTION), AssetInstance::Index(item.into())).into()
+}
+
+fn sibling_collection() -> Location {
+	Location::new(1, [Parachain(SIBLING), PalletInstance(50), GeneralIndex(7)])
+}
+
+fn bounded<S: Get<u32>>(data: &[u8]) -> BoundedVec<u8, S> {
+	data.to_vec().try_into().unwrap()
+}
+
+fn attributes(pairs: &[(&[u8], &[u8])]) -> AttributesOf<Test> {
+	pairs
+		.iter()
+		.map(|(key, value)| (bounded(key), bounded(value)))
+		.collect::<Vec<_>>()
+		.try_into()
+		.unwrap()
+}
+
+fn attributes_of(
+	collection: u32,
+	item: u32,
+	namespace: AttributeNamespace<AccountId>,
+) -> Vec<(Vec<u8>, Vec<u8>)> {
+	let mut attributes =
+		pallet_nfts::Attribute::<Test>::iter_prefix((collection, Some(item), namespace))
+			.map(|(key, (value, _))| (key.into_inner(), value.into_inner()))
+			.collect::<Vec<_>>();
+	attributes.sort();
+	attributes
+}
+
+fn metadata_of(collection: u32, item: u32) -> Option<Vec<u8>> {
+	pallet_nfts::ItemMetadataOf::<Test>::get(collection, item).map(|m| m.data.into_inner())
+}
+
+/// Create a local item owned by `ALICE`, with metadata and `attributes` attributes.
+fn create_local_item(attributes: u8) {
+	assert_ok!(Nfts::force_create(
+		RuntimeOrigin::root(),
+		ALICE,
+		pallet_nfts::CollectionConfig {
+			settings: pallet_nfts::CollectionSettings::all_enabled(),
+			max_supply: None,
+			mint_settings: Default::default(),
+		},
+	));
+	assert_ok!(Nfts::force_mint(
+		RuntimeOrigin::root(),
+		COLLECTION,
+		ITEM,
+		ALICE,
+		Default::default(),
+	));
+	assert_ok!(Nfts::set_metadata(RuntimeOrigin::root(), COLLECTION, ITEM, bounded(b"meta")));
+	for i in 0..attributes {
+		assert_ok!(Nfts::force_set_attribute(
+			RuntimeOrigin::root(),
+			None,
+			COLLECTION,
+			Some(ITEM),
+			AttributeNamespace::CollectionOwner,
+			bounded(&[i]),
+			bounded(b"value"),
+		));
+	}
+}
+
+/// Move the local item to the sovereign account of `dest`, as a reserve transfer would.
+fn transfer_to(dest: &Location) {
+	assert_ok!(XcmNfts::withdraw_asset(&local_asset(ITEM), &account(&ALICE), None));
+	assert_ok!(XcmNfts::deposit_asset(&local_asset(ITEM), dest, None));
+}
+
+fn mirror(data: ItemData<Test>) -> sp_runtime::DispatchResult {
+	XcmNfts::mirror_item_data(
+		RuntimeOrigin::signed(sibling_account(SIBLING)),
+		Box::new(sibling_collection().into()),
+		AssetInstance::Index(ITEM.into()),
+		data,
+	)
+}
+
+#[test]
+fn local_items_keep_their_data_in_transit() {
+	new_test_ext().execute_with(|| {
+		create_local_item(2);
+
+		assert_ok!(XcmNfts::withdraw_asset(&local_asset(ITEM), &account(&ALICE), None));
+		assert_eq!(Nfts::owner(COLLECTION, ITEM), Some(XcmNfts::account_id()));
+		assert_ok!(XcmNfts::deposit_asset(&local_asset(ITEM), &sibling(), None));
+		assert_eq!(Nfts::owner(COLLECTION, ITEM), Some(sibling_account(SIBLING)));
+
+		// Coming back.
+		assert_ok!(XcmNfts::withdraw_asset(&local_asset(ITEM), &sibling(), None));
+		assert_ok!(XcmNfts::deposit_asset(&local_asset(ITEM), &account(&BOB), None));
+		assert_eq!(Nfts::owner(COLLECTION, ITEM), Some(BOB));
+		assert_eq!(metadata_of(COLLECTION, ITEM), Some(b"meta".to_vec()));
+		assert_eq!(
+			attributes_of(COLLECTION, ITEM, AttributeNamespace::CollectionOwner),
+			vec![(vec![0], b"value".to_vec()), (vec![1], b"value".to_vec())]
+		);
+	});
+}
+
+#[test]
+fn local_items_must_be_withdrawn_from_their_owner() {
+	new_test_ext().execute_with(|| {
+		create_local_item(0);
+
+		assert_eq!(
+			XcmNfts::withdraw_asset(&local_asset(ITEM), &account(&BOB), None).err(),
+			Some(XcmError::NotWithdrawable)
+		);
+		// Not in transit.
+		assert_eq!(
+			XcmNfts::deposit_asset(&local_asset(ITEM), &account(&BOB), None),
+			Err(XcmError::NotDepositable)
+		);
+		// Unknown assets are left to other transactors.
+		let fungible: Asset = (Here, 100u128).into();
+		assert_eq!(
+			XcmNfts::deposit_asset(&fungible, &account(&BOB), None),
+			Err(XcmError::AssetNotFound)
+		);
+	});
+}
+
+#[test]
+fn foreign_deposits_mint_derivatives() {
+	new_test_ext().execute_with(|| {
+		let asset = |item: u128| -> Asset { (sibling_collection(), Index(item)).into() };
+
+		assert_ok!(XcmNfts::deposit_asset(&asset(0), &account(&ALICE), None));
+		let derivative = DerivativeCollections::<Test>::get(sibling_collection()).unwrap();
+		System::assert_has_event(
+			Event::DerivativeCollectionCreated {
+				original: sibling_collection(),
+				collection: derivative,
+			}
+			.into(),
+		);
+		assert_eq!(Nfts::collection_owner(derivative), Some(XcmNfts::account_id()));
+		assert_eq!(Nfts::owner(derivative, 0), Some(ALICE));
+
+		// The derivative collection is reused.
+		assert_ok!(XcmNfts::deposit_asset(&asset(1), &account(&BOB), None));
+		assert_eq!(Nfts::owner(derivative, 1), Some(BOB));
+		assert_eq!(DerivativeCollections::<Test>::iter().count(), 1);
+
+		// Moving derivatives around.
+		assert_ok!(XcmNfts::internal_transfer_asset(
+			&asset(1),
+			&account(&BOB),
+			&account(&ALICE),
+			&XcmContext::with_message_id([0; 32]),
+		));
+		assert_eq!(Nfts::owner(derivative, 1), Some(ALICE));
+	});
+}
+
+#[test]
+fn mirror_item_data_replaces_data() {
+	new_test_ext().execute_with(|| {
+		let asset: Asset = (sibling_collection(), Index(ITEM.into())).into();
+		assert_ok!(XcmNfts::deposit_asset(&asset, &account(&ALICE), None));
+		let derivative = DerivativeCollections::<Test>::get(sibling_collection()).unwrap();
+
+		assert_ok!(mirror(ItemData {
+			metadata: bounded(b"meta"),
+			attributes: attributes(&[(b"a", b"1"), (b"b", b"2")]),
+		}));
+		System::assert_last_event(
+			Event::ItemDataMirrored { collection: derivative, item: ITEM }.into(),
+		);
+		assert_eq!(metadata_of(derivative, ITEM), Some(b"meta".to_vec()));
+		assert_eq!(
+			attributes_of(derivative, ITEM, AttributeNamespace::Pallet),
+			vec![(b"a".to_vec(), b"1".to_vec()), (b"b".to_vec(), b"2".to_vec())]
+		);
+
+		assert_ok!(mirror(ItemData {
+			metadata: bounded(b""),
+			attributes: attributes(&[(b"c", b"3")]),
+		}));
+		assert_eq!(metadata_of(derivative, ITEM), None);
+		assert_eq!(
+			attributes_of(derivative, ITEM, AttributeNamespace::Pallet),
+			vec![(b"c".to_vec(), b"3".to_vec())]
+		);
+
+		// Withdrawing burns the derivative, along with its data.
+		assert_ok!(XcmNfts::withdraw_asset(&asset, &account(&ALICE), None));
+		assert_eq!(Nfts::owner(derivative, ITEM), None);
+		assert!(attributes_of(derivative, ITEM, AttributeNamespace::Pallet).is_empty());
+	});
+}
+
+#[test]
+fn mirror_item_data_skips_system_attributes() {
+	new_test_ext().execute_with(|| {
+		let asset: Asset = (sibling_collection(), Index(ITEM.into())).into();
+		assert_ok!(XcmNfts::deposit_asset(&asset, &account(&ALICE), None));
+		let derivative = DerivativeCollections::<Test>::get(sibling_collection()).unwrap();
+
+		// The key of `TransferDisabled` would lock the derivative.
+		let transfer_disabled = pallet_nfts::PalletAttributes::<u32>::TransferDisabled.encode();
+		assert_ok!(mirror(ItemData {
+			metadata: bounded(b"meta"),
+			attributes: attributes(&[(&transfer_disabled, b""), (b"a", b"1")]),
+		}));
+		assert_eq!(
+			attributes_of(derivative, ITEM, AttributeNamespace::Pallet),
+			vec![(b"a".to_vec(), b"1".to_vec())]
+		);
+
+		assert_ok!(XcmNfts::withdraw_asset(&asset, &account(&ALICE), None));
+		assert_eq!(Nfts::owner(derivative, ITEM), None);
+		assert!(attributes_of(derivative, ITEM, AttributeNamespace::Pallet).is_empty());
+	});
+}
+
+#[test]
+fn derivatives_are_found_from_older_xcm_versions() {
+	new_test_ext().execute_with(|| {
+		let asset: Asset = (sibling_collection(), Index(ITEM.into())).into();
+		assert_ok!(XcmNfts::deposit_asset(&asset, &account(&ALICE), None));
+		let derivative = DerivativeCollections::<Test>::get(sibling_collection()).unwrap();
+
+		// The reserve sends the collection in an older XCM version.
+		let collection = xcm::v4::Location::try_from(sibling_collection()).unwrap();
+		assert_ok!(XcmNfts::mirror_item_data(
+			RuntimeOrigin::signed(sibling_account(SIBLING)),
+			Box::new(VersionedLocation::V4(collection)),
+			AssetInstance::Index(ITEM.into()),
+			ItemData { metadata: bounded(b"meta"), attributes: Default::default() },
+		));
+		assert_eq!(metadata_of(derivative, ITEM), Some(b"meta".to_vec()));
+		assert_eq!(DerivativeCollections::<Test>::iter().count(), 1);
+	});
+}
+
+#[test]
+fn mirror_item_data_checks_the_reserve() {
+	new_test_ext().execute_with(|| {
+		let data = ItemData { metadata: bounded(b"meta"), attributes: Default::default() };
+		let collection: Box<VersionedLocation> = Box::new(sibling_collection().into());
+		let instance = AssetInstance::Index(ITEM.into());
+
+		assert_noop!(
+			XcmNfts::mirror_item_data(
+				RuntimeOrigin::signed(ALICE),
+				collection.clone(),
+				instance,
+				data.clone(),
+			),
+			DispatchError::BadOrigin
+		);
+		assert_noop!(
+			XcmNfts::mirror_item_data(
+				RuntimeOrigin::signed(sibling_account(3000)),
+				collection.clone(),
+				instance,
+				data.clone(),
+			),
+			Error::<Test>::NotReserve
+		);
+		assert_noop!(mirror(data.clone()), Error::<Test>::UnknownDerivative);
+
+		let asset: Asset = (sibling_collection(), Index(1)).into();
+		assert_ok!(XcmNfts::deposit_asset(&asset, &account(&ALICE), None));
+		assert_noop!(mirror(data), Error::<Test>::UnknownItem);
+	});
+}
+
+#[test]
+fn send_item_data_checks_the_destination() {
+	new_test_ext().execute_with(|| {
+		create_local_item(0);
+		let send = |dest: Location| {
+			XcmNfts::send_item_data(
+				RuntimeOrigin::signed(ALICE),
+				COLLECTION,
+				ITEM,
+				Box::new(dest.into()),
+			)
+		};
+
+		assert_noop!(send(sibling()), Error::<Test>::NotHeldByDestination);
+
+		let unsupported = Location::new(1, [Parachain(3000)]);
+		transfer_to(&unsupported);
+		assert_noop!(send(unsupported), Error::<Test>::UnsupportedDestination);
+		assert!(sent_xcm().is_empty());
+		assert!(charged_fees().is_empty());
+	});
+}
+
+#[test]
+fn send_item_data_bounds_attributes() {
+	new_test_ext().execute_with(|| {
+		create_local_item(5);
+		transfer_to(&sibling());
+
+		assert_noop!(
+			XcmNfts::send_item_data(
+				RuntimeOrigin::signed(ALICE),
+				COLLECTION,
+				ITEM,
+				Box::new(sibling().into()),
+			),
+			Error::<Test>::TooManyAttributes
+		);
+	});
+}
+
+#[test]
+fn item_data_is_mirrored_to_the_holder() {
+	new_test_ext().execute_with(|| {
+		create_local_item(2);
+		transfer_to(&sibling());
+
+		assert_ok!(XcmNfts::send_item_data(
+			RuntimeOrigin::signed(BOB),
+			COLLECTION,
+			ITEM,
+			Box::new(sibling().into()),
+		));
+		let sent = sent_xcm();
+		assert_eq!(sent.len(), 1);
+		let (dest, message) = &sent[0];
+		assert_eq!(dest, &sibling());
+		System::assert_last_event(
+			Event::ItemDataSent {
+				collection: COLLECTION,
+				item: ITEM,
+				destination: sibling(),
+				message_id: message.using_encoded(sp_io::hashing::blake2_256),
+			}
+			.into(),
+		);
+		// The execution is paid by the sovereign account of the reserve, and the fee charged to
+		// the sender.
+		let remote_fees: Asset = (Location::new(1, Here), 1_000u128).into();
+		assert_eq!(charged_fees(), vec![(account(&BOB), remote_fees.clone().into())]);
+		let [WithdrawAsset(withdrawn), BuyExecution { fees, .. }, Transact { origin_kind: OriginKind::Xcm, call, .. }, RefundSurplus, DepositAsset { beneficiary, .. }] =
+			message.0.as_slice()
+		else {
+			panic!("unexpected message: {message:?}")
+		};
+		assert_eq!(withdrawn, &remote_fees.clone().into());
+		assert_eq!(fees, &remote_fees);
+		assert_eq!(beneficiary, &Location::new(1, [Parachain(PARA_ID)]));
+		let call = RuntimeCall::decode(&mut &call.clone().into_encoded()[..]).unwrap();
+
+		// The test runtime is the sibling too: receive the item and dispatch the call as the
+		// reserve.
+		let reserve_collection = local_collection(COLLECTION)
+			.reanchored(&sibling(), &UniversalLocation::get())
+			.unwrap();
+		assert_eq!(reserve_collection.parents, 1);
+		let asset: Asset = (reserve_collection.clone(), Index(ITEM.into())).into();
+		assert_ok!(XcmNfts::deposit_asset(&asset, &account(&ALICE), None));
+		assert_ok!(call.dispatch(RuntimeOrigin::signed(sibling_account(PARA_ID))));
+
+		let derivative =
+			DerivativeCollections::<Test>::get(reserve_collection)
+				.unwrap();
+		assert_eq!(metadata_of(derivative, ITEM), Some(b"meta".to_vec()));
+		assert_eq!(
+			attributes_of(derivative, ITEM, AttributeNamespace::Pallet),
+			attributes_of(COLLECTION, ITEM, AttributeNamespace::CollectionOwner)
+		);
+	});
+}
@@ -680,6 +680,84 @@ mod benchmarks {
 		Ok(())
 	}
 
+	#[benchmark]
+	fn set_remote_fee_table() -> Result<(), BenchmarkError> {
+		let location = T::reachable_dest()
+			.ok_or(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))?;
+		// The largest table there can be.
+		let asset = AssetId(Location::new(
+			1,
+			[Parachain(u32::MAX), PalletInstance(u8::MAX), GeneralIndex(u128::MAX)],
+		));
+		let rate = ExecutionRate {
+			asset: asset.clone().into(),
+			units_per_second: u128::MAX,
+			units_per_mb: u128::MAX,
+		};
+		let table = RemoteFeeTable {
+			execution: BoundedVec::truncate_from(vec![rate; MaxExecutionRates::get() as usize]),
+			delivery: Some(DeliveryRate {
+				asset: asset.into(),
+				base: u128::MAX,
+				per_byte: u128::MAX,
+			}),
+		};
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, Box::new(location.clone().into()), Some(table));
+
+		assert!(RemoteFeeTables::<T>::contains_key(location));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn set_outbound_transfer_limit() -> Result<(), BenchmarkError> {
+		let destination = T::reachable_dest()
+			.ok_or(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))?;
+		let asset = AssetId(Here.into());
+		// Removing a limit also removes its usage.
+		OutboundTransferLimits::<T>::insert(
+			&destination,
+			&asset,
+			TransferLimit { amount: u128::MAX, period: 1u32.into() },
+		);
+		OutboundTransferWindows::<T>::insert(
+			&destination,
+			&asset,
+			TransferWindow { used: u128::MAX, updated_at: Zero::zero(), suspended: true },
+		);
+
+		#[extrinsic_call]
+		_(
+			RawOrigin::Root,
+			Box::new(destination.clone().into()),
+			Box::new(asset.clone().into()),
+			None,
+		);
+
+		assert!(!OutboundTransferLimits::<T>::contains_key(&destination, &asset));
+		assert!(!OutboundTransferWindows::<T>::contains_key(&destination, &asset));
+		Ok(())
+	}
+
+	#[benchmark]
+	fn resume_outbound_transfers() -> Result<(), BenchmarkError> {
+		let destination = T::reachable_dest()
+			.ok_or(BenchmarkError::Override(BenchmarkResult::from_weight(Weight::MAX)))?;
+		let asset = AssetId(Here.into());
+		OutboundTransferWindows::<T>::insert(
+			&destination,
+			&asset,
+			TransferWindow { used: u128::MAX, updated_at: Zero::zero(), suspended: true },
+		);
+
+		#[extrinsic_call]
+		_(RawOrigin::Root, Box::new(destination.clone().into()), Box::new(asset.clone().into()));
+
+		assert!(!OutboundTransferWindows::<T>::contains_key(&destination, &asset));
+		Ok(())
+	}
+
 	impl_benchmark_test_suite!(
 		Pallet,
 		crate::mock::new_test_ext_with_balances(Vec::new()),
@@ -26,6 +26,8 @@ mod mock;
 mod tests;
 
 pub mod migration;
+mod transfer_fees;
+mod transfer_limits;
 
 extern crate alloc;
 
@@ -37,6 +39,7 @@ use frame_support::{
 		DispatchErrorWithPostInfo, GetDispatchInfo, PostDispatchInfo, WithPostDispatchInfo,
 	},
 	pallet_prelude::*,
+	storage::{with_transaction_opaque_err, TransactionOutcome},
 	traits::{
 		Consideration, Contains, ContainsPair, Currency, Defensive, EnsureOrigin, Footprint, Get,
 		LockableCurrency, OriginTrait, WithdrawReasons,
@@ -62,22 +65,26 @@ use xcm_builder::{
 use xcm_executor::{
 	traits::{
 		AssetTransferError, CheckSuspension, ClaimAssets, ConvertLocation, ConvertOrigin,
-		DropAssets, EventEmitter, FeeManager, FeeReason, MatchesFungible, OnResponse, Properties,
-		QueryHandler, QueryResponseStatus, RecordXcm, TransactAsset, TransferType,
-		VersionChangeNotifier, WeightBounds, XcmAssetTransfers,
+		DropAssets, EventEmitter, FeeManager, FeeReason, LimitTransfers, MatchesFungible,
+		OnResponse, Properties, QueryHandler, QueryResponseStatus, RecordXcm, TransactAsset,
+		TransferType, VersionChangeNotifier, WeightBounds, XcmAssetTransfers,
 	},
 	AssetsInHolding,
 };
 use xcm_runtime_apis::{
 	authorized_aliases::{Error as AuthorizedAliasersApiError, OriginAliaser},
 	dry_run::{CallDryRunEffects, Error as XcmDryRunApiError, XcmDryRunEffects},
-	fees::Error as XcmPaymentApiError,
+	fees::{Error as XcmPaymentApiError, FeeItem, TransferFeesError},
+	lifecycle::LifecycleStage,
 	trusted_query::Error as TrustedQueryApiError,
 };
 
 #[cfg(any(feature = "try-runtime", test))]
 use sp_runtime::TryRuntimeError;
 
+pub use transfer_fees::{DeliveryRate, ExecutionRate, MaxExecutionRates, RemoteFeeTable};
+pub use transfer_limits::{TransferLimit, TransferWindow};
+
 pub trait WeightInfo {
 	fn send() -> Weight;
 	fn teleport_assets() -> Weight;
@@ -101,6 +108,9 @@ pub trait WeightInfo {
 	fn claim_assets() -> Weight;
 	fn add_authorized_alias() -> Weight;
 	fn remove_authorized_alias() -> Weight;
+	fn set_remote_fee_table() -> Weight;
+	fn set_outbound_transfer_limit() -> Weight;
+	fn resume_outbound_transfers() -> Weight;
 }
 
 /// fallback implementation
@@ -193,6 +203,18 @@ impl WeightInfo for TestWeightInfo {
 	fn remove_authorized_alias() -> Weight {
 		Weight::from_parts(100_000, 0)
 	}
+
+	fn set_remote_fee_table() -> Weight {
+		Weight::from_parts(100_000, 0)
+	}
+
+	fn set_outbound_transfer_limit() -> Weight {
+		Weight::from_parts(100_000, 0)
+	}
+
+	fn resume_outbound_transfers() -> Weight {
+		Weight::from_parts(100_000, 0)
+	}
 }
 
 #[derive(Clone, Debug, Encode, Decode, MaxEncodedLen, TypeInfo)]
@@ -458,6 +480,10 @@ pub mod pallet {
 		fn emit_process_failure_event(origin: Location, error: XcmError, message_id: XcmHash) {
 			Self::deposit_event(Event::ProcessXcmError { origin, error, message_id });
 		}
+
+		fn emit_executed_event(origin: Location, weight_used: Weight, message_id: XcmHash) {
+			Self::deposit_event(Event::Executed { origin, weight_used, message_id });
+		}
 	}
 
 	#[pallet::event]
@@ -579,6 +605,17 @@ pub mod pallet {
 		AliasAuthorizationRemoved { aliaser: Location, target: Location },
 		/// `target` removed all alias authorizations.
 		AliasesAuthorizationsRemoved { target: Location },
+		/// The cached fee table of `location` was set or removed.
+		RemoteFeeTableChanged { location: Location },
+		/// The limit of the transfers of `asset` to `destination` was set or removed.
+		OutboundTransferLimitChanged { destination: Location, asset: AssetId },
+		/// Transferring `amount` of `asset` to `destination` exceeded the limit, further transfers
+		/// are suspended until resumed by the `AdminOrigin`.
+		OutboundTransfersSuspended { destination: Location, asset: AssetId, amount: u128 },
+		/// The transfers of `asset` to `destination` were resumed.
+		OutboundTransfersResumed { destination: Location, asset: AssetId },
+		/// An XCM message from `origin` was executed successfully.
+		Executed { origin: Location, weight_used: Weight, message_id: XcmHash },
 	}
 
 	#[pallet::origin]
@@ -678,6 +715,12 @@ pub mod pallet {
 		/// The alias to remove authorization for was not found.
 		#[codec(index = 27)]
 		AliasNotFound,
+		/// The period of a transfer limit must not be zero.
+		#[codec(index = 28)]
+		InvalidTransferLimit,
+		/// The transfers of the asset to the destination are not suspended.
+		#[codec(index = 29)]
+		TransfersNotSuspended,
 	}
 
 	impl<T: Config> From<SendError> for Error<T> {
@@ -907,6 +950,48 @@ pub mod pallet {
 		OptionQuery,
 	>;
 
+	/// The fees of remote chains, cached to quote the fees of transfers across several chains.
+	///
+	/// Keyed by the latest `Location`, so that the tables set in any XCM version are found when
+	/// quoting. The keys must be migrated when the latest XCM version changes.
+	///
+	/// Only used by [`Pallet::query_transfer_fees`], never to charge any fees.
+	#[pallet::storage]
+	pub(super) type RemoteFeeTables<T: Config> =
+		StorageMap<_, Blake2_128Concat, Location, RemoteFeeTable, OptionQuery>;
+
+	/// Limits of the assets transferred out of the chain, by destination and asset, both as seen
+	/// by this chain.
+	///
+	/// Keyed by the latest XCM types, so that the limits keep applying to the transfers of the
+	/// executor whatever version they were set in. The keys must be migrated when the latest XCM
+	/// version changes.
+	///
+	/// Only relevant if this pallet is being used as the [`xcm_executor::traits::LimitTransfers`]
+	/// implementation in the XCM executor configuration.
+	#[pallet::storage]
+	pub(super) type OutboundTransferLimits<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Location,
+		Blake2_128Concat,
+		AssetId,
+		TransferLimit<BlockNumberFor<T>>,
+		OptionQuery,
+	>;
+
+	/// The usage of the [`OutboundTransferLimits`], keyed like them.
+	#[pallet::storage]
+	pub(super) type OutboundTransferWindows<T: Config> = StorageDoubleMap<
+		_,
+		Blake2_128Concat,
+		Location,
+		Blake2_128Concat,
+		AssetId,
+		TransferWindow<BlockNumberFor<T>>,
+		ValueQuery,
+	>;
+
 	#[pallet::genesis_config]
 	pub struct GenesisConfig<T: Config> {
 		#[serde(skip)]
@@ -1697,6 +1782,94 @@ pub mod pallet {
 				Err(Error::<T>::AliasNotFound.into())
 			}
 		}
+
+		/// Set or remove the cached fee table of a remote chain.
+		///
+		/// The table is only used to quote the fees of transfers through the remote chain with
+		/// `XcmTransferFeesApi`.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `location`: The remote chain, relative to this chain.
+		/// - `table`: The fees of the remote chain, or `None` to remove them.
+		#[pallet::call_index(17)]
+		#[pallet::weight(T::WeightInfo::set_remote_fee_table())]
+		pub fn set_remote_fee_table(
+			origin: OriginFor<T>,
+			location: Box<VersionedLocation>,
+			table: Option<RemoteFeeTable>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let location: Location = (*location).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			RemoteFeeTables::<T>::set(&location, table);
+			Self::deposit_event(Event::RemoteFeeTableChanged { location });
+			Ok(())
+		}
+
+		/// Set or remove the limit of the transfers of an asset out of the chain to a destination.
+		///
+		/// Transfers exceeding the limit fail and suspend further transfers of the asset to the
+		/// destination, until resumed with `resume_outbound_transfers`. Removing the limit also
+		/// lifts the suspension. Only transfers of assets owned by their origin suspend further
+		/// transfers.
+		///
+		/// Transfers of calls of this pallet exceeding the limit fail without suspending further
+		/// transfers, since the failed call is reverted.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `destination`: The destination of the transfers, relative to this chain.
+		/// - `asset`: The transferred asset, as seen by this chain.
+		/// - `limit`: The limit, or `None` to remove it.
+		#[pallet::call_index(18)]
+		#[pallet::weight(T::WeightInfo::set_outbound_transfer_limit())]
+		pub fn set_outbound_transfer_limit(
+			origin: OriginFor<T>,
+			destination: Box<VersionedLocation>,
+			asset: Box<VersionedAssetId>,
+			limit: Option<TransferLimit<BlockNumberFor<T>>>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let destination: Location =
+				(*destination).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let asset: AssetId = (*asset).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			ensure!(
+				limit.as_ref().map_or(true, |limit| !limit.period.is_zero()),
+				Error::<T>::InvalidTransferLimit
+			);
+			if limit.is_none() {
+				OutboundTransferWindows::<T>::remove(&destination, &asset);
+			}
+			OutboundTransferLimits::<T>::set(&destination, &asset, limit);
+			Self::deposit_event(Event::OutboundTransferLimitChanged { destination, asset });
+			Ok(())
+		}
+
+		/// Resume the transfers of an asset out of the chain to a destination, suspended after
+		/// exceeding their limit.
+		///
+		/// The usage of the limit is reset.
+		///
+		/// - `origin`: Must be an origin specified by AdminOrigin.
+		/// - `destination`: The destination of the transfers, relative to this chain.
+		/// - `asset`: The transferred asset, as seen by this chain.
+		#[pallet::call_index(19)]
+		#[pallet::weight(T::WeightInfo::resume_outbound_transfers())]
+		pub fn resume_outbound_transfers(
+			origin: OriginFor<T>,
+			destination: Box<VersionedLocation>,
+			asset: Box<VersionedAssetId>,
+		) -> DispatchResult {
+			T::AdminOrigin::ensure_origin(origin)?;
+			let destination: Location =
+				(*destination).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			let asset: AssetId = (*asset).try_into().map_err(|()| Error::<T>::BadVersion)?;
+			ensure!(
+				OutboundTransferWindows::<T>::get(&destination, &asset).suspended,
+				Error::<T>::TransfersNotSuspended
+			);
+			OutboundTransferWindows::<T>::remove(&destination, &asset);
+			Self::deposit_event(Event::OutboundTransfersResumed { destination, asset });
+			Ok(())
+		}
 	}
 }
 
@@ -1931,12 +2104,36 @@ impl<T: Config> Pallet<T> {
 		origin: Location,
 		dest: Location,
 		beneficiary: Either<Location, Xcm<()>>,
-		mut assets: Vec<Asset>,
+		assets: Vec<Asset>,
 		assets_transfer_type: TransferType,
 		fee_asset_index: usize,
 		fees_transfer_type: TransferType,
 		weight_limit: WeightLimit,
 	) -> DispatchResult {
+		let (local_xcm, remote_xcm) = Self::build_transfer_assets(
+			origin.clone(),
+			dest.clone(),
+			beneficiary,
+			assets,
+			assets_transfer_type,
+			fee_asset_index,
+			fees_transfer_type,
+			weight_limit,
+		)?;
+		Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
+	}
+
+	/// Build the local and remote XCM programs of `do_transfer_assets`.
+	fn build_transfer_assets(
+		origin: Location,
+		dest: Location,
+		beneficiary: Either<Location, Xcm<()>>,
+		mut assets: Vec<Asset>,
+		assets_transfer_type: TransferType,
+		fee_asset_index: usize,
+		fees_transfer_type: TransferType,
+		weight_limit: WeightLimit,
+	) -> Result<(Xcm<<T as Config>::RuntimeCall>, Option<Xcm<()>>), Error<T>> {
 		// local and remote XCM programs to potentially handle fees separately
 		let fees = if fees_transfer_type == assets_transfer_type {
 			let fees = assets.get(fee_asset_index).ok_or(Error::<T>::Empty)?.clone();
@@ -1976,21 +2173,20 @@ impl<T: Config> Pallet<T> {
 					weight_limit,
 				)?,
 				TransferType::RemoteReserve(_) =>
-					return Err(Error::<T>::InvalidAssetUnsupportedReserve.into()),
+					return Err(Error::<T>::InvalidAssetUnsupportedReserve),
 			};
 			FeesHandling::Separate { local_xcm, remote_xcm }
 		};
 
-		let (local_xcm, remote_xcm) = Self::build_xcm_transfer_type(
-			origin.clone(),
-			dest.clone(),
+		Self::build_xcm_transfer_type(
+			origin,
+			dest,
 			beneficiary,
 			assets,
 			assets_transfer_type,
 			fees,
 			weight_limit,
-		)?;
-		Self::execute_xcm_transfer(origin, dest, local_xcm, remote_xcm)
+		)
 	}
 
 	fn build_xcm_transfer_type(
@@ -2987,6 +3183,221 @@ impl<T: Config> Pallet<T> {
 			})
 	}
 
+	/// Quote the fees of the transfer `call` dispatched by `origin`.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::fees::XcmTransferFeesApi` runtime API. Supports
+	/// `transfer_assets` and `transfer_assets_using_type_and_then`.
+	///
+	/// The messages of the transfer are followed through all chains they reach. The local
+	/// execution is priced with `local_execution_fees`, usually what the runtime implements for
+	/// `XcmPaymentApi::query_weight_to_asset_fee`, and deliveries from this chain are quoted by
+	/// the `XcmRouter`. Execution on and deliveries from remote chains are priced with their fee
+	/// tables, see [`Pallet::set_remote_fee_table`]. The weight of remote execution is estimated
+	/// with the local `Weigher`, once the message is known to be expressible in the XCM version of
+	/// the remote chain.
+	pub fn query_transfer_fees(
+		origin: VersionedLocation,
+		call: Call<T>,
+		result_xcms_version: XcmVersion,
+		local_execution_fees: impl Fn(Weight) -> Result<Asset, XcmPaymentApiError>,
+	) -> Result<Vec<FeeItem>, TransferFeesError> {
+		use TransferFeesError::Fees;
+		let conversion_failed = |()| Fees(XcmPaymentApiError::VersionedConversionFailed);
+		let location = |location: Location| {
+			VersionedLocation::from(location)
+				.into_version(result_xcms_version)
+				.map_err(conversion_failed)
+		};
+		let assets = |assets: Assets| {
+			VersionedAssets::from(assets)
+				.into_version(result_xcms_version)
+				.map_err(conversion_failed)
+		};
+		let unpriced = |chain: &Location| {
+			tracing::debug!(target: "xcm::pallet_xcm::query_transfer_fees", ?chain, "No fees known for chain");
+			location(chain.clone()).map_or_else(|error| error, TransferFeesError::UnpricedHop)
+		};
+
+		let origin: Location = origin.try_into().map_err(conversion_failed)?;
+		// Building a teleport checks the assets out, which must not be kept.
+		let (dest, mut local_xcm, remote_xcm) = with_transaction_opaque_err(|| {
+			TransactionOutcome::Rollback(Self::transfer_programs(origin, call))
+		})
+		.map_err(|()| TransferFeesError::InvalidTransfer)??;
+
+		let weight = T::Weigher::weight(&mut local_xcm)
+			.map_err(|()| Fees(XcmPaymentApiError::WeightNotComputable))?;
+		let fees = local_execution_fees(weight).map_err(Fees)?;
+		let mut items = vec![FeeItem::LocalExecution { weight, fees: assets(fees.into())? }];
+
+		let context = T::UniversalLocation::get();
+		// The messages to quote: the sending chain, the destination as seen by the sending chain
+		// and the message. All chains are relative to this chain.
+		let mut messages: Vec<(Location, Location, Xcm<()>)> =
+			transfer_fees::forwarded_messages(&Xcm::<()>::from(local_xcm), &context)
+				.into_iter()
+				.chain(remote_xcm.map(|remote_xcm| (dest, remote_xcm)))
+				.map(|(destination, message)| (Location::here(), destination, message))
+				.collect();
+		let mut next = 0;
+		while let Some((sender, destination, message)) = messages.get(next).cloned() {
+			next += 1;
+			let chain = sender
+				.clone()
+				.appended_with(destination.clone())
+				.map_err(|_| Fees(XcmPaymentApiError::Unroutable))?;
+
+			let delivery_fees = if sender.is_here() {
+				validate_send::<T::XcmRouter>(destination, message.clone())
+					.map_err(|error| {
+						tracing::debug!(target: "xcm::pallet_xcm::query_transfer_fees", ?error, ?chain, "Failed to validate send");
+						Fees(XcmPaymentApiError::Unroutable)
+					})?
+					.1
+			} else {
+				RemoteFeeTables::<T>::get(&sender)
+					.and_then(|table| table.delivery_fees(&message).ok())
+					.ok_or_else(|| unpriced(&sender))?
+			};
+			items.push(FeeItem::Delivery {
+				from: location(sender)?,
+				to: location(chain.clone())?,
+				fees: assets(delivery_fees)?,
+			});
+
+			// Estimate only what the remote chain is able to receive.
+			let version = Self::get_version_for(&chain)
+				.or_else(|| SafeXcmVersion::<T>::get())
+				.ok_or(Fees(XcmPaymentApiError::UnhandledXcmVersion))?;
+			VersionedXcm::from(message.clone())
+				.into_version(version.min(XCM_VERSION))
+				.map_err(conversion_failed)?;
+			let weight = T::Weigher::weight(&mut message.clone().into())
+				.map_err(|()| Fees(XcmPaymentApiError::WeightNotComputable))?;
+			let fees = match transfer_fees::fee_asset(&message) {
+				Some(asset) => RemoteFeeTables::<T>::get(&chain)
+					.and_then(|table| table.execution_fees(asset, weight))
+					.ok_or_else(|| unpriced(&chain))?
+					.into(),
+				None => Assets::new(),
+			};
+			items.push(FeeItem::RemoteExecution {
+				chain: location(chain.clone())?,
+				weight,
+				fees: assets(fees)?,
+			});
+
+			let chain_context = Location::new(0, context.clone())
+				.appended_with(chain.clone())
+				.ok()
+				.filter(|location| location.parent_count() == 0)
+				.ok_or(Fees(XcmPaymentApiError::Unroutable))?
+				.interior;
+			messages.extend(
+				transfer_fees::forwarded_messages(&message, &chain_context)
+					.into_iter()
+					.map(|(destination, message)| (chain.clone(), destination, message)),
+			);
+		}
+
+		Ok(items)
+	}
+
+	/// The destination and the local and remote programs of the transfer `call` dispatched by
+	/// `origin`.
+	fn transfer_programs(
+		origin: Location,
+		call: Call<T>,
+	) -> Result<(Location, Xcm<<T as Config>::RuntimeCall>, Option<Xcm<()>>), TransferFeesError> {
+		let conversion_failed =
+			|()| TransferFeesError::Fees(XcmPaymentApiError::VersionedConversionFailed);
+		let invalid = |error: Error<T>| {
+			tracing::debug!(target: "xcm::pallet_xcm::query_transfer_fees", ?error, "Invalid transfer");
+			TransferFeesError::InvalidTransfer
+		};
+
+		let (dest, assets, fee_asset_index, types, beneficiary, weight_limit) = match call {
+			Call::transfer_assets { dest, beneficiary, assets, fee_asset_item, weight_limit } => {
+				let dest: Location = (*dest).try_into().map_err(conversion_failed)?;
+				let beneficiary: Location = (*beneficiary).try_into().map_err(conversion_failed)?;
+				let assets: Assets = (*assets).try_into().map_err(conversion_failed)?;
+				let assets = assets.into_inner();
+				let fee_asset_index = fee_asset_item as usize;
+				let types =
+					Self::find_fee_and_assets_transfer_types(&assets, fee_asset_index, &dest)
+						.map_err(invalid)?;
+				(dest, assets, fee_asset_index, types, Either::Left(beneficiary), weight_limit)
+			},
+			Call::transfer_assets_using_type_and_then {
+				dest,
+				assets,
+				assets_transfer_type,
+				remote_fees_id,
+				fees_transfer_type,
+				custom_xcm_on_dest,
+				weight_limit,
+			} => {
+				let dest: Location = (*dest).try_into().map_err(conversion_failed)?;
+				let assets: Assets = (*assets).try_into().map_err(conversion_failed)?;
+				let fees_id: AssetId = (*remote_fees_id).try_into().map_err(conversion_failed)?;
+				let remote_xcm: Xcm<()> =
+					(*custom_xcm_on_dest).try_into().map_err(conversion_failed)?;
+				let assets = assets.into_inner();
+				let fee_asset_index = assets
+					.iter()
+					.position(|a| a.id == fees_id)
+					.ok_or(Error::<T>::FeesNotMet)
+					.map_err(invalid)?;
+				let types = (*fees_transfer_type, *assets_transfer_type);
+				(dest, assets, fee_asset_index, types, Either::Right(remote_xcm), weight_limit)
+			},
+			_ => return Err(TransferFeesError::UnsupportedCall),
+		};
+		if assets.len() > MAX_ASSETS_FOR_TRANSFER {
+			return Err(invalid(Error::<T>::TooManyAssets))
+		}
+
+		let (fees_transfer_type, assets_transfer_type) = types;
+		let (local_xcm, remote_xcm) = Self::build_transfer_assets(
+			origin,
+			dest.clone(),
+			beneficiary,
+			assets,
+			assets_transfer_type,
+			fee_asset_index,
+			fees_transfer_type,
+			weight_limit,
+		)
+		.map_err(invalid)?;
+		Ok((dest, local_xcm, remote_xcm))
+	}
+
+	/// The id of the XCM message and its lifecycle stage reported by `event`, if any.
+	///
+	/// Meant to be used in the `xcm_runtime_apis::lifecycle::XcmLifecycleApi` runtime API,
+	/// together with the events of the message queue processing XCMs.
+	pub fn lifecycle_stage(event: Event<T>) -> Option<(XcmHash, LifecycleStage)> {
+		match event {
+			Event::Sent { origin, destination, message_id, .. } => Some((
+				message_id,
+				LifecycleStage::Sent { origin: origin.into(), destination: destination.into() },
+			)),
+			Event::SendFailed { origin, destination, error, message_id } => Some((
+				message_id,
+				LifecycleStage::SendFailed {
+					origin: origin.into(),
+					destination: destination.into(),
+					error,
+				},
+			)),
+			Event::ProcessXcmError { origin, error, message_id } =>
+				Some((message_id, LifecycleStage::ExecutionFailed { origin: origin.into(), error })),
+			Event::Executed { origin, weight_used, message_id } =>
+				Some((message_id, LifecycleStage::Executed { origin: origin.into(), weight_used })),
+			_ => None,
+		}
+	}
+
 	/// Given an Asset and a Location, returns if the provided location is a trusted reserve for the
 	/// given asset.
 	pub fn is_trusted_reserve(
@@ -3791,6 +4202,79 @@ impl<T: Config> CheckSuspension for Pallet<T> {
 	}
 }
 
+impl<T: Config> Pallet<T> {
+	/// The assets of `assets` exceeding their limit if transferred to `destination`, with the
+	/// window of their limit and the transferred amount.
+	///
+	/// Returns an error if the transfers of any of the assets are suspended.
+	fn exceeded_outbound_limits(
+		destination: &Location,
+		assets: &Assets,
+	) -> Result<Vec<(AssetId, TransferWindow<BlockNumberFor<T>>, u128)>, XcmError> {
+		let now = frame_system::Pallet::<T>::current_block_number();
+		let mut exceeded = Vec::new();
+		for (asset, amount) in transfer_limits::limited_amounts(assets) {
+			let Some(limit) = OutboundTransferLimits::<T>::get(destination, &asset) else {
+				continue
+			};
+			let window = OutboundTransferWindows::<T>::get(destination, &asset);
+			if window.suspended {
+				tracing::debug!(
+					target: "xcm::pallet_xcm::check_outbound",
+					?destination, ?asset, "Outbound transfers suspended",
+				);
+				return Err(XcmError::FailedToTransactAsset("Outbound transfers suspended"))
+			}
+			let used = limit.used(&window, now).checked_add(amount);
+			if used.map_or(true, |used| used > limit.amount) {
+				exceeded.push((asset, window, amount));
+			}
+		}
+		Ok(exceeded)
+	}
+}
+
+impl<T: Config> LimitTransfers for Pallet<T> {
+	fn check_outbound(destination: &Location, assets: &Assets) -> Result<(), XcmError> {
+		if Self::exceeded_outbound_limits(destination, assets)?.is_empty() {
+			Ok(())
+		} else {
+			Err(XcmError::FailedToTransactAsset("Outbound transfer limit exceeded"))
+		}
+	}
+
+	fn note_outbound(destination: &Location, assets: &Assets) {
+		let now = frame_system::Pallet::<T>::current_block_number();
+		for (asset, amount) in transfer_limits::limited_amounts(assets) {
+			let Some(limit) = OutboundTransferLimits::<T>::get(destination, &asset) else {
+				continue
+			};
+			OutboundTransferWindows::<T>::mutate(destination, asset, |window| {
+				window.used = limit.used(window, now).saturating_add(amount);
+				window.updated_at = now;
+			});
+		}
+	}
+
+	fn note_rejected(destination: &Location, assets: &Assets) {
+		// Nothing to suspend if the transfers are suspended already.
+		let Ok(exceeded) = Self::exceeded_outbound_limits(destination, assets) else { return };
+		for (asset, mut window, amount) in exceeded {
+			tracing::debug!(
+				target: "xcm::pallet_xcm::check_outbound",
+				?destination, ?asset, ?amount, "Outbound transfer limit exceeded, suspending transfers",
+			);
+			window.suspended = true;
+			OutboundTransferWindows::<T>::insert(destination, &asset, window);
+			Self::deposit_event(Event::OutboundTransfersSuspended {
+				destination: destination.clone(),
+				asset,
+				amount,
+			});
+		}
+	}
+}
+
 impl<T: Config> RecordXcm for Pallet<T> {
 	fn should_record() -> bool {
 		ShouldRecordXcm::<T>::get()
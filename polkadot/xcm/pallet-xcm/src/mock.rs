@@ -528,6 +528,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type TransferLimiter = XcmPallet;
 }
 
 /// Converts a local signed origin into an XCM location. Forms the basis for local origins
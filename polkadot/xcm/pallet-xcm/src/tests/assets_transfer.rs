@@ -2543,7 +2543,7 @@ fn remote_asset_reserve_and_remote_fee_reserve_paid_call<Call>(
 		]);
 		let sent_msg_id = fake_message_hash(&sent_message);
 
-		let mut last_events = last_events(7).into_iter();
+		let mut last_events = last_events(8).into_iter();
 		// asset events
 		// forceCreate
 		last_events.next().unwrap();
@@ -2565,6 +2565,10 @@ fn remote_asset_reserve_and_remote_fee_reserve_paid_call<Call>(
 				message_id: sent_msg_id,
 			})
 		);
+		assert!(matches!(
+			last_events.next().unwrap(),
+			RuntimeEvent::XcmPallet(crate::Event::Executed { .. })
+		));
 		assert_eq!(
 			last_events.next().unwrap(),
 			RuntimeEvent::XcmPallet(crate::Event::Attempted {
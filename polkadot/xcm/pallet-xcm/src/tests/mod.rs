@@ -112,7 +112,7 @@ fn report_outcome_notify_works() {
 		);
 		assert_eq!(r, Outcome::Complete { used: Weight::from_parts(1_000, 1_000) });
 		assert_eq!(
-			last_events(2),
+			last_events(3),
 			vec![
 				RuntimeEvent::TestNotifier(pallet_test_notifier::Event::ResponseReceived(
 					Parachain(OTHER_PARA_ID).into(),
@@ -124,6 +124,11 @@ fn report_outcome_notify_works() {
 					pallet_index: 5,
 					call_index: 2
 				}),
+				RuntimeEvent::XcmPallet(crate::Event::Executed {
+					origin: Parachain(OTHER_PARA_ID).into(),
+					weight_used: Weight::from_parts(1_000, 1_000),
+					message_id: hash,
+				}),
 			]
 		);
 		assert_eq!(crate::Queries::<Test>::iter().collect::<Vec<_>>(), vec![]);
@@ -593,32 +598,35 @@ fn trapped_assets_can_be_claimed() {
 		let weight = BaseXcmWeight::get() * 6;
 		let dest: Location = Junction::AccountId32 { network: None, id: BOB.into() }.into();
 
-		assert_ok!(XcmPallet::execute(
-			RuntimeOrigin::signed(ALICE),
-			Box::new(VersionedXcm::from(Xcm(vec![
-				WithdrawAsset((Here, SEND_AMOUNT).into()),
-				buy_execution((Here, SEND_AMOUNT)),
-				// Don't propagated the error into the result.
-				SetErrorHandler(Xcm(vec![ClearError])),
-				// This will make an error.
-				Trap(0),
-				// This would succeed, but we never get to it.
-				DepositAsset { assets: AllCounted(1).into(), beneficiary: dest.clone() },
-			]))),
-			weight
-		));
+		let message = VersionedXcm::from(Xcm(vec![
+			WithdrawAsset((Here, SEND_AMOUNT).into()),
+			buy_execution((Here, SEND_AMOUNT)),
+			// Don't propagated the error into the result.
+			SetErrorHandler(Xcm(vec![ClearError])),
+			// This will make an error.
+			Trap(0),
+			// This would succeed, but we never get to it.
+			DepositAsset { assets: AllCounted(1).into(), beneficiary: dest.clone() },
+		]));
+		let message_id = BlakeTwo256::hash_of(&message).into();
+		assert_ok!(XcmPallet::execute(RuntimeOrigin::signed(ALICE), Box::new(message), weight));
 		let source: Location = Junction::AccountId32 { network: None, id: ALICE.into() }.into();
 		let trapped = AssetTraps::<Test>::iter().collect::<Vec<_>>();
 		let vma = VersionedAssets::from(Assets::from((Here, SEND_AMOUNT)));
 		let hash = BlakeTwo256::hash_of(&(source.clone(), vma.clone()));
 		assert_eq!(
-			last_events(2),
+			last_events(3),
 			vec![
 				RuntimeEvent::XcmPallet(crate::Event::AssetsTrapped {
 					hash,
-					origin: source,
+					origin: source.clone(),
 					assets: vma
 				}),
+				RuntimeEvent::XcmPallet(crate::Event::Executed {
+					origin: source,
+					weight_used: BaseXcmWeight::get() * 5,
+					message_id,
+				}),
 				RuntimeEvent::XcmPallet(crate::Event::Attempted {
 					outcome: Outcome::Complete { used: BaseXcmWeight::get() * 5 }
 				}),
@@ -670,6 +678,7 @@ fn claim_assets_works() {
 		let trapping_program =
 			Xcm::<RuntimeCall>::builder_unsafe().withdraw_asset((Here, SEND_AMOUNT)).build();
 		// Even though assets are trapped, the extrinsic returns success.
+		let message_id = BlakeTwo256::hash_of(&VersionedXcm::from(trapping_program.clone())).into();
 		assert_ok!(XcmPallet::execute(
 			RuntimeOrigin::signed(ALICE),
 			Box::new(VersionedXcm::from(trapping_program)),
@@ -684,13 +693,18 @@ fn claim_assets_works() {
 
 		// Assets were indeed trapped.
 		assert_eq!(
-			last_events(2),
+			last_events(3),
 			vec![
 				RuntimeEvent::XcmPallet(crate::Event::AssetsTrapped {
 					hash,
-					origin: source,
+					origin: source.clone(),
 					assets: versioned_assets
 				}),
+				RuntimeEvent::XcmPallet(crate::Event::Executed {
+					origin: source,
+					weight_used: BaseXcmWeight::get() * 1,
+					message_id,
+				}),
 				RuntimeEvent::XcmPallet(crate::Event::Attempted {
 					outcome: Outcome::Complete { used: BaseXcmWeight::get() * 1 }
 				})
@@ -1691,14 +1705,19 @@ fn execute_initiate_transfer_and_check_sent_event() {
 			let origin: Location = AccountId32 { network: None, id: ALICE.into() }.into();
 			let sent_msg_id = fake_message_hash(&sent_message);
 			assert_eq!(
-				last_events(2),
+				last_events(3),
 				vec![
 					RuntimeEvent::XcmPallet(Event::Sent {
-						origin,
+						origin: origin.clone(),
 						destination: Parent.into(),
 						message: Xcm::default(),
 						message_id: sent_msg_id,
 					}),
+					RuntimeEvent::XcmPallet(Event::Executed {
+						origin,
+						weight_used: Weight::from_parts(1_000, 1_000),
+						message_id: BlakeTwo256::hash_of(&VersionedXcm::from(message)).into(),
+					}),
 					RuntimeEvent::XcmPallet(Event::Attempted {
 						outcome: Outcome::Complete { used: Weight::from_parts(1_000, 1_000) }
 					}),
@@ -1743,3 +1762,159 @@ fn deliver_failure_with_expect_error() {
 		})
 	});
 }
+
+#[test]
+fn outbound_transfer_limit_suspends_transfers() {
+	use crate::{pallet::OutboundTransferWindows, Event, TransferLimit};
+
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let destination = RelayLocation::get();
+		let asset = AssetId(Here.into());
+		let window = || OutboundTransferWindows::<Test>::get(&destination, &asset);
+		let teleport = || {
+			let message = Xcm(vec![
+				WithdrawAsset((Here, SEND_AMOUNT).into()),
+				InitiateTeleport {
+					assets: AllCounted(1).into(),
+					dest: destination.clone(),
+					xcm: Xcm(vec![]),
+				},
+			]);
+			let mut hash = fake_message_hash(&message);
+			let origin: Location = AccountId32 { network: None, id: ALICE.into() }.into();
+			let weight = Weight::from_parts(1_000_000_000, 1_000_000_000);
+			XcmExecutor::<XcmConfig>::prepare_and_execute(
+				origin, message, &mut hash, weight, weight,
+			)
+		};
+
+		assert_noop!(
+			XcmPallet::set_outbound_transfer_limit(
+				RuntimeOrigin::root(),
+				Box::new(destination.clone().into()),
+				Box::new(asset.clone().into()),
+				Some(TransferLimit { amount: 15, period: 0 }),
+			),
+			Error::<Test>::InvalidTransferLimit
+		);
+		assert_ok!(XcmPallet::set_outbound_transfer_limit(
+			RuntimeOrigin::root(),
+			Box::new(destination.clone().into()),
+			Box::new(asset.clone().into()),
+			Some(TransferLimit { amount: 15, period: 10 }),
+		));
+
+		// Failed transfers are not counted.
+		set_send_xcm_artificial_failure(true);
+		assert!(matches!(teleport(), Outcome::Incomplete { .. }));
+		set_send_xcm_artificial_failure(false);
+		assert_eq!(window(), Default::default());
+
+		assert!(matches!(teleport(), Outcome::Complete { .. }));
+		assert_eq!(window().used, SEND_AMOUNT);
+
+		// A second transfer in the same block exceeds the limit and suspends the transfers.
+		assert!(matches!(
+			teleport(),
+			Outcome::Incomplete { error: XcmError::FailedToTransactAsset(_), .. }
+		));
+		// The withdrawn assets of the failed transfers are trapped.
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - 3 * SEND_AMOUNT);
+		assert!(window().suspended);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::XcmPallet(Event::OutboundTransfersSuspended {
+				destination: destination.clone(),
+				asset: asset.clone(),
+				amount: SEND_AMOUNT,
+			})));
+
+		// Transfers stay suspended once the window passed.
+		System::set_block_number(20);
+		assert!(matches!(teleport(), Outcome::Incomplete { .. }));
+
+		assert_ok!(XcmPallet::resume_outbound_transfers(
+			RuntimeOrigin::root(),
+			Box::new(destination.clone().into()),
+			Box::new(asset.clone().into()),
+		));
+		assert_noop!(
+			XcmPallet::resume_outbound_transfers(
+				RuntimeOrigin::root(),
+				Box::new(destination.clone().into()),
+				Box::new(asset.clone().into()),
+			),
+			Error::<Test>::TransfersNotSuspended
+		);
+		assert!(matches!(teleport(), Outcome::Complete { .. }));
+
+		// Half of the period later, 7 out of the 10 units are released.
+		System::set_block_number(25);
+		assert!(matches!(teleport(), Outcome::Complete { .. }));
+		assert_eq!(window().used, 13);
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - 6 * SEND_AMOUNT);
+	});
+}
+
+#[test]
+fn only_funded_transfers_exceeding_outbound_limit_suspend_transfers() {
+	use crate::{pallet::OutboundTransferWindows, Event, TransferLimit};
+
+	let balances = vec![(ALICE, INITIAL_BALANCE)];
+	new_test_ext_with_balances(balances).execute_with(|| {
+		let destination: Location = Parachain(OTHER_PARA_ID).into();
+		let asset = AssetId(Here.into());
+		let window = || OutboundTransferWindows::<Test>::get(&destination, &asset);
+		let suspended = || {
+			System::events().iter().any(|record| {
+				matches!(
+					record.event,
+					RuntimeEvent::XcmPallet(Event::OutboundTransfersSuspended { .. })
+				)
+			})
+		};
+		let reserve_transfer = |who: AccountId, amount: u128| {
+			let message = Xcm(vec![TransferReserveAsset {
+				assets: (Here, amount).into(),
+				dest: destination.clone(),
+				xcm: Xcm(vec![]),
+			}]);
+			let mut hash = fake_message_hash(&message);
+			let origin: Location = AccountId32 { network: None, id: who.into() }.into();
+			let weight = Weight::from_parts(1_000_000_000, 1_000_000_000);
+			XcmExecutor::<XcmConfig>::prepare_and_execute(
+				origin, message, &mut hash, weight, weight,
+			)
+		};
+
+		assert_ok!(XcmPallet::set_outbound_transfer_limit(
+			RuntimeOrigin::root(),
+			Box::new(destination.clone().into()),
+			Box::new(asset.clone().into()),
+			Some(TransferLimit { amount: 15, period: 10 }),
+		));
+
+		// Transfers of assets not owned by their origin don't suspend the transfers.
+		assert!(matches!(reserve_transfer(BOB, 2 * SEND_AMOUNT), Outcome::Incomplete { .. }));
+		assert_eq!(window(), Default::default());
+		assert!(!suspended());
+		assert!(matches!(reserve_transfer(ALICE, SEND_AMOUNT), Outcome::Complete { .. }));
+		assert_eq!(window().used, SEND_AMOUNT);
+
+		// Funded transfers exceeding the limit are reverted and suspend the transfers.
+		assert!(matches!(
+			reserve_transfer(ALICE, SEND_AMOUNT),
+			Outcome::Incomplete { error: XcmError::FailedToTransactAsset(_), .. }
+		));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - SEND_AMOUNT);
+		assert!(window().suspended);
+		assert!(System::events().iter().any(|record| record.event ==
+			RuntimeEvent::XcmPallet(Event::OutboundTransfersSuspended {
+				destination: destination.clone(),
+				asset: asset.clone(),
+				amount: SEND_AMOUNT,
+			})));
+		assert!(matches!(reserve_transfer(ALICE, 1), Outcome::Incomplete { .. }));
+		assert_eq!(Balances::total_balance(&ALICE), INITIAL_BALANCE - SEND_AMOUNT);
+	});
+}
@@ -0,0 +1,167 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Fee tables of remote chains and helpers to follow the messages of a transfer across chains,
+//! used to quote the fees of a transfer through `XcmTransferFeesApi`.
+
+use alloc::{vec, vec::Vec};
+use codec::{Decode, DecodeWithMemTracking, Encode};
+use frame_support::{
+	parameter_types,
+	weights::constants::{WEIGHT_PROOF_SIZE_PER_MB, WEIGHT_REF_TIME_PER_SECOND},
+	BoundedVec,
+};
+use scale_info::TypeInfo;
+use sp_runtime::RuntimeDebug;
+use xcm::prelude::*;
+
+parameter_types! {
+	/// The maximum number of fee assets in a [`RemoteFeeTable`].
+	pub const MaxExecutionRates: u32 = 16;
+}
+
+/// The price of execution on a remote chain in one of its fee assets.
+///
+/// Computed like [`xcm_builder::FixedRateOfFungible`]: `units_per_second` are charged per second of
+/// `ref_time` and `units_per_mb` per megabyte of `proof_size`.
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct ExecutionRate {
+	/// The fee asset, as seen by the remote chain.
+	pub asset: VersionedAssetId,
+	pub units_per_second: u128,
+	pub units_per_mb: u128,
+}
+
+impl ExecutionRate {
+	/// The fees for executing `weight`.
+	pub fn fees(&self, weight: Weight) -> u128 {
+		self.units_per_second.saturating_mul(weight.ref_time() as u128) /
+			(WEIGHT_REF_TIME_PER_SECOND as u128) +
+			self.units_per_mb.saturating_mul(weight.proof_size() as u128) /
+				(WEIGHT_PROOF_SIZE_PER_MB as u128)
+	}
+}
+
+/// The price a remote chain charges for delivering the messages it forwards.
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct DeliveryRate {
+	/// The fee asset, as seen by the remote chain.
+	pub asset: VersionedAssetId,
+	/// The fees charged for every message.
+	pub base: u128,
+	/// The fees charged for every byte of the encoded message.
+	pub per_byte: u128,
+}
+
+impl DeliveryRate {
+	/// The fees for delivering a message of `len` bytes.
+	pub fn fees(&self, len: usize) -> u128 {
+		self.base.saturating_add(self.per_byte.saturating_mul(len as u128))
+	}
+}
+
+/// The fees of a remote chain, as cached by governance of the local chain.
+///
+/// They are only used to quote the fees of transfers, e.g. by wallets, and never to charge any
+/// fees. They should be updated whenever the fees of the remote chain change.
+#[derive(Clone, Encode, Decode, DecodeWithMemTracking, Eq, PartialEq, RuntimeDebug, TypeInfo)]
+pub struct RemoteFeeTable {
+	/// The prices of execution, one for every fee asset accepted by the remote chain.
+	pub execution: BoundedVec<ExecutionRate, MaxExecutionRates>,
+	/// The price of delivering messages forwarded by the remote chain, if it charges for them.
+	pub delivery: Option<DeliveryRate>,
+}
+
+impl RemoteFeeTable {
+	/// The fees for executing `weight`, paid in `asset`.
+	pub fn execution_fees(&self, asset: &AssetId, weight: Weight) -> Option<Asset> {
+		self.execution
+			.iter()
+			.find(|rate| AssetId::try_from(rate.asset.clone()).as_ref() == Ok(asset))
+			.map(|rate| (asset.clone(), rate.fees(weight)).into())
+	}
+
+	/// The fees for delivering `message`, if the remote chain charges any.
+	pub fn delivery_fees(&self, message: &Xcm<()>) -> Result<Assets, ()> {
+		let Some(rate) = &self.delivery else { return Ok(Assets::new()) };
+		let asset = AssetId::try_from(rate.asset.clone())?;
+		let len = VersionedXcm::from(message.clone()).encoded_size();
+		Ok((asset, rate.fees(len)).into())
+	}
+}
+
+/// The asset `message` buys execution with, `None` if it doesn't pay for its execution.
+pub(crate) fn fee_asset(message: &Xcm<()>) -> Option<&AssetId> {
+	message.inner().iter().find_map(|instruction| match instruction {
+		BuyExecution { fees, .. } | PayFees { asset: fees } => Some(&fees.id),
+		_ => None,
+	})
+}
+
+/// The messages sent by the instructions of `message` executed on the chain at `context`, with
+/// their destination relative to that chain.
+///
+/// The instructions prepended by the executor to the forwarded messages are approximated, only
+/// their weight matters for the quote: assets selected by wildcards are left out.
+pub(crate) fn forwarded_messages(
+	message: &Xcm<()>,
+	context: &InteriorLocation,
+) -> Vec<(Location, Xcm<()>)> {
+	fn definite(assets: &AssetFilter) -> Assets {
+		match assets {
+			Definite(assets) => assets.clone(),
+			Wild(_) => Assets::new(),
+		}
+	}
+	fn prefixed(first: Instruction<()>, xcm: &Xcm<()>) -> Xcm<()> {
+		let mut message = vec![first, ClearOrigin];
+		message.extend(xcm.inner().iter().cloned());
+		Xcm(message)
+	}
+
+	message
+		.inner()
+		.iter()
+		.filter_map(|instruction| match instruction {
+			TransferReserveAsset { assets, dest, xcm } =>
+				Some((dest.clone(), prefixed(ReserveAssetDeposited(assets.clone()), xcm))),
+			DepositReserveAsset { assets, dest, xcm } =>
+				Some((dest.clone(), prefixed(ReserveAssetDeposited(definite(assets)), xcm))),
+			InitiateReserveWithdraw { assets, reserve, xcm } =>
+				Some((reserve.clone(), prefixed(WithdrawAsset(definite(assets)), xcm))),
+			InitiateTeleport { assets, dest, xcm } =>
+				Some((dest.clone(), prefixed(ReceiveTeleportedAsset(definite(assets)), xcm))),
+			InitiateTransfer { destination, remote_fees, preserve_origin, remote_xcm, .. } => {
+				let mut message = Vec::new();
+				// The executor pays for the execution on `destination` with `remote_fees`.
+				let fees = remote_fees.as_ref().and_then(|fees| match fees.inner() {
+					Definite(fees) => fees.get(0).cloned(),
+					Wild(_) => None,
+				});
+				if let Some(fees) = fees.and_then(|fees| fees.reanchored(destination, context).ok())
+				{
+					message.push(PayFees { asset: fees });
+				}
+				if !preserve_origin {
+					message.push(ClearOrigin);
+				}
+				message.extend(remote_xcm.inner().iter().cloned());
+				Some((destination.clone(), Xcm(message)))
+			},
+			_ => None,
+		})
+		.collect()
+}
@@ -0,0 +1,94 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Rolling-window limits of the assets transferred out of the chain, see
+//! [`Pallet::set_outbound_transfer_limit`](crate::Pallet::set_outbound_transfer_limit).
+
+use alloc::vec::Vec;
+use codec::{Decode, DecodeWithMemTracking, Encode, MaxEncodedLen};
+use scale_info::TypeInfo;
+use sp_runtime::{
+	traits::{AtLeast32BitUnsigned, Saturating},
+	Perquintill, RuntimeDebug, SaturatedConversion,
+};
+use xcm::prelude::*;
+
+/// The amount of an asset allowed to be transferred to a destination per period.
+///
+/// Usage decays linearly over `period`: bursts of up to `amount` are allowed and the sustained
+/// rate is `amount` per `period`.
+#[derive(
+	Clone,
+	Encode,
+	Decode,
+	DecodeWithMemTracking,
+	Eq,
+	PartialEq,
+	RuntimeDebug,
+	TypeInfo,
+	MaxEncodedLen,
+)]
+pub struct TransferLimit<BlockNumber> {
+	/// The amount in units of the asset, or in instances for non-fungible assets.
+	pub amount: u128,
+	/// The length of the rolling window, in blocks.
+	pub period: BlockNumber,
+}
+
+/// The usage of a [`TransferLimit`].
+#[derive(Clone, Default, Encode, Decode, Eq, PartialEq, RuntimeDebug, TypeInfo, MaxEncodedLen)]
+pub struct TransferWindow<BlockNumber> {
+	/// The amount transferred, as of `updated_at`.
+	pub used: u128,
+	/// The block `used` was last updated at.
+	pub updated_at: BlockNumber,
+	/// Whether transfers are suspended after the limit was exceeded.
+	pub suspended: bool,
+}
+
+impl<BlockNumber: AtLeast32BitUnsigned + Copy> TransferLimit<BlockNumber> {
+	/// The amount of `window` still counted against the limit at `now`.
+	pub fn used(&self, window: &TransferWindow<BlockNumber>, now: BlockNumber) -> u128 {
+		let elapsed = now.saturating_sub(window.updated_at);
+		if elapsed >= self.period {
+			return 0
+		}
+		let released = Perquintill::from_rational(
+			elapsed.saturated_into::<u64>(),
+			self.period.saturated_into::<u64>(),
+		)
+		.mul_floor(self.amount);
+		window.used.saturating_sub(released)
+	}
+}
+
+/// The amounts `assets` count for against the [`TransferLimit`]s of their ids.
+///
+/// Instances of non-fungible assets count for one unit of their id each.
+pub(crate) fn limited_amounts(assets: &Assets) -> Vec<(AssetId, u128)> {
+	let mut amounts: Vec<(AssetId, u128)> = Vec::new();
+	for asset in assets.inner() {
+		let amount = match asset.fun {
+			Fungible(amount) => amount,
+			NonFungible(_) => 1,
+		};
+		match amounts.iter_mut().find(|(id, _)| *id == asset.id) {
+			Some((_, total)) => *total = total.saturating_add(amount),
+			None => amounts.push((asset.id.clone(), amount)),
+		}
+	}
+	amounts
+}
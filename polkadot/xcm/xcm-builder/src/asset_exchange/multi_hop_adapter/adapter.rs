@@ -0,0 +1,350 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Multi-hop asset exchange adapter.
+
+extern crate alloc;
+use alloc::{vec, vec::Vec};
+use core::marker::PhantomData;
+use frame_support::{
+	ensure,
+	traits::{tokens::fungibles, Get},
+};
+use pallet_asset_conversion::{QuotePrice, SwapCredit};
+use xcm::prelude::*;
+use xcm_executor::{
+	traits::{AssetExchange, MatchesFungibles},
+	AssetsInHolding,
+};
+
+/// An adapter from [`pallet_asset_conversion::SwapCredit`] and
+/// [`pallet_asset_conversion::QuotePrice`] to [`xcm_executor::traits::AssetExchange`] which swaps
+/// through a route of pools when there's no pool between the two assets.
+///
+/// Routes go from the asset in `give` to the asset in `want` through up to
+/// `AssetConversion::max_path_len() - 2` assets from `Intermediates` (e.g. the relay chain token),
+/// each visited at most once. Of all routes whose pools exist, the one giving the most of `want`
+/// is used if the exchange is `maximal`, and the one requiring the least of `give` otherwise.
+/// The swap happens along the whole route at once: the minimum of `want` when `maximal` is
+/// enforced on the final amount and nothing is exchanged if any of the hops fails.
+///
+/// The route taken is reported by the `SwapCreditExecuted` event of `pallet-asset-conversion`.
+///
+/// Quoting a route reads the reserves of each of its pools, so the weight of `ExchangeAsset` has
+/// to account for the number of routes built from `Intermediates`, which grows with the power of
+/// the number of intermediate assets a route can go through. See
+/// [`MultiHopAssetExchangeAdapter::max_quoted_pools`].
+///
+/// Like [`crate::SingleAssetExchangeAdapter`], this adapter takes just one fungible asset in
+/// `give` and allows only one fungible asset in `want`.
+pub struct MultiHopAssetExchangeAdapter<
+	AssetConversion,
+	Fungibles,
+	Matcher,
+	Intermediates,
+	AccountId,
+>(PhantomData<(AssetConversion, Fungibles, Matcher, Intermediates, AccountId)>);
+
+impl<AssetConversion, Fungibles, Matcher, Intermediates, AccountId>
+	MultiHopAssetExchangeAdapter<AssetConversion, Fungibles, Matcher, Intermediates, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediates: Get<Vec<Fungibles::AssetId>>,
+{
+	/// All the routes from `give` to `want`, starting with the direct one.
+	pub(super) fn routes(
+		give: Fungibles::AssetId,
+		want: Fungibles::AssetId,
+	) -> Vec<Vec<Fungibles::AssetId>> {
+		let max_len = <AssetConversion as SwapCredit<_>>::max_path_len() as usize;
+		let intermediates: Vec<_> = Intermediates::get()
+			.into_iter()
+			.filter(|asset| asset != &give && asset != &want)
+			.collect();
+
+		let mut routes = vec![];
+		let mut partial = vec![vec![give]];
+		while let Some(route) = partial.pop() {
+			if route.len() + 1 > max_len {
+				continue
+			}
+			let mut complete = route.clone();
+			complete.push(want.clone());
+			routes.push(complete);
+			for intermediate in intermediates.iter().filter(|asset| !route.contains(asset)) {
+				let mut extended = route.clone();
+				extended.push(intermediate.clone());
+				partial.push(extended);
+			}
+		}
+		routes.sort_by_key(|route| route.len());
+		routes
+	}
+
+	/// The number of pools whose reserves are read to quote all the routes of an exchange, in the
+	/// worst case where neither of the exchanged assets is one of `Intermediates`.
+	///
+	/// With `k` intermediate assets, there are `k! / (k - j)!` routes going through `j` of them,
+	/// each of them swapping through `j + 1` pools, for every `j` up to
+	/// `AssetConversion::max_path_len() - 2`.
+	pub fn max_quoted_pools() -> u64 {
+		let max_len = <AssetConversion as SwapCredit<_>>::max_path_len() as u64;
+		let intermediates = Intermediates::get().len() as u64;
+
+		let mut pools = 0u64;
+		let mut routes = 1u64;
+		let mut via = 0u64;
+		while via <= intermediates && via + 2 <= max_len {
+			pools = pools.saturating_add(routes.saturating_mul(via + 1));
+			routes = routes.saturating_mul(intermediates - via);
+			via += 1;
+		}
+		pools
+	}
+
+	/// The amount of the last asset of `route` obtained by swapping `amount_in` of the first.
+	fn quote_exact_in(route: &[Fungibles::AssetId], amount_in: u128) -> Option<u128> {
+		route.windows(2).try_fold(amount_in, |amount, hop| {
+			<AssetConversion as QuotePrice>::quote_price_exact_tokens_for_tokens(
+				hop[0].clone(),
+				hop[1].clone(),
+				amount,
+				true, // Include fee.
+			)
+		})
+	}
+
+	/// The amount of the first asset of `route` required to obtain `amount_out` of the last.
+	fn quote_exact_out(route: &[Fungibles::AssetId], amount_out: u128) -> Option<u128> {
+		route.windows(2).rev().try_fold(amount_out, |amount, hop| {
+			<AssetConversion as QuotePrice>::quote_price_tokens_for_exact_tokens(
+				hop[0].clone(),
+				hop[1].clone(),
+				amount,
+				true, // Include fee.
+			)
+		})
+	}
+
+	/// The best route for the exchange, along with the amount of `want` it gives if `maximal`, or
+	/// the amount of `give` it requires otherwise.
+	fn best_route(
+		give: Fungibles::AssetId,
+		give_amount: u128,
+		want: Fungibles::AssetId,
+		want_amount: u128,
+		maximal: bool,
+	) -> Option<(Vec<Fungibles::AssetId>, u128)> {
+		let routes = Self::routes(give, want).into_iter();
+		if maximal {
+			routes
+				.filter_map(|route| Some((Self::quote_exact_in(&route, give_amount)?, route)))
+				// Routes are sorted by length, so ties go to the shortest one.
+				.fold(None, |best: Option<(u128, Vec<_>)>, (amount, route)| match best {
+					Some((best_amount, _)) if best_amount >= amount => best,
+					_ => Some((amount, route)),
+				})
+				.map(|(amount, route)| (route, amount))
+		} else {
+			routes
+				.filter_map(|route| Some((Self::quote_exact_out(&route, want_amount)?, route)))
+				// `min_by_key` returns the first minimum, i.e. the shortest route.
+				.min_by_key(|(amount, _)| *amount)
+				.map(|(amount, route)| (route, amount))
+		}
+	}
+}
+
+impl<AssetConversion, Fungibles, Matcher, Intermediates, AccountId> AssetExchange
+	for MultiHopAssetExchangeAdapter<AssetConversion, Fungibles, Matcher, Intermediates, AccountId>
+where
+	AssetConversion: SwapCredit<
+			AccountId,
+			Balance = u128,
+			AssetKind = Fungibles::AssetId,
+			Credit = fungibles::Credit<AccountId, Fungibles>,
+		> + QuotePrice<Balance = u128, AssetKind = Fungibles::AssetId>,
+	Fungibles: fungibles::Balanced<AccountId, Balance = u128>,
+	Matcher: MatchesFungibles<Fungibles::AssetId, Fungibles::Balance>,
+	Intermediates: Get<Vec<Fungibles::AssetId>>,
+{
+	fn exchange_asset(
+		_: Option<&Location>,
+		give: AssetsInHolding,
+		want: &Assets,
+		maximal: bool,
+	) -> Result<AssetsInHolding, AssetsInHolding> {
+		let mut give_iter = give.fungible_assets_iter();
+		let give_asset = give_iter.next().ok_or_else(|| {
+			tracing::trace!(
+				target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+				?give, "No fungible asset was in `give`.",
+			);
+			give.clone()
+		})?;
+		ensure!(give_iter.next().is_none(), give.clone()); // We only support 1 asset in `give`.
+		ensure!(give.non_fungible_assets_iter().next().is_none(), give.clone()); // We don't allow non-fungible assets.
+		ensure!(want.len() == 1, give.clone()); // We only support 1 asset in `want`.
+		let want_asset = want.get(0).ok_or_else(|| give.clone())?;
+		let (give_asset_id, give_amount) =
+			Matcher::matches_fungibles(&give_asset).map_err(|error| {
+				tracing::trace!(
+					target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+					?give_asset,
+					?error,
+					"Could not map XCM asset give to FRAME asset.",
+				);
+				give.clone()
+			})?;
+		let (want_asset_id, want_amount) =
+			Matcher::matches_fungibles(&want_asset).map_err(|error| {
+				tracing::trace!(
+					target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+					?want_asset,
+					?error,
+					"Could not map XCM asset want to FRAME asset.",
+				);
+				give.clone()
+			})?;
+
+		let (route, _) = Self::best_route(
+			give_asset_id.clone(),
+			give_amount,
+			want_asset_id,
+			want_amount,
+			maximal,
+		)
+		.ok_or_else(|| {
+			tracing::debug!(
+				target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+				?give_asset,
+				?want_asset,
+				"No route between the assets",
+			);
+			give.clone()
+		})?;
+		tracing::trace!(
+			target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+			?route,
+			?maximal,
+			"Swapping along route",
+		);
+
+		// We have to do this to convert the XCM assets into credit the pools can use.
+		let credit_in = Fungibles::issue(give_asset_id, give_amount);
+
+		// Do the swap.
+		let (credit_out, maybe_credit_change) = if maximal {
+			// If `maximal`, then we swap exactly `credit_in` to get as much of `want_asset_id` as
+			// we can, with a minimum of `want_amount`.
+			let credit_out = <AssetConversion as SwapCredit<_>>::swap_exact_tokens_for_tokens(
+				route,
+				credit_in,
+				Some(want_amount),
+			)
+			.map_err(|(credit_in, error)| {
+				tracing::debug!(
+					target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+					?error,
+					"Could not perform the swap",
+				);
+				drop(credit_in);
+				give.clone()
+			})?;
+
+			// We don't have leftover assets if exchange was maximal.
+			(credit_out, None)
+		} else {
+			// If `minimal`, then we swap as little of `credit_in` as we can to get exactly
+			// `want_amount` of `want_asset_id`.
+			let (credit_out, credit_change) =
+				<AssetConversion as SwapCredit<_>>::swap_tokens_for_exact_tokens(
+					route,
+					credit_in,
+					want_amount,
+				)
+				.map_err(|(credit_in, error)| {
+					tracing::debug!(
+						target: "xcm::MultiHopAssetExchangeAdapter::exchange_asset",
+						?error,
+						"Could not perform the swap",
+					);
+					drop(credit_in);
+					give.clone()
+				})?;
+
+			(credit_out, if credit_change.peek() > 0 { Some(credit_change) } else { None })
+		};
+
+		// We create an `AssetsInHolding` instance by putting in the resulting asset
+		// of the exchange.
+		let resulting_asset: Asset = (want_asset.id.clone(), credit_out.peek()).into();
+		let mut result: AssetsInHolding = resulting_asset.into();
+
+		// If we have some leftover assets from the exchange, also put them in the result.
+		if let Some(credit_change) = maybe_credit_change {
+			let leftover_asset: Asset = (give_asset.id.clone(), credit_change.peek()).into();
+			result.subsume(leftover_asset);
+		}
+
+		Ok(result)
+	}
+
+	fn quote_exchange_price(give: &Assets, want: &Assets, maximal: bool) -> Option<Assets> {
+		if give.len() != 1 || want.len() != 1 {
+			return None
+		} // We only support 1 asset in `give` or `want`.
+		let give_asset = give.get(0)?;
+		let want_asset = want.get(0)?;
+		// We first match both XCM assets to the asset ID types `AssetConversion` can handle.
+		let (give_asset_id, give_amount) = Matcher::matches_fungibles(give_asset)
+			.map_err(|error| {
+				tracing::trace!(
+					target: "xcm::MultiHopAssetExchangeAdapter::quote_exchange_price",
+					?give_asset,
+					?error,
+					"Could not map XCM asset to FRAME asset.",
+				);
+			})
+			.ok()?;
+		let (want_asset_id, want_amount) = Matcher::matches_fungibles(want_asset)
+			.map_err(|error| {
+				tracing::trace!(
+					target: "xcm::MultiHopAssetExchangeAdapter::quote_exchange_price",
+					?want_asset,
+					?error,
+					"Could not map XCM asset to FRAME asset.",
+				);
+			})
+			.ok()?;
+		let (_, amount) =
+			Self::best_route(give_asset_id, give_amount, want_asset_id, want_amount, maximal)?;
+		if maximal {
+			// The amount of `want` resulting from swapping `give`.
+			Some((want_asset.id.clone(), amount).into())
+		} else {
+			// The `give` amount required to obtain `want`.
+			Some((give_asset.id.clone(), amount).into())
+		}
+	}
+}
@@ -0,0 +1,286 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Tests for the [`MultiHopAssetExchangeAdapter`] type.
+
+use super::MultiHopAssetExchangeAdapter;
+use crate::asset_exchange::single_asset_adapter::mock::*;
+use frame_support::{
+	assert_ok, parameter_types,
+	traits::{fungible::NativeOrWithId, fungibles::Mutate},
+};
+use xcm::prelude::*;
+use xcm_executor::{traits::AssetExchange, AssetsInHolding};
+
+parameter_types! {
+	pub Intermediates: Vec<NativeOrWithId<u32>> = vec![NativeOrWithId::Native];
+}
+
+type MultiHopExchanger = MultiHopAssetExchangeAdapter<
+	AssetConversion,
+	NativeAndAssets,
+	PoolAssetsMatcher,
+	Intermediates,
+	AccountId,
+>;
+
+/// Same as [`MultiHopExchanger`], but without intermediate assets to route through.
+type DirectExchanger = MultiHopAssetExchangeAdapter<
+	AssetConversion,
+	NativeAndAssets,
+	PoolAssetsMatcher,
+	(),
+	AccountId,
+>;
+
+/// The mock only creates a pool between the native token and asset 1. This adds asset 2, with a
+/// pool against the native token, and asset 3, without any pool.
+fn new_multi_hop_test_ext() -> sp_io::TestExternalities {
+	let mut ext = new_test_ext();
+	ext.execute_with(|| {
+		let owner = 0;
+		for id in [2, 3] {
+			assert_ok!(AssetsPallet::force_create(RuntimeOrigin::root(), id, owner, false, 1));
+			assert_ok!(AssetsPallet::mint_into(id, &owner, INITIAL_BALANCE));
+		}
+		assert_ok!(AssetConversion::create_pool(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+		));
+		assert_ok!(AssetConversion::add_liquidity(
+			RuntimeOrigin::signed(owner),
+			Box::new(NativeOrWithId::Native),
+			Box::new(NativeOrWithId::WithId(2)),
+			50_000_000,
+			200_000_000,
+			0,
+			0,
+			owner,
+		));
+	});
+	ext
+}
+
+fn asset(id: u128, amount: u128) -> Asset {
+	([PalletInstance(2), GeneralIndex(id)], amount).into()
+}
+
+// ========== Happy path ==========
+
+#[test]
+fn maximal_exchange_through_intermediate() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let quoted = quote(&asset(1, 10_000_000), &asset(2, 1), true).unwrap();
+		let assets = MultiHopExchanger::exchange_asset(
+			None,
+			vec![asset(1, 10_000_000)].into(),
+			&vec![asset(2, 1)].into(),
+			true, // Maximal
+		)
+		.unwrap();
+		let amounts = fungible_amounts(&assets);
+		assert_eq!(amounts, vec![get_amount(&quoted)]);
+		assert_eq!(last_swap_route(), vec![id(1), NativeOrWithId::Native, id(2)]);
+	});
+}
+
+#[test]
+fn minimal_exchange_through_intermediate() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let quoted = quote(&asset(1, 10_000_000), &asset(2, 2_000_000), false).unwrap();
+		let assets = MultiHopExchanger::exchange_asset(
+			None,
+			vec![asset(1, 10_000_000)].into(),
+			&vec![asset(2, 2_000_000)].into(),
+			false, // Minimal
+		)
+		.unwrap();
+		// We get exactly what we want, and the change of what we gave.
+		assert_eq!(assets.fungible.get(&asset(2, 0).id), Some(&2_000_000));
+		assert_eq!(assets.fungible.get(&asset(1, 0).id), Some(&(10_000_000 - get_amount(&quoted))),);
+		assert_eq!(last_swap_route(), vec![id(1), NativeOrWithId::Native, id(2)]);
+	});
+}
+
+#[test]
+fn direct_pool_is_used_when_it_exists() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let direct = quote(&asset(1, 10_000_000), &(Here, 1).into(), true);
+		assert_eq!(
+			direct,
+			DirectExchanger::quote_exchange_price(
+				&vec![asset(1, 10_000_000)].into(),
+				&vec![(Here, 1).into()].into(),
+				true,
+			)
+		);
+		assert_ok!(MultiHopExchanger::exchange_asset(
+			None,
+			vec![asset(1, 10_000_000)].into(),
+			&vec![(Here, 1).into()].into(),
+			true, // Maximal
+		));
+		assert_eq!(last_swap_route(), vec![id(1), NativeOrWithId::Native]);
+	});
+}
+
+// ========== Unhappy path ==========
+
+#[test]
+fn no_route_without_intermediates() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let give: AssetsInHolding = vec![asset(1, 10_000_000)].into();
+		assert_eq!(
+			DirectExchanger::exchange_asset(None, give.clone(), &vec![asset(2, 1)].into(), true),
+			Err(give),
+		);
+		assert!(DirectExchanger::quote_exchange_price(
+			&vec![asset(1, 10_000_000)].into(),
+			&vec![asset(2, 1)].into(),
+			true,
+		)
+		.is_none());
+	});
+}
+
+#[test]
+fn no_route_without_pools() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let give: AssetsInHolding = vec![asset(1, 10_000_000)].into();
+		assert_eq!(
+			MultiHopExchanger::exchange_asset(None, give.clone(), &vec![asset(3, 1)].into(), true),
+			Err(give),
+		);
+	});
+}
+
+#[test]
+fn maximal_exchange_respects_minimum_out() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let quoted = quote(&asset(1, 10_000_000), &asset(2, 1), true).unwrap();
+		let give: AssetsInHolding = vec![asset(1, 10_000_000)].into();
+		assert_eq!(
+			MultiHopExchanger::exchange_asset(
+				None,
+				give.clone(),
+				&vec![asset(2, get_amount(&quoted) + 1)].into(),
+				true, // Maximal
+			),
+			Err(give),
+		);
+	});
+}
+
+#[test]
+fn minimal_exchange_fails_when_give_is_not_enough() {
+	new_multi_hop_test_ext().execute_with(|| {
+		let give: AssetsInHolding = vec![asset(1, 1_000)].into();
+		assert_eq!(
+			MultiHopExchanger::exchange_asset(
+				None,
+				give.clone(),
+				&vec![asset(2, 2_000_000)].into(),
+				false, // Minimal
+			),
+			Err(give),
+		);
+	});
+}
+
+#[test]
+fn max_quoted_pools_counts_the_pools_of_all_routes() {
+	parameter_types! {
+		pub ManyIntermediates: Vec<NativeOrWithId<u32>> =
+			vec![NativeOrWithId::Native, NativeOrWithId::WithId(2), NativeOrWithId::WithId(3)];
+	}
+	type ManyHopsExchanger = MultiHopAssetExchangeAdapter<
+		AssetConversion,
+		NativeAndAssets,
+		PoolAssetsMatcher,
+		ManyIntermediates,
+		AccountId,
+	>;
+	let quoted_pools = |routes: Vec<Vec<NativeOrWithId<u32>>>| {
+		routes.iter().map(|route| route.len() as u64 - 1).sum::<u64>()
+	};
+
+	assert_eq!(DirectExchanger::max_quoted_pools(), 1);
+	assert_eq!(
+		DirectExchanger::max_quoted_pools(),
+		quoted_pools(DirectExchanger::routes(id(1), id(4)))
+	);
+	// The direct route and the one through the native token.
+	assert_eq!(MultiHopExchanger::max_quoted_pools(), 3);
+	assert_eq!(
+		MultiHopExchanger::max_quoted_pools(),
+		quoted_pools(MultiHopExchanger::routes(id(1), id(4)))
+	);
+	// The direct route and one through each of the intermediate assets, as paths are limited to
+	// three assets.
+	assert_eq!(ManyHopsExchanger::max_quoted_pools(), 7);
+	assert_eq!(
+		ManyHopsExchanger::max_quoted_pools(),
+		quoted_pools(ManyHopsExchanger::routes(id(1), id(4)))
+	);
+	// Fewer routes if one of the assets is an intermediate asset.
+	assert!(quoted_pools(ManyHopsExchanger::routes(id(1), id(2))) < 7);
+}
+
+// ========== Helper functions ==========
+
+fn id(id: u32) -> NativeOrWithId<u32> {
+	NativeOrWithId::WithId(id)
+}
+
+fn get_amount(assets: &Assets) -> u128 {
+	let Fungible(amount) = assets.get(0).unwrap().fun else {
+		unreachable!("Asset should be fungible");
+	};
+	amount
+}
+
+fn fungible_amounts(assets: &AssetsInHolding) -> Vec<u128> {
+	assets
+		.fungible_assets_iter()
+		.map(|asset| match asset.fun {
+			Fungible(amount) => amount,
+			NonFungible(_) => unreachable!("Asset should be fungible"),
+		})
+		.collect()
+}
+
+fn last_swap_route() -> Vec<NativeOrWithId<u32>> {
+	System::events()
+		.into_iter()
+		.rev()
+		.find_map(|record| match record.event {
+			RuntimeEvent::AssetConversion(pallet_asset_conversion::Event::SwapCreditExecuted {
+				path,
+				..
+			}) => Some(path.into_iter().map(|(asset, _)| asset).collect()),
+			_ => None,
+		})
+		.expect("a swap was executed")
+}
+
+fn quote(asset_1: &Asset, asset_2: &Asset, maximal: bool) -> Option<Assets> {
+	MultiHopExchanger::quote_exchange_price(
+		&asset_1.clone().into(),
+		&asset_2.clone().into(),
+		maximal,
+	)
+}
@@ -204,16 +204,18 @@ impl MaybeEquivalence<Location, NativeOrWithId<u32>> for LocationToAssetId {
 	}
 }
 
+pub type PoolAssetsMatcher = MatchedConvertedConcreteId<
+	NativeOrWithId<u32>,
+	Balance,
+	(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
+	LocationToAssetId,
+	TryConvertInto,
+>;
+
 pub type PoolAssetsExchanger = crate::SingleAssetExchangeAdapter<
 	AssetConversion,
 	NativeAndAssets,
-	MatchedConvertedConcreteId<
-		NativeOrWithId<u32>,
-		Balance,
-		(StartsWith<TrustBackedAssetsPalletLocation>, Equals<HereLocation>),
-		LocationToAssetId,
-		TryConvertInto,
-	>,
+	PoolAssetsMatcher,
 	AccountId,
 >;
 
@@ -251,6 +253,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type TransferLimiter = ();
 }
 
 /// Simple converter from a [`Location`] with an [`AccountIndex64`] junction and no parent to a
@@ -37,7 +37,7 @@ pub use asset_conversion::{
 };
 
 mod asset_exchange;
-pub use asset_exchange::SingleAssetExchangeAdapter;
+pub use asset_exchange::{MultiHopAssetExchangeAdapter, SingleAssetExchangeAdapter};
 
 mod barriers;
 pub use barriers::{
@@ -770,6 +770,7 @@ impl Config for TestConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = ();
+	type TransferLimiter = ();
 }
 
 pub fn fungible_multi_asset(location: Location, amount: u128) -> Asset {
@@ -0,0 +1,276 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! A chain taking part in a dry-run.
+
+use crate::{events::EventDecoder, Error, ExecutionFees};
+use codec::{Decode, Encode};
+use frame_system::AccountInfo;
+use pallet_balances::AccountData;
+use sc_executor::WasmExecutor;
+use sp_core::{
+	crypto::AccountId32,
+	hashing::{blake2_128, blake2_256, twox_128},
+	storage::well_known_keys,
+	traits::{CallContext, CodeExecutor, RuntimeCode, WrappedRuntimeCode},
+};
+use sp_externalities::Externalities;
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::TestExternalities;
+use sp_version::{ApiId, RuntimeVersion};
+use std::borrow::Cow;
+use xcm::prelude::*;
+use xcm_runtime_apis::{
+	dry_run::{self, CallDryRunEffects, XcmDryRunEffects},
+	fees::Error as FeesError,
+};
+
+const LOG_TARGET: &str = "xcm::dry-run";
+
+/// The host functions provided to the runtimes.
+///
+/// A runtime calling any other host function fails once it does so.
+pub type HostFunctions = (
+	sp_io::SubstrateHostFunctions,
+	cumulus_primitives_proof_size_hostfunction::storage_proof_size::HostFunctions,
+);
+
+/// A chain with its runtime and state.
+pub struct Chain {
+	name: String,
+	location: InteriorLocation,
+	ext: TestExternalities<BlakeTwo256>,
+	code: Vec<u8>,
+	code_hash: Vec<u8>,
+	heap_pages: Option<u64>,
+	executor: WasmExecutor<HostFunctions>,
+	events: Option<EventDecoder>,
+}
+
+impl Chain {
+	/// Create a new chain, executing the runtime found in its state.
+	///
+	/// `location` is the universal location of the chain, e.g.
+	/// `[GlobalConsensus(Polkadot), Parachain(1000)]`. It is used to find the destination of the
+	/// forwarded messages.
+	pub fn new(
+		name: impl Into<String>,
+		location: impl Into<InteriorLocation>,
+		mut ext: TestExternalities<BlakeTwo256>,
+	) -> Self {
+		let (code, heap_pages) = {
+			let mut ext = ext.ext();
+			let code = ext.storage(well_known_keys::CODE).unwrap_or_default();
+			let heap_pages = ext
+				.storage(well_known_keys::HEAP_PAGES)
+				.and_then(|pages| u64::decode(&mut &pages[..]).ok());
+			(code, heap_pages)
+		};
+
+		Self {
+			name: name.into(),
+			location: location.into(),
+			ext,
+			code_hash: blake2_256(&code).to_vec(),
+			code,
+			heap_pages,
+			executor: WasmExecutor::builder().with_allow_missing_host_functions(true).build(),
+			events: None,
+		}
+	}
+
+	/// Execute `code` instead of the runtime found in the state.
+	pub fn with_code(mut self, code: Vec<u8>) -> Self {
+		self.code_hash = blake2_256(&code).to_vec();
+		self.code = code;
+		self.events = None;
+		self
+	}
+
+	/// The name of the chain.
+	pub fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The universal location of the chain.
+	pub fn location(&self) -> &InteriorLocation {
+		&self.location
+	}
+
+	/// The state of the chain, including the changes of all dry-runs so far.
+	pub fn ext(&mut self) -> &mut TestExternalities<BlakeTwo256> {
+		&mut self.ext
+	}
+
+	/// Call `method` of the runtime with the SCALE encoded `data`.
+	///
+	/// The changes made to the state are kept.
+	pub fn call(&mut self, method: &'static str, data: &[u8]) -> Result<Vec<u8>, Error> {
+		if self.code.is_empty() {
+			return Err(Error::RuntimeCall {
+				chain: self.name.clone(),
+				method,
+				error: "No runtime code found".into(),
+			})
+		}
+
+		let runtime_code = RuntimeCode {
+			code_fetcher: &WrappedRuntimeCode(Cow::Borrowed(&self.code)),
+			heap_pages: self.heap_pages,
+			hash: self.code_hash.clone(),
+		};
+		self.executor
+			.call(&mut self.ext.ext(), &runtime_code, method, data, CallContext::Offchain)
+			.0
+			.map_err(|error| Error::RuntimeCall {
+				chain: self.name.clone(),
+				method,
+				error: error.to_string(),
+			})
+	}
+
+	/// Call `method` of the runtime and decode its result.
+	pub fn call_decoded<R: Decode>(
+		&mut self,
+		method: &'static str,
+		data: &[u8],
+	) -> Result<R, Error> {
+		let result = self.call(method, data)?;
+		R::decode(&mut &result[..]).map_err(|error| Error::Decode {
+			chain: self.name.clone(),
+			method,
+			error,
+		})
+	}
+
+	/// The version of the runtime API with the given `id`, if the runtime implements it.
+	pub(crate) fn api_version(&mut self, id: &ApiId) -> Result<Option<u32>, Error> {
+		let version: RuntimeVersion = self.call_decoded("Core_version", &[])?;
+		Ok(version.api_version(id))
+	}
+
+	/// The decoder for the events of the runtime, built from its metadata on first use.
+	pub(crate) fn event_decoder(&mut self) -> Result<&EventDecoder, Error> {
+		if self.events.is_none() {
+			let metadata: Option<Vec<u8>> =
+				self.call_decoded("Metadata_metadata_at_version", &15u32.encode())?;
+			let decoder = metadata
+				.and_then(|metadata| EventDecoder::from_metadata(&metadata))
+				.ok_or_else(|| Error::Metadata(self.name.clone()))?;
+			self.events = Some(decoder);
+		}
+		Ok(self.events.as_ref().expect("Set above; qed"))
+	}
+
+	/// Call `DryRunApi::dry_run_call` with the SCALE encoded arguments.
+	pub(crate) fn dry_run_call(&mut self, data: &[u8]) -> Result<CallDryRunEffects<String>, Error> {
+		self.call_with_events("DryRunApi_dry_run_call", data, EventDecoder::decode_call_effects)
+	}
+
+	/// Call `DryRunApi::dry_run_xcm` for `message` sent by `origin`.
+	pub(crate) fn dry_run_xcm(
+		&mut self,
+		origin: &Location,
+		message: &Xcm<()>,
+	) -> Result<XcmDryRunEffects<String>, Error> {
+		let data =
+			(VersionedLocation::from(origin.clone()), VersionedXcm::from(message.clone())).encode();
+		self.call_with_events("DryRunApi_dry_run_xcm", &data, EventDecoder::decode_xcm_effects)
+	}
+
+	/// Call a runtime API returning a `Result` whose `Ok` value contains `RuntimeEvent`s.
+	fn call_with_events<R>(
+		&mut self,
+		method: &'static str,
+		data: &[u8],
+		decode: impl FnOnce(
+			&EventDecoder,
+			&mut &[u8],
+		) -> Result<Result<R, dry_run::Error>, codec::Error>,
+	) -> Result<R, Error> {
+		let output = self.call(method, data)?;
+		match decode(self.event_decoder()?, &mut &output[..]) {
+			Ok(Ok(result)) => Ok(result),
+			Ok(Err(error)) =>
+				Err(Error::Api { chain: self.name.clone(), method, error: format!("{:?}", error) }),
+			Err(error) => Err(Error::Decode { chain: self.name.clone(), method, error }),
+		}
+	}
+
+	/// Quote the weight of `message` and the fees for it, in the asset `message` buys execution
+	/// with or in the native asset.
+	pub(crate) fn execution_fees(&mut self, message: &Xcm<()>) -> Result<ExecutionFees, FeesError> {
+		let weight = self.query_fees::<Weight>(
+			"XcmPaymentApi_query_xcm_weight",
+			&VersionedXcm::from(message.clone()).encode(),
+		)?;
+		let asset = fee_asset(message);
+		let fees = self
+			.query_fees::<u128>(
+				"XcmPaymentApi_query_weight_to_asset_fee",
+				&(weight, VersionedAssetId::from(asset.clone())).encode(),
+			)
+			.map(|amount| (asset, amount).into());
+		Ok(ExecutionFees { weight, fees })
+	}
+
+	/// Quote the fees for delivering `message` to `destination`.
+	pub(crate) fn delivery_fees(
+		&mut self,
+		destination: &Location,
+		message: &Xcm<()>,
+	) -> Result<Assets, FeesError> {
+		let fees = self.query_fees::<VersionedAssets>(
+			"XcmPaymentApi_query_delivery_fees",
+			&(VersionedLocation::from(destination.clone()), VersionedXcm::from(message.clone()))
+				.encode(),
+		)?;
+		fees.try_into().map_err(|_| FeesError::VersionedConversionFailed)
+	}
+
+	/// Call a method of the `XcmPaymentApi`, treating a failing call as an unimplemented API.
+	fn query_fees<R: Decode>(&mut self, method: &'static str, data: &[u8]) -> Result<R, FeesError> {
+		self.call_decoded::<Result<R, FeesError>>(method, data).unwrap_or_else(|e| {
+			log::debug!(target: LOG_TARGET, "{}", e);
+			Err(FeesError::Unimplemented)
+		})
+	}
+
+	/// The free balance of `account`, as stored by `frame_system` for `pallet_balances`.
+	///
+	/// Returns `None` if the account data has a different layout.
+	pub fn free_balance(&mut self, account: &AccountId32) -> Option<u128> {
+		let account: &[u8] = account.as_ref();
+		let key = [&twox_128(b"System")[..], &twox_128(b"Account"), &blake2_128(account), account]
+			.concat();
+		let Some(info) = self.ext.ext().storage(&key) else { return Some(0) };
+		AccountInfo::<u32, AccountData<u128>>::decode(&mut &info[..])
+			.ok()
+			.map(|info| info.data.free)
+	}
+}
+
+/// The asset used to pay for the execution of `message`, the native asset if it doesn't pay.
+fn fee_asset(message: &Xcm<()>) -> AssetId {
+	message
+		.inner()
+		.iter()
+		.find_map(|instruction| match instruction {
+			BuyExecution { fees, .. } | PayFees { asset: fees } => Some(fees.id.clone()),
+			_ => None,
+		})
+		.unwrap_or(AssetId(Location::here()))
+}
@@ -0,0 +1,308 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Drive a dry-run across several chains.
+
+use crate::{Balance, Chain, Error, Forwarded, Hop, Outcome, Trace};
+use codec::Encode;
+use sp_api::RuntimeApiInfo;
+use sp_core::crypto::AccountId32;
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+use xcm::prelude::*;
+use xcm_runtime_apis::dry_run::DryRunApi;
+
+/// Any block type, only needed to get the id of the runtime APIs.
+type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+/// The location of `to` relative to `from`, both being universal locations.
+pub fn relative_location(from: &InteriorLocation, to: &InteriorLocation) -> Location {
+	let common = from.iter().zip(to.iter()).take_while(|(a, b)| a == b).count();
+	let mut location = Location::new((from.len() - common) as u8, Here);
+	for junction in to.iter().skip(common) {
+		location
+			.push_interior(junction.clone())
+			.expect("`to` has no more than `MAX_JUNCTIONS` junctions; qed");
+	}
+	location
+}
+
+/// The universal location of `destination` as seen from `from`, if it is within the same
+/// universe.
+fn universal_location(from: &InteriorLocation, destination: &Location) -> Option<InteriorLocation> {
+	Location::new(0, from.clone())
+		.appended_with(destination.clone())
+		.ok()
+		.filter(|location| location.parent_count() == 0)
+		.map(|location| location.interior)
+}
+
+/// Dry-runs calls and XCM programs across several [`Chain`]s, following every forwarded message.
+pub struct Driver {
+	chains: Vec<Chain>,
+	watched: Vec<(usize, AccountId32)>,
+	max_hops: usize,
+}
+
+impl Default for Driver {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl Driver {
+	/// Create a new driver without any chains.
+	pub fn new() -> Self {
+		Self { chains: Vec::new(), watched: Vec::new(), max_hops: 32 }
+	}
+
+	/// Add a chain, to which forwarded messages can be routed.
+	pub fn with_chain(mut self, chain: Chain) -> Self {
+		self.chains.push(chain);
+		self
+	}
+
+	/// Report the free balance of `account` on `chain` before and after the dry-run.
+	pub fn watch(mut self, chain: &str, account: AccountId32) -> Result<Self, Error> {
+		let index = self.chain_index(chain)?;
+		self.watched.push((index, account));
+		Ok(self)
+	}
+
+	/// The maximal number of hops of a dry-run, to stop messages bouncing between chains forever.
+	pub fn max_hops(mut self, max_hops: usize) -> Self {
+		self.max_hops = max_hops;
+		self
+	}
+
+	/// The chain with the given name.
+	pub fn chain(&mut self, name: &str) -> Option<&mut Chain> {
+		self.chains.iter_mut().find(|chain| chain.name() == name)
+	}
+
+	/// Dry-run `call` dispatched by `origin` on `chain`, and follow all forwarded messages.
+	///
+	/// `origin` and `call` are the SCALE encoded `OriginCaller` and `RuntimeCall` of the chain.
+	pub fn dry_run_call(
+		&mut self,
+		chain: &str,
+		origin: &[u8],
+		call: &[u8],
+	) -> Result<Trace, Error> {
+		let index = self.chain_index(chain)?;
+		let before = self.balances();
+
+		let api_version = self.chains[index]
+			.api_version(&<dyn DryRunApi<Block, (), (), ()> as RuntimeApiInfo>::ID)?;
+		let mut data = [origin, call].concat();
+		// Version 1 doesn't take the version of the forwarded messages.
+		if api_version.map_or(true, |version| version >= 2) {
+			XCM_VERSION.encode_to(&mut data);
+		}
+
+		let chain = &mut self.chains[index];
+		let effects = chain.dry_run_call(&data)?;
+		let execution_fees = effects.local_xcm.map(|local_xcm| {
+			let local_xcm = Xcm::<()>::try_from(local_xcm)
+				.map_err(|_| xcm_runtime_apis::fees::Error::VersionedConversionFailed)?;
+			chain.execution_fees(&local_xcm)
+		});
+		let hop = Hop {
+			chain: chain.name().into(),
+			origin: Location::here(),
+			outcome: Outcome::Call(effects.execution_result),
+			events: effects.emitted_events,
+			execution_fees,
+			forwarded: self.forwarded(index, effects.forwarded_xcms)?,
+		};
+
+		self.follow(index, hop, before)
+	}
+
+	/// Dry-run `message` sent by `origin` to `chain`, and follow all forwarded messages.
+	///
+	/// `origin` is relative to `chain`.
+	pub fn dry_run_xcm(
+		&mut self,
+		chain: &str,
+		origin: Location,
+		message: Xcm<()>,
+	) -> Result<Trace, Error> {
+		let index = self.chain_index(chain)?;
+		let before = self.balances();
+		let hop = self.xcm_hop(index, origin, message)?;
+		self.follow(index, hop, before)
+	}
+
+	/// Replay the messages forwarded by `first` and all following hops on their destination.
+	fn follow(
+		&mut self,
+		index: usize,
+		first: Hop,
+		before: Vec<Option<u128>>,
+	) -> Result<Trace, Error> {
+		let mut hops = vec![(index, first)];
+		let mut next = 0;
+
+		while next < hops.len() {
+			let sender = hops[next].0;
+			for i in 0..hops[next].1.forwarded.len() {
+				let destination = &hops[next].1.forwarded[i].destination;
+				let Some(receiver) = self.destination_chain(sender, destination) else {
+					log::debug!(
+						target: "xcm::dry-run",
+						"Message to {:?} from {} is not followed",
+						destination,
+						self.chains[sender].name(),
+					);
+					continue
+				};
+				if hops.len() >= self.max_hops {
+					return Err(Error::TooManyHops(self.max_hops))
+				}
+
+				let origin = relative_location(
+					self.chains[receiver].location(),
+					self.chains[sender].location(),
+				);
+				let message = hops[next].1.forwarded[i].message.clone();
+				let hop = self.xcm_hop(receiver, origin, message)?;
+				hops[next].1.forwarded[i].hop = Some(hops.len());
+				hops.push((receiver, hop));
+			}
+			next += 1;
+		}
+
+		let after = self.balances();
+		let balances = self
+			.watched
+			.iter()
+			.zip(before.into_iter().zip(after))
+			.map(|((index, account), (before, after))| Balance {
+				chain: self.chains[*index].name().into(),
+				account: account.clone(),
+				before,
+				after,
+			})
+			.collect();
+
+		Ok(Trace { hops: hops.into_iter().map(|(_, hop)| hop).collect(), balances })
+	}
+
+	fn xcm_hop(&mut self, index: usize, origin: Location, message: Xcm<()>) -> Result<Hop, Error> {
+		let chain = &mut self.chains[index];
+		let effects = chain.dry_run_xcm(&origin, &message)?;
+		let execution_fees = Some(chain.execution_fees(&message));
+		Ok(Hop {
+			chain: chain.name().into(),
+			origin,
+			outcome: Outcome::Xcm(effects.execution_result),
+			events: effects.emitted_events,
+			execution_fees,
+			forwarded: self.forwarded(index, effects.forwarded_xcms)?,
+		})
+	}
+
+	/// Convert the messages forwarded by the chain at `index` and quote their delivery fees.
+	fn forwarded(
+		&mut self,
+		index: usize,
+		forwarded_xcms: Vec<(VersionedLocation, Vec<VersionedXcm<()>>)>,
+	) -> Result<Vec<Forwarded>, Error> {
+		let chain = &mut self.chains[index];
+		let mut forwarded = Vec::new();
+		for (destination, messages) in forwarded_xcms {
+			let destination = Location::try_from(destination)
+				.map_err(|_| Error::VersionedConversion("VersionedLocation"))?;
+			for message in messages {
+				let message = Xcm::<()>::try_from(message)
+					.map_err(|_| Error::VersionedConversion("VersionedXcm"))?;
+				let delivery_fees = chain.delivery_fees(&destination, &message);
+				forwarded.push(Forwarded {
+					destination: destination.clone(),
+					message,
+					delivery_fees,
+					hop: None,
+				});
+			}
+		}
+		Ok(forwarded)
+	}
+
+	/// The index of the chain `destination` refers to, as seen from the chain at `sender`.
+	fn destination_chain(&self, sender: usize, destination: &Location) -> Option<usize> {
+		let location = universal_location(self.chains[sender].location(), destination)?;
+		self.chains.iter().position(|chain| chain.location() == &location)
+	}
+
+	fn chain_index(&self, name: &str) -> Result<usize, Error> {
+		self.chains
+			.iter()
+			.position(|chain| chain.name() == name)
+			.ok_or_else(|| Error::UnknownChain(name.into()))
+	}
+
+	fn balances(&mut self) -> Vec<Option<u128>> {
+		self.watched
+			.iter()
+			.map(|(index, account)| self.chains[*index].free_balance(account))
+			.collect()
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+
+	const POLKADOT: Junction = GlobalConsensus(Polkadot);
+
+	#[test]
+	fn relative_location_works() {
+		let relay: InteriorLocation = [POLKADOT].into();
+		let asset_hub: InteriorLocation = [POLKADOT, Parachain(1000)].into();
+		let bridge_hub: InteriorLocation = [POLKADOT, Parachain(1002)].into();
+		let kusama_hub: InteriorLocation = [GlobalConsensus(Kusama), Parachain(1000)].into();
+
+		assert_eq!(relative_location(&relay, &asset_hub), Location::new(0, [Parachain(1000)]));
+		assert_eq!(relative_location(&asset_hub, &relay), Location::parent());
+		assert_eq!(relative_location(&asset_hub, &bridge_hub), Location::new(1, [Parachain(1002)]));
+		assert_eq!(
+			relative_location(&bridge_hub, &kusama_hub),
+			Location::new(2, [GlobalConsensus(Kusama), Parachain(1000)])
+		);
+		assert_eq!(relative_location(&relay, &relay), Location::here());
+	}
+
+	#[test]
+	fn universal_location_works() {
+		let relay: InteriorLocation = [POLKADOT].into();
+		let asset_hub: InteriorLocation = [POLKADOT, Parachain(1000)].into();
+
+		assert_eq!(
+			universal_location(&asset_hub, &Location::new(1, [Parachain(1002)])),
+			Some([POLKADOT, Parachain(1002)].into())
+		);
+		assert_eq!(universal_location(&asset_hub, &Location::parent()), Some(relay.clone()));
+		assert_eq!(
+			universal_location(&relay, &Location::new(0, [Parachain(1000)])),
+			Some(asset_hub.clone())
+		);
+		// Routing a message there and back again ends up where it started.
+		let there = relative_location(&asset_hub, &relay);
+		assert_eq!(universal_location(&asset_hub, &there), Some(relay.clone()));
+		// Outside of the universe.
+		assert_eq!(universal_location(&relay, &Location::new(2, Here)), None);
+	}
+}
@@ -0,0 +1,283 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Decode the results of the dry-run API without knowing the event type of the runtime.
+
+use codec::{Compact, Decode, Error};
+use frame_metadata::{RuntimeMetadata, RuntimeMetadataPrefixed};
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use scale_info::{form::PortableForm, Field, PortableRegistry, TypeDef, TypeDefPrimitive};
+use xcm::prelude::*;
+use xcm_runtime_apis::dry_run::{self, CallDryRunEffects, XcmDryRunEffects};
+
+/// Decodes the `RuntimeEvent`s of a runtime into readable strings, using the type information
+/// of its metadata.
+pub(crate) struct EventDecoder {
+	registry: PortableRegistry,
+	event_ty: u32,
+}
+
+impl EventDecoder {
+	/// Create a decoder from the encoded `RuntimeMetadataPrefixed`, which must be V15.
+	pub(crate) fn from_metadata(metadata: &[u8]) -> Option<Self> {
+		match RuntimeMetadataPrefixed::decode(&mut &metadata[..]).ok()?.1 {
+			RuntimeMetadata::V15(metadata) =>
+				Some(Self::new(metadata.types, metadata.outer_enums.event_enum_ty.id)),
+			_ => None,
+		}
+	}
+
+	pub(crate) fn new(registry: PortableRegistry, event_ty: u32) -> Self {
+		Self { registry, event_ty }
+	}
+
+	/// Decode the result of `DryRunApi::dry_run_call`.
+	pub(crate) fn decode_call_effects(
+		&self,
+		input: &mut &[u8],
+	) -> Result<Result<CallDryRunEffects<String>, dry_run::Error>, Error> {
+		match u8::decode(input)? {
+			0 => Ok(Ok(CallDryRunEffects {
+				execution_result: DispatchResultWithPostInfo::decode(input)?,
+				emitted_events: self.decode_events(input)?,
+				local_xcm: Decode::decode(input)?,
+				forwarded_xcms: Decode::decode(input)?,
+			})),
+			1 => Ok(Err(dry_run::Error::decode(input)?)),
+			_ => Err("Invalid `Result` variant".into()),
+		}
+	}
+
+	/// Decode the result of `DryRunApi::dry_run_xcm`.
+	pub(crate) fn decode_xcm_effects(
+		&self,
+		input: &mut &[u8],
+	) -> Result<Result<XcmDryRunEffects<String>, dry_run::Error>, Error> {
+		match u8::decode(input)? {
+			0 => Ok(Ok(XcmDryRunEffects {
+				execution_result: Outcome::decode(input)?,
+				emitted_events: self.decode_events(input)?,
+				forwarded_xcms: Decode::decode(input)?,
+			})),
+			1 => Ok(Err(dry_run::Error::decode(input)?)),
+			_ => Err("Invalid `Result` variant".into()),
+		}
+	}
+
+	/// Decode a `Vec<RuntimeEvent>`.
+	pub(crate) fn decode_events(&self, input: &mut &[u8]) -> Result<Vec<String>, Error> {
+		let len = Compact::<u32>::decode(input)?.0;
+		(0..len)
+			.map(|_| {
+				let mut event = String::new();
+				self.decode_value(self.event_ty, input, &mut event)?;
+				Ok(event)
+			})
+			.collect()
+	}
+
+	fn decode_value(&self, id: u32, input: &mut &[u8], out: &mut String) -> Result<(), Error> {
+		let ty = self.registry.resolve(id).ok_or("Unknown type id")?;
+
+		match &ty.type_def {
+			TypeDef::Composite(composite) => match &composite.fields[..] {
+				// Newtypes are shown as their inner value.
+				[field] if field.name.is_none() => self.decode_value(field.ty.id, input, out)?,
+				fields => self.decode_fields(fields, input, out)?,
+			},
+			TypeDef::Variant(variant) => {
+				let index = u8::decode(input)?;
+				let variant = variant
+					.variants
+					.iter()
+					.find(|v| v.index == index)
+					.ok_or("Unknown variant index")?;
+				out.push_str(&variant.name);
+				self.decode_fields(&variant.fields, input, out)?;
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = Compact::<u32>::decode(input)?.0;
+				self.decode_items(sequence.type_param.id, len, input, out)?;
+			},
+			TypeDef::Array(array) =>
+				self.decode_items(array.type_param.id, array.len, input, out)?,
+			TypeDef::Tuple(tuple) => {
+				out.push('(');
+				for (i, field) in tuple.fields.iter().enumerate() {
+					if i > 0 {
+						out.push_str(", ");
+					}
+					self.decode_value(field.id, input, out)?;
+				}
+				out.push(')');
+			},
+			TypeDef::Primitive(primitive) => self.decode_primitive(primitive, input, out)?,
+			TypeDef::Compact(_) => out.push_str(&Compact::<u128>::decode(input)?.0.to_string()),
+			TypeDef::BitSequence(_) => return Err("Bit sequences are not supported".into()),
+		}
+
+		Ok(())
+	}
+
+	fn decode_fields(
+		&self,
+		fields: &[Field<PortableForm>],
+		input: &mut &[u8],
+		out: &mut String,
+	) -> Result<(), Error> {
+		if fields.is_empty() {
+			return Ok(())
+		}
+		let named = fields.iter().all(|f| f.name.is_some());
+		out.push_str(if named { " { " } else { "(" });
+		for (i, field) in fields.iter().enumerate() {
+			if i > 0 {
+				out.push_str(", ");
+			}
+			if let Some(name) = &field.name {
+				out.push_str(name);
+				out.push_str(": ");
+			}
+			self.decode_value(field.ty.id, input, out)?;
+		}
+		out.push_str(if named { " }" } else { ")" });
+		Ok(())
+	}
+
+	/// Decode `len` items of type `id`, showing bytes as hex.
+	fn decode_items(
+		&self,
+		id: u32,
+		len: u32,
+		input: &mut &[u8],
+		out: &mut String,
+	) -> Result<(), Error> {
+		let ty = self.registry.resolve(id).ok_or("Unknown type id")?;
+		if let TypeDef::Primitive(TypeDefPrimitive::U8) = ty.type_def {
+			let len = len as usize;
+			if input.len() < len {
+				return Err("Not enough data to fill buffer".into())
+			}
+			out.push_str(&array_bytes::bytes2hex("0x", &input[..len]));
+			*input = &input[len..];
+			return Ok(())
+		}
+
+		out.push('[');
+		for i in 0..len {
+			if i > 0 {
+				out.push_str(", ");
+			}
+			self.decode_value(id, input, out)?;
+		}
+		out.push(']');
+		Ok(())
+	}
+
+	fn decode_primitive(
+		&self,
+		primitive: &TypeDefPrimitive,
+		input: &mut &[u8],
+		out: &mut String,
+	) -> Result<(), Error> {
+		let value = match primitive {
+			TypeDefPrimitive::Bool => bool::decode(input)?.to_string(),
+			TypeDefPrimitive::Char =>
+				char::from_u32(u32::decode(input)?).ok_or("Invalid char")?.to_string(),
+			TypeDefPrimitive::Str => format!("{:?}", String::decode(input)?),
+			TypeDefPrimitive::U8 => u8::decode(input)?.to_string(),
+			TypeDefPrimitive::U16 => u16::decode(input)?.to_string(),
+			TypeDefPrimitive::U32 => u32::decode(input)?.to_string(),
+			TypeDefPrimitive::U64 => u64::decode(input)?.to_string(),
+			TypeDefPrimitive::U128 => u128::decode(input)?.to_string(),
+			TypeDefPrimitive::I8 => i8::decode(input)?.to_string(),
+			TypeDefPrimitive::I16 => i16::decode(input)?.to_string(),
+			TypeDefPrimitive::I32 => i32::decode(input)?.to_string(),
+			TypeDefPrimitive::I64 => i64::decode(input)?.to_string(),
+			TypeDefPrimitive::I128 => i128::decode(input)?.to_string(),
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 =>
+				array_bytes::bytes2hex("0x", <[u8; 32]>::decode(input)?),
+		};
+		out.push_str(&value);
+		Ok(())
+	}
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use codec::Encode;
+	use scale_info::{MetaType, Registry, TypeInfo};
+
+	#[derive(Encode, TypeInfo)]
+	struct AccountId([u8; 4]);
+
+	#[derive(Encode, TypeInfo)]
+	enum BalancesEvent {
+		Transfer { from: AccountId, to: AccountId, amount: u128 },
+		Endowed(AccountId, #[codec(compact)] u64),
+	}
+
+	#[derive(Encode, TypeInfo)]
+	enum RuntimeEvent {
+		System,
+		Balances(BalancesEvent),
+		Tuple((bool, Vec<u16>, String)),
+	}
+
+	fn decoder() -> EventDecoder {
+		let mut registry = Registry::new();
+		let id = registry.register_type(&MetaType::new::<RuntimeEvent>()).id;
+		EventDecoder::new(registry.into(), id)
+	}
+
+	#[test]
+	fn events_are_decoded() {
+		let events = vec![
+			RuntimeEvent::System,
+			RuntimeEvent::Balances(BalancesEvent::Transfer {
+				from: AccountId([1; 4]),
+				to: AccountId([2; 4]),
+				amount: 100,
+			}),
+			RuntimeEvent::Balances(BalancesEvent::Endowed(AccountId([3; 4]), 7)),
+			RuntimeEvent::Tuple((true, vec![1, 2], "hi".into())),
+		];
+		let encoded = (events, 42u8).encode();
+		let mut input = &encoded[..];
+
+		assert_eq!(
+			decoder().decode_events(&mut input).unwrap(),
+			vec![
+				"System",
+				"Balances(Transfer { from: 0x01010101, to: 0x02020202, amount: 100 })",
+				"Balances(Endowed(0x03030303, 7))",
+				"Tuple((true, [1, 2], \"hi\"))",
+			]
+		);
+		// Everything after the events is left untouched.
+		assert_eq!(input, &[42]);
+	}
+
+	#[test]
+	fn invalid_events_are_rejected() {
+		let encoded = vec![RuntimeEvent::System].encode();
+		// Unknown variant index.
+		assert!(decoder().decode_events(&mut &[4, 9][..]).is_err());
+		// Missing data.
+		assert!(decoder().decode_events(&mut &encoded[..1]).is_err());
+	}
+}
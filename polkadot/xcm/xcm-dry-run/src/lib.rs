@@ -0,0 +1,84 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # XCM Dry-Run Driver
+//!
+//! Dry-run an XCM transfer across several chains.
+//!
+//! The [`DryRunApi`](xcm_runtime_apis::dry_run::DryRunApi) only simulates a single hop: it returns
+//! the messages forwarded by a call or an XCM program, but doesn't execute them on their
+//! destination. The [`Driver`] holds the runtimes and states of several [`Chain`]s. It executes
+//! `dry_run_call` or `dry_run_xcm` on the origin chain and then replays every forwarded message
+//! with `dry_run_xcm` on its destination, until no more messages are sent to any of the loaded
+//! chains.
+//!
+//! The result is a [`Trace`] with every hop, including its outcome, the emitted events, the
+//! execution and delivery fees quoted by the
+//! [`XcmPaymentApi`](xcm_runtime_apis::fees::XcmPaymentApi), and the balances of the watched
+//! accounts before and after the transfer.
+//!
+//! The state changes of a hop are kept, so that later hops executing on the same chain and the
+//! final balances see them.
+//!
+//! ## Usage
+//!
+//! ```ignore
+//! let trace = Driver::new()
+//! 	.with_chain(Chain::new("polkadot", [GlobalConsensus(Polkadot)], relay_ext))
+//! 	.with_chain(Chain::new("asset-hub", [GlobalConsensus(Polkadot), Parachain(1000)], ah_ext))
+//! 	.watch("asset-hub", beneficiary)?
+//! 	.dry_run_call("polkadot", &origin, &call)?;
+//! println!("{trace}");
+//! ```
+//!
+//! The `xcm-dry-run` binary does the same for chains loaded from state snapshots of
+//! `remote-externalities`.
+
+mod chain;
+mod driver;
+mod events;
+mod trace;
+
+pub use chain::{Chain, HostFunctions};
+pub use driver::{relative_location, Driver};
+pub use trace::{Balance, ExecutionFees, Forwarded, Hop, Outcome, Trace};
+
+/// Errors of the [`Driver`].
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+	/// No chain with the given name was loaded.
+	#[error("Unknown chain `{0}`")]
+	UnknownChain(String),
+	/// Calling into the runtime failed.
+	#[error("Runtime call `{method}` failed on `{chain}`: {error}")]
+	RuntimeCall { chain: String, method: &'static str, error: String },
+	/// The result of a runtime call couldn't be decoded.
+	#[error("Failed to decode the result of `{method}` on `{chain}`: {error}")]
+	Decode { chain: String, method: &'static str, error: codec::Error },
+	/// The runtime API returned an error.
+	#[error("`{method}` on `{chain}` returned {error}")]
+	Api { chain: String, method: &'static str, error: String },
+	/// The runtime of the chain doesn't provide metadata V15, which is needed to decode its
+	/// events.
+	#[error("Metadata V15 of `{0}` is missing or invalid")]
+	Metadata(String),
+	/// A versioned XCM type couldn't be converted to the latest version.
+	#[error("Failed to convert `{0}` to the latest XCM version")]
+	VersionedConversion(&'static str),
+	/// More messages were forwarded than the configured maximum of hops.
+	#[error("Exceeded the maximum of {0} hops")]
+	TooManyHops(usize),
+}
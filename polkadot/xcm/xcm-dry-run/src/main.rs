@@ -0,0 +1,214 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-run an XCM transfer across chains loaded from state snapshots.
+//!
+//! ```sh
+//! xcm-dry-run --network polkadot \
+//! 	--chain polkadot:relay:polkadot.snap \
+//! 	--chain asset-hub:1000:asset-hub.snap:asset_hub_polkadot_runtime.wasm \
+//! 	--from polkadot --origin 0x.. --call 0x.. \
+//! 	--watch asset-hub:0x..
+//! ```
+
+use clap::Parser;
+use codec::Decode;
+use remote_externalities::{Builder, Mode, OfflineConfig, SnapshotConfig};
+use sp_core::crypto::AccountId32;
+use sp_runtime::{generic, traits::BlakeTwo256, OpaqueExtrinsic};
+use std::{path::PathBuf, process::ExitCode};
+use xcm::prelude::*;
+use xcm_dry_run::{Chain, Driver};
+
+type Block = generic::Block<generic::Header<u32, BlakeTwo256>, OpaqueExtrinsic>;
+
+#[derive(Debug, Parser)]
+#[command(author, version, about)]
+struct Cli {
+	/// A chain to load, as `<NAME>:<relay|PARA_ID>:<SNAPSHOT>[:<RUNTIME>]`.
+	///
+	/// The snapshot is a state snapshot created by `remote-externalities`, e.g. with
+	/// `try-runtime create-snapshot`. The runtime, if given, is executed instead of the one found
+	/// in the snapshot.
+	#[arg(long = "chain", required = true)]
+	chains: Vec<ChainArg>,
+
+	/// The consensus system of all chains, one of `polkadot`, `kusama`, `westend` or `rococo`.
+	#[arg(long, default_value = "polkadot", value_parser = parse_network)]
+	network: NetworkId,
+
+	/// The chain on which the dry-run starts.
+	#[arg(long)]
+	from: String,
+
+	/// The SCALE encoded `OriginCaller` dispatching `--call`.
+	#[arg(long, requires = "call", value_parser = parse_hex)]
+	origin: Option<Vec<u8>>,
+
+	/// The SCALE encoded `RuntimeCall` to dry-run.
+	#[arg(long, conflicts_with = "xcm", value_parser = parse_hex)]
+	call: Option<Vec<u8>>,
+
+	/// The SCALE encoded `VersionedXcm<()>` to dry-run.
+	#[arg(long, requires = "xcm_origin", value_parser = parse_hex)]
+	xcm: Option<Vec<u8>>,
+
+	/// The SCALE encoded `VersionedLocation` sending `--xcm`, relative to `--from`.
+	#[arg(long, value_parser = parse_hex)]
+	xcm_origin: Option<Vec<u8>>,
+
+	/// An account whose balance is reported, as `<CHAIN>:<ACCOUNT_ID>` in hex.
+	#[arg(long = "watch")]
+	watched: Vec<WatchArg>,
+
+	/// The maximal number of hops.
+	#[arg(long, default_value_t = 32)]
+	max_hops: usize,
+}
+
+#[derive(Debug, Clone)]
+struct ChainArg {
+	name: String,
+	para_id: Option<u32>,
+	snapshot: PathBuf,
+	runtime: Option<PathBuf>,
+}
+
+impl std::str::FromStr for ChainArg {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let parts: Vec<_> = s.split(':').collect();
+		let [name, kind, snapshot, rest @ ..] = &parts[..] else {
+			return Err(format!(
+				"Expected `<NAME>:<relay|PARA_ID>:<SNAPSHOT>[:<RUNTIME>]`, got `{s}`"
+			))
+		};
+		let para_id = match *kind {
+			"relay" => None,
+			para_id => Some(para_id.parse().map_err(|_| format!("Invalid para id `{para_id}`"))?),
+		};
+		let runtime = match rest {
+			[] => None,
+			[runtime] => Some(runtime.into()),
+			_ => return Err(format!("Too many parts in `{s}`")),
+		};
+		Ok(Self { name: name.to_string(), para_id, snapshot: snapshot.into(), runtime })
+	}
+}
+
+#[derive(Debug, Clone)]
+struct WatchArg {
+	chain: String,
+	account: AccountId32,
+}
+
+impl std::str::FromStr for WatchArg {
+	type Err = String;
+
+	fn from_str(s: &str) -> Result<Self, Self::Err> {
+		let (chain, account) = s
+			.split_once(':')
+			.ok_or_else(|| format!("Expected `<CHAIN>:<ACCOUNT_ID>`, got `{s}`"))?;
+		let account = array_bytes::hex2array::<_, 32>(account)
+			.map_err(|e| format!("Invalid account `{account}`: {e:?}"))?;
+		Ok(Self { chain: chain.into(), account: account.into() })
+	}
+}
+
+fn parse_hex(s: &str) -> Result<Vec<u8>, String> {
+	array_bytes::hex2bytes(s).map_err(|e| format!("Invalid hex: {e:?}"))
+}
+
+fn parse_network(s: &str) -> Result<NetworkId, String> {
+	match s {
+		"polkadot" => Ok(Polkadot),
+		"kusama" => Ok(Kusama),
+		"westend" => Ok(ByGenesis(xcm::latest::WESTEND_GENESIS_HASH)),
+		"rococo" => Ok(ByGenesis(xcm::latest::ROCOCO_GENESIS_HASH)),
+		_ => Err(format!("Unknown network `{s}`")),
+	}
+}
+
+async fn load(chain: &ChainArg, network: NetworkId) -> Result<Chain, String> {
+	log::info!(target: "xcm::dry-run", "Loading {} from {:?}", chain.name, chain.snapshot);
+	let ext = Builder::<Block>::new()
+		.mode(Mode::Offline(OfflineConfig {
+			state_snapshot: SnapshotConfig::new(chain.snapshot.clone()),
+		}))
+		.build()
+		.await
+		.map_err(|e| format!("Failed to load the snapshot of `{}`: {e}", chain.name))?;
+
+	let location: InteriorLocation = match chain.para_id {
+		None => [GlobalConsensus(network)].into(),
+		Some(para_id) => [GlobalConsensus(network), Parachain(para_id)].into(),
+	};
+	let mut loaded = Chain::new(chain.name.clone(), location, ext.inner_ext);
+	if let Some(runtime) = &chain.runtime {
+		let code = std::fs::read(runtime)
+			.map_err(|e| format!("Failed to read the runtime {runtime:?}: {e}"))?;
+		loaded = loaded.with_code(code);
+	}
+	Ok(loaded)
+}
+
+async fn run(cli: Cli) -> Result<bool, String> {
+	let mut driver = Driver::new().max_hops(cli.max_hops);
+	for chain in &cli.chains {
+		driver = driver.with_chain(load(chain, cli.network).await?);
+	}
+	for WatchArg { chain, account } in cli.watched {
+		driver = driver.watch(&chain, account).map_err(|e| e.to_string())?;
+	}
+
+	let trace = match (cli.origin, cli.call, cli.xcm, cli.xcm_origin) {
+		(Some(origin), Some(call), None, _) => driver.dry_run_call(&cli.from, &origin, &call),
+		(None, None, Some(message), Some(origin)) => {
+			let origin = VersionedLocation::decode(&mut &origin[..])
+				.map_err(|e| format!("Invalid `--xcm-origin`: {e}"))?
+				.try_into()
+				.map_err(|_| "Unsupported version of `--xcm-origin`".to_string())?;
+			let message = VersionedXcm::<()>::decode(&mut &message[..])
+				.map_err(|e| format!("Invalid `--xcm`: {e}"))?
+				.try_into()
+				.map_err(|_| "Unsupported version of `--xcm`".to_string())?;
+			driver.dry_run_xcm(&cli.from, origin, message)
+		},
+		_ =>
+			return Err(
+				"Either `--origin` and `--call` or `--xcm` and `--xcm-origin` must be given".into()
+			),
+	}
+	.map_err(|e| e.to_string())?;
+
+	print!("{trace}");
+	Ok(trace.is_success())
+}
+
+#[tokio::main]
+async fn main() -> ExitCode {
+	env_logger::Builder::from_env(env_logger::Env::default().default_filter_or("info")).init();
+
+	match run(Cli::parse()).await {
+		Ok(true) => ExitCode::SUCCESS,
+		Ok(false) => ExitCode::FAILURE,
+		Err(e) => {
+			eprintln!("Error: {e}");
+			ExitCode::from(2)
+		},
+	}
+}
@@ -0,0 +1,142 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! The cross-chain trace of a dry-run.
+
+use frame_support::dispatch::DispatchResultWithPostInfo;
+use sp_core::crypto::AccountId32;
+use std::fmt;
+use xcm::latest::{Asset, Assets, Location, Weight, Xcm};
+use xcm_runtime_apis::fees::Error as FeesError;
+
+/// The outcome of a hop.
+#[derive(Debug, Clone)]
+pub enum Outcome {
+	/// The result of the call dispatched on the origin chain.
+	Call(DispatchResultWithPostInfo),
+	/// The outcome of an XCM program.
+	Xcm(xcm::latest::Outcome),
+}
+
+/// The fees for executing an XCM program, as quoted by the `XcmPaymentApi` of the executing chain.
+#[derive(Debug, Clone)]
+pub struct ExecutionFees {
+	/// The weight of the program.
+	pub weight: Weight,
+	/// The fees for the weight, in the asset used to buy execution by the program.
+	pub fees: Result<Asset, FeesError>,
+}
+
+/// A message forwarded by a hop.
+#[derive(Debug, Clone)]
+pub struct Forwarded {
+	/// The destination, relative to the sending chain.
+	pub destination: Location,
+	/// The forwarded message.
+	pub message: Xcm<()>,
+	/// The delivery fees, as quoted by the `XcmPaymentApi` of the sending chain.
+	pub delivery_fees: Result<Assets, FeesError>,
+	/// The index of the hop executing the message, `None` if the destination wasn't loaded.
+	pub hop: Option<usize>,
+}
+
+/// A call or XCM program executed on one of the chains.
+#[derive(Debug, Clone)]
+pub struct Hop {
+	/// The name of the chain.
+	pub chain: String,
+	/// The origin of the call or program, relative to the chain.
+	pub origin: Location,
+	/// The outcome of the execution.
+	pub outcome: Outcome,
+	/// The events emitted by the execution.
+	pub events: Vec<String>,
+	/// The fees for the XCM executed by this hop, `None` for calls without local XCM execution.
+	pub execution_fees: Option<Result<ExecutionFees, FeesError>>,
+	/// The messages forwarded to other chains.
+	pub forwarded: Vec<Forwarded>,
+}
+
+/// The balance of a watched account.
+#[derive(Debug, Clone)]
+pub struct Balance {
+	/// The name of the chain.
+	pub chain: String,
+	/// The account.
+	pub account: AccountId32,
+	/// The free balance before the dry-run.
+	pub before: Option<u128>,
+	/// The free balance after the dry-run.
+	pub after: Option<u128>,
+}
+
+/// The full cross-chain trace of a dry-run.
+#[derive(Debug, Clone, Default)]
+pub struct Trace {
+	/// All hops, in execution order. The first hop is the initial call or program.
+	pub hops: Vec<Hop>,
+	/// The balances of the watched accounts.
+	pub balances: Vec<Balance>,
+}
+
+impl Trace {
+	/// Whether every hop executed successfully.
+	pub fn is_success(&self) -> bool {
+		self.hops.iter().all(|hop| match &hop.outcome {
+			Outcome::Call(result) => result.is_ok(),
+			Outcome::Xcm(outcome) => outcome.clone().ensure_complete().is_ok(),
+		})
+	}
+}
+
+impl fmt::Display for Trace {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		for (i, hop) in self.hops.iter().enumerate() {
+			writeln!(f, "#{} {} (origin {:?})", i, hop.chain, hop.origin)?;
+			match &hop.outcome {
+				Outcome::Call(result) => writeln!(f, "  result: {:?}", result)?,
+				Outcome::Xcm(outcome) => writeln!(f, "  outcome: {:?}", outcome)?,
+			}
+			match &hop.execution_fees {
+				Some(Ok(fees)) =>
+					writeln!(f, "  execution: weight {:?}, fees {:?}", fees.weight, fees.fees)?,
+				Some(Err(e)) => writeln!(f, "  execution: weight unknown ({:?})", e)?,
+				None => {},
+			}
+			for event in &hop.events {
+				writeln!(f, "  event: {}", event)?;
+			}
+			for forwarded in &hop.forwarded {
+				let target = forwarded
+					.hop
+					.map_or_else(|| "not followed".to_string(), |hop| format!("hop #{}", hop));
+				writeln!(
+					f,
+					"  forwarded to {:?} ({}), delivery fees {:?}",
+					forwarded.destination, target, forwarded.delivery_fees
+				)?;
+			}
+		}
+		for balance in &self.balances {
+			writeln!(
+				f,
+				"balance of {} on {}: {:?} -> {:?}",
+				balance.account, balance.chain, balance.before, balance.after
+			)?;
+		}
+		Ok(())
+	}
+}
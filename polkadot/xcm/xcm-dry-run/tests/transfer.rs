@@ -0,0 +1,185 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Dry-run transfers between the Westend relay chain and Asset Hub, executing their runtimes on
+//! their development genesis state.
+
+use codec::Encode;
+use polkadot_parachain_primitives::primitives::{HeadData, Id as ParaId};
+use sc_chain_spec::GenesisConfigBuilderRuntimeCaller;
+use sp_core::crypto::AccountId32;
+use sp_keyring::Sr25519Keyring;
+use sp_runtime::traits::BlakeTwo256;
+use sp_state_machine::TestExternalities;
+use xcm::prelude::*;
+use xcm_dry_run::{Chain, Driver, Outcome};
+
+const ASSET_HUB_ID: u32 = 1000;
+const AMOUNT: u128 = 10 * westend_runtime_constants::currency::UNITS;
+
+/// The development genesis state of the runtime `code`, at block 1 so that events are kept.
+fn genesis<Runtime: frame_system::Config>(code: &[u8]) -> TestExternalities<BlakeTwo256> {
+	let storage = GenesisConfigBuilderRuntimeCaller::<()>::new(code)
+		.get_storage_for_named_preset(Some(&sp_genesis_builder::DEV_RUNTIME_PRESET.into()))
+		.expect("The development preset builds");
+	let mut ext = TestExternalities::new(storage);
+	ext.execute_with(|| frame_system::Pallet::<Runtime>::set_block_number(1u32.into()));
+	ext
+}
+
+fn westend() -> Chain {
+	let code = westend_runtime::WASM_BINARY.expect("Built with the WASM binary");
+	let mut chain = Chain::new(
+		"westend",
+		[GlobalConsensus(Westend)],
+		genesis::<westend_runtime::Runtime>(code),
+	)
+	.with_code(code.to_vec());
+	// Downward messages are only routed to parachains with a head.
+	chain.ext().execute_with(|| {
+		polkadot_runtime_parachains::paras::Heads::<westend_runtime::Runtime>::insert(
+			ParaId::from(ASSET_HUB_ID),
+			HeadData(vec![1]),
+		)
+	});
+	chain
+}
+
+fn asset_hub() -> Chain {
+	let code = asset_hub_westend_runtime::WASM_BINARY.expect("Built with the WASM binary");
+	Chain::new(
+		"asset-hub",
+		[GlobalConsensus(Westend), Parachain(ASSET_HUB_ID)],
+		genesis::<asset_hub_westend_runtime::Runtime>(code),
+	)
+	.with_code(code.to_vec())
+}
+
+fn teleport_to_asset_hub(beneficiary: &AccountId32) -> Vec<u8> {
+	westend_runtime::RuntimeCall::XcmPallet(pallet_xcm::Call::limited_teleport_assets {
+		dest: Box::new(Location::new(0, [Parachain(ASSET_HUB_ID)]).into()),
+		beneficiary: Box::new(
+			Location::new(0, [AccountId32 { network: None, id: beneficiary.clone().into() }])
+				.into(),
+		),
+		assets: Box::new((Here, AMOUNT).into()),
+		fee_asset_item: 0,
+		weight_limit: Unlimited,
+	})
+	.encode()
+}
+
+fn signed(account: &AccountId32) -> Vec<u8> {
+	westend_runtime::OriginCaller::system(frame_system::RawOrigin::Signed(account.clone())).encode()
+}
+
+#[test]
+fn teleport_is_followed_to_asset_hub() {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let bob = Sr25519Keyring::Bob.to_account_id();
+
+	let trace = Driver::new()
+		.with_chain(westend())
+		.with_chain(asset_hub())
+		.watch("westend", alice.clone())
+		.unwrap()
+		.watch("asset-hub", bob.clone())
+		.unwrap()
+		.dry_run_call("westend", &signed(&alice), &teleport_to_asset_hub(&bob))
+		.unwrap();
+
+	assert!(trace.is_success(), "{trace}");
+	assert_eq!(trace.hops.len(), 2, "{trace}");
+
+	let call = &trace.hops[0];
+	assert_eq!(call.chain, "westend");
+	assert!(matches!(call.outcome, Outcome::Call(Ok(_))));
+	assert!(call.execution_fees.as_ref().is_some_and(|fees| fees.is_ok()));
+	assert_eq!(call.forwarded.len(), 1);
+	assert_eq!(call.forwarded[0].destination, Location::new(0, [Parachain(ASSET_HUB_ID)]));
+	assert_eq!(call.forwarded[0].hop, Some(1));
+	assert!(call.forwarded[0].delivery_fees.is_ok());
+
+	let receive = &trace.hops[1];
+	assert_eq!(receive.chain, "asset-hub");
+	assert_eq!(receive.origin, Location::parent());
+	assert!(matches!(receive.outcome, Outcome::Xcm(xcm::latest::Outcome::Complete { .. })));
+	assert!(receive.forwarded.is_empty());
+	assert!(!receive.events.is_empty());
+
+	// Alice paid the amount and the fees, Bob received the amount minus the execution fees.
+	let (alice, bob) = (&trace.balances[0], &trace.balances[1]);
+	assert_eq!(alice.chain, "westend");
+	assert!(alice.after.unwrap() <= alice.before.unwrap() - AMOUNT);
+	assert_eq!(bob.chain, "asset-hub");
+	assert!(bob.after.unwrap() > bob.before.unwrap());
+	assert!(bob.after.unwrap() < bob.before.unwrap() + AMOUNT);
+}
+
+#[test]
+fn messages_to_chains_not_loaded_are_not_followed() {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let bob = Sr25519Keyring::Bob.to_account_id();
+
+	let trace = Driver::new()
+		.with_chain(westend())
+		.dry_run_call("westend", &signed(&alice), &teleport_to_asset_hub(&bob))
+		.unwrap();
+
+	assert!(trace.is_success(), "{trace}");
+	assert_eq!(trace.hops.len(), 1);
+	assert_eq!(trace.hops[0].forwarded.len(), 1);
+	assert_eq!(trace.hops[0].forwarded[0].hop, None);
+	assert!(trace.to_string().contains("not followed"));
+}
+
+#[test]
+fn state_changes_are_kept_between_dry_runs() {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+	let bob = Sr25519Keyring::Bob.to_account_id();
+
+	let mut driver = Driver::new()
+		.with_chain(westend())
+		.with_chain(asset_hub())
+		.watch("asset-hub", bob.clone())
+		.unwrap();
+
+	let first = driver
+		.dry_run_call("westend", &signed(&alice), &teleport_to_asset_hub(&bob))
+		.unwrap();
+	let second = driver
+		.dry_run_call("westend", &signed(&alice), &teleport_to_asset_hub(&bob))
+		.unwrap();
+
+	assert!(first.is_success() && second.is_success());
+	// The second transfer starts from the balance the first one ended with.
+	assert_eq!(second.balances[0].before, first.balances[0].after);
+	assert!(second.balances[0].after > first.balances[0].after);
+}
+
+#[test]
+fn unknown_chains_are_rejected() {
+	let alice = Sr25519Keyring::Alice.to_account_id();
+
+	assert!(matches!(
+		Driver::new().with_chain(westend()).watch("asset-hub", alice.clone()),
+		Err(xcm_dry_run::Error::UnknownChain(name)) if name == "asset-hub"
+	));
+	assert!(matches!(
+		Driver::new().dry_run_xcm("westend", Location::here(), Xcm::new()),
+		Err(xcm_dry_run::Error::UnknownChain(_))
+	));
+}
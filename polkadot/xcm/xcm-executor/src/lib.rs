@@ -35,9 +35,9 @@ pub mod traits;
 use traits::{
 	validate_export, AssetExchange, AssetLock, CallDispatcher, ClaimAssets, ConvertOrigin,
 	DropAssets, Enact, EventEmitter, ExportXcm, FeeManager, FeeReason, HandleHrmpChannelAccepted,
-	HandleHrmpChannelClosing, HandleHrmpNewChannelOpenRequest, OnResponse, ProcessTransaction,
-	Properties, ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds, WeightTrader,
-	XcmAssetTransfers,
+	HandleHrmpChannelClosing, HandleHrmpNewChannelOpenRequest, LimitTransfers, OnResponse,
+	ProcessTransaction, Properties, ShouldExecute, TransactAsset, VersionChangeNotifier,
+	WeightBounds, WeightTrader, XcmAssetTransfers,
 };
 
 pub use traits::RecordXcm;
@@ -404,7 +404,14 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		};
 
 		match self.error {
-			None => Outcome::Complete { used: weight_used },
+			None => {
+				Config::XcmEventEmitter::emit_executed_event(
+					self.original_origin,
+					weight_used,
+					self.context.message_id,
+				);
+				Outcome::Complete { used: weight_used }
+			},
 			// TODO: #2841 #REALWEIGHT We should deduct the cost of any instructions following
 			// the error which didn't end up being executed.
 			Some((_i, e)) => {
@@ -711,6 +718,43 @@ impl<Config: config::Config> XcmExecutor<Config> {
 		self.send(destination, message, fee_reason)
 	}
 
+	/// Check the assets of the holding register selected by `filters` in order, i.e. the assets
+	/// an instruction transfers out of the chain, with the `TransferLimiter` before the
+	/// instruction is processed.
+	///
+	/// The assets of the holding register are owned by the origin, so a rejected transfer is
+	/// noted with the `TransferLimiter`.
+	///
+	/// Returns the transfer to note with [`Self::note_outbound`] once the instruction succeeded.
+	fn check_outbound<'a>(
+		&self,
+		destination: &Location,
+		filters: impl IntoIterator<Item = &'a AssetFilter>,
+	) -> Result<Option<(Location, Assets)>, XcmError> {
+		let mut holding = self.holding.clone();
+		let mut assets = AssetsInHolding::new();
+		for filter in filters {
+			assets.subsume_assets(holding.saturating_take(filter.clone()));
+		}
+		if assets.is_empty() {
+			return Ok(None)
+		}
+		let assets = assets.into();
+		if let Err(error) = Config::TransferLimiter::check_outbound(destination, &assets) {
+			Config::TransferLimiter::note_rejected(destination, &assets);
+			return Err(error)
+		}
+		Ok(Some((destination.clone(), assets)))
+	}
+
+	/// Note the transfer checked by [`Self::check_outbound`] with the `TransferLimiter`, if the
+	/// instruction succeeded.
+	fn note_outbound(transfer: Option<(Location, Assets)>, result: &Result<(), XcmError>) {
+		if let (Some((destination, assets)), Ok(())) = (transfer, result) {
+			Config::TransferLimiter::note_outbound(&destination, &assets);
+		}
+	}
+
 	fn do_reserve_deposit_assets(
 		assets: AssetsInHolding,
 		dest: &Location,
@@ -936,7 +980,9 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				})
 			},
 			TransferReserveAsset { mut assets, dest, xcm } => {
-				Config::TransactionalProcessor::process(|| {
+				let transfer = (!assets.is_none()).then(|| (dest.clone(), assets.clone()));
+				let mut rejected = false;
+				let result = Config::TransactionalProcessor::process(|| {
 					let origin = self.origin_ref().ok_or(XcmError::BadOrigin)?;
 					// Take `assets` from the origin account (on-chain) and place into dest account.
 					for asset in assets.inner() {
@@ -947,6 +993,12 @@ impl<Config: config::Config> XcmExecutor<Config> {
 							&self.context,
 						)?;
 					}
+					// Checked once the origin is known to own the assets, so that only funded
+					// transfers suspend further transfers. The withdrawal is reverted.
+					if let Err(error) = Config::TransferLimiter::check_outbound(&dest, &assets) {
+						rejected = true;
+						return Err(error)
+					}
 					let reanchor_context = Config::UniversalLocation::get();
 					assets
 						.reanchor(&dest, &reanchor_context)
@@ -955,7 +1007,13 @@ impl<Config: config::Config> XcmExecutor<Config> {
 					message.extend(xcm.0.into_iter());
 					self.send(dest, Xcm(message), FeeReason::TransferReserveAsset)?;
 					Ok(())
-				})
+				});
+				match transfer {
+					Some((destination, assets)) if rejected =>
+						Config::TransferLimiter::note_rejected(&destination, &assets),
+					transfer => Self::note_outbound(transfer, &result),
+				}
+				result
 			},
 			ReceiveTeleportedAsset(assets) => {
 				let origin = self.origin_ref().ok_or(XcmError::BadOrigin)?;
@@ -1112,6 +1170,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				result
 			},
 			DepositReserveAsset { assets, dest, xcm } => {
+				let transfer = self.check_outbound(&dest, [&assets])?;
 				let old_holding = self.holding.clone();
 				let result = Config::TransactionalProcessor::process(|| {
 					let mut assets = self.holding.saturating_take(assets);
@@ -1145,9 +1204,11 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
 					self.holding = old_holding;
 				}
+				Self::note_outbound(transfer, &result);
 				result
 			},
 			InitiateReserveWithdraw { assets, reserve, xcm } => {
+				let transfer = self.check_outbound(&reserve, [&assets])?;
 				let old_holding = self.holding.clone();
 				let result = Config::TransactionalProcessor::process(|| {
 					let mut assets = self.holding.saturating_take(assets);
@@ -1180,9 +1241,11 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
 					self.holding = old_holding;
 				}
+				Self::note_outbound(transfer, &result);
 				result
 			},
 			InitiateTeleport { assets, dest, xcm } => {
+				let transfer = self.check_outbound(&dest, [&assets])?;
 				let old_holding = self.holding.clone();
 				let result = Config::TransactionalProcessor::process(|| {
 					let mut assets = self.holding.saturating_take(assets);
@@ -1210,9 +1273,14 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
 					self.holding = old_holding;
 				}
+				Self::note_outbound(transfer, &result);
 				result
 			},
 			InitiateTransfer { destination, remote_fees, preserve_origin, assets, remote_xcm } => {
+				let transfer = self.check_outbound(
+					&destination,
+					remote_fees.iter().chain(assets.iter()).map(AssetTransferFilter::inner),
+				)?;
 				let old_holding = self.holding.clone();
 				let result = Config::TransactionalProcessor::process(|| {
 					let mut message = Vec::with_capacity(assets.len() + remote_xcm.len() + 2);
@@ -1335,6 +1403,7 @@ impl<Config: config::Config> XcmExecutor<Config> {
 				if Config::TransactionalProcessor::IS_TRANSACTIONAL && result.is_err() {
 					self.holding = old_holding;
 				}
+				Self::note_outbound(transfer, &result);
 				result
 			},
 			ReportHolding { response_info, assets } => {
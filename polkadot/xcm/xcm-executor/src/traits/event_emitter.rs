@@ -15,7 +15,7 @@
 // along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
 
 use xcm::{
-	latest::{Location, SendError, Xcm, XcmHash},
+	latest::{Location, SendError, Weight, Xcm, XcmHash},
 	prelude::XcmError,
 };
 
@@ -58,6 +58,14 @@ pub trait EventEmitter {
 	/// - `error`: The error encountered while processing.
 	/// - `message_id`: The unique identifier for the failed message.
 	fn emit_process_failure_event(origin: Location, error: XcmError, message_id: XcmHash);
+
+	/// Emits an event when an XCM is executed successfully.
+	///
+	/// # Parameters
+	/// - `origin`: The origin location of the message.
+	/// - `weight_used`: The weight used to execute the message.
+	/// - `message_id`: The unique identifier for the executed message.
+	fn emit_executed_event(origin: Location, weight_used: Weight, message_id: XcmHash);
 }
 
 /// A no-op implementation of `EventEmitter` for unit type `()`.
@@ -80,4 +88,6 @@ impl EventEmitter for () {
 	}
 
 	fn emit_process_failure_event(_origin: Location, _error: XcmError, _message_id: XcmHash) {}
+
+	fn emit_executed_event(_origin: Location, _weight_used: Weight, _message_id: XcmHash) {}
 }
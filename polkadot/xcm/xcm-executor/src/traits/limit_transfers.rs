@@ -0,0 +1,51 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// Polkadot is free software: you can redistribute it and/or modify
+// it under the terms of the GNU General Public License as published by
+// the Free Software Foundation, either version 3 of the License, or
+// (at your option) any later version.
+
+// Polkadot is distributed in the hope that it will be useful,
+// but WITHOUT ANY WARRANTY; without even the implied warranty of
+// MERCHANTABILITY or FITNESS FOR A PARTICULAR PURPOSE.  See the
+// GNU General Public License for more details.
+
+// You should have received a copy of the GNU General Public License
+// along with Polkadot.  If not, see <http://www.gnu.org/licenses/>.
+
+//! Trait for limiting the assets transferred out of the chain and a dummy implementation.
+
+use xcm::latest::{Assets, Error as XcmError, Location};
+
+/// Trait for limiting the assets the executor transfers out of the chain.
+pub trait LimitTransfers {
+	/// Check that `assets`, as seen by the local chain, may leave the chain to `destination`.
+	///
+	/// Called before assets are teleported or reserve-transferred to another chain. Must not
+	/// change any state, since the assets may not even be owned by the origin of the transfer.
+	///
+	/// Returns an error if the transfer is not allowed, in which case the instruction fails.
+	fn check_outbound(destination: &Location, assets: &Assets) -> Result<(), XcmError>;
+
+	/// Note `assets` having left the chain to `destination`, once the instruction transferring
+	/// them succeeded.
+	fn note_outbound(destination: &Location, assets: &Assets);
+
+	/// Note the transfer of `assets` to `destination` having been rejected by
+	/// [`Self::check_outbound`], although the origin of the transfer owned them.
+	///
+	/// Called outside of the transactional processing of the instruction, so that its state
+	/// changes, e.g. suspending further transfers, are kept although the instruction fails.
+	fn note_rejected(destination: &Location, assets: &Assets);
+}
+
+impl LimitTransfers for () {
+	fn check_outbound(_: &Location, _: &Assets) -> Result<(), XcmError> {
+		Ok(())
+	}
+
+	fn note_outbound(_: &Location, _: &Assets) {}
+
+	fn note_rejected(_: &Location, _: &Assets) {}
+}
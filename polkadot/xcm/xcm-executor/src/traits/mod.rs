@@ -50,9 +50,11 @@ pub use hrmp::{
 	HandleHrmpChannelAccepted, HandleHrmpChannelClosing, HandleHrmpNewChannelOpenRequest,
 };
 mod event_emitter;
+mod limit_transfers;
 mod record_xcm;
 mod weight;
 pub use event_emitter::EventEmitter;
+pub use limit_transfers::LimitTransfers;
 
 pub use record_xcm::RecordXcm;
 #[deprecated = "Use `sp_runtime::traits::` instead"]
@@ -62,10 +64,10 @@ pub use weight::{WeightBounds, WeightTrader};
 pub mod prelude {
 	pub use super::{
 		export_xcm, validate_export, AssetExchange, AssetLock, ClaimAssets, ConvertOrigin,
-		DropAssets, Enact, Error, EventEmitter, ExportXcm, FeeManager, FeeReason, LockError,
-		MatchesFungible, MatchesFungibles, MatchesNonFungible, MatchesNonFungibles, OnResponse,
-		ProcessTransaction, ShouldExecute, TransactAsset, VersionChangeNotifier, WeightBounds,
-		WeightTrader, WithOriginFilter,
+		DropAssets, Enact, Error, EventEmitter, ExportXcm, FeeManager, FeeReason, LimitTransfers,
+		LockError, MatchesFungible, MatchesFungibles, MatchesNonFungible, MatchesNonFungibles,
+		OnResponse, ProcessTransaction, ShouldExecute, TransactAsset, VersionChangeNotifier,
+		WeightBounds, WeightTrader, WithOriginFilter,
 	};
 	#[allow(deprecated)]
 	pub use super::{Identity, JustTry};
@@ -0,0 +1,87 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Indexer of the lifecycle of XCM messages in imported blocks.
+
+use crate::{schema, LOG_TARGET};
+use futures::StreamExt;
+use sc_client_api::{AuxStore, BlockchainEvents};
+use sp_api::{ApiExt, ProvideRuntimeApi};
+use sp_blockchain::Result as ClientResult;
+use sp_runtime::traits::{Block as BlockT, Header as HeaderT, NumberFor};
+use std::{marker::PhantomData, sync::Arc};
+use xcm_runtime_apis::lifecycle::XcmLifecycleApi;
+
+/// The default number of blocks the records are kept for, a week of 6 seconds blocks.
+pub const DEFAULT_RETENTION: u32 = 7 * 24 * 600;
+
+/// Records the lifecycle of the XCM messages of every imported block in the aux storage.
+///
+/// Blocks whose runtime doesn't implement `XcmLifecycleApi` are skipped. Blocks imported during
+/// the major sync aren't announced by the client and thus not indexed either, so the index only
+/// covers the blocks imported once the node is synced.
+pub struct LifecycleIndexer<Block, Client> {
+	client: Arc<Client>,
+	retention: u32,
+	_phantom: PhantomData<Block>,
+}
+
+impl<Block, Client> LifecycleIndexer<Block, Client>
+where
+	Block: BlockT,
+	Client: BlockchainEvents<Block> + ProvideRuntimeApi<Block> + AuxStore + Send + Sync,
+	Client::Api: XcmLifecycleApi<Block>,
+{
+	/// Create a new indexer, keeping the records for [`DEFAULT_RETENTION`] blocks.
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, retention: DEFAULT_RETENTION, _phantom: PhantomData }
+	}
+
+	/// Keep the records for `retention` blocks.
+	pub fn with_retention(mut self, retention: u32) -> Self {
+		self.retention = retention;
+		self
+	}
+
+	/// Index the imported blocks until the client shuts down.
+	pub async fn run(self) {
+		let mut imports = self.client.import_notification_stream();
+		while let Some(notification) = imports.next().await {
+			let number = *notification.header.number();
+			if let Err(error) = self.index_block(notification.hash, number) {
+				log::warn!(
+					target: LOG_TARGET,
+					"Failed to index the XCM lifecycle of block #{number} ({:?}): {error}",
+					notification.hash,
+				);
+			}
+		}
+	}
+
+	fn index_block(&self, hash: Block::Hash, number: NumberFor<Block>) -> ClientResult<()> {
+		let api = self.client.runtime_api();
+		if !api.has_api::<dyn XcmLifecycleApi<Block>>(hash)? {
+			return Ok(())
+		}
+		let events = api.xcm_lifecycle_events(hash)?;
+		log::trace!(
+			target: LOG_TARGET,
+			"Indexing {} XCM lifecycle events of block #{number} ({hash:?})",
+			events.len(),
+		);
+		schema::write_block(&*self.client, hash, number, events, self.retention)
+	}
+}
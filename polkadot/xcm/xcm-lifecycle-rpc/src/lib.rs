@@ -0,0 +1,201 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+#![warn(missing_docs)]
+
+//! Index the lifecycle of XCM messages on the node and query it over RPC.
+//!
+//! The [`LifecycleIndexer`] records the stages of the XCM messages sent, received and processed
+//! in every imported block, as reported by the `XcmLifecycleApi` runtime API, in the aux storage
+//! of the node. Messages are identified by their id, which is their topic if they end with
+//! `SetTopic`, so that the stages of a message are found under the same id on every chain it
+//! reaches.
+//!
+//! The [`XcmLifecycle`] RPC returns the recorded stages of a message with
+//! `xcm_messageLifecycle`.
+
+mod indexer;
+mod schema;
+
+pub use indexer::{LifecycleIndexer, DEFAULT_RETENTION};
+pub use xcm_runtime_apis::lifecycle::XcmLifecycleApi as XcmLifecycleRuntimeApi;
+
+use codec::Encode;
+use jsonrpsee::{
+	core::RpcResult,
+	proc_macros::rpc,
+	types::{error::ErrorObject, ErrorObjectOwned},
+};
+use sc_client_api::AuxStore;
+use serde::{Deserialize, Serialize};
+use sp_blockchain::HeaderBackend;
+use sp_core::{Bytes, H256};
+use sp_runtime::traits::{Block as BlockT, NumberFor};
+use sp_weights::Weight;
+use std::{marker::PhantomData, sync::Arc};
+use xcm_runtime_apis::lifecycle::LifecycleStage;
+
+const LOG_TARGET: &str = "xcm::lifecycle";
+
+const STORAGE_ERROR: i32 = 8300;
+
+/// The kind of a stage of the lifecycle of a message.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum StageKind {
+	/// The message was sent.
+	Sent,
+	/// Sending the message failed.
+	SendFailed,
+	/// The message was processed by the message queue.
+	Processed,
+	/// The message queue could not process the message.
+	ProcessingFailed,
+	/// The message was too heavy to be processed and awaits manual execution.
+	Overweight,
+	/// The execution of the message failed.
+	ExecutionFailed,
+	/// The message was executed successfully.
+	Executed,
+}
+
+/// A recorded stage of the lifecycle of a message.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LifecycleRecord<BlockHash, BlockNumber> {
+	/// Hash of the block reporting the stage.
+	pub block_hash: BlockHash,
+	/// Number of the block reporting the stage.
+	pub block_number: BlockNumber,
+	/// Index of the event reporting the stage in the block.
+	pub event_index: u32,
+	/// Whether the block is part of the best chain.
+	pub canonical: bool,
+	/// The kind of the stage.
+	pub kind: StageKind,
+	/// Whether the stage succeeded.
+	pub success: bool,
+	/// The weight used to process or execute the message, if it was processed or executed.
+	pub weight_used: Option<Weight>,
+	/// The reason of the failure, if the stage failed.
+	pub error: Option<String>,
+	/// SCALE-encoded stage, see `xcm_runtime_apis::lifecycle::LifecycleStage`.
+	pub stage: Bytes,
+}
+
+impl<BlockHash, BlockNumber> LifecycleRecord<BlockHash, BlockNumber> {
+	fn new(
+		block_hash: BlockHash,
+		block_number: BlockNumber,
+		event_index: u32,
+		canonical: bool,
+		stage: LifecycleStage,
+	) -> Self {
+		let encoded = Bytes(stage.encode());
+		let (kind, success, weight_used, error) = match stage {
+			LifecycleStage::Sent { .. } => (StageKind::Sent, true, None, None),
+			LifecycleStage::SendFailed { error, .. } =>
+				(StageKind::SendFailed, false, None, Some(format!("{error:?}"))),
+			LifecycleStage::Processed { weight_used, success } =>
+				(StageKind::Processed, success, Some(weight_used), None),
+			LifecycleStage::ProcessingFailed { error } =>
+				(StageKind::ProcessingFailed, false, None, Some(format!("{error:?}"))),
+			LifecycleStage::Overweight => (StageKind::Overweight, false, None, None),
+			LifecycleStage::ExecutionFailed { error, .. } =>
+				(StageKind::ExecutionFailed, false, None, Some(format!("{error:?}"))),
+			LifecycleStage::Executed { weight_used, .. } =>
+				(StageKind::Executed, true, Some(weight_used), None),
+		};
+		Self {
+			block_hash,
+			block_number,
+			event_index,
+			canonical,
+			kind,
+			success,
+			weight_used,
+			error,
+			stage: encoded,
+		}
+	}
+}
+
+/// XCM lifecycle RPC methods.
+#[rpc(client, server)]
+pub trait XcmLifecycleApi<BlockHash, BlockNumber> {
+	/// Returns the recorded stages of the lifecycle of the message `message_id` on this chain,
+	/// oldest first.
+	///
+	/// The id of a message is its topic if it ends with `SetTopic`. Stages reported by blocks
+	/// which are not part of the best chain are returned too, see `canonical`.
+	#[method(name = "xcm_messageLifecycle")]
+	fn message_lifecycle(
+		&self,
+		message_id: H256,
+	) -> RpcResult<Vec<LifecycleRecord<BlockHash, BlockNumber>>>;
+}
+
+/// XCM lifecycle RPC methods.
+pub struct XcmLifecycle<Client, Block> {
+	client: Arc<Client>,
+	_marker: PhantomData<Block>,
+}
+
+impl<Client, Block> XcmLifecycle<Client, Block> {
+	/// Create new `XcmLifecycle` reading the records written by the [`LifecycleIndexer`].
+	pub fn new(client: Arc<Client>) -> Self {
+		Self { client, _marker: Default::default() }
+	}
+}
+
+impl<Client, Block> XcmLifecycleApiServer<Block::Hash, NumberFor<Block>>
+	for XcmLifecycle<Client, Block>
+where
+	Block: BlockT,
+	Client: HeaderBackend<Block> + AuxStore + Send + Sync + 'static,
+{
+	fn message_lifecycle(
+		&self,
+		message_id: H256,
+	) -> RpcResult<Vec<LifecycleRecord<Block::Hash, NumberFor<Block>>>> {
+		let records =
+			schema::load_records::<_, Block::Hash, NumberFor<Block>>(&*self.client, &message_id.0)
+				.map_err(storage_error_into_rpc_error)?;
+		records
+			.into_iter()
+			.map(|record| {
+				let canonical =
+					self.client.hash(record.block_number).map_err(storage_error_into_rpc_error)? ==
+						Some(record.block_hash);
+				Ok(LifecycleRecord::new(
+					record.block_hash,
+					record.block_number,
+					record.event_index,
+					canonical,
+					record.stage,
+				))
+			})
+			.collect()
+	}
+}
+
+fn storage_error_into_rpc_error(err: sp_blockchain::Error) -> ErrorObjectOwned {
+	ErrorObject::owned(
+		STORAGE_ERROR,
+		"Failed to read the XCM lifecycle index",
+		Some(err.to_string()),
+	)
+}
@@ -0,0 +1,314 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Schema of the lifecycle records in the aux storage of the node.
+//!
+//! The records of a message are stored under its id. The ids of the messages recorded in the
+//! blocks of a height are stored under that height, to prune the records once they are older
+//! than the retention period. The lowest height which is not pruned yet is stored too, so that
+//! heights skipped by the indexer, e.g. while the node was offline, are pruned as well.
+
+use codec::{Decode, Encode};
+use sc_client_api::AuxStore;
+use sp_blockchain::{Error as ClientError, Result as ClientResult};
+use sp_runtime::traits::{AtLeast32BitUnsigned, One};
+use std::collections::BTreeMap;
+use xcm::latest::XcmHash;
+use xcm_runtime_apis::lifecycle::{LifecycleEvent, LifecycleStage};
+
+const MESSAGE_PREFIX: &[u8] = b"xcm_lifecycle_message";
+const HEIGHT_PREFIX: &[u8] = b"xcm_lifecycle_height";
+const LOWEST_HEIGHT_KEY: &[u8] = b"xcm_lifecycle_lowest_height";
+
+/// The maximal number of heights pruned when writing a block.
+///
+/// Bounds the work done for a block after a long gap, the pruning catches up over the next blocks.
+pub(crate) const MAX_PRUNED_HEIGHTS: u32 = 256;
+
+/// The maximal number of records kept for a message, older records are dropped first.
+///
+/// Bounds the records of ids reused by many messages, e.g. a constant topic.
+pub(crate) const MAX_RECORDS_PER_MESSAGE: usize = 256;
+
+/// A stage of the lifecycle of a message, as stored by the indexer.
+#[derive(Clone, Debug, Encode, Decode, PartialEq)]
+pub(crate) struct StoredRecord<Hash, Number> {
+	pub block_hash: Hash,
+	pub block_number: Number,
+	pub event_index: u32,
+	pub stage: LifecycleStage,
+}
+
+fn message_key(id: &XcmHash) -> Vec<u8> {
+	(MESSAGE_PREFIX, id).encode()
+}
+
+fn height_key<Number: Encode>(number: &Number) -> Vec<u8> {
+	(HEIGHT_PREFIX, number).encode()
+}
+
+fn load_decode<B: AuxStore, T: Decode>(backend: &B, key: &[u8]) -> ClientResult<Option<T>> {
+	match backend.get_aux(key)? {
+		None => Ok(None),
+		Some(t) => T::decode(&mut &t[..])
+			.map_err(|e| ClientError::Backend(format!("XCM lifecycle DB is corrupted: {e}")))
+			.map(Some),
+	}
+}
+
+/// The records of the message `id`, oldest first.
+pub(crate) fn load_records<B, Hash, Number>(
+	backend: &B,
+	id: &XcmHash,
+) -> ClientResult<Vec<StoredRecord<Hash, Number>>>
+where
+	B: AuxStore,
+	Hash: Decode,
+	Number: Decode,
+{
+	Ok(load_decode(backend, &message_key(id))?.unwrap_or_default())
+}
+
+/// Pending writes to the aux storage, by key, `None` deleting the key.
+#[derive(Default)]
+struct Changes(BTreeMap<Vec<u8>, Option<Vec<u8>>>);
+
+impl Changes {
+	fn get<B: AuxStore, T: Decode>(&self, backend: &B, key: &[u8]) -> ClientResult<Option<T>> {
+		match self.0.get(key) {
+			Some(Some(value)) => Ok(Some(T::decode(&mut &value[..]).expect("encoded by us; qed"))),
+			Some(None) => Ok(None),
+			None => load_decode(backend, key),
+		}
+	}
+
+	fn set<T: Encode>(&mut self, key: Vec<u8>, value: Option<T>) {
+		self.0.insert(key, value.map(|value| value.encode()));
+	}
+
+	fn commit<B: AuxStore>(self, backend: &B) -> ClientResult<()> {
+		let insert: Vec<_> = self
+			.0
+			.iter()
+			.filter_map(|(key, value)| value.as_ref().map(|value| (&key[..], &value[..])))
+			.collect();
+		let delete: Vec<_> = self
+			.0
+			.iter()
+			.filter(|(_, value)| value.is_none())
+			.map(|(key, _)| &key[..])
+			.collect();
+		backend.insert_aux(&insert, &delete)
+	}
+}
+
+/// Record the lifecycle `events` of the block `block_hash` at `block_number`.
+///
+/// The records of the blocks up to `block_number - retention` are pruned, at most
+/// [`MAX_PRUNED_HEIGHTS`] heights at a time.
+pub(crate) fn write_block<B, Hash, Number>(
+	backend: &B,
+	block_hash: Hash,
+	block_number: Number,
+	events: Vec<LifecycleEvent>,
+	retention: u32,
+) -> ClientResult<()>
+where
+	B: AuxStore,
+	Hash: Encode + Decode + Copy,
+	Number: AtLeast32BitUnsigned + Encode + Decode + Copy,
+{
+	let mut changes = Changes::default();
+
+	let mut lowest: Option<Number> = changes.get(backend, LOWEST_HEIGHT_KEY)?;
+	if let (Some(height), Some(pruned)) =
+		(lowest.as_mut(), block_number.checked_sub(&retention.into()))
+	{
+		let mut count = 0;
+		while *height <= pruned && count < MAX_PRUNED_HEIGHTS {
+			prune_height::<_, Hash, _>(&mut changes, backend, *height)?;
+			*height += One::one();
+			count += 1;
+		}
+	}
+
+	if !events.is_empty() {
+		// Blocks of old forks may be imported below the lowest height.
+		lowest = Some(lowest.map_or(block_number, |lowest| lowest.min(block_number)));
+
+		let key = height_key(&block_number);
+		let mut ids: Vec<XcmHash> = changes.get(backend, &key)?.unwrap_or_default();
+		for LifecycleEvent { message_id, event_index, stage } in events {
+			let key = message_key(&message_id);
+			let mut records: Vec<StoredRecord<Hash, Number>> =
+				changes.get(backend, &key)?.unwrap_or_default();
+			records.push(StoredRecord { block_hash, block_number, event_index, stage });
+			let excess = records.len().saturating_sub(MAX_RECORDS_PER_MESSAGE);
+			records.drain(..excess);
+			changes.set(key, Some(records));
+			if !ids.contains(&message_id) {
+				ids.push(message_id);
+			}
+		}
+		changes.set(key, Some(ids));
+	}
+	changes.set(LOWEST_HEIGHT_KEY.to_vec(), lowest);
+
+	changes.commit(backend)
+}
+
+fn prune_height<B, Hash, Number>(
+	changes: &mut Changes,
+	backend: &B,
+	height: Number,
+) -> ClientResult<()>
+where
+	B: AuxStore,
+	Hash: Encode + Decode,
+	Number: AtLeast32BitUnsigned + Encode + Decode + Copy,
+{
+	let key = height_key(&height);
+	let ids: Vec<XcmHash> = changes.get(backend, &key)?.unwrap_or_default();
+	for id in ids {
+		let key = message_key(&id);
+		let mut records: Vec<StoredRecord<Hash, Number>> =
+			changes.get(backend, &key)?.unwrap_or_default();
+		records.retain(|record| record.block_number > height);
+		changes.set(key, Some(records).filter(|records| !records.is_empty()));
+	}
+	changes.set::<Vec<XcmHash>>(key, None);
+	Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+	use super::*;
+	use std::{cell::RefCell, collections::HashMap};
+	use xcm::latest::prelude::*;
+
+	#[derive(Default)]
+	struct TestBackend(RefCell<HashMap<Vec<u8>, Vec<u8>>>);
+
+	impl AuxStore for TestBackend {
+		fn insert_aux<
+			'a,
+			'b: 'a,
+			'c: 'a,
+			I: IntoIterator<Item = &'a (&'c [u8], &'c [u8])>,
+			D: IntoIterator<Item = &'a &'b [u8]>,
+		>(
+			&self,
+			insert: I,
+			delete: D,
+		) -> ClientResult<()> {
+			let mut storage = self.0.borrow_mut();
+			for (key, value) in insert {
+				storage.insert(key.to_vec(), value.to_vec());
+			}
+			for key in delete {
+				storage.remove(*key);
+			}
+			Ok(())
+		}
+
+		fn get_aux(&self, key: &[u8]) -> ClientResult<Option<Vec<u8>>> {
+			Ok(self.0.borrow().get(key).cloned())
+		}
+	}
+
+	fn sent(message_id: XcmHash, event_index: u32) -> LifecycleEvent {
+		LifecycleEvent {
+			message_id,
+			event_index,
+			stage: LifecycleStage::Sent {
+				origin: Location::here().into(),
+				destination: Location::parent().into(),
+			},
+		}
+	}
+
+	#[test]
+	fn records_are_written_by_message_and_pruned() {
+		let backend = TestBackend::default();
+		let processed = LifecycleEvent {
+			message_id: [1; 32],
+			event_index: 3,
+			stage: LifecycleStage::Processed {
+				weight_used: sp_weights::Weight::from_parts(10, 10),
+				success: false,
+			},
+		};
+
+		write_block(&backend, [10u8; 32], 10u32, vec![sent([1; 32], 1), sent([2; 32], 2)], 5)
+			.unwrap();
+		write_block(&backend, [12u8; 32], 12u32, vec![processed.clone()], 5).unwrap();
+
+		let records = load_records::<_, [u8; 32], u32>(&backend, &[1; 32]).unwrap();
+		assert_eq!(
+			records,
+			vec![
+				StoredRecord {
+					block_hash: [10; 32],
+					block_number: 10,
+					event_index: 1,
+					stage: sent([1; 32], 1).stage,
+				},
+				StoredRecord {
+					block_hash: [12; 32],
+					block_number: 12,
+					event_index: 3,
+					stage: processed.stage,
+				},
+			]
+		);
+
+		// The records of height 10 are pruned at height 15.
+		write_block(&backend, [15u8; 32], 15u32, vec![], 5).unwrap();
+		let records = load_records::<_, [u8; 32], u32>(&backend, &[1; 32]).unwrap();
+		assert_eq!(records.iter().map(|r| r.block_number).collect::<Vec<_>>(), vec![12]);
+		assert!(load_records::<_, [u8; 32], u32>(&backend, &[2; 32]).unwrap().is_empty());
+		assert!(backend.get_aux(&height_key(&10u32)).unwrap().is_none());
+	}
+
+	#[test]
+	fn heights_skipped_by_the_indexer_are_pruned() {
+		let backend = TestBackend::default();
+
+		write_block(&backend, [10u8; 32], 10u32, vec![sent([1; 32], 1)], 5).unwrap();
+		write_block(&backend, [11u8; 32], 11u32, vec![sent([2; 32], 1)], 5).unwrap();
+		// An old fork below the lowest height.
+		write_block(&backend, [8u8; 32], 8u32, vec![sent([3; 32], 1)], 5).unwrap();
+
+		// Heights 12 to 29 are not indexed, all heights up to 25 are pruned at 30.
+		write_block(&backend, [30u8; 32], 30u32, vec![sent([4; 32], 1)], 5).unwrap();
+		for id in [[1; 32], [2; 32], [3; 32]] {
+			assert!(load_records::<_, [u8; 32], u32>(&backend, &id).unwrap().is_empty());
+		}
+		assert_eq!(load_records::<_, [u8; 32], u32>(&backend, &[4; 32]).unwrap().len(), 1);
+		assert_eq!(load_decode::<_, u32>(&backend, LOWEST_HEIGHT_KEY).unwrap(), Some(26));
+
+		// The pruning is bounded, and catches up over the next blocks.
+		let far = 26 + MAX_PRUNED_HEIGHTS + 10;
+		write_block(&backend, [0u8; 32], far, vec![], 5).unwrap();
+		assert_eq!(
+			load_decode::<_, u32>(&backend, LOWEST_HEIGHT_KEY).unwrap(),
+			Some(26 + MAX_PRUNED_HEIGHTS)
+		);
+		assert!(load_records::<_, [u8; 32], u32>(&backend, &[4; 32]).unwrap().is_empty());
+		write_block(&backend, [0u8; 32], far + 1, vec![], 5).unwrap();
+		assert_eq!(load_decode::<_, u32>(&backend, LOWEST_HEIGHT_KEY).unwrap(), Some(far - 3));
+	}
+}
@@ -67,6 +67,23 @@ sp_api::decl_runtime_apis! {
 		///   different senders that charge different fees.
 		fn query_delivery_fees(destination: VersionedLocation, message: VersionedXcm<()>) -> Result<VersionedAssets, Error>;
 	}
+
+	/// Extends the [`XcmPaymentApi`] with quotes for the whole path of an asset transfer.
+	///
+	/// The `XcmPaymentApi` only quotes fees of the local chain. This API follows the messages of a
+	/// transfer call through all chains they reach, e.g. a reserve and the final destination, and
+	/// itemizes what every step costs. Execution on remote chains is estimated with the local
+	/// weigher and priced with fee tables of the remote chains cached by the local chain.
+	pub trait XcmTransferFeesApi<Call> where Call: Encode {
+		/// Returns an itemized quote of the fees of `call` dispatched by `origin`.
+		///
+		/// # Arguments
+		///
+		/// * `origin`: The location dispatching the call, relative to the local chain.
+		/// * `call`: The transfer call, e.g. `pallet_xcm::Call::transfer_assets_using_type_and_then`.
+		/// * `result_xcms_version`: The XCM version of the returned locations and assets.
+		fn query_transfer_fees(origin: VersionedLocation, call: Call, result_xcms_version: Version) -> Result<Vec<FeeItem>, TransferFeesError>;
+	}
 }
 
 #[derive(Copy, Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
@@ -95,3 +112,47 @@ pub enum Error {
 	#[codec(index = 5)]
 	Unroutable,
 }
+
+/// A single item of the quote returned by [`XcmTransferFeesApi::query_transfer_fees`].
+///
+/// All locations are relative to the local chain.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum FeeItem {
+	/// Executing the transfer program on the local chain, quoted by the local chain.
+	#[codec(index = 0)]
+	LocalExecution { weight: Weight, fees: VersionedAssets },
+
+	/// Delivering a message from the chain `from` to the chain `to`.
+	///
+	/// Quoted by the local chain for the messages it sends, priced with the cached fee table of
+	/// `from` otherwise. The assets are as seen by `from`.
+	#[codec(index = 1)]
+	Delivery { from: VersionedLocation, to: VersionedLocation, fees: VersionedAssets },
+
+	/// Executing a message on the remote chain `chain`.
+	///
+	/// The weight is estimated by the local chain, the fees are priced with the cached fee table
+	/// of `chain` in the asset the message buys execution with, as seen by `chain`. No fees are
+	/// quoted for messages which don't pay for their execution.
+	#[codec(index = 2)]
+	RemoteExecution { chain: VersionedLocation, weight: Weight, fees: VersionedAssets },
+}
+
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum TransferFeesError {
+	/// The call is not a supported asset transfer.
+	#[codec(index = 0)]
+	UnsupportedCall,
+
+	/// The transfer is invalid, e.g. because of unknown reserves or filtered assets.
+	#[codec(index = 1)]
+	InvalidTransfer,
+
+	/// There is no fee table for the given chain, or it doesn't price the required asset.
+	#[codec(index = 2)]
+	UnpricedHop(VersionedLocation),
+
+	/// Quoting the fees failed.
+	#[codec(index = 3)]
+	Fees(Error),
+}
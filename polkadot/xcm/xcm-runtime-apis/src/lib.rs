@@ -30,6 +30,9 @@ pub mod dry_run;
 /// Fee estimation API.
 /// Given an XCM program, it will return the fees needed to execute it properly or send it.
 pub mod fees;
+/// Lifecycle API.
+/// Reports the stages of the XCM messages sent, received and processed in a block.
+pub mod lifecycle;
 /// Exposes runtime API for querying whether a Location is trusted as a reserve or teleporter for a
 /// given Asset.
 pub mod trusted_query;
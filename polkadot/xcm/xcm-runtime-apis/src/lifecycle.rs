@@ -0,0 +1,75 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Runtime API for following the lifecycle of XCM messages on the local chain.
+
+use alloc::vec::Vec;
+use codec::{Decode, Encode};
+use frame_support::{pallet_prelude::TypeInfo, traits::ProcessMessageError};
+use sp_weights::Weight;
+use xcm::{
+	latest::{Error as XcmError, SendError, XcmHash},
+	VersionedLocation,
+};
+
+/// A stage of the lifecycle of an XCM message, reported by the block.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub enum LifecycleStage {
+	/// The message was sent from `origin` to `destination`.
+	#[codec(index = 0)]
+	Sent { origin: VersionedLocation, destination: VersionedLocation },
+	/// Sending the message from `origin` to `destination` failed.
+	#[codec(index = 1)]
+	SendFailed { origin: VersionedLocation, destination: VersionedLocation, error: SendError },
+	/// The message was processed by the message queue.
+	///
+	/// `success` is `false` if the message was executed but its execution failed.
+	#[codec(index = 2)]
+	Processed { weight_used: Weight, success: bool },
+	/// The message queue could not process the message.
+	#[codec(index = 3)]
+	ProcessingFailed { error: ProcessMessageError },
+	/// The message was too heavy to be processed and awaits manual execution.
+	#[codec(index = 4)]
+	Overweight,
+	/// The execution of the message received from `origin` failed with `error`.
+	#[codec(index = 5)]
+	ExecutionFailed { origin: VersionedLocation, error: XcmError },
+	/// The message received from `origin` was executed successfully.
+	#[codec(index = 6)]
+	Executed { origin: VersionedLocation, weight_used: Weight },
+}
+
+/// A stage of the lifecycle of an XCM message reported by the block.
+#[derive(Clone, Encode, Decode, Eq, PartialEq, Debug, TypeInfo)]
+pub struct LifecycleEvent {
+	/// The id of the message, i.e. its topic if it ends with `SetTopic`.
+	pub message_id: XcmHash,
+	/// The index of the event reporting the stage in the block.
+	pub event_index: u32,
+	pub stage: LifecycleStage,
+}
+
+sp_api::decl_runtime_apis! {
+	/// API for indexing the lifecycle of XCM messages sent, received and processed by the chain.
+	///
+	/// Messages are identified by their id, which is their topic if they have one, so that all
+	/// stages of a message are found under the same id, even across chains.
+	pub trait XcmLifecycleApi {
+		/// Returns the lifecycle events of the XCM messages in the events of the block.
+		fn xcm_lifecycle_events() -> Vec<LifecycleEvent>;
+	}
+}
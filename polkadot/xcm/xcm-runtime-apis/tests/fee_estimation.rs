@@ -22,14 +22,14 @@ use sp_api::ProvideRuntimeApi;
 use xcm::prelude::*;
 use xcm_runtime_apis::{
 	dry_run::{CallDryRunEffects, DryRunApi},
-	fees::XcmPaymentApi,
+	fees::{FeeItem, TransferFeesError, XcmPaymentApi, XcmTransferFeesApi},
 };
 
 mod mock;
 use mock::{
 	fake_message_hash, new_test_ext_with_balances, new_test_ext_with_balances_and_assets,
 	DeliveryFees, ExistentialDeposit, HereLocation, OriginCaller, RuntimeCall, RuntimeEvent,
-	TestClient,
+	RuntimeOrigin, TestClient, XcmPallet,
 };
 
 // Scenario: User `1` in the local chain (id 2000) wants to transfer assets to account `[0u8; 32]`
@@ -70,17 +70,13 @@ fn fee_estimation_for_teleport() {
 			.unwrap()
 			.unwrap();
 
-		assert_eq!(
-			dry_run_effects.local_xcm,
-			Some(VersionedXcm::from(
-				Xcm::builder_unsafe()
-					.withdraw_asset((Parent, 20u128))
-					.burn_asset((Parent, 20u128))
-					.withdraw_asset((Here, 100u128))
-					.burn_asset((Here, 100u128))
-					.build()
-			)),
-		);
+		let local_xcm = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 20u128))
+			.burn_asset((Parent, 20u128))
+			.withdraw_asset((Here, 100u128))
+			.burn_asset((Here, 100u128))
+			.build();
+		assert_eq!(dry_run_effects.local_xcm, Some(VersionedXcm::from(local_xcm.clone())));
 		let send_destination = Location::new(1, [Parachain(1000)]);
 		let send_message = Xcm::<()>::builder_unsafe()
 			.withdraw_asset((Parent, 20u128))
@@ -117,6 +113,11 @@ fn fee_estimation_for_teleport() {
 					balance: 20
 				}),
 				RuntimeEvent::Balances(pallet_balances::Event::Burned { who: 1, amount: 100 }),
+				RuntimeEvent::XcmPallet(pallet_xcm::Event::Executed {
+					origin: AccountIndex64 { index: 1, network: None }.into(),
+					weight_used: Weight::from_parts(400, 40),
+					message_id: fake_message_hash(&local_xcm),
+				}),
 				RuntimeEvent::XcmPallet(pallet_xcm::Event::Attempted {
 					outcome: Outcome::Complete { used: Weight::from_parts(400, 40) },
 				}),
@@ -192,6 +193,114 @@ fn fee_estimation_for_teleport() {
 	});
 }
 
+// Same scenario as in `fee_estimation_for_teleport`, but the fees of the whole transfer are
+// quoted at once with the `XcmTransferFeesApi`. The fees on "AssetHub" are priced with its fee
+// table cached by governance.
+#[test]
+fn transfer_fees_quote_for_teleport() {
+	sp_tracing::init_for_tests();
+	let who = 1; // AccountId = u64.
+	let balances = vec![(who, 100 + DeliveryFees::get() + ExistentialDeposit::get())];
+	let assets = vec![(1, who, 50)];
+	new_test_ext_with_balances_and_assets(balances, assets).execute_with(|| {
+		let client = TestClient;
+		let runtime_api = client.runtime_api();
+		let asset_hub = Location::new(1, [Parachain(1000)]);
+		let origin = VersionedLocation::from(AccountIndex64 { index: who, network: None });
+		let call = RuntimeCall::XcmPallet(pallet_xcm::Call::transfer_assets {
+			dest: Box::new(VersionedLocation::from(asset_hub.clone())),
+			beneficiary: Box::new(VersionedLocation::from(AccountId32 {
+				id: [0u8; 32],
+				network: None,
+			})),
+			assets: Box::new(VersionedAssets::from(vec![
+				(Here, 100u128).into(),
+				(Parent, 20u128).into(),
+			])),
+			fee_asset_item: 1, // Fees are paid with the RelayToken
+			weight_limit: Unlimited,
+		});
+		XcmPallet::force_default_xcm_version(RuntimeOrigin::root(), Some(XCM_VERSION)).unwrap();
+
+		// Without the fees of "AssetHub", the remote execution can't be priced.
+		assert_eq!(
+			runtime_api
+				.query_transfer_fees(H256::zero(), origin.clone(), call.clone(), XCM_VERSION)
+				.unwrap(),
+			Err(TransferFeesError::UnpricedHop(asset_hub.clone().into())),
+		);
+
+		// One unit of the relay token per unit of `ref_time` and `proof_size`.
+		let table = pallet_xcm::RemoteFeeTable {
+			execution: vec![pallet_xcm::ExecutionRate {
+				asset: AssetId(Location::parent()).into(),
+				units_per_second: 1_000_000_000_000,
+				units_per_mb: 1024 * 1024,
+			}]
+			.try_into()
+			.unwrap(),
+			delivery: None,
+		};
+		XcmPallet::set_remote_fee_table(
+			RuntimeOrigin::root(),
+			Box::new(asset_hub.clone().into()),
+			Some(table.clone()),
+		)
+		.unwrap();
+
+		let quote = runtime_api
+			.query_transfer_fees(H256::zero(), origin.clone(), call.clone(), XCM_VERSION)
+			.unwrap()
+			.unwrap();
+		assert_eq!(
+			quote,
+			vec![
+				FeeItem::LocalExecution {
+					weight: Weight::from_parts(400, 40),
+					fees: (HereLocation::get(), 440u128).into(),
+				},
+				FeeItem::Delivery {
+					from: Location::here().into(),
+					to: asset_hub.clone().into(),
+					fees: (Here, DeliveryFees::get()).into(),
+				},
+				FeeItem::RemoteExecution {
+					chain: asset_hub.clone().into(),
+					weight: Weight::from_parts(500, 50),
+					fees: (Parent, 550u128).into(),
+				},
+			]
+		);
+
+		// Fee tables set with an older XCM version are used as well.
+		XcmPallet::set_remote_fee_table(
+			RuntimeOrigin::root(),
+			Box::new(asset_hub.clone().into()),
+			None,
+		)
+		.unwrap();
+		XcmPallet::set_remote_fee_table(
+			RuntimeOrigin::root(),
+			Box::new(VersionedLocation::V4(asset_hub.clone().try_into().unwrap())),
+			Some(table),
+		)
+		.unwrap();
+		assert_eq!(
+			runtime_api
+				.query_transfer_fees(H256::zero(), origin, call, XCM_VERSION)
+				.unwrap()
+				.unwrap(),
+			quote
+		);
+
+		// Quoting doesn't transfer anything.
+		assert_eq!(
+			pallet_balances::Pallet::<mock::TestRuntime>::free_balance(who),
+			100 + DeliveryFees::get() + ExistentialDeposit::get()
+		);
+	});
+}
+
 // Same scenario as in `fee_estimation_for_teleport`, but the user in parachain 2000 wants
 // to send relay tokens over to parachain 1000.
 //
@@ -232,17 +341,16 @@ fn dry_run_reserve_asset_transfer_common(
 		let origin = OriginCaller::system(RawOrigin::Signed(who));
 		let dry_run_effects = dry_run_call(&TestClient, origin, call);
 
+		let local_xcm = Xcm::<()>::builder_unsafe()
+			.withdraw_asset((Parent, 100u128))
+			.burn_asset((Parent, 100u128))
+			.build();
 		assert_eq!(
 			dry_run_effects.local_xcm,
 			Some(
-				VersionedXcm::from(
-					Xcm::builder_unsafe()
-						.withdraw_asset((Parent, 100u128))
-						.burn_asset((Parent, 100u128))
-						.build()
-				)
-				.into_version(expected_result_xcms_version)
-				.unwrap()
+				VersionedXcm::from(local_xcm.clone())
+					.into_version(expected_result_xcms_version)
+					.unwrap()
 			),
 		);
 
@@ -275,6 +383,11 @@ fn dry_run_reserve_asset_transfer_common(
 					owner: 1,
 					balance: 100
 				}),
+				RuntimeEvent::XcmPallet(pallet_xcm::Event::Executed {
+					origin: AccountIndex64 { index: 1, network: None }.into(),
+					weight_used: Weight::from_parts(200, 20),
+					message_id: fake_message_hash(&local_xcm),
+				}),
 				RuntimeEvent::XcmPallet(pallet_xcm::Event::Attempted {
 					outcome: Outcome::Complete { used: Weight::from_parts(200, 20) }
 				}),
@@ -381,6 +494,7 @@ fn dry_run_xcm_common(xcm_version: XcmVersion) {
 		who,
 		transfer_amount + execution_fees + DeliveryFees::get() + ExistentialDeposit::get(),
 	)];
+	let message_id = fake_message_hash(&xcm);
 	new_test_ext_with_balances(balances).execute_with(|| {
 		let dry_run_effects = runtime_api
 			.dry_run_xcm(
@@ -427,7 +541,12 @@ fn dry_run_xcm_common(xcm_version: XcmVersion) {
 					destination: (Parent, Parachain(2100)).into(),
 					message: Xcm::default(),
 					message_id: expected_msg_id,
-				})
+				}),
+				RuntimeEvent::XcmPallet(pallet_xcm::Event::Executed {
+					origin: (who,).into(),
+					weight_used: dry_run_effects.execution_result.weight_used(),
+					message_id,
+				}),
 			]
 		);
 	});
@@ -0,0 +1,109 @@
+// Copyright (C) Parity Technologies (UK) Ltd.
+// This file is part of Polkadot.
+
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the XCM lifecycle API.
+
+mod mock;
+
+use frame_support::sp_runtime::testing::H256;
+use mock::*;
+use sp_api::ProvideRuntimeApi;
+use xcm::prelude::*;
+use xcm_executor::XcmExecutor;
+use xcm_runtime_apis::lifecycle::{LifecycleEvent, LifecycleStage, XcmLifecycleApi};
+
+#[test]
+fn lifecycle_events_follow_event_indices() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let client = TestClient {};
+		let runtime_api = client.runtime_api();
+
+		let origin = Location::new(0, [AccountId32 { network: None, id: [1; 32] }]);
+		let destination = Location::new(1, [Parachain(1000)]);
+		System::deposit_event(RuntimeEvent::Balances(pallet_balances::Event::Issued {
+			amount: 100,
+		}));
+		System::deposit_event(RuntimeEvent::XcmPallet(pallet_xcm::Event::SendFailed {
+			origin: origin.clone(),
+			destination: destination.clone(),
+			error: SendError::Unroutable,
+			message_id: [1; 32],
+		}));
+		System::deposit_event(RuntimeEvent::XcmPallet(pallet_xcm::Event::ProcessXcmError {
+			origin: destination.clone(),
+			error: XcmError::Barrier,
+			message_id: [2; 32],
+		}));
+
+		let events = runtime_api.xcm_lifecycle_events(H256::zero()).unwrap();
+		assert_eq!(
+			events,
+			vec![
+				LifecycleEvent {
+					message_id: [1; 32],
+					event_index: 1,
+					stage: LifecycleStage::SendFailed {
+						origin: origin.into(),
+						destination: destination.clone().into(),
+						error: SendError::Unroutable,
+					},
+				},
+				LifecycleEvent {
+					message_id: [2; 32],
+					event_index: 2,
+					stage: LifecycleStage::ExecutionFailed {
+						origin: destination.into(),
+						error: XcmError::Barrier,
+					},
+				},
+			]
+		);
+	});
+}
+
+#[test]
+fn executed_messages_are_reported() {
+	new_test_ext_with_balances(vec![]).execute_with(|| {
+		let client = TestClient {};
+		let runtime_api = client.runtime_api();
+
+		let origin = Location::new(0, [AccountId32 { network: None, id: [1; 32] }]);
+		let weight = Weight::from_parts(1_000_000_000, 1_000_000);
+		System::deposit_event(RuntimeEvent::Balances(pallet_balances::Event::Issued {
+			amount: 100,
+		}));
+		let mut message_id = [3; 32];
+		let outcome = XcmExecutor::<XcmConfig>::prepare_and_execute(
+			origin.clone(),
+			Xcm(vec![ClearOrigin]),
+			&mut message_id,
+			weight,
+			weight,
+		);
+		let Outcome::Complete { used } = outcome else { panic!("{outcome:?}") };
+
+		let events = runtime_api.xcm_lifecycle_events(H256::zero()).unwrap();
+		assert_eq!(
+			events,
+			vec![LifecycleEvent {
+				message_id: [3; 32],
+				event_index: 1,
+				stage: LifecycleStage::Executed { origin: origin.into(), weight_used: used },
+			}]
+		);
+	});
+}
@@ -47,7 +47,10 @@ use xcm_executor::{
 use xcm_runtime_apis::{
 	conversions::{Error as LocationToAccountApiError, LocationToAccountApi},
 	dry_run::{CallDryRunEffects, DryRunApi, Error as XcmDryRunApiError, XcmDryRunEffects},
-	fees::{Error as XcmPaymentApiError, XcmPaymentApi},
+	fees::{
+		Error as XcmPaymentApiError, FeeItem, TransferFeesError, XcmPaymentApi, XcmTransferFeesApi,
+	},
+	lifecycle::{LifecycleEvent, XcmLifecycleApi},
 	trusted_query::{Error as TrustedQueryApiError, TrustedQueryApi},
 };
 
@@ -328,6 +331,7 @@ impl xcm_executor::Config for XcmConfig {
 	type HrmpChannelAcceptedHandler = ();
 	type HrmpChannelClosingHandler = ();
 	type XcmRecorder = XcmPallet;
+	type TransferLimiter = ();
 }
 
 /// Converts a signed origin of a u64 account into a location with only the `AccountIndex64`
@@ -502,6 +506,28 @@ sp_api::mock_impl_runtime_apis! {
 		}
 	}
 
+	impl XcmTransferFeesApi<Block, RuntimeCall> for RuntimeApi {
+		fn query_transfer_fees(origin: VersionedLocation, call: RuntimeCall, result_xcms_version: XcmVersion) -> Result<Vec<FeeItem>, TransferFeesError> {
+			let RuntimeCall::XcmPallet(call) = call else { return Err(TransferFeesError::UnsupportedCall) };
+			XcmPallet::query_transfer_fees(origin, call, result_xcms_version, |weight| {
+				Ok((HereLocation::get(), WeightToFee::weight_to_fee(&weight)).into())
+			})
+		}
+	}
+
+	impl XcmLifecycleApi<Block> for RuntimeApi {
+		fn xcm_lifecycle_events() -> Vec<LifecycleEvent> {
+			System::read_events_no_consensus()
+				.enumerate()
+				.filter_map(|(index, record)| {
+					let RuntimeEvent::XcmPallet(event) = record.event else { return None };
+					let (message_id, stage) = XcmPallet::lifecycle_stage(event)?;
+					Some(LifecycleEvent { message_id, event_index: index as u32, stage })
+				})
+				.collect()
+		}
+	}
+
 	impl DryRunApi<Block, RuntimeCall, RuntimeEvent, OriginCaller> for RuntimeApi {
 		fn dry_run_call(
 			origin: OriginCaller,
@@ -39,19 +39,25 @@ use sp_trie::{
 use std::{
 	cell::{Cell, RefCell},
 	collections::HashMap,
-	sync::Arc,
+	sync::{
+		atomic::{AtomicU32, Ordering},
+		Arc,
+	},
 };
 
 type State<H> = DbState<H>;
 
 struct StorageDb<Hasher> {
 	db: Arc<dyn KeyValueDB>,
+	/// Number of trie nodes fetched from `db`.
+	fetches: Arc<AtomicU32>,
 	_phantom: std::marker::PhantomData<Hasher>,
 }
 
 impl<Hasher: Hash> sp_state_machine::Storage<Hasher> for StorageDb<Hasher> {
 	fn get(&self, key: &Hasher::Output, prefix: Prefix) -> Result<Option<DBValue>, String> {
 		let prefixed_key = prefixed_key::<Hasher>(key, prefix);
+		self.fetches.fetch_add(1, Ordering::Relaxed);
 		self.db
 			.get(0, &prefixed_key)
 			.map_err(|e| format!("Database backend error: {:?}", e))
@@ -69,6 +75,9 @@ struct KeyTracker {
 	/// We track the total number of reads and writes to these keys,
 	/// not de-duplicated for repeats.
 	child_keys: LinkedHashMap<Vec<u8>, LinkedHashMap<Vec<u8>, TrackedStorageKey>>,
+	/// Number of first reads of a key which were served by the trie cache,
+	/// without fetching anything from the database.
+	warm_reads: u32,
 }
 
 /// State that manages the backend database reference. Allows runtime to control the database.
@@ -84,6 +93,7 @@ pub struct BenchmarkingState<Hasher: Hash> {
 	proof_recorder: Option<sp_trie::recorder::Recorder<Hasher>>,
 	proof_recorder_root: Cell<Hasher::Output>,
 	shared_trie_cache: SharedTrieCache<Hasher>,
+	db_fetches: Arc<AtomicU32>,
 }
 
 /// A raw iterator over the `BenchmarkingState`.
@@ -127,9 +137,12 @@ impl<Hasher: Hash> StorageIterator<Hasher> for RawIter<Hasher> {
 
 impl<Hasher: Hash> BenchmarkingState<Hasher> {
 	/// Create a new instance that creates a database in a temporary dir.
+	///
+	/// `cache_size_mb` is the size of the shared trie cache that is kept between commits. Without
+	/// it, every benchmark starts with a cold cache and all reads hit the database.
 	pub fn new(
 		genesis: Storage,
-		_cache_size_mb: Option<usize>,
+		cache_size_mb: Option<usize>,
 		record_proof: bool,
 		enable_tracking: bool,
 	) -> Result<Self, String> {
@@ -148,13 +161,17 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 			key_tracker: Arc::new(Mutex::new(KeyTracker {
 				main_keys: Default::default(),
 				child_keys: Default::default(),
+				warm_reads: 0,
 				enable_tracking,
 			})),
 			whitelist: Default::default(),
 			proof_recorder: record_proof.then(Default::default),
 			proof_recorder_root: Cell::new(root),
-			// Enable the cache, but do not sync anything to the shared state.
-			shared_trie_cache: SharedTrieCache::new(CacheSize::new(0)),
+			// Without a cache size, enable the cache but do not sync anything to the shared state.
+			shared_trie_cache: SharedTrieCache::new(CacheSize::new(
+				cache_size_mb.unwrap_or(0) * 1024 * 1024,
+			)),
+			db_fetches: Default::default(),
 		};
 
 		state.add_whitelist_to_tracker();
@@ -195,7 +212,11 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 			recorder.reset();
 			self.proof_recorder_root.set(self.root.get());
 		}
-		let storage_db = Arc::new(StorageDb::<Hasher> { db, _phantom: Default::default() });
+		let storage_db = Arc::new(StorageDb::<Hasher> {
+			db,
+			fetches: self.db_fetches.clone(),
+			_phantom: Default::default(),
+		});
 		*self.state.borrow_mut() = Some(
 			DbStateBuilder::<Hasher>::new(storage_db, self.root.get())
 				.with_optional_recorder(self.proof_recorder.clone())
@@ -213,6 +234,7 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 		let mut key_tracker = self.key_tracker.lock();
 		key_tracker.main_keys = LinkedHashMap::new();
 		key_tracker.child_keys = LinkedHashMap::new();
+		key_tracker.warm_reads = 0;
 		key_tracker.add_whitelist(&self.whitelist.borrow());
 	}
 
@@ -220,6 +242,23 @@ impl<Hasher: Hash> BenchmarkingState<Hasher> {
 		self.key_tracker.lock().add_read_key(childtrie, key);
 	}
 
+	/// Track the read of `key` done by `read`, counting it as warm if it is the first read of the
+	/// key and nothing had to be fetched from the database.
+	fn tracked_read<R>(
+		&self,
+		childtrie: Option<&[u8]>,
+		key: &[u8],
+		read: impl FnOnce(&State<Hasher>) -> Result<R, String>,
+	) -> Result<R, String> {
+		let first_read = self.key_tracker.lock().add_read_key(childtrie, key);
+		let fetches = self.db_fetches.load(Ordering::Relaxed);
+		let result = read(self.state.borrow().as_ref().ok_or_else(state_err)?);
+		if first_read && self.db_fetches.load(Ordering::Relaxed) == fetches {
+			self.key_tracker.lock().warm_reads += 1;
+		}
+		result
+	}
+
 	fn add_write_key(&self, childtrie: Option<&[u8]>, key: &[u8]) {
 		self.key_tracker.lock().add_write_key(childtrie, key);
 	}
@@ -239,9 +278,11 @@ impl KeyTracker {
 	}
 
 	// Childtrie is identified by its storage key (i.e. `ChildInfo::storage_key`)
-	fn add_read_key(&mut self, childtrie: Option<&[u8]>, key: &[u8]) {
+	//
+	// Returns whether this is the first read of a key which is not whitelisted.
+	fn add_read_key(&mut self, childtrie: Option<&[u8]>, key: &[u8]) -> bool {
 		if !self.enable_tracking {
-			return
+			return false
 		}
 
 		let child_key_tracker = &mut self.child_keys;
@@ -277,6 +318,7 @@ impl KeyTracker {
 				log::trace!(target: "benchmark", "Read: {}", HexDisplay::from(&key));
 			}
 		}
+		should_log
 	}
 
 	// Childtrie is identified by its storage key (i.e. `ChildInfo::storage_key`)
@@ -349,13 +391,11 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 	type RawIter = RawIter<Hasher>;
 
 	fn storage(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
-		self.add_read_key(None, key);
-		self.state.borrow().as_ref().ok_or_else(state_err)?.storage(key)
+		self.tracked_read(None, key, |state| state.storage(key))
 	}
 
 	fn storage_hash(&self, key: &[u8]) -> Result<Option<Hasher::Output>, Self::Error> {
-		self.add_read_key(None, key);
-		self.state.borrow().as_ref().ok_or_else(state_err)?.storage_hash(key)
+		self.tracked_read(None, key, |state| state.storage_hash(key))
 	}
 
 	fn child_storage(
@@ -363,12 +403,9 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 		child_info: &ChildInfo,
 		key: &[u8],
 	) -> Result<Option<Vec<u8>>, Self::Error> {
-		self.add_read_key(Some(child_info.storage_key()), key);
-		self.state
-			.borrow()
-			.as_ref()
-			.ok_or_else(state_err)?
-			.child_storage(child_info, key)
+		self.tracked_read(Some(child_info.storage_key()), key, |state| {
+			state.child_storage(child_info, key)
+		})
 	}
 
 	fn child_storage_hash(
@@ -376,20 +413,16 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 		child_info: &ChildInfo,
 		key: &[u8],
 	) -> Result<Option<Hasher::Output>, Self::Error> {
-		self.add_read_key(Some(child_info.storage_key()), key);
-		self.state
-			.borrow()
-			.as_ref()
-			.ok_or_else(state_err)?
-			.child_storage_hash(child_info, key)
+		self.tracked_read(Some(child_info.storage_key()), key, |state| {
+			state.child_storage_hash(child_info, key)
+		})
 	}
 
 	fn closest_merkle_value(
 		&self,
 		key: &[u8],
 	) -> Result<Option<MerkleValue<Hasher::Output>>, Self::Error> {
-		self.add_read_key(None, key);
-		self.state.borrow().as_ref().ok_or_else(state_err)?.closest_merkle_value(key)
+		self.tracked_read(None, key, |state| state.closest_merkle_value(key))
 	}
 
 	fn child_closest_merkle_value(
@@ -397,17 +430,11 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 		child_info: &ChildInfo,
 		key: &[u8],
 	) -> Result<Option<MerkleValue<Hasher::Output>>, Self::Error> {
-		self.add_read_key(None, key);
-		self.state
-			.borrow()
-			.as_ref()
-			.ok_or_else(state_err)?
-			.child_closest_merkle_value(child_info, key)
+		self.tracked_read(None, key, |state| state.child_closest_merkle_value(child_info, key))
 	}
 
 	fn exists_storage(&self, key: &[u8]) -> Result<bool, Self::Error> {
-		self.add_read_key(None, key);
-		self.state.borrow().as_ref().ok_or_else(state_err)?.exists_storage(key)
+		self.tracked_read(None, key, |state| state.exists_storage(key))
 	}
 
 	fn exists_child_storage(
@@ -415,12 +442,9 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 		child_info: &ChildInfo,
 		key: &[u8],
 	) -> Result<bool, Self::Error> {
-		self.add_read_key(Some(child_info.storage_key()), key);
-		self.state
-			.borrow()
-			.as_ref()
-			.ok_or_else(state_err)?
-			.exists_child_storage(child_info, key)
+		self.tracked_read(Some(child_info.storage_key()), key, |state| {
+			state.exists_child_storage(child_info, key)
+		})
 	}
 
 	fn next_storage_key(&self, key: &[u8]) -> Result<Option<Vec<u8>>, Self::Error> {
@@ -539,6 +563,8 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 
 		self.root.set(self.genesis_root);
 		self.reopen()?;
+		// Every run of a benchmark should start with the same cache.
+		self.shared_trie_cache.reset();
 		self.wipe_tracker();
 		Ok(())
 	}
@@ -575,6 +601,11 @@ impl<Hasher: Hash> StateBackend<Hasher> for BenchmarkingState<Hasher> {
 		self.wipe_tracker()
 	}
 
+	/// Get the number of first reads of keys which did not hit the database.
+	fn warm_read_count(&self) -> u32 {
+		self.key_tracker.lock().warm_reads
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.whitelist.borrow().to_vec()
 	}
@@ -730,4 +761,41 @@ mod test {
 			bench_state.wipe().unwrap();
 		}
 	}
+
+	#[test]
+	fn reads_served_by_the_trie_cache_are_counted_as_warm() {
+		let storage = sp_runtime::Storage {
+			top: vec![(vec![1; 32], vec![1; 64]), (vec![2; 32], vec![2; 64])]
+				.into_iter()
+				.collect(),
+			..sp_runtime::Storage::default()
+		};
+
+		for (cache_size_mb, expected_warm_reads) in [(None, 0), (Some(1), 1)] {
+			let bench_state = BenchmarkingState::<HashingFor<crate::tests::Block>>::new(
+				storage.clone(),
+				cache_size_mb,
+				false,
+				true,
+			)
+			.unwrap();
+
+			// Read the first key in the setup and commit, as the benchmarking harness does.
+			bench_state.storage(&[1; 32]).unwrap();
+			bench_state
+				.commit(bench_state.root.get(), Default::default(), Vec::new(), Vec::new())
+				.unwrap();
+			bench_state.reset_read_write_count();
+
+			bench_state.storage(&[1; 32]).unwrap();
+			bench_state.storage(&[2; 32]).unwrap();
+			assert_eq!(bench_state.read_write_count(), (2, 0, 0, 0));
+			assert_eq!(bench_state.warm_read_count(), expected_warm_reads);
+
+			// Wiping starts again with a cold cache.
+			bench_state.wipe().unwrap();
+			bench_state.storage(&[1; 32]).unwrap();
+			assert_eq!(bench_state.warm_read_count(), 0);
+		}
+	}
 }
@@ -25,15 +25,22 @@ use sp_wasm_interface::{
 	Function, FunctionContext, HostFunctions, Pointer, Value, ValueType, WordSize,
 };
 
-#[repr(transparent)]
-pub struct InstancePre(polkavm::InstancePre<(), String>);
+pub struct InstancePre {
+	instance_pre: polkavm::InstancePre<(), String>,
+	heap_limit: u32,
+}
 
-#[repr(transparent)]
-pub struct Instance(polkavm::Instance<(), String>);
+pub struct Instance {
+	instance: polkavm::Instance<(), String>,
+	heap_limit: u32,
+}
 
 impl WasmModule for InstancePre {
 	fn new_instance(&self) -> Result<Box<dyn WasmInstance>, Error> {
-		Ok(Box::new(Instance(self.0.instantiate()?)))
+		Ok(Box::new(Instance {
+			instance: self.instance_pre.instantiate()?,
+			heap_limit: self.heap_limit,
+		}))
 	}
 }
 
@@ -43,7 +50,7 @@ impl WasmInstance for Instance {
 		name: &str,
 		raw_data: &[u8],
 	) -> (Result<Vec<u8>, Error>, Option<AllocationStats>) {
-		let pc = match self.0.module().exports().find(|e| e.symbol() == name) {
+		let pc = match self.instance.module().exports().find(|e| e.symbol() == name) {
 			Some(export) => export.program_counter(),
 			None =>
 				return (
@@ -62,7 +69,7 @@ impl WasmInstance for Instance {
 		// TODO: This will leak guest memory; find a better solution.
 
 		// Make sure that the memory is cleared...
-		if let Err(err) = self.0.reset_memory() {
+		if let Err(err) = self.instance.reset_memory() {
 			return (
 				Err(format!(
 					"call into the runtime method '{name}' failed: reset memory failed: {err}"
@@ -73,7 +80,7 @@ impl WasmInstance for Instance {
 		}
 
 		// ... and allocate space for the input payload.
-		if let Err(err) = self.0.sbrk(raw_data_length) {
+		if let Err(err) = self.instance.sbrk(raw_data_length) {
 			return (
 				Err(format!(
 					"call into the runtime method '{name}' failed: reset memory failed: {err}"
@@ -85,13 +92,13 @@ impl WasmInstance for Instance {
 
 		// Grab the address of where the guest's heap starts; that's where we've just allocated
 		// the memory for the input payload.
-		let data_pointer = self.0.module().memory_map().heap_base();
+		let data_pointer = self.instance.module().memory_map().heap_base();
 
-		if let Err(err) = self.0.write_memory(data_pointer, raw_data) {
+		if let Err(err) = self.instance.write_memory(data_pointer, raw_data) {
 			return (Err(format!("call into the runtime method '{name}': failed to write the input payload into guest memory: {err}").into()), None);
 		}
 
-		match self.0.call_typed(&mut (), pc, (data_pointer, raw_data_length)) {
+		match self.instance.call_typed(&mut (), pc, (data_pointer, raw_data_length)) {
 			Ok(()) => {},
 			Err(CallError::Trap) =>
 				return (
@@ -111,9 +118,20 @@ impl WasmInstance for Instance {
 			Err(CallError::NotEnoughGas) => unreachable!("gas metering is never enabled"),
 		};
 
-		let result_pointer = self.0.reg(Reg::A0);
-		let result_length = self.0.reg(Reg::A1);
-		let output = match self.0.read_memory(result_pointer as u32, result_length as u32) {
+		if self.instance.heap_size() > self.heap_limit {
+			return (
+				Err(format!(
+					"call into the runtime method '{name}' failed: heap limit of {} bytes exceeded",
+					self.heap_limit,
+				)
+				.into()),
+				None,
+			);
+		}
+
+		let result_pointer = self.instance.reg(Reg::A0);
+		let result_length = self.instance.reg(Reg::A1);
+		let output = match self.instance.read_memory(result_pointer as u32, result_length as u32) {
 			Ok(output) => output,
 			Err(error) => {
 				return (Err(format!("call into the runtime method '{name}' failed: failed to read the return payload: {error}").into()), None)
@@ -124,7 +142,10 @@ impl WasmInstance for Instance {
 	}
 }
 
-struct Context<'r, 'a>(&'r mut polkavm::Caller<'a, ()>);
+struct Context<'r, 'a> {
+	caller: &'r mut polkavm::Caller<'a, ()>,
+	heap_limit: u32,
+}
 
 impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	fn read_memory_into(
@@ -132,7 +153,7 @@ impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 		address: Pointer<u8>,
 		dest: &mut [u8],
 	) -> sp_wasm_interface::Result<()> {
-		self.0
+		self.caller
 			.instance
 			.read_memory_into(u32::from(address), dest)
 			.map_err(|error| error.to_string())
@@ -140,20 +161,27 @@ impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	}
 
 	fn write_memory(&mut self, address: Pointer<u8>, data: &[u8]) -> sp_wasm_interface::Result<()> {
-		self.0
+		self.caller
 			.instance
 			.write_memory(u32::from(address), data)
 			.map_err(|error| error.to_string())
 	}
 
 	fn allocate_memory(&mut self, size: WordSize) -> sp_wasm_interface::Result<Pointer<u8>> {
-		let pointer = match self.0.instance.sbrk(0) {
+		let pointer = match self.caller.instance.sbrk(0) {
 			Ok(pointer) => pointer.expect("fetching the current heap pointer never fails"),
 			Err(err) => return Err(format!("sbrk failed: {err}")),
 		};
 
+		if self.caller.instance.heap_size().saturating_add(size) > self.heap_limit {
+			return Err(format!(
+				"allocation error: heap limit of {} bytes exceeded",
+				self.heap_limit
+			))
+		}
+
 		// TODO: This will leak guest memory; find a better solution.
-		match self.0.instance.sbrk(size) {
+		match self.caller.instance.sbrk(size) {
 			Ok(Some(_)) => (),
 			Ok(None) => return Err(String::from("allocation error")),
 			Err(err) => return Err(format!("sbrk failed: {err}")),
@@ -172,7 +200,11 @@ impl<'r, 'a> FunctionContext for Context<'r, 'a> {
 	}
 }
 
-fn call_host_function(caller: &mut Caller<()>, function: &dyn Function) -> Result<(), String> {
+fn call_host_function(
+	caller: &mut Caller<()>,
+	function: &dyn Function,
+	heap_limit: u32,
+) -> Result<(), String> {
 	let mut args = [Value::I64(0); Reg::ARG_REGS.len()];
 	let mut nth_reg = 0;
 	for (nth_arg, kind) in function.signature().args.iter().enumerate() {
@@ -221,9 +253,18 @@ fn call_host_function(caller: &mut Caller<()>, function: &dyn Function) -> Resul
 		&args[..function.signature().args.len()]
 	);
 
-	let value = match function
-		.execute(&mut Context(caller), &mut args.into_iter().take(function.signature().args.len()))
-	{
+	// the guest may grow its heap without calling the host, so we check the limit here too
+	if caller.instance.heap_size() > heap_limit {
+		return Err(format!(
+			"call into the host function '{}' failed: heap limit of {heap_limit} bytes exceeded",
+			function.name(),
+		))
+	}
+
+	let value = match function.execute(
+		&mut Context { caller: &mut *caller, heap_limit },
+		&mut args.into_iter().take(function.signature().args.len()),
+	) {
 		Ok(value) => value,
 		Err(error) => {
 			let name = function.name();
@@ -259,6 +300,9 @@ fn call_host_function(caller: &mut Caller<()>, function: &dyn Function) -> Resul
 	Ok(())
 }
 
+/// Creates the PolkaVM runtime for the given program.
+///
+/// The engine is configured using the `POLKAVM_*` environment variables.
 pub fn create_runtime<H>(blob: &polkavm::ProgramBlob) -> Result<Box<dyn WasmModule>, WasmError>
 where
 	H: HostFunctions,
@@ -278,16 +322,32 @@ where
 		},
 	};
 
-	let module =
-		polkavm::Module::from_blob(&engine, &polkavm::ModuleConfig::default(), blob.clone())?;
+	create_runtime_with_config::<H>(engine, &polkavm::ModuleConfig::default(), blob, u32::MAX)
+}
+
+/// Creates the PolkaVM runtime for the given program, using the given engine and module
+/// configuration.
+///
+/// The guest heap may not grow beyond `heap_limit` bytes. The limit is checked when the host
+/// allocates guest memory and every time the control returns from the guest to the host.
+pub fn create_runtime_with_config<H>(
+	engine: &polkavm::Engine,
+	module_config: &polkavm::ModuleConfig,
+	blob: &polkavm::ProgramBlob,
+	heap_limit: u32,
+) -> Result<Box<dyn WasmModule>, WasmError>
+where
+	H: HostFunctions,
+{
+	let module = polkavm::Module::from_blob(engine, module_config, blob.clone())?;
 
 	let mut linker = polkavm::Linker::new();
 
 	for function in H::host_functions() {
-		linker.define_untyped(function.name(), |mut caller: Caller<()>| {
-			call_host_function(&mut caller, function)
+		linker.define_untyped(function.name(), move |mut caller: Caller<()>| {
+			call_host_function(&mut caller, function, heap_limit)
 		})?;
 	}
 	let instance_pre = linker.instantiate_pre(&module)?;
-	Ok(Box::new(InstancePre(instance_pre)))
+	Ok(Box::new(InstancePre { instance_pre, heap_limit }))
 }
@@ -35,6 +35,10 @@ pub enum BenchmarkSelector {
 	ExtrinsicTime,
 	StorageRootTime,
 	Reads,
+	/// Reads of keys which were not found in the trie cache and hit the database.
+	ColdReads,
+	/// Reads of keys which were served by the trie cache.
+	WarmReads,
 	Writes,
 	ProofSize,
 }
@@ -84,6 +88,8 @@ impl BenchmarkSelector {
 			BenchmarkSelector::ExtrinsicTime => result.extrinsic_time,
 			BenchmarkSelector::StorageRootTime => result.storage_root_time,
 			BenchmarkSelector::Reads => result.reads.into(),
+			BenchmarkSelector::ColdReads => result.cold_reads().into(),
+			BenchmarkSelector::WarmReads => result.warm_reads.into(),
 			BenchmarkSelector::Writes => result.writes.into(),
 			BenchmarkSelector::ProofSize => result.proof_size.into(),
 		}
@@ -208,6 +214,8 @@ impl Analysis {
 				BenchmarkSelector::ExtrinsicTime => result.extrinsic_time,
 				BenchmarkSelector::StorageRootTime => result.storage_root_time,
 				BenchmarkSelector::Reads => result.reads.into(),
+				BenchmarkSelector::ColdReads => result.cold_reads().into(),
+				BenchmarkSelector::WarmReads => result.warm_reads.into(),
 				BenchmarkSelector::Writes => result.writes.into(),
 				BenchmarkSelector::ProofSize => result.proof_size.into(),
 			})
@@ -261,6 +269,8 @@ impl Analysis {
 							BenchmarkSelector::ExtrinsicTime => result.extrinsic_time,
 							BenchmarkSelector::StorageRootTime => result.storage_root_time,
 							BenchmarkSelector::Reads => result.reads.into(),
+							BenchmarkSelector::ColdReads => result.cold_reads().into(),
+							BenchmarkSelector::WarmReads => result.warm_reads.into(),
 							BenchmarkSelector::Writes => result.writes.into(),
 							BenchmarkSelector::ProofSize => result.proof_size.into(),
 						};
@@ -339,6 +349,8 @@ impl Analysis {
 				BenchmarkSelector::ExtrinsicTime => result.extrinsic_time,
 				BenchmarkSelector::StorageRootTime => result.storage_root_time,
 				BenchmarkSelector::Reads => result.reads.into(),
+				BenchmarkSelector::ColdReads => result.cold_reads().into(),
+				BenchmarkSelector::WarmReads => result.warm_reads.into(),
 				BenchmarkSelector::Writes => result.writes.into(),
 				BenchmarkSelector::ProofSize => result.proof_size.into(),
 			})
@@ -536,6 +548,7 @@ mod tests {
 			repeat_writes: 0,
 			proof_size: 0,
 			keys: vec![],
+			warm_reads: 0,
 		}
 	}
 
@@ -120,12 +120,23 @@ pub struct BenchmarkResult {
 	pub proof_size: u32,
 	#[cfg_attr(feature = "std", serde(skip))]
 	pub keys: Vec<(Vec<u8>, u32, u32, bool)>,
+	/// The number of `reads` which were served by the trie cache instead of the database.
+	///
+	/// Only non-zero if the benchmarks run with a warm trie cache. Only returned by version 3 of
+	/// the [`Benchmark`] runtime API onwards.
+	#[cfg_attr(feature = "std", serde(default))]
+	pub warm_reads: u32,
 }
 
 impl BenchmarkResult {
 	pub fn from_weight(w: Weight) -> Self {
 		Self { extrinsic_time: (w.ref_time() / 1_000) as u128, ..Default::default() }
 	}
+
+	/// The number of `reads` which had to go to the database.
+	pub fn cold_reads(&self) -> u32 {
+		self.reads.saturating_sub(self.warm_reads)
+	}
 }
 
 /// Helper module to make serde serialize `Vec<u8>` as strings.
@@ -231,7 +242,9 @@ pub struct BenchmarkMetadata {
 
 sp_api::decl_runtime_apis! {
 	/// Runtime api for benchmarking a FRAME runtime.
-	#[api_version(2)]
+	///
+	/// Version 3 appends `warm_reads` to every [`BenchmarkResult`].
+	#[api_version(3)]
 	pub trait Benchmark {
 		/// Get the benchmark metadata available for this runtime.
 		///
@@ -279,6 +292,11 @@ pub trait Benchmarking {
 		self.reset_read_write_count()
 	}
 
+	/// Get the number of reads which were served by the trie cache instead of the database.
+	fn warm_read_count(&self) -> u32 {
+		self.warm_read_count()
+	}
+
 	/// Get the DB whitelist.
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.get_whitelist()
@@ -1122,6 +1122,7 @@ macro_rules! impl_benchmark {
 						"End Benchmark: {} ns", elapsed_extrinsic
 					);
 					let read_write_count = $crate::benchmarking::read_write_count();
+					let warm_reads = $crate::benchmarking::warm_read_count();
 					$crate::__private::log::trace!(
 						target: "benchmark",
 						"Read/Write Count {:?}", read_write_count
@@ -1154,6 +1155,7 @@ macro_rules! impl_benchmark {
 						repeat_writes: read_write_count.3,
 						proof_size: diff_pov,
 						keys: read_and_written_keys,
+						warm_reads,
 					});
 				}
 
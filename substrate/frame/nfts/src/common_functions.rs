@@ -61,6 +61,32 @@ impl<T: Config<I>, I: 'static> Pallet<T, I> {
 		Ok(())
 	}
 
+	/// Create a collection with the next available ID, owned and managed by `owner`, without
+	/// reserving a deposit.
+	///
+	/// Meant for pallets managing collections on behalf of the chain, e.g. mirrors of collections
+	/// living on other chains.
+	pub fn force_create_collection(
+		owner: T::AccountId,
+		config: CollectionConfigFor<T, I>,
+	) -> Result<T::CollectionId, DispatchError> {
+		let collection = NextCollectionId::<T, I>::get()
+			.or(T::CollectionId::initial_value())
+			.ok_or(Error::<T, I>::UnknownCollection)?;
+
+		Self::do_create_collection(
+			collection,
+			owner.clone(),
+			owner.clone(),
+			config,
+			Zero::zero(),
+			Event::ForceCreated { collection, owner },
+		)?;
+
+		Self::set_next_collection_id(collection);
+		Ok(collection)
+	}
+
 	pub(crate) fn set_next_collection_id(collection: T::CollectionId) {
 		let next_id = collection.increment();
 		NextCollectionId::<T, I>::set(next_id);
@@ -763,21 +763,7 @@ pub mod pallet {
 		) -> DispatchResult {
 			T::ForceOrigin::ensure_origin(origin)?;
 			let owner = T::Lookup::lookup(owner)?;
-
-			let collection = NextCollectionId::<T, I>::get()
-				.or(T::CollectionId::initial_value())
-				.ok_or(Error::<T, I>::UnknownCollection)?;
-
-			Self::do_create_collection(
-				collection,
-				owner.clone(),
-				owner.clone(),
-				config,
-				Zero::zero(),
-				Event::ForceCreated { collection, owner },
-			)?;
-
-			Self::set_next_collection_id(collection);
+			Self::force_create_collection(owner, config)?;
 			Ok(())
 		}
 
@@ -105,6 +105,12 @@ impl<Address, Signature, E: EthExtra> ExtrinsicMetadata
 		Signature,
 		E::Extension,
 	>::VERSIONS;
+	const EXTENSION_VERSIONS: &'static [u8] = generic::UncheckedExtrinsic::<
+		Address,
+		CallOf<E::Config>,
+		Signature,
+		E::Extension,
+	>::EXTENSION_VERSIONS;
 	type TransactionExtensions = E::Extension;
 }
 
@@ -814,6 +814,7 @@ pub fn benchmarks(
 							"End Benchmark: {} ns", elapsed_extrinsic
 						);
 						let read_write_count = #krate::benchmarking::read_write_count();
+						let warm_reads = #krate::benchmarking::warm_read_count();
 						#krate::__private::log::trace!(
 							target: "benchmark",
 							"Read/Write Count {:?}", read_write_count
@@ -842,6 +843,7 @@ pub fn benchmarks(
 							repeat_writes: read_write_count.3,
 							proof_size: diff_pov,
 							keys: read_and_written_keys,
+							warm_reads,
 						});
 					}
 
@@ -216,6 +216,27 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 					let max_values = max_values.unwrap_or_else(|| default_max_values.clone());
 					args.args.push(syn::GenericArgument::Type(max_values));
 				},
+				StorageGenerics::IndexedMap {
+					hasher,
+					key,
+					value,
+					indices,
+					query_kind,
+					on_empty,
+					max_values,
+				} => {
+					args.args.push(syn::GenericArgument::Type(hasher));
+					args.args.push(syn::GenericArgument::Type(key));
+					args.args.push(syn::GenericArgument::Type(value.clone()));
+					args.args.push(syn::GenericArgument::Type(indices));
+					let mut query_kind = query_kind.unwrap_or_else(|| default_query_kind.clone());
+					set_result_query_type_parameter(&mut query_kind)?;
+					args.args.push(syn::GenericArgument::Type(query_kind));
+					let on_empty = on_empty.unwrap_or_else(|| default_on_empty(value));
+					args.args.push(syn::GenericArgument::Type(on_empty));
+					let max_values = max_values.unwrap_or_else(|| default_max_values.clone());
+					args.args.push(syn::GenericArgument::Type(max_values));
+				},
 				StorageGenerics::DoubleMap {
 					hasher1,
 					key1,
@@ -265,12 +286,15 @@ pub fn process_generics(def: &mut Def) -> syn::Result<Vec<ResultOnEmptyStructMet
 				Metadata::Value { .. } => (1, 2, 3),
 				Metadata::NMap { .. } | Metadata::CountedNMap { .. } => (2, 3, 4),
 				Metadata::Map { .. } | Metadata::CountedMap { .. } => (3, 4, 5),
+				Metadata::IndexedMap { .. } => (3, 5, 6),
 				Metadata::DoubleMap { .. } => (5, 6, 7),
 			};
 
 			if storage_def.use_default_hasher {
 				let hasher_indices: Vec<usize> = match storage_def.metadata {
-					Metadata::Map { .. } | Metadata::CountedMap { .. } => vec![1],
+					Metadata::Map { .. } |
+					Metadata::CountedMap { .. } |
+					Metadata::IndexedMap { .. } => vec![1],
 					Metadata::DoubleMap { .. } => vec![1, 3],
 					_ => vec![],
 				};
@@ -376,6 +400,14 @@ fn augment_final_docs(def: &mut Def) {
 			);
 			push_string_literal(&doc_line, storage);
 		},
+		Metadata::IndexedMap { key, value } => {
+			let doc_line = format!(
+				"Storage type is [`IndexedStorageMap`] with key type {} and value type {}.",
+				key.to_token_stream(),
+				value.to_token_stream()
+			);
+			push_string_literal(&doc_line, storage);
+		},
 	});
 }
 
@@ -515,7 +547,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 						}
 					)
 				},
-				Metadata::CountedMap { key, value } => {
+				Metadata::CountedMap { key, value } | Metadata::IndexedMap { key, value } => {
 					let query = match storage.query_kind.as_ref().expect("Checked by def") {
 						QueryKind::OptionQuery => quote::quote_spanned!(storage.attr_span =>
 							Option<#value>
@@ -535,7 +567,7 @@ pub fn expand_storages(def: &mut Def) -> proc_macro2::TokenStream {
 								KArg: #frame_support::__private::codec::EncodeLike<#key>,
 							{
 								// NOTE: we can't use any trait here because CountedStorageMap
-								// doesn't implement any.
+								// and IndexedStorageMap don't implement any.
 								<#full_ident>::get(k)
 							}
 						}
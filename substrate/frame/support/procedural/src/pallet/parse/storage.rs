@@ -153,6 +153,7 @@ pub enum Metadata {
 	Value { value: syn::Type },
 	Map { value: syn::Type, key: syn::Type },
 	CountedMap { value: syn::Type, key: syn::Type },
+	IndexedMap { value: syn::Type, key: syn::Type },
 	DoubleMap { value: syn::Type, key1: syn::Type, key2: syn::Type },
 	NMap { keys: Vec<syn::Type>, keygen: syn::Type, value: syn::Type },
 	CountedNMap { keys: Vec<syn::Type>, keygen: syn::Type, value: syn::Type },
@@ -240,6 +241,15 @@ pub enum StorageGenerics {
 		on_empty: Option<syn::Type>,
 		max_values: Option<syn::Type>,
 	},
+	IndexedMap {
+		hasher: syn::Type,
+		key: syn::Type,
+		value: syn::Type,
+		indices: syn::Type,
+		query_kind: Option<syn::Type>,
+		on_empty: Option<syn::Type>,
+		max_values: Option<syn::Type>,
+	},
 	Value {
 		value: syn::Type,
 		query_kind: Option<syn::Type>,
@@ -268,6 +278,7 @@ impl StorageGenerics {
 			Self::DoubleMap { value, key1, key2, .. } => Metadata::DoubleMap { value, key1, key2 },
 			Self::Map { value, key, .. } => Metadata::Map { value, key },
 			Self::CountedMap { value, key, .. } => Metadata::CountedMap { value, key },
+			Self::IndexedMap { value, key, .. } => Metadata::IndexedMap { value, key },
 			Self::Value { value, .. } => Metadata::Value { value },
 			Self::NMap { keygen, value, .. } =>
 				Metadata::NMap { keys: collect_keys(&keygen)?, keygen, value },
@@ -284,6 +295,7 @@ impl StorageGenerics {
 			Self::DoubleMap { query_kind, .. } |
 			Self::Map { query_kind, .. } |
 			Self::CountedMap { query_kind, .. } |
+			Self::IndexedMap { query_kind, .. } |
 			Self::Value { query_kind, .. } |
 			Self::NMap { query_kind, .. } |
 			Self::CountedNMap { query_kind, .. } => query_kind.clone(),
@@ -295,6 +307,7 @@ enum StorageKind {
 	Value,
 	Map,
 	CountedMap,
+	IndexedMap,
 	DoubleMap,
 	NMap,
 	CountedNMap,
@@ -461,6 +474,40 @@ fn process_named_generics(
 				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
 			}
 		},
+		StorageKind::IndexedMap => {
+			let mut indexed_map_mandatory_generics = map_mandatory_generics.clone();
+			indexed_map_mandatory_generics.push("Indices");
+
+			check_generics(
+				&parsed,
+				&indexed_map_mandatory_generics,
+				&map_optional_generics,
+				"IndexedStorageMap",
+				args_span,
+			)?;
+
+			StorageGenerics::IndexedMap {
+				hasher: parsed
+					.remove("Hasher")
+					.map(|binding| binding.ty)
+					.unwrap_or(syn::parse_quote!(Blake2_128Concat)),
+				key: parsed
+					.remove("Key")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				value: parsed
+					.remove("Value")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				indices: parsed
+					.remove("Indices")
+					.map(|binding| binding.ty)
+					.expect("checked above as mandatory generic"),
+				query_kind: parsed.remove("QueryKind").map(|binding| binding.ty),
+				on_empty: parsed.remove("OnEmpty").map(|binding| binding.ty),
+				max_values: parsed.remove("MaxValues").map(|binding| binding.ty),
+			}
+		},
 		StorageKind::DoubleMap => {
 			let mut double_map_mandatory_generics = vec!["Key1", "Key2", "Value"];
 			if dev_mode {
@@ -614,6 +661,12 @@ fn process_unnamed_generics(
 			retrieve_arg(4).ok(),
 			use_default_hasher(1)?,
 		),
+		StorageKind::IndexedMap => (
+			None,
+			Metadata::IndexedMap { key: retrieve_arg(2)?, value: retrieve_arg(3)? },
+			retrieve_arg(5).ok(),
+			use_default_hasher(1)?,
+		),
 		StorageKind::DoubleMap => (
 			None,
 			Metadata::DoubleMap {
@@ -658,14 +711,15 @@ fn process_generics(
 		"StorageValue" => StorageKind::Value,
 		"StorageMap" => StorageKind::Map,
 		"CountedStorageMap" => StorageKind::CountedMap,
+		"IndexedStorageMap" => StorageKind::IndexedMap,
 		"StorageDoubleMap" => StorageKind::DoubleMap,
 		"StorageNMap" => StorageKind::NMap,
 		"CountedStorageNMap" => StorageKind::CountedNMap,
 		found => {
 			let msg = format!(
 				"Invalid pallet::storage, expected ident: `StorageValue` or \
-				`StorageMap` or `CountedStorageMap` or `StorageDoubleMap` or `StorageNMap` or `CountedStorageNMap` \
-				in order to expand metadata, found `{}`.",
+				`StorageMap` or `CountedStorageMap` or `IndexedStorageMap` or `StorageDoubleMap` or `StorageNMap` \
+				or `CountedStorageNMap` in order to expand metadata, found `{}`.",
 				found,
 			);
 			return Err(syn::Error::new(segment.ident.span(), msg));
@@ -0,0 +1,629 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Storage map type with secondary indices.
+
+use crate::{
+	storage::{
+		generator::StorageMap as _,
+		types::{OptionQuery, QueryKindTrait, StorageEntryMetadataBuilder, StorageMap},
+		IterableStorageDoubleMap,
+	},
+	traits::{Get, GetDefault, StorageInfo, StorageInfoTrait, StorageInstance},
+	Never,
+};
+use alloc::vec::Vec;
+use codec::{Encode, EncodeLike, FullCodec, MaxEncodedLen, Ref};
+use sp_metadata_ir::StorageEntryMetadataIR;
+use sp_runtime::TryRuntimeError;
+
+/// A wrapper around a [`StorageMap`] that keeps one or more secondary indices consistent with the
+/// values of the map.
+///
+/// Every index is a separate storage item (usually a [`super::StorageDoubleMap`] from the index
+/// key to the primary key, with `()` as value) together with a [`SecondaryKey`] describing how
+/// the index key is derived from a value. The indices are passed as the `Indices` generic, either
+/// as a single [`SecondaryIndex`] or as a tuple of them.
+///
+/// All the functions which modify the map (`insert`, `remove`, `mutate`, `take`, ...) also update
+/// every index. Functions of [`StorageMap`] which would write raw values without decoding them
+/// (e.g. `append` or `swap`) are intentionally not exposed, as they can't keep the indices in
+/// sync.
+///
+/// Compared to a regular storage map, each write reads the previous value of the entry and writes
+/// to each index whose key changed.
+///
+/// Entries with a given index key can be enumerated with
+/// [`IndexedStorageMap::iter_by_index`], and the consistency of the indices with the map can be
+/// verified with [`IndexedStorageMap::try_state`], which is intended to be called from the
+/// `try_state` hook of the pallet.
+///
+/// For general information regarding the `#[pallet::storage]` attribute, refer to
+/// [`crate::pallet_macros::storage`].
+///
+/// # Examples
+///
+/// Declaring an indexed map:
+///
+/// ```
+/// #[frame_support::pallet]
+/// mod pallet {
+/// # 	use frame_support::pallet_prelude::*;
+/// # 	use frame_support::storage::types::{DoubleMapIndex, SecondaryKey};
+/// # 	#[pallet::config]
+/// # 	pub trait Config: frame_system::Config {}
+/// # 	#[pallet::pallet]
+/// # 	pub struct Pallet<T>(_);
+/// 	#[derive(Encode, Decode, MaxEncodedLen, TypeInfo)]
+/// 	pub struct Item {
+/// 		pub owner: u64,
+/// 		pub price: u32,
+/// 	}
+///
+/// 	/// Index items by their owner.
+/// 	pub struct ByOwner;
+/// 	impl SecondaryKey<Item> for ByOwner {
+/// 		type IndexKey = u64;
+/// 		fn secondary_key(value: &Item) -> Option<u64> {
+/// 			Some(value.owner)
+/// 		}
+/// 	}
+///
+/// 	/// All items, indexed by their owner in [`ItemsByOwner`].
+/// 	#[pallet::storage]
+/// 	pub type Items<T> = IndexedStorageMap<
+/// 		_,
+/// 		Blake2_128Concat,
+/// 		u32,
+/// 		Item,
+/// 		DoubleMapIndex<ItemsByOwner<T>, ByOwner>,
+/// 	>;
+///
+/// 	/// The secondary index of [`Items`].
+/// 	#[pallet::storage]
+/// 	pub type ItemsByOwner<T> =
+/// 		StorageDoubleMap<_, Blake2_128Concat, u64, Blake2_128Concat, u32, ()>;
+/// }
+/// ```
+pub struct IndexedStorageMap<
+	Prefix,
+	Hasher,
+	Key,
+	Value,
+	Indices,
+	QueryKind = OptionQuery,
+	OnEmpty = GetDefault,
+	MaxValues = GetDefault,
+>(core::marker::PhantomData<(Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues)>);
+
+/// Derive the key under which a value is stored in a secondary index.
+pub trait SecondaryKey<Value> {
+	/// The key of the secondary index.
+	type IndexKey: FullCodec;
+
+	/// Return the index key of `value`, or `None` if the value should not be indexed.
+	fn secondary_key(value: &Value) -> Option<Self::IndexKey>;
+}
+
+/// A secondary index maintained by an [`IndexedStorageMap`].
+///
+/// Implemented by [`DoubleMapIndex`] and for tuples of secondary indices.
+pub trait SecondaryIndex<Key, Value> {
+	/// Update the index after the entry under `key` changed from `old` to `new`.
+	///
+	/// `None` means the entry does not exist.
+	fn on_update<KeyArg: EncodeLike<Key>>(key: &KeyArg, old: Option<&Value>, new: Option<&Value>);
+
+	/// Ensure that the entry `(key, value)` of the primary map is present in the index.
+	fn ensure_indexed(key: &Key, value: &Value) -> Result<(), TryRuntimeError>;
+
+	/// Ensure that every entry of the index points to an entry of the primary map which has the
+	/// corresponding index key.
+	///
+	/// `get` returns the value of the primary map for a given key.
+	fn ensure_no_dangling<F: Fn(&Key) -> Option<Value>>(get: &F) -> Result<(), TryRuntimeError>;
+}
+
+#[impl_trait_for_tuples::impl_for_tuples(8)]
+impl<Key, Value> SecondaryIndex<Key, Value> for Tuple {
+	fn on_update<KeyArg: EncodeLike<Key>>(key: &KeyArg, old: Option<&Value>, new: Option<&Value>) {
+		for_tuples!( #( Tuple::on_update(key, old, new); )* );
+	}
+
+	fn ensure_indexed(key: &Key, value: &Value) -> Result<(), TryRuntimeError> {
+		for_tuples!( #( Tuple::ensure_indexed(key, value)?; )* );
+		Ok(())
+	}
+
+	fn ensure_no_dangling<F: Fn(&Key) -> Option<Value>>(get: &F) -> Result<(), TryRuntimeError> {
+		for_tuples!( #( Tuple::ensure_no_dangling(get)?; )* );
+		Ok(())
+	}
+}
+
+/// A secondary index that can be iterated by index key.
+pub trait IterableSecondaryIndex<Key, Value> {
+	/// The key of the secondary index.
+	type IndexKey: FullCodec;
+
+	/// The type that iterates over the primary keys stored under an index key.
+	type KeyIterator: Iterator<Item = Key>;
+
+	/// Enumerate all primary keys stored under `index_key`.
+	fn iter_keys(index_key: impl EncodeLike<Self::IndexKey>) -> Self::KeyIterator;
+}
+
+/// A [`SecondaryIndex`] stored in a double map from the index key to the primary key.
+///
+/// `Map` is the storage double map holding the index, it should have `()` as value and use a
+/// reversible hasher for its second key, so that primary keys can be recovered when iterating.
+/// `Extractor` derives the index key from a value of the primary map.
+pub struct DoubleMapIndex<Map, Extractor>(core::marker::PhantomData<(Map, Extractor)>);
+
+impl<Map, Extractor, Key, Value> SecondaryIndex<Key, Value> for DoubleMapIndex<Map, Extractor>
+where
+	Key: FullCodec,
+	Extractor: SecondaryKey<Value>,
+	Map: IterableStorageDoubleMap<Extractor::IndexKey, Key, ()>,
+{
+	fn on_update<KeyArg: EncodeLike<Key>>(key: &KeyArg, old: Option<&Value>, new: Option<&Value>) {
+		let old_index_key = old.and_then(Extractor::secondary_key);
+		let new_index_key = new.and_then(Extractor::secondary_key);
+		if old_index_key.as_ref().map(Encode::encode) == new_index_key.as_ref().map(Encode::encode)
+		{
+			return
+		}
+		if let Some(index_key) = old_index_key {
+			Map::remove(index_key, Ref::from(key));
+		}
+		if let Some(index_key) = new_index_key {
+			Map::insert(index_key, Ref::from(key), ());
+		}
+	}
+
+	fn ensure_indexed(key: &Key, value: &Value) -> Result<(), TryRuntimeError> {
+		if let Some(index_key) = Extractor::secondary_key(value) {
+			crate::ensure!(
+				Map::contains_key(index_key, key),
+				"Entry of the indexed map is missing from its secondary index"
+			);
+		}
+		Ok(())
+	}
+
+	fn ensure_no_dangling<F: Fn(&Key) -> Option<Value>>(get: &F) -> Result<(), TryRuntimeError> {
+		for (index_key, key) in Map::iter_keys() {
+			let value = get(&key).ok_or("Secondary index points to a missing entry")?;
+			crate::ensure!(
+				Extractor::secondary_key(&value).map(|k| k.encode()) == Some(index_key.encode()),
+				"Secondary index key doesn't match the entry of the indexed map"
+			);
+		}
+		Ok(())
+	}
+}
+
+impl<Map, Extractor, Key, Value> IterableSecondaryIndex<Key, Value>
+	for DoubleMapIndex<Map, Extractor>
+where
+	Key: FullCodec,
+	Extractor: SecondaryKey<Value>,
+	Map: IterableStorageDoubleMap<Extractor::IndexKey, Key, ()>,
+{
+	type IndexKey = Extractor::IndexKey;
+	type KeyIterator = Map::PartialKeyIterator;
+
+	fn iter_keys(index_key: impl EncodeLike<Self::IndexKey>) -> Self::KeyIterator {
+		Map::iter_key_prefix(index_key)
+	}
+}
+
+// Private helper trait to access the primary map from the indexed storage map.
+trait MapWrapper {
+	type Map;
+}
+
+impl<P: StorageInstance, H, K, V, I, Q, O, M> MapWrapper
+	for IndexedStorageMap<P, H, K, V, I, Q, O, M>
+{
+	type Map = StorageMap<P, H, K, V, Q, O, M>;
+}
+
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+	IndexedStorageMap<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	Indices: SecondaryIndex<Key, Value>,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	/// Get the storage key used to fetch a value corresponding to a specific key.
+	pub fn hashed_key_for<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Vec<u8> {
+		<Self as MapWrapper>::Map::hashed_key_for(key)
+	}
+
+	/// Does the value (explicitly) exist in storage?
+	pub fn contains_key<KeyArg: EncodeLike<Key>>(key: KeyArg) -> bool {
+		<Self as MapWrapper>::Map::contains_key(key)
+	}
+
+	/// Load the value associated with the given key from the map.
+	pub fn get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		<Self as MapWrapper>::Map::get(key)
+	}
+
+	/// Try to get the value for the given key from the map.
+	///
+	/// Returns `Ok` if it exists, `Err` if not.
+	pub fn try_get<KeyArg: EncodeLike<Key>>(key: KeyArg) -> Result<Value, ()> {
+		<Self as MapWrapper>::Map::try_get(key)
+	}
+
+	/// Store or remove the value to be associated with `key` so that `get` returns the `query`.
+	pub fn set<KeyArg: EncodeLike<Key>>(key: KeyArg, q: QueryKind::Query) {
+		match QueryKind::from_query_to_optional_value(q) {
+			Some(v) => Self::insert(key, v),
+			None => Self::remove(key),
+		}
+	}
+
+	/// Store a value to be associated with the given key from the map.
+	pub fn insert<KeyArg: EncodeLike<Key>>(key: KeyArg, val: Value) {
+		Self::mutate_exists(key, |value| *value = Some(val))
+	}
+
+	/// Remove the value under a key.
+	pub fn remove<KeyArg: EncodeLike<Key>>(key: KeyArg) {
+		Self::mutate_exists(key, |value| *value = None)
+	}
+
+	/// Take the value under a key.
+	pub fn take<KeyArg: EncodeLike<Key>>(key: KeyArg) -> QueryKind::Query {
+		let removed_value = Self::mutate_exists(key, |value| value.take());
+		<Self as MapWrapper>::Map::from_optional_value_to_query(removed_value)
+	}
+
+	/// Mutate the value under a key.
+	pub fn mutate<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut QueryKind::Query) -> R>(
+		key: KeyArg,
+		f: F,
+	) -> R {
+		Self::try_mutate(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned.
+	pub fn try_mutate<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut QueryKind::Query) -> Result<R, E>,
+	{
+		Self::try_mutate_exists(key, |option_value_ref| {
+			let option_value = core::mem::replace(option_value_ref, None);
+			let mut query = <Self as MapWrapper>::Map::from_optional_value_to_query(option_value);
+			let res = f(&mut query);
+			let option_value = <Self as MapWrapper>::Map::from_query_to_optional_value(query);
+			let _ = core::mem::replace(option_value_ref, option_value);
+			res
+		})
+	}
+
+	/// Mutate the value under a key. Deletes the item if mutated to a `None`.
+	pub fn mutate_exists<KeyArg: EncodeLike<Key>, R, F: FnOnce(&mut Option<Value>) -> R>(
+		key: KeyArg,
+		f: F,
+	) -> R {
+		Self::try_mutate_exists(key, |v| Ok::<R, Never>(f(v)))
+			.expect("`Never` can not be constructed; qed")
+	}
+
+	/// Mutate the item, only if an `Ok` value is returned. Deletes the item if mutated to a `None`.
+	/// `f` will always be called with an option representing if the storage item exists (`Some<V>`)
+	/// or if the storage item does not exist (`None`), independent of the `QueryType`.
+	///
+	/// The indices are only updated if `f` returns `Ok`.
+	pub fn try_mutate_exists<KeyArg, R, E, F>(key: KeyArg, f: F) -> Result<R, E>
+	where
+		KeyArg: EncodeLike<Key>,
+		F: FnOnce(&mut Option<Value>) -> Result<R, E>,
+	{
+		let old_value = <Self as MapWrapper>::Map::try_get(Ref::from(&key)).ok();
+		<Self as MapWrapper>::Map::try_mutate_exists(Ref::from(&key), |option_value| {
+			let res = f(option_value);
+			if res.is_ok() {
+				Indices::on_update(&key, old_value.as_ref(), option_value.as_ref());
+			}
+			res
+		})
+	}
+
+	/// Iter over all value of the storage.
+	///
+	/// NOTE: If a value failed to decode because storage is corrupted then it is skipped.
+	pub fn iter_values() -> crate::storage::PrefixIterator<Value> {
+		<Self as MapWrapper>::Map::iter_values()
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+	IndexedStorageMap<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher + crate::ReversibleStorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	Indices: SecondaryIndex<Key, Value>,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	/// Enumerate all elements in the map in no particular order.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter() -> crate::storage::PrefixIterator<(Key, Value)> {
+		<Self as MapWrapper>::Map::iter()
+	}
+
+	/// Enumerate all keys in the map.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter_keys() -> crate::storage::KeyPrefixIterator<Key> {
+		<Self as MapWrapper>::Map::iter_keys()
+	}
+
+	/// Enumerate all elements of the map stored under `index_key` in the secondary index `Index`.
+	///
+	/// `Index` should be one of the indices of this map. Index entries pointing to a missing value
+	/// are skipped.
+	///
+	/// If you alter the map while doing this, you'll get undefined results.
+	pub fn iter_by_index<Index: IterableSecondaryIndex<Key, Value>>(
+		index_key: impl EncodeLike<Index::IndexKey>,
+	) -> impl Iterator<Item = (Key, Value)> {
+		Index::iter_keys(index_key).filter_map(|key| {
+			let value = <Self as MapWrapper>::Map::try_get(&key).ok()?;
+			Some((key, value))
+		})
+	}
+
+	/// Ensure that all the secondary indices are consistent with the map.
+	///
+	/// Every entry of the map must be present in every index it has an index key for, and every
+	/// entry of an index must point to an entry of the map with the same index key.
+	///
+	/// This iterates over the whole map and all the indices, so it should only be used in
+	/// `try_state` checks and tests.
+	pub fn try_state() -> Result<(), TryRuntimeError> {
+		for (key, value) in Self::iter() {
+			Indices::ensure_indexed(&key, &value)?;
+		}
+		Indices::ensure_no_dangling(&|key: &Key| <Self as MapWrapper>::Map::try_get(key).ok())
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues> StorageEntryMetadataBuilder
+	for IndexedStorageMap<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + scale_info::StaticTypeInfo,
+	Value: FullCodec + scale_info::StaticTypeInfo,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	fn build_metadata(
+		deprecation_status: sp_metadata_ir::DeprecationStatusIR,
+		docs: Vec<&'static str>,
+		entries: &mut Vec<StorageEntryMetadataIR>,
+	) {
+		<Self as MapWrapper>::Map::build_metadata(deprecation_status, docs, entries);
+	}
+}
+
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues> StorageInfoTrait
+	for IndexedStorageMap<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec + MaxEncodedLen,
+	Value: FullCodec + MaxEncodedLen,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	fn storage_info() -> Vec<StorageInfo> {
+		<Self as MapWrapper>::Map::storage_info()
+	}
+}
+
+/// It doesn't require to implement `MaxEncodedLen` and give no information for `max_size`.
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+	crate::traits::PartialStorageInfoTrait
+	for IndexedStorageMap<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues>
+where
+	Prefix: StorageInstance,
+	Hasher: crate::hash::StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	fn partial_storage_info() -> Vec<StorageInfo> {
+		<Self as MapWrapper>::Map::partial_storage_info()
+	}
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+	use crate::{hash::*, storage::types::StorageDoubleMap};
+	use sp_io::TestExternalities;
+
+	struct Prefix;
+	impl StorageInstance for Prefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo";
+	}
+
+	struct IndexPrefix;
+	impl StorageInstance for IndexPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo_by_parity";
+	}
+
+	struct OtherIndexPrefix;
+	impl StorageInstance for OtherIndexPrefix {
+		fn pallet_prefix() -> &'static str {
+			"test"
+		}
+		const STORAGE_PREFIX: &'static str = "foo_by_bucket";
+	}
+
+	/// Index even values only, by their parity.
+	struct EvenOnly;
+	impl SecondaryKey<u32> for EvenOnly {
+		type IndexKey = bool;
+		fn secondary_key(value: &u32) -> Option<bool> {
+			(value % 2 == 0).then_some(true)
+		}
+	}
+
+	/// Index all values by `value / 10`.
+	struct Bucket;
+	impl SecondaryKey<u32> for Bucket {
+		type IndexKey = u32;
+		fn secondary_key(value: &u32) -> Option<u32> {
+			Some(value / 10)
+		}
+	}
+
+	type ParityIndex = StorageDoubleMap<IndexPrefix, Twox64Concat, bool, Twox64Concat, u16, ()>;
+	type BucketIndex = StorageDoubleMap<OtherIndexPrefix, Twox64Concat, u32, Twox64Concat, u16, ()>;
+
+	type A = IndexedStorageMap<
+		Prefix,
+		Twox64Concat,
+		u16,
+		u32,
+		(DoubleMapIndex<ParityIndex, EvenOnly>, DoubleMapIndex<BucketIndex, Bucket>),
+	>;
+
+	fn keys_in_bucket(bucket: u32) -> Vec<u16> {
+		let mut keys = BucketIndex::iter_key_prefix(bucket).collect::<Vec<_>>();
+		keys.sort();
+		keys
+	}
+
+	#[test]
+	fn indices_follow_writes() {
+		TestExternalities::default().execute_with(|| {
+			A::insert(1, 12);
+			A::insert(2, 13);
+			A::insert(3, 24);
+			assert_eq!(keys_in_bucket(1), vec![1, 2]);
+			assert_eq!(keys_in_bucket(2), vec![3]);
+			assert!(ParityIndex::contains_key(true, 1));
+			assert!(!ParityIndex::contains_key(true, 2));
+			assert_eq!(A::try_state(), Ok(()));
+
+			// Overwriting moves the entry between index keys.
+			A::insert(1, 25);
+			assert_eq!(keys_in_bucket(1), vec![2]);
+			assert_eq!(keys_in_bucket(2), vec![1, 3]);
+			assert!(!ParityIndex::contains_key(true, 1));
+
+			A::mutate(2, |v| *v = Some(14));
+			assert!(ParityIndex::contains_key(true, 2));
+			assert_eq!(A::try_state(), Ok(()));
+
+			assert_eq!(A::take(3), Some(24));
+			A::remove(2);
+			assert_eq!(keys_in_bucket(1), Vec::<u16>::new());
+			assert_eq!(keys_in_bucket(2), vec![1]);
+			assert_eq!(ParityIndex::iter().count(), 0);
+			assert_eq!(A::try_state(), Ok(()));
+		});
+	}
+
+	#[test]
+	fn failed_mutation_leaves_indices_untouched() {
+		TestExternalities::default().execute_with(|| {
+			A::insert(1, 12);
+			let res = A::try_mutate_exists(1, |v| {
+				*v = Some(30);
+				Err::<(), _>(())
+			});
+			assert_eq!(res, Err(()));
+			assert_eq!(A::get(1), Some(12));
+			assert_eq!(keys_in_bucket(1), vec![1]);
+			assert_eq!(keys_in_bucket(3), Vec::<u16>::new());
+			assert_eq!(A::try_state(), Ok(()));
+		});
+	}
+
+	#[test]
+	fn iter_by_index_works() {
+		TestExternalities::default().execute_with(|| {
+			A::insert(1, 12);
+			A::insert(2, 13);
+			A::insert(3, 24);
+
+			let mut bucket =
+				A::iter_by_index::<DoubleMapIndex<BucketIndex, Bucket>>(1u32).collect::<Vec<_>>();
+			bucket.sort();
+			assert_eq!(bucket, vec![(1, 12), (2, 13)]);
+
+			let mut even =
+				A::iter_by_index::<DoubleMapIndex<ParityIndex, EvenOnly>>(true).collect::<Vec<_>>();
+			even.sort();
+			assert_eq!(even, vec![(1, 12), (3, 24)]);
+		});
+	}
+
+	#[test]
+	fn try_state_detects_inconsistencies() {
+		TestExternalities::default().execute_with(|| {
+			A::insert(1, 12);
+			assert_eq!(A::try_state(), Ok(()));
+
+			// Missing index entry.
+			BucketIndex::remove(1, 1);
+			assert!(A::try_state().is_err());
+			BucketIndex::insert(1, 1, ());
+			assert_eq!(A::try_state(), Ok(()));
+
+			// Dangling index entry.
+			BucketIndex::insert(7, 5, ());
+			assert!(A::try_state().is_err());
+			BucketIndex::remove(7, 5);
+
+			// Index entry under the wrong index key.
+			BucketIndex::insert(4, 1, ());
+			assert!(A::try_state().is_err());
+		});
+	}
+}
@@ -25,6 +25,7 @@ use sp_metadata_ir::{StorageEntryMetadataIR, StorageEntryModifierIR};
 mod counted_map;
 mod counted_nmap;
 mod double_map;
+mod indexed_map;
 mod key;
 mod map;
 mod nmap;
@@ -33,6 +34,9 @@ mod value;
 pub use counted_map::{CountedStorageMap, CountedStorageMapInstance, Counter};
 pub use counted_nmap::{CountedStorageNMap, CountedStorageNMapInstance};
 pub use double_map::StorageDoubleMap;
+pub use indexed_map::{
+	DoubleMapIndex, IndexedStorageMap, IterableSecondaryIndex, SecondaryIndex, SecondaryKey,
+};
 pub use key::{
 	EncodeLikeTuple, HasKeyPrefix, HasReversibleKeyPrefix, Key, KeyGenerator,
 	KeyGeneratorMaxEncodedLen, ReversibleKeyGenerator, TupleToEncodedIter,
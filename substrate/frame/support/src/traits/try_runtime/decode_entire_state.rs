@@ -218,6 +218,35 @@ where
 	}
 }
 
+impl<Prefix, Hasher, Key, Value, Indices, QueryKind, OnEmpty, MaxValues> TryDecodeEntireStorage
+	for crate::storage::types::IndexedStorageMap<
+		Prefix,
+		Hasher,
+		Key,
+		Value,
+		Indices,
+		QueryKind,
+		OnEmpty,
+		MaxValues,
+	>
+where
+	Prefix: StorageInstance,
+	Hasher: StorageHasher,
+	Key: FullCodec,
+	Value: FullCodec,
+	QueryKind: QueryKindTrait<Value, OnEmpty>,
+	OnEmpty: Get<QueryKind::Query> + 'static,
+	MaxValues: Get<Option<u32>>,
+{
+	fn try_decode_entire_state() -> Result<usize, Vec<TryDecodeEntireStorageError>> {
+		let info = Self::partial_storage_info()
+			.first()
+			.cloned()
+			.expect("Indexed map has only one storage info; qed");
+		decode_storage_info::<Value>(info)
+	}
+}
+
 impl<Prefix, Hasher1, Key1, Hasher2, Key2, Value, QueryKind, OnEmpty, MaxValues>
 	TryDecodeEntireStorage
 	for crate::storage::types::StorageDoubleMap<
@@ -50,11 +50,11 @@ parameter_types! {
 	storage UpdateStorageVersion: bool = false;
 }
 
-/// Latest stable metadata version used for testing.
-const LATEST_METADATA_VERSION: u32 = 15;
+/// Metadata version 15, whose layout is checked by `metadata`.
+const METADATA_VERSION_15: u32 = 15;
 
-/// Unstable metadata version.
-const UNSTABLE_METADATA_VERSION: u32 = u32::MAX;
+/// Latest stable metadata version used for testing.
+const LATEST_METADATA_VERSION: u32 = 16;
 
 pub struct SomeType1;
 impl From<SomeType1> for u64 {
@@ -1963,8 +1963,8 @@ fn metadata() {
 		_ => panic!("metadata has been bumped, test needs to be updated"),
 	};
 
-	let bytes = &Runtime::metadata_at_version(LATEST_METADATA_VERSION)
-		.expect("Metadata must be present; qed");
+	let bytes =
+		&Runtime::metadata_at_version(METADATA_VERSION_15).expect("Metadata must be present; qed");
 
 	let actual_metadata: RuntimeMetadataPrefixed =
 		Decode::decode(&mut &bytes[..]).expect("Metadata encoded properly; qed");
@@ -1999,11 +1999,54 @@ fn metadata_at_version() {
 #[test]
 fn metadata_versions() {
 	assert_eq!(
-		vec![14, LATEST_METADATA_VERSION, UNSTABLE_METADATA_VERSION],
+		vec![14, METADATA_VERSION_15, LATEST_METADATA_VERSION],
 		Runtime::metadata_versions()
 	);
 }
 
+#[test]
+fn metadata_at_version_16() {
+	use frame_metadata::{
+		v16::{DeprecationInfo, DeprecationStatus},
+		RuntimeMetadata, RuntimeMetadataPrefixed,
+	};
+	use sp_core::Decode;
+
+	let bytes = &Runtime::metadata_at_version(LATEST_METADATA_VERSION)
+		.expect("Metadata must be present; qed");
+	let metadata: RuntimeMetadataPrefixed =
+		Decode::decode(&mut &bytes[..]).expect("Metadata encoded properly; qed");
+	let metadata = match metadata.1 {
+		RuntimeMetadata::V16(metadata) => metadata,
+		_ => panic!("metadata has been bumped, test needs to be updated"),
+	};
+
+	// All transaction extensions are used by the only extension version.
+	let extensions = metadata.extrinsic.transaction_extensions.len() as u32;
+	assert_eq!(
+		metadata.extrinsic.transaction_extensions_by_version,
+		BTreeMap::from([(0, (0..extensions).collect())])
+	);
+
+	let example = metadata.pallets.iter().find(|pallet| pallet.name == "Example").unwrap();
+	assert_eq!(
+		example.view_functions.iter().map(|f| f.name.as_str()).collect::<Vec<_>>(),
+		vec!["get_value", "get_value_with_arg"]
+	);
+	assert!(example.associated_types.iter().any(|ty| ty.name == "Balance"));
+	assert!(matches!(
+		example.calls.as_ref().unwrap().deprecation_info,
+		DeprecationInfo::VariantsDeprecated(_)
+	));
+	assert!(matches!(
+		example.event.as_ref().unwrap().deprecation_info,
+		DeprecationInfo::ItemDeprecated(DeprecationStatus::Deprecated { .. })
+	));
+
+	let example2 = metadata.pallets.iter().find(|pallet| pallet.name == "Example2").unwrap();
+	assert!(matches!(example2.deprecation_info, DeprecationStatus::Deprecated { .. }));
+}
+
 #[test]
 fn metadata_ir_pallet_runtime_docs() {
 	let ir = Runtime::metadata_ir();
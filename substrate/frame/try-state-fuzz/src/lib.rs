@@ -0,0 +1,301 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! # Try-State Fuzzer
+//!
+//! Randomized testing of the [`try_state`](frame_support::traits::Hooks::try_state) invariants
+//! of FRAME pallets, runnable as part of `cargo test`.
+//!
+//! The [`TryStateFuzzer`] builds fresh [`TestExternalities`] for every iteration, then executes a
+//! number of blocks in them. In each block, a random number of calls is generated by a
+//! [`CallGenerator`] and dispatched with a random origin out of a configured set. At the end of
+//! every block, the hooks of all pallets are executed and their
+//! [`TryState`](frame_support::traits::TryState) is checked. The first failing check is reported,
+//! together with the sequence of calls that led to it.
+//!
+//! Calls can be generated either from the [`TypeInfo`](scale_info::TypeInfo) of the call type with
+//! [`TypeInfoCalls`], which works for any pallet out of the box, or from an
+//! [`arbitrary::Arbitrary`] implementation with [`ArbitraryCalls`].
+//!
+//! ## Usage
+//!
+//! In the tests of a pallet, using its mock runtime:
+//!
+//! ```ignore
+//! #[test]
+//! fn try_state_fuzz() {
+//! 	TryStateFuzzer::<Test, AllPalletsWithSystem, _, _>::new(
+//! 		new_test_ext,
+//! 		TypeInfoCalls::<pallet_example::Call<Test>>::new(),
+//! 	)
+//! 	.origins(vec![RuntimeOrigin::root(), RuntimeOrigin::signed(1), RuntimeOrigin::signed(2)])
+//! 	.blocks(20)
+//! 	.run();
+//! }
+//! ```
+//!
+//! The seed of a run is logged and reported on failure, and can be fixed with the
+//! `TRY_STATE_FUZZ_SEED` environment variable to reproduce the failure. The number of iterations
+//! can be overridden with `TRY_STATE_FUZZ_ITERATIONS`.
+//!
+//! For coverage guided fuzzing, [`TryStateFuzzer::run_with_data`] can be fed the input of a fuzzer
+//! such as `honggfuzz` directly.
+//!
+//! Like the `try_state` hooks it checks, the fuzzer is only available with the `try-runtime`
+//! feature, which the tests using it have to enable.
+
+#![cfg(feature = "try-runtime")]
+
+#[cfg(test)]
+mod tests;
+mod type_info;
+
+pub use arbitrary;
+pub use type_info::TypeInfoCalls;
+
+use arbitrary::{Arbitrary, Unstructured};
+use core::{fmt, marker::PhantomData};
+use frame_support::traits::{OnFinalize, OnInitialize, TryState, TryStateSelect};
+use frame_system::pallet_prelude::BlockNumberFor;
+use rand::{rngs::StdRng, Rng, RngCore, SeedableRng};
+use sp_io::TestExternalities;
+use sp_runtime::{
+	traits::{Dispatchable, One},
+	TryRuntimeError,
+};
+
+const LOG_TARGET: &str = "runtime::try-state-fuzz";
+
+/// Amount of random bytes made available for each call of a block in [`TryStateFuzzer::run`].
+const BYTES_PER_CALL: usize = 256;
+
+/// Generates the calls dispatched by the [`TryStateFuzzer`].
+pub trait CallGenerator<Call> {
+	/// Generate a call out of the given unstructured data.
+	///
+	/// Returning [`arbitrary::Error::IncorrectFormat`] skips the call, any other error ends the
+	/// run.
+	fn generate(&self, u: &mut Unstructured) -> arbitrary::Result<Call>;
+}
+
+/// A [`CallGenerator`] using the [`Arbitrary`] implementation of the call.
+pub struct ArbitraryCalls;
+
+impl<Call: for<'a> Arbitrary<'a>> CallGenerator<Call> for ArbitraryCalls {
+	fn generate(&self, u: &mut Unstructured) -> arbitrary::Result<Call> {
+		Call::arbitrary(u)
+	}
+}
+
+/// A `try_state` check failed.
+#[derive(Debug)]
+pub struct Failure<BlockNumber> {
+	/// The block after which the check failed.
+	pub block: BlockNumber,
+	/// The calls dispatched so far, with their results.
+	pub calls: Vec<String>,
+	/// The error returned by the check.
+	pub error: TryRuntimeError,
+}
+
+impl<BlockNumber: fmt::Debug> fmt::Display for Failure<BlockNumber> {
+	fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+		writeln!(f, "try_state failed after block {:?}: {:?}", self.block, self.error)?;
+		writeln!(f, "dispatched calls:")?;
+		for call in &self.calls {
+			writeln!(f, "  {}", call)?;
+		}
+		Ok(())
+	}
+}
+
+/// Executes random sequences of calls in a mock runtime and checks `try_state` after each block.
+///
+/// - `T` is the mock runtime.
+/// - `AllPallets` are the pallets whose hooks are executed and whose state is checked, usually
+///   `AllPalletsWithSystem` as generated by `construct_runtime!`.
+/// - `Call` is the type of the generated calls, usually the `Call` of the pallet under test.
+/// - `Generator` generates the calls, see [`TypeInfoCalls`] and [`ArbitraryCalls`].
+pub struct TryStateFuzzer<T: frame_system::Config, AllPallets, Call, Generator> {
+	ext: Box<dyn Fn() -> TestExternalities>,
+	generator: Generator,
+	origins: Vec<T::RuntimeOrigin>,
+	blocks: u32,
+	max_calls_per_block: u32,
+	iterations: u32,
+	_phantom: PhantomData<(AllPallets, Call)>,
+}
+
+impl<T, AllPallets, Call, Generator> TryStateFuzzer<T, AllPallets, Call, Generator>
+where
+	T: frame_system::Config,
+	AllPallets: OnInitialize<BlockNumberFor<T>>
+		+ OnFinalize<BlockNumberFor<T>>
+		+ TryState<BlockNumberFor<T>>,
+	Call: Into<T::RuntimeCall> + fmt::Debug,
+	Generator: CallGenerator<Call>,
+{
+	/// Create a new fuzzer.
+	///
+	/// `ext` builds the externalities every iteration starts from, usually the `new_test_ext` of
+	/// the mock. Calls are dispatched with the root origin, unless configured otherwise with
+	/// [`Self::origins`].
+	pub fn new(ext: impl Fn() -> TestExternalities + 'static, generator: Generator) -> Self {
+		Self {
+			ext: Box::new(ext),
+			generator,
+			origins: vec![frame_system::RawOrigin::Root.into()],
+			blocks: 10,
+			max_calls_per_block: 8,
+			iterations: 32,
+			_phantom: PhantomData,
+		}
+	}
+
+	/// The origins calls are dispatched with, one of them is picked at random for each call.
+	pub fn origins(mut self, origins: Vec<T::RuntimeOrigin>) -> Self {
+		assert!(!origins.is_empty(), "At least one origin is needed to dispatch calls");
+		self.origins = origins;
+		self
+	}
+
+	/// The number of blocks executed in each iteration.
+	pub fn blocks(mut self, blocks: u32) -> Self {
+		self.blocks = blocks;
+		self
+	}
+
+	/// The maximal number of calls dispatched in a block.
+	pub fn max_calls_per_block(mut self, max_calls_per_block: u32) -> Self {
+		self.max_calls_per_block = max_calls_per_block;
+		self
+	}
+
+	/// The number of iterations of [`Self::run`], each starting from fresh externalities.
+	pub fn iterations(mut self, iterations: u32) -> Self {
+		self.iterations = iterations;
+		self
+	}
+
+	/// Run the fuzzer, panicking on the first failing `try_state` check.
+	///
+	/// The seed is taken from the `TRY_STATE_FUZZ_SEED` environment variable if set, and is
+	/// random otherwise.
+	pub fn run(&self) {
+		let seed = if let Ok(seed) = std::env::var("TRY_STATE_FUZZ_SEED") {
+			seed.parse().expect("Need valid u64 as TRY_STATE_FUZZ_SEED env variable")
+		} else {
+			rand::thread_rng().gen::<u64>()
+		};
+		log::info!(target: LOG_TARGET, "Using seed: {}", seed);
+
+		if let Err(failure) = self.run_seeded(seed) {
+			panic!("{}\nReproduce with TRY_STATE_FUZZ_SEED={}", failure, seed);
+		}
+	}
+
+	/// Run all the iterations with data generated from `seed`.
+	pub fn run_seeded(&self, seed: u64) -> Result<(), Failure<BlockNumberFor<T>>> {
+		let iterations = std::env::var("TRY_STATE_FUZZ_ITERATIONS")
+			.ok()
+			.and_then(|i| i.parse().ok())
+			.unwrap_or(self.iterations);
+		let mut rng = StdRng::seed_from_u64(seed);
+		let mut data = vec![
+			0u8;
+			BYTES_PER_CALL *
+				self.blocks.max(1) as usize *
+				self.max_calls_per_block.max(1) as usize
+		];
+
+		for iteration in 0..iterations {
+			rng.fill_bytes(&mut data);
+			let dispatched = self.run_with_data(&data)?;
+			log::debug!(
+				target: LOG_TARGET,
+				"iteration {} dispatched {} calls",
+				iteration,
+				dispatched,
+			);
+		}
+		Ok(())
+	}
+
+	/// Run a single iteration, generating everything from `data`.
+	///
+	/// The iteration ends early once `data` is exhausted. Returns the number of dispatched calls.
+	pub fn run_with_data(&self, data: &[u8]) -> Result<u32, Failure<BlockNumberFor<T>>> {
+		let mut u = Unstructured::new(data);
+		let mut calls = Vec::new();
+
+		(self.ext)().execute_with(|| {
+			let mut now = frame_system::Pallet::<T>::block_number();
+			Self::check(now, &calls)?;
+
+			for _ in 0..self.blocks {
+				now += One::one();
+				frame_system::Pallet::<T>::reset_events();
+				frame_system::Pallet::<T>::set_block_number(now);
+				AllPallets::on_initialize(now);
+
+				let exhausted =
+					self.dispatch_block(&mut u, now, &mut calls).is_err() || u.is_empty();
+
+				AllPallets::on_finalize(now);
+				Self::check(now, &calls)?;
+
+				if exhausted {
+					break
+				}
+			}
+			Ok(calls.len() as u32)
+		})
+	}
+
+	/// Dispatch a random number of calls, stopping early once the data is exhausted.
+	fn dispatch_block(
+		&self,
+		u: &mut Unstructured,
+		now: BlockNumberFor<T>,
+		calls: &mut Vec<String>,
+	) -> arbitrary::Result<()> {
+		let count = u.int_in_range(0..=self.max_calls_per_block)?;
+		for _ in 0..count {
+			if u.is_empty() {
+				break
+			}
+			let origin = u.choose(&self.origins)?.clone();
+			let call = match self.generator.generate(u) {
+				Ok(call) => call,
+				Err(arbitrary::Error::IncorrectFormat) => continue,
+				Err(e) => return Err(e),
+			};
+			let description = format!("#{:?} {:?}", now, call);
+			let result = call.into().dispatch(origin).map(|_| ()).map_err(|e| e.error);
+			calls.push(format!("{} => {:?}", description, result));
+		}
+		Ok(())
+	}
+
+	fn check(now: BlockNumberFor<T>, calls: &[String]) -> Result<(), Failure<BlockNumberFor<T>>> {
+		AllPallets::try_state(now, TryStateSelect::All).map_err(|error| Failure {
+			block: now,
+			calls: calls.to_vec(),
+			error,
+		})
+	}
+}
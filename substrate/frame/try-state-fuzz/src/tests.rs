@@ -0,0 +1,182 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Tests for the try-state fuzzer, using a pallet that keeps a running total of its values.
+
+use super::*;
+use frame_support::derive_impl;
+use sp_runtime::BuildStorage;
+
+#[frame_support::pallet(dev_mode)]
+pub mod pallet_totals {
+	use frame_support::pallet_prelude::*;
+	use frame_system::pallet_prelude::*;
+
+	#[pallet::config]
+	pub trait Config: frame_system::Config {}
+
+	#[pallet::pallet]
+	pub struct Pallet<T>(_);
+
+	#[pallet::storage]
+	pub type Values<T> = StorageMap<_, Twox64Concat, u8, u32>;
+
+	/// Must always be the sum of [`Values`].
+	#[pallet::storage]
+	pub type Total<T> = StorageValue<_, u32, ValueQuery>;
+
+	#[pallet::error]
+	pub enum Error<T> {
+		Overflow,
+	}
+
+	#[pallet::call]
+	impl<T: Config> Pallet<T> {
+		/// Correctly maintains the total.
+		pub fn set(origin: OriginFor<T>, key: u8, value: u32) -> DispatchResult {
+			ensure_signed(origin)?;
+			let old = Values::<T>::get(key).unwrap_or_default();
+			let total = Total::<T>::get()
+				.checked_sub(old)
+				.and_then(|t| t.checked_add(value))
+				.ok_or(Error::<T>::Overflow)?;
+			Values::<T>::insert(key, value);
+			Total::<T>::put(total);
+			Ok(())
+		}
+
+		/// Forgets to update the total when setting a value of `7`.
+		pub fn set_buggy(origin: OriginFor<T>, key: u8, value: u32) -> DispatchResult {
+			if value != 7 {
+				return Self::set(origin, key, value)
+			}
+			ensure_signed(origin)?;
+			Values::<T>::insert(key, value);
+			Ok(())
+		}
+
+		/// Removes a value, only root may call it.
+		pub fn remove(origin: OriginFor<T>, key: u8) -> DispatchResult {
+			ensure_root(origin)?;
+			if let Some(old) = Values::<T>::take(key) {
+				Total::<T>::mutate(|t| *t = t.saturating_sub(old));
+			}
+			Ok(())
+		}
+	}
+
+	#[pallet::hooks]
+	impl<T: Config> Hooks<BlockNumberFor<T>> for Pallet<T> {
+		fn try_state(_: BlockNumberFor<T>) -> Result<(), sp_runtime::TryRuntimeError> {
+			let sum = Values::<T>::iter_values().fold(0u32, |acc, v| acc.saturating_add(v));
+			ensure!(sum == Total::<T>::get(), "Total doesn't match the sum of the values");
+			Ok(())
+		}
+	}
+}
+
+type Block = frame_system::mocking::MockBlock<Test>;
+
+frame_support::construct_runtime!(
+	pub enum Test {
+		System: frame_system,
+		Totals: pallet_totals,
+	}
+);
+
+#[derive_impl(frame_system::config_preludes::TestDefaultConfig)]
+impl frame_system::Config for Test {
+	type Block = Block;
+}
+
+impl pallet_totals::Config for Test {}
+
+fn new_test_ext() -> TestExternalities {
+	frame_system::GenesisConfig::<Test>::default().build_storage().unwrap().into()
+}
+
+/// Only generates `set` and `remove`.
+struct CorrectCalls;
+impl CallGenerator<pallet_totals::Call<Test>> for CorrectCalls {
+	fn generate(&self, u: &mut Unstructured) -> arbitrary::Result<pallet_totals::Call<Test>> {
+		Ok(if u.arbitrary::<bool>()? {
+			pallet_totals::Call::set { key: u.arbitrary()?, value: u.int_in_range(0..=1000)? }
+		} else {
+			pallet_totals::Call::remove { key: u.arbitrary()? }
+		})
+	}
+}
+
+fn origins() -> Vec<RuntimeOrigin> {
+	vec![RuntimeOrigin::root(), RuntimeOrigin::signed(1), RuntimeOrigin::signed(2)]
+}
+
+#[test]
+fn type_info_calls_generates_all_variants() {
+	let generator = TypeInfoCalls::<pallet_totals::Call<Test>>::new();
+	let data = (0..4096u32)
+		.map(|i| (i.wrapping_mul(2654435761) >> 13) as u8)
+		.collect::<Vec<_>>();
+	let mut u = Unstructured::new(&data);
+
+	let (mut set, mut set_buggy, mut remove) = (0, 0, 0);
+	while !u.is_empty() {
+		match generator.generate(&mut u).unwrap() {
+			pallet_totals::Call::set { .. } => set += 1,
+			pallet_totals::Call::set_buggy { .. } => set_buggy += 1,
+			pallet_totals::Call::remove { .. } => remove += 1,
+			_ => unreachable!("no other calls are generated"),
+		}
+	}
+	assert!(set > 0 && set_buggy > 0 && remove > 0);
+}
+
+#[test]
+fn correct_pallet_passes() {
+	TryStateFuzzer::<Test, AllPalletsWithSystem, _, _>::new(new_test_ext, CorrectCalls)
+		.origins(origins())
+		.iterations(16)
+		.run_seeded(42)
+		.unwrap();
+}
+
+#[test]
+fn broken_invariant_is_detected() {
+	let failure = TryStateFuzzer::<Test, AllPalletsWithSystem, _, _>::new(
+		new_test_ext,
+		TypeInfoCalls::<pallet_totals::Call<Test>>::new(),
+	)
+	.origins(origins())
+	.blocks(20)
+	.iterations(64)
+	.run_seeded(42)
+	.unwrap_err();
+
+	assert!(failure.calls.iter().any(|c| c.contains("set_buggy") && c.contains("value: 7")));
+}
+
+#[test]
+fn run_with_data_is_deterministic() {
+	let fuzzer =
+		TryStateFuzzer::<Test, AllPalletsWithSystem, _, _>::new(new_test_ext, CorrectCalls)
+			.origins(origins());
+	let data = [7u8; 2048];
+
+	assert_eq!(fuzzer.run_with_data(&data).unwrap(), fuzzer.run_with_data(&data).unwrap());
+	// Empty input still checks the genesis state and then stops.
+	assert_eq!(fuzzer.run_with_data(&[]).unwrap(), 0);
+}
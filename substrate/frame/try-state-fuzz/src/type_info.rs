@@ -0,0 +1,197 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+//! Generate calls from their type information.
+
+use crate::CallGenerator;
+use arbitrary::{Error, Unstructured};
+use codec::{Compact, Decode, DecodeAll, Encode};
+use core::marker::PhantomData;
+use scale_info::{MetaType, PortableRegistry, Registry, TypeDef, TypeDefPrimitive, TypeInfo};
+
+/// Upper bound for "small" integers, which are generated with a higher probability than others
+/// since they are more likely to be valid indices, account ids or amounts in a test runtime.
+const MAX_SMALL_INT: u8 = 16;
+
+/// A [`CallGenerator`] which builds calls from the [`TypeInfo`] of the call type.
+///
+/// A variant of the call is picked at random, and all of its arguments are generated by walking
+/// their type information, so that the pallet doesn't have to implement
+/// [`arbitrary::Arbitrary`] for its calls or any of their argument types. The generated value is
+/// SCALE encoded and then decoded into `Call`, which means that types with additional decoding
+/// invariants (e.g. bounded collections) may occasionally fail to be generated; such attempts are
+/// skipped by the fuzzer.
+///
+/// Bit sequences are not supported.
+pub struct TypeInfoCalls<Call> {
+	registry: PortableRegistry,
+	root: u32,
+	max_depth: u32,
+	max_len: u32,
+	_phantom: PhantomData<Call>,
+}
+
+impl<Call: TypeInfo + 'static> Default for TypeInfoCalls<Call> {
+	fn default() -> Self {
+		Self::new()
+	}
+}
+
+impl<Call: TypeInfo + 'static> TypeInfoCalls<Call> {
+	/// Create a new generator for `Call`.
+	pub fn new() -> Self {
+		let mut registry = Registry::new();
+		let root = registry.register_type(&MetaType::new::<Call>()).id;
+		Self { registry: registry.into(), root, max_depth: 32, max_len: 8, _phantom: PhantomData }
+	}
+
+	/// Maximal nesting depth of the generated values.
+	pub fn max_depth(mut self, max_depth: u32) -> Self {
+		self.max_depth = max_depth;
+		self
+	}
+
+	/// Maximal length of the generated sequences and strings.
+	pub fn max_len(mut self, max_len: u32) -> Self {
+		self.max_len = max_len;
+		self
+	}
+
+	fn encode_arbitrary(
+		&self,
+		id: u32,
+		u: &mut Unstructured,
+		depth: u32,
+		out: &mut Vec<u8>,
+	) -> arbitrary::Result<()> {
+		if depth > self.max_depth {
+			return Err(Error::IncorrectFormat)
+		}
+		let ty = self.registry.resolve(id).ok_or(Error::IncorrectFormat)?;
+
+		match &ty.type_def {
+			TypeDef::Composite(composite) =>
+				for field in &composite.fields {
+					self.encode_arbitrary(field.ty.id, u, depth + 1, out)?;
+				},
+			TypeDef::Variant(variant) => {
+				let variant = u.choose(&variant.variants)?;
+				out.push(variant.index);
+				for field in &variant.fields {
+					self.encode_arbitrary(field.ty.id, u, depth + 1, out)?;
+				}
+			},
+			TypeDef::Sequence(sequence) => {
+				let len = u.int_in_range(0..=self.max_len)?;
+				Compact(len).encode_to(out);
+				for _ in 0..len {
+					self.encode_arbitrary(sequence.type_param.id, u, depth + 1, out)?;
+				}
+			},
+			TypeDef::Array(array) =>
+				for _ in 0..array.len {
+					self.encode_arbitrary(array.type_param.id, u, depth + 1, out)?;
+				},
+			TypeDef::Tuple(tuple) =>
+				for field in &tuple.fields {
+					self.encode_arbitrary(field.id, u, depth + 1, out)?;
+				},
+			TypeDef::Primitive(primitive) => self.encode_primitive(primitive, u, out)?,
+			TypeDef::Compact(compact) => self.encode_compact(compact.type_param.id, u, out)?,
+			TypeDef::BitSequence(_) => return Err(Error::IncorrectFormat),
+		}
+
+		Ok(())
+	}
+
+	fn encode_primitive(
+		&self,
+		primitive: &TypeDefPrimitive,
+		u: &mut Unstructured,
+		out: &mut Vec<u8>,
+	) -> arbitrary::Result<()> {
+		match primitive {
+			TypeDefPrimitive::Bool => u.arbitrary::<bool>()?.encode_to(out),
+			TypeDefPrimitive::Char => (u.arbitrary::<char>()? as u32).encode_to(out),
+			TypeDefPrimitive::Str => {
+				let len = u.int_in_range(0..=self.max_len)? as usize;
+				let s = u.arbitrary::<String>()?.chars().take(len).collect::<String>();
+				s.encode_to(out)
+			},
+			TypeDefPrimitive::U8 => small_biased::<u8>(u)?.encode_to(out),
+			TypeDefPrimitive::U16 => small_biased::<u16>(u)?.encode_to(out),
+			TypeDefPrimitive::U32 => small_biased::<u32>(u)?.encode_to(out),
+			TypeDefPrimitive::U64 => small_biased::<u64>(u)?.encode_to(out),
+			TypeDefPrimitive::U128 => small_biased::<u128>(u)?.encode_to(out),
+			TypeDefPrimitive::I8 => u.arbitrary::<i8>()?.encode_to(out),
+			TypeDefPrimitive::I16 => small_biased::<i16>(u)?.encode_to(out),
+			TypeDefPrimitive::I32 => small_biased::<i32>(u)?.encode_to(out),
+			TypeDefPrimitive::I64 => small_biased::<i64>(u)?.encode_to(out),
+			TypeDefPrimitive::I128 => small_biased::<i128>(u)?.encode_to(out),
+			TypeDefPrimitive::U256 | TypeDefPrimitive::I256 => out.extend_from_slice(u.bytes(32)?),
+		}
+		Ok(())
+	}
+
+	/// Compact encoding is only defined for unsigned integers and for types wrapping exactly one
+	/// of them (e.g. `Perbill`), which is encoded as the compact of the inner integer.
+	fn encode_compact(
+		&self,
+		id: u32,
+		u: &mut Unstructured,
+		out: &mut Vec<u8>,
+	) -> arbitrary::Result<()> {
+		let ty = self.registry.resolve(id).ok_or(Error::IncorrectFormat)?;
+		match &ty.type_def {
+			TypeDef::Primitive(TypeDefPrimitive::U8) =>
+				Compact(small_biased::<u8>(u)?).encode_to(out),
+			TypeDef::Primitive(TypeDefPrimitive::U16) =>
+				Compact(small_biased::<u16>(u)?).encode_to(out),
+			TypeDef::Primitive(TypeDefPrimitive::U32) =>
+				Compact(small_biased::<u32>(u)?).encode_to(out),
+			TypeDef::Primitive(TypeDefPrimitive::U64) =>
+				Compact(small_biased::<u64>(u)?).encode_to(out),
+			TypeDef::Primitive(TypeDefPrimitive::U128) =>
+				Compact(small_biased::<u128>(u)?).encode_to(out),
+			TypeDef::Composite(composite) if composite.fields.len() == 1 =>
+				return self.encode_compact(composite.fields[0].ty.id, u, out),
+			TypeDef::Tuple(tuple) if tuple.fields.is_empty() => {},
+			_ => return Err(Error::IncorrectFormat),
+		}
+		Ok(())
+	}
+}
+
+/// Generate an integer which is small with probability one half, and arbitrary otherwise.
+fn small_biased<'a, I>(u: &mut Unstructured<'a>) -> arbitrary::Result<I>
+where
+	I: arbitrary::Arbitrary<'a> + From<u8>,
+{
+	if u.arbitrary::<bool>()? {
+		Ok(I::from(u.int_in_range(0..=MAX_SMALL_INT)?))
+	} else {
+		u.arbitrary::<I>()
+	}
+}
+
+impl<Call: TypeInfo + Decode + 'static> CallGenerator<Call> for TypeInfoCalls<Call> {
+	fn generate(&self, u: &mut Unstructured) -> arbitrary::Result<Call> {
+		let mut encoded = Vec::new();
+		self.encode_arbitrary(self.root, u, 0, &mut encoded)?;
+		Call::decode_all(&mut &encoded[..]).map_err(|_| Error::IncorrectFormat)
+	}
+}
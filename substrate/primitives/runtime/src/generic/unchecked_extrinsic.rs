@@ -396,6 +396,7 @@ impl<Address, Call: Dispatchable, Signature, Extension: TransactionExtension<Cal
 	ExtrinsicMetadata for UncheckedExtrinsic<Address, Call, Signature, Extension>
 {
 	const VERSIONS: &'static [u8] = &[LEGACY_EXTRINSIC_FORMAT_VERSION, EXTRINSIC_FORMAT_VERSION];
+	const EXTENSION_VERSIONS: &'static [u8] = &[EXTENSION_VERSION];
 	type TransactionExtensions = Extension;
 }
 
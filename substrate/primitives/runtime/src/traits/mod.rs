@@ -1434,6 +1434,12 @@ pub trait ExtrinsicMetadata {
 	/// By format we mean the encoded representation of the `Extrinsic`.
 	const VERSIONS: &'static [u8];
 
+	/// The versions of the transaction extensions accepted by this `Extrinsic`.
+	///
+	/// All of the [`Self::TransactionExtensions`] are used in each of these versions. Defaults to
+	/// only version `0`, the only version of transaction extensions there is so far.
+	const EXTENSION_VERSIONS: &'static [u8] = &[0];
+
 	/// Transaction extensions attached to this `Extrinsic`.
 	type TransactionExtensions;
 }
@@ -363,6 +363,11 @@ pub trait Backend<H: Hasher>: core::fmt::Debug {
 		unimplemented!()
 	}
 
+	/// Get the number of reads which were served by the trie cache instead of the db
+	fn warm_read_count(&self) -> u32 {
+		0
+	}
+
 	/// Get the whitelist for tracking db reads/writes
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		Default::default()
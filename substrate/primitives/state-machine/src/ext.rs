@@ -634,6 +634,10 @@ where
 		self.backend.reset_read_write_count()
 	}
 
+	fn warm_read_count(&self) -> u32 {
+		self.backend.warm_read_count()
+	}
+
 	fn get_whitelist(&self) -> Vec<TrackedStorageKey> {
 		self.backend.get_whitelist()
 	}
@@ -16,6 +16,7 @@
 // limitations under the License.
 
 use super::{
+	compare,
 	types::{ComponentRange, ComponentRangeMap},
 	writer, ListOutput, PalletCmd,
 };
@@ -102,6 +103,52 @@ struct SelectedBenchmark {
 	pov_modes: Vec<(String, String)>,
 }
 
+/// [`BenchmarkBatch`] as returned by versions of the `Benchmark` runtime API before 3.
+#[derive(Decode)]
+struct BenchmarkBatchV2 {
+	pallet: Vec<u8>,
+	instance: Vec<u8>,
+	benchmark: Vec<u8>,
+	results: Vec<BenchmarkResultV2>,
+}
+
+/// [`BenchmarkResult`] as returned by versions of the `Benchmark` runtime API before 3, without
+/// the `warm_reads`.
+#[derive(Decode)]
+struct BenchmarkResultV2 {
+	components: Vec<(BenchmarkParameter, u32)>,
+	extrinsic_time: u128,
+	storage_root_time: u128,
+	reads: u32,
+	repeat_reads: u32,
+	writes: u32,
+	repeat_writes: u32,
+	proof_size: u32,
+	keys: Vec<(Vec<u8>, u32, u32, bool)>,
+}
+
+impl From<BenchmarkBatchV2> for BenchmarkBatch {
+	fn from(batch: BenchmarkBatchV2) -> Self {
+		let BenchmarkBatchV2 { pallet, instance, benchmark, results } = batch;
+		let results = results
+			.into_iter()
+			.map(|r| BenchmarkResult {
+				components: r.components,
+				extrinsic_time: r.extrinsic_time,
+				storage_root_time: r.storage_root_time,
+				reads: r.reads,
+				repeat_reads: r.repeat_reads,
+				writes: r.writes,
+				repeat_writes: r.repeat_writes,
+				proof_size: r.proof_size,
+				keys: r.keys,
+				warm_reads: 0,
+			})
+			.collect();
+		BenchmarkBatch { pallet, instance, benchmark, results }
+	}
+}
+
 // This takes multiple benchmark batches and combines all the results where the pallet, instance,
 // and benchmark are the same.
 fn combine_batches(
@@ -273,7 +320,7 @@ impl PalletCmd {
 		let genesis_storage =
 			state_handler.build_storage::<SubstrateAndExtraHF<ExtraHostFunctions>>(None)?;
 
-		let cache_size = Some(self.database_cache_size as usize);
+		let cache_size = self.trie_cache_size.map(|size| size as usize);
 		let state_with_tracking = BenchmarkingState::<Hasher>::new(
 			genesis_storage.clone(),
 			cache_size,
@@ -451,11 +498,7 @@ impl PalletCmd {
 				if !self.no_verify {
 					let state = &state_without_tracking;
 					// Don't use these results since verification code will add overhead.
-					let _batch: Vec<BenchmarkBatch> = match Self::exec_state_machine::<
-						std::result::Result<Vec<BenchmarkBatch>, String>,
-						_,
-						_,
-					>(
+					let _batch: Vec<BenchmarkBatch> = match Self::exec_dispatch_benchmark(
 						StateMachine::new(
 							state,
 							&mut Default::default(),
@@ -466,7 +509,7 @@ impl PalletCmd {
 							&runtime_code,
 							CallContext::Offchain,
 						),
-						"dispatch a benchmark",
+						benchmark_api_version,
 					) {
 						Err(e) => {
 							log::error!(target: LOG_TARGET, "Error executing and verifying runtime benchmark: {}", e);
@@ -484,11 +527,7 @@ impl PalletCmd {
 				// Do one loop of DB tracking.
 				{
 					let state = &state_with_tracking;
-					let batch: Vec<BenchmarkBatch> = match Self::exec_state_machine::<
-						std::result::Result<Vec<BenchmarkBatch>, String>,
-						_,
-						_,
-					>(
+					let batch: Vec<BenchmarkBatch> = match Self::exec_dispatch_benchmark(
 						StateMachine::new(
 							state, // todo remove tracking
 							&mut Default::default(),
@@ -499,7 +538,7 @@ impl PalletCmd {
 							&runtime_code,
 							CallContext::Offchain,
 						),
-						"dispatch a benchmark",
+						benchmark_api_version,
 					) {
 						Err(e) => {
 							log::error!(target: LOG_TARGET, "Error executing runtime benchmark: {}", e);
@@ -519,11 +558,7 @@ impl PalletCmd {
 				// Finally run a bunch of loops to get extrinsic timing information.
 				for r in 0..self.external_repeat {
 					let state = &state_without_tracking;
-					let batch = match Self::exec_state_machine::<
-						std::result::Result<Vec<BenchmarkBatch>, String>,
-						_,
-						_,
-					>(
+					let batch = match Self::exec_dispatch_benchmark(
 						StateMachine::new(
 							state, // todo remove tracking
 							&mut Default::default(),
@@ -534,7 +569,7 @@ impl PalletCmd {
 							&runtime_code,
 							CallContext::Offchain,
 						),
-						"dispatch a benchmark",
+						benchmark_api_version,
 					) {
 						Err(e) => {
 							return Err(format!("Error executing runtime benchmark: {e}",).into());
@@ -670,6 +705,22 @@ impl PalletCmd {
 		Ok(res)
 	}
 
+	/// Execute `Benchmark_dispatch_benchmark`, decoding the result according to the version of
+	/// the `Benchmark` runtime API.
+	fn exec_dispatch_benchmark<H: Hash, Exec: CodeExecutor>(
+		machine: StateMachine<BenchmarkingState<H>, H, Exec>,
+		benchmark_api_version: u32,
+	) -> Result<std::result::Result<Vec<BenchmarkBatch>, String>> {
+		let hint = "dispatch a benchmark";
+		if benchmark_api_version >= 3 {
+			Self::exec_state_machine(machine, hint)
+		} else {
+			let batches: std::result::Result<Vec<BenchmarkBatchV2>, String> =
+				Self::exec_state_machine(machine, hint)?;
+			Ok(batches.map(|batches| batches.into_iter().map(Into::into).collect()))
+		}
+	}
+
 	/// Build the extension that are available for pallet benchmarks.
 	fn build_extensions<E: CodeExecutor, H: Hasher + 'static>(
 		exe: E,
@@ -733,6 +784,21 @@ impl PalletCmd {
 		component_ranges: &ComponentRangeMap,
 		pov_modes: PovModesMap,
 	) -> Result<()> {
+		// Load the old weights first, since they may be overwritten by the new ones.
+		let old_weights = match &self.compare {
+			Some(dir) => Some(compare::load_weights(dir, |batches| {
+				writer::analyze_results(
+					batches,
+					storage_info,
+					&Default::default(),
+					pov_modes.clone(),
+					self.default_pov_mode,
+					self,
+				)
+			})?),
+			None => None,
+		};
+
 		// Jsonify the result and write it to a file or stdout if desired.
 		if !self.jsonify(&batches)? && !self.quiet {
 			// Print the summary only if `jsonify` did not write to stdout.
@@ -745,16 +811,64 @@ impl PalletCmd {
 				&batches,
 				&storage_info,
 				&component_ranges,
-				pov_modes,
+				pov_modes.clone(),
 				self.default_pov_mode,
 				output_path,
 				self,
 			)?;
 		}
 
+		if let Some(old_weights) = old_weights {
+			let new_weights = writer::analyze_results(
+				batches,
+				storage_info,
+				component_ranges,
+				pov_modes,
+				self.default_pov_mode,
+				self,
+			)?;
+			self.compare_weights(&new_weights, &old_weights)?;
+		}
+
 		Ok(())
 	}
 
+	/// Reports the changes of the weights and fails on regressions, unless they are allowed.
+	fn compare_weights(
+		&self,
+		new_weights: &HashMap<(String, String), Vec<writer::BenchmarkData>>,
+		old_weights: &HashMap<String, compare::PalletWeights>,
+	) -> Result<()> {
+		let thresholds = compare::Thresholds {
+			ref_time: self.compare_ref_time_threshold,
+			proof_size: self.compare_proof_size_threshold,
+		};
+		let changes = compare::compare_weights(new_weights, old_weights);
+		let report = compare::report(&changes, &thresholds);
+
+		println!("{report}");
+		if let Some(path) = &self.compare_report {
+			fs::write(path, &report)?;
+			println!("Created file: {:?}", path);
+		}
+
+		let regressions = changes.iter().filter(|c| c.is_regression(&thresholds)).count();
+		if regressions == 0 {
+			return Ok(())
+		}
+		let msg = format!(
+			"{} benchmarks regressed by more than the configured thresholds of {}% ref time and \
+			{}% proof size.",
+			regressions, thresholds.ref_time, thresholds.proof_size
+		);
+		if self.compare_allow_regressions {
+			println!("{msg}");
+			Ok(())
+		} else {
+			Err(msg.into())
+		}
+	}
+
 	/// Re-analyze a batch historic benchmark timing data. Will not take the PoV into account.
 	fn output_from_results(&self, batches: &[BenchmarkBatchSplitResults]) -> Result<()> {
 		let mut component_ranges = HashMap::<(String, String), HashMap<String, (u32, u32)>>::new();
@@ -872,6 +986,13 @@ impl PalletCmd {
 				{
 					println!("Reads = {:?}", analysis);
 				}
+				if self.trie_cache_size.is_some() {
+					if let Some(analysis) =
+						Analysis::median_slopes(&batch.db_results, BenchmarkSelector::WarmReads)
+					{
+						println!("Warm Reads = {:?}", analysis);
+					}
+				}
 				if let Some(analysis) =
 					Analysis::median_slopes(&batch.db_results, BenchmarkSelector::Writes)
 				{
@@ -896,6 +1017,13 @@ impl PalletCmd {
 				{
 					println!("Reads = {:?}", analysis);
 				}
+				if self.trie_cache_size.is_some() {
+					if let Some(analysis) =
+						Analysis::min_squares_iqr(&batch.db_results, BenchmarkSelector::WarmReads)
+					{
+						println!("Warm Reads = {:?}", analysis);
+					}
+				}
 				if let Some(analysis) =
 					Analysis::min_squares_iqr(&batch.db_results, BenchmarkSelector::Writes)
 				{
@@ -986,6 +1114,21 @@ impl PalletCmd {
 			}
 		}
 
+		if let Some(compare_dir) = &self.compare {
+			if !compare_dir.is_dir() {
+				return Err((
+					ErrorKind::InvalidValue,
+					format!("Weights to compare against could not be found: {compare_dir:?}"),
+				));
+			}
+			if self.compare_ref_time_threshold < 0.0 || self.compare_proof_size_threshold < 0.0 {
+				return Err((
+					ErrorKind::InvalidValue,
+					"The `--compare-*-threshold` arguments must not be negative".to_string(),
+				));
+			}
+		}
+
 		if let Some(header_file) = &self.header {
 			if !header_file.is_file() {
 				return Err((
@@ -1221,4 +1364,20 @@ mod tests {
 		]);
 		Ok(())
 	}
+
+	#[test]
+	fn test_cli_compare() -> Result<(), clap::Error> {
+		let args = ["test", "--extrinsic", "", "--pallet", "", "--runtime", "path/to/runtime"];
+		cli_succeed(&[&args[..], &["--compare", "."]].concat())?;
+		cli_succeed(
+			&[&args[..], &["--compare", ".", "--compare-ref-time-threshold", "5"]].concat(),
+		)?;
+		cli_fail(&[&args[..], &["--compare", "does/not/exist"]].concat());
+		cli_fail(
+			&[&args[..], &["--compare", ".", "--compare-proof-size-threshold", "-1"]].concat(),
+		);
+		cli_fail(&[&args[..], &["--compare-ref-time-threshold", "5"]].concat());
+		cli_fail(&[&args[..], &["--compare-allow-regressions"]].concat());
+		Ok(())
+	}
 }
@@ -0,0 +1,465 @@
+// This file is part of Substrate.
+
+// Copyright (C) Parity Technologies (UK) Ltd.
+// SPDX-License-Identifier: Apache-2.0
+
+// Licensed under the Apache License, Version 2.0 (the "License");
+// you may not use this file except in compliance with the License.
+// You may obtain a copy of the License at
+//
+// 	http://www.apache.org/licenses/LICENSE-2.0
+//
+// Unless required by applicable law or agreed to in writing, software
+// distributed under the License is distributed on an "AS IS" BASIS,
+// WITHOUT WARRANTIES OR CONDITIONS OF ANY KIND, either express or implied.
+// See the License for the specific language governing permissions and
+// limitations under the License.
+
+// Compares benchmark results against previously generated weights, to catch weight regressions.
+
+use std::{
+	collections::{BTreeMap, HashMap},
+	fs,
+	path::Path,
+};
+
+use comfy_table::{presets::ASCII_MARKDOWN, Table};
+use frame_benchmarking::BenchmarkBatchSplitResults;
+use sc_cli::Result;
+
+use crate::pallet::writer::{weight_file_stem, BenchmarkData};
+
+/// The weight of a benchmark as a linear function of its components.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub(crate) struct WeightFormula {
+	/// Ref time in picoseconds when all components are zero.
+	pub(crate) base_ref_time: u128,
+	/// Proof size in bytes when all components are zero.
+	pub(crate) base_proof_size: u128,
+	/// Additional ref time per unit of each component.
+	pub(crate) ref_time_slopes: BTreeMap<String, u128>,
+	/// Additional proof size per unit of each component.
+	pub(crate) proof_size_slopes: BTreeMap<String, u128>,
+	/// The maximal value of each component, as far as it is known.
+	pub(crate) component_max: BTreeMap<String, u32>,
+}
+
+/// Ref time and proof size of a benchmark with all of its components at their maximum.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub(crate) struct WorstCase {
+	pub(crate) ref_time: u128,
+	pub(crate) proof_size: u128,
+}
+
+impl WeightFormula {
+	/// Evaluate the formula with each component set to its value in `components`.
+	///
+	/// Components without a value are assumed to be zero.
+	fn evaluate(&self, components: &BTreeMap<String, u32>) -> WorstCase {
+		let evaluate = |base: u128, slopes: &BTreeMap<String, u128>| {
+			slopes.iter().fold(base, |acc, (name, slope)| {
+				let value = components.get(name).copied().unwrap_or_default();
+				acc.saturating_add(slope.saturating_mul(value.into()))
+			})
+		};
+
+		WorstCase {
+			ref_time: evaluate(self.base_ref_time, &self.ref_time_slopes),
+			proof_size: evaluate(self.base_proof_size, &self.proof_size_slopes),
+		}
+	}
+}
+
+/// The weight formulas of all benchmarks of a pallet, by benchmark name.
+pub(crate) type PalletWeights = BTreeMap<String, WeightFormula>;
+
+/// The maximal relative increase of the worst case weight before a benchmark is reported as a
+/// regression, in percent.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub(crate) struct Thresholds {
+	pub(crate) ref_time: f64,
+	pub(crate) proof_size: f64,
+}
+
+/// How the weight of a single benchmark changed.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct WeightChange {
+	/// The weight file of the pallet, without extension.
+	pub(crate) pallet: String,
+	pub(crate) benchmark: String,
+	/// `None` if the benchmark is new.
+	pub(crate) old: Option<WorstCase>,
+	/// `None` if the benchmark was removed.
+	pub(crate) new: Option<WorstCase>,
+}
+
+impl WeightChange {
+	/// Relative change of the ref time and the proof size in percent.
+	fn deltas(&self) -> Option<(f64, f64)> {
+		let (old, new) = (self.old?, self.new?);
+		Some((
+			percent_change(old.ref_time, new.ref_time),
+			percent_change(old.proof_size, new.proof_size),
+		))
+	}
+
+	/// Whether any of the dimensions increased by more than its threshold.
+	pub(crate) fn is_regression(&self, thresholds: &Thresholds) -> bool {
+		self.deltas().is_some_and(|(ref_time, proof_size)| {
+			ref_time > thresholds.ref_time || proof_size > thresholds.proof_size
+		})
+	}
+
+	fn status(&self, thresholds: &Thresholds) -> &'static str {
+		match self.deltas() {
+			None if self.old.is_none() => "Added",
+			None => "Removed",
+			Some(_) if self.is_regression(thresholds) => "❌ Regression",
+			Some((ref_time, proof_size))
+				if ref_time < -thresholds.ref_time || proof_size < -thresholds.proof_size =>
+				"Improvement",
+			Some(_) => "✅ Ok",
+		}
+	}
+}
+
+/// The relative change from `old` to `new` in percent.
+///
+/// Any increase from zero is an infinite change.
+fn percent_change(old: u128, new: u128) -> f64 {
+	match (old, new) {
+		(0, 0) => 0.0,
+		(0, _) => f64::INFINITY,
+		(old, new) => (new as f64 - old as f64) * 100.0 / old as f64,
+	}
+}
+
+/// Load the weights of all pallets in `dir`, by the name of their weight file.
+///
+/// Weight files (`*.rs`) as generated with `--output` are parsed, and raw results (`*.json`) as
+/// generated with `--json-file` are analyzed with `analyze`. Raw results take precedence over
+/// weight files when both contain the same benchmark, since they are not rounded.
+pub(crate) fn load_weights(
+	dir: &Path,
+	analyze: impl Fn(
+		&[BenchmarkBatchSplitResults],
+	) -> Result<HashMap<(String, String), Vec<BenchmarkData>>>,
+) -> Result<HashMap<String, PalletWeights>> {
+	let mut weights = HashMap::<String, PalletWeights>::new();
+	let mut raw_weights = HashMap::<String, PalletWeights>::new();
+
+	let mut paths = fs::read_dir(dir)
+		.map_err(|e| format!("Could not read weights directory {:?}: {:?}", dir, e))?
+		.map(|entry| entry.map(|entry| entry.path()))
+		.collect::<std::result::Result<Vec<_>, _>>()?;
+	paths.sort();
+
+	for path in paths {
+		match path.extension().and_then(|e| e.to_str()) {
+			Some("rs") => {
+				let Some(stem) = path.file_stem().and_then(|s| s.to_str()) else { continue };
+				let content = fs::read_to_string(&path)?;
+				weights.entry(stem.to_string()).or_default().extend(parse_weight_file(&content));
+			},
+			Some("json") => {
+				let raw_data = fs::read(&path)?;
+				let batches: Vec<BenchmarkBatchSplitResults> = serde_json::from_slice(&raw_data)
+					.map_err(|e| format!("Failed to deserialize {:?}: {}", path, e))?;
+				let results = analyze(&batches)?;
+				for ((pallet, instance), benchmarks) in results.iter() {
+					let stem = weight_file_stem(pallet, instance, &results);
+					raw_weights.entry(stem).or_default().extend(
+						benchmarks.iter().map(|b| (b.name().to_string(), b.weight_formula())),
+					);
+				}
+			},
+			_ => {},
+		}
+	}
+
+	for (stem, benchmarks) in raw_weights {
+		weights.entry(stem).or_default().extend(benchmarks);
+	}
+	Ok(weights)
+}
+
+/// Parse the weight functions out of a weight file.
+///
+/// Only the `Weight::from_parts` terms are taken into account, since the weight of database
+/// accesses depends on the runtime configuration. When a file implements the weight functions
+/// multiple times, as the pallet-local `weights.rs` files do, the first implementation is used.
+pub(crate) fn parse_weight_file(content: &str) -> PalletWeights {
+	let mut formulas = PalletWeights::new();
+	let mut ranges = BTreeMap::new();
+	let mut current: Option<(String, WeightFormula)> = None;
+
+	for line in content.lines().map(str::trim) {
+		if let Some((_, formula)) = current.as_mut() {
+			if line == "}" {
+				let (name, formula) = current.take().expect("Checked to be `Some` above; qed");
+				formulas.entry(name).or_insert(formula);
+			} else {
+				parse_weight_terms(line, formula);
+			}
+			continue
+		}
+
+		if let Some((component, max)) = parse_component_range(line) {
+			ranges.insert(component, max);
+		} else if let Some(name) = parse_function_name(line) {
+			let formula =
+				WeightFormula { component_max: core::mem::take(&mut ranges), ..Default::default() };
+			current = Some((name, formula));
+		} else if !line.starts_with("///") {
+			ranges.clear();
+		}
+	}
+
+	formulas
+}
+
+/// Parse `fn name(..) -> Weight {` into `name`.
+fn parse_function_name(line: &str) -> Option<String> {
+	let rest = line.strip_prefix("fn ")?;
+	if !line.ends_with('{') || !line.contains("-> Weight") {
+		return None
+	}
+	rest.split_once('(').map(|(name, _)| name.trim().to_string())
+}
+
+/// Parse ``/// The range of component `c` is `[min, max]`.`` into `(c, max)`.
+fn parse_component_range(line: &str) -> Option<(String, u32)> {
+	let rest = line.strip_prefix("/// The range of component `")?;
+	let (name, rest) = rest.split_once('`')?;
+	let (_, max) = rest.split_once(", ")?;
+	let max = parse_number(max.split_once(']')?.0)?;
+	Some((name.to_string(), max.try_into().ok()?))
+}
+
+/// Add all `Weight::from_parts(ref_time, proof_size)` terms of a line to the formula.
+///
+/// A term followed by `.saturating_mul(c.into())` is a slope of the component `c`.
+fn parse_weight_terms(line: &str, formula: &mut WeightFormula) {
+	const FROM_PARTS: &str = "Weight::from_parts(";
+
+	let mut rest = line;
+	while let Some(start) = rest.find(FROM_PARTS) {
+		rest = &rest[start + FROM_PARTS.len()..];
+		let Some((args, tail)) = rest.split_once(')') else { return };
+		let Some((ref_time, proof_size)) = args.split_once(',') else { continue };
+		let (Some(ref_time), Some(proof_size)) = (parse_number(ref_time), parse_number(proof_size))
+		else {
+			continue
+		};
+		rest = tail;
+
+		match tail.strip_prefix(".saturating_mul(").and_then(|t| t.split_once(".into()")) {
+			Some((component, _)) => {
+				let component = component.trim();
+				if ref_time > 0 {
+					*formula.ref_time_slopes.entry(component.to_string()).or_default() += ref_time;
+				}
+				if proof_size > 0 {
+					*formula.proof_size_slopes.entry(component.to_string()).or_default() +=
+						proof_size;
+				}
+			},
+			None => {
+				formula.base_ref_time += ref_time;
+				formula.base_proof_size += proof_size;
+			},
+		}
+	}
+}
+
+/// Parse a number like `12_345` or `12_345_u64`.
+fn parse_number(s: &str) -> Option<u128> {
+	let digits = s
+		.trim()
+		.chars()
+		.take_while(|c| c.is_ascii_digit() || *c == '_')
+		.filter(|c| *c != '_')
+		.collect::<String>();
+	digits.parse().ok()
+}
+
+/// Compare the new results against the old weights.
+///
+/// Only pallets which are part of the new results are compared. Both formulas are evaluated with
+/// the components at their maximum, preferring the ranges of the new results.
+pub(crate) fn compare_weights(
+	new: &HashMap<(String, String), Vec<BenchmarkData>>,
+	old: &HashMap<String, PalletWeights>,
+) -> Vec<WeightChange> {
+	let mut changes = Vec::new();
+
+	for ((pallet, instance), results) in new.iter() {
+		let stem = weight_file_stem(pallet, instance, new);
+		let new_weights = results
+			.iter()
+			.map(|b| (b.name().to_string(), b.weight_formula()))
+			.collect::<PalletWeights>();
+		let empty = PalletWeights::new();
+		let old_weights = old.get(&stem).unwrap_or(&empty);
+
+		let mut benchmarks = new_weights.keys().chain(old_weights.keys()).collect::<Vec<_>>();
+		benchmarks.sort();
+		benchmarks.dedup();
+
+		for benchmark in benchmarks {
+			let (old_formula, new_formula) =
+				(old_weights.get(benchmark), new_weights.get(benchmark));
+			let mut components = old_formula.map(|f| f.component_max.clone()).unwrap_or_default();
+			components.extend(new_formula.into_iter().flat_map(|f| f.component_max.clone()));
+
+			changes.push(WeightChange {
+				pallet: stem.clone(),
+				benchmark: benchmark.clone(),
+				old: old_formula.map(|f| f.evaluate(&components)),
+				new: new_formula.map(|f| f.evaluate(&components)),
+			});
+		}
+	}
+
+	changes.sort_by(|a, b| (&a.pallet, &a.benchmark).cmp(&(&b.pallet, &b.benchmark)));
+	changes
+}
+
+/// Render the changes as a Markdown table.
+pub(crate) fn report(changes: &[WeightChange], thresholds: &Thresholds) -> String {
+	let format_value = |value: Option<u128>| value.map_or("-".into(), |v| v.to_string());
+	let format_delta = |delta: Option<f64>| delta.map_or("-".into(), |d| format!("{:+.2}%", d));
+
+	let mut table = Table::new();
+	table.load_preset(ASCII_MARKDOWN);
+	table.set_header([
+		"Pallet",
+		"Extrinsic",
+		"Old ref time (ps)",
+		"New ref time (ps)",
+		"Δ ref time",
+		"Old proof size (B)",
+		"New proof size (B)",
+		"Δ proof size",
+		"Status",
+	]);
+	for change in changes {
+		let deltas = change.deltas();
+		table.add_row([
+			change.pallet.clone(),
+			change.benchmark.clone(),
+			format_value(change.old.map(|w| w.ref_time)),
+			format_value(change.new.map(|w| w.ref_time)),
+			format_delta(deltas.map(|d| d.0)),
+			format_value(change.old.map(|w| w.proof_size)),
+			format_value(change.new.map(|w| w.proof_size)),
+			format_delta(deltas.map(|d| d.1)),
+			change.status(thresholds).to_string(),
+		]);
+	}
+
+	format!(
+		"Worst case weights with all components at their maximum. Regression thresholds: \
+		{}% ref time, {}% proof size.\n\n{}\n",
+		thresholds.ref_time, thresholds.proof_size, table
+	)
+}
+
+#[cfg(test)]
+mod test {
+	use super::*;
+
+	const WEIGHT_FILE: &str = r#"
+/// Weight functions for `pallet_example`.
+pub struct WeightInfo<T>(PhantomData<T>);
+impl<T: frame_system::Config> pallet_example::WeightInfo for WeightInfo<T> {
+	/// Storage: `Example::Value` (r:1 w:1)
+	/// Proof: `Example::Value` (`max_values`: Some(1), `max_size`: Some(4), added: 499, mode: `MaxEncodedLen`)
+	fn set_value() -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `1489`
+		// Minimum execution time: 4_000_000 picoseconds.
+		Weight::from_parts(4_250_000, 0)
+			.saturating_add(Weight::from_parts(0, 1489))
+			.saturating_add(T::DbWeight::get().reads(1))
+			.saturating_add(T::DbWeight::get().writes(1))
+	}
+	/// The range of component `c` is `[0, 1000]`.
+	/// The range of component `d` is `[1, 10]`.
+	fn batch(c: u32, _d: u32, ) -> Weight {
+		// Proof Size summary in bytes:
+		//  Measured:  `0`
+		//  Estimated: `0`
+		// Minimum execution time: 5_000_000 picoseconds.
+		Weight::from_parts(5_000_000, 0)
+			.saturating_add(Weight::from_parts(0, 12))
+			// Standard Error: 1_000
+			.saturating_add(Weight::from_parts(3_000, 0).saturating_mul(c.into()))
+			.saturating_add(T::DbWeight::get().reads((1_u64).saturating_mul(c.into())))
+			.saturating_add(Weight::from_parts(0, 2_500).saturating_mul(c.into()))
+	}
+}
+impl WeightInfo for () {
+	fn set_value() -> Weight {
+		Weight::from_parts(1, 2)
+	}
+}
+"#;
+
+	#[test]
+	fn parse_weight_file_works() {
+		let weights = parse_weight_file(WEIGHT_FILE);
+
+		assert_eq!(weights.len(), 2);
+		assert_eq!(
+			weights["set_value"],
+			WeightFormula { base_ref_time: 4_250_000, base_proof_size: 1489, ..Default::default() }
+		);
+		assert_eq!(
+			weights["batch"],
+			WeightFormula {
+				base_ref_time: 5_000_000,
+				base_proof_size: 12,
+				ref_time_slopes: [("c".to_string(), 3_000)].into(),
+				proof_size_slopes: [("c".to_string(), 2_500)].into(),
+				component_max: [("c".to_string(), 1000), ("d".to_string(), 10)].into(),
+			}
+		);
+		assert_eq!(
+			weights["batch"].evaluate(&weights["batch"].component_max),
+			WorstCase { ref_time: 8_000_000, proof_size: 2_500_012 }
+		);
+	}
+
+	#[test]
+	fn percent_change_works() {
+		assert_eq!(percent_change(0, 0), 0.0);
+		assert_eq!(percent_change(0, 1), f64::INFINITY);
+		assert_eq!(percent_change(200, 250), 25.0);
+		assert_eq!(percent_change(200, 150), -25.0);
+	}
+
+	#[test]
+	fn regressions_are_detected() {
+		let thresholds = Thresholds { ref_time: 10.0, proof_size: 5.0 };
+		let change = |old: (u128, u128), new: (u128, u128)| WeightChange {
+			pallet: "pallet_example".into(),
+			benchmark: "set_value".into(),
+			old: Some(WorstCase { ref_time: old.0, proof_size: old.1 }),
+			new: Some(WorstCase { ref_time: new.0, proof_size: new.1 }),
+		};
+
+		assert!(!change((100, 100), (110, 105)).is_regression(&thresholds));
+		assert!(change((100, 100), (111, 100)).is_regression(&thresholds));
+		assert!(change((100, 100), (100, 106)).is_regression(&thresholds));
+		assert!(change((100, 0), (100, 1)).is_regression(&thresholds));
+		assert_eq!(change((100, 100), (50, 100)).status(&thresholds), "Improvement");
+
+		let added = WeightChange { old: None, ..change((0, 0), (1, 1)) };
+		assert!(!added.is_regression(&thresholds));
+		assert_eq!(added.status(&thresholds), "Added");
+		let removed = WeightChange { new: None, ..change((1, 1), (0, 0)) };
+		assert_eq!(removed.status(&thresholds), "Removed");
+	}
+}
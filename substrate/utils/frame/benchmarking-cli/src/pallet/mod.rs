@@ -16,6 +16,7 @@
 // limitations under the License.
 
 mod command;
+mod compare;
 mod types;
 mod writer;
 
@@ -201,6 +202,20 @@ pub struct PalletCmd {
 	#[arg(long = "db-cache", value_name = "MiB", default_value_t = 1024)]
 	pub database_cache_size: u32,
 
+	/// Size of the shared trie cache that is kept between the setup and the measured part of a
+	/// benchmark.
+	///
+	/// By default, every benchmark starts with a cold cache and all reads hit the database. With a
+	/// warm cache, reads of storage that was already accessed in the setup are served by the
+	/// cache. They are recorded separately and charged with `--warm-read-weight` instead of as DB
+	/// reads in the weight files.
+	#[arg(long, value_name = "MiB", requires = "warm_read_weight")]
+	pub trie_cache_size: Option<u32>,
+
+	/// The `ref_time` weight in picoseconds charged for every read served by the trie cache.
+	#[arg(long, value_name = "PICOSECONDS", requires = "trie_cache_size")]
+	pub warm_read_weight: Option<u64>,
+
 	/// List and print available benchmarks in a csv-friendly format.
 	///
 	/// NOTE: `num_args` and `require_equals` are required to allow `--list`
@@ -249,6 +264,33 @@ pub struct PalletCmd {
 	#[arg(long)]
 	pub unsafe_overwrite_results: bool,
 
+	/// Compare the results against the weights in the given directory and report the changes.
+	///
+	/// The directory may contain weight files as written by `--output` and raw results as
+	/// written by `--json-file`. They are matched by the name of the weight file that the results
+	/// would be written to. The old weights are loaded before any output is written, so this can
+	/// point to the same directory as `--output`.
+	#[arg(long, value_name = "DIR")]
+	pub compare: Option<PathBuf>,
+
+	/// Increase of the worst case ref time of a benchmark, in percent, above which `--compare`
+	/// reports a regression.
+	#[arg(long, value_name = "PERCENT", default_value_t = 10.0, requires = "compare")]
+	pub compare_ref_time_threshold: f64,
+
+	/// Increase of the worst case proof size of a benchmark, in percent, above which `--compare`
+	/// reports a regression.
+	#[arg(long, value_name = "PERCENT", default_value_t = 10.0, requires = "compare")]
+	pub compare_proof_size_threshold: f64,
+
+	/// Write the report of `--compare` as Markdown into the given file.
+	#[arg(long, requires = "compare")]
+	pub compare_report: Option<PathBuf>,
+
+	/// Do not fail if `--compare` finds regressions.
+	#[arg(long, requires = "compare")]
+	pub compare_allow_regressions: bool,
+
 	/// Do not print a summary at the end of the run.
 	///
 	/// These summaries can be very long when benchmarking multiple pallets at once. For CI
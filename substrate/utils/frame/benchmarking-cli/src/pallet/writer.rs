@@ -30,6 +30,7 @@ use serde::Serialize;
 use crate::{
 	pallet::{
 		command::{PovEstimationMode, PovModesMap},
+		compare::WeightFormula,
 		types::{ComponentRange, ComponentRangeMap},
 	},
 	shared::UnderscoreHelper,
@@ -62,7 +63,7 @@ struct TemplateData {
 
 // This was the final data we have about each benchmark.
 #[derive(Serialize, Default, Debug, Clone, PartialEq)]
-struct BenchmarkData {
+pub(crate) struct BenchmarkData {
 	name: String,
 	components: Vec<Component>,
 	#[serde(serialize_with = "string_serialize")]
@@ -70,6 +71,8 @@ struct BenchmarkData {
 	#[serde(serialize_with = "string_serialize")]
 	base_reads: u128,
 	#[serde(serialize_with = "string_serialize")]
+	base_warm_reads: u128,
+	#[serde(serialize_with = "string_serialize")]
 	base_writes: u128,
 	#[serde(serialize_with = "string_serialize")]
 	base_calculated_proof_size: u128,
@@ -77,6 +80,7 @@ struct BenchmarkData {
 	base_recorded_proof_size: u128,
 	component_weight: Vec<ComponentSlope>,
 	component_reads: Vec<ComponentSlope>,
+	component_warm_reads: Vec<ComponentSlope>,
 	component_writes: Vec<ComponentSlope>,
 	component_calculated_proof_size: Vec<ComponentSlope>,
 	component_recorded_proof_size: Vec<ComponentSlope>,
@@ -96,6 +100,8 @@ struct CmdData {
 	wasm_execution: String,
 	chain: String,
 	db_cache: u32,
+	trie_cache: Option<u32>,
+	warm_read_weight: Option<u64>,
 	analysis_choice: String,
 	worst_case_map_values: u32,
 	additional_trie_layers: u8,
@@ -215,8 +221,12 @@ fn get_benchmark_data(
 
 	let extrinsic_time = analysis_function(&batch.time_results, BenchmarkSelector::ExtrinsicTime)
 		.expect("analysis function should return an extrinsic time for valid inputs");
-	let reads = analysis_function(&batch.db_results, BenchmarkSelector::Reads)
+	// Reads served by the trie cache are charged with their own weight, only the ones hitting the
+	// database are charged as DB reads.
+	let reads = analysis_function(&batch.db_results, BenchmarkSelector::ColdReads)
 		.expect("analysis function should return the number of reads for valid inputs");
+	let warm_reads = analysis_function(&batch.db_results, BenchmarkSelector::WarmReads)
+		.expect("analysis function should return the number of warm reads for valid inputs");
 	let writes = analysis_function(&batch.db_results, BenchmarkSelector::Writes)
 		.expect("analysis function should return the number of writes for valid inputs");
 	let recorded_proof_size =
@@ -228,6 +238,7 @@ fn get_benchmark_data(
 	let mut used_components = Vec::new();
 	let mut used_extrinsic_time = Vec::new();
 	let mut used_reads = Vec::new();
+	let mut used_warm_reads = Vec::new();
 	let mut used_writes = Vec::new();
 	let mut used_calculated_proof_size = Vec::<ComponentSlope>::new();
 	let mut used_recorded_proof_size = Vec::<ComponentSlope>::new();
@@ -258,6 +269,19 @@ fn get_benchmark_data(
 				used_reads.push(ComponentSlope { name: name.clone(), slope, error });
 			}
 		});
+	warm_reads
+		.slopes
+		.into_iter()
+		.zip(warm_reads.names.iter())
+		.zip(extract_errors(&warm_reads.errors))
+		.for_each(|((slope, name), error)| {
+			if !slope.is_zero() {
+				if !used_components.contains(&name) {
+					used_components.push(name);
+				}
+				used_warm_reads.push(ComponentSlope { name: name.clone(), slope, error });
+			}
+		});
 	writes
 		.slopes
 		.into_iter()
@@ -362,11 +386,13 @@ fn get_benchmark_data(
 		components,
 		base_weight: extrinsic_time.base,
 		base_reads: reads.base,
+		base_warm_reads: warm_reads.base,
 		base_writes: writes.base,
 		base_calculated_proof_size,
 		base_recorded_proof_size: recorded_proof_size.base,
 		component_weight: used_extrinsic_time,
 		component_reads: used_reads,
+		component_warm_reads: used_warm_reads,
 		component_writes: used_writes,
 		component_calculated_proof_size: used_calculated_proof_size,
 		component_recorded_proof_size: used_recorded_proof_size,
@@ -376,11 +402,86 @@ fn get_benchmark_data(
 	}
 }
 
+impl BenchmarkData {
+	/// The name of the benchmark.
+	pub(crate) fn name(&self) -> &str {
+		&self.name
+	}
+
+	/// The weight formula of the benchmark, as it is written into the weight file.
+	pub(crate) fn weight_formula(&self) -> WeightFormula {
+		WeightFormula {
+			base_ref_time: self.base_weight,
+			base_proof_size: self.base_calculated_proof_size,
+			ref_time_slopes: self
+				.component_weight
+				.iter()
+				.map(|c| (c.name.clone(), c.slope))
+				.collect(),
+			proof_size_slopes: self
+				.component_calculated_proof_size
+				.iter()
+				.map(|c| (c.name.clone(), c.slope))
+				.collect(),
+			component_max: self.component_ranges.iter().map(|r| (r.name.clone(), r.max)).collect(),
+		}
+	}
+}
+
+/// Analyze the benchmark results of all pallets, the same way as for the weight files.
+///
+/// The results are keyed by pallet and instance name.
+pub(crate) fn analyze_results(
+	batches: &[BenchmarkBatchSplitResults],
+	storage_info: &[StorageInfo],
+	component_ranges: &ComponentRangeMap,
+	pov_modes: PovModesMap,
+	default_pov_mode: PovEstimationMode,
+	cmd: &PalletCmd,
+) -> Result<HashMap<(String, String), Vec<BenchmarkData>>, sc_cli::Error> {
+	// Which analysis function should be used when outputting benchmarks
+	let analysis_choice: AnalysisChoice =
+		cmd.output_analysis.clone().try_into().map_err(io_error)?;
+	let pov_analysis_choice: AnalysisChoice =
+		cmd.output_pov_analysis.clone().try_into().map_err(io_error)?;
+
+	map_results(
+		batches,
+		storage_info,
+		component_ranges,
+		pov_modes,
+		default_pov_mode,
+		&analysis_choice,
+		&pov_analysis_choice,
+		cmd.worst_case_map_values,
+		cmd.additional_trie_layers,
+	)
+	.map_err(Into::into)
+}
+
+/// The name of the weight file of a pallet instance within an output directory, without the
+/// extension.
+pub(crate) fn weight_file_stem(
+	pallet: &str,
+	instance: &str,
+	all_results: &HashMap<(String, String), Vec<BenchmarkData>>,
+) -> String {
+	// Start with "path/to/pallet_name".
+	let mut file_name = pallet.to_string();
+	// Check if there might be multiple instances benchmarked.
+	if all_results.keys().any(|(p, i)| p == pallet && i != instance) {
+		// Append "_instance_name".
+		file_name = format!("{}_{}", file_name, instance.to_snake_case());
+	}
+	// "mod::pallet_name.rs" becomes "mod_pallet_name.rs".
+	file_name.replace("::", "_")
+}
+
 /// Create weight file from benchmark data and Handlebars template.
 pub(crate) fn write_results(
 	batches: &[BenchmarkBatchSplitResults],
 	storage_info: &[StorageInfo],
-	component_ranges: &HashMap<(String, String), Vec<ComponentRange>>,
+	component_ranges: &ComponentRangeMap,
 	pov_modes: PovModesMap,
 	default_pov_mode: PovEstimationMode,
 	path: &PathBuf,
@@ -410,8 +511,6 @@ pub(crate) fn write_results(
 	// Which analysis function should be used when outputting benchmarks
 	let analysis_choice: AnalysisChoice =
 		cmd.output_analysis.clone().try_into().map_err(io_error)?;
-	let pov_analysis_choice: AnalysisChoice =
-		cmd.output_pov_analysis.clone().try_into().map_err(io_error)?;
 
 	if cmd.additional_trie_layers > 4 {
 		println!(
@@ -429,6 +528,8 @@ pub(crate) fn write_results(
 		wasm_execution: cmd.wasm_method.to_string(),
 		chain: format!("{:?}", cmd.shared_params.chain),
 		db_cache: cmd.database_cache_size,
+		trie_cache: cmd.trie_cache_size,
+		warm_read_weight: cmd.warm_read_weight,
 		analysis_choice: format!("{:?}", analysis_choice),
 		worst_case_map_values: cmd.worst_case_map_values,
 		additional_trie_layers: cmd.additional_trie_layers,
@@ -442,32 +543,15 @@ pub(crate) fn write_results(
 	handlebars.register_escape_fn(|s| -> String { s.to_string() });
 
 	// Organize results by pallet into a JSON map
-	let all_results = map_results(
-		batches,
-		storage_info,
-		component_ranges,
-		pov_modes,
-		default_pov_mode,
-		&analysis_choice,
-		&pov_analysis_choice,
-		cmd.worst_case_map_values,
-		cmd.additional_trie_layers,
-	)?;
+	let all_results =
+		analyze_results(batches, storage_info, component_ranges, pov_modes, default_pov_mode, cmd)?;
 	let mut created_files = Vec::new();
 
 	for ((pallet, instance), results) in all_results.iter() {
 		let mut file_path = path.clone();
 		// If a user only specified a directory...
 		if file_path.is_dir() {
-			// Start with "path/to/pallet_name".
-			let mut file_name = pallet.clone();
-			// Check if there might be multiple instances benchmarked.
-			if all_results.keys().any(|(p, i)| p == pallet && i != instance) {
-				// Append "_instance_name".
-				file_name = format!("{}_{}", file_name, instance.to_snake_case());
-			}
-			// "mod::pallet_name.rs" becomes "mod_pallet_name.rs".
-			file_path.push(file_name.replace("::", "_"));
+			file_path.push(weight_file_stem(pallet, instance, &all_results));
 			file_path.set_extension("rs");
 		}
 
@@ -851,6 +935,7 @@ mod test {
 				proof_size: (i + 1) * 1024,
 				// All R/W come from this key:
 				keys: vec![(b"bounded".to_vec(), (base + slope * i), (base + slope * i), false)],
+				warm_reads: 0,
 			})
 		}
 
@@ -930,6 +1015,7 @@ mod test {
 				repeat_writes: 999,
 				proof_size: i * 1024,
 				keys: vec![(b"mel".to_vec(), 1, 1, false)],
+				warm_reads: 0,
 			})
 		}
 
@@ -987,6 +1073,7 @@ mod test {
 				repeat_writes: 999,
 				proof_size: i * 1024,
 				keys: vec![("mel".as_bytes().to_vec(), i, 1, false)],
+				warm_reads: 0,
 			})
 		}
 
@@ -1044,6 +1131,7 @@ mod test {
 				repeat_writes: 999,
 				proof_size: 1024,
 				keys: vec![("measured".as_bytes().to_vec(), 1, 1, false)],
+				warm_reads: 0,
 			})
 		}
 
@@ -1099,6 +1187,7 @@ mod test {
 				repeat_writes: 999,
 				proof_size: i * 1024,
 				keys: vec![("measured".as_bytes().to_vec(), i, 1, false)],
+				warm_reads: 0,
 			})
 		}
 
@@ -1156,6 +1245,7 @@ mod test {
 				repeat_writes: 999,
 				proof_size: i * 1024,
 				keys: vec![("ignored".as_bytes().to_vec(), i, 1, false)],
+				warm_reads: 0,
 			})
 		}
 
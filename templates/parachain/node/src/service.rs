@@ -282,6 +282,7 @@ pub async fn start_parachain_node(
 			relay_chain_interface: relay_chain_interface.clone(),
 			import_queue: params.import_queue,
 			sybil_resistance_level: CollatorSybilResistance::Resistant, // because of Aura
+			serve_available_data: false,
 		})
 		.await?;
 
@@ -328,7 +329,7 @@ pub async fn start_parachain_node(
 		config: parachain_config,
 		keystore: params.keystore_container.keystore(),
 		backend: backend.clone(),
-		network,
+		network: network.clone(),
 		sync_service: sync_service.clone(),
 		system_rpc_tx,
 		tx_handler_controller,
@@ -386,6 +387,8 @@ pub async fn start_parachain_node(
 		relay_chain_slot_duration,
 		recovery_handle: Box::new(overseer_handle.clone()),
 		sync_service: sync_service.clone(),
+		network: Some(network),
+		available_data_store: None,
 	})?;
 
 	if validator {
@@ -225,6 +225,13 @@ impl_runtime_apis! {
 		}
 	}
 
+	impl cumulus_primitives_core::RelayStorageKeysApi<Block> for Runtime {
+		fn relay_storage_keys() -> Vec<Vec<u8>> {
+			// Only the relay chain storage read by `cumulus-pallet-parachain-system` is proven.
+			Vec::new()
+		}
+	}
+
 	#[cfg(feature = "try-runtime")]
 	impl frame_try_runtime::TryRuntime<Block> for Runtime {
 		fn on_runtime_upgrade(checks: frame_try_runtime::UpgradeCheckSelect) -> (Weight, Weight) {
@@ -733,6 +733,11 @@ pub use pallet_whitelist;
 #[cfg(feature = "pallet-xcm")]
 pub use pallet_xcm;
 
+/// An XCM barrier whose allowlist of origins and instructions is kept in storage and managed
+/// by governance.
+#[cfg(feature = "pallet-xcm-barrier")]
+pub use pallet_xcm_barrier;
+
 /// Benchmarks for the XCM pallet.
 #[cfg(feature = "pallet-xcm-benchmarks")]
 pub use pallet_xcm_benchmarks;